@@ -220,7 +220,9 @@ config_namespace! {
         /// The default time zone
         ///
         /// Some functions, e.g. `EXTRACT(HOUR from SOME_TIME)`, shift the underlying datetime
-        /// according to this time zone, and then extract the hour
+        /// according to this time zone, and then extract the hour. Timestamps without a
+        /// time zone are the wall clock time in this time zone when compared with, or cast
+        /// to, timestamps with a time zone
         pub time_zone: Option<String>, default = Some("+00:00".into())
 
         /// Parquet options
@@ -235,6 +237,55 @@ config_namespace! {
         ///
         /// Defaults to the number of CPU cores on the system
         pub planning_concurrency: usize, default = num_cpus::get()
+
+        /// When set to true, the build side of a hash join with many columns, all
+        /// fixed-width, is additionally encoded in a row format, so that matched
+        /// build rows are copied as contiguous bytes when producing output batches.
+        /// Build sides with variable-length columns are not encoded yet
+        pub hash_join_build_row_format: bool, default = false
+
+        /// Number of entries of the hash table of a hash join's build side above
+        /// which the rows of every probe batch are looked up in the order of their
+        /// position in the hash table, improving cache locality for large build sides
+        pub hash_join_radix_probe_threshold: usize, default = 1024 * 1024
+
+        /// Maximum size in bytes of a Bloom filter over the join keys of a hash
+        /// join's build side, with which probe rows without a match are mostly
+        /// skipped without looking them up in the hash table. The filter takes
+        /// about a byte per build side row, and is not built if this size allows
+        /// less than a bit per row. 0 disables the Bloom filter
+        pub hash_join_bloom_filter_max_size: usize, default = 0
+
+        /// Number of probe side batches a hash join reads ahead of the one it is
+        /// joining, so that producing the probe side, e.g. reading and decoding
+        /// files, overlaps with the join. The batches read ahead are reserved in
+        /// the memory pool, and no more are read while the output of the join is
+        /// not consumed. 0 disables reading ahead
+        pub hash_join_probe_read_ahead: usize, default = 0
+
+        /// Number of build side rows of a hash join in CollectLeft mode above
+        /// which collecting the build side is aborted, and the join falls back
+        /// to Partitioned mode, repartitioning both sides by the hash of the join
+        /// keys. The join does not fall back if its probe side is hash
+        /// partitioned, or if it is a null-aware anti join. 0 disables the fallback
+        pub hash_join_collect_left_max_rows: usize, default = 0
+
+        /// Size in bytes of the memory the build side of a partition of a hash
+        /// join in Partitioned mode may use, above which the partition spills
+        /// both of its inputs to disk and joins them sub-partition by
+        /// sub-partition, as when the memory pool is exhausted. 0 limits the
+        /// partitions by the memory pool only
+        pub hash_join_partition_memory_budget: usize, default = 0
+
+        /// Compression codec of the spill files of a hash join, written to the
+        /// directories of the runtime's disk manager: none, lz4 or zstd.
+        /// Compressing the spill files requires the `compression` feature
+        pub hash_join_spill_compression: String, default = "none".to_string()
+
+        /// When set to true, the schema of every batch emitted by operators that
+        /// support it is checked against the schema they declare, returning an
+        /// internal error on mismatch
+        pub validate_batch_schemas: bool, default = false
     }
 }
 
@@ -276,13 +327,13 @@ config_namespace! {
 config_namespace! {
     /// Options related to aggregate execution
     pub struct AggregateOptions {
-        /// Specifies the threshold for using `ScalarValue`s to update
-        /// accumulators during high-cardinality aggregations for each input batch.
+        /// Specifies the threshold for updating the accumulators of all groups at once,
+        /// row by row, during high-cardinality aggregations for each input batch.
         ///
         /// The aggregation is considered high-cardinality if the number of affected groups
         /// is greater than or equal to `batch_size / scalar_update_factor`. In such cases,
-        /// `ScalarValue`s are utilized for updating accumulators, rather than the default
-        /// batch-slice approach. This can lead to performance improvements.
+        /// the accumulators are updated with a single pass over the rows of the batch,
+        /// rather than the default batch-slice approach. This can lead to performance improvements.
         ///
         /// By adjusting the `scalar_update_factor`, you can balance the trade-off between
         /// more efficient accumulator updates and the number of groups affected.
@@ -370,6 +421,27 @@ config_namespace! {
         /// The maximum estimated size in bytes for one input side of a HashJoin
         /// will be collected into a single partition
         pub hash_join_single_partition_threshold: usize, default = 1024 * 1024
+
+        /// The maximum estimated number of rows for one input side of a HashJoin
+        /// will be collected into a single partition, used when its size in bytes
+        /// is unknown. Without statistics the input is never collected
+        pub hash_join_single_partition_threshold_rows: usize, default = 1024 * 128
+
+        /// When the build side of a partitioned HashJoin is estimated to have fewer
+        /// rows than this per partition, it is collected into a single partition instead,
+        /// avoiding the overhead of many tiny hash tables. 0 disables this check
+        pub hash_join_min_partition_build_rows: usize, default = 0
+
+        /// The fraction of the rows matching the join keys assumed to be kept by
+        /// a conjunct of the filter of a HashJoin which can not be estimated from
+        /// the statistics of the join inputs, used to estimate the number of rows
+        /// produced by the join
+        pub hash_join_default_filter_selectivity: f64, default = 0.2
+
+        /// The inputs of a HashJoin are swapped to build the hash table on the
+        /// right input when the left input is estimated to be larger than this
+        /// many times the right input
+        pub hash_join_swap_size_ratio: f64, default = 1.0
     }
 }
 
@@ -720,6 +792,7 @@ macro_rules! config_field {
 config_field!(String);
 config_field!(bool);
 config_field!(usize);
+config_field!(f64);
 
 /// An implementation trait used to recursively walk configuration
 trait Visit {
@@ -38,12 +38,12 @@ use arrow::{
     array::*,
     compute::kernels::cast::{cast_with_options, CastOptions},
     datatypes::{
-        ArrowDictionaryKeyType, ArrowNativeType, DataType, Field, Float32Type,
-        Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, IntervalDayTimeType,
-        IntervalMonthDayNanoType, IntervalUnit, IntervalYearMonthType, TimeUnit,
-        TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
-        TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
-        DECIMAL128_MAX_PRECISION,
+        ArrowDictionaryKeyType, ArrowNativeType, ArrowNativeTypeOp, DataType, Field,
+        Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+        IntervalDayTimeType, IntervalMonthDayNanoType, IntervalUnit,
+        IntervalYearMonthType, TimeUnit, TimestampMicrosecondType,
+        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+        UInt16Type, UInt32Type, UInt64Type, UInt8Type, DECIMAL128_MAX_PRECISION,
     },
 };
 use arrow_array::timezone::Tz;
@@ -888,7 +888,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 *rhs,
                 get_sign!($OPERATION),
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalDayTime(Some(lhs)),
                 ScalarValue::IntervalDayTime(Some(rhs)),
@@ -896,7 +896,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 *rhs,
                 get_sign!($OPERATION),
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalMonthDayNano(Some(lhs)),
                 ScalarValue::IntervalMonthDayNano(Some(rhs)),
@@ -904,7 +904,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 *rhs,
                 get_sign!($OPERATION),
-            )))),
+            )?))),
             // Binary operations on arguments with different types:
             (ScalarValue::Date32(Some(days)), _) => {
                 let value = date32_op(*days, $RHS, get_sign!($OPERATION))?;
@@ -954,7 +954,7 @@ macro_rules! impl_op_arithmetic {
                 *rhs,
                 get_sign!($OPERATION),
                 false,
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalYearMonth(Some(lhs)),
                 ScalarValue::IntervalMonthDayNano(Some(rhs)),
@@ -963,7 +963,7 @@ macro_rules! impl_op_arithmetic {
                 *rhs,
                 get_sign!($OPERATION),
                 false,
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalDayTime(Some(lhs)),
                 ScalarValue::IntervalYearMonth(Some(rhs)),
@@ -972,7 +972,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 get_sign!($OPERATION),
                 true,
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalDayTime(Some(lhs)),
                 ScalarValue::IntervalMonthDayNano(Some(rhs)),
@@ -981,7 +981,7 @@ macro_rules! impl_op_arithmetic {
                 *rhs,
                 get_sign!($OPERATION),
                 false,
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalMonthDayNano(Some(lhs)),
                 ScalarValue::IntervalYearMonth(Some(rhs)),
@@ -990,7 +990,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 get_sign!($OPERATION),
                 true,
-            )))),
+            )?))),
             (
                 ScalarValue::IntervalMonthDayNano(Some(lhs)),
                 ScalarValue::IntervalDayTime(Some(rhs)),
@@ -999,7 +999,7 @@ macro_rules! impl_op_arithmetic {
                 *lhs,
                 get_sign!($OPERATION),
                 true,
-            )))),
+            )?))),
             _ => Err(DataFusionError::Internal(format!(
                 "Operator {} is not implemented for types {:?} and {:?}",
                 stringify!($OPERATION),
@@ -1010,22 +1010,54 @@ macro_rules! impl_op_arithmetic {
     };
 }
 
+/// Adds (`sign` = 1) or subtracts (`sign` = -1) a component of two intervals,
+/// returning an error on overflow.
+#[inline]
+fn op_interval_component<T: ArrowNativeTypeOp>(lhs: T, rhs: T, sign: i32) -> Result<T> {
+    if sign < 0 {
+        Ok(lhs.sub_checked(rhs)?)
+    } else {
+        Ok(lhs.add_checked(rhs)?)
+    }
+}
+
+/// Splits a "raw" [`IntervalDayTimeType`] interval into the month, day and
+/// nanosecond components of a [`IntervalMonthDayNanoType`] interval.
+#[inline]
+fn dt_to_mdn_parts(interval: i64) -> (i32, i32, i64) {
+    let (days, millis) = IntervalDayTimeType::to_parts(interval);
+    (0, days, millis as i64 * 1_000_000)
+}
+
+/// Adds/subtracts the month, day and nanosecond components of two intervals,
+/// returning a "raw" [`IntervalMonthDayNanoType`] interval.
+#[inline]
+fn op_mdn_parts(
+    (lhs_months, lhs_days, lhs_nanos): (i32, i32, i64),
+    (rhs_months, rhs_days, rhs_nanos): (i32, i32, i64),
+    sign: i32,
+) -> Result<i128> {
+    Ok(IntervalMonthDayNanoType::make_value(
+        op_interval_component(lhs_months, rhs_months, sign)?,
+        op_interval_component(lhs_days, rhs_days, sign)?,
+        op_interval_component(lhs_nanos, rhs_nanos, sign)?,
+    ))
+}
+
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of different
 /// types ([`IntervalYearMonthType`] and [`IntervalDayTimeType`], respectively).
 /// The argument `sign` chooses between addition and subtraction, the argument
 /// `commute` swaps `lhs` and `rhs`. The return value is an 128-bit integer.
 /// It can be involved in a [`IntervalMonthDayNanoType`] in the outer scope.
+/// Returns an error if any component of the result overflows.
 #[inline]
-pub fn op_ym_dt(mut lhs: i32, rhs: i64, sign: i32, commute: bool) -> i128 {
-    let (mut days, millis) = IntervalDayTimeType::to_parts(rhs);
-    let mut nanos = (millis as i64) * 1_000_000;
+pub fn op_ym_dt(lhs: i32, rhs: i64, sign: i32, commute: bool) -> Result<i128> {
+    let (ym, dt) = ((lhs, 0, 0), dt_to_mdn_parts(rhs));
     if commute {
-        lhs *= sign;
+        op_mdn_parts(dt, ym, sign)
     } else {
-        days *= sign;
-        nanos *= sign as i64;
-    };
-    IntervalMonthDayNanoType::make_value(lhs, days, nanos)
+        op_mdn_parts(ym, dt, sign)
+    }
 }
 
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of different
@@ -1033,17 +1065,15 @@ pub fn op_ym_dt(mut lhs: i32, rhs: i64, sign: i32, commute: bool) -> i128 {
 /// The argument `sign` chooses between addition and subtraction, the argument
 /// `commute` swaps `lhs` and `rhs`. The return value is an 128-bit integer.
 /// It can be involved in a [`IntervalMonthDayNanoType`] in the outer scope.
+/// Returns an error if any component of the result overflows.
 #[inline]
-pub fn op_ym_mdn(lhs: i32, rhs: i128, sign: i32, commute: bool) -> i128 {
-    let (mut months, mut days, mut nanos) = IntervalMonthDayNanoType::to_parts(rhs);
+pub fn op_ym_mdn(lhs: i32, rhs: i128, sign: i32, commute: bool) -> Result<i128> {
+    let (ym, mdn) = ((lhs, 0, 0), IntervalMonthDayNanoType::to_parts(rhs));
     if commute {
-        months += lhs * sign;
+        op_mdn_parts(mdn, ym, sign)
     } else {
-        months = lhs + (months * sign);
-        days *= sign;
-        nanos *= sign as i64;
+        op_mdn_parts(ym, mdn, sign)
     }
-    IntervalMonthDayNanoType::make_value(months, days, nanos)
 }
 
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of different
@@ -1051,60 +1081,56 @@ pub fn op_ym_mdn(lhs: i32, rhs: i128, sign: i32, commute: bool) -> i128 {
 /// The argument `sign` chooses between addition and subtraction, the argument
 /// `commute` swaps `lhs` and `rhs`. The return value is an 128-bit integer.
 /// It can be involved in a [`IntervalMonthDayNanoType`] in the outer scope.
+/// Returns an error if any component of the result overflows.
 #[inline]
-pub fn op_dt_mdn(lhs: i64, rhs: i128, sign: i32, commute: bool) -> i128 {
-    let (lhs_days, lhs_millis) = IntervalDayTimeType::to_parts(lhs);
-    let (rhs_months, rhs_days, rhs_nanos) = IntervalMonthDayNanoType::to_parts(rhs);
+pub fn op_dt_mdn(lhs: i64, rhs: i128, sign: i32, commute: bool) -> Result<i128> {
+    let (dt, mdn) = (
+        dt_to_mdn_parts(lhs),
+        IntervalMonthDayNanoType::to_parts(rhs),
+    );
     if commute {
-        IntervalMonthDayNanoType::make_value(
-            rhs_months,
-            lhs_days * sign + rhs_days,
-            (lhs_millis * sign) as i64 * 1_000_000 + rhs_nanos,
-        )
+        op_mdn_parts(mdn, dt, sign)
     } else {
-        IntervalMonthDayNanoType::make_value(
-            rhs_months * sign,
-            lhs_days + rhs_days * sign,
-            (lhs_millis as i64) * 1_000_000 + rhs_nanos * (sign as i64),
-        )
+        op_mdn_parts(dt, mdn, sign)
     }
 }
 
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of
 /// the same type [`IntervalYearMonthType`]. The argument `sign` chooses between
 /// addition and subtraction. The return value is an 32-bit integer. It can be
-/// involved in a [`IntervalYearMonthType`] in the outer scope.
+/// involved in a [`IntervalYearMonthType`] in the outer scope. Returns an error
+/// if the result overflows.
 #[inline]
-pub fn op_ym(lhs: i32, rhs: i32, sign: i32) -> i32 {
-    lhs + rhs * sign
+pub fn op_ym(lhs: i32, rhs: i32, sign: i32) -> Result<i32> {
+    op_interval_component(lhs, rhs, sign)
 }
 
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of
 /// the same type [`IntervalDayTimeType`]. The argument `sign` chooses between
 /// addition and subtraction. The return value is an 64-bit integer. It can be
-/// involved in a [`IntervalDayTimeType`] in the outer scope.
+/// involved in a [`IntervalDayTimeType`] in the outer scope. Returns an error
+/// if any component of the result overflows.
 #[inline]
-pub fn op_dt(lhs: i64, rhs: i64, sign: i32) -> i64 {
+pub fn op_dt(lhs: i64, rhs: i64, sign: i32) -> Result<i64> {
     let (lhs_days, lhs_millis) = IntervalDayTimeType::to_parts(lhs);
     let (rhs_days, rhs_millis) = IntervalDayTimeType::to_parts(rhs);
-    IntervalDayTimeType::make_value(
-        lhs_days + rhs_days * sign,
-        lhs_millis + rhs_millis * sign,
-    )
+    Ok(IntervalDayTimeType::make_value(
+        op_interval_component(lhs_days, rhs_days, sign)?,
+        op_interval_component(lhs_millis, rhs_millis, sign)?,
+    ))
 }
 
 /// This function adds/subtracts two "raw" intervals (`lhs` and `rhs`) of
 /// the same type [`IntervalMonthDayNanoType`]. The argument `sign` chooses between
 /// addition and subtraction. The return value is an 128-bit integer. It can be
-/// involved in a [`IntervalMonthDayNanoType`] in the outer scope.
+/// involved in a [`IntervalMonthDayNanoType`] in the outer scope. Returns an
+/// error if any component of the result overflows.
 #[inline]
-pub fn op_mdn(lhs: i128, rhs: i128, sign: i32) -> i128 {
-    let (lhs_months, lhs_days, lhs_nanos) = IntervalMonthDayNanoType::to_parts(lhs);
-    let (rhs_months, rhs_days, rhs_nanos) = IntervalMonthDayNanoType::to_parts(rhs);
-    IntervalMonthDayNanoType::make_value(
-        lhs_months + rhs_months * sign,
-        lhs_days + rhs_days * sign,
-        lhs_nanos + rhs_nanos * (sign as i64),
+pub fn op_mdn(lhs: i128, rhs: i128, sign: i32) -> Result<i128> {
+    op_mdn_parts(
+        IntervalMonthDayNanoType::to_parts(lhs),
+        IntervalMonthDayNanoType::to_parts(rhs),
+        sign,
     )
 }
 
@@ -5689,6 +5715,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scalar_interval_overflow() {
+        let cases = [
+            (
+                ScalarValue::IntervalYearMonth(Some(i32::MAX)),
+                ScalarValue::new_interval_ym(0, 1),
+            ),
+            (
+                ScalarValue::new_interval_dt(i32::MAX, 0),
+                ScalarValue::new_interval_dt(1, 0),
+            ),
+            (
+                ScalarValue::new_interval_mdn(0, 0, i64::MAX),
+                ScalarValue::new_interval_mdn(0, 0, 1),
+            ),
+            (
+                ScalarValue::new_interval_ym(0, 1),
+                ScalarValue::new_interval_mdn(i32::MAX, 0, 0),
+            ),
+            (
+                ScalarValue::new_interval_dt(1, 0),
+                ScalarValue::new_interval_mdn(0, i32::MAX, 0),
+            ),
+        ];
+        for (lhs, rhs) in cases.iter() {
+            let err = lhs.add(rhs).unwrap_err().to_string();
+            assert!(err.contains("Overflow"), "lhs:{lhs:?} + rhs:{rhs:?}: {err}");
+            let err = rhs.add(lhs).unwrap_err().to_string();
+            assert!(err.contains("Overflow"), "lhs:{rhs:?} + rhs:{lhs:?}: {err}");
+        }
+
+        let err = ScalarValue::IntervalYearMonth(Some(i32::MIN))
+            .sub(&ScalarValue::new_interval_ym(0, 1))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Overflow"), "{err}");
+    }
+
     #[test]
     fn timestamp_op_tests() {
         // positive interval, edge cases
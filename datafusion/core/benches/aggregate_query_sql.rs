@@ -163,6 +163,20 @@ fn criterion_benchmark(c: &mut Criterion) {
             )
         })
     });
+
+    // u64_wide has (almost) no repeated values: about 2M groups with 2^21 rows
+    let high_cardinality_ctx =
+        create_context(partitions_len, array_len * 32, batch_size).unwrap();
+
+    c.bench_function("aggregate_query_group_by_u64_wide_2m_groups", |b| {
+        b.iter(|| {
+            query(
+                high_cardinality_ctx.clone(),
+                "SELECT u64_wide, SUM(f64), MIN(f64), MAX(f64), AVG(f64), COUNT(f64) \
+                 FROM t GROUP BY u64_wide",
+            )
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,895 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! Benchmarks for hash join output materialization and probing
+//!
+//! Joins a 30-column Int64 build table with a narrow probe table, with and
+//! without the row-format copy of the build side
+//! (`datafusion.execution.hash_join_build_row_format`).
+//!
+//! Builds and probes the same build table split into many small batches,
+//! which are kept as they are rather than concatenated, and prints the peak
+//! memory of its build side.
+//!
+//! Builds the hash table of a large build table of many partitions with an
+//! increasing number of tasks (`datafusion.execution.target_partitions`), and
+//! prints the peak memory of its build side, which includes the partial hash
+//! tables merged into the hash table.
+//!
+//! Probes a build table of 100M keys with random keys, with and without
+//! looking up the probe rows in the order of their position in the hash table
+//! (`datafusion.execution.hash_join_radix_probe_threshold`).
+//!
+//! Builds and probes a hash table of keys with many duplicates, which are
+//! chained in the hash table, and probes such a table repeatedly to measure
+//! the comparison of the keys of the chained rows.
+//!
+//! Probes a build table with a stream of many small batches, which is
+//! dominated by the work done once per probe batch. With the `tracing`
+//! feature, the same join is also measured on its own as the cost of the span
+//! of every probe batch when no subscriber is installed, to compare with the
+//! benchmark built without the feature.
+//!
+//! Joins on low-cardinality dictionary encoded string keys, whose build and
+//! probe sides share their dictionary values.
+//!
+//! Joins on composite keys of integer and string columns, which are compared
+//! in the row format.
+//!
+//! Left semi and anti joins a build table with skewed keys, most rows of which
+//! have the same key.
+
+#[macro_use]
+extern crate criterion;
+use criterion::Criterion;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow::array::{ArrayRef, DictionaryArray, Int32Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::common::JoinType;
+use datafusion::datasource::streaming::PartitionStream;
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::expressions::Column;
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::StreamingTableExec;
+use datafusion::physical_plan::{collect, ExecutionPlan, SendableRecordBatchStream};
+use datafusion::prelude::{SessionConfig, SessionContext};
+use futures::StreamExt;
+use tokio::runtime::Runtime;
+
+/// Number of columns of the build table
+const BUILD_COLUMNS: usize = 30;
+/// Number of rows of the build table
+const BUILD_ROWS: usize = 100_000;
+/// Number of rows of the probe table
+const PROBE_ROWS: usize = 1_000_000;
+/// Rows per batch of the probe table
+const BATCH_SIZE: usize = 8192;
+/// Number of rows of the build table of the probe benchmark
+const LARGE_BUILD_ROWS: usize = 100_000_000;
+/// Number of distinct keys of the build table of the duplicate keys benchmark
+const DISTINCT_KEYS: usize = 1_000;
+/// Rows per batch of the probe tables of the small probe batches benchmark
+const SMALL_BATCH_SIZE: usize = 64;
+/// Rows per batch of the smallest probe table of the small probe batches
+/// benchmark, dominated by the work done once per batch
+const TINY_BATCH_SIZE: usize = 8;
+/// Number of rows of the build table of the parallel build benchmark
+const PARALLEL_BUILD_ROWS: usize = 10_000_000;
+/// Number of partitions of the build table of the parallel build benchmark
+const BUILD_PARTITIONS: usize = 16;
+/// Number of key columns of the composite keys benchmark
+const COMPOSITE_KEY_COLUMNS: usize = 4;
+/// Time the slow probe side takes to produce every batch
+const PROBE_BATCH_DELAY: Duration = Duration::from_millis(2);
+
+/// Build table: key column `k` followed by `BUILD_COLUMNS - 1` payload columns,
+/// in batches of `batch_size` rows
+fn build_table(batch_size: usize) -> Arc<dyn ExecutionPlan> {
+    let fields = (0..BUILD_COLUMNS)
+        .map(|i| {
+            let name = if i == 0 {
+                "k".to_string()
+            } else {
+                format!("c{i}")
+            };
+            Field::new(name, DataType::Int64, false)
+        })
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let batches = (0..BUILD_ROWS)
+        .step_by(batch_size)
+        .map(|start| {
+            let end = (start + batch_size).min(BUILD_ROWS);
+            let columns = (0..BUILD_COLUMNS)
+                .map(|i| {
+                    let values =
+                        (start as i64..end as i64).map(|row| row * (i as i64 + 1));
+                    Arc::new(Int64Array::from_iter_values(values)) as ArrayRef
+                })
+                .collect::<Vec<_>>();
+            RecordBatch::try_new(schema.clone(), columns).unwrap()
+        })
+        .collect::<Vec<_>>();
+    Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+}
+
+/// Probe table: key column `pk` matching every build row ten times, and a
+/// payload column `p`
+fn probe_table() -> Arc<dyn ExecutionPlan> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("pk", DataType::Int64, false),
+        Field::new("p", DataType::Int64, false),
+    ]));
+    let batches = (0..PROBE_ROWS)
+        .step_by(BATCH_SIZE)
+        .map(|start| {
+            let end = (start + BATCH_SIZE).min(PROBE_ROWS);
+            let keys = (start..end).map(|row| (row % BUILD_ROWS) as i64);
+            let values = (start..end).map(|row| row as i64);
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(keys)),
+                    Arc::new(Int64Array::from_iter_values(values)),
+                ],
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+}
+
+/// Single-column table with key column `name` holding `keys`, in batches of
+/// `batch_size` rows
+fn key_table(
+    name: &str,
+    keys: impl Iterator<Item = i64>,
+    batch_size: usize,
+) -> Arc<dyn ExecutionPlan> {
+    let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int64, false)]));
+    let keys = keys.collect::<Vec<_>>();
+    let batches = keys
+        .chunks(batch_size)
+        .map(|keys| {
+            let keys = Int64Array::from_iter_values(keys.iter().copied());
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(keys)]).unwrap()
+        })
+        .collect::<Vec<_>>();
+    Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+}
+
+/// Probes a build table of `LARGE_BUILD_ROWS` keys with `PROBE_ROWS` keys
+/// spread randomly over the build table
+fn radix_probe_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table("k", 0..LARGE_BUILD_ROWS as i64, BATCH_SIZE);
+    let right = key_table(
+        "pk",
+        (0..PROBE_ROWS as u64).map(|row| {
+            (row.wrapping_mul(0x9E37_79B9_7F4A_7C15) % LARGE_BUILD_ROWS as u64) as i64
+        }),
+        BATCH_SIZE,
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    // The build side is only collected and hashed once, on the first execution
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    );
+
+    for (threshold, order) in [(usize::MAX, "row order"), (0, "radix order")] {
+        let config = SessionConfig::new().set_usize(
+            "datafusion.execution.hash_join_radix_probe_threshold",
+            threshold,
+        );
+        let ctx = SessionContext::with_config(config);
+        rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+        c.bench_function(
+            &format!("hash join probe {LARGE_BUILD_ROWS} build rows ({order})"),
+            |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+        );
+    }
+}
+
+/// Builds a hash table of `BUILD_ROWS` rows with `DISTINCT_KEYS` distinct keys,
+/// and probes it with every key once
+fn duplicate_keys_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table(
+        "k",
+        (0..BUILD_ROWS).map(|row| (row % DISTINCT_KEYS) as i64),
+        BATCH_SIZE,
+    );
+    let right = key_table("pk", 0..DISTINCT_KEYS as i64, BATCH_SIZE);
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    let ctx = SessionContext::new();
+
+    c.bench_function(
+        &format!("hash join {BUILD_ROWS} build rows with {DISTINCT_KEYS} distinct keys"),
+        |b| {
+            b.iter(|| {
+                // A new join, so that the build side is hashed on every iteration
+                let join = Arc::new(
+                    HashJoinExec::try_new(
+                        left.clone(),
+                        right.clone(),
+                        on.clone(),
+                        None,
+                        &JoinType::Inner,
+                        PartitionMode::CollectLeft,
+                        false,
+                    )
+                    .unwrap(),
+                );
+                rt.block_on(collect(join, ctx.task_ctx())).unwrap()
+            })
+        },
+    );
+}
+
+/// Single-column table with dictionary encoded key column `name`, whose keys
+/// are `keys` into the shared `values`
+fn dictionary_key_table(
+    name: &str,
+    values: &ArrayRef,
+    keys: impl Iterator<Item = i32>,
+) -> Arc<dyn ExecutionPlan> {
+    let data_type =
+        DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let schema = Arc::new(Schema::new(vec![Field::new(name, data_type, false)]));
+    let keys = keys.collect::<Vec<_>>();
+    let batches = keys
+        .chunks(BATCH_SIZE)
+        .map(|keys| {
+            let keys = Int32Array::from_iter_values(keys.iter().copied());
+            let array =
+                DictionaryArray::<Int32Type>::try_new(keys, values.clone()).unwrap();
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap()
+        })
+        .collect::<Vec<_>>();
+    Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+}
+
+/// Table of `COMPOSITE_KEY_COLUMNS` key columns named `{prefix}0`,
+/// `{prefix}1`, ..., alternately Int64 and Utf8 columns derived from `rows`
+fn composite_key_table(
+    prefix: &str,
+    rows: impl Iterator<Item = usize>,
+) -> Arc<dyn ExecutionPlan> {
+    let fields = (0..COMPOSITE_KEY_COLUMNS)
+        .map(|i| {
+            let data_type = if i % 2 == 0 {
+                DataType::Int64
+            } else {
+                DataType::Utf8
+            };
+            Field::new(format!("{prefix}{i}"), data_type, false)
+        })
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let rows = rows.collect::<Vec<_>>();
+    let batches = rows
+        .chunks(BATCH_SIZE)
+        .map(|rows| {
+            let columns = (0..COMPOSITE_KEY_COLUMNS)
+                .map(|i| -> ArrayRef {
+                    if i % 2 == 0 {
+                        Arc::new(Int64Array::from_iter_values(
+                            rows.iter().map(|row| (row / (i + 1)) as i64),
+                        ))
+                    } else {
+                        Arc::new(StringArray::from_iter_values(
+                            rows.iter().map(|row| format!("key {i} of row {row}")),
+                        ))
+                    }
+                })
+                .collect();
+            RecordBatch::try_new(schema.clone(), columns).unwrap()
+        })
+        .collect::<Vec<_>>();
+    Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+}
+
+/// Probes a build table of `BUILD_ROWS` composite keys with `PROBE_ROWS` keys
+fn composite_keys_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = composite_key_table("k", 0..BUILD_ROWS);
+    let right = composite_key_table("pk", (0..PROBE_ROWS).map(|row| row % BUILD_ROWS));
+    let on = (0..COMPOSITE_KEY_COLUMNS)
+        .map(|i| {
+            (
+                Arc::new(
+                    Column::new_with_schema(&format!("k{i}"), &left.schema()).unwrap(),
+                ) as _,
+                Arc::new(
+                    Column::new_with_schema(&format!("pk{i}"), &right.schema()).unwrap(),
+                ) as _,
+            )
+        })
+        .collect::<Vec<_>>();
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+    c.bench_function(
+        &format!("hash join {COMPOSITE_KEY_COLUMNS} column composite keys"),
+        |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+    );
+}
+
+/// Probes a build table of `DISTINCT_KEYS` dictionary encoded strings with
+/// `PROBE_ROWS` keys sharing the dictionary values of the build side
+fn dictionary_keys_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let values: ArrayRef = Arc::new(StringArray::from_iter_values(
+        (0..DISTINCT_KEYS).map(|i| format!("a rather long dictionary value {i}")),
+    ));
+    let left = dictionary_key_table("k", &values, 0..DISTINCT_KEYS as i32);
+    let right = dictionary_key_table(
+        "pk",
+        &values,
+        (0..PROBE_ROWS).map(|row| (row % DISTINCT_KEYS) as i32),
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+    c.bench_function(
+        &format!("hash join {PROBE_ROWS} dictionary keys of {DISTINCT_KEYS} values"),
+        |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+    );
+}
+
+/// Join of a build table of `BUILD_ROWS` keys with `PROBE_ROWS` keys in batches
+/// of `batch_size` rows
+fn small_probe_batches_join(batch_size: usize) -> Arc<HashJoinExec> {
+    let left = key_table("k", 0..BUILD_ROWS as i64, BATCH_SIZE);
+    let right = key_table(
+        "pk",
+        (0..PROBE_ROWS).map(|row| (row % BUILD_ROWS) as i64),
+        batch_size,
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    )
+}
+
+/// Probes a build table of `BUILD_ROWS` keys with `PROBE_ROWS` keys in batches
+/// of `TINY_BATCH_SIZE` and `SMALL_BATCH_SIZE` rows, where the cost of every
+/// batch adds up
+fn small_probe_batches_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let ctx = SessionContext::new();
+
+    for batch_size in [TINY_BATCH_SIZE, SMALL_BATCH_SIZE] {
+        let join = small_probe_batches_join(batch_size);
+        // The build side is only collected and hashed once, on the first execution
+        rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+        c.bench_function(
+            &format!("hash join probe batches of {batch_size} rows"),
+            |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+        );
+    }
+}
+
+/// Probes a build table with batches of `TINY_BATCH_SIZE` rows with the
+/// `tracing` feature enabled and no subscriber installed, so that every probe
+/// batch enters a disabled `hash_join_probe` span. The difference with the
+/// same join of [`small_probe_batches_benchmark`] built without the feature is
+/// the overhead of the spans.
+#[cfg(feature = "tracing")]
+fn probe_span_benchmark(c: &mut Criterion) {
+    assert!(
+        !tracing::dispatcher::has_been_set(),
+        "the probe spans are only disabled without a subscriber"
+    );
+    let rt = Runtime::new().unwrap();
+    let ctx = SessionContext::new();
+    let join = small_probe_batches_join(TINY_BATCH_SIZE);
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+    c.bench_function(
+        &format!("hash join probe batches of {TINY_BATCH_SIZE} rows, disabled spans"),
+        |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+fn probe_span_benchmark(_c: &mut Criterion) {}
+
+/// Probes a hash table of `BUILD_ROWS` rows with `DISTINCT_KEYS` distinct keys,
+/// chains of `BUILD_ROWS / DISTINCT_KEYS` rows, with every key 10 times
+fn collision_chains_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table(
+        "k",
+        (0..BUILD_ROWS).map(|row| (row % DISTINCT_KEYS) as i64),
+        BATCH_SIZE,
+    );
+    let right = key_table(
+        "pk",
+        (0..10 * DISTINCT_KEYS).map(|row| (row % DISTINCT_KEYS) as i64),
+        BATCH_SIZE,
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    // The build side is only collected and hashed once, on the first execution
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+    c.bench_function(
+        &format!(
+            "hash join probe chains of {} rows",
+            BUILD_ROWS / DISTINCT_KEYS
+        ),
+        |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+    );
+}
+
+/// Left semi and anti joins a build table of `BUILD_ROWS` keys, 90% of which
+/// are the same key, with `10 * DISTINCT_KEYS` keys
+fn skewed_keys_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table(
+        "k",
+        (0..BUILD_ROWS).map(|row| if row % 10 == 0 { row as i64 } else { 0 }),
+        BATCH_SIZE,
+    );
+    let right = key_table(
+        "pk",
+        (0..10 * DISTINCT_KEYS).map(|row| (row % DISTINCT_KEYS) as i64),
+        BATCH_SIZE,
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    let ctx = SessionContext::new();
+
+    for join_type in [JoinType::LeftSemi, JoinType::LeftAnti] {
+        c.bench_function(&format!("hash join {join_type} skewed keys"), |b| {
+            b.iter(|| {
+                // A new join, as the build side rows matched by the probe side
+                // are recorded in the build side
+                let join = Arc::new(
+                    HashJoinExec::try_new(
+                        left.clone(),
+                        right.clone(),
+                        on.clone(),
+                        None,
+                        &join_type,
+                        PartitionMode::CollectLeft,
+                        false,
+                    )
+                    .unwrap(),
+                );
+                rt.block_on(collect(join, ctx.task_ctx())).unwrap()
+            })
+        });
+    }
+}
+
+/// Probes a build table of `BUILD_ROWS` keys with `PROBE_ROWS` keys of which
+/// less than 1% have a match, with and without a Bloom filter over the build side
+fn bloom_filter_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table(
+        "k",
+        (0..BUILD_ROWS as i64).map(|row| row * 1000),
+        BATCH_SIZE,
+    );
+    let right = key_table("pk", 0..PROBE_ROWS as i64, BATCH_SIZE);
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+
+    for max_size in [0, 1 << 20] {
+        // A new join, as the Bloom filter is built with the build side on the
+        // first execution
+        let join = Arc::new(
+            HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        );
+        let config = SessionConfig::new().set_usize(
+            "datafusion.execution.hash_join_bloom_filter_max_size",
+            max_size,
+        );
+        let ctx = SessionContext::with_config(config);
+        rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+        c.bench_function(
+            &format!("hash join probe sparse matches (bloom filter max size {max_size})"),
+            |b| b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap()),
+        );
+    }
+}
+
+/// A partition producing `batches`, each after `delay`, like a scan of a slow
+/// source
+struct SlowPartition {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    delay: Duration,
+}
+
+impl PartitionStream for SlowPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let delay = self.delay;
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::iter(self.batches.clone()).then(move |batch| async move {
+                tokio::task::spawn_blocking(move || std::thread::sleep(delay))
+                    .await
+                    .unwrap();
+                Ok::<_, DataFusionError>(batch)
+            }),
+        ))
+    }
+}
+
+/// Probes the build table with the probe table produced at `PROBE_BATCH_DELAY`
+/// per batch, with and without reading the probe side ahead of the join
+fn probe_read_ahead_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let probe = probe_table();
+    let batches = rt.block_on(collect(probe.clone(), SessionContext::new().task_ctx()));
+    let partition = SlowPartition {
+        schema: probe.schema(),
+        batches: batches.unwrap(),
+        delay: PROBE_BATCH_DELAY,
+    };
+    let right: Arc<dyn ExecutionPlan> = Arc::new(
+        StreamingTableExec::try_new(
+            probe.schema(),
+            vec![Arc::new(partition)],
+            None,
+            false,
+        )
+        .unwrap(),
+    );
+    let left = build_table(BUILD_ROWS);
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+
+    let mut group = c.benchmark_group("hash join slow probe side");
+    group.sample_size(10);
+    for read_ahead in [0, 2] {
+        // The build side is only collected and hashed once, on the first execution
+        let join = Arc::new(
+            HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        );
+        let config = SessionConfig::new().set_usize(
+            "datafusion.execution.hash_join_probe_read_ahead",
+            read_ahead,
+        );
+        let ctx = SessionContext::with_config(config);
+        rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+        group.bench_function(format!("read ahead {read_ahead}"), |b| {
+            b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Probes a build table of `BUILD_ROWS` keys with `PROBE_ROWS` keys of a single
+/// Int64 column, each matching one build row
+fn single_int64_key_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = key_table("k", 0..BUILD_ROWS as i64, BATCH_SIZE);
+    let right = key_table(
+        "pk",
+        (0..PROBE_ROWS).map(|row| (row % BUILD_ROWS) as i64),
+        BATCH_SIZE,
+    );
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    // The build side is only collected and hashed once, on the first execution
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap(),
+    );
+    let ctx = SessionContext::new();
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+
+    c.bench_function("hash join single Int64 key", |b| {
+        b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap())
+    });
+}
+
+/// Joins the build table split into batches of `SMALL_BATCH_SIZE` rows with
+/// the probe table, building a new hash table every iteration
+fn many_build_batches_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = build_table(SMALL_BATCH_SIZE);
+    let right = probe_table();
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    let ctx = SessionContext::new();
+    let new_join = || {
+        Arc::new(
+            HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    };
+    let num_batches = (BUILD_ROWS + SMALL_BATCH_SIZE - 1) / SMALL_BATCH_SIZE;
+
+    // Criterion only measures time, the peak memory of the build side is
+    // reported by the metrics of the join
+    let join = new_join();
+    rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+    let build_mem_used = join
+        .metrics()
+        .and_then(|metrics| metrics.sum_by_name("build_mem_used"))
+        .map(|value| value.as_usize())
+        .unwrap_or_default();
+    println!(
+        "hash join {num_batches} build batches: peak build memory {build_mem_used} bytes"
+    );
+
+    let mut group = c.benchmark_group("hash join many build batches");
+    group.sample_size(10);
+    group.bench_function(format!("{num_batches} build batches"), |b| {
+        b.iter(|| rt.block_on(collect(new_join(), ctx.task_ctx())).unwrap())
+    });
+    group.finish();
+}
+
+/// Probes a build table of `PARALLEL_BUILD_ROWS` keys in `BUILD_PARTITIONS`
+/// partitions with `DISTINCT_KEYS` keys, building a new hash table every
+/// iteration with up to `BUILD_PARTITIONS` tasks
+fn parallel_build_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("k", DataType::Int64, false)]));
+    let partitions = (0..BUILD_PARTITIONS)
+        .map(|partition| {
+            let keys = (partition..PARALLEL_BUILD_ROWS)
+                .step_by(BUILD_PARTITIONS)
+                .map(|key| key as i64)
+                .collect::<Vec<_>>();
+            keys.chunks(BATCH_SIZE)
+                .map(|keys| {
+                    let keys = Int64Array::from_iter_values(keys.iter().copied());
+                    RecordBatch::try_new(schema.clone(), vec![Arc::new(keys)]).unwrap()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let left: Arc<dyn ExecutionPlan> =
+        Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap());
+    let right = key_table("pk", 0..DISTINCT_KEYS as i64, BATCH_SIZE);
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+    let new_join = || {
+        Arc::new(
+            HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    };
+
+    let mut group = c.benchmark_group("hash join parallel build");
+    group.sample_size(10);
+    for tasks in [1, 4, BUILD_PARTITIONS] {
+        let config = SessionConfig::new().with_target_partitions(tasks);
+        let ctx = SessionContext::with_config(config);
+
+        // Criterion only measures time, the peak memory of the build side is
+        // reported by the metrics of the join
+        let join = new_join();
+        rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap();
+        let build_mem_used = join
+            .metrics()
+            .and_then(|metrics| metrics.sum_by_name("build_mem_used"))
+            .map(|value| value.as_usize())
+            .unwrap_or_default();
+        println!(
+            "hash join parallel build with {tasks} tasks: peak build memory {build_mem_used} bytes"
+        );
+
+        group.bench_function(
+            format!("{PARALLEL_BUILD_ROWS} build rows with {tasks} tasks"),
+            |b| b.iter(|| rt.block_on(collect(new_join(), ctx.task_ctx())).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let left = build_table(BUILD_ROWS);
+    let right = probe_table();
+    let on = vec![(
+        Arc::new(Column::new_with_schema("k", &left.schema()).unwrap()) as _,
+        Arc::new(Column::new_with_schema("pk", &right.schema()).unwrap()) as _,
+    )];
+
+    for join_type in [JoinType::Inner, JoinType::Left, JoinType::Full] {
+        for row_format in [false, true] {
+            let config = SessionConfig::new().set_bool(
+                "datafusion.execution.hash_join_build_row_format",
+                row_format,
+            );
+            let ctx = SessionContext::with_config(config);
+            let join = Arc::new(
+                HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    None,
+                    &join_type,
+                    PartitionMode::CollectLeft,
+                    false,
+                )
+                .unwrap(),
+            );
+            let layout = if row_format { "rows" } else { "columns" };
+            c.bench_function(
+                &format!(
+                    "hash join {join_type} {BUILD_COLUMNS} build columns ({layout})"
+                ),
+                |b| {
+                    b.iter(|| rt.block_on(collect(join.clone(), ctx.task_ctx())).unwrap())
+                },
+            );
+        }
+    }
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    radix_probe_benchmark,
+    duplicate_keys_benchmark,
+    small_probe_batches_benchmark,
+    probe_span_benchmark,
+    dictionary_keys_benchmark,
+    composite_keys_benchmark,
+    collision_chains_benchmark,
+    skewed_keys_benchmark,
+    bloom_filter_benchmark,
+    probe_read_ahead_benchmark,
+    single_int64_key_benchmark,
+    many_build_batches_benchmark,
+    parallel_build_benchmark
+);
+criterion_main!(benches);
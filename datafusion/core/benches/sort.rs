@@ -68,7 +68,8 @@
 
 use std::sync::Arc;
 
-use arrow::array::DictionaryArray;
+use arrow::array::{BooleanArray, DictionaryArray};
+use arrow::compute::filter_record_batch;
 use arrow::datatypes::Int32Type;
 use arrow::{
     array::{Float64Array, Int64Array, StringArray},
@@ -100,6 +101,10 @@ use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 /// models 8 partition plan (should it be 16??)
 const NUM_STREAMS: usize = 8;
 
+/// Total number of streams when merging the output of many partitions, e.g.
+/// of an order preserving repartition
+const MANY_STREAMS: usize = 64;
+
 /// The size of each batch within each stream
 const BATCH_SIZE: usize = 1024;
 
@@ -128,6 +133,15 @@ fn criterion_benchmark(c: &mut Criterion) {
             b.iter(move || case.run())
         });
 
+        c.bench_function(
+            &format!("merge sorted {name} {MANY_STREAMS} streams"),
+            |b| {
+                let data = split_streams(f(true), MANY_STREAMS / NUM_STREAMS);
+                let case = BenchCase::merge_sorted(&data);
+                b.iter(move || case.run())
+            },
+        );
+
         c.bench_function(&format!("sort merge {name}"), |b| {
             let data = f(false);
             let case = BenchCase::sort_merge(&data);
@@ -577,5 +591,29 @@ where
         .collect()
 }
 
+/// Splits each of the `partitions` randomly into `factor` partitions, preserving
+/// any ordering
+fn split_streams(partitions: PartitionedBatches, factor: usize) -> PartitionedBatches {
+    let mut rng = StdRng::seed_from_u64(1337);
+
+    partitions
+        .into_iter()
+        .flat_map(|batches| {
+            let mut outputs = vec![vec![]; factor];
+            for batch in batches {
+                let targets: Vec<_> = (0..batch.num_rows())
+                    .map(|_| rng.gen_range(0..factor))
+                    .collect();
+                for (idx, output) in outputs.iter_mut().enumerate() {
+                    let mask: BooleanArray =
+                        targets.iter().map(|target| Some(*target == idx)).collect();
+                    output.push(filter_record_batch(&batch, &mask).unwrap());
+                }
+            }
+            outputs
+        })
+        .collect()
+}
+
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
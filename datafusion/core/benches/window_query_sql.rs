@@ -222,6 +222,38 @@ fn criterion_benchmark(c: &mut Criterion) {
             })
         },
     );
+
+    c.bench_function(
+        "window partition and order by, u64_wide, single rank function",
+        |b| {
+            b.iter(|| {
+                query(
+                    ctx.clone(),
+                    "SELECT \
+                        RANK() OVER (PARTITION BY u64_wide ORDER by f64) \
+                    FROM t",
+                )
+            })
+        },
+    );
+
+    c.bench_function(
+        "window partition and order by, u64_wide, rank functions",
+        |b| {
+            b.iter(|| {
+                query(
+                    ctx.clone(),
+                    "SELECT \
+                        RANK() OVER (PARTITION BY u64_wide ORDER by f64), \
+                        DENSE_RANK() OVER (PARTITION BY u64_wide ORDER by f64), \
+                        PERCENT_RANK() OVER (PARTITION BY u64_wide ORDER by f64), \
+                        CUME_DIST() OVER (PARTITION BY u64_wide ORDER by f64), \
+                        ROW_NUMBER() OVER (PARTITION BY u64_wide ORDER by f64) \
+                    FROM t",
+                )
+            })
+        },
+    );
 }
 
 criterion_group!(benches, criterion_benchmark);
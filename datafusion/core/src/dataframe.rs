@@ -48,8 +48,9 @@ use crate::logical_expr::{
     col, utils::find_window_exprs, Expr, JoinType, LogicalPlan, LogicalPlanBuilder,
     Partitioning, TableType,
 };
-use crate::physical_plan::SendableRecordBatchStream;
+use crate::physical_plan::metrics::PlanMetrics;
 use crate::physical_plan::{collect, collect_partitioned};
+use crate::physical_plan::{common, SendableRecordBatchStream};
 use crate::physical_plan::{execute_stream, execute_stream_partitioned, ExecutionPlan};
 use crate::prelude::SessionContext;
 
@@ -98,6 +99,14 @@ impl DataFrame {
         self.session_state.create_physical_plan(&self.plan).await
     }
 
+    /// Creates the physical plan about to be executed, and retains it in the
+    /// session, see [`SessionContext::last_execution_metrics`]
+    async fn create_executed_plan(self) -> Result<Arc<dyn ExecutionPlan>> {
+        let plan = self.session_state.create_physical_plan(&self.plan).await?;
+        self.session_state.record_execution(plan.clone());
+        Ok(plan)
+    }
+
     /// Filter the DataFrame by column. Returns a new DataFrame only containing the
     /// specified columns.
     ///
@@ -659,10 +668,52 @@ impl DataFrame {
     /// ```
     pub async fn collect(self) -> Result<Vec<RecordBatch>> {
         let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
+        let plan = self.create_executed_plan().await?;
         collect(plan, task_ctx).await
     }
 
+    /// Executes this DataFrame and collects the first `n` rows into a vector of
+    /// RecordBatch, stopping the execution as soon as they have been produced.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let batches = df.collect_limited(10).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn collect_limited(self, n: usize) -> Result<Vec<RecordBatch>> {
+        let task_ctx = Arc::new(self.task_ctx());
+        let plan = self.limit(0, Some(n))?.create_executed_plan().await?;
+        common::collect_limited(execute_stream(plan, task_ctx)?, n).await
+    }
+
+    /// Executes this DataFrame and collects all results into a vector of RecordBatch,
+    /// together with the metrics of every operator of the executed plan.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let (batches, metrics) = df.collect_with_metrics().await?;
+    /// println!("{metrics}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn collect_with_metrics(self) -> Result<(Vec<RecordBatch>, PlanMetrics)> {
+        let task_ctx = Arc::new(self.task_ctx());
+        let plan = self.create_executed_plan().await?;
+        let batches = collect(plan.clone(), task_ctx).await?;
+        Ok((batches, PlanMetrics::new(plan)))
+    }
+
     /// Print results.
     ///
     /// ```
@@ -718,10 +769,34 @@ impl DataFrame {
     /// ```
     pub async fn execute_stream(self) -> Result<SendableRecordBatchStream> {
         let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
+        let plan = self.create_executed_plan().await?;
         execute_stream(plan, task_ctx)
     }
 
+    /// Executes this DataFrame and returns a stream over a single partition, together
+    /// with the metrics of every operator of the executing plan, which can be
+    /// inspected while the stream is consumed.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let (stream, metrics) = df.execute_stream_with_metrics().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_stream_with_metrics(
+        self,
+    ) -> Result<(SendableRecordBatchStream, PlanMetrics)> {
+        let task_ctx = Arc::new(self.task_ctx());
+        let plan = self.create_executed_plan().await?;
+        let stream = execute_stream(plan.clone(), task_ctx)?;
+        Ok((stream, PlanMetrics::new(plan)))
+    }
+
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
     /// maintaining the input partitioning.
     ///
@@ -738,7 +813,7 @@ impl DataFrame {
     /// ```
     pub async fn collect_partitioned(self) -> Result<Vec<Vec<RecordBatch>>> {
         let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
+        let plan = self.create_executed_plan().await?;
         collect_partitioned(plan, task_ctx).await
     }
 
@@ -759,7 +834,7 @@ impl DataFrame {
         self,
     ) -> Result<Vec<SendableRecordBatchStream>> {
         let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
+        let plan = self.create_executed_plan().await?;
         execute_stream_partitioned(plan, task_ctx)
     }
 
@@ -1141,6 +1216,7 @@ mod tests {
 
     use crate::execution::context::SessionConfig;
     use crate::execution::options::{CsvReadOptions, ParquetReadOptions};
+    use crate::physical_plan::metrics::OperatorMetrics;
     use crate::physical_plan::ColumnarValue;
     use crate::physical_plan::Partitioning;
     use crate::physical_plan::PhysicalExpr;
@@ -1342,6 +1418,87 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_metrics() -> Result<()> {
+        let left = test_table().await?.select_columns(&["c1", "c2"])?;
+        let right = test_table_with_name("c2")
+            .await?
+            .select_columns(&["c1", "c3"])?;
+        let join = left.join(right, JoinType::Inner, &["c1"], &["c1"], None)?;
+        let (join_rows, metrics) = join.collect_with_metrics().await?;
+        assert_eq!(2008, join_rows.iter().map(|x| x.num_rows()).sum::<usize>());
+
+        let operators = metrics.operators();
+        assert_eq!(operators[0].path, "0");
+        let hash_join = operators
+            .iter()
+            .find(|operator| operator.name == "HashJoinExec")
+            .expect("plan should contain a HashJoinExec");
+        let hash_join_metrics = hash_join.metrics.clone().unwrap();
+        assert_eq!(hash_join_metrics.output_rows(), Some(2008));
+        assert!(hash_join_metrics.sum_by_name("build_input_rows").is_some());
+
+        // the operator can be looked up again by its path
+        let same = metrics.operator(&hash_join.path).unwrap();
+        assert_eq!(same.name, "HashJoinExec");
+        assert!(metrics
+            .to_string()
+            .contains("HashJoinExec: output_rows=2008"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn last_execution_metrics() -> Result<()> {
+        let mut ctx = SessionContext::new();
+        register_aggregate_csv(&mut ctx, "a").await?;
+        register_aggregate_csv(&mut ctx, "b").await?;
+        assert!(ctx.last_execution_metrics().is_none());
+
+        let left = ctx.table("a").await?.select_columns(&["c1", "c2"])?;
+        let right = ctx.table("b").await?.select_columns(&["c1", "c3"])?;
+        let join = left.join(right, JoinType::Inner, &["c1"], &["c1"], None)?;
+        let join_rows = join.collect().await?;
+        assert_eq!(2008, join_rows.iter().map(|x| x.num_rows()).sum::<usize>());
+
+        let metrics = ctx
+            .last_execution_metrics()
+            .expect("the join should be retained by the session");
+        let hash_join = metrics
+            .operators()
+            .into_iter()
+            .find(|operator| operator.name == "HashJoinExec")
+            .expect("plan should contain a HashJoinExec");
+        assert_eq!(hash_join.metrics.unwrap().output_rows(), Some(2008));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn metrics_snapshot_diff() -> Result<()> {
+        let (stream, metrics) = test_table().await?.execute_stream_with_metrics().await?;
+        let output_rows = |operator: &OperatorMetrics| {
+            operator
+                .metrics
+                .as_ref()
+                .and_then(|metrics| metrics.output_rows())
+                .unwrap_or_default()
+        };
+
+        let before = metrics.operators();
+        let batches = common::collect(stream).await?;
+        assert_eq!(100, batches.iter().map(|x| x.num_rows()).sum::<usize>());
+
+        // the snapshot is not updated by the execution
+        assert_eq!(output_rows(&before[0]), 0);
+        let after = metrics.operators();
+        assert_eq!(output_rows(&after[0]), 100);
+        assert_eq!(output_rows(&metrics.diff(&before)[0]), 100);
+        assert_eq!(output_rows(&metrics.diff(&after)[0]), 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_on() -> Result<()> {
         let left = test_table_with_name("a")
@@ -1576,6 +1733,17 @@ mod tests {
         ctx.table(name).await
     }
 
+    #[tokio::test]
+    async fn collect_limited() -> Result<()> {
+        let batches = test_table().await?.collect_limited(7).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 7);
+
+        // the whole table has fewer rows than requested
+        let batches = test_table().await?.collect_limited(1000).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+        Ok(())
+    }
+
     async fn test_table() -> Result<DataFrame> {
         test_table_with_name("aggregate_test_100").await
     }
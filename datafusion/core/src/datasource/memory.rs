@@ -23,10 +23,12 @@ use std::any::Any;
 use std::fmt::{self, Debug, Display};
 use std::sync::Arc;
 
-use arrow::datatypes::SchemaRef;
+use arrow::compute::cast;
+use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
 use datafusion_execution::TaskContext;
+use datafusion_expr::type_coercion::functions::can_coerce_from;
 use tokio::sync::RwLock;
 
 use crate::datasource::{TableProvider, TableType};
@@ -172,7 +174,10 @@ impl TableProvider for MemTable {
 
     /// Returns an ExecutionPlan that inserts the execution results of a given [`ExecutionPlan`] into this [`MemTable`].
     ///
-    /// The [`ExecutionPlan`] must have the same schema as this [`MemTable`].
+    /// The [`ExecutionPlan`] must produce the same number of columns as this
+    /// [`MemTable`], and each column must either have the type of the
+    /// corresponding table column or be safely coercible to it (e.g.
+    /// `Int32` into `Int64`). Coerced columns are cast as they are written.
     ///
     /// # Arguments
     ///
@@ -187,20 +192,50 @@ impl TableProvider for MemTable {
         _state: &SessionState,
         input: Arc<dyn ExecutionPlan>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        // Create a physical plan from the logical plan.
-        // Check that the schema of the plan matches the schema of this table.
-        if !input.schema().eq(&self.schema) {
-            return Err(DataFusionError::Plan(
-                "Inserting query must have the same schema with the table.".to_string(),
-            ));
-        }
-        let sink = Arc::new(MemSink::new(self.batches.clone()));
+        // Check that the schema of the plan is compatible with the schema of this table.
+        check_insert_schema(&self.schema, &input.schema())?;
+        let sink = Arc::new(MemSink::new(self.schema.clone(), self.batches.clone()));
         Ok(Arc::new(InsertExec::new(input, sink)))
     }
 }
 
+/// Checks that batches of `input_schema` can be inserted into a table of
+/// `table_schema`: the number of columns must match and every input column
+/// must be safely coercible to the type of the corresponding table column.
+///
+/// Nullability is not checked here, as nulls written into a non-nullable
+/// column are rejected when the batches are written.
+fn check_insert_schema(table_schema: &Schema, input_schema: &Schema) -> Result<()> {
+    if table_schema.fields().len() != input_schema.fields().len() {
+        return Err(DataFusionError::Plan(format!(
+            "Inserting query must have the same number of columns as the table: \
+             expected {}, got {}",
+            table_schema.fields().len(),
+            input_schema.fields().len()
+        )));
+    }
+    for (table_field, input_field) in table_schema
+        .fields()
+        .iter()
+        .zip(input_schema.fields().iter())
+    {
+        if !can_coerce_from(table_field.data_type(), input_field.data_type()) {
+            return Err(DataFusionError::Plan(format!(
+                "Inserting query has incompatible type for column '{}': \
+                 expected {:?}, got {:?}",
+                table_field.name(),
+                table_field.data_type(),
+                input_field.data_type()
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Implements for writing to a [`MemTable`]
 struct MemSink {
+    /// Schema of the table, incoming batches are cast to it
+    schema: SchemaRef,
     /// Target locations for writing data
     batches: Vec<PartitionData>,
 }
@@ -221,8 +256,29 @@ impl Display for MemSink {
 }
 
 impl MemSink {
-    fn new(batches: Vec<PartitionData>) -> Self {
-        Self { batches }
+    fn new(schema: SchemaRef, batches: Vec<PartitionData>) -> Self {
+        Self { schema, batches }
+    }
+
+    /// Casts `batch` to the schema of the table, renaming its columns and
+    /// coercing their types where needed
+    fn cast_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        if batch.schema() == self.schema {
+            return Ok(batch);
+        }
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.schema.fields().iter())
+            .map(|(column, field)| {
+                if column.data_type() == field.data_type() {
+                    Ok(column.clone())
+                } else {
+                    Ok(cast(column, field.data_type())?)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
     }
 }
 
@@ -242,7 +298,7 @@ impl DataSink for MemSink {
         let mut row_count = 0;
         while let Some(batch) = data.next().await.transpose()? {
             row_count += batch.num_rows();
-            new_batches[i].push(batch);
+            new_batches[i].push(self.cast_batch(batch)?);
             i = (i + 1) % num_partitions;
         }
 
@@ -262,8 +318,8 @@ mod tests {
     use crate::datasource::provider_as_source;
     use crate::physical_plan::collect;
     use crate::prelude::SessionContext;
-    use arrow::array::{AsArray, Int32Array};
-    use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+    use arrow::array::{AsArray, Int32Array, Int64Array};
+    use arrow::datatypes::{DataType, Field, Int64Type, Schema, UInt64Type};
     use arrow::error::ArrowError;
     use datafusion_expr::LogicalPlanBuilder;
     use futures::StreamExt;
@@ -659,4 +715,140 @@ mod tests {
         assert_eq!(resulting_data_in_table[0].len(), 2);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert_from_query_then_join() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let table = Arc::new(MemTable::try_new(schema, vec![vec![], vec![]])?);
+        session_ctx.register_table("t", table)?;
+        session_ctx
+            .sql(
+                "CREATE TABLE source AS VALUES \
+                 (1, 'a', 10), (2, 'b', 20), (3, 'c', 30), (4, 'd', 40)",
+            )
+            .await?
+            .collect()
+            .await?;
+
+        // the source columns are coerced and renamed to the table columns
+        let res = session_ctx
+            .sql("INSERT INTO t SELECT column1, column2 FROM source WHERE column3 > 10")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(extract_count(res), 3);
+
+        // subsequent scans, including hash joins against the table, see the new data
+        let res = session_ctx
+            .sql(
+                "SELECT t.id, t.name, source.column3 FROM t \
+                 JOIN source ON t.id = source.column1 AND t.name = source.column2",
+            )
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+----+------+---------+",
+            "| id | name | column3 |",
+            "+----+------+---------+",
+            "| 2  | b    | 20      |",
+            "| 3  | c    | 30      |",
+            "| 4  | d    | 40      |",
+            "+----+------+---------+",
+        ];
+        crate::assert_batches_sorted_eq!(expected, &res);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_coerces_input() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let table_schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let input_schema =
+            Arc::new(Schema::new(vec![Field::new("b", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            input_schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], input_schema, None)?);
+        let table = MemTable::try_new(table_schema.clone(), vec![vec![]])?;
+        let plan = table.insert_into(&session_ctx.state(), input).await?;
+        let res = collect(plan, session_ctx.task_ctx()).await?;
+        assert_eq!(extract_count(res), 3);
+
+        let batches = table.batches[0].read().await.clone();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].schema(), table_schema);
+        assert_eq!(
+            batches[0].column(0).as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![1, 2, 3])
+        );
+
+        // nulls cannot be written into a non-nullable column
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)])),
+            vec![Arc::new(Int32Array::from(vec![Some(4), None]))],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone()]],
+            batch.schema(),
+            None,
+        )?);
+        let plan = table.insert_into(&session_ctx.state(), input).await?;
+        let err = collect(plan, session_ctx.task_ctx()).await.unwrap_err();
+        assert!(
+            err.to_string().contains("non-nullable"),
+            "unexpected error: {err}"
+        );
+        assert_eq!(table.batches[0].read().await.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_schema_mismatch() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let table = MemTable::try_new(schema, vec![vec![]])?;
+
+        let cases = vec![
+            (
+                Schema::new(vec![Field::new("a", DataType::Int32, false)]),
+                "Error during planning: Inserting query must have the same number \
+                 of columns as the table: expected 2, got 1",
+            ),
+            (
+                Schema::new(vec![
+                    Field::new("a", DataType::Int32, false),
+                    Field::new("b", DataType::Int64, false),
+                ]),
+                "Error during planning: Inserting query has incompatible type \
+                 for column 'b': expected Int32, got Int64",
+            ),
+            (
+                Schema::new(vec![
+                    Field::new("a", DataType::Boolean, false),
+                    Field::new("b", DataType::Int32, false),
+                ]),
+                "Error during planning: Inserting query has incompatible type \
+                 for column 'a': expected Int32, got Boolean",
+            ),
+        ];
+        for (input_schema, expected) in cases {
+            let input = Arc::new(MemoryExec::try_new(&[], Arc::new(input_schema), None)?);
+            let err = table
+                .insert_into(&session_ctx.state(), input)
+                .await
+                .unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+        Ok(())
+    }
 }
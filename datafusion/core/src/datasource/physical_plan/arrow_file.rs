@@ -138,7 +138,7 @@ impl ExecutionPlan for ArrowExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "ArrowExec: {}", self.base_config)
             }
         }
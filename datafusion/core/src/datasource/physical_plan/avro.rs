@@ -147,7 +147,7 @@ impl ExecutionPlan for AvroExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "AvroExec: {}", self.base_config)
             }
         }
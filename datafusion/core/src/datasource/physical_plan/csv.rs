@@ -178,7 +178,7 @@ impl ExecutionPlan for CsvExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
                     "CsvExec: {}, has_header={}",
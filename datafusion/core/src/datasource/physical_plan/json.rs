@@ -156,7 +156,7 @@ impl ExecutionPlan for NdJsonExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "JsonExec: {}", self.base_config)
             }
         }
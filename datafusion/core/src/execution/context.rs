@@ -38,7 +38,7 @@ use datafusion_expr::{
 };
 pub use datafusion_physical_expr::execution_props::ExecutionProps;
 use datafusion_physical_expr::var_provider::is_system_variables;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::hash_map::Entry;
 use std::string::String;
 use std::sync::Arc;
@@ -84,6 +84,7 @@ use crate::config::ConfigOptions;
 use crate::datasource::physical_plan::{plan_to_csv, plan_to_json, plan_to_parquet};
 use crate::execution::{runtime_env::RuntimeEnv, FunctionRegistry};
 use crate::physical_optimizer::dist_enforcement::EnforceDistribution;
+use crate::physical_plan::metrics::PlanMetrics;
 use crate::physical_plan::planner::DefaultPhysicalPlanner;
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
@@ -106,6 +107,8 @@ use crate::datasource::object_store::ObjectStoreUrl;
 use crate::physical_optimizer::global_sort_selection::GlobalSortSelection;
 use crate::physical_optimizer::pipeline_checker::PipelineChecker;
 use crate::physical_optimizer::pipeline_fixer::PipelineFixer;
+use crate::physical_optimizer::pull_filter_into_join::PullFilterIntoJoin;
+use crate::physical_optimizer::simplify_filtered_outer_join::SimplifyFilteredOuterJoin;
 use crate::physical_optimizer::sort_enforcement::EnforceSorting;
 use datafusion_optimizer::{
     analyzer::{Analyzer, AnalyzerRule},
@@ -329,6 +332,14 @@ impl SessionContext {
         self.session_id.clone()
     }
 
+    /// Returns the metrics of the plan executed last by a [`DataFrame`] of
+    /// this session, `None` if no [`DataFrame`] was executed yet.
+    ///
+    /// If that plan is still running, the metrics report its progress so far.
+    pub fn last_execution_metrics(&self) -> Option<PlanMetrics> {
+        self.state.read().last_execution_metrics()
+    }
+
     /// Return the [`TableProviderFactory`] that is registered for the
     /// specified file type, if any.
     pub fn table_factory(
@@ -1356,6 +1367,9 @@ pub struct SessionState {
     table_factories: HashMap<String, Arc<dyn TableProviderFactory>>,
     /// Runtime environment
     runtime_env: Arc<RuntimeEnv>,
+    /// The plan executed last by a [`DataFrame`], shared by all the clones
+    /// of this state
+    last_execution: Arc<Mutex<Option<PlanMetrics>>>,
 }
 
 impl Debug for SessionState {
@@ -1428,6 +1442,13 @@ impl SessionState {
         // We need to take care of the rule ordering. They may influence each other.
         let physical_optimizers: Vec<Arc<dyn PhysicalOptimizerRule + Sync + Send>> = vec![
             Arc::new(AggregateStatistics::new()),
+            // The SimplifyFilteredOuterJoin rule turns outer joins below null-rejecting
+            // filters that the logical plan did not simplify, e.g. of plans built directly,
+            // into inner joins, which JoinSelection can swap. It should run before it.
+            Arc::new(SimplifyFilteredOuterJoin::new()),
+            // The PullFilterIntoJoin rule may change the join type of outer joins, so it
+            // should run before JoinSelection, which decides how joins are executed.
+            Arc::new(PullFilterIntoJoin::new()),
             // Statistics-based join selection will change the Auto mode to a real join implementation,
             // like collect left, or hash join, or future sort merge join, which will influence the
             // EnforceDistribution and EnforceSorting rules as they decide whether to add additional
@@ -1488,6 +1509,7 @@ impl SessionState {
             execution_props: ExecutionProps::new(),
             runtime_env: runtime,
             table_factories,
+            last_execution: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -1914,6 +1936,17 @@ impl SessionState {
         &self.session_id
     }
 
+    /// Retains `plan` as the plan executed last in this session
+    pub(crate) fn record_execution(&self, plan: Arc<dyn ExecutionPlan>) {
+        *self.last_execution.lock() = Some(PlanMetrics::new(plan));
+    }
+
+    /// Returns the metrics of the plan executed last by a [`DataFrame`] in
+    /// this session, see [`SessionContext::last_execution_metrics`]
+    pub fn last_execution_metrics(&self) -> Option<PlanMetrics> {
+        self.last_execution.lock().clone()
+    }
+
     /// Return the runtime env
     pub fn runtime_env(&self) -> &Arc<RuntimeEnv> {
         &self.runtime_env
@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cost model used by physical optimizer rules to compare alternative plans
+//! based on the [`Statistics`] of their inputs.
+
+use std::fmt::Debug;
+
+use crate::physical_plan::joins::PartitionMode;
+use crate::physical_plan::Statistics;
+
+/// Turns the [`Statistics`] of operator inputs into cost estimates.
+///
+/// Costs are expressed in arbitrary units and are only meaningful when compared
+/// with other estimates of the same model. Every method returns `None` when the
+/// statistics required for the estimate are not available.
+///
+/// [`DefaultCostModel`] is used unless a rule is given another implementation,
+/// e.g. with [`JoinSelection::with_cost_model`].
+///
+/// [`JoinSelection::with_cost_model`]: super::join_selection::JoinSelection::with_cost_model
+pub trait CostModel: Debug + Send + Sync {
+    /// Cost of processing every row of `input` once, e.g. to probe a hash table
+    fn cpu_cost(&self, input: &Statistics) -> Option<f64>;
+
+    /// Cost of building a hash table on all the rows of `input`
+    fn hash_build_cost(&self, input: &Statistics) -> Option<f64>;
+
+    /// Cost of sorting all the rows of `input`
+    fn sort_cost(&self, input: &Statistics) -> Option<f64>;
+
+    /// Cost of moving `input` between partitions, e.g. by a `RepartitionExec`
+    /// or a `CoalescePartitionsExec`
+    fn exchange_cost(&self, input: &Statistics) -> Option<f64>;
+
+    /// Cost of a hash join that builds its hash table on `build` and probes it
+    /// with `probe` using `mode`, when running `target_partitions` partitions
+    /// in parallel.
+    ///
+    /// With [`PartitionMode::CollectLeft`] the build side is gathered into a
+    /// single hash table, built once and shared by all probe partitions. With
+    /// [`PartitionMode::Partitioned`] both sides are exchanged and every
+    /// partition builds and probes its own share of the inputs. The estimate is
+    /// `None` for [`PartitionMode::Auto`], which has not been decided yet.
+    fn hash_join_cost(
+        &self,
+        build: &Statistics,
+        probe: &Statistics,
+        mode: PartitionMode,
+        target_partitions: usize,
+    ) -> Option<f64> {
+        let partitions = target_partitions.max(1) as f64;
+        let build_cost = self.hash_build_cost(build)?;
+        let probe_cost = self.cpu_cost(probe)? / partitions;
+        match mode {
+            PartitionMode::CollectLeft => {
+                Some(self.exchange_cost(build)? + build_cost + probe_cost)
+            }
+            PartitionMode::Partitioned => {
+                let exchange_cost =
+                    self.exchange_cost(build)? + self.exchange_cost(probe)?;
+                Some((exchange_cost + build_cost) / partitions + probe_cost)
+            }
+            PartitionMode::Auto => None,
+        }
+    }
+
+    /// Whether the inputs of a join should be swapped so that its hash table is
+    /// built on the smaller one, i.e. whether `left` is larger than `swap_ratio`
+    /// times `right`. Like in PrestoSQL, a ratio above 1 only swaps the inputs
+    /// if the right side is much smaller than the left side.
+    ///
+    /// The sizes are compared in bytes if known for both inputs, in rows
+    /// otherwise. The inputs are not swapped if their sizes are unknown.
+    fn should_swap_join_order(
+        &self,
+        left: &Statistics,
+        right: &Statistics,
+        swap_ratio: f64,
+    ) -> bool {
+        // TODO we need some performance test for Right Semi/Right Join swap to Left Semi/Left Join
+        // in case that the right side is smaller but not much smaller.
+        let (left_size, right_size) = match (left.total_byte_size, right.total_byte_size)
+        {
+            (Some(l), Some(r)) => (Some(l), Some(r)),
+            _ => (left.num_rows, right.num_rows),
+        };
+
+        match (left_size, right_size) {
+            (Some(l), Some(r)) => l as f64 > r as f64 * swap_ratio,
+            _ => false,
+        }
+    }
+}
+
+/// The default [`CostModel`], estimating costs linearly from the number of rows
+/// and bytes of the inputs, using tunable constants.
+#[derive(Debug, Clone)]
+pub struct DefaultCostModel {
+    /// Cost of processing a single row
+    pub cpu_per_row: f64,
+    /// Cost of hashing a single row and inserting it into a hash table
+    pub hash_build_per_row: f64,
+    /// Factor applied to `n * log2(n)` to estimate the cost of sorting `n` rows
+    pub sort_factor: f64,
+    /// Cost of moving a single byte between partitions
+    pub exchange_per_byte: f64,
+}
+
+impl Default for DefaultCostModel {
+    fn default() -> Self {
+        Self {
+            cpu_per_row: 1.0,
+            hash_build_per_row: 2.0,
+            sort_factor: 1.0,
+            exchange_per_byte: 0.1,
+        }
+    }
+}
+
+impl CostModel for DefaultCostModel {
+    fn cpu_cost(&self, input: &Statistics) -> Option<f64> {
+        input
+            .num_rows
+            .map(|num_rows| num_rows as f64 * self.cpu_per_row)
+    }
+
+    fn hash_build_cost(&self, input: &Statistics) -> Option<f64> {
+        input
+            .num_rows
+            .map(|num_rows| num_rows as f64 * self.hash_build_per_row)
+    }
+
+    fn sort_cost(&self, input: &Statistics) -> Option<f64> {
+        input.num_rows.map(|num_rows| {
+            let num_rows = num_rows as f64;
+            self.sort_factor * num_rows * num_rows.max(1.0).log2()
+        })
+    }
+
+    fn exchange_cost(&self, input: &Statistics) -> Option<f64> {
+        input
+            .total_byte_size
+            .map(|size| size as f64 * self.exchange_per_byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(num_rows: usize, total_byte_size: usize) -> Statistics {
+        Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: Some(total_byte_size),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn small_build_side_prefers_collect_left() {
+        let model = DefaultCostModel::default();
+        let small = stats(100, 1_000);
+        let huge = stats(10_000_000, 100_000_000);
+
+        let collect_left =
+            model.hash_join_cost(&small, &huge, PartitionMode::CollectLeft, 8);
+        let partitioned =
+            model.hash_join_cost(&small, &huge, PartitionMode::Partitioned, 8);
+        assert!(collect_left.unwrap() < partitioned.unwrap());
+
+        // building on the small side is cheaper than building on the huge one
+        let swapped = model.hash_join_cost(&huge, &small, PartitionMode::Partitioned, 8);
+        assert!(partitioned.unwrap() < swapped.unwrap());
+    }
+
+    #[test]
+    fn huge_inputs_prefer_partitioned() {
+        let model = DefaultCostModel::default();
+        let huge1 = stats(10_000_000, 100_000_000);
+        let huge2 = stats(20_000_000, 200_000_000);
+
+        let collect_left =
+            model.hash_join_cost(&huge1, &huge2, PartitionMode::CollectLeft, 8);
+        let partitioned =
+            model.hash_join_cost(&huge1, &huge2, PartitionMode::Partitioned, 8);
+        assert!(partitioned.unwrap() < collect_left.unwrap());
+
+        // without parallelism there is nothing to gain from repartitioning
+        let collect_left =
+            model.hash_join_cost(&huge1, &huge2, PartitionMode::CollectLeft, 1);
+        let partitioned =
+            model.hash_join_cost(&huge1, &huge2, PartitionMode::Partitioned, 1);
+        assert!(collect_left.unwrap() < partitioned.unwrap());
+    }
+
+    #[test]
+    fn swap_to_build_on_smaller_side() {
+        let model = DefaultCostModel::default();
+        let small = stats(100, 1_000);
+        let big = stats(1_000, 10_000);
+
+        assert!(model.should_swap_join_order(&big, &small, 1.0));
+        assert!(!model.should_swap_join_order(&small, &big, 1.0));
+        // only much smaller right sides are swapped with a higher ratio
+        assert!(!model.should_swap_join_order(&big, &small, 20.0));
+
+        // bytes are compared before rows
+        let wide = stats(100, 100_000);
+        assert!(model.should_swap_join_order(&wide, &big, 1.0));
+        // rows are compared if the size in bytes of a side is unknown
+        let rows_only = Statistics {
+            num_rows: Some(100),
+            ..Default::default()
+        };
+        assert!(model.should_swap_join_order(&big, &rows_only, 1.0));
+        assert!(!model.should_swap_join_order(&big, &Statistics::default(), 1.0));
+    }
+
+    #[test]
+    fn sort_cost_grows_faster_than_linear() {
+        let model = DefaultCostModel::default();
+        let small = model.sort_cost(&stats(1_000, 8_000)).unwrap();
+        let big = model.sort_cost(&stats(1_000_000, 8_000_000)).unwrap();
+        assert!(big > 1_000.0 * small);
+        assert_eq!(model.sort_cost(&stats(0, 0)), Some(0.0));
+    }
+
+    #[test]
+    fn missing_statistics() {
+        let model = DefaultCostModel::default();
+        let unknown = Statistics::default();
+        let known = stats(100, 1_000);
+
+        assert_eq!(model.cpu_cost(&unknown), None);
+        assert_eq!(model.exchange_cost(&unknown), None);
+        for mode in [PartitionMode::CollectLeft, PartitionMode::Partitioned] {
+            assert_eq!(model.hash_join_cost(&unknown, &known, mode, 4), None);
+            assert_eq!(model.hash_join_cost(&known, &unknown, mode, 4), None);
+        }
+        assert_eq!(
+            model.hash_join_cost(&known, &known, PartitionMode::Auto, 4),
+            None
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! InstrumentExecution optimizer that wraps every operator of a plan
+//! in an [`InstrumentedExec`] so its execution is recorded as `tracing` spans
+
+use crate::config::ConfigOptions;
+use crate::{
+    error::Result,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{instrumented::InstrumentedExec, ExecutionPlan},
+};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use std::sync::Arc;
+
+/// Optimizer rule that wraps each operator in an [`InstrumentedExec`], producing
+/// one `tracing` span per (operator, partition) when the plan is executed.
+///
+/// This rule is only available with the `tracing` feature and is not part of the
+/// default rule set. As other rules downcast operators to their concrete types,
+/// it should be registered last, e.g. with `SessionState::add_physical_optimizer_rule`.
+#[derive(Default)]
+pub struct InstrumentExecution {}
+
+impl InstrumentExecution {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for InstrumentExecution {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            if plan.as_any().downcast_ref::<InstrumentedExec>().is_some() {
+                Ok(Transformed::No(plan))
+            } else {
+                Ok(Transformed::Yes(Arc::new(InstrumentedExec::new(plan))))
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "instrument_execution"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
@@ -18,18 +18,16 @@
 //! Select the proper PartitionMode and build side based on the avaliable statistics for hash join.
 use std::sync::Arc;
 
-use arrow::datatypes::Schema;
-
 use crate::config::ConfigOptions;
 use crate::logical_expr::JoinType;
-use crate::physical_plan::expressions::Column;
+pub use crate::physical_plan::joins::utils::{swap_join_type, swap_reverting_projection};
 use crate::physical_plan::joins::{
-    utils::{ColumnIndex, JoinFilter, JoinSide},
-    CrossJoinExec, HashJoinExec, PartitionMode,
+    utils::supports_collect_by_thresholds, CrossJoinExec, HashJoinExec, PartitionMode,
 };
 use crate::physical_plan::projection::ProjectionExec;
-use crate::physical_plan::{ExecutionPlan, PhysicalExpr};
+use crate::physical_plan::ExecutionPlan;
 
+use super::cost::{CostModel, DefaultCostModel};
 use super::optimizer::PhysicalOptimizerRule;
 use crate::error::Result;
 use datafusion_common::tree_node::{Transformed, TreeNode};
@@ -37,58 +35,68 @@ use datafusion_common::tree_node::{Transformed, TreeNode};
 /// For hash join with the partition mode [PartitionMode::Auto], JoinSelection rule will make
 /// a cost based decision to select which PartitionMode mode(Partitioned/CollectLeft) is optimal
 /// based on the available statistics that the inputs have.
+/// A build side smaller than `hash_join_single_partition_threshold` bytes, or than
+/// `hash_join_single_partition_threshold_rows` rows if its size in bytes is unknown, is always collected, otherwise
+/// the [CostModel] of the rule decides whether collecting it is cheaper than repartitioning both inputs.
 /// If the statistics information is not available, the partition mode will fall back to [PartitionMode::Partitioned].
 ///
+/// A [PartitionMode::Partitioned] hash join is turned into a [PartitionMode::CollectLeft] one when
+/// its build side would only have a few rows per partition, according to
+/// `hash_join_min_partition_build_rows`.
+///
 /// JoinSelection rule will also reorder the build and probe phase of the hash joins
 /// based on the avaliable statistics that the inputs have.
-/// The rule optimizes the order such that the left (build) side of the join is the smallest.
+/// The rule optimizes the order such that the left (build) side of the join is the smallest,
+/// swapping the inputs only if the left side is larger than `hash_join_swap_size_ratio`
+/// times the right side.
 /// If the statistics information is not available, the order stays the same as the original query.
 /// JoinSelection rule will also swap the left and right sides for cross join to keep the left side
 /// is the smallest.
-#[derive(Default)]
-pub struct JoinSelection {}
+#[derive(Debug)]
+pub struct JoinSelection {
+    /// Cost model used to choose the partition mode of hash joins and the
+    /// order of the inputs of joins
+    cost_model: Arc<dyn CostModel>,
+}
 
 impl JoinSelection {
     #[allow(missing_docs)]
     pub fn new() -> Self {
-        Self {}
+        Self::with_cost_model(Arc::new(DefaultCostModel::default()))
     }
-}
 
-// TODO we need some performance test for Right Semi/Right Join swap to Left Semi/Left Join in case that the right side is smaller but not much smaller.
-// TODO In PrestoSQL, the optimizer flips join sides only if one side is much smaller than the other by more than SIZE_DIFFERENCE_THRESHOLD times, by default is is 8 times.
-fn should_swap_join_order(left: &dyn ExecutionPlan, right: &dyn ExecutionPlan) -> bool {
-    // Get the left and right table's total bytes
-    // If both the left and right tables contain total_byte_size statistics,
-    // use `total_byte_size` to determine `should_swap_join_order`, else use `num_rows`
-    let (left_size, right_size) = match (
-        left.statistics().total_byte_size,
-        right.statistics().total_byte_size,
-    ) {
-        (Some(l), Some(r)) => (Some(l), Some(r)),
-        _ => (left.statistics().num_rows, right.statistics().num_rows),
-    };
+    /// Create a new [`JoinSelection`] rule that estimates the cost of the
+    /// alternative plans with the given [`CostModel`]
+    pub fn with_cost_model(cost_model: Arc<dyn CostModel>) -> Self {
+        Self { cost_model }
+    }
+}
 
-    match (left_size, right_size) {
-        (Some(l), Some(r)) => l > r,
-        _ => false,
+impl Default for JoinSelection {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-fn supports_collect_by_size(
+/// Whether the hash tables built on `plan` by a [PartitionMode::Partitioned] join would
+/// hold fewer than `min_partition_rows` rows on average, in which case collecting `plan`
+/// into a single hash table is cheaper than paying the overhead of every partition.
+fn has_small_partitions(
     plan: &dyn ExecutionPlan,
-    collection_size_threshold: usize,
+    min_partition_rows: usize,
+    target_partitions: usize,
 ) -> bool {
-    // Currently we do not trust the 0 value from stats, due to stats collection might have bug
-    // TODO check the logic in datasource::get_statistics_with_limit()
-    if let Some(size) = plan.statistics().total_byte_size {
-        size != 0 && size < collection_size_threshold
-    } else if let Some(row_count) = plan.statistics().num_rows {
-        row_count != 0 && row_count < collection_size_threshold
-    } else {
-        false
+    // As in `supports_collect_by_thresholds`, a 0 row count is not trusted
+    match plan.statistics().num_rows {
+        Some(row_count) => {
+            min_partition_rows > 0
+                && row_count != 0
+                && row_count / target_partitions.max(1) < min_partition_rows
+        }
+        None => false,
     }
 }
+
 /// Predicate that checks whether the given join type supports input swapping.
 pub fn supports_swap(join_type: JoinType) -> bool {
     matches!(
@@ -103,109 +111,13 @@ pub fn supports_swap(join_type: JoinType) -> bool {
             | JoinType::RightAnti
     )
 }
-/// This function returns the new join type we get after swapping the given
-/// join's inputs.
-pub fn swap_join_type(join_type: JoinType) -> JoinType {
-    match join_type {
-        JoinType::Inner => JoinType::Inner,
-        JoinType::Full => JoinType::Full,
-        JoinType::Left => JoinType::Right,
-        JoinType::Right => JoinType::Left,
-        JoinType::LeftSemi => JoinType::RightSemi,
-        JoinType::RightSemi => JoinType::LeftSemi,
-        JoinType::LeftAnti => JoinType::RightAnti,
-        JoinType::RightAnti => JoinType::LeftAnti,
-    }
-}
 
 /// This function swaps the inputs of the given join operator.
 pub fn swap_hash_join(
     hash_join: &HashJoinExec,
     partition_mode: PartitionMode,
 ) -> Result<Arc<dyn ExecutionPlan>> {
-    let left = hash_join.left();
-    let right = hash_join.right();
-    let new_join = HashJoinExec::try_new(
-        Arc::clone(right),
-        Arc::clone(left),
-        hash_join
-            .on()
-            .iter()
-            .map(|(l, r)| (r.clone(), l.clone()))
-            .collect(),
-        swap_join_filter(hash_join.filter()),
-        &swap_join_type(*hash_join.join_type()),
-        partition_mode,
-        hash_join.null_equals_null(),
-    )?;
-    if matches!(
-        hash_join.join_type(),
-        JoinType::LeftSemi
-            | JoinType::RightSemi
-            | JoinType::LeftAnti
-            | JoinType::RightAnti
-    ) {
-        Ok(Arc::new(new_join))
-    } else {
-        // TODO avoid adding ProjectionExec again and again, only adding Final Projection
-        let proj = ProjectionExec::try_new(
-            swap_reverting_projection(&left.schema(), &right.schema()),
-            Arc::new(new_join),
-        )?;
-        Ok(Arc::new(proj))
-    }
-}
-
-/// When the order of the join is changed by the optimizer, the columns in
-/// the output should not be impacted. This function creates the expressions
-/// that will allow to swap back the values from the original left as the first
-/// columns and those on the right next.
-pub fn swap_reverting_projection(
-    left_schema: &Schema,
-    right_schema: &Schema,
-) -> Vec<(Arc<dyn PhysicalExpr>, String)> {
-    let right_cols = right_schema.fields().iter().enumerate().map(|(i, f)| {
-        (
-            Arc::new(Column::new(f.name(), i)) as Arc<dyn PhysicalExpr>,
-            f.name().to_owned(),
-        )
-    });
-    let right_len = right_cols.len();
-    let left_cols = left_schema.fields().iter().enumerate().map(|(i, f)| {
-        (
-            Arc::new(Column::new(f.name(), right_len + i)) as Arc<dyn PhysicalExpr>,
-            f.name().to_owned(),
-        )
-    });
-
-    left_cols.chain(right_cols).collect()
-}
-
-/// Swaps join sides for filter column indices and produces new JoinFilter
-fn swap_join_filter(filter: Option<&JoinFilter>) -> Option<JoinFilter> {
-    filter.map(|filter| {
-        let column_indices = filter
-            .column_indices()
-            .iter()
-            .map(|idx| {
-                let side = if matches!(idx.side, JoinSide::Left) {
-                    JoinSide::Right
-                } else {
-                    JoinSide::Left
-                };
-                ColumnIndex {
-                    index: idx.index,
-                    side,
-                }
-            })
-            .collect();
-
-        JoinFilter::new(
-            filter.expression().clone(),
-            column_indices,
-            filter.schema().clone(),
-        )
-    })
+    hash_join.swap_inputs_in_mode(partition_mode)
 }
 
 impl PhysicalOptimizerRule for JoinSelection {
@@ -214,30 +126,77 @@ impl PhysicalOptimizerRule for JoinSelection {
         plan: Arc<dyn ExecutionPlan>,
         config: &ConfigOptions,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let target_partitions = config.execution.target_partitions;
         let config = &config.optimizer;
         let collect_left_threshold = config.hash_join_single_partition_threshold;
+        let collect_left_threshold_rows =
+            config.hash_join_single_partition_threshold_rows;
+        let min_partition_build_rows = config.hash_join_min_partition_build_rows;
+        let swap_ratio = config.hash_join_swap_size_ratio;
+        let cost_model = self.cost_model.as_ref();
         plan.transform_up(&|plan| {
             let transformed = if let Some(hash_join) =
                 plan.as_any().downcast_ref::<HashJoinExec>()
             {
                 match hash_join.partition_mode() {
+                    // Null-aware anti joins have to see the whole subquery side,
+                    // so they keep their CollectLeft mode
+                    _ if hash_join.null_aware() => None,
                     PartitionMode::Auto => {
-                        try_collect_left(hash_join, Some(collect_left_threshold))?
+                        try_collect_left(hash_join, cost_model, swap_ratio, |plan| {
+                            supports_collect_by_thresholds(
+                                plan,
+                                collect_left_threshold,
+                                collect_left_threshold_rows,
+                            ) || has_small_partitions(
+                                plan,
+                                min_partition_build_rows,
+                                target_partitions,
+                            )
+                        })?
+                        .map_or_else(
+                            || {
+                                cheapest_hash_join(
+                                    hash_join,
+                                    cost_model,
+                                    target_partitions,
+                                    swap_ratio,
+                                )
+                                .map(Some)
+                            },
+                            |v| Ok(Some(v)),
+                        )?
+                    }
+                    PartitionMode::CollectLeft => {
+                        try_collect_left(hash_join, cost_model, swap_ratio, |_| true)?
                             .map_or_else(
-                                || partitioned_hash_join(hash_join).map(Some),
+                                || {
+                                    partitioned_hash_join(
+                                        hash_join, cost_model, swap_ratio,
+                                    )
+                                    .map(Some)
+                                },
                                 |v| Ok(Some(v)),
                             )?
                     }
-                    PartitionMode::CollectLeft => try_collect_left(hash_join, None)?
-                        .map_or_else(
-                            || partitioned_hash_join(hash_join).map(Some),
-                            |v| Ok(Some(v)),
-                        )?,
                     PartitionMode::Partitioned => {
                         let left = hash_join.left();
                         let right = hash_join.right();
-                        if should_swap_join_order(&**left, &**right)
-                            && supports_swap(*hash_join.join_type())
+                        if let Some(collect_left) =
+                            try_collect_left(hash_join, cost_model, swap_ratio, |plan| {
+                                has_small_partitions(
+                                    plan,
+                                    min_partition_build_rows,
+                                    target_partitions,
+                                )
+                            })?
+                        {
+                            Some(collect_left)
+                        } else if cost_model.should_swap_join_order(
+                            &left.statistics(),
+                            &right.statistics(),
+                            swap_ratio,
+                        ) && supports_swap(*hash_join.join_type())
                         {
                             swap_hash_join(hash_join, PartitionMode::Partitioned)
                                 .map(Some)?
@@ -250,7 +209,13 @@ impl PhysicalOptimizerRule for JoinSelection {
             {
                 let left = cross_join.left();
                 let right = cross_join.right();
-                if should_swap_join_order(&**left, &**right) {
+                // `hash_join_swap_size_ratio` only applies to hash joins, a
+                // cross join is swapped whenever its left side is the larger
+                if cost_model.should_swap_join_order(
+                    &left.statistics(),
+                    &right.statistics(),
+                    1.0,
+                ) {
                     let new_join =
                         CrossJoinExec::new(Arc::clone(right), Arc::clone(left));
                     // TODO avoid adding ProjectionExec again and again, only adding Final Projection
@@ -286,65 +251,54 @@ impl PhysicalOptimizerRule for JoinSelection {
 /// Try to create the PartitionMode::CollectLeft HashJoinExec when possible.
 /// The method will first consider the current join type and check whether it is applicable to run CollectLeft mode
 /// and will try to swap the join if the orignal type is unapplicable to run CollectLeft.
-/// When the collect_threshold is provided, the method will also check both the left side and right side sizes
+/// The `can_collect` predicate is checked for the side(s) that could be collected, e.g. against their sizes,
+/// and the `cost_model` decides which side to collect if both can be.
 ///
 /// For [JoinType::Full], it is alway unable to run CollectLeft mode and will return None.
-/// For [JoinType::Left] and [JoinType::LeftAnti], can not run CollectLeft mode, should swap join type to [JoinType::Right] and [JoinType::RightAnti]
+/// Either side of the other join types can be collected, the right side by swapping the join type: the build side
+/// rows without a match of a [JoinType::Left] or [JoinType::LeftAnti] join are produced from the visited rows shared
+/// by all partitions, once every partition of the probe side is probed.
 fn try_collect_left(
     hash_join: &HashJoinExec,
-    collect_threshold: Option<usize>,
+    cost_model: &dyn CostModel,
+    swap_ratio: f64,
+    can_collect: impl Fn(&dyn ExecutionPlan) -> bool,
 ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
     let left = hash_join.left();
     let right = hash_join.right();
     let join_type = hash_join.join_type();
-
-    let left_can_collect = match join_type {
-        JoinType::Left | JoinType::Full | JoinType::LeftAnti => false,
-        JoinType::Inner
-        | JoinType::LeftSemi
-        | JoinType::Right
-        | JoinType::RightSemi
-        | JoinType::RightAnti => collect_threshold.map_or(true, |threshold| {
-            supports_collect_by_size(&**left, threshold)
-        }),
-    };
-    let right_can_collect = match join_type {
-        JoinType::Right | JoinType::Full | JoinType::RightAnti => false,
-        JoinType::Inner
-        | JoinType::RightSemi
-        | JoinType::Left
-        | JoinType::LeftSemi
-        | JoinType::LeftAnti => collect_threshold.map_or(true, |threshold| {
-            supports_collect_by_size(&**right, threshold)
-        }),
+    let collect_left = || -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(
+            HashJoinExec::try_new(
+                Arc::clone(left),
+                Arc::clone(right),
+                hash_join.on().to_vec(),
+                hash_join.filter().cloned(),
+                hash_join.join_type(),
+                PartitionMode::CollectLeft,
+                hash_join.null_equals_null(),
+            )?
+            .with_null_equals_null_per_key(hash_join.null_equals_null_per_key().to_vec())?
+            .with_settings_of(hash_join)?,
+        ))
     };
+
+    let left_can_collect = *join_type != JoinType::Full && can_collect(&**left);
+    let right_can_collect = *join_type != JoinType::Full && can_collect(&**right);
     match (left_can_collect, right_can_collect) {
         (true, true) => {
-            if should_swap_join_order(&**left, &**right)
-                && supports_swap(*hash_join.join_type())
+            if cost_model.should_swap_join_order(
+                &left.statistics(),
+                &right.statistics(),
+                swap_ratio,
+            ) && supports_swap(*hash_join.join_type())
             {
                 Ok(Some(swap_hash_join(hash_join, PartitionMode::CollectLeft)?))
             } else {
-                Ok(Some(Arc::new(HashJoinExec::try_new(
-                    Arc::clone(left),
-                    Arc::clone(right),
-                    hash_join.on().to_vec(),
-                    hash_join.filter().cloned(),
-                    hash_join.join_type(),
-                    PartitionMode::CollectLeft,
-                    hash_join.null_equals_null(),
-                )?)))
+                Ok(Some(collect_left()?))
             }
         }
-        (true, false) => Ok(Some(Arc::new(HashJoinExec::try_new(
-            Arc::clone(left),
-            Arc::clone(right),
-            hash_join.on().to_vec(),
-            hash_join.filter().cloned(),
-            hash_join.join_type(),
-            PartitionMode::CollectLeft,
-            hash_join.null_equals_null(),
-        )?))),
+        (true, false) => Ok(Some(collect_left()?)),
         (false, true) => {
             if supports_swap(*hash_join.join_type()) {
                 Ok(Some(swap_hash_join(hash_join, PartitionMode::CollectLeft)?))
@@ -356,22 +310,84 @@ fn try_collect_left(
     }
 }
 
-fn partitioned_hash_join(hash_join: &HashJoinExec) -> Result<Arc<dyn ExecutionPlan>> {
+/// Choose between the [PartitionMode::CollectLeft] and [PartitionMode::Partitioned]
+/// variants of the given join, whatever the size of its inputs, picking the one the
+/// `cost_model` estimates cheaper. Falls back to [PartitionMode::Partitioned] when
+/// the cost of either variant can not be estimated, or when there is a single target
+/// partition, in which case neither input is repartitioned and both variants build
+/// the same hash table.
+fn cheapest_hash_join(
+    hash_join: &HashJoinExec,
+    cost_model: &dyn CostModel,
+    target_partitions: usize,
+    swap_ratio: f64,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let partitioned = partitioned_hash_join(hash_join, cost_model, swap_ratio)?;
+    if target_partitions <= 1 {
+        return Ok(partitioned);
+    }
+    let collect_left =
+        match try_collect_left(hash_join, cost_model, swap_ratio, |_| true)? {
+            Some(collect_left) => collect_left,
+            None => return Ok(partitioned),
+        };
+
+    let collect_left_cost = hash_join_cost(&collect_left, cost_model, target_partitions);
+    let partitioned_cost = hash_join_cost(&partitioned, cost_model, target_partitions);
+    match (collect_left_cost, partitioned_cost) {
+        (Some(c), Some(p)) if c < p => Ok(collect_left),
+        _ => Ok(partitioned),
+    }
+}
+
+/// Estimate the cost of a [HashJoinExec] created by this rule, which may be wrapped
+/// in the [ProjectionExec] restoring the column order of a swapped join
+fn hash_join_cost(
+    plan: &Arc<dyn ExecutionPlan>,
+    cost_model: &dyn CostModel,
+    target_partitions: usize,
+) -> Option<f64> {
+    let plan = match plan.as_any().downcast_ref::<ProjectionExec>() {
+        Some(projection) => projection.input(),
+        None => plan,
+    };
+    let hash_join = plan.as_any().downcast_ref::<HashJoinExec>()?;
+    cost_model.hash_join_cost(
+        &hash_join.left().statistics(),
+        &hash_join.right().statistics(),
+        *hash_join.partition_mode(),
+        target_partitions,
+    )
+}
+
+fn partitioned_hash_join(
+    hash_join: &HashJoinExec,
+    cost_model: &dyn CostModel,
+    swap_ratio: f64,
+) -> Result<Arc<dyn ExecutionPlan>> {
     let left = hash_join.left();
     let right = hash_join.right();
-    if should_swap_join_order(&**left, &**right) && supports_swap(*hash_join.join_type())
+    if cost_model.should_swap_join_order(
+        &left.statistics(),
+        &right.statistics(),
+        swap_ratio,
+    ) && supports_swap(*hash_join.join_type())
     {
         swap_hash_join(hash_join, PartitionMode::Partitioned)
     } else {
-        Ok(Arc::new(HashJoinExec::try_new(
-            Arc::clone(left),
-            Arc::clone(right),
-            hash_join.on().to_vec(),
-            hash_join.filter().cloned(),
-            hash_join.join_type(),
-            PartitionMode::Partitioned,
-            hash_join.null_equals_null(),
-        )?))
+        Ok(Arc::new(
+            HashJoinExec::try_new(
+                Arc::clone(left),
+                Arc::clone(right),
+                hash_join.on().to_vec(),
+                hash_join.filter().cloned(),
+                hash_join.join_type(),
+                PartitionMode::Partitioned,
+                hash_join.null_equals_null(),
+            )?
+            .with_null_equals_null_per_key(hash_join.null_equals_null_per_key().to_vec())?
+            .with_settings_of(hash_join)?,
+        ))
     }
 }
 
@@ -379,7 +395,10 @@ fn partitioned_hash_join(hash_join: &HashJoinExec) -> Result<Arc<dyn ExecutionPl
 mod tests {
     use crate::{
         physical_plan::{
-            displayable, joins::PartitionMode, ColumnStatistics, Statistics,
+            displayable,
+            expressions::Column,
+            joins::{utils::JoinOn, PartitionMode},
+            ColumnStatistics, PhysicalExpr, Statistics,
         },
         test::exec::StatisticsExec,
     };
@@ -387,7 +406,12 @@ mod tests {
     use super::*;
     use std::sync::Arc;
 
+    use crate::error::DataFusionError;
+    use crate::physical_plan::{collect, memory::MemoryExec};
+    use crate::prelude::SessionContext;
+    use crate::test::build_table_i32;
     use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::util::pretty::pretty_format_batches;
     use datafusion_common::ScalarValue;
 
     fn create_big_and_small() -> (Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>) {
@@ -488,8 +512,9 @@ mod tests {
             Arc::clone(&big),
             Arc::clone(&small),
             vec![(
-                Column::new_with_schema("big_col", &big.schema()).unwrap(),
-                Column::new_with_schema("small_col", &small.schema()).unwrap(),
+                Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
+                Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap())
+                    as _,
             )],
             None,
             &JoinType::Left,
@@ -536,8 +561,9 @@ mod tests {
             Arc::clone(&small),
             Arc::clone(&big),
             vec![(
-                Column::new_with_schema("small_col", &small.schema()).unwrap(),
-                Column::new_with_schema("big_col", &big.schema()).unwrap(),
+                Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap())
+                    as _,
+                Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
             )],
             None,
             &JoinType::Left,
@@ -587,8 +613,11 @@ mod tests {
                 Arc::clone(&big),
                 Arc::clone(&small),
                 vec![(
-                    Column::new_with_schema("big_col", &big.schema()).unwrap(),
-                    Column::new_with_schema("small_col", &small.schema()).unwrap(),
+                    Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap())
+                        as _,
+                    Arc::new(
+                        Column::new_with_schema("small_col", &small.schema()).unwrap(),
+                    ) as _,
                 )],
                 None,
                 &join_type,
@@ -652,8 +681,9 @@ mod tests {
             Arc::clone(&big),
             Arc::clone(&small),
             vec![(
-                Column::new_with_schema("big_col", &big.schema()).unwrap(),
-                Column::new_with_schema("small_col", &small.schema()).unwrap(),
+                Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
+                Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap())
+                    as _,
             )],
             None,
             &JoinType::Inner,
@@ -668,8 +698,10 @@ mod tests {
             Arc::clone(&medium),
             Arc::new(child_join),
             vec![(
-                Column::new_with_schema("medium_col", &medium.schema()).unwrap(),
-                Column::new_with_schema("small_col", &child_schema).unwrap(),
+                Arc::new(Column::new_with_schema("medium_col", &medium.schema()).unwrap())
+                    as _,
+                Arc::new(Column::new_with_schema("small_col", &child_schema).unwrap())
+                    as _,
             )],
             None,
             &JoinType::Left,
@@ -687,9 +719,9 @@ mod tests {
         // has an exact cardinality of 10_000 rows).
         let expected = [
             "ProjectionExec: expr=[medium_col@2 as medium_col, big_col@0 as big_col, small_col@1 as small_col]",
-            "  HashJoinExec: mode=CollectLeft, join_type=Right, on=[(Column { name: \"small_col\", index: 1 }, Column { name: \"medium_col\", index: 0 })]",
+            "  HashJoinExec: mode=CollectLeft, join_type=Right, on=[(small_col@1, medium_col@0)]",
             "    ProjectionExec: expr=[big_col@1 as big_col, small_col@0 as small_col]",
-            "      HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(Column { name: \"small_col\", index: 0 }, Column { name: \"big_col\", index: 0 })]",
+            "      HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(small_col@0, big_col@0)]",
             "        StatisticsExec: col_count=1, row_count=Some(1000)",
             "        StatisticsExec: col_count=1, row_count=Some(100000)",
             "    StatisticsExec: col_count=1, row_count=Some(10000)",
@@ -705,8 +737,9 @@ mod tests {
             Arc::clone(&small),
             Arc::clone(&big),
             vec![(
-                Column::new_with_schema("small_col", &small.schema()).unwrap(),
-                Column::new_with_schema("big_col", &big.schema()).unwrap(),
+                Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap())
+                    as _,
+                Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
             )],
             None,
             &JoinType::Inner,
@@ -796,8 +829,8 @@ mod tests {
         ));
 
         let join_on = vec![(
-            Column::new_with_schema("small_col", &small.schema()).unwrap(),
-            Column::new_with_schema("big_col", &big.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             small.clone(),
@@ -808,8 +841,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("big_col", &big.schema()).unwrap(),
-            Column::new_with_schema("small_col", &small.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("big_col", &big.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             big,
@@ -820,8 +853,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("small_col", &small.schema()).unwrap(),
-            Column::new_with_schema("empty_col", &empty.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("empty_col", &empty.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             small.clone(),
@@ -832,8 +865,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("empty_col", &empty.schema()).unwrap(),
-            Column::new_with_schema("small_col", &small.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("empty_col", &empty.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("small_col", &small.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             empty,
@@ -874,8 +907,8 @@ mod tests {
         ));
 
         let join_on = vec![(
-            Column::new_with_schema("big_col1", &big1.schema()).unwrap(),
-            Column::new_with_schema("big_col2", &big2.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("big_col1", &big1.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("big_col2", &big2.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             big1.clone(),
@@ -886,8 +919,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("big_col2", &big2.schema()).unwrap(),
-            Column::new_with_schema("big_col1", &big1.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("big_col2", &big2.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("big_col1", &big1.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             big2,
@@ -898,8 +931,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("empty_col", &empty.schema()).unwrap(),
-            Column::new_with_schema("big_col1", &big1.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("empty_col", &empty.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("big_col1", &big1.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             empty.clone(),
@@ -910,8 +943,8 @@ mod tests {
         );
 
         let join_on = vec![(
-            Column::new_with_schema("big_col1", &big1.schema()).unwrap(),
-            Column::new_with_schema("empty_col", &empty.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("big_col1", &big1.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("empty_col", &empty.schema()).unwrap()) as _,
         )];
         check_join_partition_mode(
             big1,
@@ -922,10 +955,489 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_join_selection_collect_left_by_cost() {
+        // above `hash_join_single_partition_threshold`, but much smaller than the probe side
+        let medium = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Some(1_000_000),
+                total_byte_size: Some(8_000_000),
+                ..Default::default()
+            },
+            Schema::new(vec![Field::new("medium_col", DataType::Int32, false)]),
+        ));
+
+        let huge = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Some(100_000_000),
+                total_byte_size: Some(800_000_000),
+                ..Default::default()
+            },
+            Schema::new(vec![Field::new("huge_col", DataType::Int32, false)]),
+        ));
+
+        // the cost of the partitioned variant depends on the number of partitions
+        let mut config = ConfigOptions::new();
+        config.execution.target_partitions = 8;
+        let optimize = |left: &Arc<StatisticsExec>,
+                        right: &Arc<StatisticsExec>,
+                        cost_model: DefaultCostModel| {
+            let on = vec![(
+                Arc::new(
+                    Column::new_with_schema(
+                        left.schema().field(0).name(),
+                        &left.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+                Arc::new(
+                    Column::new_with_schema(
+                        right.schema().field(0).name(),
+                        &right.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+            )];
+            let join = HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on,
+                None,
+                &JoinType::Inner,
+                PartitionMode::Auto,
+                false,
+            )
+            .unwrap();
+            let optimized = JoinSelection::with_cost_model(Arc::new(cost_model))
+                .optimize(Arc::new(join), &config)
+                .unwrap();
+            // a swapped join is wrapped in a projection restoring the column order
+            let join = match optimized.as_any().downcast_ref::<ProjectionExec>() {
+                Some(projection) => projection.input().clone(),
+                None => optimized,
+            };
+            let join = join
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+            (
+                join.left().schema().field(0).name().clone(),
+                *join.partition_mode(),
+            )
+        };
+
+        // the medium side is collected, swapping the inputs if needed
+        assert_eq!(
+            optimize(&medium, &huge, DefaultCostModel::default()),
+            ("medium_col".to_string(), PartitionMode::CollectLeft)
+        );
+        assert_eq!(
+            optimize(&huge, &medium, DefaultCostModel::default()),
+            ("medium_col".to_string(), PartitionMode::CollectLeft)
+        );
+
+        // a cost model for which moving data between partitions is free
+        // never collects the inputs
+        let cost_model = DefaultCostModel {
+            exchange_per_byte: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            optimize(&medium, &huge, cost_model),
+            ("medium_col".to_string(), PartitionMode::Partitioned)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_selection_single_partition_thresholds() {
+        let statistics_exec = |num_rows: Option<usize>, name: &str| {
+            Arc::new(StatisticsExec::new(
+                Statistics {
+                    num_rows,
+                    ..Default::default()
+                },
+                Schema::new(vec![Field::new(name, DataType::Int32, false)]),
+            ))
+        };
+        let auto_join = |left: &Arc<StatisticsExec>, right: &Arc<StatisticsExec>| {
+            let on = vec![(
+                Arc::new(
+                    Column::new_with_schema(
+                        left.schema().field(0).name(),
+                        &left.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+                Arc::new(
+                    Column::new_with_schema(
+                        right.schema().field(0).name(),
+                        &right.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+            )];
+            Arc::new(
+                HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on,
+                    None,
+                    &JoinType::Inner,
+                    PartitionMode::Auto,
+                    false,
+                )
+                .unwrap(),
+            ) as Arc<dyn ExecutionPlan>
+        };
+        let optimized_mode = |plan: Arc<dyn ExecutionPlan>, threshold_rows: usize| {
+            let mut config = ConfigOptions::new();
+            config.optimizer.hash_join_single_partition_threshold_rows = threshold_rows;
+            let optimized = JoinSelection::new().optimize(plan, &config).unwrap();
+            let join = optimized
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+            *join.partition_mode()
+        };
+
+        // without their size in bytes, the inputs are collected by their number of rows
+        let join = auto_join(
+            &statistics_exec(Some(1000), "small_col"),
+            &statistics_exec(Some(2000), "big_col"),
+        );
+        assert_eq!(
+            optimized_mode(join.clone(), 1001),
+            PartitionMode::CollectLeft
+        );
+        assert_eq!(optimized_mode(join, 1000), PartitionMode::Partitioned);
+
+        // without statistics, the build side is never collected
+        let join = auto_join(
+            &statistics_exec(None, "small_col"),
+            &statistics_exec(None, "big_col"),
+        );
+        assert_eq!(optimized_mode(join, usize::MAX), PartitionMode::Partitioned);
+    }
+
+    #[tokio::test]
+    async fn test_join_selection_collect_small_partitions() {
+        let small = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Some(4000),
+                total_byte_size: Some(16000),
+                ..Default::default()
+            },
+            Schema::new(vec![Field::new("small_col", DataType::Int32, false)]),
+        ));
+
+        let big = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Some(10000000),
+                total_byte_size: Some(40000000),
+                ..Default::default()
+            },
+            Schema::new(vec![Field::new("big_col", DataType::Int32, false)]),
+        ));
+
+        let partitioned_join = |left: &Arc<StatisticsExec>,
+                                right: &Arc<StatisticsExec>,
+                                join_type: JoinType|
+         -> Arc<dyn ExecutionPlan> {
+            let on = vec![(
+                Arc::new(
+                    Column::new_with_schema(
+                        left.schema().field(0).name(),
+                        &left.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+                Arc::new(
+                    Column::new_with_schema(
+                        right.schema().field(0).name(),
+                        &right.schema(),
+                    )
+                    .unwrap(),
+                ) as _,
+            )];
+            Arc::new(
+                HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on,
+                    None,
+                    &join_type,
+                    PartitionMode::Partitioned,
+                    false,
+                )
+                .unwrap(),
+            )
+        };
+        let optimized_mode = |plan: Arc<dyn ExecutionPlan>, min_rows: usize| {
+            let mut config = ConfigOptions::new();
+            config.execution.target_partitions = 8;
+            config.optimizer.hash_join_min_partition_build_rows = min_rows;
+            let optimized = JoinSelection::new().optimize(plan, &config).unwrap();
+            let optimized = match optimized.as_any().downcast_ref::<ProjectionExec>() {
+                Some(projection) => projection.input().clone(),
+                None => optimized,
+            };
+            let join = optimized
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+            (
+                *join.partition_mode(),
+                join.left().schema().field(0).name().clone(),
+            )
+        };
+
+        // 4000 rows over 8 partitions is 500 rows per partition
+        let join = partitioned_join(&small, &big, JoinType::Inner);
+        assert_eq!(
+            optimized_mode(join.clone(), 1000),
+            (PartitionMode::CollectLeft, "small_col".to_string())
+        );
+        assert_eq!(
+            optimized_mode(join.clone(), 500),
+            (PartitionMode::Partitioned, "small_col".to_string())
+        );
+        assert_eq!(
+            optimized_mode(join, 0),
+            (PartitionMode::Partitioned, "small_col".to_string())
+        );
+
+        // the small side becomes the build side if the join can be swapped
+        let join = partitioned_join(&big, &small, JoinType::Left);
+        assert_eq!(
+            optimized_mode(join, 1000),
+            (PartitionMode::CollectLeft, "small_col".to_string())
+        );
+
+        // large inputs keep their partitioned mode
+        let join = partitioned_join(&big, &big, JoinType::Inner);
+        assert_eq!(
+            optimized_mode(join, 1000),
+            (PartitionMode::Partitioned, "big_col".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_with_swap_size_ratio() -> Result<()> {
+        // the tables have 3 and 30 rows, with the statistics of their batches
+        let table = |name: &str, num_rows: i32| -> Result<Arc<dyn ExecutionPlan>> {
+            let batch = build_table_i32(
+                (&format!("{name}_a"), &(0..num_rows).collect()),
+                (
+                    &format!("{name}_b"),
+                    &(0..num_rows).map(|i| i % 5).collect(),
+                ),
+                (
+                    &format!("{name}_c"),
+                    &(0..num_rows).map(|i| i * 10).collect(),
+                ),
+            );
+            let schema = batch.schema();
+            Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?))
+        };
+        let small = table("small", 3)?;
+        let big = table("big", 30)?;
+        let join = |left: &Arc<dyn ExecutionPlan>,
+                    right: &Arc<dyn ExecutionPlan>|
+         -> Result<Arc<dyn ExecutionPlan>> {
+            let on = vec![(
+                Arc::new(Column::new_with_schema(
+                    left.schema().field(1).name(),
+                    &left.schema(),
+                )?) as _,
+                Arc::new(Column::new_with_schema(
+                    right.schema().field(1).name(),
+                    &right.schema(),
+                )?) as _,
+            )];
+            Ok(Arc::new(HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on,
+                None,
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )?))
+        };
+        let optimize = |plan: Arc<dyn ExecutionPlan>, swap_ratio: f64| {
+            let mut config = ConfigOptions::new();
+            config.optimizer.hash_join_swap_size_ratio = swap_ratio;
+            JoinSelection::new().optimize(plan, &config)
+        };
+        let build_side = |plan: &Arc<dyn ExecutionPlan>| {
+            let plan = match plan.as_any().downcast_ref::<ProjectionExec>() {
+                Some(projection) => projection.input().clone(),
+                None => plan.clone(),
+            };
+            let join = plan
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+            join.left().schema().field(0).name().clone()
+        };
+        let sorted_output = |plan: Arc<dyn ExecutionPlan>| async move {
+            let batches = collect(plan, SessionContext::new().task_ctx()).await?;
+            let formatted = pretty_format_batches(&batches)?.to_string();
+            let mut lines = formatted.lines().map(String::from).collect::<Vec<_>>();
+            lines.sort_unstable();
+            Ok::<_, DataFusionError>(lines)
+        };
+
+        // whichever way round the join is written, the small table is built
+        let small_first = optimize(join(&small, &big)?, 1.0)?;
+        let big_first = optimize(join(&big, &small)?, 1.0)?;
+        assert_eq!(build_side(&small_first), "small_a");
+        assert_eq!(build_side(&big_first), "small_a");
+
+        // the swapped join produces the columns in the original order
+        assert_eq!(big_first.schema(), join(&big, &small)?.schema());
+        assert_eq!(
+            sorted_output(big_first).await?,
+            sorted_output(join(&big, &small)?).await?
+        );
+
+        // the big table is not 100 times larger than the small one
+        let big_first = optimize(join(&big, &small)?, 100.0)?;
+        assert_eq!(build_side(&big_first), "big_a");
+
+        // the ratio does not apply to cross joins
+        let cross_join: Arc<dyn ExecutionPlan> =
+            Arc::new(CrossJoinExec::new(big.clone(), small.clone()));
+        let optimized = optimize(cross_join, 100.0)?;
+        let projection = optimized
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("A swapped cross join should be projected");
+        let swapped = projection
+            .input()
+            .as_any()
+            .downcast_ref::<CrossJoinExec>()
+            .expect("The type of the plan should not be changed");
+        assert_eq!(swapped.left().schema().field(0).name(), "small_a");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_join_selection_collect_preserved_right_side() -> Result<()> {
+        // the big table has 30 rows in 3 partitions, and the small table 3 rows,
+        // one of which has no match in the big table
+        let batch = |name: &str, rows: std::ops::Range<i32>, key: fn(i32) -> i32| {
+            build_table_i32(
+                (&format!("{name}_a"), &rows.clone().collect()),
+                (&format!("{name}_b"), &rows.clone().map(key).collect()),
+                (&format!("{name}_c"), &rows.map(|i| i * 10).collect()),
+            )
+        };
+        let big_partitions = (0..3)
+            .map(|i| vec![batch("big", i * 10..(i + 1) * 10, |i| i % 5)])
+            .collect::<Vec<_>>();
+        let big: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &big_partitions,
+            big_partitions[0][0].schema(),
+            None,
+        )?);
+        let small_batch = batch("small", 0..3, |i| i * 3);
+        let small: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &[vec![small_batch.clone()]],
+            small_batch.schema(),
+            None,
+        )?);
+
+        let join = |join_type: JoinType,
+                    mode: PartitionMode|
+         -> Result<Arc<dyn ExecutionPlan>> {
+            let on = vec![(
+                Arc::new(Column::new_with_schema("big_b", &big.schema())?) as _,
+                Arc::new(Column::new_with_schema("small_b", &small.schema())?) as _,
+            )];
+            Ok(Arc::new(HashJoinExec::try_new(
+                big.clone(),
+                small.clone(),
+                on,
+                None,
+                &join_type,
+                mode,
+                false,
+            )?))
+        };
+        // only the small table has small enough partitions to be collected
+        let mut config = ConfigOptions::new();
+        config.execution.target_partitions = 1;
+        config.optimizer.hash_join_min_partition_build_rows = 10;
+        let sorted_output = |plan: Arc<dyn ExecutionPlan>| async move {
+            let batches = collect(plan, SessionContext::new().task_ctx()).await?;
+            let formatted = pretty_format_batches(&batches)?.to_string();
+            let mut lines = formatted.lines().map(String::from).collect::<Vec<_>>();
+            lines.sort_unstable();
+            Ok::<_, DataFusionError>(lines)
+        };
+
+        for join_type in [JoinType::Right, JoinType::RightSemi, JoinType::RightAnti] {
+            let optimized = JoinSelection::new()
+                .optimize(join(join_type, PartitionMode::Partitioned)?, &config)?;
+            let swapped = match optimized.as_any().downcast_ref::<ProjectionExec>() {
+                Some(projection) => projection.input().clone(),
+                None => optimized.clone(),
+            };
+            let swapped = swapped
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+
+            // the small right side is collected as the build side of the
+            // swapped join, which produces its rows without a match
+            assert_eq!(*swapped.partition_mode(), PartitionMode::CollectLeft);
+            assert_eq!(*swapped.join_type(), swap_join_type(join_type));
+            assert_eq!(swapped.left().schema().field(0).name(), "small_a");
+            assert_eq!(
+                optimized.schema(),
+                join(join_type, PartitionMode::Partitioned)?.schema()
+            );
+            assert_eq!(
+                sorted_output(optimized).await?,
+                sorted_output(join(join_type, PartitionMode::CollectLeft)?).await?,
+                "{join_type}"
+            );
+        }
+
+        // the small left side of the joins preserving their left side is
+        // collected as is
+        for join_type in [JoinType::Left, JoinType::LeftSemi, JoinType::LeftAnti] {
+            let on = vec![(
+                Arc::new(Column::new_with_schema("small_b", &small.schema())?) as _,
+                Arc::new(Column::new_with_schema("big_b", &big.schema())?) as _,
+            )];
+            let plan: Arc<dyn ExecutionPlan> = Arc::new(HashJoinExec::try_new(
+                small.clone(),
+                big.clone(),
+                on,
+                None,
+                &join_type,
+                PartitionMode::Partitioned,
+                false,
+            )?);
+            let optimized = JoinSelection::new().optimize(plan, &config)?;
+            let collected = optimized
+                .as_any()
+                .downcast_ref::<HashJoinExec>()
+                .expect("The type of the plan should not be changed");
+            assert_eq!(*collected.partition_mode(), PartitionMode::CollectLeft);
+            assert_eq!(collected.join_type(), &join_type);
+            assert_eq!(collected.left().schema().field(0).name(), "small_a");
+        }
+        Ok(())
+    }
+
     fn check_join_partition_mode(
         left: Arc<StatisticsExec>,
         right: Arc<StatisticsExec>,
-        on: Vec<(Column, Column)>,
+        on: JoinOn,
         is_swapped: bool,
         expected_mode: PartitionMode,
     ) {
@@ -24,13 +24,18 @@
 pub mod aggregate_statistics;
 pub mod coalesce_batches;
 pub mod combine_partial_final_agg;
+pub mod cost;
 pub mod dist_enforcement;
 pub mod global_sort_selection;
+#[cfg(feature = "tracing")]
+pub mod instrument;
 pub mod join_selection;
 pub mod optimizer;
 pub mod pipeline_checker;
 pub mod pruning;
+pub mod pull_filter_into_join;
 pub mod repartition;
+pub mod simplify_filtered_outer_join;
 pub mod sort_enforcement;
 mod sort_pushdown;
 mod utils;
@@ -89,7 +89,16 @@ fn hash_join_convert_symmetric_subrule(
         let ub_flags = &input.children_unbounded;
         let (left_unbounded, right_unbounded) = (ub_flags[0], ub_flags[1]);
         input.unbounded = left_unbounded || right_unbounded;
-        let result = if left_unbounded && right_unbounded {
+        // The symmetric hash join does not support null-aware anti joins, nor
+        // null_equals_null differing between the join keys
+        let null_equals_null = hash_join.null_equals_null_per_key();
+        let mixed_null_equals_null =
+            null_equals_null.contains(&true) && null_equals_null.contains(&false);
+        let result = if left_unbounded
+            && right_unbounded
+            && !hash_join.null_aware()
+            && !mixed_null_equals_null
+        {
             SymmetricHashJoinExec::try_new(
                 hash_join.left().clone(),
                 hash_join.right().clone(),
@@ -637,8 +646,8 @@ mod hash_join_tests {
             Arc::clone(&left_exec),
             Arc::clone(&right_exec),
             vec![(
-                Column::new_with_schema("a", &left_exec.schema())?,
-                Column::new_with_schema("b", &right_exec.schema())?,
+                Arc::new(Column::new_with_schema("a", &left_exec.schema())?) as _,
+                Arc::new(Column::new_with_schema("b", &right_exec.schema())?) as _,
             )],
             None,
             &t.initial_join_type,
@@ -0,0 +1,435 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! PullFilterIntoJoin optimizer rule moves the predicate of a [`FilterExec`]
+//! placed directly above a [`HashJoinExec`] into the [`JoinFilter`] of the join
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion_expr::{JoinType, Operator};
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::error::Result;
+use crate::physical_optimizer::simplify_filtered_outer_join::{
+    simplify_join_type, with_join_type,
+};
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::joins::utils::{ColumnIndex, JoinFilter, JoinSide};
+use crate::physical_plan::joins::HashJoinExec;
+use crate::physical_plan::ExecutionPlan;
+
+/// PullFilterIntoJoin optimizer rule evaluates the predicate of a [`FilterExec`]
+/// that sits directly above a [`HashJoinExec`] inside the join, as part of its
+/// [`JoinFilter`], when the predicate references columns from both sides of the
+/// join. This avoids materializing join output rows that are discarded right
+/// away.
+///
+/// The predicate is only moved into inner joins. For outer joins, a predicate
+/// that rejects the rows padded with nulls (e.g. `l.a < r.b` rejects the rows
+/// of a left join without a match on the right) allows simplifying the join
+/// type first, e.g. turning a left join into an inner join, which this rule
+/// does with the same check as the [`SimplifyFilteredOuterJoin`] rule.
+///
+/// [`SimplifyFilteredOuterJoin`]: crate::physical_optimizer::simplify_filtered_outer_join::SimplifyFilteredOuterJoin
+#[derive(Default)]
+pub struct PullFilterIntoJoin {}
+
+impl PullFilterIntoJoin {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for PullFilterIntoJoin {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            let Some(join) = filter.input().as_any().downcast_ref::<HashJoinExec>()
+            else {
+                return Ok(Transformed::No(plan));
+            };
+            Ok(match pull_filter_into_join(filter.predicate(), join)? {
+                Some(plan) => Transformed::Yes(plan),
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "PullFilterIntoJoin"
+    }
+
+    /// Simplifying an outer join makes the columns of its null-supplying side
+    /// non-nullable
+    fn schema_check(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the plan replacing a [`FilterExec`] with `predicate` above `join`,
+/// or `None` if the plan can not be improved
+fn pull_filter_into_join(
+    predicate: &Arc<dyn PhysicalExpr>,
+    join: &HashJoinExec,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if !matches!(
+        join.join_type(),
+        JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full
+    ) {
+        return Ok(None);
+    }
+
+    // The output of the join consists of the left columns followed by the right ones
+    let left_len = join.left().schema().fields().len();
+    let side_of = |column: &Column| {
+        if column.index() < left_len {
+            JoinSide::Left
+        } else {
+            JoinSide::Right
+        }
+    };
+    let columns = collect_columns(predicate);
+    let references_left = columns.iter().any(|c| side_of(c) == JoinSide::Left);
+    let references_right = columns.iter().any(|c| side_of(c) == JoinSide::Right);
+    if !(references_left && references_right) {
+        return Ok(None);
+    }
+
+    let join_type = simplify_join_type(*join.join_type(), predicate, &side_of);
+    if join_type != JoinType::Inner {
+        if join_type == *join.join_type() {
+            return Ok(None);
+        }
+        // The join is simplified but the predicate has to be evaluated on its output
+        let join = with_join_type(join, join.filter().cloned(), join_type)?;
+        return Ok(Some(Arc::new(FilterExec::try_new(
+            predicate.clone(),
+            join,
+        )?)));
+    }
+
+    // Remap the columns of the predicate into the intermediate schema of the
+    // join filter, reusing the columns the existing filter already has
+    let (mut column_indices, mut fields): (Vec<ColumnIndex>, Vec<Field>) =
+        match join.filter() {
+            Some(filter) => (
+                filter.column_indices().to_vec(),
+                filter
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| Field::clone(field))
+                    .collect(),
+            ),
+            None => (vec![], vec![]),
+        };
+    let left_schema = join.left().schema();
+    let right_schema = join.right().schema();
+    let mut positions = HashMap::new();
+    predicate.apply(&mut |expr| {
+        if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+            let (index, side, field) = match side_of(column) {
+                JoinSide::Left => (
+                    column.index(),
+                    JoinSide::Left,
+                    left_schema.field(column.index()),
+                ),
+                JoinSide::Right => (
+                    column.index() - left_len,
+                    JoinSide::Right,
+                    right_schema.field(column.index() - left_len),
+                ),
+            };
+            let position = column_indices
+                .iter()
+                .position(|c| c.index == index && c.side == side)
+                .unwrap_or_else(|| {
+                    column_indices.push(ColumnIndex { index, side });
+                    fields.push(field.clone());
+                    column_indices.len() - 1
+                });
+            positions.insert(column.index(), position);
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    let remapped = predicate.clone().transform_up(&|expr| {
+        Ok(match expr.as_any().downcast_ref::<Column>() {
+            Some(column) => Transformed::Yes(Arc::new(Column::new(
+                column.name(),
+                positions[&column.index()],
+            ))),
+            None => Transformed::No(expr),
+        })
+    })?;
+    let expression: Arc<dyn PhysicalExpr> = match join.filter() {
+        Some(filter) => Arc::new(BinaryExpr::new(
+            filter.expression().clone(),
+            Operator::And,
+            remapped,
+        )),
+        None => remapped,
+    };
+    let filter = JoinFilter::new(expression, column_indices, Schema::new(fields));
+
+    Ok(Some(with_join_type(join, Some(filter), JoinType::Inner)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::joins::PartitionMode;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{collect, displayable};
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::DataType;
+    use arrow::record_batch::RecordBatch;
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion_common::ScalarValue;
+    use datafusion_physical_expr::expressions::{
+        binary, col, is_null, lit, IsNotNullExpr,
+    };
+
+    fn memory_exec(
+        names: (&str, &str),
+        a: Vec<Option<i32>>,
+        b: Vec<Option<i32>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(names.0, DataType::Int32, true),
+            Field::new(names.1, DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )?;
+        Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?))
+    }
+
+    /// Joins `l(a, b)` with `r(c, d)` on `a = c` and filters the output with
+    /// the predicate built by `predicate`
+    fn filtered_join(
+        join_type: JoinType,
+        join_filter: Option<JoinFilter>,
+        predicate: impl Fn(&Schema) -> Result<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let left = memory_exec(
+            ("a", "b"),
+            vec![Some(1), Some(2), Some(3), Some(4), None],
+            vec![Some(1), Some(2), Some(3), None, Some(5)],
+        )?;
+        let right = memory_exec(
+            ("c", "d"),
+            vec![Some(1), Some(2), Some(2), Some(3), Some(5)],
+            vec![Some(10), Some(1), Some(3), None, Some(50)],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+        let join = Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            join_filter,
+            &join_type,
+            PartitionMode::CollectLeft,
+            false,
+        )?);
+        let predicate = predicate(join.schema().as_ref())?;
+        Ok(Arc::new(FilterExec::try_new(predicate, join)?))
+    }
+
+    fn get_plan_string(plan: &Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let formatted = displayable(plan.as_ref()).indent().to_string();
+        formatted
+            .trim()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Optimizes `plan`, checks the optimized plan against `expected` and
+    /// that both plans produce the same results
+    async fn assert_optimized(
+        plan: Arc<dyn ExecutionPlan>,
+        expected: &[&str],
+    ) -> Result<()> {
+        let optimized =
+            PullFilterIntoJoin::new().optimize(plan.clone(), &ConfigOptions::new())?;
+        assert_eq!(get_plan_string(&optimized), expected);
+
+        let task_ctx = SessionContext::new().task_ctx();
+        let expected = collect(plan, task_ctx.clone()).await?;
+        let actual = collect(optimized, task_ctx).await?;
+        let mut expected = pretty_format_batches(&expected)?
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        let mut actual = pretty_format_batches(&actual)?
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inner_join() -> Result<()> {
+        // b + d > 10 AND b < 3
+        let plan = filtered_join(JoinType::Inner, None, |schema| {
+            let sum =
+                binary(col("b", schema)?, Operator::Plus, col("d", schema)?, schema)?;
+            let gt = binary(sum, Operator::Gt, lit(10i32), schema)?;
+            let lt = binary(col("b", schema)?, Operator::Lt, lit(3i32), schema)?;
+            binary(gt, Operator::And, lt, schema)
+        })?;
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)], filter=b@0 + d@1 > 10 AND b@0 < 3",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn inner_join_with_filter() -> Result<()> {
+        // the existing filter `d <> b` is combined with the predicate `b < d`
+        let column_indices = JoinFilter::build_column_indices(vec![1], vec![1]);
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let join_filter = JoinFilter::new(
+            binary(
+                col("d", &schema)?,
+                Operator::NotEq,
+                col("b", &schema)?,
+                &schema,
+            )?,
+            vec![column_indices[1].clone(), column_indices[0].clone()],
+            schema,
+        );
+        let plan = filtered_join(JoinType::Inner, Some(join_filter), |schema| {
+            binary(col("b", schema)?, Operator::Lt, col("d", schema)?, schema)
+        })?;
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)], filter=d@0 != b@1 AND b@1 < d@0",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn left_join_null_rejecting() -> Result<()> {
+        // `a = 2` keeps the rows of `a = 2` without a match on the right
+        let plan = filtered_join(JoinType::Left, None, |schema| {
+            let lt = binary(col("b", schema)?, Operator::Lt, col("d", schema)?, schema)?;
+            let eq = binary(col("a", schema)?, Operator::Eq, lit(2i32), schema)?;
+            binary(lt, Operator::Or, eq, schema)
+        })?;
+        let expected = [
+            "FilterExec: b@1 < d@3 OR a@0 = 2",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // b < d rejects the rows without a match on the right
+        let plan = filtered_join(JoinType::Left, None, |schema| {
+            let lt = binary(col("b", schema)?, Operator::Lt, col("d", schema)?, schema)?;
+            let gt = binary(col("a", schema)?, Operator::Gt, lit(1i32), schema)?;
+            binary(lt, Operator::And, gt, schema)
+        })?;
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)], filter=b@0 < d@1 AND a@2 > 1",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn left_join_not_null_rejecting() -> Result<()> {
+        // d IS NULL OR b < d keeps the rows without a match on the right
+        let plan = filtered_join(JoinType::Left, None, |schema| {
+            let lt = binary(col("b", schema)?, Operator::Lt, col("d", schema)?, schema)?;
+            binary(is_null(col("d", schema)?)?, Operator::Or, lt, schema)
+        })?;
+        let expected = [
+            "FilterExec: d@3 IS NULL OR b@1 < d@3",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn full_join_simplified() -> Result<()> {
+        // b + d IS NOT NULL rejects the rows padded with nulls on both sides
+        let plan = filtered_join(JoinType::Full, None, |schema| {
+            let sum =
+                binary(col("b", schema)?, Operator::Plus, col("d", schema)?, schema)?;
+            Ok(Arc::new(IsNotNullExpr::new(sum)))
+        })?;
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)], filter=b@0 + d@1 IS NOT NULL",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // b > 1 only rejects the rows padded with nulls on the left
+        let plan = filtered_join(JoinType::Full, None, |schema| {
+            let gt = binary(col("b", schema)?, Operator::Gt, lit(1i32), schema)?;
+            let ne = binary(
+                col("d", schema)?,
+                Operator::IsDistinctFrom,
+                lit(ScalarValue::Int32(Some(3))),
+                schema,
+            )?;
+            binary(gt, Operator::And, ne, schema)
+        })?;
+        let expected = [
+            "FilterExec: b@1 > 1 AND d@3 IS DISTINCT FROM 3",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+}
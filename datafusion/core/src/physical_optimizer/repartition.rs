@@ -23,10 +23,9 @@ use super::optimizer::PhysicalOptimizerRule;
 use crate::config::ConfigOptions;
 use crate::datasource::physical_plan::ParquetExec;
 use crate::error::Result;
+use crate::physical_plan::tree_node::TransformWithPayload;
 use crate::physical_plan::Partitioning::*;
-use crate::physical_plan::{
-    repartition::RepartitionExec, with_new_children_if_necessary, ExecutionPlan,
-};
+use crate::physical_plan::{repartition::RepartitionExec, ExecutionPlan};
 
 /// Optimizer that introduces repartition to introduce more
 /// parallelism in the plan
@@ -131,6 +130,19 @@ impl Repartition {
     }
 }
 
+/// Properties of a plan decided by its parent while [`optimize_partitions`]
+/// walks down the plan
+#[derive(Debug, Clone, Copy)]
+struct RepartitionContext {
+    /// Whether the plan is the root of the whole plan, whose output is never
+    /// repartitioned
+    is_root: bool,
+    /// Whether the output of the plan may be reordered
+    can_reorder: bool,
+    /// Whether the parent of the plan would benefit from additional partitions
+    would_benefit: bool,
+}
+
 /// Recursively attempts to increase the overall parallelism of the
 /// plan, while respecting ordering, by adding a `RepartitionExec` at
 /// the output of `plan` if it would help parallelism and not destroy
@@ -166,53 +178,74 @@ impl Repartition {
 fn optimize_partitions(
     target_partitions: usize,
     plan: Arc<dyn ExecutionPlan>,
-    is_root: bool,
-    can_reorder: bool,
-    would_benefit: bool,
+    context: RepartitionContext,
     repartition_file_scans: bool,
     repartition_file_min_size: usize,
 ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
     // Recurse into children bottom-up (attempt to repartition as
     // early as possible)
-    let new_plan = if plan.children().is_empty() {
-        // leaf node - don't replace children
-        Transformed::No(plan)
-    } else {
-        let children = plan
-            .children()
-            .iter()
-            .enumerate()
-            .map(|(idx, child)| {
-                // Does plan itself (not its parent) require its input to
-                // be sorted in some way?
-                let required_input_ordering =
-                    plan_has_required_input_ordering(plan.as_ref());
-
-                // We can reorder a child if:
-                //   - It has no ordering to preserve, or
-                //   - Its parent has no required input ordering and does not
-                //     maintain input ordering.
-                // Check if this condition holds:
-                let can_reorder_child = child.output_ordering().is_none()
-                    || (!required_input_ordering
-                        && (can_reorder || !plan.maintains_input_order()[idx]));
-
-                optimize_partitions(
-                    target_partitions,
-                    child.clone(),
-                    false, // child is not root
-                    can_reorder_child,
-                    plan.benefits_from_input_partitioning(),
-                    repartition_file_scans,
-                    repartition_file_min_size,
-                )
-                .map(Transformed::into)
-            })
-            .collect::<Result<_>>()?;
-        with_new_children_if_necessary(plan, children)?
-    };
+    plan.transform_with_payload(
+        context,
+        &mut |plan, context| Ok(children_contexts(plan.as_ref(), context)),
+        &mut |plan, context| {
+            repartition_output(
+                target_partitions,
+                plan,
+                context,
+                repartition_file_scans,
+                repartition_file_min_size,
+            )
+        },
+    )
+}
 
-    let (new_plan, transformed) = new_plan.into_pair();
+/// Returns the [`RepartitionContext`] of each child of `plan`
+fn children_contexts(
+    plan: &dyn ExecutionPlan,
+    context: RepartitionContext,
+) -> Vec<RepartitionContext> {
+    // Does plan itself (not its parent) require its input to
+    // be sorted in some way?
+    let required_input_ordering = plan_has_required_input_ordering(plan);
+    let maintains_input_order = plan.maintains_input_order();
+
+    plan.children()
+        .iter()
+        .enumerate()
+        .map(|(idx, child)| {
+            // We can reorder a child if:
+            //   - It has no ordering to preserve, or
+            //   - Its parent has no required input ordering and does not
+            //     maintain input ordering.
+            // Check if this condition holds:
+            let can_reorder = child.output_ordering().is_none()
+                || (!required_input_ordering
+                    && (context.can_reorder || !maintains_input_order[idx]));
+
+            RepartitionContext {
+                is_root: false,
+                can_reorder,
+                would_benefit: plan.benefits_from_input_partitioning(),
+            }
+        })
+        .collect()
+}
+
+/// Adds a `RepartitionExec` at the output of `new_plan`, whose children
+/// have already been optimized, if it is allowed by `context` and would
+/// increase the parallelism
+fn repartition_output(
+    target_partitions: usize,
+    new_plan: Arc<dyn ExecutionPlan>,
+    context: RepartitionContext,
+    repartition_file_scans: bool,
+    repartition_file_min_size: usize,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let RepartitionContext {
+        is_root,
+        can_reorder,
+        would_benefit,
+    } = context;
 
     // decide if we should bother trying to repartition the output of this plan
     let mut could_repartition = match new_plan.output_partitioning() {
@@ -240,11 +273,7 @@ fn optimize_partitions(
 
     // If repartition is not allowed - return plan as it is
     if !repartition_allowed {
-        return Ok(if transformed {
-            Transformed::Yes(new_plan)
-        } else {
-            Transformed::No(new_plan)
-        });
+        return Ok(Transformed::No(new_plan));
     }
 
     // For ParquetExec return internally repartitioned version of the plan in case `repartition_file_scans` is set
@@ -286,15 +315,15 @@ impl PhysicalOptimizerRule for Repartition {
         if !enabled || target_partitions == 1 {
             Ok(plan)
         } else {
-            let is_root = true;
-            let can_reorder = plan.output_ordering().is_none();
-            let would_benefit = false;
+            let context = RepartitionContext {
+                is_root: true,
+                can_reorder: plan.output_ordering().is_none(),
+                would_benefit: false,
+            };
             optimize_partitions(
                 target_partitions,
                 plan.clone(),
-                is_root,
-                can_reorder,
-                would_benefit,
+                context,
                 repartition_file_scans,
                 repartition_file_min_size,
             )
@@ -0,0 +1,505 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! SimplifyFilteredOuterJoin optimizer rule turns outer joins into inner (or less
+//! outer) joins when the [`FilterExec`] above them rejects the rows padded with nulls
+use std::sync::Arc;
+
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_expr::null_rejection::{is_null_rejecting, simplify_outer_join_type};
+use datafusion_expr::JoinType;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::joins::utils::{JoinFilter, JoinSide};
+use crate::physical_plan::joins::{HashJoinExec, SortMergeJoinExec};
+use crate::physical_plan::ExecutionPlan;
+
+/// SimplifyFilteredOuterJoin optimizer rule simplifies the join type of a
+/// [`HashJoinExec`] or [`SortMergeJoinExec`] directly below a [`FilterExec`]
+/// whose predicate rejects the rows the join pads with nulls.
+///
+/// For example, the predicate `r.x > 5` above `l LEFT JOIN r` is never true
+/// for the rows of `l` without a match in `r`, so the join is an inner join.
+/// A full join becomes a left (right) join if only the rows padded with nulls
+/// on the left (right) side are rejected. Inner joins can swap their inputs
+/// and have better statistics, so this rule runs before `JoinSelection`.
+///
+/// Predicates that may be true for null inputs, like `r.x IS NULL` or
+/// `coalesce(r.x, 0) > 5`, keep the join type unchanged.
+///
+/// The logical `EliminateOuterJoin` rule of `datafusion_optimizer` already does
+/// this for the filters of the logical plan, with the same null rejection check,
+/// see [`is_null_rejecting`]. This physical pass is only needed for the physical
+/// plans that were not planned from an optimized logical plan, like plans built
+/// directly or deserialized. [`PullFilterIntoJoin`] simplifies the hash joins
+/// below the predicates it moves into joins itself, but leaves the predicates
+/// referencing a single side of the join and the sort merge joins to this rule.
+///
+/// [`PullFilterIntoJoin`]: crate::physical_optimizer::pull_filter_into_join::PullFilterIntoJoin
+#[derive(Default)]
+pub struct SimplifyFilteredOuterJoin {}
+
+impl SimplifyFilteredOuterJoin {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for SimplifyFilteredOuterJoin {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            Ok(
+                match simplify_filtered_join(filter.predicate(), filter.input())? {
+                    Some(join) => Transformed::Yes(Arc::new(FilterExec::try_new(
+                        filter.predicate().clone(),
+                        join,
+                    )?)),
+                    None => Transformed::No(plan),
+                },
+            )
+        })
+    }
+
+    fn name(&self) -> &str {
+        "SimplifyFilteredOuterJoin"
+    }
+
+    /// Simplifying an outer join makes the columns of its null-supplying side
+    /// non-nullable
+    fn schema_check(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the `input` join with the join type simplified for `predicate`, or
+/// `None` if `input` is not a join that can be simplified
+fn simplify_filtered_join(
+    predicate: &Arc<dyn PhysicalExpr>,
+    input: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    // The output of the joins consists of the left columns followed by the right ones
+    let simplify = |join_type: JoinType, left: &Arc<dyn ExecutionPlan>| {
+        let left_len = left.schema().fields().len();
+        let side_of = |column: &Column| {
+            if column.index() < left_len {
+                JoinSide::Left
+            } else {
+                JoinSide::Right
+            }
+        };
+        let simplified = simplify_join_type(join_type, predicate, &side_of);
+        (simplified != join_type).then_some(simplified)
+    };
+
+    if let Some(join) = input.as_any().downcast_ref::<HashJoinExec>() {
+        return match simplify(*join.join_type(), join.left()) {
+            Some(join_type) => Ok(Some(with_join_type(
+                join,
+                join.filter().cloned(),
+                join_type,
+            )?)),
+            None => Ok(None),
+        };
+    }
+    if let Some(join) = input.as_any().downcast_ref::<SortMergeJoinExec>() {
+        return match simplify(join.join_type, &join.left) {
+            Some(join_type) => Ok(Some(Arc::new(SortMergeJoinExec::try_new(
+                join.left.clone(),
+                join.right.clone(),
+                join.on.clone(),
+                join_type,
+                join.sort_options.clone(),
+                join.null_equals_null,
+            )?))),
+            None => Ok(None),
+        };
+    }
+    Ok(None)
+}
+
+/// Returns a copy of the hash `join` with the given `filter` and `join_type`
+pub(crate) fn with_join_type(
+    join: &HashJoinExec,
+    filter: Option<JoinFilter>,
+    join_type: JoinType,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    Ok(Arc::new(
+        HashJoinExec::try_new(
+            join.left().clone(),
+            join.right().clone(),
+            join.on().to_vec(),
+            filter,
+            &join_type,
+            *join.partition_mode(),
+            join.null_equals_null(),
+        )?
+        .with_null_equals_null_per_key(join.null_equals_null_per_key().to_vec())?
+        .with_settings_of(join)?,
+    ))
+}
+
+/// Simplifies an outer join whose output is filtered by `predicate`, if the
+/// predicate rejects the rows padded with nulls by the join, with the same null
+/// rejection check as the logical `EliminateOuterJoin` rule
+pub(crate) fn simplify_join_type(
+    join_type: JoinType,
+    predicate: &Arc<dyn PhysicalExpr>,
+    side_of: &dyn Fn(&Column) -> JoinSide,
+) -> JoinType {
+    let rejects_nulls = |side| {
+        is_null_rejecting(predicate.as_ref(), &|expr: &dyn PhysicalExpr| {
+            matches!(
+                expr.as_any().downcast_ref::<Column>(),
+                Some(column) if side_of(column) == side
+            )
+        })
+    };
+    simplify_outer_join_type(
+        join_type,
+        rejects_nulls(JoinSide::Left),
+        rejects_nulls(JoinSide::Right),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::PhysicalSortExpr;
+    use crate::physical_plan::joins::PartitionMode;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::sorts::sort::SortExec;
+    use crate::physical_plan::{collect, displayable};
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion_expr::{BuiltinScalarFunction, Operator};
+    use datafusion_physical_expr::execution_props::ExecutionProps;
+    use datafusion_physical_expr::expressions::{
+        binary, col, is_null, lit, IsNotNullExpr,
+    };
+    use datafusion_physical_expr::functions::create_physical_expr;
+
+    fn memory_exec(
+        names: (&str, &str),
+        a: Vec<Option<i32>>,
+        b: Vec<Option<i32>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(names.0, DataType::Int32, true),
+            Field::new(names.1, DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )?;
+        Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?))
+    }
+
+    /// The inputs `l(a, b)` and `r(c, d)`, both with rows without a match on
+    /// `a = c` on the other side
+    fn inputs() -> Result<(Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>)> {
+        let left = memory_exec(
+            ("a", "b"),
+            vec![Some(1), Some(2), Some(3), Some(4), None],
+            vec![Some(1), Some(2), Some(3), None, Some(5)],
+        )?;
+        let right = memory_exec(
+            ("c", "d"),
+            vec![Some(1), Some(2), Some(2), Some(5), Some(6)],
+            vec![Some(10), Some(1), None, Some(3), Some(50)],
+        )?;
+        Ok((left, right))
+    }
+
+    /// Hash joins `l(a, b)` with `r(c, d)` on `a = c` and filters the output
+    /// with the predicate built by `predicate`
+    fn filtered_hash_join(
+        join_type: JoinType,
+        predicate: impl Fn(&Schema) -> Result<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let (left, right) = inputs()?;
+        let on = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+        let join = Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &join_type,
+            PartitionMode::CollectLeft,
+            false,
+        )?);
+        let predicate = predicate(join.schema().as_ref())?;
+        Ok(Arc::new(FilterExec::try_new(predicate, join)?))
+    }
+
+    /// Sort merge joins `l(a, b)` with `r(c, d)` on `a = c` and filters the
+    /// output with the predicate built by `predicate`
+    fn filtered_sort_merge_join(
+        join_type: JoinType,
+        predicate: impl Fn(&Schema) -> Result<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let (left, right) = inputs()?;
+        let sorted = |input: Arc<dyn ExecutionPlan>, name: &str| {
+            let expr = vec![PhysicalSortExpr {
+                expr: Arc::new(Column::new(name, 0)),
+                options: SortOptions::default(),
+            }];
+            Arc::new(SortExec::new(expr, input)) as Arc<dyn ExecutionPlan>
+        };
+        let on = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+        let join = Arc::new(SortMergeJoinExec::try_new(
+            sorted(left, "a"),
+            sorted(right, "c"),
+            on,
+            join_type,
+            vec![SortOptions::default()],
+            false,
+        )?);
+        let predicate = predicate(join.schema().as_ref())?;
+        Ok(Arc::new(FilterExec::try_new(predicate, join)?))
+    }
+
+    fn get_plan_string(plan: &Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let formatted = displayable(plan.as_ref()).indent().to_string();
+        formatted
+            .trim()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Optimizes `plan`, checks the optimized plan against `expected` and
+    /// that both plans produce the same results
+    async fn assert_optimized(
+        plan: Arc<dyn ExecutionPlan>,
+        expected: &[&str],
+    ) -> Result<()> {
+        let optimized = SimplifyFilteredOuterJoin::new()
+            .optimize(plan.clone(), &ConfigOptions::new())?;
+        assert_eq!(get_plan_string(&optimized), expected);
+
+        let task_ctx = SessionContext::new().task_ctx();
+        let expected = collect(plan, task_ctx.clone()).await?;
+        let actual = collect(optimized, task_ctx).await?;
+        let mut expected = pretty_format_batches(&expected)?
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        let mut actual = pretty_format_batches(&actual)?
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn left_join_null_rejecting() -> Result<()> {
+        // d > 5 is null for the rows without a match on the right
+        let plan = filtered_hash_join(JoinType::Left, |schema| {
+            binary(col("d", schema)?, Operator::Gt, lit(5i32), schema)
+        })?;
+        let expected = [
+            "FilterExec: d@3 > 5",
+            "  HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // c IS NOT NULL is false for the rows without a match on the right
+        let plan = filtered_hash_join(JoinType::Left, |schema| {
+            Ok(Arc::new(IsNotNullExpr::new(col("c", schema)?)))
+        })?;
+        let expected = [
+            "FilterExec: c@2 IS NOT NULL",
+            "  HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn left_join_not_null_rejecting() -> Result<()> {
+        // d IS NULL keeps the rows without a match on the right
+        let plan =
+            filtered_hash_join(JoinType::Left, |schema| is_null(col("d", schema)?))?;
+        let expected = [
+            "FilterExec: d@3 IS NULL",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // coalesce(d, 10) > 5 keeps the rows without a match on the right
+        let plan = filtered_hash_join(JoinType::Left, |schema| {
+            let coalesce = create_physical_expr(
+                &BuiltinScalarFunction::Coalesce,
+                &[col("d", schema)?, lit(10i32)],
+                schema,
+                &ExecutionProps::new(),
+            )?;
+            binary(coalesce, Operator::Gt, lit(5i32), schema)
+        })?;
+        let expected = [
+            "FilterExec: coalesce(d@3, 10) > 5",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // b > 1 only references the preserved side
+        let plan = filtered_hash_join(JoinType::Left, |schema| {
+            binary(col("b", schema)?, Operator::Gt, lit(1i32), schema)
+        })?;
+        let expected = [
+            "FilterExec: b@1 > 1",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn right_join_null_rejecting() -> Result<()> {
+        // a + b < 10 is null for the rows without a match on the left
+        let plan = filtered_hash_join(JoinType::Right, |schema| {
+            let sum =
+                binary(col("a", schema)?, Operator::Plus, col("b", schema)?, schema)?;
+            binary(sum, Operator::Lt, lit(10i32), schema)
+        })?;
+        let expected = [
+            "FilterExec: a@0 + b@1 < 10",
+            "  HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn full_join() -> Result<()> {
+        let gt = |name: &str, schema: &Schema| {
+            binary(col(name, schema)?, Operator::Gt, lit(1i32), schema)
+        };
+
+        // b > 1 only rejects the rows padded with nulls on the left
+        let plan = filtered_hash_join(JoinType::Full, |schema| gt("b", schema))?;
+        let expected = [
+            "FilterExec: b@1 > 1",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // d > 1 only rejects the rows padded with nulls on the right
+        let plan = filtered_hash_join(JoinType::Full, |schema| gt("d", schema))?;
+        let expected = [
+            "FilterExec: d@3 > 1",
+            "  HashJoinExec: mode=CollectLeft, join_type=Right, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // b > 1 AND d > 1 rejects the rows padded with nulls on both sides
+        let plan = filtered_hash_join(JoinType::Full, |schema| {
+            binary(gt("b", schema)?, Operator::And, gt("d", schema)?, schema)
+        })?;
+        let expected = [
+            "FilterExec: b@1 > 1 AND d@3 > 1",
+            "  HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // b > 1 OR d > 1 keeps rows padded with nulls on either side
+        let plan = filtered_hash_join(JoinType::Full, |schema| {
+            binary(gt("b", schema)?, Operator::Or, gt("d", schema)?, schema)
+        })?;
+        let expected = [
+            "FilterExec: b@1 > 1 OR d@3 > 1",
+            "  HashJoinExec: mode=CollectLeft, join_type=Full, on=[(a@0, c@0)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+
+    #[tokio::test]
+    async fn sort_merge_join() -> Result<()> {
+        // d < 40 is null for the rows without a match on the right
+        let plan = filtered_sort_merge_join(JoinType::Left, |schema| {
+            binary(col("d", schema)?, Operator::Lt, lit(40i32), schema)
+        })?;
+        let expected = [
+            "FilterExec: d@3 < 40",
+            "  SortMergeJoin: join_type=Inner, on=[(a@0, c@0)]",
+            "    SortExec: expr=[a@0 ASC]",
+            "      MemoryExec: partitions=1, partition_sizes=[1]",
+            "    SortExec: expr=[c@0 ASC]",
+            "      MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await?;
+
+        // d IS NULL keeps the rows without a match on the right
+        let plan = filtered_sort_merge_join(JoinType::Full, |schema| {
+            is_null(col("d", schema)?)
+        })?;
+        let expected = [
+            "FilterExec: d@3 IS NULL",
+            "  SortMergeJoin: join_type=Full, on=[(a@0, c@0)]",
+            "    SortExec: expr=[a@0 ASC]",
+            "      MemoryExec: partitions=1, partition_sizes=[1]",
+            "    SortExec: expr=[c@0 ASC]",
+            "      MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_optimized(plan, &expected).await
+    }
+}
@@ -973,7 +973,7 @@ mod tests {
     use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
     use crate::physical_plan::filter::FilterExec;
     use crate::physical_plan::joins::utils::JoinOn;
-    use crate::physical_plan::joins::SortMergeJoinExec;
+    use crate::physical_plan::joins::{HashJoinExec, PartitionMode, SortMergeJoinExec};
     use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
     use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::repartition::RepartitionExec;
@@ -2354,8 +2354,9 @@ mod tests {
 
         // Join on (nullable_col == col_a)
         let join_on = vec![(
-            Column::new_with_schema("nullable_col", &left.schema()).unwrap(),
-            Column::new_with_schema("col_a", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("nullable_col", &left.schema()).unwrap())
+                as _,
+            Arc::new(Column::new_with_schema("col_a", &right.schema()).unwrap()) as _,
         )];
 
         let join_types = vec![
@@ -2376,9 +2377,9 @@ mod tests {
             let physical_plan = sort_preserving_merge_exec(sort_exprs.clone(), join);
 
             let join_plan =
-                format!("SortMergeJoin: join_type={join_type}, on=[(Column {{ name: \"nullable_col\", index: 0 }}, Column {{ name: \"col_a\", index: 0 }})]");
+                format!("SortMergeJoin: join_type={join_type}, on=[(nullable_col@0, col_a@0)]");
             let join_plan2 =
-                format!("  SortMergeJoin: join_type={join_type}, on=[(Column {{ name: \"nullable_col\", index: 0 }}, Column {{ name: \"col_a\", index: 0 }})]");
+                format!("  SortMergeJoin: join_type={join_type}, on=[(nullable_col@0, col_a@0)]");
             let expected_input = vec![
                 "SortPreservingMergeExec: [nullable_col@0 ASC,non_nullable_col@1 ASC]",
                 join_plan2.as_str(),
@@ -2416,6 +2417,54 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_hash_join_keeps_probe_side_order() -> Result<()> {
+        let left_schema = create_test_schema()?;
+        let right_schema = create_test_schema2()?;
+
+        let left = parquet_exec(&left_schema);
+        let right = sort_exec(
+            vec![sort_expr("col_a", &right_schema)],
+            parquet_exec(&right_schema),
+        );
+
+        // Join on (nullable_col == col_a)
+        let join_on = vec![(
+            Arc::new(Column::new_with_schema("nullable_col", &left.schema()).unwrap())
+                as _,
+            Arc::new(Column::new_with_schema("col_a", &right.schema()).unwrap()) as _,
+        )];
+
+        for join_type in [JoinType::Inner, JoinType::Right, JoinType::Left] {
+            let join = hash_join_exec(left.clone(), right.clone(), &join_on, &join_type);
+            let physical_plan =
+                sort_exec(vec![sort_expr("col_a", &join.schema())], join.clone());
+
+            let join_plan = format!("HashJoinExec: mode=CollectLeft, join_type={join_type}, on=[(nullable_col@0, col_a@0)]");
+            let join_plan2 = format!("  {join_plan}");
+            let expected_input = vec![
+                "SortExec: expr=[col_a@2 ASC]",
+                join_plan2.as_str(),
+                "    ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
+                "    SortExec: expr=[col_a@0 ASC]",
+                "      ParquetExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b]",
+            ];
+            let expected_optimized = match join_type {
+                // the join keeps the order of its probe side, so the sort on top
+                // of it is removed
+                JoinType::Inner | JoinType::Right => vec![
+                    join_plan.as_str(),
+                    "  ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
+                    "  SortExec: expr=[col_a@0 ASC]",
+                    "    ParquetExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b]",
+                ],
+                _ => expected_input.clone(),
+            };
+            assert_optimized!(expected_input, expected_optimized, physical_plan);
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_sort_merge_join_order_by_right() -> Result<()> {
         let left_schema = create_test_schema()?;
@@ -2426,8 +2475,9 @@ mod tests {
 
         // Join on (nullable_col == col_a)
         let join_on = vec![(
-            Column::new_with_schema("nullable_col", &left.schema()).unwrap(),
-            Column::new_with_schema("col_a", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("nullable_col", &left.schema()).unwrap())
+                as _,
+            Arc::new(Column::new_with_schema("col_a", &right.schema()).unwrap()) as _,
         )];
 
         let join_types = vec![
@@ -2447,7 +2497,7 @@ mod tests {
             let physical_plan = sort_preserving_merge_exec(sort_exprs, join);
 
             let join_plan =
-                format!("SortMergeJoin: join_type={join_type}, on=[(Column {{ name: \"nullable_col\", index: 0 }}, Column {{ name: \"col_a\", index: 0 }})]");
+                format!("SortMergeJoin: join_type={join_type}, on=[(nullable_col@0, col_a@0)]");
             let spm_plan = match join_type {
                 JoinType::RightAnti => {
                     "SortPreservingMergeExec: [col_a@0 ASC,col_b@1 ASC]"
@@ -2455,7 +2505,7 @@ mod tests {
                 _ => "SortPreservingMergeExec: [col_a@2 ASC,col_b@3 ASC]",
             };
             let join_plan2 =
-                format!("  SortMergeJoin: join_type={join_type}, on=[(Column {{ name: \"nullable_col\", index: 0 }}, Column {{ name: \"col_a\", index: 0 }})]");
+                format!("  SortMergeJoin: join_type={join_type}, on=[(nullable_col@0, col_a@0)]");
             let expected_input = vec![
                 spm_plan,
                 join_plan2.as_str(),
@@ -2500,8 +2550,9 @@ mod tests {
 
         // Join on (nullable_col == col_a)
         let join_on = vec![(
-            Column::new_with_schema("nullable_col", &left.schema()).unwrap(),
-            Column::new_with_schema("col_a", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("nullable_col", &left.schema()).unwrap())
+                as _,
+            Arc::new(Column::new_with_schema("col_a", &right.schema()).unwrap()) as _,
         )];
 
         let join = sort_merge_join_exec(left, right, &join_on, &JoinType::Inner);
@@ -2515,7 +2566,7 @@ mod tests {
 
         let expected_input = vec![
             "SortPreservingMergeExec: [col_b@3 ASC,col_a@2 ASC]",
-            "  SortMergeJoin: join_type=Inner, on=[(Column { name: \"nullable_col\", index: 0 }, Column { name: \"col_a\", index: 0 })]",
+            "  SortMergeJoin: join_type=Inner, on=[(nullable_col@0, col_a@0)]",
             "    ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
             "    ParquetExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b]",
         ];
@@ -2523,7 +2574,7 @@ mod tests {
         // can not push down the sort requirements, need to add SortExec
         let expected_optimized = vec![
             "SortExec: expr=[col_b@3 ASC,col_a@2 ASC]",
-            "  SortMergeJoin: join_type=Inner, on=[(Column { name: \"nullable_col\", index: 0 }, Column { name: \"col_a\", index: 0 })]",
+            "  SortMergeJoin: join_type=Inner, on=[(nullable_col@0, col_a@0)]",
             "    SortExec: expr=[nullable_col@0 ASC]",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
             "    SortExec: expr=[col_a@0 ASC]",
@@ -2541,7 +2592,7 @@ mod tests {
 
         let expected_input = vec![
             "SortPreservingMergeExec: [nullable_col@0 ASC,col_b@3 ASC,col_a@2 ASC]",
-            "  SortMergeJoin: join_type=Inner, on=[(Column { name: \"nullable_col\", index: 0 }, Column { name: \"col_a\", index: 0 })]",
+            "  SortMergeJoin: join_type=Inner, on=[(nullable_col@0, col_a@0)]",
             "    ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
             "    ParquetExec: file_groups={1 group: [[x]]}, projection=[col_a, col_b]",
         ];
@@ -2549,7 +2600,7 @@ mod tests {
         // can not push down the sort requirements, need to add SortExec
         let expected_optimized = vec![
             "SortExec: expr=[nullable_col@0 ASC,col_b@3 ASC,col_a@2 ASC]",
-            "  SortMergeJoin: join_type=Inner, on=[(Column { name: \"nullable_col\", index: 0 }, Column { name: \"col_a\", index: 0 })]",
+            "  SortMergeJoin: join_type=Inner, on=[(nullable_col@0, col_a@0)]",
             "    SortExec: expr=[nullable_col@0 ASC]",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
             "    SortExec: expr=[col_a@0 ASC]",
@@ -2874,6 +2925,26 @@ mod tests {
         )
     }
 
+    fn hash_join_exec(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        join_on: &JoinOn,
+        join_type: &JoinType,
+    ) -> Arc<dyn ExecutionPlan> {
+        Arc::new(
+            HashJoinExec::try_new(
+                left,
+                right,
+                join_on.clone(),
+                None,
+                join_type,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
     fn sort_merge_join_exec(
         left: Arc<dyn ExecutionPlan>,
         right: Arc<dyn ExecutionPlan>,
@@ -138,7 +138,7 @@ impl BoundedAggregateStream {
 
         let timer = baseline_metrics.elapsed_compute().timer();
 
-        let mut start_idx = agg_group_by.expr.len();
+        let mut start_idx = agg_group_by.num_output_exprs();
         let mut row_aggr_expr = vec![];
         let mut row_agg_indices = vec![];
         let mut row_aggregate_expressions = vec![];
@@ -193,7 +193,7 @@ impl BoundedAggregateStream {
 
         let row_aggr_schema = aggr_state_schema(&row_aggr_expr);
 
-        let group_schema = group_schema(&agg_schema, agg_group_by.expr.len());
+        let group_schema = group_schema(&agg_schema, agg_group_by.num_output_exprs());
         let row_converter = RowConverter::new(
             group_schema
                 .fields()
@@ -395,10 +395,6 @@ impl BoundedAggregateStream {
                     // Add new entry to group_states and save newly created index
                     let group_state = GroupState {
                         group_by_values: owned_row,
-                        aggregation_buffer: vec![
-                            0;
-                            self.row_aggr_layout.fixed_part_width()
-                        ],
                         accumulator_set,
                         indices: (range.start as u32..range.end as u32)
                             .collect::<Vec<_>>(), // 1.3
@@ -407,9 +403,10 @@ impl BoundedAggregateStream {
 
                     // NOTE: do NOT include the `RowGroupState` struct size in here because this is captured by
                     // `group_states` (see allocation down below)
+                    let aggregation_buffer =
+                        vec![0; self.row_aggr_layout.fixed_part_width()];
                     *allocated += std::mem::size_of_val(&group_state.group_by_values)
-                        + (std::mem::size_of::<u8>()
-                            * group_state.aggregation_buffer.capacity())
+                        + (std::mem::size_of::<u8>() * aggregation_buffer.capacity())
                         + (std::mem::size_of::<u32>() * group_state.indices.capacity());
 
                     // Allocation done by normal accumulators
@@ -430,6 +427,7 @@ impl BoundedAggregateStream {
 
                     let ordered_group_state = OrderedGroupState {
                         group_state,
+                        aggregation_buffer,
                         ordered_columns,
                         status: GroupStatus::GroupProgress,
                         hash,
@@ -502,10 +500,6 @@ impl BoundedAggregateStream {
                         .collect::<Vec<_>>();
                     let group_state = GroupState {
                         group_by_values: group_rows.row(row).owned(),
-                        aggregation_buffer: vec![
-                            0;
-                            self.row_aggr_layout.fixed_part_width()
-                        ],
                         accumulator_set,
                         indices: vec![row as u32], // 1.3
                     };
@@ -513,9 +507,10 @@ impl BoundedAggregateStream {
 
                     // NOTE: do NOT include the `GroupState` struct size in here because this is captured by
                     // `group_states` (see allocation down below)
+                    let aggregation_buffer =
+                        vec![0; self.row_aggr_layout.fixed_part_width()];
                     *allocated += std::mem::size_of_val(&group_state.group_by_values)
-                        + (std::mem::size_of::<u8>()
-                            * group_state.aggregation_buffer.capacity())
+                        + (std::mem::size_of::<u8>() * aggregation_buffer.capacity())
                         + (std::mem::size_of::<u32>() * group_state.indices.capacity());
 
                     // Allocation done by normal accumulators
@@ -537,6 +532,7 @@ impl BoundedAggregateStream {
                     // Add new entry to group_states and save newly created index
                     let ordered_group_state = OrderedGroupState {
                         group_state,
+                        aggregation_buffer,
                         ordered_columns,
                         status: GroupStatus::GroupProgress,
                         hash,
@@ -571,8 +567,11 @@ impl BoundedAggregateStream {
             .iter()
             .zip(offsets.windows(2))
             .try_for_each(|(group_idx, offsets)| {
-                let group_state =
-                    &mut self.aggr_state.ordered_group_states[*group_idx].group_state;
+                let OrderedGroupState {
+                    group_state,
+                    aggregation_buffer,
+                    ..
+                } = &mut self.aggr_state.ordered_group_states[*group_idx];
                 // 2.2
                 // Process row accumulators
                 self.row_accumulators
@@ -587,8 +586,7 @@ impl BoundedAggregateStream {
                         )?;
                         let mut state_accessor =
                             RowAccessor::new_from_layout(self.row_aggr_layout.clone());
-                        state_accessor
-                            .point_to(0, group_state.aggregation_buffer.as_mut_slice());
+                        state_accessor.point_to(0, aggregation_buffer.as_mut_slice());
                         match self.mode {
                             AggregateMode::Partial | AggregateMode::Single => {
                                 accumulator.update_batch(&values, &mut state_accessor)
@@ -650,11 +648,14 @@ impl BoundedAggregateStream {
             .collect::<Result<Vec<_>>>()?;
 
         for group_idx in groups_with_rows {
-            let group_state =
-                &mut self.aggr_state.ordered_group_states[*group_idx].group_state;
+            let OrderedGroupState {
+                group_state,
+                aggregation_buffer,
+                ..
+            } = &mut self.aggr_state.ordered_group_states[*group_idx];
             let mut state_accessor =
                 RowAccessor::new_from_layout(self.row_aggr_layout.clone());
-            state_accessor.point_to(0, group_state.aggregation_buffer.as_mut_slice());
+            state_accessor.point_to(0, aggregation_buffer.as_mut_slice());
             for idx in &group_state.indices {
                 for (accumulator, values_array, filter_array) in izip!(
                     self.row_accumulators.iter_mut(),
@@ -849,6 +850,8 @@ enum GroupStatus {
 #[derive(Debug)]
 pub struct OrderedGroupState {
     group_state: GroupState,
+    // Accumulator state, stored sequentially
+    aggregation_buffer: Vec<u8>,
     ordered_columns: Vec<ScalarValue>,
     status: GroupStatus,
     hash: u64,
@@ -934,15 +937,16 @@ impl BoundedAggregateStream {
         // Buffers for each distinct group (i.e. row accumulator memories)
         let mut state_buffers = group_state_chunk
             .iter()
-            .map(|gs| gs.group_state.aggregation_buffer.clone())
+            .map(|gs| gs.aggregation_buffer.clone())
             .collect::<Vec<_>>();
 
         let output_fields = self.schema.fields();
         // Store row accumulator results (either final output or intermediate state):
         let row_columns = match self.mode {
-            AggregateMode::Partial => {
-                read_as_batch(&state_buffers, &self.row_aggr_schema)
-            }
+            AggregateMode::Partial => read_as_batch(
+                state_buffers.iter().map(|buffer| buffer.as_slice()),
+                &self.row_aggr_schema,
+            ),
             AggregateMode::Final
             | AggregateMode::FinalPartitioned
             | AggregateMode::Single => {
@@ -26,8 +26,8 @@ use crate::physical_plan::{
     DisplayFormatType, Distribution, EquivalenceProperties, ExecutionPlan, Partitioning,
     SendableRecordBatchStream, Statistics,
 };
-use arrow::array::ArrayRef;
-use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::utils::longest_consecutive_prefix;
 use datafusion_common::{DataFusionError, Result};
@@ -93,6 +93,11 @@ pub enum GroupByOrderMode {
     FullyOrdered,
 }
 
+/// Name of the column holding the grouping set id of each group, which is added
+/// after the group columns to the output of aggregations over several grouping
+/// sets. See [`PhysicalGroupBy::grouping_id`].
+pub const INTERNAL_GROUPING_ID: &str = "__grouping_id";
+
 /// Represents `GROUP BY` clause in the plan (including the more general GROUPING SET)
 /// In the case of a simple `GROUP BY a, b` clause, this will contain the expression [a, b]
 /// and a single group [false, false].
@@ -109,6 +114,10 @@ pub enum GroupByOrderMode {
 ///         [true, false]   // (b) <=> (NULL, b)
 ///     ]
 /// }
+///
+/// When there is more than one group, every group is also tagged with its
+/// grouping id (output as the [`INTERNAL_GROUPING_ID`] column), so that a NULL
+/// group value is not mixed up with the NULL aligning a grouping set.
 #[derive(Clone, Debug, Default)]
 pub struct PhysicalGroupBy {
     /// Distinct (Physical Expr, Alias) in the grouping set
@@ -171,6 +180,50 @@ impl PhysicalGroupBy {
     pub fn is_empty(&self) -> bool {
         self.expr.is_empty()
     }
+
+    /// Returns true if this `PhysicalGroupBy` has more than one group, in which
+    /// case its output has an additional [`INTERNAL_GROUPING_ID`] column
+    pub fn has_grouping_set(&self) -> bool {
+        self.groups.len() > 1
+    }
+
+    /// Returns the number of group columns in the output, including the
+    /// [`INTERNAL_GROUPING_ID`] column if any
+    pub fn num_output_exprs(&self) -> usize {
+        self.expr.len() + usize::from(self.has_grouping_set())
+    }
+
+    /// Returns the grouping id of the `group`-th group. Following the SQL
+    /// `GROUPING` function, the bit `1 << (n - 1 - i)` is set if the i-th of the
+    /// n group expressions is NULL in that group, e.g. the grouping id of
+    /// `(a, NULL, NULL)` in `ROLLUP (a, b, c)` is `0b011`.
+    pub fn grouping_id(&self, group: usize) -> u32 {
+        let num_exprs = self.expr.len();
+        self.groups[group]
+            .iter()
+            .enumerate()
+            .filter(|(_, is_null)| **is_null)
+            .fold(0, |id, (idx, _)| id | 1 << (num_exprs - 1 - idx))
+    }
+
+    /// Returns the fields of the group columns in the output
+    fn output_fields(&self, input_schema: &Schema) -> Result<Vec<Field>> {
+        let mut fields = Vec::with_capacity(self.num_output_exprs());
+        for (expr, name) in &self.expr {
+            fields.push(Field::new(
+                name,
+                expr.data_type(input_schema)?,
+                // In cases where we have multiple grouping sets, we will use NULL expressions in
+                // order to align the grouping sets. So the field must be nullable even if the underlying
+                // schema field is not.
+                self.contains_null() || expr.nullable(input_schema)?,
+            ))
+        }
+        if self.has_grouping_set() {
+            fields.push(Field::new(INTERNAL_GROUPING_ID, DataType::UInt32, false));
+        }
+        Ok(fields)
+    }
 }
 
 impl PartialEq for PhysicalGroupBy {
@@ -334,12 +387,19 @@ fn calc_aggregation_ordering(
 fn output_group_expr_helper(group_by: &PhysicalGroupBy) -> Vec<Arc<dyn PhysicalExpr>> {
     // Update column indices. Since the group by columns come first in the output schema, their
     // indices are simply 0..self.group_expr(len).
-    group_by
+    let mut output_exprs = group_by
         .expr()
         .iter()
         .enumerate()
         .map(|(index, (_, name))| Arc::new(Column::new(name, index)) as _)
-        .collect()
+        .collect::<Vec<_>>();
+    if group_by.has_grouping_set() {
+        output_exprs.push(Arc::new(Column::new(
+            INTERNAL_GROUPING_ID,
+            group_by.expr().len(),
+        )));
+    }
+    output_exprs
 }
 
 /// This function returns the ordering requirement of the first non-reversible
@@ -533,13 +593,14 @@ impl AggregateExec {
         input: Arc<dyn ExecutionPlan>,
         input_schema: SchemaRef,
     ) -> Result<Self> {
-        let schema = create_schema(
-            &input.schema(),
-            &group_by.expr,
-            &aggr_expr,
-            group_by.contains_null(),
-            mode,
-        )?;
+        if group_by.has_grouping_set() && group_by.expr.len() > 32 {
+            return Err(DataFusionError::Plan(format!(
+                "Grouping sets support at most 32 distinct group expressions, got {}",
+                group_by.expr.len()
+            )));
+        }
+
+        let schema = create_schema(&input.schema(), &group_by, &aggr_expr, mode)?;
 
         let schema = Arc::new(schema);
         // Reset ordering requirement to `None` if aggregator is not order-sensitive
@@ -789,6 +850,25 @@ impl ExecutionPlan for AggregateExec {
         new_properties
     }
 
+    /// The group columns, which come first in the output, if every group is
+    /// produced once: by a final aggregation, or by a single aggregation of a
+    /// single partition
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        let unique_groups = match self.mode {
+            AggregateMode::Final | AggregateMode::FinalPartitioned => true,
+            AggregateMode::Single => {
+                self.input.output_partitioning().partition_count() == 1
+            }
+            AggregateMode::Partial => false,
+        };
+        if unique_groups && !self.group_by.is_empty() && !self.group_by.has_grouping_set()
+        {
+            vec![(0..self.group_by.expr().len()).collect()]
+        } else {
+            vec![]
+        }
+    }
+
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
         vec![self.input.clone()]
     }
@@ -827,7 +907,7 @@ impl ExecutionPlan for AggregateExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "AggregateExec: mode={:?}", self.mode)?;
                 let g: Vec<String> = if self.group_by.groups.len() == 1 {
                     self.group_by
@@ -921,22 +1001,12 @@ impl ExecutionPlan for AggregateExec {
 
 fn create_schema(
     input_schema: &Schema,
-    group_expr: &[(Arc<dyn PhysicalExpr>, String)],
+    group_by: &PhysicalGroupBy,
     aggr_expr: &[Arc<dyn AggregateExpr>],
-    contains_null_expr: bool,
     mode: AggregateMode,
 ) -> Result<Schema> {
-    let mut fields = Vec::with_capacity(group_expr.len() + aggr_expr.len());
-    for (expr, name) in group_expr {
-        fields.push(Field::new(
-            name,
-            expr.data_type(input_schema)?,
-            // In cases where we have multiple grouping sets, we will use NULL expressions in
-            // order to align the grouping sets. So the field must be nullable even if the underlying
-            // schema field is not.
-            contains_null_expr || expr.nullable(input_schema)?,
-        ))
-    }
+    let mut fields = group_by.output_fields(input_schema)?;
+    fields.reserve(aggr_expr.len());
 
     match mode {
         AggregateMode::Partial => {
@@ -1162,8 +1232,9 @@ fn evaluate_group_by(
     Ok(group_by
         .groups
         .iter()
-        .map(|group| {
-            group
+        .enumerate()
+        .map(|(group_idx, group)| {
+            let mut group_values = group
                 .iter()
                 .enumerate()
                 .map(|(idx, is_null)| {
@@ -1173,7 +1244,15 @@ fn evaluate_group_by(
                         exprs[idx].clone()
                     }
                 })
-                .collect()
+                .collect::<Vec<_>>();
+            if group_by.has_grouping_set() {
+                let grouping_id = group_by.grouping_id(group_idx);
+                group_values.push(Arc::new(UInt32Array::from_value(
+                    grouping_id,
+                    batch.num_rows(),
+                )));
+            }
+            group_values
         })
         .collect())
 }
@@ -1187,17 +1266,19 @@ mod tests {
         PhysicalGroupBy,
     };
     use crate::physical_plan::expressions::{col, Avg};
+    use crate::physical_plan::memory::MemoryExec;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
     use crate::test::{assert_is_pending, csv_exec_sorted};
     use crate::{assert_batches_sorted_eq, physical_plan::common};
-    use arrow::array::{Float64Array, UInt32Array};
+    use arrow::array::{Float64Array, Int64Array, UInt32Array};
     use arrow::compute::{concat_batches, SortOptions};
     use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
     use arrow::record_batch::RecordBatch;
     use datafusion_common::{DataFusionError, Result, ScalarValue};
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{
-        lit, ApproxDistinct, Column, Count, FirstValue, Median,
+        binary, lit, ApproxDistinct, Column, Count, FirstValue, Max, Median, Min, Sum,
     };
     use datafusion_physical_expr::{
         AggregateExpr, EquivalenceProperties, OrderingEquivalenceProperties,
@@ -1304,6 +1385,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_grouping_id() -> Result<()> {
+        let schema = create_test_schema()?;
+        let group_by = PhysicalGroupBy::new(
+            vec![
+                (col("a", &schema)?, "a".to_string()),
+                (col("b", &schema)?, "b".to_string()),
+                (col("c", &schema)?, "c".to_string()),
+            ],
+            vec![
+                (lit(ScalarValue::Int32(None)), "a".to_string()),
+                (lit(ScalarValue::Int32(None)), "b".to_string()),
+                (lit(ScalarValue::Int32(None)), "c".to_string()),
+            ],
+            vec![
+                vec![false, false, false], // (a, b, c)
+                vec![false, false, true],  // (a, b)
+                vec![false, true, true],   // (a)
+                vec![true, true, true],    // ()
+                vec![true, false, true],   // (b)
+            ],
+        );
+        assert!(group_by.has_grouping_set());
+        assert_eq!(group_by.num_output_exprs(), 4);
+        let ids = (0..group_by.groups().len())
+            .map(|group| group_by.grouping_id(group))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![0b000, 0b001, 0b011, 0b111, 0b101]);
+
+        let single = PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".into())]);
+        assert!(!single.has_grouping_set());
+        assert_eq!(single.num_output_exprs(), 1);
+        Ok(())
+    }
+
     /// some mock data to aggregates
     fn some_data() -> (Arc<Schema>, Vec<RecordBatch>) {
         // define a schema.
@@ -1378,34 +1494,35 @@ mod tests {
             common::collect(partial_aggregate.execute(0, task_ctx.clone())?).await?;
 
         let expected = vec![
-            "+---+-----+-----------------+",
-            "| a | b   | COUNT(1)[count] |",
-            "+---+-----+-----------------+",
-            "|   | 1.0 | 2               |",
-            "|   | 2.0 | 2               |",
-            "|   | 3.0 | 2               |",
-            "|   | 4.0 | 2               |",
-            "| 2 |     | 2               |",
-            "| 2 | 1.0 | 2               |",
-            "| 3 |     | 3               |",
-            "| 3 | 2.0 | 2               |",
-            "| 3 | 3.0 | 1               |",
-            "| 4 |     | 3               |",
-            "| 4 | 3.0 | 1               |",
-            "| 4 | 4.0 | 2               |",
-            "+---+-----+-----------------+",
+            "+---+-----+---------------+-----------------+",
+            "| a | b   | __grouping_id | COUNT(1)[count] |",
+            "+---+-----+---------------+-----------------+",
+            "|   | 1.0 | 2             | 2               |",
+            "|   | 2.0 | 2             | 2               |",
+            "|   | 3.0 | 2             | 2               |",
+            "|   | 4.0 | 2             | 2               |",
+            "| 2 |     | 1             | 2               |",
+            "| 2 | 1.0 | 0             | 2               |",
+            "| 3 |     | 1             | 3               |",
+            "| 3 | 2.0 | 0             | 2               |",
+            "| 3 | 3.0 | 0             | 1               |",
+            "| 4 |     | 1             | 3               |",
+            "| 4 | 3.0 | 0             | 1               |",
+            "| 4 | 4.0 | 0             | 2               |",
+            "+---+-----+---------------+-----------------+",
         ];
         assert_batches_sorted_eq!(expected, &result);
 
-        let groups = partial_aggregate.group_expr().expr().to_vec();
+        let partial_schema = partial_aggregate.schema();
+        let final_group: Vec<(Arc<dyn PhysicalExpr>, String)> = partial_aggregate
+            .output_group_expr()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, expr)| (expr, partial_schema.field(idx).name().clone()))
+            .collect();
 
         let merge = Arc::new(CoalescePartitionsExec::new(partial_aggregate));
 
-        let final_group: Vec<(Arc<dyn PhysicalExpr>, String)> = groups
-            .iter()
-            .map(|(_expr, name)| Ok((col(name, &input_schema)?, name.clone())))
-            .collect::<Result<_>>()?;
-
         let final_grouping_set = PhysicalGroupBy::new_single(final_group);
 
         let merged_aggregate = Arc::new(AggregateExec::try_new(
@@ -1421,26 +1538,26 @@ mod tests {
         let result =
             common::collect(merged_aggregate.execute(0, task_ctx.clone())?).await?;
         let batch = concat_batches(&result[0].schema(), &result)?;
-        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(batch.num_columns(), 4);
         assert_eq!(batch.num_rows(), 12);
 
         let expected = vec![
-            "+---+-----+----------+",
-            "| a | b   | COUNT(1) |",
-            "+---+-----+----------+",
-            "|   | 1.0 | 2        |",
-            "|   | 2.0 | 2        |",
-            "|   | 3.0 | 2        |",
-            "|   | 4.0 | 2        |",
-            "| 2 |     | 2        |",
-            "| 2 | 1.0 | 2        |",
-            "| 3 |     | 3        |",
-            "| 3 | 2.0 | 2        |",
-            "| 3 | 3.0 | 1        |",
-            "| 4 |     | 3        |",
-            "| 4 | 3.0 | 1        |",
-            "| 4 | 4.0 | 2        |",
-            "+---+-----+----------+",
+            "+---+-----+---------------+----------+",
+            "| a | b   | __grouping_id | COUNT(1) |",
+            "+---+-----+---------------+----------+",
+            "|   | 1.0 | 2             | 2        |",
+            "|   | 2.0 | 2             | 2        |",
+            "|   | 3.0 | 2             | 2        |",
+            "|   | 4.0 | 2             | 2        |",
+            "| 2 |     | 1             | 2        |",
+            "| 2 | 1.0 | 0             | 2        |",
+            "| 3 |     | 1             | 3        |",
+            "| 3 | 2.0 | 0             | 2        |",
+            "| 3 | 3.0 | 0             | 1        |",
+            "| 4 |     | 1             | 3        |",
+            "| 4 | 3.0 | 0             | 1        |",
+            "| 4 | 4.0 | 0             | 2        |",
+            "+---+-----+---------------+----------+",
         ];
 
         assert_batches_sorted_eq!(&expected, &result);
@@ -1914,4 +2031,113 @@ mod tests {
         assert_eq!(res, order_by_exprs[4]);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn row_accumulators_update_paths() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::Int64, true),
+            Field::new("c", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 2, 3, 4, 1, 2, 3, 4])),
+                Arc::new(Int64Array::from(vec![
+                    Some(i64::MAX),
+                    Some(5),
+                    None,
+                    Some(7),
+                    Some(1),
+                    None,
+                    None,
+                    Some(-7),
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(1.5),
+                    None,
+                    Some(2.0),
+                    Some(4.0),
+                    Some(-1.5),
+                    Some(3.0),
+                    None,
+                    Some(0.5),
+                ])),
+            ],
+        )?;
+        let input: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone(), batch]],
+            schema.clone(),
+            None,
+        )?);
+
+        let group_by =
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".to_string())]);
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Sum::new(col("b", &schema)?, "SUM(b)", DataType::Int64)),
+            Arc::new(Sum::new(
+                col("b", &schema)?,
+                "SUM(b) FILTER",
+                DataType::Int64,
+            )),
+            Arc::new(Count::new(col("b", &schema)?, "COUNT(b)", DataType::Int64)),
+            Arc::new(Min::new(col("c", &schema)?, "MIN(c)", DataType::Float64)),
+            Arc::new(Max::new(col("c", &schema)?, "MAX(c)", DataType::Float64)),
+            Arc::new(Avg::new(col("c", &schema)?, "AVG(c)", DataType::Float64)),
+        ];
+        let c_positive = binary(
+            col("c", &schema)?,
+            Operator::Gt,
+            lit(ScalarValue::Float64(Some(0.0))),
+            &schema,
+        )?;
+        let filters = vec![None, Some(c_positive), None, None, None, None];
+
+        let run = |mode: AggregateMode, scalar_update_factor: usize| {
+            let aggregate = AggregateExec::try_new(
+                mode,
+                group_by.clone(),
+                aggregates.clone(),
+                filters.clone(),
+                vec![None; aggregates.len()],
+                input.clone(),
+                schema.clone(),
+            );
+            let config = SessionConfig::new()
+                .with_batch_size(3)
+                .with_agg_scalar_update_factor(scalar_update_factor);
+            async move {
+                let task_ctx = SessionContext::with_config(config).task_ctx();
+                common::collect(aggregate?.execute(0, task_ctx)?).await
+            }
+        };
+
+        // With 4 groups in batches of 8 rows, a factor of 1 updates the row
+        // accumulators group by group with array slices, while a factor of 8
+        // updates all the groups at once row by row. Sums wrap around on overflow.
+        let expected = vec![
+            "+---+--------+---------------+----------+--------+--------+--------+",
+            "| a | SUM(b) | SUM(b) FILTER | COUNT(b) | MIN(c) | MAX(c) | AVG(c) |",
+            "+---+--------+---------------+----------+--------+--------+--------+",
+            "| 1 | 0      | -2            | 4        | -1.5   | 1.5    | 0.0    |",
+            "| 2 | 10     |               | 2        | 3.0    | 3.0    | 3.0    |",
+            "| 3 |        |               | 0        | 2.0    | 2.0    | 2.0    |",
+            "| 4 | 0      | 0             | 4        | 0.5    | 4.0    | 2.25   |",
+            "+---+--------+---------------+----------+--------+--------+--------+",
+        ];
+        for factor in [1, 8] {
+            let result = run(AggregateMode::Single, factor).await?;
+            assert_batches_sorted_eq!(expected, &result);
+        }
+
+        // the intermediate states must match as well
+        let by_slices = run(AggregateMode::Partial, 1).await?;
+        let by_rows = run(AggregateMode::Partial, 8).await?;
+        assert_eq!(
+            concat_batches(&by_slices[0].schema(), &by_slices)?,
+            concat_batches(&by_rows[0].schema(), &by_rows)?
+        );
+
+        Ok(())
+    }
 }
@@ -30,8 +30,8 @@ use futures::ready;
 use futures::stream::{Stream, StreamExt};
 
 use crate::physical_plan::aggregates::utils::{
-    aggr_state_schema, col_to_scalar, get_at_indices, get_optional_filters,
-    read_as_batch, slice_and_maybe_filter, ExecutionState, GroupState,
+    aggr_state_schema, get_at_indices, get_optional_filters, read_as_batch,
+    slice_and_maybe_filter, ExecutionState, GroupState,
 };
 use crate::physical_plan::aggregates::{
     evaluate_group_by, evaluate_many, evaluate_optional, group_schema, AggregateMode,
@@ -100,8 +100,8 @@ pub(crate) struct GroupedHashAggregateStream {
     random_state: RandomState,
     /// size to be used for resulting RecordBatches
     batch_size: usize,
-    /// threshold for using `ScalarValue`s to update
-    /// accumulators during high-cardinality aggregations for each input batch.
+    /// threshold for updating the row accumulators of all groups at once, row
+    /// by row, during high-cardinality aggregations for each input batch.
     scalar_update_factor: usize,
     /// if the result is chunked into batches,
     /// last offset is preserved for continuation.
@@ -130,7 +130,7 @@ impl GroupedHashAggregateStream {
 
         let timer = baseline_metrics.elapsed_compute().timer();
 
-        let mut start_idx = agg_group_by.expr.len();
+        let mut start_idx = agg_group_by.num_output_exprs();
         let mut row_aggr_expr = vec![];
         let mut row_agg_indices = vec![];
         let mut row_aggregate_expressions = vec![];
@@ -185,7 +185,7 @@ impl GroupedHashAggregateStream {
 
         let row_aggr_schema = aggr_state_schema(&row_aggr_expr);
 
-        let group_schema = group_schema(&agg_schema, agg_group_by.expr.len());
+        let group_schema = group_schema(&agg_schema, agg_group_by.num_output_exprs());
         let row_converter = RowConverter::new(
             group_schema
                 .fields()
@@ -201,6 +201,7 @@ impl GroupedHashAggregateStream {
             reservation: MemoryConsumer::new(name).register(context.memory_pool()),
             map: RawTable::with_capacity(0),
             group_states: Vec::with_capacity(0),
+            aggregation_buffer: Vec::with_capacity(0),
         };
 
         timer.done();
@@ -326,7 +327,10 @@ impl GroupedHashAggregateStream {
         create_hashes(group_values, &self.random_state, &mut batch_hashes)?;
 
         let AggregationState {
-            map, group_states, ..
+            map,
+            group_states,
+            aggregation_buffer,
+            ..
         } = &mut self.aggr_state;
 
         for (row, hash) in batch_hashes.into_iter().enumerate() {
@@ -357,10 +361,6 @@ impl GroupedHashAggregateStream {
                     // Add new entry to group_states and save newly created index
                     let group_state = GroupState {
                         group_by_values: group_rows.row(row).owned(),
-                        aggregation_buffer: vec![
-                            0;
-                            self.row_aggr_layout.fixed_part_width()
-                        ],
                         accumulator_set,
                         indices: vec![row as u32], // 1.3
                     };
@@ -369,10 +369,17 @@ impl GroupedHashAggregateStream {
                     // NOTE: do NOT include the `GroupState` struct size in here because this is captured by
                     // `group_states` (see allocation down below)
                     *allocated += std::mem::size_of_val(&group_state.group_by_values)
-                        + (std::mem::size_of::<u8>()
-                            * group_state.aggregation_buffer.capacity())
                         + (std::mem::size_of::<u32>() * group_state.indices.capacity());
 
+                    // Zeroed row accumulator state of the new group
+                    let capacity_pre = aggregation_buffer.capacity();
+                    aggregation_buffer.resize(
+                        aggregation_buffer.len()
+                            + self.row_aggr_layout.fixed_part_width(),
+                        0,
+                    );
+                    *allocated += aggregation_buffer.capacity() - capacity_pre;
+
                     // Allocation done by normal accumulators
                     *allocated += (std::mem::size_of::<Box<dyn Accumulator>>()
                         * group_state.accumulator_set.capacity())
@@ -415,11 +422,14 @@ impl GroupedHashAggregateStream {
         // 2.3 `slice` from each of its arrays the keys' values
         // 2.4 update / merge the accumulator with the values
         // 2.5 clear indices
+        let width = self.row_aggr_layout.fixed_part_width();
         groups_with_rows
             .iter()
             .zip(offsets.windows(2))
             .try_for_each(|(group_idx, offsets)| {
                 let group_state = &mut self.aggr_state.group_states[*group_idx];
+                let aggregation_buffer = &mut self.aggr_state.aggregation_buffer
+                    [*group_idx * width..(*group_idx + 1) * width];
                 // 2.2
                 // Process row accumulators
                 self.row_accumulators
@@ -434,8 +444,7 @@ impl GroupedHashAggregateStream {
                         )?;
                         let mut state_accessor =
                             RowAccessor::new_from_layout(self.row_aggr_layout.clone());
-                        state_accessor
-                            .point_to(0, group_state.aggregation_buffer.as_mut_slice());
+                        state_accessor.point_to(0, aggregation_buffer);
                         match self.mode {
                             AggregateMode::Partial | AggregateMode::Single => {
                                 accumulator.update_batch(&values, &mut state_accessor)
@@ -481,52 +490,40 @@ impl GroupedHashAggregateStream {
         Ok(())
     }
 
-    // Update the accumulator results, according to row_aggr_state.
-    fn update_accumulators_using_scalar(
+    // Update the row accumulator results of all the groups at once, row by row.
+    fn update_accumulators_using_rows(
         &mut self,
         groups_with_rows: &[usize],
+        num_rows: usize,
         row_values: &[Vec<ArrayRef>],
         row_filter_values: &[Option<ArrayRef>],
     ) -> Result<()> {
-        let filter_bool_array = row_filter_values
-            .iter()
-            .map(|filter_opt| match filter_opt {
-                Some(f) => Ok(Some(as_boolean_array(f)?)),
-                None => Ok(None),
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        for group_idx in groups_with_rows {
-            let group_state = &mut self.aggr_state.group_states[*group_idx];
-            let mut state_accessor =
-                RowAccessor::new_from_layout(self.row_aggr_layout.clone());
-            state_accessor.point_to(0, group_state.aggregation_buffer.as_mut_slice());
-            for idx in &group_state.indices {
-                for (accumulator, values_array, filter_array) in izip!(
-                    self.row_accumulators.iter_mut(),
-                    row_values.iter(),
-                    filter_bool_array.iter()
-                ) {
-                    if values_array.len() == 1 {
-                        let scalar_value =
-                            col_to_scalar(&values_array[0], filter_array, *idx as usize)?;
-                        accumulator.update_scalar(&scalar_value, &mut state_accessor)?;
-                    } else {
-                        let scalar_values = values_array
-                            .iter()
-                            .map(|array| {
-                                col_to_scalar(array, filter_array, *idx as usize)
-                            })
-                            .collect::<Result<Vec<_>>>()?;
-                        accumulator
-                            .update_scalar_values(&scalar_values, &mut state_accessor)?;
-                    }
-                }
+        // the group of each input row
+        let mut groups = vec![0; num_rows];
+        for &group_idx in groups_with_rows {
+            let group_state = &mut self.aggr_state.group_states[group_idx];
+            for &row in &group_state.indices {
+                groups[row as usize] = group_idx;
             }
             // clear the group indices in this group
             group_state.indices.clear();
         }
 
+        for (accumulator, values, filter) in izip!(
+            self.row_accumulators.iter_mut(),
+            row_values.iter(),
+            row_filter_values.iter()
+        ) {
+            let filter = filter.as_ref().map(|f| as_boolean_array(f)).transpose()?;
+            accumulator.update_rows(
+                values,
+                filter,
+                &groups,
+                &mut self.aggr_state.aggregation_buffer,
+                &self.row_aggr_layout,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -555,7 +552,7 @@ impl GroupedHashAggregateStream {
         for group_values in &group_by_values {
             let groups_with_rows =
                 self.update_group_state(group_values, &mut allocated)?;
-            // Decide the accumulators update mode, use scalar value to update the accumulators when all of the conditions are meet:
+            // Decide the accumulators update mode, update the row accumulators of all groups at once, row by row, when all of the conditions are meet:
             // 1) The aggregation mode is Partial or Single
             // 2) There is not normal aggregation expressions
             // 3) The number of affected groups is high (entries in `aggr_state` have rows need to update). Usually the high cardinality case
@@ -564,8 +561,9 @@ impl GroupedHashAggregateStream {
                 && normal_filter_values.is_empty()
                 && groups_with_rows.len() >= batch.num_rows() / self.scalar_update_factor
             {
-                self.update_accumulators_using_scalar(
+                self.update_accumulators_using_rows(
                     &groups_with_rows,
+                    batch.num_rows(),
                     &row_aggr_input_values,
                     &row_filter_values,
                 )?;
@@ -623,6 +621,11 @@ pub(crate) struct AggregationState {
 
     /// State for each group
     pub group_states: Vec<GroupState>,
+
+    /// Row accumulator states of all the groups, stored contiguously: the state
+    /// of the group at index `i` in `group_states` is at `i * width..(i + 1) * width`,
+    /// `width` being the fixed part width of the row accumulators layout
+    pub aggregation_buffer: Vec<u8>,
 }
 
 impl std::fmt::Debug for AggregationState {
@@ -659,26 +662,28 @@ impl GroupedHashAggregateStream {
             return Ok(Some(RecordBatch::new_empty(schema)));
         }
 
-        // Buffers for each distinct group (i.e. row accumulator memories)
-        let mut state_buffers = group_state_chunk
-            .iter()
-            .map(|gs| gs.aggregation_buffer.clone())
-            .collect::<Vec<_>>();
+        // Row accumulator states of the groups in this chunk
+        let width = self.row_aggr_layout.fixed_part_width();
+        let state_buffer =
+            &mut self.aggr_state.aggregation_buffer[skip_items * width..end_idx * width];
 
         let output_fields = self.schema.fields();
         // Store row accumulator results (either final output or intermediate state):
         let row_columns = match self.mode {
-            AggregateMode::Partial => {
-                read_as_batch(&state_buffers, &self.row_aggr_schema)
-            }
+            AggregateMode::Partial => read_as_batch(
+                (0..group_state_chunk.len())
+                    .map(|i| &state_buffer[i * width..(i + 1) * width]),
+                &self.row_aggr_schema,
+            ),
             AggregateMode::Final
             | AggregateMode::FinalPartitioned
             | AggregateMode::Single => {
                 let mut results = vec![];
                 for (idx, acc) in self.row_accumulators.iter().enumerate() {
                     let mut state_accessor = RowAccessor::new(&self.row_aggr_schema);
-                    let current = state_buffers
-                        .iter_mut()
+                    // there is at least one row accumulator, thus `width` is not zero
+                    let current = state_buffer
+                        .chunks_exact_mut(width)
                         .map(|buffer| {
                             state_accessor.point_to(0, buffer);
                             acc.evaluate(&state_accessor)
@@ -39,9 +39,6 @@ pub(crate) struct GroupState {
     /// The actual group by values, stored sequentially
     pub group_by_values: OwnedRow,
 
-    // Accumulator state, stored sequentially
-    pub aggregation_buffer: Vec<u8>,
-
     // Accumulator state, one for each aggregate that doesn't support row accumulation
     pub accumulator_set: Vec<AccumulatorItem>,
 
@@ -66,7 +63,10 @@ pub(crate) fn aggr_state_schema(aggr_expr: &[Arc<dyn AggregateExpr>]) -> SchemaR
     Arc::new(Schema::new(fields))
 }
 
-pub(crate) fn read_as_batch(rows: &[Vec<u8>], schema: &Schema) -> Vec<ArrayRef> {
+pub(crate) fn read_as_batch<'a>(
+    rows: impl ExactSizeIterator<Item = &'a [u8]>,
+    schema: &Schema,
+) -> Vec<ArrayRef> {
     let mut output = MutableRecordBatch::new(rows.len(), Arc::new(schema.clone()));
     let mut row = RowReader::new(schema);
 
@@ -42,6 +42,9 @@ use super::{metrics::ExecutionPlanMetricsSet, Statistics};
 
 /// CoalesceBatchesExec combines small batches into larger batches for more efficient use of
 /// vectorized processing by upstream operators.
+///
+/// The batch size set with [`TaskContext::with_batch_size`], if any, takes precedence
+/// over the target batch size of the plan.
 #[derive(Debug)]
 pub struct CoalesceBatchesExec {
     /// The input plan
@@ -111,6 +114,10 @@ impl ExecutionPlan for CoalesceBatchesExec {
         self.input.equivalence_properties()
     }
 
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.input.unique_keys()
+    }
+
     fn with_new_children(
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -126,10 +133,13 @@ impl ExecutionPlan for CoalesceBatchesExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        let target_batch_size = context
+            .batch_size_override()
+            .unwrap_or(self.target_batch_size);
         Ok(Box::pin(CoalesceBatchesStream {
             input: self.input.execute(partition, context)?,
             schema: self.input.schema(),
-            target_batch_size: self.target_batch_size,
+            target_batch_size,
             buffer: Vec::new(),
             buffered_rows: 0,
             is_closed: false,
@@ -143,7 +153,7 @@ impl ExecutionPlan for CoalesceBatchesExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
                     "CoalesceBatchesExec: target_batch_size={}",
@@ -295,7 +305,9 @@ mod tests {
     use crate::config::ConfigOptions;
     use crate::datasource::MemTable;
     use crate::physical_plan::filter::FilterExec;
-    use crate::physical_plan::{memory::MemoryExec, repartition::RepartitionExec};
+    use crate::physical_plan::{
+        collect, memory::MemoryExec, repartition::RepartitionExec,
+    };
     use crate::prelude::SessionContext;
     use crate::test::create_vec_batches;
     use arrow::datatypes::{DataType, Field, Schema};
@@ -353,6 +365,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_task_context_batch_size() -> Result<()> {
+        let schema = test_schema();
+        let partition = create_vec_batches(&schema, 10);
+        let exec = MemoryExec::try_new(&[partition], schema.clone(), None)?;
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(CoalesceBatchesExec::new(Arc::new(exec), 8192));
+
+        // input is 10 batches x 8 rows (80 rows)
+        let session_ctx = SessionContext::new();
+        let batches = collect(plan.clone(), session_ctx.task_ctx()).await?;
+        let sizes: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(sizes, vec![80]);
+
+        let task_ctx = TaskContext::from(&session_ctx.state()).with_batch_size(30);
+        let batches = collect(plan, Arc::new(task_ctx)).await?;
+        let sizes: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(sizes, vec![32, 32, 16]);
+
+        Ok(())
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
@@ -45,6 +45,33 @@ pub async fn collect(stream: SendableRecordBatchStream) -> Result<Vec<RecordBatc
     stream.try_collect::<Vec<_>>().await
 }
 
+/// Create a vector of record batches holding the first `limit` rows of a stream,
+/// truncating the last batch if needed.
+///
+/// The stream is dropped as soon as `limit` rows have been read, which cancels
+/// the remaining work of the plan producing it.
+pub async fn collect_limited(
+    mut stream: SendableRecordBatchStream,
+    limit: usize,
+) -> Result<Vec<RecordBatch>> {
+    let mut batches = vec![];
+    let mut remaining = limit;
+    while remaining > 0 {
+        let batch = match stream.next().await {
+            Some(batch) => batch?,
+            None => break,
+        };
+        if batch.num_rows() >= remaining {
+            batches.push(batch.slice(0, remaining));
+            break;
+        }
+        remaining -= batch.num_rows();
+        batches.push(batch);
+    }
+    drop(stream);
+    Ok(batches)
+}
+
 /// Recursively builds a list of files in a directory with a given extension
 pub fn build_checked_file_list(dir: &str, ext: &str) -> Result<Vec<String>> {
     let mut filenames: Vec<String> = Vec::new();
@@ -287,11 +314,17 @@ fn get_meet_of_orderings_helper(
 #[cfg(test)]
 mod tests {
     use std::ops::Not;
+    use std::sync::Weak;
 
     use super::*;
+    use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
     use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::repartition::RepartitionExec;
     use crate::physical_plan::sorts::sort::SortExec;
     use crate::physical_plan::union::UnionExec;
+    use crate::physical_plan::Partitioning;
+    use crate::prelude::SessionContext;
+    use crate::test::exec::{assert_strong_count_converges_to_zero, InfiniteExec};
     use arrow::compute::SortOptions;
     use arrow::{
         array::{Float32Array, Float64Array},
@@ -301,6 +334,57 @@ mod tests {
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{col, Column};
 
+    fn infinite_exec() -> Result<InfiniteExec> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Float32, false)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Float32Array::from(vec![1., 2., 3.]))],
+        )?;
+        Ok(InfiniteExec::new(batch))
+    }
+
+    #[tokio::test]
+    async fn test_collect_limited() -> Result<()> {
+        let task_ctx = SessionContext::new().task_ctx();
+        for (limit, num_batches) in [(0, 0), (2, 1), (3, 1), (4, 2), (7, 3), (9, 3)] {
+            let exec = infinite_exec()?;
+            let refs = exec.refs();
+            let batches =
+                collect_limited(exec.execute(0, task_ctx.clone())?, limit).await?;
+
+            let num_rows: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+            assert_eq!(num_rows.len(), num_batches, "limit {limit}");
+            assert_eq!(num_rows.iter().sum::<usize>(), limit);
+            // the last batch is truncated
+            if let Some(last) = num_rows.last() {
+                assert_eq!(*last, limit - 3 * (num_batches - 1));
+            }
+            // no batch is read past the limit and the stream is dropped
+            assert_eq!(exec.batches_produced(), num_batches);
+            assert_eq!(Weak::strong_count(&refs), 1);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_limited_cancels_input() -> Result<()> {
+        let exec = infinite_exec()?;
+        let refs = exec.refs();
+        let repartition =
+            RepartitionExec::try_new(Arc::new(exec), Partitioning::RoundRobinBatch(3))?;
+        let plan = Arc::new(CoalescePartitionsExec::new(Arc::new(repartition)));
+
+        let task_ctx = SessionContext::new().task_ctx();
+        let batches = collect_limited(plan.execute(0, task_ctx)?, 10).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+
+        // the tasks reading the input stop once the output stream is dropped
+        drop(plan);
+        assert_strong_count_converges_to_zero(refs).await;
+        Ok(())
+    }
+
     #[test]
     fn get_meet_of_orderings_helper_common_prefix_test() -> Result<()> {
         let input1: Vec<PhysicalSortExpr> = vec![
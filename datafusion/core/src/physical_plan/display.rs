@@ -30,6 +30,9 @@ use super::{accept, ExecutionPlan, ExecutionPlanVisitor};
 pub enum DisplayFormatType {
     /// Default, compact format. Example: `FilterExec: c12 < 10.0`
     Default,
+    /// Verbose format, which may add details on separate lines, indented
+    /// below the first line of the node, used by `EXPLAIN VERBOSE`
+    Verbose,
 }
 
 /// Wraps an `ExecutionPlan` with various ways to display this plan
@@ -37,6 +40,8 @@ pub struct DisplayableExecutionPlan<'a> {
     inner: &'a dyn ExecutionPlan,
     /// How to show metrics
     show_metrics: ShowMetrics,
+    /// How each node should format itself
+    format_type: DisplayFormatType,
 }
 
 impl<'a> DisplayableExecutionPlan<'a> {
@@ -46,6 +51,7 @@ impl<'a> DisplayableExecutionPlan<'a> {
         Self {
             inner,
             show_metrics: ShowMetrics::None,
+            format_type: DisplayFormatType::Default,
         }
     }
 
@@ -56,6 +62,7 @@ impl<'a> DisplayableExecutionPlan<'a> {
         Self {
             inner,
             show_metrics: ShowMetrics::Aggregated,
+            format_type: DisplayFormatType::Default,
         }
     }
 
@@ -66,9 +73,17 @@ impl<'a> DisplayableExecutionPlan<'a> {
         Self {
             inner,
             show_metrics: ShowMetrics::Full,
+            format_type: DisplayFormatType::Default,
         }
     }
 
+    /// Sets how each node of the plan formats itself, the
+    /// [`DisplayFormatType::Default`] format if not set
+    pub fn with_format_type(mut self, format_type: DisplayFormatType) -> Self {
+        self.format_type = format_type;
+        self
+    }
+
     /// Return a `format`able structure that produces a single line
     /// per node.
     ///
@@ -83,15 +98,16 @@ impl<'a> DisplayableExecutionPlan<'a> {
         struct Wrapper<'a> {
             plan: &'a dyn ExecutionPlan,
             show_metrics: ShowMetrics,
+            format_type: DisplayFormatType,
         }
         impl<'a> fmt::Display for Wrapper<'a> {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let t = DisplayFormatType::Default;
                 let mut visitor = IndentVisitor {
-                    t,
+                    t: self.format_type,
                     f,
                     indent: 0,
                     show_metrics: self.show_metrics,
+                    show_summary: true,
                 };
                 accept(self.plan, &mut visitor)
             }
@@ -99,6 +115,7 @@ impl<'a> DisplayableExecutionPlan<'a> {
         Wrapper {
             plan: self.inner,
             show_metrics: self.show_metrics,
+            format_type: self.format_type,
         }
     }
 
@@ -108,15 +125,17 @@ impl<'a> DisplayableExecutionPlan<'a> {
         struct Wrapper<'a> {
             plan: &'a dyn ExecutionPlan,
             show_metrics: ShowMetrics,
+            format_type: DisplayFormatType,
         }
 
         impl<'a> fmt::Display for Wrapper<'a> {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
                 let mut visitor = IndentVisitor {
                     f,
-                    t: DisplayFormatType::Default,
+                    t: self.format_type,
                     indent: 0,
                     show_metrics: self.show_metrics,
+                    show_summary: false,
                 };
                 visitor.pre_visit(self.plan)?;
                 Ok(())
@@ -126,6 +145,7 @@ impl<'a> DisplayableExecutionPlan<'a> {
         Wrapper {
             plan: self.inner,
             show_metrics: self.show_metrics,
+            format_type: self.format_type,
         }
     }
 }
@@ -152,13 +172,25 @@ struct IndentVisitor<'a, 'b> {
     indent: usize,
     /// How to show metrics
     show_metrics: ShowMetrics,
+    /// Whether to show the summary of the execution of each node on a line
+    /// under it, along with its metrics
+    show_summary: bool,
 }
 
 impl<'a, 'b> ExecutionPlanVisitor for IndentVisitor<'a, 'b> {
     type Error = fmt::Error;
     fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
         write!(self.f, "{:indent$}", "", indent = self.indent * 2)?;
-        plan.fmt_as(self.t, self.f)?;
+        match self.t {
+            DisplayFormatType::Default => plan.fmt_as(self.t, self.f)?,
+            DisplayFormatType::Verbose => {
+                // the lines after the first line of the node are indented
+                // like the node itself
+                let node = format!("{}", FormatAs(plan, self.t));
+                let newline = format!("\n{:indent$}", "", indent = self.indent * 2);
+                write!(self.f, "{}", node.replace('\n', &newline))?;
+            }
+        }
         match self.show_metrics {
             ShowMetrics::None => {}
             ShowMetrics::Aggregated => {
@@ -182,6 +214,12 @@ impl<'a, 'b> ExecutionPlanVisitor for IndentVisitor<'a, 'b> {
             }
         }
         writeln!(self.f)?;
+        if self.show_summary && !matches!(self.show_metrics, ShowMetrics::None) {
+            if let Some(summary) = plan.metrics_summary() {
+                let indent = self.indent * 2 + 2;
+                writeln!(self.f, "{:indent$}summary=[{summary}]", "")?;
+            }
+        }
         self.indent += 1;
         Ok(true)
     }
@@ -192,6 +230,26 @@ impl<'a, 'b> ExecutionPlanVisitor for IndentVisitor<'a, 'b> {
     }
 }
 
+/// Formats a node of a plan with [`ExecutionPlan::fmt_as`]
+struct FormatAs<'a>(&'a dyn ExecutionPlan, DisplayFormatType);
+
+impl<'a> fmt::Display for FormatAs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_as(self.1, f)
+    }
+}
+
+/// Returns the name of the operator, e.g. `HashJoinExec`, as printed by its
+/// one line display
+pub(crate) fn operator_name(plan: &dyn ExecutionPlan) -> String {
+    let line = DisplayableExecutionPlan::new(plan).one_line().to_string();
+    line.split(':')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
 impl<'a> ToStringifiedPlan for DisplayableExecutionPlan<'a> {
     fn to_stringified(
         &self,
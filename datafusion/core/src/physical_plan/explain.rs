@@ -162,7 +162,7 @@ impl ExecutionPlan for ExplainExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "ExplainExec")
             }
         }
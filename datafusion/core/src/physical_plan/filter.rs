@@ -132,6 +132,10 @@ impl ExecutionPlan for FilterExec {
         input_properties
     }
 
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.input.unique_keys()
+    }
+
     fn with_new_children(
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -163,7 +167,7 @@ impl ExecutionPlan for FilterExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "FilterExec: {}", self.predicate)
             }
         }
@@ -185,7 +185,7 @@ impl ExecutionPlan for InsertExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "InsertExec: sink={}", self.sink)
             }
         }
@@ -0,0 +1,435 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the [`InstrumentedExec`] wrapper, which records a `tracing` span for
+//! every (operator, partition) pair that is executed.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::Result;
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::equivalence::OrderingEquivalenceProperties;
+use datafusion_physical_expr::PhysicalSortRequirement;
+use futures::{Stream, StreamExt};
+use tracing::field::Empty;
+use tracing::Span;
+
+use super::display::operator_name;
+use super::expressions::PhysicalSortExpr;
+use super::metrics::MetricsSet;
+use super::{
+    DisplayFormatType, Distribution, EquivalenceProperties, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+
+/// Wraps an [`ExecutionPlan`] so that each call to `execute` opens a span named
+/// `execute`, carrying the operator name and partition. The span is entered
+/// while the wrapped operator creates and polls its stream, so the spans of its
+/// children (and of any phases the operator instruments itself, such as the
+/// hash join build) are nested below it. Once the stream is exhausted the
+/// `output_rows` and `elapsed_compute` fields are recorded on the span.
+///
+/// The wrapper is transparent for planning purposes: it reports the children,
+/// properties and display of the wrapped operator. It is normally inserted by
+/// the [`InstrumentExecution`] physical optimizer rule.
+///
+/// [`InstrumentExecution`]: crate::physical_optimizer::instrument::InstrumentExecution
+#[derive(Debug)]
+pub struct InstrumentedExec {
+    /// The wrapped operator
+    inner: Arc<dyn ExecutionPlan>,
+}
+
+impl InstrumentedExec {
+    /// Create a new [`InstrumentedExec`] wrapping `inner`
+    pub fn new(inner: Arc<dyn ExecutionPlan>) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped operator
+    pub fn inner(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.inner
+    }
+}
+
+impl ExecutionPlan for InstrumentedExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        self.inner.unbounded_output(children)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.inner.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.inner.required_input_distribution()
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        self.inner.required_input_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.inner.maintains_input_order()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        self.inner.benefits_from_input_partitioning()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.inner.equivalence_properties()
+    }
+
+    fn ordering_equivalence_properties(&self) -> OrderingEquivalenceProperties {
+        self.inner.ordering_equivalence_properties()
+    }
+
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.inner.unique_keys()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inner.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(InstrumentedExec::new(
+            self.inner.clone().with_new_children(children)?,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let span = tracing::info_span!(
+            "execute",
+            operator = %operator_name(self.inner.as_ref()),
+            partition,
+            output_rows = Empty,
+            elapsed_compute = Empty,
+        );
+        let input = {
+            let _entered = span.enter();
+            self.inner.execute(partition, context)?
+        };
+
+        Ok(Box::pin(InstrumentedStream {
+            input,
+            span,
+            output_rows: 0,
+            elapsed_compute: Duration::ZERO,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.inner.metrics()
+    }
+
+    fn metrics_summary(&self) -> Option<String> {
+        self.inner.metrics_summary()
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        self.inner.fmt_as(t, f)
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.inner.statistics()
+    }
+}
+
+/// Stream returned by [`InstrumentedExec::execute`], polling the wrapped stream
+/// inside the operator span
+struct InstrumentedStream {
+    /// The wrapped stream
+    input: SendableRecordBatchStream,
+    /// Span of this (operator, partition) pair
+    span: Span,
+    /// Number of rows produced so far
+    output_rows: usize,
+    /// Time spent polling the wrapped stream so far
+    elapsed_compute: Duration,
+}
+
+impl Stream for InstrumentedStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let _entered = this.span.enter();
+
+        let start = Instant::now();
+        let poll = this.input.poll_next_unpin(cx);
+        this.elapsed_compute += start.elapsed();
+
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => this.output_rows += batch.num_rows(),
+            Poll::Ready(None) => {
+                this.span.record("output_rows", this.output_rows as u64);
+                this.span
+                    .record("elapsed_compute", this.elapsed_compute.as_nanos() as u64);
+            }
+            _ => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for InstrumentedStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    use parking_lot::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::*;
+    use crate::config::ConfigOptions;
+    use crate::physical_optimizer::instrument::InstrumentExecution;
+    use crate::physical_optimizer::PhysicalOptimizerRule;
+    use crate::physical_plan::expressions::Column;
+    use crate::physical_plan::joins::{HashJoinExec, PartitionMode};
+    use crate::physical_plan::{common, memory::MemoryExec};
+    use crate::prelude::SessionContext;
+    use crate::test::build_table_i32;
+    use datafusion_common::JoinType;
+
+    /// Label of a span: the operator name for operator spans, the span name otherwise
+    struct Label(String);
+
+    #[derive(Default)]
+    struct LabelVisitor {
+        operator: Option<String>,
+        output_rows: Option<String>,
+        rows: Option<String>,
+    }
+
+    impl Visit for LabelVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            match field.name() {
+                "operator" => self.operator = Some(format!("{value:?}")),
+                "output_rows" => self.output_rows = Some(format!("{value:?}")),
+                "rows" => self.rows = Some(format!("{value:?}")),
+                _ => {}
+            }
+        }
+    }
+
+    /// Collects `(label, parent label)` for every span, and the recorded
+    /// `output_rows` and `rows`
+    #[derive(Clone, Default)]
+    struct SpanCollector {
+        spans: Arc<Mutex<Vec<(String, Option<String>)>>>,
+        output_rows: Arc<Mutex<HashMap<String, String>>>,
+        rows: Arc<Mutex<HashMap<String, String>>>,
+    }
+
+    impl<S> Layer<S> for SpanCollector
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut visitor = LabelVisitor::default();
+            attrs.record(&mut visitor);
+            let span = ctx.span(id).unwrap();
+            let label = visitor.operator.unwrap_or_else(|| span.name().to_string());
+            let parent = span
+                .parent()
+                .and_then(|p| p.extensions().get::<Label>().map(|l| l.0.clone()));
+            span.extensions_mut().insert(Label(label.clone()));
+            self.spans.lock().push((label, parent));
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            let mut visitor = LabelVisitor::default();
+            values.record(&mut visitor);
+            let span = ctx.span(id).unwrap();
+            let label = span.extensions().get::<Label>().unwrap().0.clone();
+            if let Some(output_rows) = visitor.output_rows {
+                self.output_rows.lock().insert(label.clone(), output_rows);
+            }
+            if let Some(rows) = visitor.rows {
+                self.rows.lock().insert(label, rows);
+            }
+        }
+    }
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn hash_join_span_hierarchy() -> Result<()> {
+        let collector = SpanCollector::default();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )?);
+
+        let plan = InstrumentExecution::new().optimize(join, &ConfigOptions::new())?;
+        assert!(plan.as_any().downcast_ref::<InstrumentedExec>().is_some());
+
+        let session_ctx = SessionContext::new();
+        let stream = plan.execute(0, session_ctx.task_ctx())?;
+        let batches = common::collect(stream).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+        let mut spans = collector.spans.lock().clone();
+        spans.sort();
+        let expected = vec![
+            ("HashJoinExec".to_string(), None),
+            ("MemoryExec".to_string(), Some("HashJoinExec".to_string())),
+            (
+                "MemoryExec".to_string(),
+                Some("hash_join_build".to_string()),
+            ),
+            (
+                "hash_join_build".to_string(),
+                Some("HashJoinExec".to_string()),
+            ),
+            (
+                "hash_join_probe".to_string(),
+                Some("HashJoinExec".to_string()),
+            ),
+        ];
+        assert_eq!(spans, expected);
+
+        let output_rows = collector.output_rows.lock();
+        assert_eq!(output_rows.get("HashJoinExec"), Some(&"3".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hash_join_build_span_records_rows() -> Result<()> {
+        let collector = SpanCollector::default();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 6]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20]),
+            ("b1", &vec![4, 5]),
+            ("c2", &vec![70, 80]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )?;
+
+        let session_ctx = SessionContext::new();
+        let stream = join.execute(0, session_ctx.task_ctx())?;
+        common::collect(stream).await?;
+
+        // the build side span records its rows once built, while the probe
+        // span of each probe batch has them from the start
+        let rows = collector.rows.lock();
+        assert_eq!(rows.get("hash_join_build"), Some(&"4".to_string()));
+        let mut spans = collector.spans.lock().clone();
+        spans.sort();
+        let expected = vec![
+            ("hash_join_build".to_string(), None),
+            ("hash_join_probe".to_string(), None),
+        ];
+        assert_eq!(spans, expected);
+
+        Ok(())
+    }
+}
@@ -19,22 +19,30 @@
 //! into a set of partitions.
 
 use ahash::RandomState;
-use arrow::array::Array;
+use arrow::array::{downcast_array, new_null_array, Array};
 use arrow::array::{
     Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
     StringArray, TimestampNanosecondArray, UInt16Array, UInt32Array, UInt64Array,
     UInt8Array,
 };
-use arrow::datatypes::{ArrowNativeType, DataType};
+use arrow::compute;
+use arrow::datatypes::{
+    ArrowNativeType, ArrowPrimitiveType, DataType, Field, DECIMAL128_MAX_PRECISION,
+    DECIMAL256_MAX_PRECISION,
+};
 use arrow::datatypes::{Schema, SchemaRef};
+use arrow::ipc::{writer::IpcWriteOptions, CompressionType};
 use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, Rows, SortField};
 use arrow::{
     array::{
-        ArrayData, ArrayRef, BooleanArray, Date32Array, Date64Array, Decimal128Array,
-        DictionaryArray, FixedSizeBinaryArray, LargeStringArray, PrimitiveArray,
-        Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray,
+        ArrayData, ArrayRef, BinaryArray, BooleanArray, BufferBuilder, Date32Array,
+        Date64Array, Decimal128Array, Decimal256Array, DictionaryArray,
+        FixedSizeBinaryArray, IntervalDayTimeArray, IntervalMonthDayNanoArray,
+        IntervalYearMonthArray, LargeBinaryArray, LargeStringArray, PrimitiveArray,
+        StructArray, Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray,
         Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
-        TimestampSecondArray, UInt32BufferBuilder, UInt64BufferBuilder,
+        TimestampSecondArray, UInt64BufferBuilder,
     },
     datatypes::{
         Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt64Type,
@@ -42,39 +50,64 @@ use arrow::{
     },
     util::bit_util,
 };
-use futures::{ready, Stream, StreamExt, TryStreamExt};
-use hashbrown::raw::RawTable;
-use smallvec::smallvec;
+use futures::future::BoxFuture;
+use futures::{ready, FutureExt, Stream, StreamExt, TryStreamExt};
+use log::info;
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::Poll;
 use std::{any::Any, usize, vec};
+use tempfile::NamedTempFile;
+use tokio::sync::Semaphore;
 
 use datafusion_common::cast::{as_dictionary_array, as_string_array};
+use datafusion_execution::disk_manager::DiskManager;
 use datafusion_execution::memory_pool::MemoryReservation;
 
 use crate::physical_plan::joins::utils::{
-    adjust_indices_by_join_type, apply_join_filter_to_indices, build_batch_from_indices,
-    get_final_indices_from_bit_map, need_produce_result_in_final, JoinSide,
+    adjust_indices_by_join_type, apply_join_filter_to_indices, batch_offsets,
+    build_batch_from_indices, get_anti_indices, locate_row, merge_right_indices,
+    need_produce_result_in_final, take_from_arrays, take_probe_column, JoinSide,
 };
 use crate::physical_plan::{
-    coalesce_batches::concat_batches,
+    aggregates::{AggregateExec, AggregateMode},
     coalesce_partitions::CoalescePartitionsExec,
+    common::{AbortOnDropSingle, IPCWriter, SharedMemoryReservation},
+    expressions::CastExpr,
     expressions::Column,
     expressions::PhysicalSortExpr,
+    filter::FilterExec,
     hash_utils::create_hashes,
     joins::utils::{
-        adjust_right_output_partitioning, build_join_schema, check_join_is_valid,
-        combine_join_equivalence_properties, estimate_join_statistics,
-        partitioned_join_output_partitioning, BuildProbeJoinMetrics, ColumnIndex,
-        JoinFilter, JoinOn,
+        add_join_filter_equivalence_properties, adjust_right_output_partitioning,
+        build_join_schema, check_join_is_valid, combine_join_equivalence_properties,
+        display_join_on, estimate_join_statistics, join_keys_unique,
+        partitioned_join_output_partitioning, supports_collect_by_thresholds,
+        swap_join_filter, swap_join_type, swap_reverting_projection,
+        BuildProbeJoinMetrics, ColumnIndex, JoinFilter, JoinOn, JoinOnRef,
+        SplitJoinFilter, DEFAULT_JOIN_FILTER_SELECTIVITY,
+    },
+    memory::MemoryStream,
+    metrics::{
+        self, BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+    },
+    projection::ProjectionExec,
+    repartition::RepartitionExec,
+    sorts::sort::read_spill_as_stream,
+    stream::{
+        validate_batch_schemas, RecordBatchReceiverStream, RecordBatchStreamAdapter,
     },
-    metrics::{ExecutionPlanMetricsSet, MetricsSet},
     DisplayFormatType, Distribution, EquivalenceProperties, ExecutionPlan, Partitioning,
     PhysicalExpr, RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
 use arrow::array::BooleanBufferBuilder;
-use arrow::datatypes::TimeUnit;
+use arrow::datatypes::{IntervalUnit, TimeUnit};
+use datafusion_common::tree_node::{Transformed, TreeNode};
 use datafusion_common::JoinType;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_execution::{memory_pool::MemoryConsumer, TaskContext};
@@ -83,9 +116,162 @@ use super::{
     utils::{OnceAsync, OnceFut},
     PartitionMode,
 };
-use crate::physical_plan::joins::hash_join_utils::JoinHashMap;
+use crate::physical_plan::joins::hash_join_utils::{
+    BloomFilter, BuildSideRows, JoinHashMap, JoinHashMapType,
+};
+
+/// Build side data of a [`HashJoinExec`], shared by the probe streams using it
+struct JoinLeftData {
+    /// Hash table of the join keys
+    hash_map: JoinHashMap,
+    /// All build side batches, as they were collected. A build side row is
+    /// addressed by its position in the concatenation of the batches, which
+    /// are not concatenated to avoid copying the whole build side
+    batches: Vec<RecordBatch>,
+    /// Total number of rows of `batches`
+    num_rows: usize,
+    /// Join keys of every batch of `batches`, cast to their common type. They
+    /// are evaluated once rather than for every probe side batch
+    join_values: Vec<Vec<ArrayRef>>,
+    /// `join_values` in the row format, for the join keys compared as rows,
+    /// see [`compare_join_keys_as_rows`]
+    join_rows: Option<Vec<Rows>>,
+    /// Bloom filter over the hash values of `join_values`, if enabled with
+    /// `datafusion.execution.hash_join_bloom_filter_max_size`
+    bloom_filter: Option<BloomFilter>,
+    /// Optional row-format copy of `batches`, used to materialize output rows
+    rows: Option<BuildSideRows>,
+    /// Build side rows matched by any of the probe streams, for the join types
+    /// producing build side rows once probing is done
+    visited_indices_bitmap: Mutex<BooleanBufferBuilder>,
+    /// Number of probe streams which have not finished probing yet
+    probe_threads_counter: AtomicUsize,
+    /// Whether any probe stream had a probe side row, for null-aware anti joins
+    probe_side_non_empty: AtomicBool,
+    /// Whether any probe stream had a null probe side join key, for null-aware
+    /// anti joins
+    probe_side_null_key: AtomicBool,
+    /// Memory reserved for the build side data, released once it is dropped
+    #[allow(dead_code)]
+    reservation: SharedMemoryReservation,
+    /// Size and shape of the hash table
+    info: BuildSideInfo,
+}
+
+impl JoinLeftData {
+    /// Returns true if any of the join keys `on` of the build side has a null
+    fn has_null_join_key(&self, on: &[Arc<dyn PhysicalExpr>]) -> Result<bool> {
+        for batch in &self.batches {
+            if has_null_join_key(on, batch)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Records that a probe stream finished probing, returning true if it was
+    /// the last one, which then produces the build side rows of the bitmap and
+    /// releases the build side
+    fn report_probe_completed(&self) -> bool {
+        self.probe_threads_counter.fetch_sub(1, Ordering::AcqRel) == 1
+    }
+}
+
+/// Formats the fields of `schema` as `name:type`, for the verbose format of a
+/// [`HashJoinExec`]
+fn display_schema(schema: &Schema) -> String {
+    schema
+        .fields()
+        .iter()
+        .map(|field| format!("{}:{:?}", field.name(), field.data_type()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Runtime information about the build side of a [`HashJoinExec`], once it has
+/// been built, see [`HashJoinExec::build_side_info`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildSideInfo {
+    /// Number of build side rows
+    pub num_rows: usize,
+    /// Memory used by the hash table in bytes
+    pub hash_table_size: usize,
+    /// Number of distinct hash values of the build side join keys
+    pub num_buckets: usize,
+    /// Largest number of build side rows with the same hash value
+    pub longest_chain: usize,
+    /// Number of slots allocated by the hash table
+    pub capacity: usize,
+}
+
+impl BuildSideInfo {
+    /// Adds the information of another build side of the same join, built by
+    /// another partition
+    fn merge(&mut self, other: &BuildSideInfo) {
+        self.num_rows += other.num_rows;
+        self.hash_table_size += other.hash_table_size;
+        self.num_buckets += other.num_buckets;
+        self.longest_chain = self.longest_chain.max(other.longest_chain);
+        self.capacity += other.capacity;
+    }
+
+    /// Ratio of the number of distinct hash values to the number of slots of
+    /// the hash table
+    pub fn load_factor(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.num_buckets as f64 / self.capacity as f64
+    }
+
+    /// True if the build side has no row, in which case a join whose output
+    /// only consists of matched rows or build side rows produces nothing, and
+    /// a join in [`PartitionMode::CollectLeft`] mode skips its probe side
+    pub fn is_empty(&self) -> bool {
+        self.num_rows == 0
+    }
+
+    /// Average number of build side rows with the same hash value
+    pub fn avg_chain_length(&self) -> f64 {
+        if self.num_buckets == 0 {
+            return 0.0;
+        }
+        self.num_rows as f64 / self.num_buckets as f64
+    }
+
+    /// Records the information of a build side of the partition `partition` in
+    /// `metrics`, and merges it into the information of the partition shared
+    /// with the join, which a partition spilling its inputs builds several times
+    fn record(
+        &self,
+        partition: usize,
+        shared: &Mutex<HashMap<usize, BuildSideInfo>>,
+        metrics: &BuildProbeJoinMetrics,
+    ) {
+        metrics.build_hash_buckets.add(self.num_buckets);
+        metrics.build_hash_table_size.add(self.hash_table_size);
+        metrics.build_longest_chain.set_max(self.longest_chain);
+        shared
+            .lock()
+            .entry(partition)
+            .and_modify(|info| info.merge(self))
+            .or_insert(*self);
+    }
+}
 
-type JoinLeftData = (JoinHashMap, RecordBatch, MemoryReservation);
+impl fmt::Display for BuildSideInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "build_rows={}, hash_table_capacity={}, load_factor={:.2}, avg_chain_length={:.2}, longest_chain={}",
+            self.num_rows,
+            self.capacity,
+            self.load_factor(),
+            self.avg_chain_length(),
+            self.longest_chain,
+        )
+    }
+}
 
 /// Join execution plan executes partitions in parallel and combines them into a set of
 /// partitions.
@@ -93,38 +279,111 @@ type JoinLeftData = (JoinHashMap, RecordBatch, MemoryReservation);
 /// Filter expression expected to contain non-equality predicates that can not be pushed
 /// down to any of join inputs.
 /// In case of outer join, filter applied to only matched rows.
+///
+/// In [`PartitionMode::Partitioned`], a partition whose build side does not fit
+/// in memory spills both of its inputs to disk, and joins them one hash
+/// partition at a time (grace hash join). In [`PartitionMode::CollectLeft`], the
+/// join falls back to the partitioned join if its build side turns out to have
+/// more rows than the `hash_join_collect_left_max_rows` option.
+///
+/// In [`PartitionMode::CollectLeft`], the `Inner`, `Right`, `RightSemi` and
+/// `RightAnti` joins produce the probe side rows in the order they arrive, and
+/// the matches of a probe row in build side order, so that their output keeps
+/// the ordering of the probe side. Such a join does not fall back to the
+/// partitioned join if its probe side is ordered.
+///
+/// In [`PartitionMode::CollectLeft`], the `Inner`, `Left`, `LeftSemi`, `LeftAnti`
+/// and `RightSemi` joins only execute their probe side once the build side is
+/// collected, and produce no output without executing it if the build side is
+/// empty, see [`BuildSideInfo::is_empty`].
 #[derive(Debug)]
 pub struct HashJoinExec {
     /// left (build) side which gets hashed
     pub(crate) left: Arc<dyn ExecutionPlan>,
     /// right (probe) side which are filtered by the hash table
     pub(crate) right: Arc<dyn ExecutionPlan>,
-    /// Set of common columns used to join on
-    pub(crate) on: Vec<(Column, Column)>,
+    /// Pairs of left and right join key expressions used to join on
+    pub(crate) on: JoinOn,
     /// Filters which are applied while finding matching rows
     pub(crate) filter: Option<JoinFilter>,
     /// How the join is performed
     pub(crate) join_type: JoinType,
     /// The schema once the join is applied
     schema: SchemaRef,
-    /// Build-side data
-    left_fut: OnceAsync<JoinLeftData>,
+    /// Build-side data, or `None` if the build side turned out to be too large
+    /// to be collected in [`PartitionMode::CollectLeft`]. Reset by the last
+    /// probe stream once it is done, to release the build side memory
+    left_fut: Arc<OnceAsync<Option<JoinLeftData>>>,
     /// Shares the `RandomState` for the hashing algorithm
     random_state: RandomState,
     /// Partitioning mode to use
     pub(crate) mode: PartitionMode,
+    /// The partitioning mode the join runs in, which is `mode` unless it is
+    /// [`PartitionMode::Auto`], see [`HashJoinExec::resolved_partition_mode`]
+    resolved_mode: PartitionMode,
+    /// Maximum estimated size of the build side a join in [`PartitionMode::Auto`]
+    /// collects, see [`HashJoinExec::with_single_partition_threshold`]
+    single_partition_threshold: usize,
+    /// Maximum estimated number of rows of the build side a join in
+    /// [`PartitionMode::Auto`] collects if its size is unknown, see
+    /// [`HashJoinExec::with_single_partition_threshold_rows`]
+    single_partition_threshold_rows: usize,
+    /// Fraction of the rows matching the join keys assumed to be kept by the
+    /// conjuncts of `filter` the statistics tell nothing about, see
+    /// [`HashJoinExec::with_default_filter_selectivity`]
+    default_filter_selectivity: f64,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
     /// Information of index and left / right placement of columns
     column_indices: Vec<ColumnIndex>,
-    /// If null_equals_null is true, null == null else null != null
-    pub(crate) null_equals_null: bool,
+    /// For every join key in `on`, if null_equals_null is true, null == null
+    /// else null != null
+    pub(crate) null_equals_null: Vec<bool>,
+    /// Common types the join keys of both sides are cast to before being hashed
+    /// and compared, for the join keys of different but comparable types
+    key_types: Vec<Option<DataType>>,
+    /// If true, the anti join has the semantics of `NOT IN` rather than of
+    /// `NOT EXISTS`, see [`HashJoinExec::with_null_aware`]
+    pub(crate) null_aware: bool,
+    /// If true, the join keys of the build side are known to be unique, so
+    /// that a probe row matches at most one build row, see [`unique_build_keys`]
+    unique_build_keys: bool,
+    /// `filter` with the conjuncts on the columns of a single side split off,
+    /// to filter the build side rows before they are hashed and the probe side
+    /// rows before they are probed, see [`JoinFilter::split_side_predicates`]
+    split_filter: Option<SplitJoinFilter>,
+    /// Both sides repartitioned by the hash of their join keys, shared by the
+    /// partitions of an execution of a join in [`PartitionMode::CollectLeft`]
+    /// which fall back to [`PartitionMode::Partitioned`]
+    fallback_inputs: Mutex<Option<FallbackInputs>>,
+    /// Information about the build sides built by the partitions of the join
+    /// by partition, reset when a partition starts executing again, see
+    /// [`HashJoinExec::build_side_info`]
+    build_side_info: Arc<Mutex<HashMap<usize, BuildSideInfo>>>,
+    /// The ordering of the probe side, kept by the output of the join, see
+    /// [`probe_side_ordering`]
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+}
+
+/// The inputs of an execution of a [`HashJoinExec`] in CollectLeft mode
+/// repartitioned by the hash of their join keys, see
+/// [`HashJoinExec::fallback_inputs`]
+#[derive(Debug)]
+struct FallbackInputs {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    /// Number of partitions of the execution which did not get the inputs yet
+    remaining_partitions: usize,
 }
 
 impl HashJoinExec {
     /// Tries to create a new [HashJoinExec].
+    ///
+    /// Join keys of different types are cast to a common type before being
+    /// compared, see [`common_join_key_type`] for the supported pairs.
     /// # Error
-    /// This function errors when it is not possible to join the left and right sides on keys `on`.
+    /// This function errors when it is not possible to join the left and right sides on keys `on`,
+    /// including when a pair of join keys have types that can not be compared.
     pub fn try_new(
         left: Arc<dyn ExecutionPlan>,
         right: Arc<dyn ExecutionPlan>,
@@ -149,6 +408,33 @@ impl HashJoinExec {
 
         let random_state = RandomState::with_seeds(0, 0, 0, 0);
 
+        let key_types = on
+            .iter()
+            .map(|(l, r)| {
+                let left_type = l.data_type(&left_schema)?;
+                let right_type = r.data_type(&right_schema)?;
+                if join_key_types_equal(&left_type, &right_type) {
+                    return Ok(None);
+                }
+                match common_join_key_type(&left_type, &right_type) {
+                    Some(key_type) => Ok(Some(key_type)),
+                    None => Err(DataFusionError::Plan(format!(
+                        "Join key {l} of type {left_type} can not be compared with join key {r} of type {right_type}"
+                    ))),
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        let null_equals_null = vec![null_equals_null; on.len()];
+        let unique_build_keys = unique_build_keys(&left, &on, partition_mode);
+        let resolved_mode = resolve_partition_mode(partition_mode, &left, 0, 0);
+        let output_ordering =
+            probe_side_ordering(&right, *join_type, resolved_mode, &column_indices);
+        let split_filter = filter
+            .as_ref()
+            .map(|filter| filter.split_side_predicates(join_type))
+            .transpose()?;
+
         Ok(HashJoinExec {
             left,
             right,
@@ -159,12 +445,207 @@ impl HashJoinExec {
             left_fut: Default::default(),
             random_state,
             mode: partition_mode,
+            resolved_mode,
+            single_partition_threshold: 0,
+            single_partition_threshold_rows: 0,
+            default_filter_selectivity: DEFAULT_JOIN_FILTER_SELECTIVITY,
             metrics: ExecutionPlanMetricsSet::new(),
             column_indices,
             null_equals_null,
+            key_types,
+            null_aware: false,
+            unique_build_keys,
+            split_filter,
+            fallback_inputs: Default::default(),
+            build_side_info: Default::default(),
+            output_ordering,
         })
     }
 
+    /// Sets whether null == null separately for every join key in `on`, for
+    /// conditions like `a = b AND c IS NOT DISTINCT FROM d` which compare some
+    /// of the keys with null-safe equality.
+    /// # Error
+    /// This function errors if `null_equals_null` does not have one entry for
+    /// every join key, or if the join is null-aware and any of them is set.
+    pub fn with_null_equals_null_per_key(
+        mut self,
+        null_equals_null: Vec<bool>,
+    ) -> Result<Self> {
+        if null_equals_null.len() != self.on.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Expected null_equals_null for {} join keys, got {}",
+                self.on.len(),
+                null_equals_null.len()
+            )));
+        }
+        if self.null_aware && null_equals_null.contains(&true) {
+            return Err(DataFusionError::Plan(
+                "Null-aware anti join is only supported with null_equals_null unset"
+                    .to_string(),
+            ));
+        }
+        self.null_equals_null = null_equals_null;
+        Ok(self)
+    }
+
+    /// Makes the anti join null-aware, giving it the semantics of `x NOT IN
+    /// (subquery)` rather than of `NOT EXISTS`: if the join key of the
+    /// subquery side has any null, no row is produced, and if the subquery side
+    /// is not empty, the rows with a null join key are not produced either.
+    ///
+    /// The subquery side is the right side of a `LeftAnti` join and the left
+    /// side of a `RightAnti` join.
+    /// # Error
+    /// This function errors if the join is not an anti join on a single key
+    /// without a filter in [`PartitionMode::CollectLeft`], or if
+    /// `null_equals_null` is set.
+    pub fn with_null_aware(mut self, null_aware: bool) -> Result<Self> {
+        if null_aware {
+            if !matches!(self.join_type, JoinType::LeftAnti | JoinType::RightAnti) {
+                return Err(DataFusionError::Plan(format!(
+                    "Null-aware join is only supported for anti joins, not {}",
+                    self.join_type
+                )));
+            }
+            if self.on.len() != 1
+                || self.filter.is_some()
+                || self.null_equals_null.contains(&true)
+            {
+                return Err(DataFusionError::Plan(
+                    "Null-aware anti join is only supported on a single join key, \
+                     without a join filter and with null_equals_null unset"
+                        .to_string(),
+                ));
+            }
+            if self.mode != PartitionMode::CollectLeft {
+                return Err(DataFusionError::Plan(format!(
+                    "Null-aware anti join is only supported in CollectLeft mode, not {:?}",
+                    self.mode
+                )));
+            }
+        }
+        self.null_aware = null_aware;
+        Ok(self)
+    }
+
+    /// Sets the maximum estimated size in bytes of the build side a join in
+    /// [`PartitionMode::Auto`] collects into a single partition. Unless an
+    /// optimizer rule such as `JoinSelection` decides its mode, such a join
+    /// runs in [`PartitionMode::CollectLeft`] mode if its build side is smaller,
+    /// and in [`PartitionMode::Partitioned`] mode otherwise, including when the
+    /// statistics of the build side are unknown or no threshold is set.
+    pub fn with_single_partition_threshold(mut self, threshold: usize) -> Self {
+        self.single_partition_threshold = threshold;
+        self.resolve_mode();
+        self
+    }
+
+    /// Sets the maximum estimated number of rows of the build side a join in
+    /// [`PartitionMode::Auto`] collects into a single partition when the size
+    /// in bytes of the build side is unknown, see
+    /// [`Self::with_single_partition_threshold`]
+    pub fn with_single_partition_threshold_rows(mut self, threshold: usize) -> Self {
+        self.single_partition_threshold_rows = threshold;
+        self.resolve_mode();
+        self
+    }
+
+    /// Resolves [`PartitionMode::Auto`] for the current thresholds, and the
+    /// output ordering that depends on the resolved mode
+    fn resolve_mode(&mut self) {
+        self.resolved_mode = resolve_partition_mode(
+            self.mode,
+            &self.left,
+            self.single_partition_threshold,
+            self.single_partition_threshold_rows,
+        );
+        self.output_ordering = probe_side_ordering(
+            &self.right,
+            self.join_type,
+            self.resolved_mode,
+            &self.column_indices,
+        );
+    }
+
+    /// Sets the fraction of the rows matching the join keys that the statistics
+    /// of the join assume a conjunct of the join filter keeps, when the
+    /// statistics of the inputs tell nothing about it. The conjuncts comparing
+    /// a column with known min/max values to a literal are estimated from them.
+    /// # Error
+    /// This function errors if `selectivity` is not between 0 and 1.
+    pub fn with_default_filter_selectivity(mut self, selectivity: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&selectivity) {
+            return Err(DataFusionError::Plan(format!(
+                "The default selectivity of a join filter must be between 0 and 1, got {selectivity}"
+            )));
+        }
+        self.default_filter_selectivity = selectivity;
+        Ok(self)
+    }
+
+    /// Copies the settings of `join` set with the `with_*` methods, other than
+    /// the null_equals_null of every join key, to this join rebuilt from it,
+    /// e.g. with other inputs or another partitioning mode. Rebuilding a join
+    /// with this rather than the `with_*` methods keeps any setting added
+    /// later. The join keys of the rebuilt join may be reordered or swapped,
+    /// so their null_equals_null is set with
+    /// [`Self::with_null_equals_null_per_key`] instead.
+    pub(crate) fn with_settings_of(self, join: &HashJoinExec) -> Result<Self> {
+        Ok(self
+            .with_null_aware(join.null_aware)?
+            .with_single_partition_threshold(join.single_partition_threshold)
+            .with_single_partition_threshold_rows(join.single_partition_threshold_rows)
+            .with_default_filter_selectivity(join.default_filter_selectivity)?)
+    }
+
+    /// Returns an equivalent join with the left and right inputs exchanged, so
+    /// that the probe side becomes the build side: the pairs of join keys are
+    /// flipped, the sides of the filter columns exchanged and the join type
+    /// mirrored, e.g. `Left` becomes `Right`. Unless the join only produces the
+    /// rows of one side, a [`ProjectionExec`] on top of the swapped join
+    /// restores the column order of the output of this join.
+    pub fn swap_inputs(&self) -> Result<Arc<dyn ExecutionPlan>> {
+        self.swap_inputs_in_mode(self.mode)
+    }
+
+    /// Like [`Self::swap_inputs`], with the swapped join in `partition_mode`
+    pub(crate) fn swap_inputs_in_mode(
+        &self,
+        partition_mode: PartitionMode,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let swapped = HashJoinExec::try_new(
+            self.right.clone(),
+            self.left.clone(),
+            self.on
+                .iter()
+                .map(|(l, r)| (r.clone(), l.clone()))
+                .collect(),
+            swap_join_filter(self.filter()),
+            &swap_join_type(self.join_type),
+            partition_mode,
+            self.null_equals_null(),
+        )?
+        .with_null_equals_null_per_key(self.null_equals_null.clone())?
+        .with_settings_of(self)?;
+        if matches!(
+            self.join_type,
+            JoinType::LeftSemi
+                | JoinType::RightSemi
+                | JoinType::LeftAnti
+                | JoinType::RightAnti
+        ) {
+            Ok(Arc::new(swapped))
+        } else {
+            // TODO avoid adding ProjectionExec again and again, only adding Final Projection
+            let projection = ProjectionExec::try_new(
+                swap_reverting_projection(&self.left.schema(), &self.right.schema()),
+                Arc::new(swapped),
+            )?;
+            Ok(Arc::new(projection))
+        }
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -175,8 +656,8 @@ impl HashJoinExec {
         &self.right
     }
 
-    /// Set of common columns used to join on
-    pub fn on(&self) -> &[(Column, Column)] {
+    /// Pairs of left and right join key expressions used to join on
+    pub fn on(&self) -> JoinOnRef {
         &self.on
     }
 
@@ -195,9 +676,156 @@ impl HashJoinExec {
         &self.mode
     }
 
-    /// Get null_equals_null
+    /// The partitioning mode this hash join runs in, which is the one
+    /// [`PartitionMode::Auto`] resolves to, see
+    /// [`Self::with_single_partition_threshold`]
+    pub fn resolved_partition_mode(&self) -> PartitionMode {
+        self.resolved_mode
+    }
+
+    /// Get single_partition_threshold, see
+    /// [`Self::with_single_partition_threshold`]
+    pub fn single_partition_threshold(&self) -> usize {
+        self.single_partition_threshold
+    }
+
+    /// Get single_partition_threshold_rows, see
+    /// [`Self::with_single_partition_threshold_rows`]
+    pub fn single_partition_threshold_rows(&self) -> usize {
+        self.single_partition_threshold_rows
+    }
+
+    /// Get default_filter_selectivity, see
+    /// [`Self::with_default_filter_selectivity`]
+    pub fn default_filter_selectivity(&self) -> f64 {
+        self.default_filter_selectivity
+    }
+
+    /// Get null_equals_null, which is true if null == null for all the join
+    /// keys, see [`Self::null_equals_null_per_key`]
     pub fn null_equals_null(&self) -> bool {
-        self.null_equals_null
+        self.null_equals_null.iter().all(|n| *n)
+    }
+
+    /// Get null_equals_null of every join key in `on`
+    pub fn null_equals_null_per_key(&self) -> &[bool] {
+        &self.null_equals_null
+    }
+
+    /// Get null_aware
+    pub fn null_aware(&self) -> bool {
+        self.null_aware
+    }
+
+    /// Runtime information about the build side, once it has been built by an
+    /// execution of the join. The information of the build sides of the
+    /// partitions of a join in [`PartitionMode::Partitioned`] is added up, but
+    /// for the longest chain, which is the longest of them. Executing the join
+    /// again replaces the information of the partitions it executes.
+    pub fn build_side_info(&self) -> Option<BuildSideInfo> {
+        let infos = self.build_side_info.lock();
+        let mut infos = infos.values();
+        let mut info = *infos.next()?;
+        infos.for_each(|other| info.merge(other));
+        Some(info)
+    }
+
+    /// Returns the partition `partition` of the join in Partitioned mode, which
+    /// spills its inputs to disk if its build side does not fit in memory
+    fn spillable_join(
+        &self,
+        partition: usize,
+        filter: Option<JoinFilter>,
+        join_metrics: BuildProbeJoinMetrics,
+        spill_metrics: SpillMetrics,
+        baseline_metrics: BaselineMetrics,
+        write_options: IpcWriteOptions,
+        context: Arc<TaskContext>,
+    ) -> SpillableHashJoin {
+        SpillableHashJoin {
+            partition,
+            schema: self.schema(),
+            on_left: self.on.iter().map(|on| on.0.clone()).collect(),
+            on_right: self.on.iter().map(|on| on.1.clone()).collect(),
+            filter,
+            join_type: self.join_type,
+            column_indices: self.column_indices.clone(),
+            random_state: self.random_state.clone(),
+            null_equals_null: self.null_equals_null.clone(),
+            key_types: self.key_types.clone(),
+            unique_build_keys: self.unique_build_keys,
+            memory_budget: context
+                .session_config()
+                .options()
+                .execution
+                .hash_join_partition_memory_budget,
+            join_metrics,
+            spill_metrics,
+            baseline_metrics,
+            write_options,
+            build_side_info: self.build_side_info.clone(),
+            context,
+        }
+    }
+
+    /// Returns `left` and `right` repartitioned by the hash of their join keys
+    /// into `partitions` partitions, created once for all the partitions of an
+    /// execution of a join in CollectLeft mode which fall back to Partitioned
+    /// mode. A [`RepartitionExec`] can only be executed once per partition, so
+    /// the next execution of the join gets new ones once all the `partitions`
+    /// partitions of this execution got theirs.
+    fn fallback_inputs(
+        &self,
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        partitions: usize,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>)> {
+        let mut fallback_inputs = self.fallback_inputs.lock();
+        let mut inputs = match fallback_inputs.take() {
+            Some(inputs) => inputs,
+            None => {
+                let (left_keys, right_keys) = self.hash_partition_keys();
+                FallbackInputs {
+                    left: Arc::new(RepartitionExec::try_new(
+                        left.clone(),
+                        Partitioning::Hash(left_keys, partitions),
+                    )?),
+                    right: Arc::new(RepartitionExec::try_new(
+                        right.clone(),
+                        Partitioning::Hash(right_keys, partitions),
+                    )?),
+                    remaining_partitions: partitions,
+                }
+            }
+        };
+        let result = (inputs.left.clone(), inputs.right.clone());
+        inputs.remaining_partitions = inputs.remaining_partitions.saturating_sub(1);
+        if inputs.remaining_partitions > 0 {
+            *fallback_inputs = Some(inputs);
+        }
+        Ok(result)
+    }
+
+    /// Returns the left and right join keys both sides are hash partitioned on
+    /// in Partitioned mode. The join keys are hashed as the common type they
+    /// are compared as, so that the equal keys of both sides are in the same
+    /// partition even if their types differ
+    fn hash_partition_keys(
+        &self,
+    ) -> (Vec<Arc<dyn PhysicalExpr>>, Vec<Arc<dyn PhysicalExpr>>) {
+        let hash_key =
+            |key: &Arc<dyn PhysicalExpr>, key_type: &Option<DataType>| match key_type {
+                Some(key_type) => {
+                    Arc::new(CastExpr::new(key.clone(), key_type.clone(), None))
+                        as Arc<dyn PhysicalExpr>
+                }
+                None => key.clone(),
+            };
+        self.on
+            .iter()
+            .zip(&self.key_types)
+            .map(|((l, r), key_type)| (hash_key(l, key_type), hash_key(r, key_type)))
+            .unzip()
     }
 }
 
@@ -211,22 +839,13 @@ impl ExecutionPlan for HashJoinExec {
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
-        match self.mode {
+        match self.resolved_mode {
             PartitionMode::CollectLeft => vec![
                 Distribution::SinglePartition,
                 Distribution::UnspecifiedDistribution,
             ],
             PartitionMode::Partitioned => {
-                let (left_expr, right_expr) = self
-                    .on
-                    .iter()
-                    .map(|(l, r)| {
-                        (
-                            Arc::new(l.clone()) as Arc<dyn PhysicalExpr>,
-                            Arc::new(r.clone()) as Arc<dyn PhysicalExpr>,
-                        )
-                    })
-                    .unzip();
+                let (left_expr, right_expr) = self.hash_partition_keys();
                 vec![
                     Distribution::HashPartitioned(left_expr),
                     Distribution::HashPartitioned(right_expr),
@@ -272,7 +891,7 @@ impl ExecutionPlan for HashJoinExec {
 
     fn output_partitioning(&self) -> Partitioning {
         let left_columns_len = self.left.schema().fields.len();
-        match self.mode {
+        match self.resolved_mode {
             PartitionMode::CollectLeft => match self.join_type {
                 JoinType::Inner | JoinType::Right => adjust_right_output_partitioning(
                     self.right.output_partitioning(),
@@ -300,22 +919,35 @@ impl ExecutionPlan for HashJoinExec {
         }
     }
 
-    // TODO Output ordering might be kept for some cases.
-    // For example if it is inner join then the stream side order can be kept
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.output_ordering.as_deref()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![
+            false,
+            maintains_probe_order(self.join_type, self.resolved_mode),
+        ]
     }
 
     fn equivalence_properties(&self) -> EquivalenceProperties {
         let left_columns_len = self.left.schema().fields.len();
-        combine_join_equivalence_properties(
+        let mut properties = combine_join_equivalence_properties(
             self.join_type,
             self.left.equivalence_properties(),
             self.right.equivalence_properties(),
             left_columns_len,
             self.on(),
             self.schema(),
-        )
+        );
+        add_join_filter_equivalence_properties(
+            &mut properties,
+            self.join_type,
+            self.filter.as_ref(),
+            left_columns_len,
+            &self.schema,
+        );
+        properties
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
@@ -326,15 +958,19 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
-            children[0].clone(),
-            children[1].clone(),
-            self.on.clone(),
-            self.filter.clone(),
-            &self.join_type,
-            self.mode,
-            self.null_equals_null,
-        )?))
+        Ok(Arc::new(
+            HashJoinExec::try_new(
+                children[0].clone(),
+                children[1].clone(),
+                self.on.clone(),
+                self.filter.clone(),
+                &self.join_type,
+                self.mode,
+                false,
+            )?
+            .with_null_equals_null_per_key(self.null_equals_null.clone())?
+            .with_settings_of(&self)?,
+        ))
     }
 
     fn execute(
@@ -346,43 +982,97 @@ impl ExecutionPlan for HashJoinExec {
         let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
         let left_partitions = self.left.output_partitioning().partition_count();
         let right_partitions = self.right.output_partitioning().partition_count();
-        if self.mode == PartitionMode::Partitioned && left_partitions != right_partitions
-        {
-            return Err(DataFusionError::Internal(format!(
-                "Invalid HashJoinExec, partition count mismatch {left_partitions}!={right_partitions},\
-                 consider using RepartitionExec",
+        let mode = self.resolved_mode;
+        // every partition of the left side is joined with the same partition of
+        // the right side
+        if mode == PartitionMode::Partitioned && left_partitions != right_partitions {
+            return Err(DataFusionError::Plan(format!(
+                "Invalid HashJoinExec in Partitioned mode, partition count mismatch: \
+                 the left input has {left_partitions} partitions and the right input \
+                 has {right_partitions}, consider hash partitioning both inputs on the \
+                 join keys into the same number of partitions with RepartitionExec",
             )));
         }
 
-        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
-        let left_fut = match self.mode {
-            PartitionMode::CollectLeft => self.left_fut.once(|| {
+        // the conjuncts of the filter on a single side filter its rows first
+        let (left_predicate, right_predicate, filter) = match &self.split_filter {
+            Some(split_filter) => (
+                split_filter.left.clone(),
+                split_filter.right.clone(),
+                split_filter.filter.clone(),
+            ),
+            None => (None, None, None),
+        };
+        let left: Arc<dyn ExecutionPlan> = match left_predicate {
+            Some(predicate) => {
+                Arc::new(FilterExec::try_new(predicate, self.left.clone())?)
+            }
+            None => self.left.clone(),
+        };
+        let right: Arc<dyn ExecutionPlan> = match right_predicate {
+            Some(predicate) => {
+                Arc::new(FilterExec::try_new(predicate, self.right.clone())?)
+            }
+            None => self.right.clone(),
+        };
+
+        // A join in CollectLeft mode falls back to Partitioned mode if its build
+        // side turns out to be too large, unless its output partitioning, the
+        // one of the probe side, is a hash partitioning it would not keep, or
+        // its output keeps the ordering of the probe side
+        let options = &context.session_config().options().execution;
+        let collect_left_max_rows = (mode == PartitionMode::CollectLeft
+            && options.hash_join_collect_left_max_rows > 0
+            && !self.null_aware
+            && !matches!(right.output_partitioning(), Partitioning::Hash(..))
+            && self.output_ordering.is_none())
+        .then_some(options.hash_join_collect_left_max_rows);
+        // an unsupported spill codec fails the execution of the join rather
+        // than its first spill
+        let write_options = spill_write_options(&options.hash_join_spill_compression)?;
+
+        // the output rows are counted by the baseline metrics of the partition
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let join_metrics = BuildProbeJoinMetrics::with_baseline(
+            partition,
+            &self.metrics,
+            &baseline_metrics,
+        );
+        // the spill metrics are reported by every partition, even if it does
+        // not spill
+        let spill_metrics = SpillMetrics::new(partition, &self.metrics);
+        let left_fut = match mode {
+            PartitionMode::CollectLeft => Some(self.left_fut.once(|| {
+                // a new execution of the join builds the build side again
+                self.build_side_info.lock().clear();
                 let reservation =
                     MemoryConsumer::new("HashJoinInput").register(context.memory_pool());
-                collect_left_input(
+                // the build side shared by all partitions is attributed to the
+                // first one, whichever partition builds it
+                let build_metrics = BuildProbeJoinMetrics::new(0, &self.metrics);
+                instrument_build(
+                    collect_left_input(
+                        self.random_state.clone(),
+                        left.clone(),
+                        on_left.clone(),
+                        self.key_types.clone(),
+                        self.null_equals_null.clone(),
+                        self.join_type,
+                        right_partitions,
+                        collect_left_max_rows,
+                        context.clone(),
+                        build_metrics,
+                        reservation,
+                        self.build_side_info.clone(),
+                    ),
                     None,
-                    self.random_state.clone(),
-                    self.left.clone(),
-                    on_left.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
-                    reservation,
                 )
-            }),
+            })),
+            // the build side of a partition is collected by its stream, which
+            // spills both inputs to disk if it does not fit in memory
             PartitionMode::Partitioned => {
-                let reservation =
-                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
-                        .register(context.memory_pool());
-
-                OnceFut::new(collect_left_input(
-                    Some(partition),
-                    self.random_state.clone(),
-                    self.left.clone(),
-                    on_left.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
-                    reservation,
-                ))
+                self.build_side_info.lock().remove(&partition);
+                None
             }
             PartitionMode::Auto => {
                 return Err(DataFusionError::Plan(format!(
@@ -392,42 +1082,199 @@ impl ExecutionPlan for HashJoinExec {
             }
         };
 
-        let reservation = MemoryConsumer::new(format!("HashJoinStream[{partition}]"))
-            .register(context.memory_pool());
+        let radix_probe_threshold = options.hash_join_radix_probe_threshold;
+        let probe_read_ahead = options.hash_join_probe_read_ahead;
+
+        // A join in CollectLeft mode without output for an empty build side only
+        // executes its probe side once the build side is collected, so that the
+        // probe side is not executed at all if the build side is empty
+        let defer_probe_side = collect_left_max_rows.is_some()
+            || (mode == PartitionMode::CollectLeft
+                && produces_no_output_without_build_rows(self.join_type));
 
         // we have the batches and the hash map with their keys. We can how create a stream
         // over the right that uses this information to issue new batches.
-        let right_stream = self.right.execute(partition, context)?;
+        let execute_right = {
+            let (right, context) = (right.clone(), context.clone());
+            move || -> Result<SendableRecordBatchStream> {
+                let right_stream = right.execute(partition, context.clone())?;
+                // the next probe batches are produced while the current one is joined
+                Ok(if probe_read_ahead > 0 {
+                    let reservation = MemoryConsumer::new(format!(
+                        "HashJoinProbeReadAhead[{partition}]"
+                    ))
+                    .register(context.memory_pool());
+                    spawn_probe_read_ahead(right_stream, probe_read_ahead, reservation)
+                } else {
+                    right_stream
+                })
+            }
+        };
+        // a join which may fall back to Partitioned mode only executes its probe
+        // side once the build side is collected too, as it executes the probe
+        // side repartitioned instead if it falls back
+        let right_stream: SendableRecordBatchStream = if defer_probe_side {
+            Box::pin(RecordBatchStreamAdapter::new(
+                right.schema(),
+                futures::stream::once(async move { execute_right() }).try_flatten(),
+            ))
+        } else {
+            execute_right()?
+        };
 
-        Ok(Box::pin(HashJoinStream {
-            schema: self.schema(),
-            on_left,
-            on_right,
-            filter: self.filter.clone(),
-            join_type: self.join_type,
-            left_fut,
-            visited_left_side: None,
-            right: right_stream,
-            column_indices: self.column_indices.clone(),
-            random_state: self.random_state.clone(),
-            join_metrics,
-            null_equals_null: self.null_equals_null,
-            is_exhausted: false,
-            reservation,
-        }))
+        let stream: SendableRecordBatchStream = match left_fut {
+            Some(left_fut) => {
+                // the partition of the join in Partitioned mode, which is only
+                // executed if the build side is too large to be collected
+                let fallback = match collect_left_max_rows {
+                    Some(_) => {
+                        let (left, right) =
+                            self.fallback_inputs(&left, &right, right_partitions)?;
+                        let join = self.spillable_join(
+                            partition,
+                            filter.clone(),
+                            join_metrics.clone(),
+                            spill_metrics,
+                            baseline_metrics.intermediate(),
+                            write_options,
+                            context.clone(),
+                        );
+                        let fallbacks = MetricBuilder::new(&self.metrics)
+                            .counter("partitioned_fallbacks", partition);
+                        let context = context.clone();
+                        let stream = futures::stream::once(async move {
+                            fallbacks.add(1);
+                            let left = left.execute(partition, context.clone())?;
+                            let right = right.execute(partition, context)?;
+                            join.execute(left, right, 0).await
+                        })
+                        .try_flatten();
+                        Some(Box::pin(RecordBatchStreamAdapter::new(
+                            self.schema(),
+                            stream,
+                        )) as SendableRecordBatchStream)
+                    }
+                    None => None,
+                };
+                Box::pin(HashJoinStream {
+                    schema: self.schema(),
+                    on_left,
+                    on_right,
+                    filter,
+                    join_type: self.join_type,
+                    left_fut: Some(left_fut),
+                    shared_left_fut: Some(self.left_fut.clone()),
+                    right: right_stream,
+                    column_indices: self.column_indices.clone(),
+                    random_state: self.random_state.clone(),
+                    partition,
+                    join_metrics,
+                    baseline_metrics,
+                    null_equals_null: self.null_equals_null.clone(),
+                    is_exhausted: false,
+                    final_indices: None,
+                    probe_indices: None,
+                    batch_size: context.session_config().batch_size(),
+                    null_aware: self.null_aware,
+                    radix_probe_threshold,
+                    key_types: self.key_types.clone(),
+                    hashes_buffer: vec![],
+                    unique_build_keys: self.unique_build_keys,
+                    fallback,
+                    keep_probe_order: self.output_ordering.is_some(),
+                    matched_build_rows: None,
+                    reservation: Arc::new(Mutex::new(
+                        MemoryConsumer::new(format!("HashJoinStream[{partition}]"))
+                            .register(context.memory_pool()),
+                    )),
+                })
+            }
+            None => {
+                let join = self.spillable_join(
+                    partition,
+                    filter,
+                    join_metrics,
+                    spill_metrics,
+                    baseline_metrics.intermediate(),
+                    write_options,
+                    context.clone(),
+                );
+                let left_stream = left.execute(partition, context.clone())?;
+                let stream =
+                    futures::stream::once(join.execute(left_stream, right_stream, 0))
+                        .try_flatten()
+                        // the partition is done once the streams joining the
+                        // partitions of its spilled inputs are
+                        .chain(futures::stream::poll_fn(move |_| {
+                            baseline_metrics.done();
+                            Poll::Ready(None)
+                        }));
+                Box::pin(RecordBatchStreamAdapter::new(self.schema(), stream))
+            }
+        };
+        Ok(validate_batch_schemas("HashJoinExec", stream, &context))
     }
 
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_filter = self.filter.as_ref().map_or_else(
+            || "".to_string(),
+            |f| format!(", filter={}", f.expression()),
+        );
+        // only shown if it differs between the join keys
+        let display_null_equals_null = if self.null_equals_null.contains(&true)
+            && self.null_equals_null.contains(&false)
+        {
+            format!(", null_equals_null={:?}", self.null_equals_null)
+        } else {
+            "".to_string()
+        };
+        let display_null_aware = if self.null_aware {
+            ", null_aware=true"
+        } else {
+            ""
+        };
+        let display_unique_build_keys = if self.unique_build_keys {
+            ", unique_build_keys=true"
+        } else {
+            ""
+        };
+        let display_mode = if self.mode == PartitionMode::Auto {
+            format!("Auto(resolved={:?})", self.resolved_mode)
+        } else {
+            format!("{:?}", self.mode)
+        };
+        write!(
+            f,
+            "HashJoinExec: mode={}, join_type={:?}, on={}{}{}{}{}",
+            display_mode,
+            self.join_type,
+            display_join_on(&self.on),
+            display_filter,
+            display_null_equals_null,
+            display_null_aware,
+            display_unique_build_keys
+        )?;
         match t {
-            DisplayFormatType::Default => {
-                let display_filter = self.filter.as_ref().map_or_else(
-                    || "".to_string(),
-                    |f| format!(", filter={}", f.expression()),
-                );
+            DisplayFormatType::Default => Ok(()),
+            DisplayFormatType::Verbose => {
+                if let Some(filter) = &self.filter {
+                    let column_indices = filter
+                        .column_indices()
+                        .iter()
+                        .map(|c| format!("{}@{}", c.side, c.index))
+                        .collect::<Vec<_>>();
+                    write!(
+                        f,
+                        "\n    filter_schema=[{}]\n    filter_column_indices=[{}]",
+                        display_schema(filter.schema()),
+                        column_indices.join(", ")
+                    )?;
+                }
                 write!(
                     f,
-                    "HashJoinExec: mode={:?}, join_type={:?}, on={:?}{}",
-                    self.mode, self.join_type, self.on, display_filter
+                    "\n    null_equals_null={:?}\n    schema=[{}]",
+                    self.null_equals_null,
+                    display_schema(&self.schema)
                 )
             }
         }
@@ -437,6 +1284,10 @@ impl ExecutionPlan for HashJoinExec {
         Some(self.metrics.clone_inner())
     }
 
+    fn metrics_summary(&self) -> Option<String> {
+        self.build_side_info().map(|info| info.to_string())
+    }
+
     fn statistics(&self) -> Statistics {
         // TODO stats: it is not possible in general to know the output size of joins
         // There are some special cases though, for example:
@@ -446,136 +1297,1349 @@ impl ExecutionPlan for HashJoinExec {
             self.right.clone(),
             self.on.clone(),
             &self.join_type,
+            self.filter.as_ref(),
+            self.default_filter_selectivity,
         )
     }
 }
 
-async fn collect_left_input(
+/// Runs the build-side future inside a `hash_join_build` span, which becomes a
+/// child of the span that is current when the join is executed. The number of
+/// rows and the memory of the build side are recorded in the span once it is
+/// built, see [`record_build_side`]
+#[cfg(feature = "tracing")]
+fn instrument_build<F: Future>(
+    fut: F,
     partition: Option<usize>,
+) -> impl Future<Output = F::Output> {
+    use tracing::field::Empty;
+    use tracing::Instrument;
+    fut.instrument(tracing::info_span!(
+        "hash_join_build",
+        partition = ?partition,
+        rows = Empty,
+        bytes = Empty,
+    ))
+}
+
+#[cfg(not(feature = "tracing"))]
+fn instrument_build<F: Future>(
+    fut: F,
+    _partition: Option<usize>,
+) -> impl Future<Output = F::Output> {
+    fut
+}
+
+/// Records the number of rows and the reserved memory of a build side in the
+/// `hash_join_build` span it is built in, see [`instrument_build`]
+#[cfg(feature = "tracing")]
+fn record_build_side(num_rows: usize, bytes: usize) {
+    let span = tracing::Span::current();
+    span.record("rows", num_rows as u64);
+    span.record("bytes", bytes as u64);
+}
+
+#[cfg(not(feature = "tracing"))]
+fn record_build_side(_num_rows: usize, _bytes: usize) {}
+
+/// Enters a `hash_join_probe` span for joining the probe batch `batch` of the
+/// partition `partition`, until the returned guard is dropped. The fields are
+/// only evaluated if a subscriber is interested in the span
+#[cfg(feature = "tracing")]
+fn enter_probe_span(partition: usize, batch: &RecordBatch) -> tracing::span::EnteredSpan {
+    tracing::debug_span!(
+        "hash_join_probe",
+        partition,
+        rows = batch.num_rows(),
+        bytes = batch.get_array_memory_size(),
+    )
+    .entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+fn enter_probe_span(_partition: usize, _batch: &RecordBatch) {}
+
+#[allow(clippy::too_many_arguments)]
+async fn collect_left_input(
     random_state: RandomState,
     left: Arc<dyn ExecutionPlan>,
-    on_left: Vec<Column>,
+    on_left: Vec<Arc<dyn PhysicalExpr>>,
+    key_types: Vec<Option<DataType>>,
+    null_equals_null: Vec<bool>,
+    join_type: JoinType,
+    probe_threads_count: usize,
+    max_rows: Option<usize>,
     context: Arc<TaskContext>,
     metrics: BuildProbeJoinMetrics,
     reservation: MemoryReservation,
-) -> Result<JoinLeftData> {
+    build_side_info: Arc<Mutex<HashMap<usize, BuildSideInfo>>>,
+) -> Result<Option<JoinLeftData>> {
     let schema = left.schema();
+    let left_partitions = left.output_partitioning().partition_count();
+    let reservation = Arc::new(Mutex::new(reservation));
+
+    // The hash tables start from the number of build side rows estimated by
+    // the statistics, and grow if the build side turns out to be larger
+    let estimated_rows = left.statistics().num_rows.unwrap_or(0);
+
+    // Up to `target_partitions` partial hash tables are built concurrently
+    let parallelism = context
+        .session_config()
+        .target_partitions()
+        .min(left_partitions)
+        .max(1);
+    let build = |streams, initial_capacity| {
+        build_partial_hash_table(
+            streams,
+            initial_capacity,
+            on_left.clone(),
+            key_types.clone(),
+            null_equals_null.clone(),
+            random_state.clone(),
+            metrics.clone(),
+            reservation.clone(),
+        )
+    };
+    // The build side streams end once they produced more than `max_rows` rows
+    // together, and the join falls back to Partitioned mode
+    let collected_rows = Arc::new(AtomicUsize::new(0));
+    let limit = |stream| match max_rows {
+        Some(max_rows) => limit_build_rows(stream, collected_rows.clone(), max_rows),
+        None => stream,
+    };
+    let too_large = || match max_rows {
+        Some(max_rows) if collected_rows.load(Ordering::Relaxed) > max_rows => {
+            info!(
+                "HashJoinExec build side has more than {max_rows} rows, \
+                 falling back to Partitioned mode"
+            );
+            true
+        }
+        _ => false,
+    };
 
-    let (left_input, left_input_partition) = if let Some(partition) = partition {
-        (left, partition)
+    let partial = if parallelism == 1 {
+        // Load the whole build side in a single hash table
+        let merge = if left_partitions != 1 {
+            Arc::new(CoalescePartitionsExec::new(left))
+        } else {
+            left
+        };
+        let stream = limit(merge.execute(0, context.clone())?);
+        let partial = build(vec![stream], estimated_rows).await?;
+        if too_large() {
+            return Ok(None);
+        }
+        partial
     } else {
-        let merge = {
-            if left.output_partitioning().partition_count() != 1 {
-                Arc::new(CoalescePartitionsExec::new(left))
-            } else {
-                left
+        // Every task builds a partial hash table of every `parallelism`-th
+        // partition of the build side, which are then merged, at the cost of
+        // a higher peak memory, see `merge_partial_hash_tables`
+        let mut task_streams = (0..parallelism).map(|_| vec![]).collect::<Vec<_>>();
+        for i in 0..left_partitions {
+            task_streams[i % parallelism].push(limit(left.execute(i, context.clone())?));
+        }
+        let tasks = task_streams.into_iter().enumerate().map(|(task, streams)| {
+            let handle = tokio::task::spawn(build(streams, estimated_rows / parallelism));
+            async move {
+                AbortOnDropSingle::new(handle).await.map_err(|e| {
+                    DataFusionError::Execution(format!(
+                        "HashJoinExec build task {task} panicked: {e}"
+                    ))
+                })?
             }
-        };
-
-        (merge, 0)
+        });
+        let partials = futures::future::try_join_all(tasks).await?;
+        if too_large() {
+            return Ok(None);
+        }
+        merge_partial_hash_tables(partials, &metrics, &reservation)?
     };
+    let left_data = finish_build_side(
+        partial,
+        schema,
+        join_type,
+        probe_threads_count,
+        &context,
+        &metrics,
+        reservation,
+    )?;
+    // the build side shared by all partitions is attributed to the first one,
+    // whichever partition builds it
+    left_data.info.record(0, &build_side_info, &metrics);
+    Ok(Some(left_data))
+}
 
-    // Depending on partition argument load single partition or whole left side in memory
-    let stream = left_input.execute(left_input_partition, context.clone())?;
-
-    // This operation performs 2 steps at once:
-    // 1. creates a [JoinHashMap] of all batches from the stream
-    // 2. stores the batches in a vector.
-    let initial = (Vec::new(), 0, metrics, reservation);
-    let (batches, num_rows, metrics, mut reservation) = stream
-        .try_fold(initial, |mut acc, batch| async {
-            let batch_size = batch.get_array_memory_size();
-            // Reserve memory for incoming batch
-            acc.3.try_grow(batch_size)?;
-            // Update metrics
-            acc.2.build_mem_used.add(batch_size);
-            acc.2.build_input_batches.add(1);
-            acc.2.build_input_rows.add(batch.num_rows());
-            // Update rowcount
-            acc.1 += batch.num_rows();
-            // Push batch to output
-            acc.0.push(batch);
-            Ok(acc)
-        })
-        .await?;
-
-    // Estimation of memory size, required for hashtable, prior to allocation.
-    // Final result can be verified using `RawTable.allocation_info()`
-    //
-    // For majority of cases hashbrown overestimates buckets qty to keep ~1/8 of them empty.
-    // This formula leads to overallocation for small tables (< 8 elements) but fine overall.
-    let estimated_buckets = (num_rows.checked_mul(8).ok_or_else(|| {
-        DataFusionError::Execution(
-            "usize overflow while estimating number of hasmap buckets".to_string(),
-        )
-    })? / 7)
-        .next_power_of_two();
-    // 32 bytes per `(u64, SmallVec<[u64; 1]>)`
-    // + 1 byte for each bucket
-    // + 16 bytes fixed
-    let estimated_hastable_size = 32 * estimated_buckets + estimated_buckets + 16;
-
-    reservation.try_grow(estimated_hastable_size)?;
-    metrics.build_mem_used.add(estimated_hastable_size);
-
-    let mut hashmap = JoinHashMap(RawTable::with_capacity(num_rows));
-    let mut hashes_buffer = Vec::new();
-    let mut offset = 0;
-    for batch in batches.iter() {
-        hashes_buffer.clear();
-        hashes_buffer.resize(batch.num_rows(), 0);
-        update_hash(
-            &on_left,
-            batch,
-            &mut hashmap,
-            offset,
-            &random_state,
-            &mut hashes_buffer,
-        )?;
-        offset += batch.num_rows();
-    }
-    // Merge all batches into a single batch, so we
-    // can directly index into the arrays
-    let single_batch = concat_batches(&schema, &batches, num_rows)?;
-
-    Ok((hashmap, single_batch, reservation))
+/// Ends the build side `stream` once the build side streams sharing
+/// `collected_rows` produced more than `max_rows` rows together
+fn limit_build_rows(
+    stream: SendableRecordBatchStream,
+    collected_rows: Arc<AtomicUsize>,
+    max_rows: usize,
+) -> SendableRecordBatchStream {
+    let schema = stream.schema();
+    let stream = stream.take_while(move |batch| {
+        let num_rows = batch.as_ref().map_or(0, |batch| batch.num_rows());
+        let collected = collected_rows.fetch_add(num_rows, Ordering::Relaxed) + num_rows;
+        futures::future::ready(collected <= max_rows)
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Completes the build side data from the batches and the hash table of
+/// `partial`, with the data structures derived from them
+#[allow(clippy::too_many_arguments)]
+fn finish_build_side(
+    partial: PartialBuildSide,
+    schema: SchemaRef,
+    join_type: JoinType,
+    probe_threads_count: usize,
+    context: &TaskContext,
+    metrics: &BuildProbeJoinMetrics,
+    reservation: SharedMemoryReservation,
+) -> Result<JoinLeftData> {
+    let _timer = metrics.build_time.timer();
+    let PartialBuildSide {
+        batches,
+        num_rows,
+        hash_map: hashmap,
+        join_values,
+        hashes,
+        hashes_size,
+        ..
+    } = partial;
+
+    // Several join keys, or string keys, are also converted to the row format
+    // to compare them at once
+    let join_rows = match join_values.first() {
+        Some(values) if compare_join_keys_as_rows(values) => {
+            let rows = join_values
+                .iter()
+                .map(|values| convert_join_keys(values))
+                .collect::<Result<Vec<_>>>()?;
+            let rows_size = rows.iter().map(|rows| rows.size()).sum::<usize>();
+            grow_build_reservation(&reservation, rows_size, metrics)?;
+            Some(rows)
+        }
+        _ => None,
+    };
+
+    // The probe rows whose hash value is not in the Bloom filter have no match,
+    // and are not looked up in the hash table
+    let bloom_filter_max_size = context
+        .session_config()
+        .options()
+        .execution
+        .hash_join_bloom_filter_max_size;
+    let bloom_filter = match BloomFilter::try_new(num_rows, bloom_filter_max_size) {
+        Some(mut bloom_filter) => {
+            for hash_value in hashes.iter().flatten() {
+                bloom_filter.insert(*hash_value);
+            }
+            grow_build_reservation(&reservation, bloom_filter.size(), metrics)?;
+            Some(bloom_filter)
+        }
+        None => None,
+    };
+    // The hash values of the build side rows are only needed by the Bloom filter
+    drop(hashes);
+    reservation.lock().shrink(hashes_size);
+
+    // Wide, fixed-width build sides are also kept in row format, so output
+    // materialization copies contiguous rows instead of gathering every column
+    let rows = if context
+        .session_config()
+        .options()
+        .execution
+        .hash_join_build_row_format
+        && BuildSideRows::is_beneficial(&schema)
+    {
+        let rows = BuildSideRows::new(schema, &batches, num_rows);
+        grow_build_reservation(&reservation, rows.size(), metrics)?;
+        Some(rows)
+    } else {
+        None
+    };
+
+    // The bitmap of visited build side rows is shared by all probe streams
+    let visited_indices_bitmap = if need_produce_result_in_final(join_type) {
+        // TODO: Replace `ceil` wrapper with stable `div_cell` after
+        // https://github.com/rust-lang/rust/issues/88581
+        let visited_bitmap_size = bit_util::ceil(num_rows, 8);
+        grow_build_reservation(&reservation, visited_bitmap_size, metrics)?;
+
+        let mut buffer = BooleanBufferBuilder::new(num_rows);
+        buffer.append_n(num_rows, false);
+        buffer
+    } else {
+        BooleanBufferBuilder::new(0)
+    };
+
+    let info = BuildSideInfo {
+        num_rows,
+        hash_table_size: hashmap.size(),
+        num_buckets: hashmap.map.len(),
+        longest_chain: hashmap.longest_chain(),
+        capacity: hashmap.buckets(),
+    };
+    record_build_side(num_rows, reservation.lock().size());
+
+    Ok(JoinLeftData {
+        hash_map: hashmap,
+        batches,
+        num_rows,
+        join_values,
+        join_rows,
+        bloom_filter,
+        rows,
+        visited_indices_bitmap: Mutex::new(visited_indices_bitmap),
+        probe_threads_counter: AtomicUsize::new(probe_threads_count),
+        probe_side_non_empty: AtomicBool::new(false),
+        probe_side_null_key: AtomicBool::new(false),
+        reservation,
+        info,
+    })
+}
+
+/// Build side batches collected from one or more build side streams, and the
+/// hash table of their join keys, see [`build_partial_hash_table`]
+struct PartialBuildSide {
+    batches: Vec<RecordBatch>,
+    num_rows: usize,
+    hash_map: JoinHashMap,
+    /// Memory reserved for `hash_map`
+    hash_map_size: usize,
+    /// The join keys of every batch, evaluated once for all probe batches
+    join_values: Vec<Vec<ArrayRef>>,
+    /// The hash values of the join keys of every batch
+    hashes: Vec<Vec<u64>>,
+    /// Memory reserved for `hashes`
+    hashes_size: usize,
+}
+
+/// Collects the batches of the build side `streams` one after the other,
+/// inserting their rows into a hash table with `initial_capacity` as they
+/// arrive
+#[allow(clippy::too_many_arguments)]
+async fn build_partial_hash_table(
+    streams: Vec<SendableRecordBatchStream>,
+    initial_capacity: usize,
+    on_left: Vec<Arc<dyn PhysicalExpr>>,
+    key_types: Vec<Option<DataType>>,
+    null_equals_null: Vec<bool>,
+    random_state: RandomState,
+    metrics: BuildProbeJoinMetrics,
+    reservation: SharedMemoryReservation,
+) -> Result<PartialBuildSide> {
+    // `initial_capacity` comes from inexact statistics: the memory of the hash
+    // table is reserved before allocating it, and the hash table starts empty
+    // if it does not fit
+    let estimated_size = JoinHashMap::estimated_size(initial_capacity);
+    let (mut hash_map, reserved) =
+        match grow_build_reservation(&reservation, estimated_size, &metrics) {
+            Ok(()) => (JoinHashMap::with_capacity(initial_capacity), estimated_size),
+            Err(_) => (JoinHashMap::with_capacity(0), 0),
+        };
+    let mut hash_map_size = hash_map.size();
+    if hash_map_size > reserved {
+        grow_build_reservation(&reservation, hash_map_size - reserved, &metrics)?;
+    } else {
+        reservation.lock().shrink(reserved - hash_map_size);
+    }
+
+    let mut batches = vec![];
+    let mut num_rows = 0;
+    let mut join_values = vec![];
+    let mut hashes = vec![];
+    let mut hashes_size = 0;
+    for mut stream in streams {
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let _timer = metrics.build_time.timer();
+            let batch_size = batch.get_array_memory_size();
+            // Reserve memory for incoming batch
+            grow_build_reservation(&reservation, batch_size, &metrics)?;
+            // Update metrics
+            metrics.build_input_batches.add(1);
+            metrics.build_input_rows.add(batch.num_rows());
+            // Grow the hash table one batch at a time when the statistics of
+            // the build side did not tell its size up front
+            if hash_map.reserve(batch.num_rows()) {
+                metrics.build_hash_table_rehashes.add(1);
+            }
+            // Insert the rows into the hash table, at their position in the
+            // concatenation of the batches
+            let mut hashes_buffer = vec![0; batch.num_rows()];
+            let values = update_hash(
+                &on_left,
+                &key_types,
+                &null_equals_null,
+                &batch,
+                &mut hash_map,
+                num_rows,
+                &random_state,
+                &mut hashes_buffer,
+            )?;
+            // The join keys are kept for the probe side, and their hash values
+            // for the Bloom filter. The keys which are cast to a common type are
+            // copies of the build side columns
+            let cast_keys_size = values
+                .iter()
+                .zip(&key_types)
+                .filter(|(_, key_type)| key_type.is_some())
+                .map(|(values, _)| values.get_array_memory_size())
+                .sum::<usize>();
+            let batch_hashes_size = hashes_buffer.len() * std::mem::size_of::<u64>();
+            grow_build_reservation(
+                &reservation,
+                cast_keys_size + batch_hashes_size,
+                &metrics,
+            )?;
+            join_values.push(values);
+            hashes.push(hashes_buffer);
+            hashes_size += batch_hashes_size;
+            // Reserve memory for the hash table if it had to grow
+            let new_hash_map_size = hash_map.size();
+            if new_hash_map_size > hash_map_size {
+                grow_build_reservation(
+                    &reservation,
+                    new_hash_map_size - hash_map_size,
+                    &metrics,
+                )?;
+                hash_map_size = new_hash_map_size;
+            }
+            num_rows += batch.num_rows();
+            batches.push(batch);
+        }
+    }
+
+    Ok(PartialBuildSide {
+        batches,
+        num_rows,
+        hash_map,
+        hash_map_size,
+        join_values,
+        hashes,
+        hashes_size,
+    })
+}
+
+/// Grows `reservation` by `size` bytes, and raises the peak memory used by the
+/// build side in `metrics` to the new size of `reservation`
+fn grow_build_reservation(
+    reservation: &SharedMemoryReservation,
+    size: usize,
+    metrics: &BuildProbeJoinMetrics,
+) -> Result<()> {
+    let mut reservation = reservation.lock();
+    reservation.try_grow(size)?;
+    metrics.build_mem_used.set_max(reservation.size());
+    Ok(())
+}
+
+/// Merges the `partials` build sides into one, whose rows are the rows of the
+/// partial build sides one after the other. The memory of the partial hash
+/// tables is released as they are merged.
+///
+/// The merge runs on a single thread. The merged hash table is allocated up
+/// front, for the hash values of all the partial hash tables, so the peak
+/// memory of the build side is the partial hash tables plus the merged one,
+/// about twice the memory of a hash table built on a single thread. The build
+/// side batches are moved, not copied.
+fn merge_partial_hash_tables(
+    partials: Vec<PartialBuildSide>,
+    metrics: &BuildProbeJoinMetrics,
+    reservation: &SharedMemoryReservation,
+) -> Result<PartialBuildSide> {
+    let _timer = metrics.build_time.timer();
+    let capacity = partials
+        .iter()
+        .map(|partial| partial.hash_map.map.len())
+        .sum();
+    let total_rows = partials.iter().map(|partial| partial.num_rows).sum();
+    let mut hash_map = JoinHashMap::with_capacity(capacity);
+    hash_map.next.reserve_exact(total_rows);
+    let hash_map_size = hash_map.size();
+    grow_build_reservation(reservation, hash_map_size, metrics)?;
+
+    let mut batches = vec![];
+    let mut num_rows = 0;
+    let mut join_values = vec![];
+    let mut hashes = vec![];
+    let mut hashes_size = 0;
+    for partial in partials {
+        // the lists of the partial hash table, whose rows follow the rows of
+        // the previous partial hash tables
+        let offset = num_rows as u64;
+        hash_map
+            .next
+            .extend(partial.hash_map.next.iter().map(|&next| {
+                if next == 0 {
+                    0
+                } else {
+                    next + offset
+                }
+            }));
+        hash_map.next.resize(num_rows + partial.num_rows, 0);
+        for (hash_value, head) in partial.hash_map.map.into_iter() {
+            let head = head + offset;
+            match hash_map
+                .map
+                .get_mut(hash_value, |(hash, _)| hash_value == *hash)
+            {
+                Some((_, existing)) => {
+                    // the first row of the partial list is followed by the
+                    // latest row of the previous partial hash tables
+                    let mut row = head as usize - 1;
+                    while hash_map.next[row] != 0 {
+                        row = hash_map.next[row] as usize - 1;
+                    }
+                    hash_map.next[row] = *existing;
+                    *existing = head;
+                }
+                None => {
+                    hash_map
+                        .map
+                        .insert(hash_value, (hash_value, head), |(hash, _)| *hash);
+                }
+            }
+        }
+        reservation.lock().shrink(partial.hash_map_size);
+        num_rows += partial.num_rows;
+        batches.extend(partial.batches);
+        join_values.extend(partial.join_values);
+        hashes.extend(partial.hashes);
+        hashes_size += partial.hashes_size;
+    }
+
+    Ok(PartialBuildSide {
+        batches,
+        num_rows,
+        hash_map,
+        hash_map_size,
+        join_values,
+        hashes,
+        hashes_size,
+    })
+}
+
+/// Number of partitions both inputs of a hash join are split into by the hash
+/// of their join keys when its build side does not fit in memory
+const SPILL_PARTITIONS: usize = 16;
+
+/// Number of times the inputs of a hash join are split into partitions when
+/// the build side of a partition still does not fit in memory, before the join
+/// fails
+const MAX_SPILL_DEPTH: usize = 4;
+
+/// Metrics of the spill files written by a partition of a hash join
+#[derive(Clone)]
+struct SpillMetrics {
+    /// Number of spill files written
+    spill_count: metrics::Count,
+    /// Total size of the batches written to spill files
+    spilled_bytes: metrics::Count,
+    /// Total number of rows written to spill files
+    spilled_rows: metrics::Count,
+    /// Total size of the spill files, smaller than `spilled_bytes` if they are
+    /// compressed
+    spill_file_bytes: metrics::Count,
+}
+
+impl SpillMetrics {
+    fn new(partition: usize, metrics: &ExecutionPlanMetricsSet) -> Self {
+        Self {
+            spill_count: MetricBuilder::new(metrics).spill_count(partition),
+            spilled_bytes: MetricBuilder::new(metrics).spilled_bytes(partition),
+            spilled_rows: MetricBuilder::new(metrics).spilled_rows(partition),
+            spill_file_bytes: MetricBuilder::new(metrics)
+                .counter("spill_file_bytes", partition),
+        }
+    }
+}
+
+/// A partition of a hash join in [`PartitionMode::Partitioned`], which spills
+/// its inputs to disk when its build side does not fit in memory.
+///
+/// Both inputs are then split by the hash of their join keys into
+/// [`SPILL_PARTITIONS`] spill files each, and the pairs of spill files of the
+/// same partition are joined one after the other, splitting them again if the
+/// build side of the partition does not fit in memory either (grace hash join).
+/// The rows of a partition are only equal to the rows of the same partition of
+/// the other side, so every join type is the union of the joins of the
+/// partitions.
+#[derive(Clone)]
+struct SpillableHashJoin {
+    partition: usize,
+    schema: SchemaRef,
+    on_left: Vec<Arc<dyn PhysicalExpr>>,
+    on_right: Vec<Arc<dyn PhysicalExpr>>,
+    filter: Option<JoinFilter>,
+    join_type: JoinType,
+    column_indices: Vec<ColumnIndex>,
+    random_state: RandomState,
+    null_equals_null: Vec<bool>,
+    key_types: Vec<Option<DataType>>,
+    unique_build_keys: bool,
+    /// Size in bytes of the memory the build side may use, 0 if it is only
+    /// limited by the memory pool
+    memory_budget: usize,
+    join_metrics: BuildProbeJoinMetrics,
+    spill_metrics: SpillMetrics,
+    /// The elapsed compute time of the streams joining the partitions
+    baseline_metrics: BaselineMetrics,
+    /// The options of the spill files, compressed with the
+    /// `hash_join_spill_compression` codec
+    write_options: IpcWriteOptions,
+    /// Information about the build sides of the join, see
+    /// [`HashJoinExec::build_side_info`]
+    build_side_info: Arc<Mutex<HashMap<usize, BuildSideInfo>>>,
+    context: Arc<TaskContext>,
+}
+
+impl SpillableHashJoin {
+    /// Joins `left` and `right`, whose rows are in the same partition of the
+    /// `depth` previous splits of the inputs
+    fn execute(
+        self,
+        mut left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        depth: usize,
+    ) -> BoxFuture<'static, Result<SendableRecordBatchStream>> {
+        async move {
+            // Buffer the build side while it fits in memory
+            let mut reservation =
+                MemoryConsumer::new(format!("HashJoinInput[{}]", self.partition))
+                    .with_can_spill(true)
+                    .register(self.context.memory_pool());
+            let mut batches = vec![];
+            let mut exhausted = None;
+            while let Some(batch) = left.next().await {
+                let batch = batch?;
+                let result = reservation
+                    .try_grow(batch.get_array_memory_size())
+                    .and_then(|_| self.check_memory_budget(reservation.size()));
+                self.join_metrics.build_mem_used.set_max(reservation.size());
+                batches.push(batch);
+                if let Err(e) = result {
+                    exhausted = Some(e);
+                    break;
+                }
+            }
+
+            let error = match exhausted {
+                Some(e) => e,
+                None => {
+                    // The hash table reserves the memory of the batches again
+                    reservation.free();
+                    let left_data = instrument_build(
+                        self.build(batches.clone(), left.schema()),
+                        Some(self.partition),
+                    )
+                    .await;
+                    match left_data {
+                        Ok(left_data) => return Ok(self.join_stream(left_data, right)),
+                        Err(e @ DataFusionError::ResourcesExhausted(_)) => e,
+                        Err(e) => return Err(e),
+                    }
+                }
+            };
+            if depth == MAX_SPILL_DEPTH {
+                return Err(error);
+            }
+
+            // The join fails as without spilling if the disk manager is disabled
+            let runtime = self.context.runtime_env();
+            let mut left_spill = match SpillPartitions::try_new(
+                &runtime.disk_manager,
+                left.schema(),
+                self.write_options.clone(),
+            ) {
+                Ok(left_spill) => left_spill,
+                Err(_) => return Err(error),
+            };
+            let mut right_spill = SpillPartitions::try_new(
+                &runtime.disk_manager,
+                right.schema(),
+                self.write_options.clone(),
+            )?;
+
+            let random_state = spill_random_state(depth);
+            for batch in batches {
+                let keys = evaluate_join_keys(&self.on_left, &self.key_types, &batch)?;
+                left_spill = left_spill.write(batch, &keys, &random_state).await?;
+            }
+            reservation.free();
+            while let Some(batch) = left.next().await {
+                let batch = batch?;
+                let keys = evaluate_join_keys(&self.on_left, &self.key_types, &batch)?;
+                left_spill = left_spill.write(batch, &keys, &random_state).await?;
+            }
+            let mut right = right;
+            while let Some(batch) = right.next().await {
+                let batch = batch?;
+                let keys = evaluate_join_keys(&self.on_right, &self.key_types, &batch)?;
+                right_spill = right_spill.write(batch, &keys, &random_state).await?;
+            }
+            let left_files = left_spill.finish(&self)?;
+            let right_files = right_spill.finish(&self)?;
+
+            // The partitions are joined one after the other, the next one once
+            // the output of the previous one is consumed
+            let (left_schema, right_schema) = (left.schema(), right.schema());
+            let schema = self.schema.clone();
+            let partitions = left_files
+                .into_iter()
+                .zip(right_files)
+                .filter(|(left_file, right_file)| {
+                    join_has_output(
+                        self.join_type,
+                        left_file.num_rows,
+                        right_file.num_rows,
+                    )
+                })
+                .map(|(left_file, right_file)| {
+                    let join = self.clone();
+                    let left_schema = left_schema.clone();
+                    let right_schema = right_schema.clone();
+                    futures::stream::once(async move {
+                        let left = read_spill_as_stream(left_file.file, left_schema)?;
+                        let right = read_spill_as_stream(right_file.file, right_schema)?;
+                        join.execute(left, right, depth + 1).await
+                    })
+                    .try_flatten()
+                })
+                .collect::<Vec<_>>();
+            let stream = futures::stream::iter(partitions).flatten();
+            Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+                as SendableRecordBatchStream)
+        }
+        .boxed()
+    }
+
+    /// Builds the hash table of the build side `batches`
+    async fn build(
+        &self,
+        batches: Vec<RecordBatch>,
+        schema: SchemaRef,
+    ) -> Result<JoinLeftData> {
+        let reservation = Arc::new(Mutex::new(
+            MemoryConsumer::new(format!("HashJoinInput[{}]", self.partition))
+                .register(self.context.memory_pool()),
+        ));
+        let num_rows = batches.iter().map(|batch| batch.num_rows()).sum();
+        let stream = Box::pin(MemoryStream::try_new(batches, schema.clone(), None)?);
+        let partial = build_partial_hash_table(
+            vec![stream],
+            num_rows,
+            self.on_left.clone(),
+            self.key_types.clone(),
+            self.null_equals_null.clone(),
+            self.random_state.clone(),
+            self.join_metrics.clone(),
+            reservation.clone(),
+        )
+        .await?;
+        let left_data = finish_build_side(
+            partial,
+            schema,
+            self.join_type,
+            1,
+            &self.context,
+            &self.join_metrics,
+            reservation.clone(),
+        )?;
+        let size = reservation.lock().size();
+        self.check_memory_budget(size)?;
+        left_data
+            .info
+            .record(self.partition, &self.build_side_info, &self.join_metrics);
+        Ok(left_data)
+    }
+
+    /// Returns an error if `size` bytes exceed the memory budget of the build
+    /// side, so that the partition is split as when the memory pool is exhausted
+    fn check_memory_budget(&self, size: usize) -> Result<()> {
+        if self.memory_budget > 0 && size > self.memory_budget {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "HashJoinInput[{}] exceeded its memory budget of {} bytes",
+                self.partition, self.memory_budget
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the stream joining the probe side `right` to the build side
+    /// `left_data`
+    fn join_stream(
+        &self,
+        left_data: JoinLeftData,
+        right: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        let options = &self.context.session_config().options().execution;
+        Box::pin(HashJoinStream {
+            schema: self.schema.clone(),
+            on_left: self.on_left.clone(),
+            on_right: self.on_right.clone(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            left_fut: Some(OnceFut::new(futures::future::ready(Ok(Some(left_data))))),
+            shared_left_fut: None,
+            right,
+            column_indices: self.column_indices.clone(),
+            random_state: self.random_state.clone(),
+            partition: self.partition,
+            join_metrics: self.join_metrics.clone(),
+            baseline_metrics: self.baseline_metrics.intermediate(),
+            null_equals_null: self.null_equals_null.clone(),
+            is_exhausted: false,
+            final_indices: None,
+            probe_indices: None,
+            batch_size: self.context.session_config().batch_size(),
+            null_aware: false,
+            radix_probe_threshold: options.hash_join_radix_probe_threshold,
+            key_types: self.key_types.clone(),
+            hashes_buffer: vec![],
+            unique_build_keys: self.unique_build_keys,
+            fallback: None,
+            keep_probe_order: false,
+            matched_build_rows: None,
+            reservation: Arc::new(Mutex::new(
+                MemoryConsumer::new(format!("HashJoinStream[{}]", self.partition))
+                    .register(self.context.memory_pool()),
+            )),
+        })
+    }
+}
+
+/// Returns the hasher splitting the inputs of a spilling hash join at `depth`.
+///
+/// The partitions of a join in [`PartitionMode::Partitioned`] are split by
+/// [`RepartitionExec`] with the same seeds as the hash table, so every split
+/// uses other seeds, otherwise the rows of a partition would all end up in the
+/// same partition of the next split.
+fn spill_random_state(depth: usize) -> RandomState {
+    let seed = depth as u64 + 1;
+    RandomState::with_seeds(seed, seed, seed, seed)
+}
+
+/// Returns the options of the spill files of a hash join, compressed with the
+/// `hash_join_spill_compression` codec. The codec is read back from the files.
+fn spill_write_options(compression: &str) -> Result<IpcWriteOptions> {
+    let compression = match compression.to_lowercase().as_str() {
+        "none" => None,
+        "lz4" => Some(CompressionType::LZ4_FRAME),
+        "zstd" => Some(CompressionType::ZSTD),
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Unsupported hash join spill compression: {compression}. \
+                 Available codecs: none, lz4, zstd."
+            )))
+        }
+    };
+    Ok(IpcWriteOptions::default().try_with_compression(compression)?)
+}
+
+/// Returns true if the join of a build side and a probe side with `left_rows`
+/// and `right_rows` rows can produce any row
+fn join_has_output(join_type: JoinType, left_rows: u64, right_rows: u64) -> bool {
+    match join_type {
+        JoinType::Inner | JoinType::LeftSemi | JoinType::RightSemi => {
+            left_rows > 0 && right_rows > 0
+        }
+        JoinType::Left | JoinType::LeftAnti => left_rows > 0,
+        JoinType::Right | JoinType::RightAnti => right_rows > 0,
+        JoinType::Full => left_rows > 0 || right_rows > 0,
+    }
+}
+
+/// The spill files of the [`SPILL_PARTITIONS`] partitions of one input of a
+/// [`SpillableHashJoin`]
+struct SpillPartitions {
+    files: Vec<NamedTempFile>,
+    writers: Vec<IPCWriter>,
+}
+
+/// A spill file of a partition written by [`SpillPartitions`]
+struct SpillFile {
+    file: NamedTempFile,
+    num_rows: u64,
+}
+
+impl SpillPartitions {
+    /// Creates the spill files in the directories of `disk_manager`
+    fn try_new(
+        disk_manager: &DiskManager,
+        schema: SchemaRef,
+        write_options: IpcWriteOptions,
+    ) -> Result<Self> {
+        let files = (0..SPILL_PARTITIONS)
+            .map(|_| disk_manager.create_tmp_file("HashJoin"))
+            .collect::<Result<Vec<_>>>()?;
+        let writers = files
+            .iter()
+            .map(|file| {
+                IPCWriter::new_with_options(file.path(), &schema, write_options.clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { files, writers })
+    }
+
+    /// Appends the rows of `batch` to the spill files of their partitions,
+    /// given by the hash values of their join keys `keys`
+    async fn write(
+        mut self,
+        batch: RecordBatch,
+        keys: &[ArrayRef],
+        random_state: &RandomState,
+    ) -> Result<Self> {
+        let mut hashes_buffer = vec![0; batch.num_rows()];
+        create_hashes(keys, random_state, &mut hashes_buffer)?;
+        let mut indices = vec![vec![]; SPILL_PARTITIONS];
+        for (row, hash_value) in hashes_buffer.iter().enumerate() {
+            indices[(*hash_value % SPILL_PARTITIONS as u64) as usize].push(row as u32);
+        }
+        let batches = indices
+            .into_iter()
+            .enumerate()
+            .filter(|(_, indices)| !indices.is_empty())
+            .map(|(partition, indices)| {
+                let indices = UInt32Array::from(indices);
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|array| compute::take(array.as_ref(), &indices, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((partition, RecordBatch::try_new(batch.schema(), columns)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let handle = tokio::task::spawn_blocking(move || {
+            for (partition, batch) in &batches {
+                self.writers[*partition].write(batch)?;
+            }
+            Ok::<_, DataFusionError>(self)
+        });
+        match handle.await {
+            Ok(result) => result,
+            Err(e) => Err(DataFusionError::Execution(format!(
+                "Error occurred while spilling {e}"
+            ))),
+        }
+    }
+
+    /// Finishes the spill files, recording the ones with any row in the spill
+    /// metrics of `join`
+    fn finish(mut self, join: &SpillableHashJoin) -> Result<Vec<SpillFile>> {
+        for writer in self.writers.iter_mut() {
+            writer.finish()?;
+            if writer.num_rows > 0 {
+                let metrics = &join.spill_metrics;
+                metrics.spill_count.add(1);
+                metrics.spilled_bytes.add(writer.num_bytes as usize);
+                metrics.spilled_rows.add(writer.num_rows as usize);
+                let file_size = std::fs::metadata(&writer.path)?.len();
+                metrics.spill_file_bytes.add(file_size as usize);
+            }
+        }
+        Ok(self
+            .files
+            .into_iter()
+            .zip(&self.writers)
+            .map(|(file, writer)| SpillFile {
+                file,
+                num_rows: writer.num_rows,
+            })
+            .collect())
+    }
+}
+
+/// Returns true if a join of `join_type` produces no output row when its build
+/// side has no row: its output only consists of matched rows, or of build side
+/// rows. The probe side of such a join in [`PartitionMode::CollectLeft`] mode is
+/// not executed if the build side turns out to be empty.
+fn produces_no_output_without_build_rows(join_type: JoinType) -> bool {
+    matches!(
+        join_type,
+        JoinType::Inner
+            | JoinType::Left
+            | JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::RightSemi
+    )
+}
+
+/// Resolves [`PartitionMode::Auto`] to [`PartitionMode::CollectLeft`] if the
+/// build side `left` is estimated to be smaller than `single_partition_threshold`
+/// bytes, or `single_partition_threshold_rows` rows if its size is unknown, and
+/// to [`PartitionMode::Partitioned`] otherwise
+fn resolve_partition_mode(
+    mode: PartitionMode,
+    left: &Arc<dyn ExecutionPlan>,
+    single_partition_threshold: usize,
+    single_partition_threshold_rows: usize,
+) -> PartitionMode {
+    match mode {
+        PartitionMode::Auto
+            if supports_collect_by_thresholds(
+                left.as_ref(),
+                single_partition_threshold,
+                single_partition_threshold_rows,
+            ) =>
+        {
+            PartitionMode::CollectLeft
+        }
+        PartitionMode::Auto => PartitionMode::Partitioned,
+        mode => mode,
+    }
+}
+
+/// Returns true if a join of `join_type` in `mode` produces the probe side rows
+/// in the order they arrive: the probe rows are probed in order, producing
+/// their matches in build side order, and the unmatched probe rows of a `Right`
+/// join between them, see [`merge_right_indices`]. The probe side of a join in
+/// [`PartitionMode::Partitioned`] may be spilled and read back in another order.
+fn maintains_probe_order(join_type: JoinType, mode: PartitionMode) -> bool {
+    mode == PartitionMode::CollectLeft
+        && matches!(
+            join_type,
+            JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+        )
+}
+
+/// Returns the ordering of the probe side `right` of a join of `join_type` in
+/// `mode`, with its columns at their position in the output of the join given
+/// by `column_indices`, if the join keeps it, see [`maintains_probe_order`].
+fn probe_side_ordering(
+    right: &Arc<dyn ExecutionPlan>,
+    join_type: JoinType,
+    mode: PartitionMode,
+    column_indices: &[ColumnIndex],
+) -> Option<Vec<PhysicalSortExpr>> {
+    if !maintains_probe_order(join_type, mode) {
+        return None;
+    }
+    right
+        .output_ordering()?
+        .iter()
+        .map(|sort_expr| {
+            let expr = sort_expr.expr.clone().transform_down(&|expr| {
+                let column = match expr.as_any().downcast_ref::<Column>() {
+                    Some(column) => column,
+                    None => return Ok(Transformed::No(expr)),
+                };
+                let index = column_indices
+                    .iter()
+                    .position(|c| c.side == JoinSide::Right && c.index == column.index())
+                    .ok_or_else(|| {
+                        DataFusionError::Internal(format!(
+                            "Probe side column {column} not in the join output"
+                        ))
+                    })?;
+                Ok(Transformed::Yes(Arc::new(Column::new(
+                    column.name(),
+                    index,
+                ))))
+            })?;
+            Ok(PhysicalSortExpr {
+                expr,
+                options: sort_expr.options,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+        .ok()
+}
+
+/// Returns true if the join keys `on` of the build side `left` are known to be
+/// unique, which is the case if
+/// - some of the join keys are a unique key of `left`, see
+///   [`ExecutionPlan::unique_keys`]
+/// - `left` is an aggregation grouped by some of the join keys, producing each
+///   group once in the partitions of the build side of a join in
+///   `partition_mode`
+/// - or `left` has exact statistics with as many distinct values as rows, none
+///   of them null, for any of the join keys
+///
+/// Casting the keys to a common type does not change which of them are equal,
+/// see [`common_join_key_type`].
+fn unique_build_keys(
+    left: &Arc<dyn ExecutionPlan>,
+    on: &JoinOn,
+    partition_mode: PartitionMode,
+) -> bool {
+    let on_left = on.iter().map(|(l, _)| l.clone()).collect::<Vec<_>>();
+    if join_keys_unique(left.as_ref(), &on_left) {
+        return true;
+    }
+
+    // only the join keys which are plain columns are known to be unique
+    let keys = on
+        .iter()
+        .filter_map(|(l, _)| l.as_any().downcast_ref::<Column>())
+        .map(|l| l.index())
+        .collect::<Vec<_>>();
+    if let Some(aggregate) = left.as_any().downcast_ref::<AggregateExec>() {
+        let group_by = aggregate.group_expr();
+        // a single aggregation with several input partitions produces a group
+        // in every partition it occurs in, each of which is the build side of
+        // the join of a partition
+        if aggregate.mode() == &AggregateMode::Single
+            && partition_mode == PartitionMode::Partitioned
+            && !group_by.is_empty()
+            && !group_by.has_grouping_set()
+            // the group columns come first in the output of the aggregation
+            && (0..group_by.expr().len()).all(|column| keys.contains(&column))
+        {
+            return true;
+        }
+    }
+
+    let statistics = left.statistics();
+    match (
+        statistics.is_exact,
+        statistics.num_rows,
+        statistics.column_statistics,
+    ) {
+        (true, Some(num_rows), Some(column_statistics)) => keys.iter().any(|key| {
+            let column_statistics = &column_statistics[*key];
+            column_statistics.null_count == Some(0)
+                && column_statistics.distinct_count == Some(num_rows)
+        }),
+        _ => false,
+    }
+}
+
+/// Returns the type both the `left` and `right` join key types can be cast to
+/// without changing which values are equal, if they are different types that
+/// can still be compared:
+/// - [`DataType::Null`] is cast to the type of the other key
+/// - dictionaries are cast to (the common type of) their value type
+/// - timestamps of the same timezone are cast to the finer unit
+/// - `Utf8` and `Binary` are cast to `LargeUtf8` and `LargeBinary`
+/// - decimals are rescaled, see [`common_decimal_type`]
+fn common_join_key_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    if left == right {
+        return None;
+    }
+    match (left, right) {
+        (DataType::Dictionary(_, value), other)
+        | (other, DataType::Dictionary(_, value)) => {
+            if value.as_ref() == other {
+                Some(other.clone())
+            } else {
+                common_join_key_type(value, other)
+            }
+        }
+        (DataType::Null, other) | (other, DataType::Null) => Some(other.clone()),
+        (
+            DataType::Timestamp(left_unit, left_tz),
+            DataType::Timestamp(right_unit, right_tz),
+        ) if left_tz == right_tz => Some(DataType::Timestamp(
+            *left_unit.max(right_unit),
+            left_tz.clone(),
+        )),
+        (DataType::Utf8, DataType::LargeUtf8) | (DataType::LargeUtf8, DataType::Utf8) => {
+            Some(DataType::LargeUtf8)
+        }
+        (DataType::Binary, DataType::LargeBinary)
+        | (DataType::LargeBinary, DataType::Binary) => Some(DataType::LargeBinary),
+        _ => common_decimal_type(left, right),
+    }
+}
+
+/// Returns the decimal type both the `left` and `right` decimal types can be
+/// rescaled to without losing any digit, if they are different decimal types
+fn common_decimal_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    let (left_precision, left_scale) = match left {
+        DataType::Decimal128(precision, scale)
+        | DataType::Decimal256(precision, scale) => (*precision as i16, *scale as i16),
+        _ => return None,
+    };
+    let (right_precision, right_scale) = match right {
+        DataType::Decimal128(precision, scale)
+        | DataType::Decimal256(precision, scale) => (*precision as i16, *scale as i16),
+        _ => return None,
+    };
+    if left == right {
+        return None;
+    }
+
+    let scale = left_scale.max(right_scale);
+    let precision =
+        (left_precision - left_scale).max(right_precision - right_scale) + scale;
+    match (left, right) {
+        (DataType::Decimal128(_, _), DataType::Decimal128(_, _))
+            if precision <= DECIMAL128_MAX_PRECISION as i16 =>
+        {
+            Some(DataType::Decimal128(precision as u8, scale as i8))
+        }
+        _ => Some(DataType::Decimal256(
+            precision.min(DECIMAL256_MAX_PRECISION as i16) as u8,
+            scale as i8,
+        )),
+    }
+}
+
+/// Returns true if join keys of types `left` and `right` can be compared
+/// without being cast to a common type, which is when the types are equal but
+/// for the nullability of their nested fields
+fn join_key_types_equal(left: &DataType, right: &DataType) -> bool {
+    let fields_equal = |left: &Field, right: &Field| {
+        left.name() == right.name()
+            && join_key_types_equal(left.data_type(), right.data_type())
+    };
+    match (left, right) {
+        (DataType::Struct(left), DataType::Struct(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| fields_equal(left, right))
+        }
+        (DataType::List(left), DataType::List(right))
+        | (DataType::LargeList(left), DataType::LargeList(right)) => {
+            fields_equal(left, right)
+        }
+        (
+            DataType::FixedSizeList(left, left_size),
+            DataType::FixedSizeList(right, right_size),
+        ) => left_size == right_size && fields_equal(left, right),
+        _ => left == right,
+    }
+}
+
+/// Evaluates the join keys `on` against `batch`, casting the keys with a
+/// common type in `key_types` to it. A value that is not representable in the
+/// common type is an error rather than a null, which could match the null keys
+/// of the other side.
+pub(crate) fn evaluate_join_keys(
+    on: &[Arc<dyn PhysicalExpr>],
+    key_types: &[Option<DataType>],
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    on.iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let values = c.evaluate(batch)?.into_array(batch.num_rows());
+            match key_types.get(i) {
+                Some(Some(key_type)) => Ok(compute::cast_with_options(
+                    &values,
+                    key_type,
+                    &compute::CastOptions {
+                        safe: false,
+                        ..Default::default()
+                    },
+                )?),
+                _ => Ok(values),
+            }
+        })
+        .collect()
+}
+
+/// Returns true if the join keys `keys` are compared in the row format, see
+/// [`convert_join_keys`], rather than one by one with [`equal_rows`]. This is
+/// the case of several keys, or string keys, of types whose equal values have
+/// equal rows, unlike floats for which NaN is equal to NaN and `-0.0` to `0.0`.
+fn compare_join_keys_as_rows(keys: &[ArrayRef]) -> bool {
+    let row_comparable = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::FixedSizeBinary(_)
+                | DataType::Date32
+                | DataType::Date64
+                | DataType::Time32(_)
+                | DataType::Time64(_)
+                | DataType::Timestamp(_, _)
+                | DataType::Decimal128(_, _)
+                | DataType::Decimal256(_, _)
+        )
+    };
+    let is_string =
+        |data_type: &DataType| matches!(data_type, DataType::Utf8 | DataType::LargeUtf8);
+    keys.iter().all(|keys| row_comparable(keys.data_type()))
+        && (keys.len() >= 2 || keys.iter().any(|keys| is_string(keys.data_type())))
+}
+
+/// Converts the join keys `keys` to the row format, in which two rows are equal
+/// if all of their keys are equal, a null key being equal to a null key
+fn convert_join_keys(keys: &[ArrayRef]) -> Result<Rows> {
+    let fields = keys
+        .iter()
+        .map(|keys| SortField::new(keys.data_type().clone()))
+        .collect();
+    Ok(RowConverter::new(fields)?.convert_columns(keys)?)
 }
 
 /// Updates `hash` with new entries from [RecordBatch] evaluated against the expressions `on`,
 /// assuming that the [RecordBatch] corresponds to the `index`th
-pub fn update_hash(
-    on: &[Column],
+///
+/// `key_types` are the common types the join keys are cast to, see [`HashJoinExec`].
+/// The rows with a null join key whose entry in `null_equals_null` is false
+/// can not match any row, and are not inserted.
+///
+/// Returns the evaluated join keys, whose hash values are left in `hashes_buffer`
+#[allow(clippy::too_many_arguments)]
+pub fn update_hash<T: JoinHashMapType>(
+    on: &[Arc<dyn PhysicalExpr>],
+    key_types: &[Option<DataType>],
+    null_equals_null: &[bool],
     batch: &RecordBatch,
-    hash_map: &mut JoinHashMap,
+    hash_map: &mut T,
     offset: usize,
     random_state: &RandomState,
     hashes_buffer: &mut Vec<u64>,
-) -> Result<()> {
+) -> Result<Vec<ArrayRef>> {
     // evaluate the keys
-    let keys_values = on
-        .iter()
-        .map(|c| Ok(c.evaluate(batch)?.into_array(batch.num_rows())))
-        .collect::<Result<Vec<_>>>()?;
+    let keys_values = evaluate_join_keys(on, key_types, batch)?;
+    let strict_keys = strict_keys_with_nulls(&keys_values, null_equals_null);
 
     // calculate the hash values
     let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
 
     // insert hashes to key of the hashmap
     for (row, hash_value) in hash_values.iter().enumerate() {
-        let item = hash_map
-            .0
-            .get_mut(*hash_value, |(hash, _)| *hash_value == *hash);
-        if let Some((_, indices)) = item {
-            indices.push((row + offset) as u64);
-        } else {
-            hash_map.0.insert(
-                *hash_value,
-                (*hash_value, smallvec![(row + offset) as u64]),
-                |(hash, _)| *hash,
-            );
+        if is_null_in_any(&strict_keys, row) {
+            continue;
         }
+        hash_map.insert(*hash_value, (row + offset) as u64);
     }
-    Ok(())
+    Ok(keys_values)
+}
+
+/// Returns the join keys of `keys_values` with nulls which are not equal to
+/// null, according to the matching entry of `null_equals_null`
+fn strict_keys_with_nulls<'a>(
+    keys_values: &'a [ArrayRef],
+    null_equals_null: &[bool],
+) -> Vec<&'a ArrayRef> {
+    keys_values
+        .iter()
+        .zip(null_equals_null)
+        .filter(|(keys, null_equals_null)| {
+            !**null_equals_null
+                && (keys.null_count() > 0 || keys.data_type() == &DataType::Null)
+        })
+        .map(|(keys, _)| keys)
+        .collect()
+}
+
+/// Returns true if the value of any of `keys` at `row` is null
+fn is_null_in_any(keys: &[&ArrayRef], row: usize) -> bool {
+    keys.iter()
+        .any(|keys| keys.data_type() == &DataType::Null || keys.is_null(row))
 }
 
 /// A stream that issues [RecordBatch]es as they arrive from the right  of the join.
@@ -583,31 +2647,72 @@ struct HashJoinStream {
     /// Input schema
     schema: Arc<Schema>,
     /// columns from the left
-    on_left: Vec<Column>,
+    on_left: Vec<Arc<dyn PhysicalExpr>>,
     /// columns from the right used to compute the hash
-    on_right: Vec<Column>,
+    on_right: Vec<Arc<dyn PhysicalExpr>>,
     /// join filter
     filter: Option<JoinFilter>,
     /// type of the join
     join_type: JoinType,
-    /// future for data from left side
-    left_fut: OnceFut<JoinLeftData>,
-    /// Keeps track of the left side rows whether they are visited
-    visited_left_side: Option<BooleanBufferBuilder>,
+    /// future for data from left side, `None` if it was too large to be
+    /// collected. Dropped once the stream is done, see
+    /// [`HashJoinStream::release_build_side`]
+    left_fut: Option<OnceFut<Option<JoinLeftData>>>,
+    /// The build side shared by all the probe streams of a join in
+    /// [`PartitionMode::CollectLeft`], reset by the last one once it is done
+    shared_left_fut: Option<Arc<OnceAsync<Option<JoinLeftData>>>>,
     /// right
     right: SendableRecordBatchStream,
     /// Random state used for hashing initialization
     random_state: RandomState,
     /// There is nothing to process anymore and left side is processed in case of left join
     is_exhausted: bool,
+    /// Rows of the left side produced once the right side is processed, in case
+    /// of left join
+    final_indices: Option<FinalIndices>,
+    /// Rows of the probe batch being joined that are not produced yet
+    probe_indices: Option<ProbeIndices>,
+    /// Maximum number of rows of the output batches
+    batch_size: usize,
+    /// If true, the anti join has the semantics of `NOT IN`
+    null_aware: bool,
+    /// Partition of the join produced by the stream
+    partition: usize,
     /// Metrics
     join_metrics: BuildProbeJoinMetrics,
+    /// Elapsed compute time of the stream, whose output rows are counted by
+    /// `join_metrics`
+    baseline_metrics: BaselineMetrics,
     /// Information of index and left / right placement of columns
     column_indices: Vec<ColumnIndex>,
-    /// If null_equals_null is true, null == null else null != null
-    null_equals_null: bool,
-    /// Memory reservation
-    reservation: MemoryReservation,
+    /// For every join key, if null_equals_null is true, null == null else
+    /// null != null
+    null_equals_null: Vec<bool>,
+    /// Number of hash table entries above which probe rows are looked up in
+    /// the order of their position in the hash table
+    radix_probe_threshold: usize,
+    /// Common types the join keys of both sides are cast to
+    key_types: Vec<Option<DataType>>,
+    /// Buffer the hash values of the probe batches are computed into, reused
+    /// for every batch
+    hashes_buffer: Vec<u64>,
+    /// If true, the join keys of the build side are unique, see
+    /// [`unique_build_keys`]
+    unique_build_keys: bool,
+    /// The partition of the join in Partitioned mode, producing the output
+    /// instead if the build side is too large to be collected, see
+    /// `hash_join_collect_left_max_rows`
+    fallback: Option<SendableRecordBatchStream>,
+    /// If true, the unmatched probe rows of a `Right` join are produced between
+    /// the matched ones, keeping the order of the probe side, see
+    /// [`maintains_probe_order`]
+    keep_probe_order: bool,
+    /// Build side rows matched by the probe batches of the stream so far, for
+    /// the left semi and anti joins without filter, reused for every batch, see
+    /// [`matched_build_indices`]
+    matched_build_rows: Option<BooleanBufferBuilder>,
+    /// Memory reserved for `matched_build_rows`
+    reservation: SharedMemoryReservation,
 }
 
 impl RecordBatchStream for HashJoinStream {
@@ -618,42 +2723,72 @@ impl RecordBatchStream for HashJoinStream {
 
 /// Gets build and probe indices which satisfy the on condition (including
 /// the equality condition and the join filter) in the join.
+///
+/// The probe indices are of type `P`, see [`build_equal_condition_join_indices`].
+/// The time evaluating the join filter is added to `filter_time`, and the
+/// pairs of rows it is evaluated on and the ones it keeps to the counters of
+/// `filter_counts`.
 #[allow(clippy::too_many_arguments)]
-pub fn build_join_indices(
+pub fn build_join_indices<P: ArrowPrimitiveType, T: JoinHashMapType>(
     probe_batch: &RecordBatch,
-    build_hashmap: &JoinHashMap,
-    build_input_buffer: &RecordBatch,
-    on_build: &[Column],
-    on_probe: &[Column],
+    build_hashmap: &T,
+    build_input_buffers: &[RecordBatch],
+    build_join_values: &[Vec<ArrayRef>],
+    build_join_rows: Option<&[Rows]>,
+    bloom_filter: Option<(&BloomFilter, &metrics::Count)>,
+    probe_counts: Option<(&metrics::Count, &metrics::Count)>,
+    probe_times: Option<(&metrics::Time, &metrics::Time)>,
+    on_probe: &[Arc<dyn PhysicalExpr>],
+    key_types: &[Option<DataType>],
     filter: Option<&JoinFilter>,
+    filter_time: Option<&metrics::Time>,
+    filter_counts: Option<(&metrics::Count, &metrics::Count)>,
     random_state: &RandomState,
-    null_equals_null: bool,
+    null_equals_null: &[bool],
     hashes_buffer: &mut Vec<u64>,
     offset: Option<usize>,
     build_side: JoinSide,
-) -> Result<(UInt64Array, UInt32Array)> {
+    radix_probe: bool,
+    unique_build_keys: bool,
+) -> Result<(UInt64Array, PrimitiveArray<P>)> {
     // Get the indices that satisfy the equality condition, like `left.a1 = right.a2`
     let (build_indices, probe_indices) = build_equal_condition_join_indices(
         build_hashmap,
-        build_input_buffer,
+        build_input_buffers,
         probe_batch,
-        on_build,
+        build_join_values,
+        build_join_rows,
+        bloom_filter,
+        probe_counts,
+        probe_times,
         on_probe,
+        key_types,
         random_state,
         null_equals_null,
         hashes_buffer,
         offset,
+        radix_probe,
+        unique_build_keys,
     )?;
     if let Some(filter) = filter {
+        let _timer = filter_time.map(|filter_time| filter_time.timer());
+        let filter_input_rows = build_indices.len();
         // Filter the indices which satisfy the non-equal join condition, like `left.b1 = 10`
-        apply_join_filter_to_indices(
-            build_input_buffer,
+        let (build_indices, probe_indices) = apply_join_filter_to_indices(
+            build_input_buffers,
             probe_batch,
             build_indices,
             probe_indices,
             filter,
             build_side,
-        )
+        )?;
+        // the unmatched rows of the outer joins are added afterwards, and are
+        // not counted as kept by the filter
+        if let Some((input_rows, output_rows)) = filter_counts {
+            input_rows.add(filter_input_rows);
+            output_rows.add(build_indices.len());
+        }
+        Ok((build_indices, probe_indices))
     } else {
         Ok((build_indices, probe_indices))
     }
@@ -690,79 +2825,527 @@ pub fn build_join_indices(
 // And the result of build and probe indices are:
 // Build indices:  5, 6, 6, 4
 // Probe indices: 3, 4, 5, 3
+//
+// When `radix_probe` is true, the probe rows are looked up in the order of
+// their position in the hash table rather than in row order (see
+// [`radix_probe_order`]), which improves cache locality for large build
+// sides. The returned indices are still ordered by probe row.
+//
+// The build indices address the rows of `build_input_buffers` by their
+// position in the concatenation of the batches. `build_join_values` are the
+// join keys of every batch of `build_input_buffers`, already evaluated and cast
+// to the common `key_types`, see [`evaluate_join_keys`]. The build rows with
+// the hash values of the probe rows are collected first, and their join keys
+// are then compared with the ones of the probe rows all at once, see
+// [`equal_join_keys`], except for a single join key of an integer type, whose
+// values are compared as the build rows are collected. If
+// `build_join_rows` has the build side join keys in the row format too, see
+// [`convert_join_keys`], they are compared as rows. This needs no
+// `null_equals_null`, as the rows with a null key not equal to null are
+// neither in the hash table nor probed.
+//
+// The probe rows whose hash value is not in the `bloom_filter` of the build
+// side have no candidate rows, and are counted by its counter of skipped rows.
+// They are still produced by the outer joins, as rows without a match.
+//
+// The pairs of rows with the same hash value whose join keys are compared, and
+// the ones with equal join keys, are added to the counters of `probe_counts`.
+// The time hashing the join keys of `probe_batch`, and the time matching its
+// rows to the build side rows, are added to the timers of `probe_times`.
+//
+// The probe indices are of type `P`, usually `UInt32Type`. Probe batches with
+// more rows than `u32::MAX` need a wider type like `UInt64Type`, and a type too
+// narrow for `probe_batch` is an error rather than truncating the indices.
 #[allow(clippy::too_many_arguments)]
-pub fn build_equal_condition_join_indices(
-    build_hashmap: &JoinHashMap,
-    build_input_buffer: &RecordBatch,
+pub fn build_equal_condition_join_indices<P: ArrowPrimitiveType, T: JoinHashMapType>(
+    build_hashmap: &T,
+    build_input_buffers: &[RecordBatch],
     probe_batch: &RecordBatch,
-    build_on: &[Column],
-    probe_on: &[Column],
+    build_join_values: &[Vec<ArrayRef>],
+    build_join_rows: Option<&[Rows]>,
+    bloom_filter: Option<(&BloomFilter, &metrics::Count)>,
+    probe_counts: Option<(&metrics::Count, &metrics::Count)>,
+    probe_times: Option<(&metrics::Time, &metrics::Time)>,
+    probe_on: &[Arc<dyn PhysicalExpr>],
+    key_types: &[Option<DataType>],
     random_state: &RandomState,
-    null_equals_null: bool,
+    null_equals_null: &[bool],
     hashes_buffer: &mut Vec<u64>,
     offset: Option<usize>,
-) -> Result<(UInt64Array, UInt32Array)> {
-    let keys_values = probe_on
-        .iter()
-        .map(|c| Ok(c.evaluate(probe_batch)?.into_array(probe_batch.num_rows())))
-        .collect::<Result<Vec<_>>>()?;
-    let build_join_values = build_on
-        .iter()
-        .map(|c| {
-            Ok(c.evaluate(build_input_buffer)?
-                .into_array(build_input_buffer.num_rows()))
-        })
-        .collect::<Result<Vec<_>>>()?;
+    radix_probe: bool,
+    unique_build_keys: bool,
+) -> Result<(UInt64Array, PrimitiveArray<P>)> {
+    if P::Native::from_usize(probe_batch.num_rows()).is_none() {
+        return Err(DataFusionError::Internal(format!(
+            "Probe batch of {} rows can not be indexed with {}",
+            probe_batch.num_rows(),
+            P::DATA_TYPE
+        )));
+    }
+    let hash_timer = probe_times.map(|(hash_time, _)| hash_time.timer());
+    let keys_values = evaluate_join_keys(probe_on, key_types, probe_batch)?;
+    for values in build_join_values {
+        check_join_key_types_match(values, &keys_values)?;
+    }
+    let build_offsets = batch_offsets(build_input_buffers);
+    let strict_keys = strict_keys_with_nulls(&keys_values, null_equals_null);
     hashes_buffer.clear();
     hashes_buffer.resize(probe_batch.num_rows(), 0);
-    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
-    // Using a buffer builder to avoid slower normal builder
-    let mut build_indices = UInt64BufferBuilder::new(0);
-    let mut probe_indices = UInt32BufferBuilder::new(0);
-    let offset_value = offset.unwrap_or(0);
-    // Visit all of the probe rows
-    for (row, hash_value) in hash_values.iter().enumerate() {
-        // Get the hash and find it in the build index
-
-        // For every item on the build and probe we check if it matches
-        // This possibly contains rows with hash collisions,
-        // So we have to check here whether rows are equal or not
-        if let Some((_, indices)) = build_hashmap
-            .0
-            .get(*hash_value, |(hash, _)| *hash_value == *hash)
-        {
-            for &i in indices {
-                // Check hash collisions
-                let offset_build_index = i as usize - offset_value;
-                // Check hash collisions
-                if equal_rows(
-                    offset_build_index,
-                    row,
-                    &build_join_values,
-                    &keys_values,
-                    null_equals_null,
-                )? {
-                    build_indices.append(offset_build_index as u64);
-                    probe_indices.append(row as u32);
+    let hash_values: &[u64] = create_hashes(&keys_values, random_state, hashes_buffer)?;
+    drop(hash_timer);
+    let _match_timer = probe_times.map(|(_, match_time)| match_time.timer());
+    macro_rules! collect {
+        ($UNIQUE_BUILD_KEYS:expr, $EQUAL_KEYS:expr) => {
+            collect_join_indices::<P, _, _>(
+                build_hashmap,
+                hash_values,
+                &strict_keys,
+                bloom_filter,
+                probe_counts.map(|(candidates, _)| candidates),
+                offset.unwrap_or(0),
+                radix_probe,
+                $UNIQUE_BUILD_KEYS,
+                $EQUAL_KEYS,
+            )
+        };
+    }
+
+    // A single join key of an integer type is compared directly in the probe
+    // loop, with the key columns downcast once. Single string keys are compared
+    // as rows instead, see [`compare_join_keys_as_rows`]
+    macro_rules! collect_with_single_key {
+        ($ARRAY:ty) => {{
+            let build_keys = build_join_values
+                .iter()
+                .map(|values| values[0].as_any().downcast_ref::<$ARRAY>().unwrap())
+                .collect::<Vec<_>>();
+            let probe_keys = keys_values[0].as_any().downcast_ref::<$ARRAY>().unwrap();
+            collect!(unique_build_keys, |build_index: u64, probe_row: usize| {
+                let (batch, build_row) = locate_row(&build_offsets, build_index as usize);
+                let build_keys = build_keys[batch];
+                match (
+                    build_keys.is_valid(build_row),
+                    probe_keys.is_valid(probe_row),
+                ) {
+                    (true, true) => {
+                        build_keys.value(build_row) == probe_keys.value(probe_row)
+                    }
+                    (false, false) => null_equals_null[0],
+                    _ => false,
+                }
+            })
+        }};
+    }
+    if keys_values.len() == 1 && build_join_rows.is_none() {
+        let single_key = match keys_values[0].data_type() {
+            DataType::Int8 => Some(collect_with_single_key!(Int8Array)),
+            DataType::Int16 => Some(collect_with_single_key!(Int16Array)),
+            DataType::Int32 => Some(collect_with_single_key!(Int32Array)),
+            DataType::Int64 => Some(collect_with_single_key!(Int64Array)),
+            DataType::UInt8 => Some(collect_with_single_key!(UInt8Array)),
+            DataType::UInt16 => Some(collect_with_single_key!(UInt16Array)),
+            DataType::UInt32 => Some(collect_with_single_key!(UInt32Array)),
+            DataType::UInt64 => Some(collect_with_single_key!(UInt64Array)),
+            _ => None,
+        };
+        if let Some(indices) = single_key {
+            let (build, probe) = indices?;
+            count_matches(probe_counts, &build);
+            return Ok((build, probe));
+        }
+    }
+
+    // the candidates are not compared yet, so all of them are kept even if the
+    // build side keys are unique, in case of hash collisions
+    let (build, probe) = collect!(false, |_, _| true)?;
+
+    // Check hash collisions
+    let mask = equal_join_keys(
+        &build,
+        &probe,
+        &build_offsets,
+        build_join_values,
+        build_join_rows,
+        &keys_values,
+        null_equals_null,
+    )?;
+    if mask.true_count() == mask.len() {
+        count_matches(probe_counts, &build);
+        return Ok((build, probe));
+    }
+    let build = compute::filter(&build, &mask)?;
+    let probe = compute::filter(&probe, &mask)?;
+    let build = downcast_array::<UInt64Array>(build.as_ref());
+    count_matches(probe_counts, &build);
+    Ok((build, downcast_array(probe.as_ref())))
+}
+
+/// Adds the number of pairs of rows with equal join keys `build` to the
+/// counter of matches of `probe_counts`
+fn count_matches(
+    probe_counts: Option<(&metrics::Count, &metrics::Count)>,
+    build: &UInt64Array,
+) {
+    if let Some((_, matches)) = probe_counts {
+        matches.add(build.len());
+    }
+}
+
+/// Returns the build and probe indices of the pairs of rows of
+/// [`build_equal_condition_join_indices`] with the same hash value, ordered by
+/// probe row and then by build row, for which `equal_keys` returns true given
+/// the build and probe row.
+///
+/// If `unique_build_keys` is true, a probe row matches at most one build row,
+/// so that the remaining rows of its hash value are skipped once `equal_keys`
+/// returned true.
+#[allow(clippy::too_many_arguments)]
+fn collect_join_indices<P, T, F>(
+    build_hashmap: &T,
+    hash_values: &[u64],
+    strict_keys: &[&ArrayRef],
+    bloom_filter: Option<(&BloomFilter, &metrics::Count)>,
+    candidates: Option<&metrics::Count>,
+    offset_value: usize,
+    radix_probe: bool,
+    unique_build_keys: bool,
+    mut equal_keys: F,
+) -> Result<(UInt64Array, PrimitiveArray<P>)>
+where
+    P: ArrowPrimitiveType,
+    T: JoinHashMapType,
+    F: FnMut(u64, usize) -> bool,
+{
+    // Using a buffer builder to avoid slower normal builder, of the capacity of
+    // a match per probe row if there is at most one
+    let capacity = if unique_build_keys {
+        hash_values.len()
+    } else {
+        0
+    };
+    let mut build_indices = UInt64BufferBuilder::new(capacity);
+    let mut probe_indices = BufferBuilder::<P::Native>::new(capacity);
+    // Appends the build indices with the hash value of the probe row `row` to
+    // `matches`. These possibly contain rows with hash collisions, unless
+    // `equal_keys` tells them apart
+    let mut probe_rows_skipped = 0;
+    let mut num_candidates = 0;
+    let mut probe_row = |row: usize, matches: &mut UInt64BufferBuilder| -> Result<()> {
+        // a null key which is not equal to null matches no row
+        if is_null_in_any(strict_keys, row) {
+            return Ok(());
+        }
+        if let Some((bloom_filter, _)) = bloom_filter {
+            if !bloom_filter.may_contain(hash_values[row]) {
+                probe_rows_skipped += 1;
+                return Ok(());
+            }
+        }
+        let start = matches.len();
+        build_hashmap.for_each_row(hash_values[row], |i| {
+            let build_index = i - offset_value as u64;
+            num_candidates += 1;
+            if equal_keys(build_index, row) {
+                matches.append(build_index);
+                // a probe row matches at most one row of unique build keys
+                if unique_build_keys {
+                    return Ok(ControlFlow::Break(()));
                 }
             }
+            Ok(ControlFlow::Continue(()))
+        })?;
+        // The rows of a hash value are visited latest first, but are matched
+        // in build side order
+        if !unique_build_keys {
+            matches.as_slice_mut()[start..].reverse();
+        }
+        Ok(())
+    };
+    if radix_probe {
+        // Probe the rows in the order of their position in the hash table,
+        // remembering where the matches of every row start and end
+        let mut matches = UInt64BufferBuilder::new(0);
+        let mut ranges = vec![0..0; hash_values.len()];
+        for row in radix_probe_order(hash_values, build_hashmap.buckets()) {
+            let start = matches.len();
+            probe_row(row, &mut matches)?;
+            ranges[row] = start..matches.len();
+        }
+        // Reassemble the matches in probe row order
+        for (row, range) in ranges.into_iter().enumerate() {
+            probe_indices.append_n(range.len(), P::Native::usize_as(row));
+            build_indices.append_slice(&matches.as_slice()[range]);
+        }
+    } else {
+        // Visit all of the probe rows, appending their matches to the build
+        // indices directly
+        for row in 0..hash_values.len() {
+            let start = build_indices.len();
+            probe_row(row, &mut build_indices)?;
+            probe_indices.append_n(build_indices.len() - start, P::Native::usize_as(row));
         }
     }
+    if let Some((_, skipped)) = bloom_filter {
+        skipped.add(probe_rows_skipped);
+    }
+    if let Some(candidates) = candidates {
+        candidates.add(num_candidates);
+    }
     let build = ArrayData::builder(DataType::UInt64)
         .len(build_indices.len())
         .add_buffer(build_indices.finish())
         .build()?;
-    let probe = ArrayData::builder(DataType::UInt32)
+    let probe = ArrayData::builder(P::DATA_TYPE)
         .len(probe_indices.len())
         .add_buffer(probe_indices.finish())
         .build()?;
-
     Ok((
         PrimitiveArray::<UInt64Type>::from(build),
-        PrimitiveArray::<UInt32Type>::from(probe),
+        PrimitiveArray::<P>::from(probe),
     ))
 }
 
+/// Returns the build side rows of `left_data` with the same join keys as any
+/// row of `probe_batch`, each once, for the joins which only need to know which
+/// build side rows have a match, like the left semi join without filter.
+///
+/// Unlike [`build_equal_condition_join_indices`], the rows of a hash value which
+/// already matched a previous probe row are skipped without comparing their
+/// join keys again, so the join keys of heavily duplicated keys are compared
+/// once per build side row rather than once per pair of matching rows.
+///
+/// `matched` has a bit per build side row, set for the rows matched by the
+/// previous probe batches of the stream, which are skipped too and not
+/// returned again. The rows matched by `probe_batch` are set in it.
+fn matched_build_indices(
+    left_data: &JoinLeftData,
+    probe_batch: &RecordBatch,
+    probe_on: &[Arc<dyn PhysicalExpr>],
+    key_types: &[Option<DataType>],
+    random_state: &RandomState,
+    null_equals_null: &[bool],
+    hashes_buffer: &mut Vec<u64>,
+    matched: &mut BooleanBufferBuilder,
+    metrics: &BuildProbeJoinMetrics,
+) -> Result<UInt64Array> {
+    let hash_timer = metrics.probe_hash_time.timer();
+    let keys_values = evaluate_join_keys(probe_on, key_types, probe_batch)?;
+    for values in &left_data.join_values {
+        check_join_key_types_match(values, &keys_values)?;
+    }
+    let probe_join_rows = left_data
+        .join_rows
+        .as_ref()
+        .map(|_| convert_join_keys(&keys_values))
+        .transpose()?;
+    let build_offsets = batch_offsets(&left_data.batches);
+    let strict_keys = strict_keys_with_nulls(&keys_values, null_equals_null);
+    hashes_buffer.clear();
+    hashes_buffer.resize(probe_batch.num_rows(), 0);
+    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
+    hash_timer.done();
+    let _match_timer = metrics.match_time.timer();
+
+    let mut build_indices = vec![];
+    let mut skipped = 0;
+    let mut candidates = 0;
+    for (row, hash_value) in hash_values.iter().enumerate() {
+        // a null key which is not equal to null matches no row
+        if is_null_in_any(&strict_keys, row) {
+            continue;
+        }
+        if let Some(bloom_filter) = &left_data.bloom_filter {
+            if !bloom_filter.may_contain(*hash_value) {
+                skipped += 1;
+                continue;
+            }
+        }
+        left_data.hash_map.for_each_row(*hash_value, |i| {
+            let build_index = i as usize;
+            if matched.get_bit(build_index) {
+                return Ok(ControlFlow::Continue(()));
+            }
+            let (build_batch, build_row) = locate_row(&build_offsets, build_index);
+            candidates += 1;
+            let equal = match (&left_data.join_rows, &probe_join_rows) {
+                (Some(build_join_rows), Some(probe_join_rows)) => {
+                    build_join_rows[build_batch].row(build_row)
+                        == probe_join_rows.row(row)
+                }
+                _ => equal_rows(
+                    build_row,
+                    row,
+                    &left_data.join_values[build_batch],
+                    &keys_values,
+                    null_equals_null.iter().copied(),
+                )?,
+            };
+            if equal {
+                matched.set_bit(build_index, true);
+                build_indices.push(i);
+            }
+            Ok(ControlFlow::Continue(()))
+        })?;
+    }
+    metrics.probe_rows_skipped.add(skipped);
+    metrics.probe_candidates.add(candidates);
+    metrics.probe_matches.add(build_indices.len());
+    Ok(UInt64Array::from(build_indices))
+}
+
+/// Returns which of the pairs of build side rows `build_indices` and probe side
+/// rows `probe_indices` have equal join keys, as a mask without nulls.
+///
+/// The build indices address the rows of the join keys `build_join_values` of
+/// every build side batch, whose first rows are at `build_offsets`, see
+/// [`batch_offsets`]. `probe_keys` are the join keys of the probe batch.
+///
+/// With the build side join keys in the row format, the rows of every pair are
+/// compared at once. Otherwise, the join keys are gathered and compared one
+/// key column at a time with the comparison kernels, or one pair at a time with
+/// [`equal_rows`] for the types with their own equality, like floats.
+fn equal_join_keys<P: ArrowPrimitiveType>(
+    build_indices: &UInt64Array,
+    probe_indices: &PrimitiveArray<P>,
+    build_offsets: &[usize],
+    build_join_values: &[Vec<ArrayRef>],
+    build_join_rows: Option<&[Rows]>,
+    probe_keys: &[ArrayRef],
+    null_equals_null: &[bool],
+) -> Result<BooleanArray> {
+    let pairs = || {
+        build_indices
+            .values()
+            .iter()
+            .zip(probe_indices.values().iter())
+            .map(|(build, probe)| {
+                (locate_row(build_offsets, *build as usize), probe.as_usize())
+            })
+    };
+    if let Some(build_join_rows) = build_join_rows {
+        let probe_rows = convert_join_keys(probe_keys)?;
+        let mask = pairs()
+            .map(|((build_batch, build_row), probe_row)| {
+                build_join_rows[build_batch].row(build_row) == probe_rows.row(probe_row)
+            })
+            .collect::<Vec<_>>();
+        return Ok(BooleanArray::from(mask));
+    }
+
+    let mut mask: Option<BooleanArray> = None;
+    for (column, (probe_keys, null_equals_null)) in
+        probe_keys.iter().zip(null_equals_null).enumerate()
+    {
+        let column_mask = if compare_join_keys_with_kernels(probe_keys.data_type()) {
+            let build_keys = build_join_values
+                .iter()
+                .map(|values| values[column].clone())
+                .collect::<Vec<_>>();
+            let build_keys =
+                take_from_arrays(&build_keys, probe_keys.data_type(), build_indices)?;
+            let probe_keys = compute::take(probe_keys.as_ref(), probe_indices, None)?;
+            let equal = compute::eq_dyn(&build_keys, &probe_keys)?;
+            // a pair with a null key is equal only if both keys are null, and
+            // null is equal to null
+            let equal = match equal.null_count() {
+                0 => equal,
+                _ => compute::prep_null_mask_filter(&equal),
+            };
+            if *null_equals_null && equal.len() > equal.true_count() {
+                let both_null = compute::and(
+                    &compute::is_null(&build_keys)?,
+                    &compute::is_null(&probe_keys)?,
+                )?;
+                compute::or(&equal, &both_null)?
+            } else {
+                equal
+            }
+        } else {
+            let mask = pairs()
+                .map(|((build_batch, build_row), probe_row)| {
+                    equal_rows(
+                        build_row,
+                        probe_row,
+                        &build_join_values[build_batch][column..=column],
+                        std::slice::from_ref(probe_keys),
+                        [*null_equals_null],
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            BooleanArray::from(mask)
+        };
+        mask = Some(match mask {
+            Some(mask) => compute::and(&mask, &column_mask)?,
+            None => column_mask,
+        });
+    }
+    Ok(mask.unwrap_or_else(|| BooleanArray::from(vec![true; build_indices.len()])))
+}
+
+/// Returns true if the join keys of type `data_type` are compared with the
+/// comparison kernels in [`equal_join_keys`], whose equality is the one of the
+/// join. Floats are not, as NaN is equal to NaN and `-0.0` to `0.0`.
+fn compare_join_keys_with_kernels(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Binary
+            | DataType::LargeBinary
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Timestamp(_, _)
+            | DataType::Decimal128(_, _)
+    )
+}
+
+/// Number of bits of the position in the hash table used to partition the
+/// probe rows in [`radix_probe_order`]
+const RADIX_PROBE_BITS: u32 = 8;
+
+/// Returns the indices of the rows with `hash_values`, ordered by the
+/// `RADIX_PROBE_BITS` high bits of the position of their hash in a hash table
+/// with `buckets` buckets.
+///
+/// `RawTable` starts looking up a hash at the bucket selected by its low bits,
+/// so probing the rows in this order makes consecutive lookups touch nearby
+/// buckets instead of random cache lines of a large table. Rows of the same
+/// partition keep their relative order.
+fn radix_probe_order(hash_values: &[u64], buckets: usize) -> Vec<usize> {
+    let table_bits = buckets.trailing_zeros();
+    let shift = table_bits - RADIX_PROBE_BITS.min(table_bits);
+    let mask = buckets as u64 - 1;
+    let partition = |hash: u64| ((hash & mask) >> shift) as usize;
+
+    // Counting sort of the rows by partition
+    let mut offsets = vec![0; (mask >> shift) as usize + 1];
+    for hash in hash_values {
+        offsets[partition(*hash)] += 1;
+    }
+    let mut start = 0;
+    for offset in offsets.iter_mut() {
+        let count = *offset;
+        *offset = start;
+        start += count;
+    }
+    let mut order = vec![0; hash_values.len()];
+    for (row, hash) in hash_values.iter().enumerate() {
+        let offset = &mut offsets[partition(*hash)];
+        order[*offset] = row;
+        *offset += 1;
+    }
+    order
+}
+
 macro_rules! equal_rows_elem {
     ($array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident, $null_equals_null: ident) => {{
         let left_array = $l.as_any().downcast_ref::<$array_type>().unwrap();
@@ -776,6 +3359,25 @@ macro_rules! equal_rows_elem {
     }};
 }
 
+/// Like `equal_rows_elem`, but NaN is equal to NaN, whatever its payload.
+/// `-0.0` is equal to `0.0` as with `==`
+macro_rules! equal_rows_float_elem {
+    ($array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident, $null_equals_null: ident) => {{
+        let left_array = $l.as_any().downcast_ref::<$array_type>().unwrap();
+        let right_array = $r.as_any().downcast_ref::<$array_type>().unwrap();
+
+        match (left_array.is_null($left), right_array.is_null($right)) {
+            (false, false) => {
+                let left_value = left_array.value($left);
+                let right_value = right_array.value($right);
+                left_value == right_value || (left_value.is_nan() && right_value.is_nan())
+            }
+            (true, true) => $null_equals_null,
+            _ => false,
+        }
+    }};
+}
+
 macro_rules! equal_rows_elem_with_string_dict {
     ($key_array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident, $null_equals_null: ident) => {{
         let left_array: &DictionaryArray<$key_array_type> =
@@ -816,10 +3418,16 @@ macro_rules! equal_rows_elem_with_string_dict {
             }
         };
 
+        // Dictionaries sharing their values array, like batches of the same
+        // file, have equal values for equal keys. The values may have duplicates,
+        // so different keys still need to compare the values
+        let shared_values = Arc::ptr_eq(left_array.values(), right_array.values());
+
         match (left_values_index, right_values_index) {
             (Some(left_values_index), Some(right_values_index)) => {
-                left_values.value(left_values_index)
-                    == right_values.value(right_values_index)
+                (shared_values && left_values_index == right_values_index)
+                    || left_values.value(left_values_index)
+                        == right_values.value(right_values_index)
             }
             (None, None) => $null_equals_null,
             _ => false,
@@ -827,7 +3435,30 @@ macro_rules! equal_rows_elem_with_string_dict {
     }};
 }
 
+/// Checks that the build and probe side join keys have the same types but for
+/// the nullability of nested fields, as [`equal_rows`] can only compare the
+/// values of the same type
+fn check_join_key_types_match(
+    build_keys: &[ArrayRef],
+    probe_keys: &[ArrayRef],
+) -> Result<()> {
+    for (build_key, probe_key) in build_keys.iter().zip(probe_keys) {
+        if !join_key_types_equal(build_key.data_type(), probe_key.data_type()) {
+            return Err(DataFusionError::Internal(format!(
+                "Join keys of different types can not be compared: {} and {}",
+                build_key.data_type(),
+                probe_key.data_type()
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Left and right row have equal values
+/// Float keys are compared with `==`, so `-0.0` is equal to `0.0`, except that NaN is
+/// equal to NaN. Note that this differs from GROUP BY, which compares the bit patterns
+/// of floats and so keeps `-0.0` apart from `0.0`.
+/// `null_equals_null` has one entry for every column of `left_arrays` and `right_arrays`.
 /// If more data types are supported here, please also add the data types in can_hash function
 /// to generate hash join logical plan.
 fn equal_rows(
@@ -835,13 +3466,14 @@ fn equal_rows(
     right: usize,
     left_arrays: &[ArrayRef],
     right_arrays: &[ArrayRef],
-    null_equals_null: bool,
+    null_equals_null: impl IntoIterator<Item = bool>,
 ) -> Result<bool> {
     let mut err = None;
     let res = left_arrays
         .iter()
         .zip(right_arrays)
-        .all(|(l, r)| match l.data_type() {
+        .zip(null_equals_null)
+        .all(|((l, r), null_equals_null)| match l.data_type() {
             DataType::Null => {
                 // lhs and rhs are both `DataType::Null`, so the equal result
                 // is dependent on `null_equals_null`
@@ -875,10 +3507,10 @@ fn equal_rows(
                 equal_rows_elem!(UInt64Array, l, r, left, right, null_equals_null)
             }
             DataType::Float32 => {
-                equal_rows_elem!(Float32Array, l, r, left, right, null_equals_null)
+                equal_rows_float_elem!(Float32Array, l, r, left, right, null_equals_null)
             }
             DataType::Float64 => {
-                equal_rows_elem!(Float64Array, l, r, left, right, null_equals_null)
+                equal_rows_float_elem!(Float64Array, l, r, left, right, null_equals_null)
             }
             DataType::Date32 => {
                 equal_rows_elem!(Date32Array, l, r, left, right, null_equals_null)
@@ -914,7 +3546,18 @@ fn equal_rows(
                     false
                 }
             }
-            DataType::Timestamp(time_unit, None) => match time_unit {
+            DataType::Interval(interval_unit) => match interval_unit {
+                IntervalUnit::YearMonth => {
+                    equal_rows_elem!(IntervalYearMonthArray, l, r, left, right, null_equals_null)
+                }
+                IntervalUnit::DayTime => {
+                    equal_rows_elem!(IntervalDayTimeArray, l, r, left, right, null_equals_null)
+                }
+                IntervalUnit::MonthDayNano => {
+                    equal_rows_elem!(IntervalMonthDayNanoArray, l, r, left, right, null_equals_null)
+                }
+            }
+            DataType::Timestamp(time_unit, _) => match time_unit {
                 TimeUnit::Second => {
                     equal_rows_elem!(
                         TimestampSecondArray,
@@ -962,6 +3605,12 @@ fn equal_rows(
             DataType::LargeUtf8 => {
                 equal_rows_elem!(LargeStringArray, l, r, left, right, null_equals_null)
             }
+            DataType::Binary => {
+                equal_rows_elem!(BinaryArray, l, r, left, right, null_equals_null)
+            }
+            DataType::LargeBinary => {
+                equal_rows_elem!(LargeBinaryArray, l, r, left, right, null_equals_null)
+            }
             DataType::FixedSizeBinary(_) => {
                 equal_rows_elem!(FixedSizeBinaryArray, l, r, left, right, null_equals_null)
             }
@@ -990,6 +3639,31 @@ fn equal_rows(
                     false
                 }
             },
+            DataType::Decimal256(_, lscale) => match r.data_type() {
+                DataType::Decimal256(_, rscale) => {
+                    if lscale == rscale {
+                        equal_rows_elem!(
+                            Decimal256Array,
+                            l,
+                            r,
+                            left,
+                            right,
+                            null_equals_null
+                        )
+                    } else {
+                        err = Some(Err(DataFusionError::Internal(
+                            "Inconsistent Decimal data type in hasher, the scale should be same".to_string(),
+                        )));
+                        false
+                    }
+                }
+                _ => {
+                    err = Some(Err(DataFusionError::Internal(
+                        "Unsupported data type in hasher".to_string(),
+                    )));
+                    false
+                }
+            },
             DataType::Dictionary(key_type, value_type)
             if *value_type.as_ref() == DataType::Utf8 =>
                 {
@@ -1083,6 +3757,29 @@ fn equal_rows(
                         }
                     }
                 }
+            DataType::Struct(_) => {
+                let left_array = l.as_any().downcast_ref::<StructArray>().unwrap();
+                let right_array = r.as_any().downcast_ref::<StructArray>().unwrap();
+
+                match (left_array.is_null(left), right_array.is_null(right)) {
+                    // compare the fields of the structs like a multi column key
+                    (false, false) => match equal_rows(
+                        left,
+                        right,
+                        left_array.columns(),
+                        right_array.columns(),
+                        std::iter::repeat(null_equals_null),
+                    ) {
+                        Ok(equal) => equal,
+                        Err(e) => {
+                            err = Some(Err(e));
+                            false
+                        }
+                    },
+                    (true, true) => null_equals_null,
+                    _ => false,
+                }
+            }
             other => {
                 // This is internal because we should have caught this before.
                 err = Some(Err(DataFusionError::Internal(format!(
@@ -1095,160 +3792,753 @@ fn equal_rows(
     err.unwrap_or(Ok(res))
 }
 
+/// Builds an output batch from the build side `left_data` and a probe batch, like
+/// [`build_batch_from_indices`], reading the build side columns from the
+/// row-format copy of the build side when there is one.
+fn build_batch_from_left_data<P: ArrowPrimitiveType>(
+    schema: &Schema,
+    left_data: &JoinLeftData,
+    probe_batch: &RecordBatch,
+    build_indices: UInt64Array,
+    probe_indices: PrimitiveArray<P>,
+    column_indices: &[ColumnIndex],
+) -> Result<RecordBatch> {
+    let rows = match &left_data.rows {
+        Some(rows)
+            if column_indices
+                .iter()
+                .any(|column_index| column_index.side == JoinSide::Left) =>
+        {
+            rows
+        }
+        _ => {
+            return build_batch_from_indices(
+                schema,
+                &left_data.batches,
+                probe_batch,
+                build_indices,
+                probe_indices,
+                column_indices,
+                JoinSide::Left,
+            )
+        }
+    };
+
+    let build_columns = rows.take(&build_indices)?;
+    let columns = column_indices
+        .iter()
+        .map(|column_index| {
+            if column_index.side == JoinSide::Left {
+                Ok(build_columns[column_index.index].clone())
+            } else {
+                take_probe_column(probe_batch.column(column_index.index), &probe_indices)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(Arc::new(schema.clone()), columns)?)
+}
+
 impl HashJoinStream {
+    /// Drops the reference of the stream to the build side once it is done
+    /// with it. The `last` probe stream of the build side also resets the
+    /// future of the [`HashJoinExec`], so that the build side memory is
+    /// released once all of its probe streams are done, rather than when the
+    /// join is dropped. The build side rows matched by the stream are released
+    /// too.
+    fn release_build_side(&mut self, last: bool) {
+        self.left_fut = None;
+        self.matched_build_rows = None;
+        self.reservation.lock().free();
+        if let Some(shared_left_fut) = self.shared_left_fut.take() {
+            if last {
+                shared_left_fut.reset();
+            }
+        }
+    }
+
+    /// Returns the output of the probe side `batch` when the build side has no
+    /// row: its right side columns, and nulls for the left side columns
+    fn join_probe_batch_with_empty_build_side(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let columns = self
+            .column_indices
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(column_index, field)| match column_index.side {
+                JoinSide::Left => new_null_array(field.data_type(), batch.num_rows()),
+                JoinSide::Right => batch.column(column_index.index).clone(),
+            })
+            .collect();
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Joins the probe side `batch` with the build side `left_data`, indexing
+    /// the rows of `batch` with `P`, and returns the indices of the output rows.
+    /// The hash values of the rows of `batch` are computed into `hashes_buffer`,
+    /// and the build side rows it matches are added to `matched_build_rows` by
+    /// the left semi and anti joins without filter.
+    fn join_probe_batch<P: ArrowPrimitiveType>(
+        &self,
+        left_data: &JoinLeftData,
+        batch: RecordBatch,
+        hashes_buffer: &mut Vec<u64>,
+        matched_build_rows: &mut Option<BooleanBufferBuilder>,
+    ) -> Result<ProbeIndices> {
+        // the left semi and anti joins without filter only record which build
+        // side rows have a match, producing them once probing is done
+        if matches!(self.join_type, JoinType::LeftSemi | JoinType::LeftAnti)
+            && self.filter.is_none()
+        {
+            let matched = match matched_build_rows {
+                Some(matched) => matched,
+                None => {
+                    self.reservation
+                        .lock()
+                        .try_grow(bit_util::ceil(left_data.num_rows, 8))?;
+                    let mut matched = BooleanBufferBuilder::new(left_data.num_rows);
+                    matched.append_n(left_data.num_rows, false);
+                    matched_build_rows.insert(matched)
+                }
+            };
+            let left_side = matched_build_indices(
+                left_data,
+                &batch,
+                &self.on_right,
+                &self.key_types,
+                &self.random_state,
+                &self.null_equals_null,
+                hashes_buffer,
+                matched,
+                &self.join_metrics,
+            )
+            .map_err(|err| {
+                DataFusionError::Execution(format!(
+                    "Fail to build join indices in HashJoinExec, error:{err}",
+                ))
+            })?;
+            let mut visited_left_side = left_data.visited_indices_bitmap.lock();
+            for &x in left_side.values().iter() {
+                visited_left_side.set_bit(x as usize, true);
+            }
+            return Ok(ProbeIndices::new(
+                batch,
+                UInt64Array::from(Vec::<u64>::new()),
+                PrimitiveArray::<P>::from_iter_values(std::iter::empty()),
+            ));
+        }
+
+        // get the matched two indices for the on condition
+        let (left_side, right_side) = build_join_indices::<P, _>(
+            &batch,
+            &left_data.hash_map,
+            &left_data.batches,
+            &left_data.join_values,
+            left_data.join_rows.as_deref(),
+            left_data.bloom_filter.as_ref().map(|bloom_filter| {
+                (bloom_filter, &self.join_metrics.probe_rows_skipped)
+            }),
+            Some((
+                &self.join_metrics.probe_candidates,
+                &self.join_metrics.probe_matches,
+            )),
+            Some((
+                &self.join_metrics.probe_hash_time,
+                &self.join_metrics.match_time,
+            )),
+            &self.on_right,
+            &self.key_types,
+            self.filter.as_ref(),
+            Some(&self.join_metrics.filter_time),
+            Some((
+                &self.join_metrics.filter_input_rows,
+                &self.join_metrics.filter_output_rows,
+            )),
+            &self.random_state,
+            &self.null_equals_null,
+            hashes_buffer,
+            None,
+            JoinSide::Left,
+            left_data.hash_map.map.len() > self.radix_probe_threshold,
+            self.unique_build_keys,
+        )
+        .map_err(|err| {
+            DataFusionError::Execution(format!(
+                "Fail to build join indices in HashJoinExec, error:{err}",
+            ))
+        })?;
+
+        // set the left bitmap
+        // and only left, full, left semi, left anti need the left bitmap
+        if need_produce_result_in_final(self.join_type) {
+            let mut visited_left_side = left_data.visited_indices_bitmap.lock();
+            left_side.iter().flatten().for_each(|x| {
+                visited_left_side.set_bit(x as usize, true);
+            });
+        }
+
+        // adjust the two side indices base on the join type
+        let (left_side, right_side) =
+            if self.keep_probe_order && self.join_type == JoinType::Right {
+                let unmatched = get_anti_indices(batch.num_rows(), &right_side);
+                merge_right_indices(left_side, right_side, unmatched)
+            } else {
+                adjust_indices_by_join_type(
+                    left_side,
+                    right_side,
+                    batch.num_rows(),
+                    self.join_type,
+                )
+            };
+        // the probe side rows with a null join key are not produced by a
+        // null-aware anti join, unless the build side is empty
+        let right_side = if self.null_aware && left_data.num_rows > 0 {
+            remove_null_join_key_indices(
+                &right_side,
+                &self.on_right,
+                std::slice::from_ref(&batch),
+            )?
+        } else {
+            right_side
+        };
+
+        Ok(ProbeIndices::new(batch, left_side, right_side))
+    }
+
     /// Separate implementation function that unpins the [`HashJoinStream`] so
     /// that partial borrows work correctly
     fn poll_next_impl(
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
-        let build_timer = self.join_metrics.build_time.timer();
-        let left_data = match ready!(self.left_fut.get(cx)) {
+        let left_fut = match self.left_fut.as_mut() {
+            Some(left_fut) => left_fut,
+            // the stream is done, and released the build side
+            None => return Poll::Ready(None),
+        };
+        let left_data = match ready!(left_fut.get_shared(cx)) {
             Ok(left_data) => left_data,
             Err(e) => return Poll::Ready(Some(Err(e))),
         };
-        build_timer.done();
-
-        // Reserving memory for visited_left_side bitmap in case it hasn't been initialied yet
-        // and join_type requires to store it
-        if self.visited_left_side.is_none()
-            && need_produce_result_in_final(self.join_type)
-        {
-            // TODO: Replace `ceil` wrapper with stable `div_cell` after
-            // https://github.com/rust-lang/rust/issues/88581
-            let visited_bitmap_size = bit_util::ceil(left_data.1.num_rows(), 8);
-            self.reservation.try_grow(visited_bitmap_size)?;
-            self.join_metrics.build_mem_used.add(visited_bitmap_size);
-        }
-
-        let visited_left_side = self.visited_left_side.get_or_insert_with(|| {
-            let num_rows = left_data.1.num_rows();
-            if need_produce_result_in_final(self.join_type) {
-                // these join type need the bitmap to identify which row has be matched or unmatched.
-                // For the `left semi` join, need to use the bitmap to produce the matched row in the left side
-                // For the `left` join, need to use the bitmap to produce the unmatched row in the left side with null
-                // For the `left anti` join, need to use the bitmap to produce the unmatched row in the left side
-                // For the `full` join, need to use the bitmap to produce the unmatched row in the left side with null
-                let mut buffer = BooleanBufferBuilder::new(num_rows);
-                buffer.append_n(num_rows, false);
-                buffer
-            } else {
-                BooleanBufferBuilder::new(0)
+        let left_data = match left_data.as_ref() {
+            Some(left_data) => left_data,
+            None => {
+                return match self.fallback.as_mut() {
+                    Some(fallback) => fallback.poll_next_unpin(cx),
+                    None => Poll::Ready(Some(Err(DataFusionError::Internal(
+                        "HashJoinExec build side too large without fallback".to_string(),
+                    )))),
+                }
             }
-        });
-        let mut hashes_buffer = vec![];
-        self.right
-            .poll_next_unpin(cx)
-            .map(|maybe_batch| match maybe_batch {
-                // one right batch in the join loop
-                Some(Ok(batch)) => {
-                    self.join_metrics.input_batches.add(1);
-                    self.join_metrics.input_rows.add(batch.num_rows());
-                    let timer = self.join_metrics.join_time.timer();
+        };
 
-                    // get the matched two indices for the on condition
-                    let left_right_indices = build_join_indices(
-                        &batch,
-                        &left_data.0,
-                        &left_data.1,
+        // all the work of the stream but waiting for the probe side is timed
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        loop {
+            let mut compute_timer = elapsed_compute.timer();
+
+            // once probing is done, the build side rows are emitted in batches of
+            // at most `batch_size` rows
+            if let Some(final_indices) = self.final_indices.as_mut() {
+                let timer = self.join_metrics.join_time.timer();
+                let skip_null_join_keys = final_indices.skip_null_join_keys;
+                let chunk = final_indices.next_chunk(
+                    &left_data.visited_indices_bitmap.lock(),
+                    self.join_type,
+                    self.batch_size,
+                );
+                let (mut left_side, mut right_side) = match chunk {
+                    Some(chunk) => chunk,
+                    None => {
+                        timer.done();
+                        self.release_build_side(true);
+                        return Poll::Ready(None);
+                    }
+                };
+                if skip_null_join_keys {
+                    left_side = match remove_null_join_key_indices(
+                        &left_side,
                         &self.on_left,
-                        &self.on_right,
-                        self.filter.as_ref(),
-                        &self.random_state,
-                        self.null_equals_null,
-                        &mut hashes_buffer,
-                        None,
-                        JoinSide::Left,
-                    );
-
-                    let result = match left_right_indices {
-                        Ok((left_side, right_side)) => {
-                            // set the left bitmap
-                            // and only left, full, left semi, left anti need the left bitmap
-                            if need_produce_result_in_final(self.join_type) {
-                                left_side.iter().flatten().for_each(|x| {
-                                    visited_left_side.set_bit(x as usize, true);
-                                });
-                            }
-
-                            // adjust the two side indices base on the join type
-                            let (left_side, right_side) = adjust_indices_by_join_type(
-                                left_side,
-                                right_side,
-                                batch.num_rows(),
-                                self.join_type,
-                            );
-
-                            let result = build_batch_from_indices(
-                                &self.schema,
-                                &left_data.1,
-                                &batch,
-                                left_side,
-                                right_side,
-                                &self.column_indices,
-                                JoinSide::Left,
-                            );
-                            self.join_metrics.output_batches.add(1);
-                            self.join_metrics.output_rows.add(batch.num_rows());
-                            Some(result)
-                        }
-                        Err(err) => Some(Err(DataFusionError::Execution(format!(
-                            "Fail to build join indices in HashJoinExec, error:{err}",
-                        )))),
+                        &left_data.batches,
+                    ) {
+                        Ok(left_side) => left_side,
+                        Err(e) => return Poll::Ready(Some(Err(e))),
                     };
-                    timer.done();
-                    result
+                    right_side = right_side.slice(0, left_side.len());
+                    if left_side.is_empty() {
+                        timer.done();
+                        continue;
+                    }
                 }
-                None => {
-                    let timer = self.join_metrics.join_time.timer();
-                    if need_produce_result_in_final(self.join_type) && !self.is_exhausted
+
+                let empty_right_batch = RecordBatch::new_empty(self.right.schema());
+                // use the left and right indices to produce the batch result
+                let output_timer = self.join_metrics.output_build_time.timer();
+                let result = build_batch_from_left_data(
+                    &self.schema,
+                    &left_data,
+                    &empty_right_batch,
+                    left_side,
+                    right_side,
+                    &self.column_indices,
+                );
+                output_timer.done();
+                if let Ok(ref batch) = result {
+                    self.join_metrics.input_batches.add(1);
+                    self.join_metrics.input_rows.add(batch.num_rows());
+
+                    // the build side rows produced once probing is done have
+                    // no match
+                    if let Some((_, unmatched)) =
+                        outer_output_counts(self.join_type, &self.join_metrics)
                     {
-                        // use the global left bitmap to produce the left indices and right indices
-                        let (left_side, right_side) = get_final_indices_from_bit_map(
-                            visited_left_side,
-                            self.join_type,
-                        );
-                        let empty_right_batch =
-                            RecordBatch::new_empty(self.right.schema());
-                        // use the left and right indices to produce the batch result
-                        let result = build_batch_from_indices(
-                            &self.schema,
-                            &left_data.1,
-                            &empty_right_batch,
-                            left_side,
-                            right_side,
-                            &self.column_indices,
-                            JoinSide::Left,
-                        );
+                        unmatched.add(batch.num_rows());
+                    }
 
-                        if let Ok(ref batch) = result {
-                            self.join_metrics.input_batches.add(1);
-                            self.join_metrics.input_rows.add(batch.num_rows());
+                    self.join_metrics.output_batches.add(1);
+                    self.join_metrics.output_rows.add(batch.num_rows());
+                }
+                timer.done();
+                return Poll::Ready(Some(result));
+            }
 
+            // the rows of a probe batch are emitted in batches of at most
+            // `batch_size` rows, as a probe row may match many build side rows
+            if let Some(probe_indices) = self.probe_indices.as_mut() {
+                let timer = self.join_metrics.join_time.timer();
+                let output_timer = self.join_metrics.output_build_time.timer();
+                let result = probe_indices.next_batch(
+                    self.batch_size,
+                    &self.schema,
+                    &left_data,
+                    &self.column_indices,
+                    outer_output_counts(self.join_type, &self.join_metrics),
+                );
+                output_timer.done();
+                timer.done();
+                match result {
+                    Some(result) => {
+                        if let Ok(ref batch) = result {
                             self.join_metrics.output_batches.add(1);
                             self.join_metrics.output_rows.add(batch.num_rows());
                         }
-                        timer.done();
+                        return Poll::Ready(Some(result));
+                    }
+                    None => self.probe_indices = None,
+                }
+            }
+
+            // without build side rows, the join type alone tells the output:
+            // either nothing, or every probe side row unchanged or null-padded
+            if left_data.num_rows == 0 {
+                match self.join_type {
+                    join_type if produces_no_output_without_build_rows(join_type) => {
                         self.is_exhausted = true;
-                        Some(result)
-                    } else {
-                        // end of the join loop
-                        None
+                        let last = left_data.report_probe_completed();
+                        self.release_build_side(last);
+                        return Poll::Ready(None);
+                    }
+                    _ => {
+                        compute_timer.stop();
+                        let maybe_batch = ready!(self.right.poll_next_unpin(cx));
+                        compute_timer.restart();
+                        let result = match maybe_batch {
+                            Some(Ok(batch)) => {
+                                self.join_metrics.input_batches.add(1);
+                                self.join_metrics.input_rows.add(batch.num_rows());
+                                let result =
+                                    self.join_probe_batch_with_empty_build_side(&batch);
+                                if let Ok(ref output) = result {
+                                    self.join_metrics.output_batches.add(1);
+                                    self.join_metrics.output_rows.add(output.num_rows());
+                                    if let Some((_, unmatched)) = outer_output_counts(
+                                        self.join_type,
+                                        &self.join_metrics,
+                                    ) {
+                                        unmatched.add(output.num_rows());
+                                    }
+                                }
+                                Some(result)
+                            }
+                            Some(Err(e)) => Some(Err(e)),
+                            None => {
+                                self.is_exhausted = true;
+                                let last = left_data.report_probe_completed();
+                                self.release_build_side(last);
+                                None
+                            }
+                        };
+                        return Poll::Ready(result);
                     }
                 }
-                Some(err) => Some(err),
-            })
-    }
-}
+            }
 
-impl Stream for HashJoinStream {
-    type Item = Result<RecordBatch>;
+            // a null-aware anti join produces no row once the subquery side has a
+            // null join key, so the rest of the probe side is skipped
+            let skip_probe_side = self.null_aware
+                && match self.join_type {
+                    JoinType::LeftAnti => {
+                        left_data.probe_side_null_key.load(Ordering::Acquire)
+                    }
+                    _ => match left_data.has_null_join_key(&self.on_left) {
+                        Ok(has_null) => has_null,
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    },
+                };
+            let maybe_batch = if skip_probe_side {
+                None
+            } else {
+                compute_timer.stop();
+                let maybe_batch = ready!(self.right.poll_next_unpin(cx));
+                compute_timer.restart();
+                maybe_batch
+            };
 
-    fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+            let result = match maybe_batch {
+                // one right batch in the join loop
+                Some(Ok(batch)) => {
+                    self.join_metrics.input_batches.add(1);
+                    self.join_metrics.input_rows.add(batch.num_rows());
+
+                    if self.null_aware && self.join_type == JoinType::LeftAnti {
+                        if batch.num_rows() > 0 {
+                            left_data
+                                .probe_side_non_empty
+                                .store(true, Ordering::Release);
+                        }
+                        match has_null_join_key(&self.on_right, &batch) {
+                            Ok(true) => {
+                                left_data
+                                    .probe_side_null_key
+                                    .store(true, Ordering::Release);
+                                continue;
+                            }
+                            Ok(false) => {}
+                            Err(e) => return Poll::Ready(Some(Err(e))),
+                        }
+                    }
+                    #[allow(clippy::let_unit_value)]
+                    let _span = enter_probe_span(self.partition, &batch);
+                    let timer = self.join_metrics.join_time.timer();
+                    let mut hashes_buffer = std::mem::take(&mut self.hashes_buffer);
+                    let mut matched_build_rows = self.matched_build_rows.take();
+                    // the probe side indices are only widened for batches too
+                    // large to be indexed with u32
+                    let result = if batch.num_rows() > u32::MAX as usize {
+                        self.join_probe_batch::<UInt64Type>(
+                            &left_data,
+                            batch,
+                            &mut hashes_buffer,
+                            &mut matched_build_rows,
+                        )
+                    } else {
+                        self.join_probe_batch::<UInt32Type>(
+                            &left_data,
+                            batch,
+                            &mut hashes_buffer,
+                            &mut matched_build_rows,
+                        )
+                    };
+                    self.hashes_buffer = hashes_buffer;
+                    self.matched_build_rows = matched_build_rows;
+                    timer.done();
+                    match result {
+                        Ok(probe_indices) => {
+                            self.probe_indices = Some(probe_indices);
+                            continue;
+                        }
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+                None => {
+                    // these join type need the bitmap to identify which row has be matched or unmatched.
+                    // For the `left semi` join, need to use the bitmap to produce the matched row in the left side
+                    // For the `left` join, need to use the bitmap to produce the unmatched row in the left side with null
+                    // For the `left anti` join, need to use the bitmap to produce the unmatched row in the left side
+                    // For the `full` join, need to use the bitmap to produce the unmatched row in the left side with null
+                    //
+                    // The bitmap is shared by all probe streams of the build side, so only
+                    // the last one to finish produces these rows.
+                    let last = !self.is_exhausted && left_data.report_probe_completed();
+                    let produce_final_rows =
+                        need_produce_result_in_final(self.join_type) && last;
+                    self.is_exhausted = true;
+                    // a null-aware anti join produces no row if the probe side has a
+                    // null join key, and no row with a null join key if the probe
+                    // side is not empty
+                    if produce_final_rows
+                        && !(self.null_aware
+                            && left_data.probe_side_null_key.load(Ordering::Acquire))
+                    {
+                        // the rows are read from the global left bitmap chunk by chunk
+                        self.final_indices = Some(FinalIndices {
+                            next_row: 0,
+                            skip_null_join_keys: self.null_aware
+                                && left_data.probe_side_non_empty.load(Ordering::Acquire),
+                        });
+                        continue;
+                    }
+                    // end of the join loop
+                    self.release_build_side(last);
+                    None
+                }
+                Some(err) => Some(err),
+            };
+            return Poll::Ready(result);
+        }
+    }
+}
+
+/// Produces the probe side `input` of a [`HashJoinStream`] in a separate task,
+/// like [`spawn_buffered`], reading ahead at most `read_ahead` batches of the
+/// ones the join consumed. The memory of the batches read ahead is reserved
+/// with `reservation`, so a join whose output is not consumed holds at most
+/// `read_ahead` probe batches, all of them accounted for.
+///
+/// Once the memory of a batch can not be reserved, no more batches are read
+/// ahead until the join consumed the ones read ahead before it: that batch is
+/// then the next one the join reads, which it would hold without read ahead too.
+///
+/// [`spawn_buffered`]: crate::physical_plan::common::spawn_buffered
+fn spawn_probe_read_ahead(
+    mut input: SendableRecordBatchStream,
+    read_ahead: usize,
+    reservation: MemoryReservation,
+) -> SendableRecordBatchStream {
+    // Use tokio only if running from a tokio context (#2201)
+    if tokio::runtime::Handle::try_current().is_err() {
+        return input;
+    };
+
+    let schema = input.schema();
+    let reservation = Arc::new(Mutex::new(reservation));
+    // A batch is only read once one of the `read_ahead` permits is free, and its
+    // permit is given back once the join consumed it
+    let permits = Arc::new(Semaphore::new(read_ahead));
+    let mut builder = RecordBatchReceiverStream::builder(schema.clone(), read_ahead);
+    let sender = builder.tx();
+    let (task_reservation, task_permits) = (reservation.clone(), permits.clone());
+    builder.spawn(async move {
+        loop {
+            match task_permits.acquire().await {
+                Ok(permit) => permit.forget(),
+                Err(_) => return,
+            }
+            let item = match input.next().await {
+                Some(Ok(batch)) => {
+                    let size = batch.get_array_memory_size();
+                    if task_reservation.lock().try_grow(size).is_err() {
+                        // wait for the join to consume the other batches read
+                        // ahead, whose memory is then released
+                        match task_permits.acquire_many(read_ahead as u32 - 1).await {
+                            Ok(others) => drop(others),
+                            Err(_) => return,
+                        }
+                        task_reservation.lock().grow(size);
+                    }
+                    Ok(batch)
+                }
+                Some(Err(e)) => Err(e),
+                None => return,
+            };
+            if sender.send(item).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let stream = builder.build().inspect(move |item| {
+        if let Ok(batch) = item {
+            reservation.lock().shrink(batch.get_array_memory_size());
+        }
+        permits.add_permits(1);
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Returns true if any of the join keys `on` of `batch` has a null
+///
+/// All the values of a key of type [`DataType::Null`] are null, even though
+/// its null count is zero.
+fn has_null_join_key(on: &[Arc<dyn PhysicalExpr>], batch: &RecordBatch) -> Result<bool> {
+    for column in on {
+        let keys = column.evaluate(batch)?.into_array(batch.num_rows());
+        if keys.null_count() > 0
+            || (keys.data_type() == &DataType::Null && !keys.is_empty())
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns the `indices` of the rows of `batches`, addressed by their position
+/// in the concatenation of the batches, none of whose join keys `on` is null
+fn remove_null_join_key_indices<T: ArrowPrimitiveType>(
+    indices: &PrimitiveArray<T>,
+    on: &[Arc<dyn PhysicalExpr>],
+    batches: &[RecordBatch],
+) -> Result<PrimitiveArray<T>> {
+    let keys = batches
+        .iter()
+        .map(|batch| {
+            on.iter()
+                .map(|c| Ok(c.evaluate(batch)?.into_array(batch.num_rows())))
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let offsets = batch_offsets(batches);
+    Ok(indices
+        .iter()
+        .flatten()
+        .filter(|index| {
+            let (batch, row) = locate_row(&offsets, index.as_usize());
+            keys[batch]
+                .iter()
+                .all(|keys| keys.data_type() != &DataType::Null && keys.is_valid(row))
+        })
+        .map(Some)
+        .collect())
+}
+
+/// Position of the next build side row a [`HashJoinStream`] produces once
+/// probing is done. The rows are read from the bitmap of the visited build side
+/// rows chunk by chunk, so that the indices of all of them are never
+/// materialized at once
+struct FinalIndices {
+    /// Position in the bitmap of the next row to check
+    next_row: usize,
+    /// If true, the rows with a null join key are not produced, for null-aware
+    /// anti joins with a non-empty probe side
+    skip_null_join_keys: bool,
+}
+
+impl FinalIndices {
+    /// Returns the build side and probe side indices of the next at most
+    /// `batch_size` rows to produce for `join_type`, whose probe side indices
+    /// are all null, or `None` if all of them were returned
+    fn next_chunk(
+        &mut self,
+        bitmap: &BooleanBufferBuilder,
+        join_type: JoinType,
+        batch_size: usize,
+    ) -> Option<(UInt64Array, UInt32Array)> {
+        // a left semi join produces the visited rows, the other joins the
+        // unmatched ones
+        let visited = join_type == JoinType::LeftSemi;
+        let mut left_side =
+            Vec::with_capacity(batch_size.min(bitmap.len() - self.next_row));
+        while self.next_row < bitmap.len() && left_side.len() < batch_size {
+            if bitmap.get_bit(self.next_row) == visited {
+                left_side.push(self.next_row as u64);
+            }
+            self.next_row += 1;
+        }
+        if left_side.is_empty() {
+            return None;
+        }
+        let right_side = std::iter::repeat(None)
+            .take(left_side.len())
+            .collect::<UInt32Array>();
+        Some((UInt64Array::from(left_side), right_side))
+    }
+}
+
+/// Build and probe side indices of the rows a [`HashJoinStream`] produces for a
+/// probe batch, and the position of the next ones to produce
+struct ProbeIndices {
+    batch: RecordBatch,
+    left_side: UInt64Array,
+    /// Of type [`DataType::UInt32`], or [`DataType::UInt64`] for probe batches
+    /// too large to be indexed with u32
+    right_side: ArrayRef,
+    offset: usize,
+}
+
+impl ProbeIndices {
+    fn new<P: ArrowPrimitiveType>(
+        batch: RecordBatch,
+        left_side: UInt64Array,
+        right_side: PrimitiveArray<P>,
+    ) -> Self {
+        Self {
+            batch,
+            left_side,
+            right_side: Arc::new(right_side),
+            offset: 0,
+        }
+    }
+
+    /// Returns the output batch of the next at most `batch_size` rows, or `None`
+    /// if all of them were produced. The rows with and without a match are
+    /// added to the counters of `output_counts`, see [`outer_output_counts`].
+    fn next_batch(
+        &mut self,
+        batch_size: usize,
+        schema: &Schema,
+        left_data: &JoinLeftData,
+        column_indices: &[ColumnIndex],
+        output_counts: Option<(&metrics::Count, &metrics::Count)>,
+    ) -> Option<Result<RecordBatch>> {
+        let remaining = self.left_side.len() - self.offset;
+        if remaining == 0 {
+            return None;
+        }
+        let len = remaining.min(batch_size);
+        let left_side = self.left_side.slice(self.offset, len);
+        let right_side = self.right_side.slice(self.offset, len);
+        self.offset += len;
+        // the probe rows without a match have no build side row
+        if let Some((matched, unmatched)) = output_counts {
+            matched.add(len - left_side.null_count());
+            unmatched.add(left_side.null_count());
+        }
+        let result = match right_side.data_type() {
+            DataType::UInt64 => build_batch_from_left_data(
+                schema,
+                left_data,
+                &self.batch,
+                left_side,
+                downcast_array::<UInt64Array>(right_side.as_ref()),
+                column_indices,
+            ),
+            _ => build_batch_from_left_data(
+                schema,
+                left_data,
+                &self.batch,
+                left_side,
+                downcast_array::<UInt32Array>(right_side.as_ref()),
+                column_indices,
+            ),
+        };
+        Some(result)
+    }
+}
+
+/// Returns the counters of the output rows with and without a match in
+/// `metrics`, if `join_type` is an outer join, whose rows without a match are
+/// padded with nulls
+fn outer_output_counts(
+    join_type: JoinType,
+    metrics: &BuildProbeJoinMetrics,
+) -> Option<(&metrics::Count, &metrics::Count)> {
+    matches!(join_type, JoinType::Left | JoinType::Right | JoinType::Full)
+        .then_some((&metrics.matched_output_rows, &metrics.unmatched_output_rows))
+}
+
+impl Stream for HashJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        self.poll_next_impl(cx)
+        let poll = self.poll_next_impl(cx);
+        if let Poll::Ready(None) = poll {
+            self.baseline_metrics.done();
+        }
+        poll
     }
 }
 
@@ -1257,31 +4547,41 @@ mod tests {
     use std::sync::Arc;
 
     use arrow::array::{ArrayRef, Date32Array, Int32Array, UInt32Builder, UInt64Builder};
-    use arrow::datatypes::{DataType, Field, Schema};
-    use smallvec::smallvec;
+    use arrow::datatypes::{
+        i256, DataType, Field, IntervalDayTimeType, IntervalMonthDayNanoType, Schema,
+    };
+    use arrow::util::pretty::pretty_format_batches;
 
+    use datafusion_common::cast::as_primitive_array;
     use datafusion_common::ScalarValue;
     use datafusion_expr::Operator;
-    use datafusion_physical_expr::expressions::Literal;
+    use datafusion_physical_expr::expressions::{CastExpr, Literal};
 
     use crate::execution::context::SessionConfig;
     use crate::physical_expr::expressions::BinaryExpr;
     use crate::prelude::SessionContext;
     use crate::{
-        assert_batches_sorted_eq,
+        assert_batches_eq, assert_batches_sorted_eq,
         common::assert_contains,
         physical_plan::{
-            common,
+            aggregates::PhysicalGroupBy,
+            coalesce_batches::CoalesceBatchesExec,
+            collect_partitioned, common, displayable,
             expressions::Column,
             hash_utils::create_hashes,
             joins::{hash_join::build_equal_condition_join_indices, utils::JoinSide},
             memory::MemoryExec,
+            metrics::MetricValue,
             repartition::RepartitionExec,
+            sorts::sort::SortExec,
+            union::UnionExec,
         },
-        test::exec::MockExec,
+        test::exec::{InfiniteExec, MockExec, StatisticsExec},
         test::{build_table_i32, columns},
     };
+    use datafusion_execution::disk_manager::DiskManagerConfig;
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use rstest::*;
 
     use super::*;
 
@@ -1295,6 +4595,17 @@ mod tests {
         Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
     }
 
+    /// Returns the context of a task which probes the build side in hash table
+    /// order if its hash table has more than `radix_probe_threshold` entries,
+    /// so that the tests run with and without the radix probe
+    fn prepare_task_ctx(radix_probe_threshold: usize) -> Arc<TaskContext> {
+        let config = SessionConfig::new().set_usize(
+            "datafusion.execution.hash_join_radix_probe_threshold",
+            radix_probe_threshold,
+        );
+        SessionContext::with_config(config).task_ctx()
+    }
+
     fn join(
         left: Arc<dyn ExecutionPlan>,
         right: Arc<dyn ExecutionPlan>,
@@ -1359,15 +4670,8 @@ mod tests {
     ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
         let partition_count = 4;
 
-        let (left_expr, right_expr) = on
-            .iter()
-            .map(|(l, r)| {
-                (
-                    Arc::new(l.clone()) as Arc<dyn PhysicalExpr>,
-                    Arc::new(r.clone()) as Arc<dyn PhysicalExpr>,
-                )
-            })
-            .unzip();
+        let (left_expr, right_expr) =
+            on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
 
         let join = HashJoinExec::try_new(
             Arc::new(RepartitionExec::try_new(
@@ -1402,10 +4706,12 @@ mod tests {
         Ok((columns, batches))
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_inner_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 5]), // this has a repetition
@@ -1418,8 +4724,8 @@ mod tests {
         );
 
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (columns, batches) = join_collect(
@@ -1449,9 +4755,61 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn partitioned_join_inner_one() -> Result<()> {
+    async fn join_inner_on_expression() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 6]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![2, 3, 5]),
+            ("c2", &vec![70, 80, 90]),
+        );
+
+        // a1 + 1 = b2, evaluated by the join itself rather than projected first
+        let on = vec![(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new_with_schema("a1", &left.schema())?),
+                Operator::Plus,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(1)))),
+            )) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        assert_eq!(
+            columns(&join.schema()),
+            vec!["a1", "b1", "c1", "a2", "b2", "c2"]
+        );
+        // the join keys are not plain columns, so they are not equivalent
+        assert!(join.equivalence_properties().classes().is_empty());
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 2  | 70 |",
+            "| 2  | 5  | 8  | 20 | 3  | 80 |",
+            "| 4  | 6  | 10 | 30 | 5  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn partitioned_join_inner_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 5]), // this has a repetition
@@ -1463,8 +4821,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (columns, batches) = partitioned_join_collect(
@@ -1493,10 +4851,12 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_one_no_shared_column_names() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_inner_one_no_shared_column_names(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 5]), // this has a repetition
@@ -1508,8 +4868,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (columns, batches) =
@@ -1532,10 +4892,12 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_two() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_inner_two(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 2]),
             ("b2", &vec![1, 2, 2]),
@@ -1548,12 +4910,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1580,10 +4942,12 @@ mod tests {
     }
 
     /// Test where the left has 2 parts, the right with 1 part => 1 part
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_one_two_parts_left() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_inner_one_two_parts_left(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let batch1 = build_table_i32(
             ("a1", &vec![1, 2]),
             ("b2", &vec![1, 2]),
@@ -1603,12 +4967,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1635,10 +4999,12 @@ mod tests {
     }
 
     /// Test where the left has 1 part, the right has 2 parts => 2 parts
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_one_two_parts_right() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_inner_one_two_parts_right(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 5]), // this has a repetition
@@ -1658,8 +5024,8 @@ mod tests {
         );
 
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let join = join(left, right, on, &JoinType::Inner, false)?;
@@ -1711,10 +5077,12 @@ mod tests {
         )
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_left_multi_batch() {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_left_multi_batch(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
@@ -1726,8 +5094,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b1", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
         )];
 
         let join = join(left, right, on, &JoinType::Left, false).unwrap();
@@ -1753,10 +5121,162 @@ mod tests {
         assert_batches_sorted_eq!(expected, &batches);
     }
 
+    /// Test that the build side rows produced once probing is done are produced
+    /// by only one of several probe partitions sharing the build side
     #[tokio::test]
-    async fn join_full_multi_batch() {
+    async fn join_collect_left_multiple_probe_partitions() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 7, 8]), // 7 and 8 do not exist on the right
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right_batches = vec![
+            build_table_i32(("a2", &vec![10]), ("b1", &vec![4]), ("c2", &vec![70])),
+            build_table_i32(("a2", &vec![20]), ("b1", &vec![5]), ("c2", &vec![80])),
+            build_table_i32(("a2", &vec![30]), ("b1", &vec![6]), ("c2", &vec![90])),
+        ];
+        let schema = right_batches[0].schema();
+        let partitions = right_batches
+            .into_iter()
+            .map(|batch| vec![batch])
+            .collect::<Vec<_>>();
+        let right = Arc::new(MemoryExec::try_new(&partitions, schema, None)?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let cases = vec![
+            (
+                JoinType::Left,
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b1 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "| 1  | 4  | 7  | 10 | 4  | 70 |",
+                    "| 2  | 5  | 8  | 20 | 5  | 80 |",
+                    "| 3  | 7  | 9  |    |    |    |",
+                    "| 4  | 8  | 10 |    |    |    |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+            (
+                JoinType::Full,
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b1 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "|    |    |    | 30 | 6  | 90 |",
+                    "| 1  | 4  | 7  | 10 | 4  | 70 |",
+                    "| 2  | 5  | 8  | 20 | 5  | 80 |",
+                    "| 3  | 7  | 9  |    |    |    |",
+                    "| 4  | 8  | 10 |    |    |    |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+            (
+                JoinType::LeftSemi,
+                vec![
+                    "+----+----+----+",
+                    "| a1 | b1 | c1 |",
+                    "+----+----+----+",
+                    "| 1  | 4  | 7  |",
+                    "| 2  | 5  | 8  |",
+                    "+----+----+----+",
+                ],
+            ),
+            (
+                JoinType::LeftAnti,
+                vec![
+                    "+----+----+----+",
+                    "| a1 | b1 | c1 |",
+                    "+----+----+----+",
+                    "| 3  | 7  | 9  |",
+                    "| 4  | 8  | 10 |",
+                    "+----+----+----+",
+                ],
+            ),
+        ];
+
+        for (join_type, expected) in cases {
+            let join_exec =
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            assert_eq!(join_exec.output_partitioning().partition_count(), 3);
+
+            // all probe partitions run concurrently
+            let batches =
+                crate::physical_plan::collect(Arc::new(join_exec), task_ctx.clone())
+                    .await?;
+            assert_batches_sorted_eq!(expected, &batches);
+
+            // the last probe partition to finish produces the build side rows
+            let join_exec =
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let mut batches = vec![];
+            for partition in 0..3 {
+                let stream = join_exec.execute(partition, task_ctx.clone())?;
+                let partition_batches = common::collect(stream).await?;
+                let num_rows: usize =
+                    partition_batches.iter().map(|b| b.num_rows()).sum();
+                if join_type == JoinType::LeftAnti && partition < 2 {
+                    assert_eq!(num_rows, 0);
+                }
+                batches.extend(partition_batches);
+            }
+            assert_batches_sorted_eq!(expected, &batches);
+        }
+
+        Ok(())
+    }
+
+    /// Test that the build side rows produced once probing is done are split
+    /// into batches of at most `batch_size` rows
+    #[tokio::test]
+    async fn join_final_rows_respect_batch_size() -> Result<()> {
+        let session_ctx =
+            SessionContext::with_config(SessionConfig::new().with_batch_size(2));
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6, 7]),
+            ("b1", &vec![4, 5, 7, 8, 9, 10, 11]), // only 4 and 5 exist on the right
+            ("c1", &vec![7, 8, 9, 10, 11, 12, 13]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20]),
+            ("b1", &vec![4, 5]),
+            ("c2", &vec![70, 80]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        // the rows of the probe batch, followed by the build side rows
+        let cases = vec![
+            (JoinType::Left, vec![2, 2, 2, 1]),
+            (JoinType::Full, vec![2, 2, 2, 1]),
+            (JoinType::LeftAnti, vec![0, 2, 2, 1]),
+            (JoinType::LeftSemi, vec![0, 2]),
+        ];
+        for (join_type, expected_num_rows) in cases {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let stream = join.execute(0, task_ctx.clone())?;
+            let batches = common::collect(stream).await?;
+            let num_rows = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+            assert_eq!(num_rows, expected_num_rows, "{join_type}");
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_full_multi_batch(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
@@ -1769,8 +5289,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b2", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
         )];
 
         let join = join(left, right, on, &JoinType::Full, false).unwrap();
@@ -1798,10 +5318,12 @@ mod tests {
         assert_batches_sorted_eq!(expected, &batches);
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_left_empty_right() {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_left_empty_right(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]),
@@ -1809,8 +5331,8 @@ mod tests {
         );
         let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b1", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
         )];
         let schema = right.schema();
         let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
@@ -1835,10 +5357,12 @@ mod tests {
         assert_batches_sorted_eq!(expected, &batches);
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_full_empty_right() {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_full_empty_right(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]),
@@ -1846,8 +5370,8 @@ mod tests {
         );
         let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b2", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
         )];
         let schema = right.schema();
         let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
@@ -1872,10 +5396,12 @@ mod tests {
         assert_batches_sorted_eq!(expected, &batches);
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn join_left_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_left_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
@@ -1887,21 +5413,17 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let (columns, batches) = join_collect(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            &JoinType::Left,
-            false,
-            task_ctx,
-        )
-        .await?;
+        let join = join(left, right, on, &JoinType::Left, false)?;
+        let columns = columns(&join.schema());
         assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
 
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
         let expected = vec![
             "+----+----+----+----+----+----+",
             "| a1 | b1 | c1 | a2 | b1 | c2 |",
@@ -1913,13 +5435,20 @@ mod tests {
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
+        let metrics = join.metrics().unwrap();
+        let count = |name| metrics.sum_by_name(name).map(|value| value.as_usize());
+        assert_eq!(count("matched_output_rows"), Some(2));
+        assert_eq!(count("unmatched_output_rows"), Some(1));
+
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test]
-    async fn partitioned_join_left_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn partitioned_join_left_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
@@ -1931,8 +5460,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (columns, batches) = partitioned_join_collect(
@@ -1960,1004 +5489,6005 @@ mod tests {
         Ok(())
     }
 
-    fn build_semi_anti_left_table() -> Arc<dyn ExecutionPlan> {
-        // just two line match
-        // b1 = 10
-        build_table(
-            ("a1", &vec![1, 3, 5, 7, 9, 11, 13]),
-            ("b1", &vec![1, 3, 5, 7, 8, 8, 10]),
-            ("c1", &vec![10, 30, 50, 70, 90, 110, 130]),
-        )
-    }
-
-    fn build_semi_anti_right_table() -> Arc<dyn ExecutionPlan> {
-        // just two line match
-        // b2 = 10
-        build_table(
-            ("a2", &vec![8, 12, 6, 2, 10, 4]),
-            ("b2", &vec![8, 10, 6, 2, 10, 4]),
-            ("c2", &vec![20, 40, 60, 80, 100, 120]),
-        )
-    }
-
     #[tokio::test]
-    async fn join_left_semi() -> Result<()> {
+    async fn join_with_failing_filter() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
         let left = build_semi_anti_left_table();
         let right = build_semi_anti_right_table();
-        // left_table left semi join right_table on left_table.b1 = right_table.b2
+
+        // left_table join right_table on left_table.b1 = right_table.b2
+        // and CAST(left_table.c1 AS TINYINT) > 0, which overflows for c1 = 130
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
+        let column_indices = vec![ColumnIndex {
+            index: 2,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(CastExpr::new(
+                Arc::new(Column::new("x", 0)),
+                DataType::Int8,
+                Some(compute::CastOptions {
+                    safe: false,
+                    ..Default::default()
+                }),
+            )),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int8(Some(0)))),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
 
-        let join = join(left, right, on, &JoinType::LeftSemi, false)?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
+        let stream = join.execute(0, task_ctx)?;
+        let err = common::collect(stream).await.unwrap_err();
+        assert_contains!(err.to_string(), "Can't cast value 130 to type Int8");
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+        Ok(())
+    }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+    #[tokio::test]
+    async fn join_key_types_mismatch() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
 
-        // ignore the order
-        let expected = vec![
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
+        let left = build_key_batch(
+            ("k1", Arc::new(Int32Array::from(vec![1, 2]))),
+            ("n1", vec![1, 2]),
+        )?;
+        let right = build_key_batch(
+            ("k2", Arc::new(Int64Array::from(vec![1, 2]))),
+            ("n2", vec![10, 20]),
+        )?;
+
+        let err = inner_join_pairs(left, right, task_ctx).await.unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Error during planning: Join key k1@0 of type Int32 can not be compared with join key k2@0 of type Int64"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_coerced_key_types() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let cases: Vec<(ArrayRef, ArrayRef)> = vec![
+            // 1s, 2s, NULL, 4s vs 1s, 2.000000001s, NULL, 4s
+            (
+                Arc::new(TimestampMillisecondArray::from(vec![
+                    Some(1_000),
+                    Some(2_000),
+                    None,
+                    Some(4_000),
+                ])),
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    Some(1_000_000_000),
+                    Some(2_000_000_001),
+                    None,
+                    Some(4_000_000_000),
+                ])),
+            ),
+            (
+                Arc::new(
+                    TimestampSecondArray::from(vec![Some(1), Some(2), None, Some(4)])
+                        .with_timezone("+01:00"),
+                ),
+                Arc::new(
+                    TimestampMicrosecondArray::from(vec![
+                        Some(1_000_000),
+                        Some(2_000_001),
+                        None,
+                        Some(4_000_000),
+                    ])
+                    .with_timezone("+01:00"),
+                ),
+            ),
+            (
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("b"),
+                    None,
+                    Some("d"),
+                ])),
+                Arc::new(LargeStringArray::from(vec![
+                    Some("a"),
+                    Some("bb"),
+                    None,
+                    Some("d"),
+                ])),
+            ),
+            (
+                Arc::new(BinaryArray::from(vec![
+                    Some(b"a".as_ref()),
+                    Some(b"b"),
+                    None,
+                    Some(b"d"),
+                ])),
+                Arc::new(LargeBinaryArray::from(vec![
+                    Some(b"a".as_ref()),
+                    Some(b"bb"),
+                    None,
+                    Some(b"d"),
+                ])),
+            ),
+            (
+                Arc::new(
+                    vec![Some("a"), Some("b"), None, Some("d")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("bb"),
+                    None,
+                    Some("d"),
+                ])),
+            ),
+            (
+                Arc::new(
+                    vec![Some("a"), Some("b"), None, Some("d")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int8Type>>(),
+                ),
+                Arc::new(
+                    vec![Some("a"), Some("bb"), None, Some("d")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+            ),
+            (
+                Arc::new(
+                    vec![Some("a"), Some("b"), None, Some("d")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int8Type>>(),
+                ),
+                Arc::new(LargeStringArray::from(vec![
+                    Some("a"),
+                    Some("bb"),
+                    None,
+                    Some("d"),
+                ])),
+            ),
+        ];
+        for (left_keys, right_keys) in cases {
+            for swap in [false, true] {
+                let (k1, k2) = if swap {
+                    (right_keys.clone(), left_keys.clone())
+                } else {
+                    (left_keys.clone(), right_keys.clone())
+                };
+                let description = format!("{} and {}", k1.data_type(), k2.data_type());
+                let left = build_key_batch(("k1", k1), ("n1", vec![1, 2, 3, 4]))?;
+                let right = build_key_batch(("k2", k2), ("n2", vec![10, 20, 30, 40]))?;
+                assert_eq!(
+                    inner_join_pairs(left, right, task_ctx.clone()).await?,
+                    vec![(1, 10), (4, 40)],
+                    "{description}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn partitioned_join_coerced_key_types() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let partition_count = 4;
+
+        let cases: Vec<(ArrayRef, ArrayRef)> = vec![
+            (
+                Arc::new(TimestampMillisecondArray::from_iter_values(
+                    (1..=16).map(|i| i * 1_000),
+                )),
+                Arc::new(TimestampNanosecondArray::from_iter_values(
+                    (1..=16).map(|i| i * 1_000_000_000),
+                )),
+            ),
+            (
+                Arc::new(
+                    Decimal128Array::from_iter_values((1..=16).map(|i| i * 100))
+                        .with_precision_and_scale(10, 2)?,
+                ),
+                Arc::new(
+                    Decimal128Array::from_iter_values((1..=16).map(|i| i * 10_000))
+                        .with_precision_and_scale(12, 4)?,
+                ),
+            ),
+        ];
+        for (left_keys, right_keys) in cases {
+            let description =
+                format!("{} and {}", left_keys.data_type(), right_keys.data_type());
+            let left = build_key_batch(("k1", left_keys), ("n1", (1..=16).collect()))?;
+            let right = build_key_batch(
+                ("k2", right_keys),
+                ("n2", (1..=16).map(|i| i * 10).collect()),
+            )?;
+            let on = vec![(
+                Arc::new(Column::new_with_schema("k1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("k2", &right.schema())?) as _,
+            )];
+            let left = Arc::new(MemoryExec::try_new(&[vec![left]], left.schema(), None)?);
+            let right =
+                Arc::new(MemoryExec::try_new(&[vec![right]], right.schema(), None)?);
+            let join = HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on,
+                None,
+                &JoinType::Inner,
+                PartitionMode::Partitioned,
+                false,
+            )?;
+
+            // Repartition the inputs the way the planner does, by the keys of
+            // the distribution the join requires
+            let children = join
+                .required_input_distribution()
+                .into_iter()
+                .zip([left as Arc<dyn ExecutionPlan>, right])
+                .map(|(distribution, child)| match distribution {
+                    Distribution::HashPartitioned(exprs) => {
+                        Ok(Arc::new(RepartitionExec::try_new(
+                            child,
+                            Partitioning::Hash(exprs, partition_count),
+                        )?) as Arc<dyn ExecutionPlan>)
+                    }
+                    _ => panic!("expected hash partitioned inputs"),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let join = Arc::new(join).with_new_children(children)?;
+
+            let mut pairs = vec![];
+            for partition in 0..partition_count {
+                let stream = join.execute(partition, task_ctx.clone())?;
+                for batch in common::collect(stream).await? {
+                    let n1 = as_primitive_array::<Int32Type>(batch.column(1))?;
+                    let n2 = as_primitive_array::<Int32Type>(batch.column(3))?;
+                    pairs.extend(
+                        n1.values().iter().copied().zip(n2.values().iter().copied()),
+                    );
+                }
+            }
+            pairs.sort_unstable();
+            assert_eq!(
+                pairs,
+                (1..=16).map(|i| (i, i * 10)).collect::<Vec<_>>(),
+                "{description}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_incomparable_key_types() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let cases: Vec<(ArrayRef, ArrayRef, &str)> = vec![
+            (
+                Arc::new(TimestampMillisecondArray::from(vec![1_000])),
+                Arc::new(TimestampMillisecondArray::from(vec![1_000]).with_timezone("UTC")),
+                "Join key k1@0 of type Timestamp(Millisecond, None) can not be compared with join key k2@0 of type Timestamp(Millisecond, Some(\"UTC\"))",
+            ),
+            (
+                Arc::new(StringArray::from(vec!["1"])),
+                Arc::new(BinaryArray::from(vec![b"1".as_ref()])),
+                "Join key k1@0 of type Utf8 can not be compared with join key k2@0 of type Binary",
+            ),
+            (
+                Arc::new(
+                    vec!["1"]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+                Arc::new(Int32Array::from(vec![1])),
+                "Join key k1@0 of type Dictionary(Int32, Utf8) can not be compared with join key k2@0 of type Int32",
+            ),
+        ];
+        for (k1, k2, expected) in cases {
+            let left = build_key_batch(("k1", k1), ("n1", vec![1]))?;
+            let right = build_key_batch(("k2", k2), ("n2", vec![10]))?;
+            let err = inner_join_pairs(left, right, task_ctx.clone())
+                .await
+                .unwrap_err();
+            assert_contains!(err.to_string(), expected);
+        }
+
+        Ok(())
+    }
+
+    /// Runs `outer NOT IN (subquery)` as a null-aware anti join of `join_type`,
+    /// with the subquery side split into the partitions `subquery`, and returns
+    /// the sorted positions (starting at 1) of the produced `outer` rows
+    async fn null_aware_anti_join(
+        outer: Vec<Option<i32>>,
+        subquery: Vec<Vec<Option<i32>>>,
+        join_type: JoinType,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<i32>> {
+        let n = (1..=outer.len() as i32).collect::<Vec<_>>();
+        let outer =
+            build_key_batch(("k1", Arc::new(Int32Array::from(outer))), ("n1", n))?;
+        let outer_schema = outer.schema();
+        let outer = Arc::new(MemoryExec::try_new(&[vec![outer]], outer_schema, None)?);
+        let subquery = subquery
+            .into_iter()
+            .map(|keys| {
+                let n = vec![0; keys.len()];
+                let batch =
+                    build_key_batch(("k2", Arc::new(Int32Array::from(keys))), ("n2", n))?;
+                Ok(vec![batch])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let subquery_schema = subquery[0][0].schema();
+        let subquery = Arc::new(MemoryExec::try_new(&subquery, subquery_schema, None)?);
+
+        let (left, right): (Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>) =
+            match join_type {
+                JoinType::LeftAnti => (outer, subquery),
+                _ => (subquery, outer),
+            };
+        let on = vec![(
+            Arc::new(Column::new_with_schema(
+                left.schema().field(0).name(),
+                &left.schema(),
+            )?) as _,
+            Arc::new(Column::new_with_schema(
+                right.schema().field(0).name(),
+                &right.schema(),
+            )?) as _,
+        )];
+        let join = join(left, right, on, &join_type, false)?.with_null_aware(true)?;
+        assert_contains!(displayable(&join).one_line().to_string(), "null_aware=true");
+
+        let batches = crate::physical_plan::collect(Arc::new(join), context).await?;
+        let mut rows = vec![];
+        for batch in batches {
+            let n = as_primitive_array::<Int32Type>(batch.column(1))?;
+            rows.extend(n.values().iter().copied());
+        }
+        rows.sort_unstable();
+        Ok(rows)
+    }
+
+    #[tokio::test]
+    async fn join_null_aware_anti() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let cases = vec![
+            // no nulls
+            (
+                vec![Some(1), Some(2), Some(3)],
+                vec![vec![Some(2), Some(4)]],
+                vec![1, 3],
+            ),
+            // null on the outer side only
+            (
+                vec![Some(1), Some(2), None, Some(3)],
+                vec![vec![Some(2), Some(4)]],
+                vec![1, 4],
+            ),
+            // null on the subquery side only
+            (
+                vec![Some(1), Some(2), Some(3)],
+                vec![vec![Some(2), None]],
+                vec![],
+            ),
+            // nulls on both sides
+            (
+                vec![Some(1), None, Some(3)],
+                vec![vec![None, Some(4)]],
+                vec![],
+            ),
+            // null in one of several subquery partitions
+            (
+                vec![Some(1), Some(2), Some(3)],
+                vec![vec![Some(2)], vec![Some(4)], vec![None]],
+                vec![],
+            ),
+            // empty subquery side
+            (vec![Some(1), None, Some(3)], vec![vec![]], vec![1, 2, 3]),
+        ];
+
+        // the outer side is the build side of `LeftAnti` and the probe side of
+        // `RightAnti` joins
+        for join_type in [JoinType::LeftAnti, JoinType::RightAnti] {
+            for (outer, subquery, expected) in &cases {
+                let rows = null_aware_anti_join(
+                    outer.clone(),
+                    subquery.clone(),
+                    join_type,
+                    task_ctx.clone(),
+                )
+                .await?;
+                assert_eq!(
+                    &rows, expected,
+                    "{join_type} with outer {outer:?} and subquery {subquery:?}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_null_aware_unsupported() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![1]), ("c1", &vec![1]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![1]), ("c2", &vec![1]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let err = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .with_null_aware(true)
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Null-aware join is only supported for anti joins, not Inner"
+        );
+
+        let err = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::LeftAnti,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_null_aware(true)
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Null-aware anti join is only supported in CollectLeft mode, not Partitioned"
+        );
+
+        Ok(())
+    }
+
+    fn build_nullable_keys_table(
+        a: (&str, Vec<Option<i32>>),
+        b: (&str, Vec<Option<i32>>),
+        n: (&str, Vec<i32>),
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(a.0, DataType::Int32, true),
+            Field::new(b.0, DataType::Int32, true),
+            Field::new(n.0, DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(a.1)),
+                Arc::new(Int32Array::from(b.1)),
+                Arc::new(Int32Array::from(n.1)),
+            ],
+        )?;
+        Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?))
+    }
+
+    #[tokio::test]
+    async fn join_null_equals_null_per_key() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let left = build_nullable_keys_table(
+            ("a1", vec![Some(1), Some(1), None, None]),
+            ("b1", vec![None, Some(2), None, Some(3)]),
+            ("n1", vec![1, 2, 3, 4]),
+        )?;
+        let right = build_nullable_keys_table(
+            ("a2", vec![Some(1), Some(1), None, None]),
+            ("b2", vec![None, Some(2), None, Some(3)]),
+            ("n2", vec![10, 20, 30, 40]),
+        )?;
+        let on = vec![
+            (
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+            ),
+            (
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            ),
+        ];
+
+        // `a1 = a2 AND b1 IS NOT DISTINCT FROM b2` and the other combinations
+        let cases = [
+            (vec![false, false], vec![(2, 20)], vec![1, 3, 4]),
+            (vec![false, true], vec![(1, 10), (2, 20)], vec![3, 4]),
+            (vec![true, false], vec![(2, 20), (4, 40)], vec![1, 3]),
+            (
+                vec![true, true],
+                vec![(1, 10), (2, 20), (3, 30), (4, 40)],
+                vec![],
+            ),
+        ];
+        for (null_equals_null, expected_pairs, expected_anti) in cases {
+            let join_exec = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )?
+            .with_null_equals_null_per_key(null_equals_null.clone())?;
+            assert_eq!(
+                join_exec.null_equals_null(),
+                null_equals_null.iter().all(|n| *n)
+            );
+            let batches =
+                common::collect(join_exec.execute(0, task_ctx.clone())?).await?;
+            let mut pairs = vec![];
+            for batch in batches {
+                let n1 = as_primitive_array::<Int32Type>(batch.column(2))?;
+                let n2 = as_primitive_array::<Int32Type>(batch.column(5))?;
+                pairs
+                    .extend(n1.values().iter().copied().zip(n2.values().iter().copied()));
+            }
+            pairs.sort_unstable();
+            assert_eq!(
+                pairs, expected_pairs,
+                "null_equals_null={null_equals_null:?}"
+            );
+
+            // the rows whose keys can't match are still produced by anti joins
+            let join_exec = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::LeftAnti,
+                false,
+            )?
+            .with_null_equals_null_per_key(null_equals_null.clone())?;
+            let batches =
+                common::collect(join_exec.execute(0, task_ctx.clone())?).await?;
+            let mut n1 = vec![];
+            for batch in batches {
+                let column = as_primitive_array::<Int32Type>(batch.column(2))?;
+                n1.extend(column.values().iter().copied());
+            }
+            n1.sort_unstable();
+            assert_eq!(n1, expected_anti, "null_equals_null={null_equals_null:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_null_equals_null_per_key_plan() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![1]), ("c1", &vec![1]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![1]), ("c2", &vec![1]));
+        let on = vec![
+            (
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+            ),
+            (
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            ),
+        ];
+
+        // the single flag of the constructor applies to all the keys
+        let join_exec = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            true,
+        )?;
+        assert_eq!(join_exec.null_equals_null_per_key(), &[true, true]);
+        assert!(!displayable(&join_exec)
+            .one_line()
+            .to_string()
+            .contains("null_equals_null"));
+
+        let join_exec = join_exec.with_null_equals_null_per_key(vec![false, true])?;
+        assert!(!join_exec.null_equals_null());
+        assert_contains!(
+            displayable(&join_exec).one_line().to_string(),
+            "null_equals_null=[false, true]"
+        );
+
+        let err = join(left, right, on, &JoinType::Inner, false)?
+            .with_null_equals_null_per_key(vec![true])
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Expected null_equals_null for 2 join keys, got 1"
+        );
+
+        Ok(())
+    }
+
+    fn build_semi_anti_left_table() -> Arc<dyn ExecutionPlan> {
+        // just two line match
+        // b1 = 10
+        build_table(
+            ("a1", &vec![1, 3, 5, 7, 9, 11, 13]),
+            ("b1", &vec![1, 3, 5, 7, 8, 8, 10]),
+            ("c1", &vec![10, 30, 50, 70, 90, 110, 130]),
+        )
+    }
+
+    fn build_semi_anti_right_table() -> Arc<dyn ExecutionPlan> {
+        // just two line match
+        // b2 = 10
+        build_table(
+            ("a2", &vec![8, 12, 6, 2, 10, 4]),
+            ("b2", &vec![8, 10, 6, 2, 10, 4]),
+            ("c2", &vec![20, 40, 60, 80, 100, 120]),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_left_semi(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left semi join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::LeftSemi, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        // ignore the order
+        let expected = vec![
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
             "+----+----+-----+",
             "| 11 | 8  | 110 |",
             "| 13 | 10 | 130 |",
             "| 9  | 8  | 90  |",
             "+----+----+-----+",
         ];
-        assert_batches_sorted_eq!(expected, &batches);
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_left_semi_anti_duplicate_keys() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6]),
+            ("b1", &vec![1, 1, 1, 2, 3, 1]),
+            ("c1", &vec![10, 20, 30, 40, 50, 60]),
+        );
+        let right = build_table_i32(
+            ("a2", &vec![1, 2, 3, 4, 5]),
+            ("b2", &vec![1, 3, 1, 3, 4]),
+            ("c2", &vec![0, 0, 0, 0, 0]),
+        );
+        let schema = right.schema();
+        // the same rows in one or two probe batches
+        let rights: Vec<Arc<dyn ExecutionPlan>> = vec![
+            Arc::new(MemoryExec::try_new(
+                &[vec![right.clone()]],
+                schema.clone(),
+                None,
+            )?),
+            Arc::new(MemoryExec::try_new(
+                &[vec![right.slice(0, 3), right.slice(3, 2)]],
+                schema.clone(),
+                None,
+            )?),
+        ];
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &schema)?) as _,
+        )];
+
+        for (join_type, expected) in [
+            (
+                JoinType::LeftSemi,
+                vec![
+                    "+----+----+----+",
+                    "| a1 | b1 | c1 |",
+                    "+----+----+----+",
+                    "| 1  | 1  | 10 |",
+                    "| 2  | 1  | 20 |",
+                    "| 3  | 1  | 30 |",
+                    "| 5  | 3  | 50 |",
+                    "| 6  | 1  | 60 |",
+                    "+----+----+----+",
+                ],
+            ),
+            (
+                JoinType::LeftAnti,
+                vec![
+                    "+----+----+----+",
+                    "| a1 | b1 | c1 |",
+                    "+----+----+----+",
+                    "| 4  | 2  | 40 |",
+                    "+----+----+----+",
+                ],
+            ),
+        ] {
+            for right in &rights {
+                let session_ctx = SessionContext::new();
+                let join =
+                    join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+                let stream = join.execute(0, session_ctx.task_ctx())?;
+                let batches = common::collect(stream).await?;
+                assert_batches_sorted_eq!(expected, &batches);
+
+                // the keys of the 5 build rows with a match are compared once,
+                // even by later probe batches
+                let metrics = join.metrics().unwrap();
+                assert_eq!(
+                    metrics
+                        .sum_by_name("probe_candidates")
+                        .map(|v| v.as_usize()),
+                    Some(5)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_left_semi_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+
+        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 10
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Right,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::LeftSemi,
+            false,
+        )?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header.clone(), vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 11 | 8  | 110 |",
+            "| 13 | 10 | 130 |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 > 10
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::LeftSemi, false)?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 13 | 10 | 130 |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_semi(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+
+        // left_table right semi join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::RightSemi, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 10 | 10 | 100 |",
+            "| 12 | 10 | 40  |",
+            "| 8  | 8  | 20  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_semi_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+
+        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(9)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::RightSemi,
+            false,
+        )?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 10 | 10 | 100 |",
+            "| 12 | 10 | 40  |",
+            "| 8  | 8  | 20  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(11)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let join =
+            join_with_filter(left, right, on, filter, &JoinType::RightSemi, false)?;
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 10 | 10 | 100 |",
+            "| 12 | 10 | 40  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_left_anti(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left anti join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::LeftAnti, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 1  | 10 |",
+            "| 3  | 3  | 30 |",
+            "| 5  | 5  | 50 |",
+            "| 7  | 7  | 70 |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_left_anti_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2!=8
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Right,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::LeftAnti,
+            false,
+        )?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 1  | 1  | 10  |",
+            "| 11 | 8  | 110 |",
+            "| 3  | 3  | 30  |",
+            "| 5  | 5  | 50  |",
+            "| 7  | 7  | 70  |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 13
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::LeftAnti, false)?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 1  | 1  | 10  |",
+            "| 11 | 8  | 110 |",
+            "| 3  | 3  | 30  |",
+            "| 5  | 5  | 50  |",
+            "| 7  | 7  | 70  |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_anti(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::RightAnti, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 2  | 2  | 80  |",
+            "| 4  | 4  | 120 |",
+            "| 6  | 6  | 60  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_anti_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table right anti join right_table on left_table.b1 = right_table.b2 and left_table.a1!=13
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(13)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices,
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::RightAnti,
+            false,
+        )?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 10 | 10 | 100 |",
+            "| 12 | 10 | 40  |",
+            "| 2  | 2  | 80  |",
+            "| 4  | 4  | 120 |",
+            "| 6  | 6  | 60  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // left_table right anti join right_table on left_table.b1 = right_table.b2 and right_table.b2!=8
+        let column_indices = vec![ColumnIndex {
+            index: 1,
+            side: JoinSide::Right,
+        }];
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let join =
+            join_with_filter(left, right, on, filter, &JoinType::RightAnti, false)?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 2  | 2  | 80  |",
+            "| 4  | 4  | 120 |",
+            "| 6  | 6  | 60  |",
+            "| 8  | 8  | 20  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Right, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn partitioned_join_right_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) =
+            partitioned_join_collect(left, right, on, &JoinType::Right, false, task_ctx)
+                .await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_full_one(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Full, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_with_hash_collision() -> Result<()> {
+        let mut hashmap_left = JoinHashMap::with_capacity(2);
+        let left = build_table_i32(
+            ("a", &vec![10, 20]),
+            ("x", &vec![100, 200]),
+            ("y", &vec![200, 300]),
+        );
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let hashes_buff = &mut vec![0; left.num_rows()];
+        let hashes =
+            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
+
+        // Create hash collisions (same hashes): both hashes lead to the list of
+        // both rows
+        hashmap_left
+            .map
+            .insert(hashes[0], (hashes[0], 2), |(h, _)| *h);
+        hashmap_left
+            .map
+            .insert(hashes[1], (hashes[1], 2), |(h, _)| *h);
+        hashmap_left.next = vec![0, 1];
+
+        let right = build_table_i32(
+            ("a", &vec![10, 20]),
+            ("b", &vec![0, 0]),
+            ("c", &vec![30, 40]),
+        );
+
+        let metrics = ExecutionPlanMetricsSet::new();
+        let candidates = MetricBuilder::new(&metrics).counter("probe_candidates", 0);
+        let matches = MetricBuilder::new(&metrics).counter("probe_matches", 0);
+
+        let left_data = (hashmap_left, left);
+        let (l, r) = build_equal_condition_join_indices::<UInt32Type, _>(
+            &left_data.0,
+            std::slice::from_ref(&left_data.1),
+            &right,
+            &[evaluate_join_keys(
+                &[Arc::new(Column::new("a", 0)) as _],
+                &[],
+                &left_data.1,
+            )?],
+            None,
+            None,
+            Some((&candidates, &matches)),
+            None,
+            &[Arc::new(Column::new("a", 0)) as _],
+            &[],
+            &random_state,
+            &[false],
+            &mut vec![0; right.num_rows()],
+            None,
+            false,
+            false,
+        )?;
+
+        let mut left_ids = UInt64Builder::with_capacity(0);
+        left_ids.append_value(0);
+        left_ids.append_value(1);
+
+        let mut right_ids = UInt32Builder::with_capacity(0);
+        right_ids.append_value(0);
+        right_ids.append_value(1);
+
+        assert_eq!(left_ids.finish(), l);
+
+        assert_eq!(right_ids.finish(), r);
+
+        // both build rows are compared with every probe row, only one of them
+        // has the same join key
+        assert_eq!(candidates.value(), 4);
+        assert_eq!(matches.value(), 2);
+        assert!(candidates.value() > matches.value());
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_indices_with_u64_probe_indices() -> Result<()> {
+        let left = build_table_i32(
+            ("a", &vec![10, 20, 30]),
+            ("x", &vec![100, 200, 300]),
+            ("y", &vec![200, 300, 400]),
+        );
+        let right = build_table_i32(
+            ("a", &vec![30, 10, 40, 10]),
+            ("b", &vec![0, 0, 0, 0]),
+            ("c", &vec![30, 40, 50, 60]),
+        );
+        let on: [Arc<dyn PhysicalExpr>; 1] = [Arc::new(Column::new("a", 0))];
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let mut hashmap = JoinHashMap::with_capacity(left.num_rows());
+        update_hash(
+            &on,
+            &[],
+            &[false],
+            &left,
+            &mut hashmap,
+            0,
+            &random_state,
+            &mut vec![0; left.num_rows()],
+        )?;
+
+        for radix_probe in [false, true] {
+            let (l, r) = build_equal_condition_join_indices::<UInt64Type, _>(
+                &hashmap,
+                std::slice::from_ref(&left),
+                &right,
+                &[evaluate_join_keys(&on, &[], &left)?],
+                None,
+                None,
+                None,
+                None,
+                &on,
+                &[],
+                &random_state,
+                &[false],
+                &mut vec![],
+                None,
+                radix_probe,
+                false,
+            )?;
+            assert_eq!(l, UInt64Array::from(vec![2, 0, 0]));
+            assert_eq!(r, UInt64Array::from(vec![0, 1, 3]));
+        }
+
+        // indices too narrow for the probe batch are an error rather than
+        // being truncated
+        let right = build_table_i32(
+            ("a", &vec![10; 300]),
+            ("b", &vec![0; 300]),
+            ("c", &vec![0; 300]),
+        );
+        let err = build_equal_condition_join_indices::<UInt8Type, _>(
+            &hashmap,
+            std::slice::from_ref(&left),
+            &right,
+            &[evaluate_join_keys(&on, &[], &left)?],
+            None,
+            None,
+            None,
+            None,
+            &on,
+            &[],
+            &random_state,
+            &[false],
+            &mut vec![],
+            None,
+            false,
+            false,
+        )
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Probe batch of 300 rows can not be indexed with UInt8"
+        );
+
+        Ok(())
+    }
+
+    /// Returns the struct keys of the `a` and `b` fields, null where `nulls` is
+    /// true
+    fn struct_keys(a: Vec<i32>, b: Vec<&str>, nulls: Vec<bool>) -> Result<ArrayRef> {
+        let struct_array = StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int32, true)),
+                Arc::new(Int32Array::from(a)) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Utf8, true)),
+                Arc::new(StringArray::from(b)) as ArrayRef,
+            ),
+        ]);
+        Ok(compute::nullif(&struct_array, &BooleanArray::from(nulls))?)
+    }
+
+    #[tokio::test]
+    async fn join_struct() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let left = build_key_batch(
+            (
+                "k1",
+                struct_keys(
+                    vec![1, 1, 2, 3],
+                    vec!["x", "y", "x", "x"],
+                    vec![false, false, false, true],
+                )?,
+            ),
+            ("n1", vec![1, 2, 3, 4]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                struct_keys(
+                    vec![1, 2, 2, 3],
+                    vec!["y", "x", "y", "x"],
+                    vec![false, false, false, true],
+                )?,
+            ),
+            ("n2", vec![10, 20, 30, 40]),
+        )?;
+
+        let matches = vec![(Some(2), Some(10)), (Some(3), Some(20))];
+        let mut null_matches = matches.clone();
+        null_matches.push((Some(4), Some(40)));
+        for (null_equals_null, expected) in [(false, matches), (true, null_matches)] {
+            let pairs = join_pairs(
+                left.clone(),
+                right.clone(),
+                &JoinType::Inner,
+                null_equals_null,
+                false,
+                task_ctx.clone(),
+            )
+            .await?;
+            assert_eq!(pairs, expected, "null_equals_null={null_equals_null}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_struct_different_field_nullability() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let left = build_key_batch(
+            (
+                "k1",
+                struct_keys(
+                    vec![1, 1, 2],
+                    vec!["x", "y", "x"],
+                    vec![false, false, false],
+                )?,
+            ),
+            ("n1", vec![1, 2, 3]),
+        )?;
+        // The same struct type but for the fields not being nullable
+        let right_struct = StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int32, false)),
+                Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Utf8, false)),
+                Arc::new(StringArray::from(vec!["y", "x"])) as ArrayRef,
+            ),
+        ]);
+        let right =
+            build_key_batch(("k2", Arc::new(right_struct)), ("n2", vec![10, 20]))?;
+
+        assert_eq!(
+            inner_join_pairs(left, right, task_ctx).await?,
+            vec![(2, 10), (3, 20)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_coerced_key_overflow() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        // The second key overflows the nanoseconds of the common type, and
+        // must not be taken for a null matching the null key of the right side
+        let left = build_key_batch(
+            (
+                "k1",
+                Arc::new(TimestampSecondArray::from(vec![1, i64::MAX / 10])),
+            ),
+            ("n1", vec![1, 2]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    Some(1_000_000_000),
+                    None,
+                ])),
+            ),
+            ("n2", vec![10, 20]),
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("k1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("k2", &right.schema())?) as _,
+        )];
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left.clone()]],
+            left.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right.clone()]],
+            right.schema(),
+            None,
+        )?);
+
+        let err = join_collect(left, right, on, &JoinType::Inner, true, task_ctx)
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "Overflow");
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_struct_with_hash_collision() -> Result<()> {
+        let mut hashmap_left = JoinHashMap::with_capacity(2);
+        let left = build_key_batch(
+            (
+                "k1",
+                struct_keys(vec![1, 1], vec!["x", "y"], vec![false, false])?,
+            ),
+            ("n1", vec![1, 2]),
+        )?;
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let hashes_buff = &mut vec![0; left.num_rows()];
+        let hashes =
+            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
+
+        // Create hash collisions, both distinct structs are in the same bucket
+        hashmap_left
+            .map
+            .insert(hashes[0], (hashes[0], 2), |(h, _)| *h);
+        hashmap_left
+            .map
+            .insert(hashes[1], (hashes[1], 2), |(h, _)| *h);
+        hashmap_left.next = vec![0, 1];
+
+        let right = build_key_batch(
+            ("k2", struct_keys(vec![1], vec!["y"], vec![false])?),
+            ("n2", vec![10]),
+        )?;
+
+        let (l, r) = build_equal_condition_join_indices::<UInt32Type, _>(
+            &hashmap_left,
+            std::slice::from_ref(&left),
+            &right,
+            &[evaluate_join_keys(
+                &[Arc::new(Column::new("k1", 0)) as _],
+                &[],
+                &left,
+            )?],
+            None,
+            None,
+            None,
+            None,
+            &[Arc::new(Column::new("k2", 0)) as _],
+            &[],
+            &random_state,
+            &[false],
+            &mut vec![0; right.num_rows()],
+            None,
+            false,
+            false,
+        )?;
+
+        // Only the second struct of the bucket is equal to the probed one
+        assert_eq!(l, UInt64Array::from(vec![1]));
+        assert_eq!(r, UInt32Array::from(vec![0]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_duplicated_column_names() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a", &vec![1, 2, 3]),
+            ("b", &vec![4, 5, 7]),
+            ("c", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30]),
+            ("b", &vec![1, 2, 7]),
+            ("c", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            // join on a=b so there are duplicate column names on unjoined columns
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+----+---+----+",
+            "| a | b | c | a  | b | c  |",
+            "+---+---+---+----+---+----+",
+            "| 1 | 4 | 7 | 10 | 1 | 70 |",
+            "| 2 | 5 | 8 | 20 | 2 | 80 |",
+            "+---+---+---+----+---+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn prepare_join_filter() -> JoinFilter {
+        let column_indices = vec![
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("c", DataType::Int32, true),
+            Field::new("c", DataType::Int32, true),
+        ]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("c", 0)),
+            Operator::Gt,
+            Arc::new(Column::new("c", 1)),
+        )) as Arc<dyn PhysicalExpr>;
+
+        JoinFilter::new(filter_expression, column_indices, intermediate_schema)
+    }
+
+    /// Returns the number of pairs of rows the join filter of `join` was
+    /// evaluated on, and the number of them it kept
+    fn filter_selectivity(join: &HashJoinExec) -> (usize, usize) {
+        let metrics = join.metrics().unwrap();
+        let count = |name| metrics.sum_by_name(name).unwrap().as_usize();
+        (count("filter_input_rows"), count("filter_output_rows"))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_inner_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+        let filter = prepare_join_filter();
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "+---+---+---+----+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // two of the four pairs of rows with equal join keys pass the filter
+        assert_eq!(filter_selectivity(&join), (4, 2));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_left_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+        let filter = prepare_join_filter();
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::Left, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "| 0 | 4 | 7 |    |   |   |",
+            "| 1 | 5 | 8 |    |   |   |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "| 2 | 8 | 1 |    |   |   |",
+            "+---+---+---+----+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert_eq!(filter_selectivity(&join), (4, 2));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_right_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+        let filter = prepare_join_filter();
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::Right, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "|   |   |   | 30 | 3 | 6 |",
+            "|   |   |   | 40 | 4 | 4 |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "+---+---+---+----+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert_eq!(filter_selectivity(&join), (4, 2));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_full_with_filter(
+        #[values(0, usize::MAX)] radix_probe_threshold: usize,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(radix_probe_threshold);
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+        let filter = prepare_join_filter();
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::Full, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "|   |   |   | 30 | 3 | 6 |",
+            "|   |   |   | 40 | 4 | 4 |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "| 0 | 4 | 7 |    |   |   |",
+            "| 1 | 5 | 8 |    |   |   |",
+            "| 2 | 8 | 1 |    |   |   |",
+            "+---+---+---+----+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert_eq!(filter_selectivity(&join), (4, 2));
+
+        // the unmatched rows of both sides are padded with nulls
+        let metrics = join.metrics().unwrap();
+        let count = |name| metrics.sum_by_name(name).unwrap().as_usize();
+        assert_eq!(count("matched_output_rows"), 2);
+        assert_eq!(count("unmatched_output_rows"), 5);
+        assert_eq!(
+            count("matched_output_rows") + count("unmatched_output_rows"),
+            metrics.output_rows().unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_null_filter_result() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_nullable_keys_table(
+            ("a1", vec![Some(1), Some(1), Some(2)]),
+            ("b1", vec![Some(1), None, Some(5)]),
+            ("n1", vec![1, 2, 3]),
+        )?;
+        let right = build_nullable_keys_table(
+            ("a2", vec![Some(1), Some(1), Some(3)]),
+            ("b2", vec![Some(2), None, Some(1)]),
+            ("n2", vec![10, 20, 30]),
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+        )];
+
+        // left_table join right_table on a1 = a2 and b1 < b2, which is NULL
+        // for all the row pairs of matching keys but (1, 10)
+        let column_indices = vec![
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("x", DataType::Int32, true),
+            Field::new("y", DataType::Int32, true),
+        ]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Lt,
+            Arc::new(Column::new("y", 1)),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let cases = [
+            (JoinType::Inner, vec![(Some(1), Some(10))]),
+            (
+                JoinType::Left,
+                vec![(Some(1), Some(10)), (Some(2), None), (Some(3), None)],
+            ),
+            (
+                JoinType::Right,
+                vec![(None, Some(20)), (None, Some(30)), (Some(1), Some(10))],
+            ),
+            (
+                JoinType::Full,
+                vec![
+                    (None, Some(20)),
+                    (None, Some(30)),
+                    (Some(1), Some(10)),
+                    (Some(2), None),
+                    (Some(3), None),
+                ],
+            ),
+        ];
+        for (join_type, expected) in cases {
+            let join = join_with_filter(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                filter.clone(),
+                &join_type,
+                false,
+            )?;
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            let mut pairs = vec![];
+            for batch in batches {
+                let n1 = as_primitive_array::<Int32Type>(batch.column(2))?;
+                let n2 = as_primitive_array::<Int32Type>(batch.column(5))?;
+                pairs.extend(n1.iter().zip(n2.iter()));
+            }
+            pairs.sort_unstable();
+            assert_eq!(pairs, expected, "{join_type} join");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_date32() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("date", DataType::Date32, false),
+            Field::new("n", DataType::Int32, false),
+        ]));
+
+        let dates: ArrayRef = Arc::new(Date32Array::from(vec![19107, 19108, 19109]));
+        let n: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![dates, n])?;
+        let left =
+            Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None).unwrap());
+
+        let dates: ArrayRef = Arc::new(Date32Array::from(vec![19108, 19108, 19109]));
+        let n: ArrayRef = Arc::new(Int32Array::from(vec![4, 5, 6]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![dates, n])?;
+        let right = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap());
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("date", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("date", &right.schema()).unwrap()) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+------------+---+------------+---+",
+            "| date       | n | date       | n |",
+            "+------------+---+------------+---+",
+            "| 2022-04-26 | 2 | 2022-04-26 | 4 |",
+            "| 2022-04-26 | 2 | 2022-04-26 | 5 |",
+            "| 2022-04-27 | 3 | 2022-04-27 | 6 |",
+            "+------------+---+------------+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn build_key_batch(
+        key: (&str, ArrayRef),
+        n: (&str, Vec<i32>),
+    ) -> Result<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(key.0, key.1.data_type().clone(), true),
+            Field::new(n.0, DataType::Int32, false),
+        ]));
+        let n: ArrayRef = Arc::new(Int32Array::from(n.1));
+        Ok(RecordBatch::try_new(schema, vec![key.1, n])?)
+    }
+
+    /// Returns the sorted `(n1, n2)` pairs of the output of a join of `left`
+    /// and `right` on their `k1` and `k2` keys, `None` for an unmatched row or
+    /// a side missing from the output
+    async fn join_pairs(
+        left: RecordBatch,
+        right: RecordBatch,
+        join_type: &JoinType,
+        null_equals_null: bool,
+        partitioned: bool,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<(Option<i32>, Option<i32>)>> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let on = vec![(
+            Arc::new(Column::new_with_schema("k1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("k2", &right_schema)?) as _,
+        )];
+        let left = Arc::new(MemoryExec::try_new(&[vec![left]], left_schema, None)?);
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], right_schema, None)?);
+
+        let (_, batches) = if partitioned {
+            partitioned_join_collect(
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                context,
+            )
+            .await?
+        } else {
+            join_collect(left, right, on, join_type, null_equals_null, context).await?
+        };
+        let mut pairs = vec![];
+        for batch in batches {
+            let schema = batch.schema();
+            let n = |name: &str| -> Result<Vec<Option<i32>>> {
+                Ok(match schema.index_of(name) {
+                    Ok(i) => as_primitive_array::<Int32Type>(batch.column(i))?
+                        .iter()
+                        .collect(),
+                    Err(_) => vec![None; batch.num_rows()],
+                })
+            };
+            pairs.extend(n("n1")?.into_iter().zip(n("n2")?));
+        }
+        pairs.sort_unstable();
+        Ok(pairs)
+    }
+
+    /// Returns the sorted `(n1, n2)` pairs of the output of an inner join
+    async fn inner_join_pairs(
+        left: RecordBatch,
+        right: RecordBatch,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<(i32, i32)>> {
+        let pairs =
+            join_pairs(left, right, &JoinType::Inner, false, false, context).await?;
+        Ok(pairs
+            .into_iter()
+            .map(|(n1, n2)| (n1.unwrap(), n2.unwrap()))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn join_binary() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let cases = vec![
+            (
+                JoinType::Inner,
+                false,
+                vec![(Some(1), Some(10)), (Some(1), Some(20))],
+            ),
+            (
+                JoinType::Inner,
+                true,
+                vec![
+                    (Some(1), Some(10)),
+                    (Some(1), Some(20)),
+                    (Some(3), Some(30)),
+                ],
+            ),
+            (
+                JoinType::Left,
+                false,
+                vec![
+                    (Some(1), Some(10)),
+                    (Some(1), Some(20)),
+                    (Some(2), None),
+                    (Some(3), None),
+                    (Some(4), None),
+                ],
+            ),
+            (
+                JoinType::Left,
+                true,
+                vec![
+                    (Some(1), Some(10)),
+                    (Some(1), Some(20)),
+                    (Some(2), None),
+                    (Some(3), Some(30)),
+                    (Some(4), None),
+                ],
+            ),
+            (JoinType::LeftSemi, false, vec![(Some(1), None)]),
+            (
+                JoinType::LeftSemi,
+                true,
+                vec![(Some(1), None), (Some(3), None)],
+            ),
+            (
+                JoinType::LeftAnti,
+                false,
+                vec![(Some(2), None), (Some(3), None), (Some(4), None)],
+            ),
+            (
+                JoinType::LeftAnti,
+                true,
+                vec![(Some(2), None), (Some(4), None)],
+            ),
+        ];
+
+        for data_type in [DataType::Binary, DataType::LargeBinary] {
+            let left_keys = BinaryArray::from(vec![
+                Some(&b"a"[..]),
+                Some(&b"b"[..]),
+                None,
+                Some(&b"c"[..]),
+            ]);
+            let right_keys = BinaryArray::from(vec![
+                Some(&b"a"[..]),
+                Some(&b"a"[..]),
+                None,
+                Some(&b"d"[..]),
+            ]);
+            let left = build_key_batch(
+                ("k1", compute::cast(&left_keys, &data_type)?),
+                ("n1", vec![1, 2, 3, 4]),
+            )?;
+            let right = build_key_batch(
+                ("k2", compute::cast(&right_keys, &data_type)?),
+                ("n2", vec![10, 20, 30, 40]),
+            )?;
+
+            for (join_type, null_equals_null, expected) in &cases {
+                let pairs = join_pairs(
+                    left.clone(),
+                    right.clone(),
+                    join_type,
+                    *null_equals_null,
+                    false,
+                    task_ctx.clone(),
+                )
+                .await?;
+                assert_eq!(&pairs, expected, "{data_type} {join_type}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_fixed_size_binary() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let fixed_size_binary_keys = |keys: Vec<Option<[u8; 2]>>| -> Result<ArrayRef> {
+            Ok(Arc::new(
+                FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                    keys.into_iter(),
+                    2,
+                )?,
+            ))
+        };
+        let left = build_key_batch(
+            (
+                "k1",
+                fixed_size_binary_keys(vec![
+                    Some([0, 1]),
+                    Some([0, 2]),
+                    Some([0, 2]),
+                    None,
+                ])?,
+            ),
+            ("n1", vec![1, 2, 3, 4]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                fixed_size_binary_keys(vec![
+                    Some([0, 2]),
+                    Some([0, 2]),
+                    Some([0, 3]),
+                    None,
+                ])?,
+            ),
+            ("n2", vec![10, 20, 30, 40]),
+        )?;
+
+        let matches = vec![
+            (Some(2), Some(10)),
+            (Some(2), Some(20)),
+            (Some(3), Some(10)),
+            (Some(3), Some(20)),
+        ];
+        let mut left_matches = matches.clone();
+        left_matches.extend([(Some(1), None), (Some(4), None)]);
+        left_matches.sort_unstable();
+        let cases = vec![(JoinType::Inner, matches), (JoinType::Left, left_matches)];
+
+        for (join_type, expected) in &cases {
+            for partitioned in [false, true] {
+                let pairs = join_pairs(
+                    left.clone(),
+                    right.clone(),
+                    join_type,
+                    false,
+                    partitioned,
+                    task_ctx.clone(),
+                )
+                .await?;
+                assert_eq!(&pairs, expected, "{join_type} partitioned={partitioned}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Casts the join key, the first column of `batch`, to a string
+    fn cast_key_to_string(batch: &RecordBatch) -> Result<RecordBatch> {
+        let schema = batch.schema();
+        let key = schema.field(0);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(key.name(), DataType::Utf8, true),
+            schema.field(1).clone(),
+        ]));
+        let keys = compute::cast(batch.column(0), &DataType::Utf8)?;
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![keys, batch.column(1).clone()],
+        )?)
+    }
+
+    #[tokio::test]
+    async fn join_decimal256() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let decimal256_keys = |keys: Vec<Option<i128>>| -> Result<ArrayRef> {
+            let keys = keys
+                .into_iter()
+                .map(|v| v.map(i256::from_i128))
+                .collect::<Vec<_>>();
+            Ok(Arc::new(
+                Decimal256Array::from(keys).with_precision_and_scale(40, 2)?,
+            ))
+        };
+        let left = build_key_batch(
+            (
+                "k1",
+                decimal256_keys(vec![Some(100), Some(200), Some(200), None, Some(300)])?,
+            ),
+            ("n1", vec![1, 2, 3, 4, 5]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                decimal256_keys(vec![Some(200), Some(200), Some(300), None, Some(400)])?,
+            ),
+            ("n2", vec![10, 20, 30, 40, 50]),
+        )?;
+
+        let pairs =
+            inner_join_pairs(left.clone(), right.clone(), task_ctx.clone()).await?;
+        let string_pairs = inner_join_pairs(
+            cast_key_to_string(&left)?,
+            cast_key_to_string(&right)?,
+            task_ctx,
+        )
+        .await?;
+
+        assert_eq!(pairs, vec![(2, 10), (2, 20), (3, 10), (3, 20), (5, 30)]);
+        assert_eq!(pairs, string_pairs);
+
+        Ok(())
+    }
+
+    #[test]
+    fn equal_rows_decimal256_scale_mismatch() {
+        let left: ArrayRef = Arc::new(
+            Decimal256Array::from(vec![i256::from_i128(100)])
+                .with_precision_and_scale(40, 2)
+                .unwrap(),
+        );
+        let right: ArrayRef = Arc::new(
+            Decimal256Array::from(vec![i256::from_i128(100)])
+                .with_precision_and_scale(40, 3)
+                .unwrap(),
+        );
+
+        let err = equal_rows(0, 0, &[left], &[right], [false]).unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Inconsistent Decimal data type in hasher, the scale should be same"
+        );
+    }
+
+    #[test]
+    fn join_keys_compared_as_rows() -> Result<()> {
+        let batch = |a: Vec<Option<i32>>, b: Vec<Option<&str>>| {
+            let schema = Schema::new(vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ]);
+            RecordBatch::try_new(
+                Arc::new(schema),
+                vec![
+                    Arc::new(Int32Array::from(a)),
+                    Arc::new(StringArray::from(b)),
+                ],
+            )
+        };
+        let left = batch(
+            vec![Some(1), Some(1), None, Some(2), None],
+            vec![Some("x"), Some("y"), Some("x"), None, None],
+        )?;
+        let right = batch(
+            vec![Some(1), None, Some(2), None, Some(1), Some(3)],
+            vec![Some("x"), Some("x"), None, None, Some("y"), Some("x")],
+        )?;
+        let on: [Arc<dyn PhysicalExpr>; 2] =
+            [Arc::new(Column::new("a", 0)), Arc::new(Column::new("b", 1))];
+        let build_join_values = [evaluate_join_keys(&on, &[], &left)?];
+        assert!(compare_join_keys_as_rows(&build_join_values[0]));
+        let build_join_rows = [convert_join_keys(&build_join_values[0])?];
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+
+        for null_equals_null in [[false, false], [true, false], [true, true]] {
+            let mut hashmap = JoinHashMap::with_capacity(left.num_rows());
+            update_hash(
+                &on,
+                &[],
+                &null_equals_null,
+                &left,
+                &mut hashmap,
+                0,
+                &random_state,
+                &mut vec![0; left.num_rows()],
+            )?;
+            let join = |build_join_rows| {
+                build_equal_condition_join_indices::<UInt32Type, _>(
+                    &hashmap,
+                    std::slice::from_ref(&left),
+                    &right,
+                    &build_join_values,
+                    build_join_rows,
+                    None,
+                    None,
+                    None,
+                    &on,
+                    &[],
+                    &random_state,
+                    &null_equals_null,
+                    &mut vec![],
+                    None,
+                    false,
+                    false,
+                )
+            };
+            let (l, r) = join(Some(build_join_rows.as_slice()))?;
+            // the keys compared one by one match the same rows
+            assert_eq!((l.clone(), r.clone()), join(None)?);
+            let expected: (Vec<u64>, Vec<u32>) = match null_equals_null {
+                [false, false] => (vec![0, 1], vec![0, 4]),
+                [true, false] => (vec![0, 2, 1], vec![0, 1, 4]),
+                _ => (vec![0, 2, 3, 4, 1], vec![0, 1, 2, 3, 4]),
+            };
+            assert_eq!(l, UInt64Array::from(expected.0), "{null_equals_null:?}");
+            assert_eq!(r, UInt32Array::from(expected.1), "{null_equals_null:?}");
+        }
+
+        // float keys are compared one by one, for NaN to be equal to NaN
+        let float_keys: ArrayRef = Arc::new(Float64Array::from(vec![f64::NAN]));
+        assert!(!compare_join_keys_as_rows(&[
+            float_keys.clone(),
+            float_keys
+        ]));
+        Ok(())
+    }
+
+    #[test]
+    fn single_join_key_compared_in_probe_loop() -> Result<()> {
+        let int_keys =
+            |keys: Vec<Option<i64>>| Arc::new(Int64Array::from(keys)) as ArrayRef;
+        let key_columns = [
+            (
+                int_keys(vec![Some(1), Some(2), None, Some(1), Some(3), None]),
+                int_keys(vec![Some(1), None, Some(4), Some(3), Some(1)]),
+            ),
+            (
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    None,
+                    Some(1),
+                    Some(3),
+                    None,
+                ])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    None,
+                    Some(4),
+                    Some(3),
+                    Some(1),
+                ])) as ArrayRef,
+            ),
+        ];
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let on: [Arc<dyn PhysicalExpr>; 1] = [Arc::new(Column::new("k", 0))];
+
+        for (build_keys, probe_keys) in key_columns {
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                "k",
+                build_keys.data_type().clone(),
+                true,
+            )]));
+            let left = RecordBatch::try_new(schema.clone(), vec![build_keys])?;
+            let right = RecordBatch::try_new(schema, vec![probe_keys])?;
+            let build_join_values = [evaluate_join_keys(&on, &[], &left)?];
+            let build_join_rows = [convert_join_keys(&build_join_values[0])?];
+
+            for null_equals_null in [false, true] {
+                let mut hashmap = JoinHashMap::with_capacity(left.num_rows());
+                update_hash(
+                    &on,
+                    &[],
+                    &[null_equals_null],
+                    &left,
+                    &mut hashmap,
+                    0,
+                    &random_state,
+                    &mut vec![0; left.num_rows()],
+                )?;
+                let join = |build_join_rows| {
+                    build_equal_condition_join_indices::<UInt32Type, _>(
+                        &hashmap,
+                        std::slice::from_ref(&left),
+                        &right,
+                        &build_join_values,
+                        build_join_rows,
+                        None,
+                        None,
+                        None,
+                        &on,
+                        &[],
+                        &random_state,
+                        &[null_equals_null],
+                        &mut vec![],
+                        None,
+                        false,
+                        false,
+                    )
+                };
+                let (l, r) = join(None)?;
+                // the same rows as the keys compared as rows, in the same order
+                assert_eq!(
+                    (l.clone(), r.clone()),
+                    join(Some(build_join_rows.as_slice()))?
+                );
+                let expected: (Vec<u64>, Vec<u32>) = if null_equals_null {
+                    (vec![0, 3, 2, 5, 4, 0, 3], vec![0, 0, 1, 1, 3, 4, 4])
+                } else {
+                    (vec![0, 3, 4, 0, 3], vec![0, 0, 3, 4, 4])
+                };
+                assert_eq!(l, UInt64Array::from(expected.0), "{null_equals_null}");
+                assert_eq!(r, UInt32Array::from(expected.1), "{null_equals_null}");
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_single_key() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let int_keys =
+            |keys: Vec<Option<i64>>| Arc::new(Int64Array::from(keys)) as ArrayRef;
+        let string_keys =
+            |keys: Vec<Option<&str>>| Arc::new(StringArray::from(keys)) as ArrayRef;
+        // integer keys are compared in the probe loop, string keys as rows
+        let key_columns = [
+            (
+                int_keys(vec![Some(1), Some(2), None, Some(1), Some(3), None]),
+                int_keys(vec![Some(1), None, Some(4), Some(3), Some(1)]),
+            ),
+            (
+                string_keys(vec![Some("a"), Some("b"), None, Some("a"), Some("c"), None]),
+                string_keys(vec![Some("a"), None, Some("d"), Some("c"), Some("a")]),
+            ),
+        ];
+
+        for (build_keys, probe_keys) in key_columns {
+            let batch = |key: &str, value: &str, keys: ArrayRef| {
+                let schema = Schema::new(vec![
+                    Field::new(key, keys.data_type().clone(), true),
+                    Field::new(value, DataType::Int32, false),
+                ]);
+                let values = Int32Array::from_iter_values(0..keys.len() as i32);
+                RecordBatch::try_new(Arc::new(schema), vec![keys, Arc::new(values)])
+            };
+            let left = batch("k1", "n1", build_keys)?;
+            let right = batch("k2", "n2", probe_keys)?;
+            let on = vec![(
+                Arc::new(Column::new_with_schema("k1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("k2", &right.schema())?) as _,
+            )];
+            let left = Arc::new(MemoryExec::try_new(
+                &[vec![left.clone()]],
+                left.schema(),
+                None,
+            )?);
+            let right = Arc::new(MemoryExec::try_new(
+                &[vec![right.clone()]],
+                right.schema(),
+                None,
+            )?);
+
+            for null_equals_null in [false, true] {
+                let (_, batches) = join_collect(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    &JoinType::Inner,
+                    null_equals_null,
+                    task_ctx.clone(),
+                )
+                .await?;
+                let mut pairs = vec![];
+                for batch in batches {
+                    let n1 = as_primitive_array::<Int32Type>(batch.column(1))?;
+                    let n2 = as_primitive_array::<Int32Type>(batch.column(3))?;
+                    pairs.extend(
+                        n1.values().iter().copied().zip(n2.values().iter().copied()),
+                    );
+                }
+                pairs.sort_unstable();
+                let expected = if null_equals_null {
+                    vec![(0, 0), (0, 4), (2, 1), (3, 0), (3, 4), (4, 3), (5, 1)]
+                } else {
+                    vec![(0, 0), (0, 4), (3, 0), (3, 4), (4, 3)]
+                };
+                assert_eq!(pairs, expected, "{null_equals_null}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equal_join_keys_of_candidates() -> Result<()> {
+        // integer keys of two build batches, compared with the kernels
+        let build_join_values: Vec<Vec<ArrayRef>> = vec![
+            vec![Arc::new(Int32Array::from(vec![Some(1), Some(2)]))],
+            vec![Arc::new(Int32Array::from(vec![Some(3), None]))],
+        ];
+        let probe_keys: Vec<ArrayRef> = vec![Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(3),
+            None,
+            Some(5),
+        ]))];
+        let build_indices = UInt64Array::from(vec![0, 2, 3, 1]);
+        let probe_indices = UInt32Array::from(vec![0, 1, 2, 3]);
+        for (null_equals_null, expected) in [
+            (false, vec![true, true, false, false]),
+            (true, vec![true, true, true, false]),
+        ] {
+            let mask = equal_join_keys(
+                &build_indices,
+                &probe_indices,
+                &[0, 2, 4],
+                &build_join_values,
+                None,
+                &probe_keys,
+                &[null_equals_null],
+            )?;
+            assert_eq!(mask, BooleanArray::from(expected));
+        }
+
+        // float keys, compared one pair at a time
+        let build_join_values: Vec<Vec<ArrayRef>> =
+            vec![vec![Arc::new(Float64Array::from(vec![
+                Some(f64::NAN),
+                Some(-0.0),
+                None,
+                Some(1.0),
+            ]))]];
+        let probe_keys: Vec<ArrayRef> = vec![Arc::new(Float64Array::from(vec![
+            Some(f64::NAN),
+            Some(0.0),
+            None,
+            Some(2.0),
+        ]))];
+        let build_indices = UInt64Array::from(vec![0, 1, 2, 3]);
+        for (null_equals_null, expected) in [
+            (false, vec![true, true, false, false]),
+            (true, vec![true, true, true, false]),
+        ] {
+            let mask = equal_join_keys(
+                &build_indices,
+                &probe_indices,
+                &[0, 4],
+                &build_join_values,
+                None,
+                &probe_keys,
+                &[null_equals_null],
+            )?;
+            assert_eq!(mask, BooleanArray::from(expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equal_rows_shared_dictionary_values() -> Result<()> {
+        // "a" is twice in the values, at positions 0 and 2
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "a"]));
+        let left: ArrayRef = Arc::new(DictionaryArray::try_new(
+            Int32Array::from(vec![Some(0), Some(1), None]),
+            values.clone(),
+        )?);
+        let right: ArrayRef = Arc::new(DictionaryArray::try_new(
+            Int32Array::from(vec![Some(0), Some(2), Some(1), None]),
+            values,
+        )?);
+        let left = [left];
+        let right = [right];
+
+        // same keys
+        assert!(equal_rows(0, 0, &left, &right, [false])?);
+        assert!(equal_rows(1, 2, &left, &right, [false])?);
+        // different keys of equal values
+        assert!(equal_rows(0, 1, &left, &right, [false])?);
+        // different keys of different values
+        assert!(!equal_rows(1, 0, &left, &right, [false])?);
+        // null keys
+        assert!(!equal_rows(2, 3, &left, &right, [false])?);
+        assert!(equal_rows(2, 3, &left, &right, [true])?);
+        Ok(())
+    }
+
+    #[test]
+    fn common_join_key_types() {
+        let dictionary = |key: DataType, value: DataType| {
+            DataType::Dictionary(Box::new(key), Box::new(value))
+        };
+        let cases = [
+            (
+                DataType::Timestamp(TimeUnit::Second, None),
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                Some(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+            ),
+            (
+                DataType::Utf8,
+                DataType::LargeUtf8,
+                Some(DataType::LargeUtf8),
+            ),
+            (
+                dictionary(DataType::Int8, DataType::Utf8),
+                DataType::Utf8,
+                Some(DataType::Utf8),
+            ),
+            (
+                dictionary(DataType::Int8, DataType::Utf8),
+                dictionary(DataType::UInt16, DataType::LargeUtf8),
+                Some(DataType::LargeUtf8),
+            ),
+            (DataType::Null, DataType::Int64, Some(DataType::Int64)),
+            (
+                DataType::Decimal128(10, 2),
+                DataType::Decimal128(12, 4),
+                Some(DataType::Decimal128(12, 4)),
+            ),
+            (DataType::Utf8, DataType::Utf8, None),
+            (DataType::Int32, DataType::Int64, None),
+            (DataType::Utf8, DataType::Binary, None),
+            (
+                dictionary(DataType::Int32, DataType::Utf8),
+                DataType::Int32,
+                None,
+            ),
+        ];
+        for (left, right, expected) in cases {
+            assert_eq!(
+                common_join_key_type(&left, &right),
+                expected,
+                "{left} and {right}"
+            );
+            assert_eq!(
+                common_join_key_type(&right, &left),
+                expected,
+                "{right} and {left}"
+            );
+        }
+    }
+
+    #[test]
+    fn common_decimal_join_key_type() {
+        assert_eq!(
+            common_decimal_type(
+                &DataType::Decimal128(10, 2),
+                &DataType::Decimal128(12, 4)
+            ),
+            Some(DataType::Decimal128(12, 4))
+        );
+        assert_eq!(
+            common_decimal_type(
+                &DataType::Decimal128(38, 2),
+                &DataType::Decimal128(20, 4)
+            ),
+            Some(DataType::Decimal256(40, 4))
+        );
+        assert_eq!(
+            common_decimal_type(
+                &DataType::Decimal128(10, 2),
+                &DataType::Decimal256(40, 2)
+            ),
+            Some(DataType::Decimal256(40, 2))
+        );
+        assert_eq!(
+            common_decimal_type(
+                &DataType::Decimal256(76, 0),
+                &DataType::Decimal256(76, 2)
+            ),
+            Some(DataType::Decimal256(76, 2))
+        );
+        assert_eq!(
+            common_decimal_type(
+                &DataType::Decimal128(10, 2),
+                &DataType::Decimal128(10, 2)
+            ),
+            None
+        );
+        assert_eq!(
+            common_decimal_type(&DataType::Decimal128(10, 2), &DataType::Int64),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn join_decimal_different_precision_and_scale() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        // 1.00, 2.50, 2.50, NULL, 3.00
+        let left = build_key_batch(
+            (
+                "k1",
+                Arc::new(
+                    Decimal128Array::from(vec![
+                        Some(100),
+                        Some(250),
+                        Some(250),
+                        None,
+                        Some(300),
+                    ])
+                    .with_precision_and_scale(38, 2)?,
+                ),
+            ),
+            ("n1", vec![1, 2, 3, 4, 5]),
+        )?;
+        // 1.0000, 2.5000, 2.5100, NULL, 3.0001
+        let right = build_key_batch(
+            (
+                "k2",
+                Arc::new(
+                    Decimal128Array::from(vec![
+                        Some(10000),
+                        Some(25000),
+                        Some(25100),
+                        None,
+                        Some(30001),
+                    ])
+                    .with_precision_and_scale(20, 4)?,
+                ),
+            ),
+            ("n2", vec![10, 20, 30, 40, 50]),
+        )?;
+
+        let pairs = inner_join_pairs(left, right, task_ctx.clone()).await?;
+        assert_eq!(pairs, vec![(1, 10), (2, 20), (3, 20)]);
+
+        // 1.00, 2.50
+        let left = build_key_batch(
+            (
+                "k1",
+                Arc::new(
+                    Decimal128Array::from(vec![Some(100), Some(250)])
+                        .with_precision_and_scale(10, 2)?,
+                ),
+            ),
+            ("n1", vec![1, 2]),
+        )?;
+        // 2.500, 1.000
+        let right = build_key_batch(
+            (
+                "k2",
+                Arc::new(
+                    Decimal256Array::from(vec![
+                        Some(i256::from_i128(2500)),
+                        Some(i256::from_i128(1000)),
+                    ])
+                    .with_precision_and_scale(40, 3)?,
+                ),
+            ),
+            ("n2", vec![10, 20]),
+        )?;
+
+        let pairs = inner_join_pairs(left, right, task_ctx).await?;
+        assert_eq!(pairs, vec![(1, 20), (2, 10)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_decimal_rescale_overflow() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        // 10^75 can not be rescaled to Decimal256(76, 2), so it matches nothing
+        // instead of failing the join
+        let big = i256::from_i128(10_i128.pow(37))
+            .wrapping_mul(i256::from_i128(10_i128.pow(38)));
+        let left = build_key_batch(
+            (
+                "k1",
+                Arc::new(
+                    Decimal256Array::from(vec![Some(big), Some(i256::from_i128(1))])
+                        .with_precision_and_scale(76, 0)?,
+                ),
+            ),
+            ("n1", vec![1, 2]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                Arc::new(
+                    Decimal256Array::from(vec![Some(i256::from_i128(100))])
+                        .with_precision_and_scale(76, 2)?,
+                ),
+            ),
+            ("n2", vec![10]),
+        )?;
+
+        let pairs = inner_join_pairs(left, right, task_ctx).await?;
+        assert_eq!(pairs, vec![(2, 10)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_float_nan_and_signed_zero() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        fn float64_keys(left: bool) -> ArrayRef {
+            if left {
+                Arc::new(Float64Array::from(vec![
+                    Some(0.0),
+                    Some(-0.0),
+                    Some(f64::NAN),
+                    Some(1.0),
+                    None,
+                ]))
+            } else {
+                Arc::new(Float64Array::from(vec![
+                    Some(-0.0),
+                    Some(f64::from_bits(0x7ff8_0000_0000_0001)),
+                    Some(2.0),
+                ]))
+            }
+        }
+        fn float32_keys(left: bool) -> ArrayRef {
+            if left {
+                Arc::new(Float32Array::from(vec![
+                    Some(0.0),
+                    Some(-0.0),
+                    Some(f32::NAN),
+                    Some(1.0),
+                    None,
+                ]))
+            } else {
+                Arc::new(Float32Array::from(vec![
+                    Some(-0.0),
+                    Some(-f32::NAN),
+                    Some(2.0),
+                ]))
+            }
+        }
+
+        for keys in [float64_keys as fn(bool) -> ArrayRef, float32_keys] {
+            let left = build_key_batch(("k1", keys(true)), ("n1", vec![1, 2, 3, 4, 5]))?;
+            let right = build_key_batch(("k2", keys(false)), ("n2", vec![10, 20, 30]))?;
+
+            let pairs =
+                inner_join_pairs(left.clone(), right.clone(), task_ctx.clone()).await?;
+            assert_eq!(pairs, vec![(1, 10), (2, 10), (3, 20)]);
+
+            let left_schema = left.schema();
+            let right_schema = right.schema();
+            let on = vec![(
+                Arc::new(Column::new_with_schema("k1", &left_schema)?) as _,
+                Arc::new(Column::new_with_schema("k2", &right_schema)?) as _,
+            )];
+            let left = Arc::new(MemoryExec::try_new(&[vec![left]], left_schema, None)?);
+            let right =
+                Arc::new(MemoryExec::try_new(&[vec![right]], right_schema, None)?);
+            let (_, batches) = join_collect(
+                left,
+                right,
+                on,
+                &JoinType::LeftAnti,
+                false,
+                task_ctx.clone(),
+            )
+            .await?;
+            let mut n1 = vec![];
+            for batch in batches {
+                let column = as_primitive_array::<Int32Type>(batch.column(1))?;
+                n1.extend(column.values().iter().copied());
+            }
+            n1.sort_unstable();
+            assert_eq!(n1, vec![4, 5]);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_interval() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let day_time = |days, millis| Some(IntervalDayTimeType::make_value(days, millis));
+        let month_day_nano = |months, days, nanos| {
+            Some(IntervalMonthDayNanoType::make_value(months, days, nanos))
+        };
+        let cases: Vec<(ArrayRef, ArrayRef)> = vec![
+            (
+                Arc::new(IntervalYearMonthArray::from(vec![
+                    Some(1),
+                    Some(12),
+                    Some(12),
+                    None,
+                    Some(13),
+                ])),
+                Arc::new(IntervalYearMonthArray::from(vec![Some(12), None, Some(14)])),
+            ),
+            (
+                Arc::new(IntervalDayTimeArray::from(vec![
+                    day_time(1, 0),
+                    day_time(1, 500),
+                    day_time(1, 500),
+                    None,
+                    day_time(2, 0),
+                ])),
+                // one day is not the same as 86400000 milliseconds
+                Arc::new(IntervalDayTimeArray::from(vec![
+                    day_time(1, 500),
+                    None,
+                    day_time(0, 86_400_000),
+                ])),
+            ),
+            (
+                Arc::new(IntervalMonthDayNanoArray::from(vec![
+                    month_day_nano(1, 0, 0),
+                    month_day_nano(0, 30, 1),
+                    month_day_nano(0, 30, 1),
+                    None,
+                    month_day_nano(0, 30, 0),
+                ])),
+                // one month is not the same as 30 days
+                Arc::new(IntervalMonthDayNanoArray::from(vec![
+                    month_day_nano(0, 30, 1),
+                    None,
+                    month_day_nano(0, 0, 1),
+                ])),
+            ),
+        ];
+
+        for (left_keys, right_keys) in cases {
+            let left = build_key_batch(("k1", left_keys), ("n1", vec![1, 2, 3, 4, 5]))?;
+            let right = build_key_batch(("k2", right_keys), ("n2", vec![10, 20, 30]))?;
+
+            let pairs =
+                inner_join_pairs(left.clone(), right.clone(), task_ctx.clone()).await?;
+            assert_eq!(pairs, vec![(2, 10), (3, 10)]);
+
+            for (null_equals_null, expected) in
+                [(false, vec![2, 3]), (true, vec![2, 3, 4])]
+            {
+                let left_schema = left.schema();
+                let right_schema = right.schema();
+                let on = vec![(
+                    Arc::new(Column::new_with_schema("k1", &left_schema)?) as _,
+                    Arc::new(Column::new_with_schema("k2", &right_schema)?) as _,
+                )];
+                let left_exec = Arc::new(MemoryExec::try_new(
+                    &[vec![left.clone()]],
+                    left_schema,
+                    None,
+                )?);
+                let right_exec = Arc::new(MemoryExec::try_new(
+                    &[vec![right.clone()]],
+                    right_schema,
+                    None,
+                )?);
+                let (_, batches) = join_collect(
+                    left_exec,
+                    right_exec,
+                    on,
+                    &JoinType::LeftSemi,
+                    null_equals_null,
+                    task_ctx.clone(),
+                )
+                .await?;
+                let mut n1 = vec![];
+                for batch in batches {
+                    let column = as_primitive_array::<Int32Type>(batch.column(1))?;
+                    n1.extend(column.values().iter().copied());
+                }
+                n1.sort_unstable();
+                assert_eq!(n1, expected);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_null_type_keys() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let null_keys = |len| new_null_array(&DataType::Null, len);
+        // the build side has several batches, whose null keys must all be
+        // inserted into the same bucket of the hash table
+        let left = vec![
+            build_key_batch(("k1", null_keys(2)), ("n1", vec![1, 2]))?,
+            build_key_batch(("k1", null_keys(1)), ("n1", vec![3]))?,
+        ];
+        let right = build_key_batch(("k2", null_keys(2)), ("n2", vec![10, 20]))?;
+
+        let cases = [
+            (JoinType::Inner, false, 0),
+            (JoinType::Inner, true, 6),
+            (JoinType::Left, false, 3),
+            (JoinType::Left, true, 6),
+            (JoinType::Full, false, 5),
+            (JoinType::Full, true, 6),
+            (JoinType::LeftSemi, false, 0),
+            (JoinType::LeftSemi, true, 3),
+            (JoinType::RightAnti, false, 2),
+            (JoinType::RightAnti, true, 0),
+        ];
+        for (join_type, null_equals_null, expected_rows) in cases {
+            let left_schema = left[0].schema();
+            let right_schema = right.schema();
+            let on = vec![(
+                Arc::new(Column::new_with_schema("k1", &left_schema)?) as _,
+                Arc::new(Column::new_with_schema("k2", &right_schema)?) as _,
+            )];
+            let left_exec =
+                Arc::new(MemoryExec::try_new(&[left.clone()], left_schema, None)?);
+            let right_exec = Arc::new(MemoryExec::try_new(
+                &[vec![right.clone()]],
+                right_schema,
+                None,
+            )?);
+            let (_, batches) = join_collect(
+                left_exec,
+                right_exec,
+                on,
+                &join_type,
+                null_equals_null,
+                task_ctx.clone(),
+            )
+            .await?;
+            let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+            assert_eq!(
+                rows, expected_rows,
+                "{join_type} join with null_equals_null={null_equals_null}"
+            );
+        }
+
+        // a null typed key combined with another key
+        let build_batch = |a: Vec<i32>, n: Vec<i32>| {
+            let schema = Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int32, false),
+                Field::new("k", DataType::Null, true),
+                Field::new("n", DataType::Int32, false),
+            ]));
+            let len = a.len();
+            RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(Int32Array::from(a)),
+                    null_keys(len),
+                    Arc::new(Int32Array::from(n)),
+                ],
+            )
+        };
+        let left = build_batch(vec![1, 1, 2], vec![1, 2, 3])?;
+        let right = build_batch(vec![1, 2, 3], vec![10, 20, 30])?;
+        for (null_equals_null, expected) in
+            [(false, vec![]), (true, vec![(1, 10), (2, 10), (3, 20)])]
+        {
+            let left_schema = left.schema();
+            let right_schema = right.schema();
+            let on = vec![
+                (
+                    Arc::new(Column::new_with_schema("a", &left_schema)?) as _,
+                    Arc::new(Column::new_with_schema("a", &right_schema)?) as _,
+                ),
+                (
+                    Arc::new(Column::new_with_schema("k", &left_schema)?) as _,
+                    Arc::new(Column::new_with_schema("k", &right_schema)?) as _,
+                ),
+            ];
+            let left_exec = Arc::new(MemoryExec::try_new(
+                &[vec![left.clone()]],
+                left_schema,
+                None,
+            )?);
+            let right_exec = Arc::new(MemoryExec::try_new(
+                &[vec![right.clone()]],
+                right_schema,
+                None,
+            )?);
+            let (_, batches) = join_collect(
+                left_exec,
+                right_exec,
+                on,
+                &JoinType::Inner,
+                null_equals_null,
+                task_ctx.clone(),
+            )
+            .await?;
+            let mut pairs = vec![];
+            for batch in batches {
+                let n1 = as_primitive_array::<Int32Type>(batch.column(2))?;
+                let n2 = as_primitive_array::<Int32Type>(batch.column(5))?;
+                pairs
+                    .extend(n1.values().iter().copied().zip(n2.values().iter().copied()));
+            }
+            pairs.sort_unstable();
+            assert_eq!(pairs, expected);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_time64() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let left = build_key_batch(
+            (
+                "k1",
+                Arc::new(Time64MicrosecondArray::from(vec![
+                    Some(1_000),
+                    Some(2_000),
+                    None,
+                    Some(3_000),
+                ])),
+            ),
+            ("n1", vec![1, 2, 3, 4]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                Arc::new(Time64MicrosecondArray::from(vec![
+                    Some(2_000),
+                    None,
+                    Some(4_000),
+                ])),
+            ),
+            ("n2", vec![10, 20, 30]),
+        )?;
+
+        let cases = vec![
+            (
+                JoinType::Inner,
+                vec![(Some(2), Some(10)), (Some(3), Some(20))],
+            ),
+            (JoinType::LeftAnti, vec![(Some(1), None), (Some(4), None)]),
+        ];
+        for (join_type, expected) in cases {
+            let pairs = join_pairs(
+                left.clone(),
+                right.clone(),
+                &join_type,
+                true,
+                false,
+                task_ctx.clone(),
+            )
+            .await?;
+            assert_eq!(pairs, expected, "{join_type}");
+        }
+
+        Ok(())
+    }
+
+    /// Returns the nanosecond timestamps `keys` in the UTC time zone
+    fn timestamp_tz_keys(keys: Vec<Option<i64>>) -> ArrayRef {
+        Arc::new(TimestampNanosecondArray::from(keys).with_timezone("UTC"))
+    }
+
+    #[tokio::test]
+    async fn join_timestamp_with_timezone() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+
+        let second = 1_000_000_000;
+        let left = build_key_batch(
+            (
+                "k1",
+                timestamp_tz_keys(vec![Some(second), Some(2 * second), None]),
+            ),
+            ("n1", vec![1, 2, 3]),
+        )?;
+        let right = build_key_batch(
+            (
+                "k2",
+                timestamp_tz_keys(vec![Some(2 * second), Some(3 * second), None]),
+            ),
+            ("n2", vec![10, 20, 30]),
+        )?;
+
+        let cases = vec![
+            (JoinType::Inner, vec![(Some(2), Some(10))]),
+            (
+                JoinType::Left,
+                vec![(Some(1), None), (Some(2), Some(10)), (Some(3), None)],
+            ),
+            (
+                JoinType::Full,
+                vec![
+                    (None, Some(20)),
+                    (None, Some(30)),
+                    (Some(1), None),
+                    (Some(2), Some(10)),
+                    (Some(3), None),
+                ],
+            ),
+        ];
+        for (join_type, expected) in cases {
+            let pairs = join_pairs(
+                left.clone(),
+                right.clone(),
+                &join_type,
+                false,
+                false,
+                task_ctx.clone(),
+            )
+            .await?;
+            assert_eq!(pairs, expected, "{join_type}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_timestamp_with_different_timezones() -> Result<()> {
+        let left =
+            build_key_batch(("k1", timestamp_tz_keys(vec![Some(1)])), ("n1", vec![1]))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left.clone()]],
+            left.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![]],
+            Arc::new(Schema::new(vec![Field::new(
+                "k2",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                true,
+            )])),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("k1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("k2", &right.schema())?) as _,
+        )];
+
+        let err = join(left, right, on, &JoinType::Inner, false).unwrap_err();
+        assert_contains!(err.to_string(), "have timestamps with different time zones");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_error_right() {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+
+        // right input stream returns one good batch and then one error.
+        // The error should be returned.
+        let err = Err(DataFusionError::Execution("bad data error".to_string()));
+        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
+        )];
+        let schema = right.schema();
+        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
+        let right_input = Arc::new(MockExec::new(vec![Ok(right), err], schema));
+
+        let join_types = vec![
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ];
+
+        for join_type in join_types {
+            let join = join(
+                left.clone(),
+                right_input.clone(),
+                on.clone(),
+                &join_type,
+                false,
+            )
+            .unwrap();
+            let session_ctx = SessionContext::new();
+            let task_ctx = session_ctx.task_ctx();
+
+            let stream = join.execute(0, task_ctx).unwrap();
+
+            // Expect that an error is returned
+            let result_string = crate::physical_plan::common::collect(stream)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(
+                result_string.contains("bad data error"),
+                "actual: {result_string}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn single_partition_join_overallocation() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 11]),
+            ("b2", &vec![12, 13]),
+            ("c2", &vec![14, 15]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
+
+        let join_types = vec![
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ];
+
+        for join_type in join_types {
+            let runtime_config = RuntimeConfig::new().with_memory_limit(100, 1.0);
+            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+            let session_ctx =
+                SessionContext::with_config_rt(SessionConfig::default(), runtime);
+            let task_ctx = session_ctx.task_ctx();
+
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+
+            let stream = join.execute(0, task_ctx)?;
+            let err = common::collect(stream).await.unwrap_err();
+
+            assert_contains!(
+                err.to_string(),
+                "External error: Resources exhausted: Failed to allocate additional"
+            );
+
+            // Asserting that operator-level reservation attempting to overallocate
+            assert_contains!(err.to_string(), "HashJoinInput");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn partitioned_join_overallocation() -> Result<()> {
+        // Prepare partitioned inputs for HashJoinExec
+        // No need to adjust partitioning, as execution should fail with `Resources exhausted` error
+        let left_batch = build_table_i32(
+            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+        );
+        let left = Arc::new(
+            MemoryExec::try_new(
+                &[vec![left_batch.clone()], vec![left_batch.clone()]],
+                left_batch.schema(),
+                None,
+            )
+            .unwrap(),
+        );
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 11]),
+            ("b2", &vec![12, 13]),
+            ("c2", &vec![14, 15]),
+        );
+        let right = Arc::new(
+            MemoryExec::try_new(
+                &[vec![right_batch.clone()], vec![right_batch.clone()]],
+                right_batch.schema(),
+                None,
+            )
+            .unwrap(),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right_batch.schema())?) as _,
+        )];
+
+        let join_types = vec![
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ];
+
+        for join_type in join_types {
+            // the partitions of the join can not spill their inputs to disk
+            let runtime_config = RuntimeConfig::new()
+                .with_memory_limit(100, 1.0)
+                .with_disk_manager(DiskManagerConfig::Disabled);
+            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+            let session_config = SessionConfig::default().with_batch_size(50);
+            let session_ctx = SessionContext::with_config_rt(session_config, runtime);
+            let task_ctx = session_ctx.task_ctx();
+
+            let join = HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &join_type,
+                PartitionMode::Partitioned,
+                false,
+            )?;
+
+            let stream = join.execute(1, task_ctx)?;
+            let err = common::collect(stream).await.unwrap_err();
+
+            assert_contains!(
+                err.to_string(),
+                "Resources exhausted: Failed to allocate additional"
+            );
+
+            // Asserting that stream-level reservation attempting to overallocate
+            assert_contains!(err.to_string(), "HashJoinInput[1]");
+        }
+
+        Ok(())
+    }
+
+    /// The rows of `batches`, formatted and sorted
+    fn sorted_rows(batches: &[RecordBatch]) -> Result<Vec<String>> {
+        let batches = batches
+            .iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut rows = pretty_format_batches(&batches)?
+            .to_string()
+            .lines()
+            .map(|row| row.to_string())
+            .collect::<Vec<_>>();
+        rows.sort();
+        Ok(rows)
+    }
+
+    #[tokio::test]
+    async fn swap_inputs_produces_same_output() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let left = build_table(
+            ("a1", &vec![1, 2, 2, 3, 5]),
+            ("b1", &vec![4, 5, 5, 6, 8]),
+            ("c1", &vec![7, 8, 9, 1, 2]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![4, 5, 6, 7]),
+            ("c2", &vec![70, 8, 6, 4]),
+        );
+        let on: JoinOn = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        // c1 > c2
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("c1", 0)),
+                Operator::Gt,
+                Arc::new(Column::new("c2", 1)),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("c1", DataType::Int32, true),
+                Field::new("c2", DataType::Int32, true),
+            ]),
+        );
+
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::RightSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+        ] {
+            for filter in [None, Some(filter.clone())] {
+                let join = HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    filter,
+                    &join_type,
+                    PartitionMode::CollectLeft,
+                    false,
+                )?;
+                let swapped = join.swap_inputs()?;
+                assert_eq!(swapped.schema(), join.schema(), "{join_type}");
+
+                let expected =
+                    crate::physical_plan::collect(Arc::new(join), session_ctx.task_ctx())
+                        .await?;
+                let actual =
+                    crate::physical_plan::collect(swapped, session_ctx.task_ctx())
+                        .await?;
+                assert_eq!(
+                    sorted_rows(&actual)?,
+                    sorted_rows(&expected)?,
+                    "{join_type}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn auto_mode_collects_small_build_side() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Auto,
+            false,
+        )?
+        .with_single_partition_threshold(1024 * 1024);
+
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::CollectLeft);
+        assert!(matches!(
+            join.required_input_distribution()[0],
+            Distribution::SinglePartition
+        ));
+        assert_eq!(join.output_partitioning().partition_count(), 1);
+        assert_contains!(
+            displayable(&join).one_line().to_string(),
+            "mode=Auto(resolved=CollectLeft)"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn auto_mode_partitions_build_side_without_statistics() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a1", DataType::Int32, true),
+            Field::new("b1", DataType::Int32, true),
+        ]);
+        let left = Arc::new(StatisticsExec::new(Statistics::default(), schema));
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Auto,
+            false,
+        )?
+        .with_single_partition_threshold(1024 * 1024);
+
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::Partitioned);
+        assert!(matches!(
+            join.required_input_distribution()[0],
+            Distribution::HashPartitioned(_)
+        ));
+        assert_contains!(
+            displayable(&join).one_line().to_string(),
+            "mode=Auto(resolved=Partitioned)"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn auto_mode_collects_build_side_by_rows() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a1", DataType::Int32, true),
+            Field::new("b1", DataType::Int32, true),
+        ]);
+        let statistics = Statistics {
+            num_rows: Some(3),
+            ..Default::default()
+        };
+        let left = Arc::new(StatisticsExec::new(statistics, schema));
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        // the size in bytes of the build side is unknown
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Auto,
+            false,
+        )?
+        .with_single_partition_threshold(1024 * 1024);
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::Partitioned);
+
+        let join = join.with_single_partition_threshold_rows(4);
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::CollectLeft);
+        let join = join.with_single_partition_threshold_rows(3);
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::Partitioned);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn right_join_keeps_probe_side_order() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40, 50]),
+            ("b1", &vec![6, 5, 4, 3, 7]),
+            ("c2", &vec![70, 80, 90, 100, 110]),
+        );
+        let sort_expr = PhysicalSortExpr {
+            expr: Arc::new(Column::new_with_schema("b1", &right.schema())?),
+            options: Default::default(),
+        };
+        let right = Arc::new(SortExec::new(vec![sort_expr], right));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Right, false)?;
+        // the ordering of the probe side, with its columns after the build side ones
+        assert_eq!(join.output_ordering().unwrap()[0].to_string(), "b1@4 ASC");
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+        // the unmatched probe rows are between the matched ones
+        let expected = vec![
+            "+----+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2  |",
+            "+----+----+----+----+----+-----+",
+            "|    |    |    | 40 | 3  | 100 |",
+            "| 1  | 4  | 7  | 30 | 4  | 90  |",
+            "| 2  | 5  | 8  | 20 | 5  | 80  |",
+            "| 3  | 5  | 9  | 20 | 5  | 80  |",
+            "|    |    |    | 10 | 6  | 70  |",
+            "|    |    |    | 50 | 7  | 110 |",
+            "+----+----+----+----+----+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // a left join produces the unmatched build side rows at the end
+        let join = HashJoinExec::try_new(
+            join.left.clone(),
+            join.right.clone(),
+            join.on.clone(),
+            None,
+            &JoinType::Left,
+            PartitionMode::CollectLeft,
+            false,
+        )?;
+        assert!(join.output_ordering().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn verbose_display_with_filter() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        // c1 > c2
+        let column_indices = vec![
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, false),
+            Field::new("c2", DataType::Int32, false),
+        ]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("c1", 0)),
+            Operator::Gt,
+            Arc::new(Column::new("c2", 1)),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        let join = join_with_filter(left, right, on, filter, &JoinType::Left, false)?;
+        let plan = CoalesceBatchesExec::new(Arc::new(join), 4096);
+
+        // the details are indented below the join, like the join itself
+        let expected = vec![
+            "CoalesceBatchesExec: target_batch_size=4096",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(b1@1, b1@1)], filter=c1@0 > c2@1",
+            "      filter_schema=[c1:Int32, c2:Int32]",
+            "      filter_column_indices=[left@2, right@2]",
+            "      null_equals_null=[false]",
+            "      schema=[a1:Int32, b1:Int32, c1:Int32, a2:Int32, b1:Int32, c2:Int32]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        let actual = displayable(&plan)
+            .with_format_type(DisplayFormatType::Verbose)
+            .indent()
+            .to_string();
+        assert_eq!(actual.trim_end().lines().collect::<Vec<_>>(), expected);
+
+        // the default format is unchanged
+        let expected = vec![
+            "CoalesceBatchesExec: target_batch_size=4096",
+            "  HashJoinExec: mode=CollectLeft, join_type=Left, on=[(b1@1, b1@1)], filter=c1@0 > c2@1",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        let actual = displayable(&plan).indent().to_string();
+        assert_eq!(actual.trim_end().lines().collect::<Vec<_>>(), expected);
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_left_semi_with_filter() -> Result<()> {
+    async fn build_side_info_after_execution() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-
-        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 10
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![1, 1, 1, 2]), // 3 rows with the same key
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![1, 2, 3]),
+            ("c2", &vec![70, 80, 90]),
+        );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Right,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
-        )) as Arc<dyn PhysicalExpr>;
-
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
-        );
-
-        let join = join_with_filter(
+        let join = Arc::new(join(
             left.clone(),
             right.clone(),
             on.clone(),
-            filter,
-            &JoinType::LeftSemi,
+            &JoinType::Inner,
             false,
-        )?;
-
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header.clone(), vec!["a1", "b1", "c1"]);
-
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 11 | 8  | 110 |",
-            "| 13 | 10 | 130 |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        )?);
+        // the build side is only known once it has been built
+        assert_eq!(join.build_side_info(), None);
+
+        let batches =
+            crate::physical_plan::collect(join.clone(), task_ctx.clone()).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 4);
+
+        let info = join.build_side_info().unwrap();
+        assert_eq!(info.num_rows, 4);
+        assert_eq!(info.num_buckets, 2);
+        assert_eq!(info.longest_chain, 3);
+        assert!(info.hash_table_size > 0);
+
+        let metrics = join.metrics().unwrap();
+        let metric = |name| {
+            metrics
+                .sum_by_name(name)
+                .map(|value| value.as_usize())
+                .unwrap()
+        };
+        assert_eq!(metric("build_input_rows"), 4);
+        assert_eq!(metric("build_hash_buckets"), 2);
+        assert_eq!(metric("build_longest_chain"), 3);
+        assert_eq!(metric("build_hash_table_size"), info.hash_table_size);
+
+        // executing the join again replaces the information of the previous
+        // execution, in either mode
+        crate::physical_plan::collect(join.clone(), task_ctx.clone()).await?;
+        assert_eq!(join.build_side_info(), Some(info));
+
+        let (left_keys, right_keys) = on.iter().cloned().unzip();
+        let join = Arc::new(HashJoinExec::try_new(
+            Arc::new(RepartitionExec::try_new(
+                left,
+                Partitioning::Hash(left_keys, 2),
+            )?),
+            Arc::new(RepartitionExec::try_new(
+                right,
+                Partitioning::Hash(right_keys, 2),
+            )?),
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+            false,
+        )?);
+        crate::physical_plan::collect(join.clone(), task_ctx.clone()).await?;
+        let info = join.build_side_info().unwrap();
+        assert_eq!(info.num_rows, 4);
+        assert_eq!(info.num_buckets, 2);
+        crate::physical_plan::collect(join.clone(), task_ctx).await?;
+        assert_eq!(join.build_side_info(), Some(info));
 
-        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 > 10
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::Gt,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
-        )) as Arc<dyn PhysicalExpr>;
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        Ok(())
+    }
 
-        let join = join_with_filter(left, right, on, filter, &JoinType::LeftSemi, false)?;
+    /// Returns the partitions with a nonzero value of the metric `name` in
+    /// `metrics`, and the value
+    fn metric_by_partition(metrics: &MetricsSet, name: &str) -> Vec<(usize, usize)> {
+        let mut values = metrics
+            .iter()
+            .filter(|metric| metric.value().name() == name)
+            .filter_map(|metric| Some((metric.partition()?, metric.value().as_usize())))
+            .filter(|(_, value)| *value > 0)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        values
+    }
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+    #[tokio::test]
+    async fn collect_left_build_metrics() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_i32(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right.clone()], vec![right]],
+            schema,
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = join(left, right, on, &JoinType::Inner, false)?;
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        // the second partition builds the build side, attributed to the first
+        for partition in [1, 0] {
+            let stream = join.execute(partition, task_ctx.clone())?;
+            common::collect(stream).await?;
+        }
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 13 | 10 | 130 |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        let metrics = join.metrics().unwrap();
+        assert_eq!(
+            metric_by_partition(&metrics, "build_input_rows"),
+            vec![(0, 3)]
+        );
+        assert_eq!(
+            metric_by_partition(&metrics, "build_input_batches"),
+            vec![(0, 1)]
+        );
+        assert_eq!(
+            metric_by_partition(&metrics, "build_time")
+                .into_iter()
+                .map(|(partition, _)| partition)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_semi() -> Result<()> {
+    async fn partitioned_build_metrics() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-
-        // left_table right semi join right_table on left_table.b1 = right_table.b2
+        let memory_exec = |batches: Vec<RecordBatch>| -> Result<Arc<dyn ExecutionPlan>> {
+            let schema = batches[0].schema();
+            let partitions = batches.into_iter().map(|b| vec![b]).collect::<Vec<_>>();
+            Ok(Arc::new(MemoryExec::try_new(&partitions, schema, None)?))
+        };
+        let left = memory_exec(vec![
+            build_table_i32(
+                ("a1", &vec![1, 2]),
+                ("b1", &vec![4, 4]),
+                ("c1", &vec![7, 8]),
+            ),
+            build_table_i32(
+                ("a1", &vec![3, 4, 5]),
+                ("b1", &vec![5, 5, 5]),
+                ("c1", &vec![9, 10, 11]),
+            ),
+        ])?;
+        let right = memory_exec(vec![
+            build_table_i32(("a2", &vec![10]), ("b1", &vec![4]), ("c2", &vec![70])),
+            build_table_i32(("a2", &vec![20]), ("b1", &vec![5]), ("c2", &vec![80])),
+        ])?;
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+            false,
+        )?;
 
-        let join = join(left, right, on, &JoinType::RightSemi, false)?;
+        let mut num_rows = 0;
+        for partition in 0..2 {
+            let stream = join.execute(partition, task_ctx.clone())?;
+            num_rows += common::collect(stream)
+                .await?
+                .iter()
+                .map(|batch| batch.num_rows())
+                .sum::<usize>();
+        }
+        assert_eq!(num_rows, 5);
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+        // every partition builds its own build side
+        let metrics = join.metrics().unwrap();
+        assert_eq!(
+            metric_by_partition(&metrics, "build_input_rows"),
+            vec![(0, 2), (1, 3)]
+        );
+        assert_eq!(
+            metric_by_partition(&metrics, "build_time")
+                .into_iter()
+                .map(|(partition, _)| partition)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        Ok(())
+    }
 
+    #[tokio::test]
+    async fn build_mem_used_is_peak() -> Result<()> {
+        let session_config = SessionConfig::new().with_target_partitions(4);
+        let task_ctx = SessionContext::with_config(session_config).task_ctx();
+        let batches = (0..4)
+            .map(|i| {
+                let keys = (i * 1000..(i + 1) * 1000).collect::<Vec<_>>();
+                build_table_i32(("a1", &keys), ("b1", &keys), ("c1", &keys))
+            })
+            .collect::<Vec<_>>();
+        let batches_size = batches
+            .iter()
+            .map(|batch| batch.get_array_memory_size())
+            .sum::<usize>();
+        let schema = batches[0].schema();
+        let partitions = batches.into_iter().map(|b| vec![b]).collect::<Vec<_>>();
+        let left = Arc::new(MemoryExec::try_new(&partitions, schema, None)?);
+        let right = build_table(
+            ("a2", &vec![10, 20]),
+            ("b2", &vec![1, 2000]),
+            ("c2", &vec![70, 80]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let join = join(left, right, on, &JoinType::Inner, false)?;
         let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 10 | 10 | 100 |",
-            "| 12 | 10 | 40  |",
-            "| 8  | 8  | 20  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+        // the partial hash tables merged into the build side are released,
+        // without lowering the peak memory of the build side
+        let build_mem_used = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("build_mem_used")
+            .map(|value| value.as_usize())
+            .unwrap();
+        assert!(build_mem_used >= batches_size);
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_semi_with_filter() -> Result<()> {
+    async fn join_time_breakdown_metrics() -> Result<()> {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
         let left = build_semi_anti_left_table();
         let right = build_semi_anti_right_table();
 
-        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+        // left_table join right_table on left_table.b1 = right_table.b2
+        // and left_table.c1 > 100
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-
         let column_indices = vec![ColumnIndex {
-            index: 0,
+            index: 2,
             side: JoinSide::Left,
         }];
         let intermediate_schema =
             Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
         let filter_expression = Arc::new(BinaryExpr::new(
             Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(9)))),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(100)))),
         )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
 
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
+        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+        // every step of the probe phase is timed, as a part of `join_time`
+        let metrics = join.metrics().unwrap();
+        let time = |name| {
+            metrics
+                .sum_by_name(name)
+                .map(|value| value.as_usize())
+                .unwrap()
+        };
+        let join_time = time("join_time");
+        for name in [
+            "probe_hash_time",
+            "match_time",
+            "filter_time",
+            "output_build_time",
+        ] {
+            assert!(time(name) > 0, "{name}");
+            assert!(time(name) <= join_time, "{name}");
+        }
+
+        // the join time is a part of the elapsed compute time of the join, whose
+        // output rows are counted once
+        assert!(join_time <= metrics.elapsed_compute().unwrap());
+        assert_eq!(metrics.output_rows(), Some(3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn build_side_info_of_unique_keys() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
         );
+        let right = build_table(
+            ("a2", &vec![10, 20]),
+            ("b1", &vec![4, 5]),
+            ("c2", &vec![70, 80]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
 
-        let join = join_with_filter(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            filter,
-            &JoinType::RightSemi,
-            false,
-        )?;
+        let join = Arc::new(join(left, right, on, &JoinType::Left, false)?);
+        crate::physical_plan::collect(join.clone(), task_ctx).await?;
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+        let info = join.build_side_info().unwrap();
+        assert_eq!(info.num_rows, 3);
+        assert_eq!(info.num_buckets, 3);
+        assert_eq!(info.longest_chain, 1);
+        assert!(info.capacity >= 3);
+        assert!(info.load_factor() > 0.0 && info.load_factor() <= 1.0);
+        assert_eq!(info.avg_chain_length(), 1.0);
 
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
+        Ok(())
+    }
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 10 | 10 | 100 |",
-            "| 12 | 10 | 40  |",
-            "| 8  | 8  | 20  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+    /// Wraps `input`, claiming in its statistics that it has `num_rows` rows
+    #[derive(Debug)]
+    struct MisestimatedExec {
+        input: Arc<dyn ExecutionPlan>,
+        num_rows: usize,
+    }
 
-        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::Gt,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(11)))),
-        )) as Arc<dyn PhysicalExpr>;
+    impl ExecutionPlan for MisestimatedExec {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
 
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        fn schema(&self) -> SchemaRef {
+            self.input.schema()
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            self.input.output_partitioning()
+        }
+
+        fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+            None
+        }
+
+        fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+            vec![self.input.clone()]
+        }
+
+        fn with_new_children(
+            self: Arc<Self>,
+            children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(MisestimatedExec {
+                input: children[0].clone(),
+                num_rows: self.num_rows,
+            }))
+        }
 
-        let join =
-            join_with_filter(left, right, on, filter, &JoinType::RightSemi, false)?;
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        fn execute(
+            &self,
+            partition: usize,
+            context: Arc<TaskContext>,
+        ) -> Result<SendableRecordBatchStream> {
+            self.input.execute(partition, context)
+        }
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 10 | 10 | 100 |",
-            "| 12 | 10 | 40  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "MisestimatedExec: num_rows={}", self.num_rows)
+        }
 
-        Ok(())
+        fn statistics(&self) -> Statistics {
+            Statistics {
+                num_rows: Some(self.num_rows),
+                ..Default::default()
+            }
+        }
     }
 
     #[tokio::test]
-    async fn join_left_anti() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table left anti join right_table on left_table.b1 = right_table.b2
+    async fn join_collect_left_falls_back_to_partitioned() -> Result<()> {
+        // the statistics of the build side claim 10 rows, it has 1M rows
+        let keys = (0..1_000_000).collect::<Vec<i32>>();
+        let batches = keys
+            .chunks(8192)
+            .map(|keys| {
+                let keys = keys.to_vec();
+                build_table_i32(("a1", &keys), ("b1", &keys), ("c1", &keys))
+            })
+            .collect::<Vec<_>>();
+        let schema = batches[0].schema();
+        let (first, second) = batches.split_at(batches.len() / 2);
+        let left = Arc::new(MisestimatedExec {
+            input: Arc::new(MemoryExec::try_new(
+                &[first.to_vec(), second.to_vec()],
+                schema,
+                None,
+            )?),
+            num_rows: 10,
+        });
+        // every 1000th build side row has a match, and half of the probe side
+        // rows do not
+        let keys = (0..2_000_000).step_by(1000).collect::<Vec<i32>>();
+        let right_batch = build_table_i32(("a2", &keys), ("b2", &keys), ("c2", &keys));
+        let right = Arc::new(MemoryExec::try_new(
+            &[
+                vec![right_batch.slice(0, 1000)],
+                vec![right_batch.slice(1000, 1000)],
+            ],
+            right_batch.schema(),
+            None,
+        )?);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::LeftAnti, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+        let mut session_config = SessionConfig::default();
+        session_config
+            .options_mut()
+            .execution
+            .hash_join_collect_left_max_rows = 10_000;
+        let task_ctx = SessionContext::with_config(session_config).task_ctx();
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        for (join_type, expected_rows) in
+            [(JoinType::Inner, 1000), (JoinType::Full, 1_001_000)]
+        {
+            let join = Arc::new(HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &join_type,
+                PartitionMode::CollectLeft,
+                false,
+            )?);
+            // the join falls back to new repartitioned inputs every execution
+            for executions in 1..=2 {
+                let partitions =
+                    collect_partitioned(join.clone(), task_ctx.clone()).await?;
+                let num_rows = partitions
+                    .iter()
+                    .flatten()
+                    .map(|batch| batch.num_rows())
+                    .sum::<usize>();
+                assert_eq!(num_rows, expected_rows, "{join_type}");
+
+                // every partition of the join fell back to Partitioned mode
+                let fallbacks = join
+                    .metrics()
+                    .unwrap()
+                    .sum_by_name("partitioned_fallbacks")
+                    .map(|value| value.as_usize());
+                assert_eq!(fallbacks, Some(2 * executions), "{join_type}");
+            }
+        }
 
-        let expected = vec![
-            "+----+----+----+",
-            "| a1 | b1 | c1 |",
-            "+----+----+----+",
-            "| 1  | 1  | 10 |",
-            "| 3  | 3  | 30 |",
-            "| 5  | 5  | 50 |",
-            "| 7  | 7  | 70 |",
-            "+----+----+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_left_anti_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2!=8
+    async fn join_overestimated_build_side() -> Result<()> {
+        // the statistics of the build side claim 100M rows, whose hash table
+        // would not fit in the memory limit, it has 1000 rows
+        let keys = (0..1000).collect::<Vec<i32>>();
+        let left_batch = build_table_i32(("a1", &keys), ("b1", &keys), ("c1", &keys));
+        let left = Arc::new(MisestimatedExec {
+            input: Arc::new(MemoryExec::try_new(
+                &[vec![left_batch.clone()]],
+                left_batch.schema(),
+                None,
+            )?),
+            num_rows: 100_000_000,
+        });
+        let right = build_table(("a2", &keys), ("b2", &keys), ("c2", &keys));
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Right,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
+        let runtime_config = RuntimeConfig::new().with_memory_limit(1 << 20, 1.0);
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+        let session_ctx =
+            SessionContext::with_config_rt(SessionConfig::default(), runtime);
+        let task_ctx = session_ctx.task_ctx();
 
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
-        );
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1000);
 
-        let join = join_with_filter(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            filter,
-            &JoinType::LeftAnti,
-            false,
-        )?;
+        let build_mem_used = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("build_mem_used")
+            .map(|value| value.as_usize());
+        assert!(build_mem_used.unwrap() < 1 << 20);
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+        Ok(())
+    }
 
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
+    #[tokio::test]
+    async fn partitioned_join_spill() -> Result<()> {
+        // both sides have 10000 rows in 4 batches, half of them with a match
+        let build_keys_table = |names: (&str, &str, &str), keys: std::ops::Range<i32>| {
+            let keys = keys.collect::<Vec<_>>();
+            let batches = keys
+                .chunks(keys.len() / 4)
+                .map(|keys| {
+                    let keys = keys.to_vec();
+                    build_table_i32((names.0, &keys), (names.1, &keys), (names.2, &keys))
+                })
+                .collect::<Vec<_>>();
+            let schema = batches[0].schema();
+            Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+                as Arc<dyn ExecutionPlan>
+        };
+        let left = build_keys_table(("a1", "b1", "c1"), 0..10000);
+        let right = build_keys_table(("a2", "b2", "c2"), 5000..15000);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let sorted_lines = |batches: &[RecordBatch]| -> Result<Vec<String>> {
+            let mut lines = pretty_format_batches(batches)?
+                .to_string()
+                .lines()
+                .map(String::from)
+                .collect::<Vec<_>>();
+            lines.sort();
+            Ok(lines)
+        };
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 1  | 1  | 10  |",
-            "| 11 | 8  | 110 |",
-            "| 3  | 3  | 30  |",
-            "| 5  | 5  | 50  |",
-            "| 7  | 7  | 70  |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
+        let join_types = vec![
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
         ];
-        assert_batches_sorted_eq!(expected, &batches);
 
-        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 13
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
+        for join_type in join_types {
+            let join = || {
+                HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    None,
+                    &join_type,
+                    PartitionMode::Partitioned,
+                    false,
+                )
+            };
+            let task_ctx = SessionContext::new().task_ctx();
+            let expected = common::collect(join()?.execute(0, task_ctx)?).await?;
 
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+            // the build side of the partitions of the first split of the inputs
+            // does not fit in memory either, they are split again
+            let runtime_config = RuntimeConfig::new().with_memory_limit(20 * 1024, 1.0);
+            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+            let session_ctx =
+                SessionContext::with_config_rt(SessionConfig::default(), runtime);
+            let join = join()?;
+            let batches =
+                common::collect(join.execute(0, session_ctx.task_ctx())?).await?;
+
+            assert_eq!(
+                sorted_lines(&batches)?,
+                sorted_lines(&expected)?,
+                "{join_type}"
+            );
+            let metrics = join.metrics().unwrap();
+            assert!(
+                metrics.spill_count().unwrap() > 2 * SPILL_PARTITIONS,
+                "{join_type}"
+            );
+            assert!(metrics.spilled_bytes().unwrap() > 0);
+            // both inputs are spilled, some of their rows twice
+            assert!(metrics.spilled_rows().unwrap() > 20_000, "{join_type}");
+        }
 
-        let join = join_with_filter(left, right, on, filter, &JoinType::LeftAnti, false)?;
+        Ok(())
+    }
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+    #[tokio::test]
+    async fn spill_metrics_without_spilling() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        for mode in [PartitionMode::CollectLeft, PartitionMode::Partitioned] {
+            let join = HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                mode,
+                false,
+            )?;
+            let stream = join.execute(0, task_ctx.clone())?;
+            common::collect(stream).await?;
+
+            // the spill metrics are reported as zero rather than missing
+            let metrics = join.metrics().unwrap();
+            assert_eq!(metrics.spill_count(), Some(0), "{mode:?}");
+            assert_eq!(metrics.spilled_bytes(), Some(0), "{mode:?}");
+            assert_eq!(metrics.spilled_rows(), Some(0), "{mode:?}");
+        }
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 1  | 1  | 10  |",
-            "| 11 | 8  | 110 |",
-            "| 3  | 3  | 30  |",
-            "| 5  | 5  | 50  |",
-            "| 7  | 7  | 70  |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn partitioned_join_spill_compression() -> Result<()> {
+        let build_keys_table = |names: (&str, &str, &str), keys: std::ops::Range<i32>| {
+            let keys = keys.collect::<Vec<_>>();
+            let batches = keys
+                .chunks(keys.len() / 4)
+                .map(|keys| {
+                    let keys = keys.to_vec();
+                    build_table_i32((names.0, &keys), (names.1, &keys), (names.2, &keys))
+                })
+                .collect::<Vec<_>>();
+            let schema = batches[0].schema();
+            Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+                as Arc<dyn ExecutionPlan>
+        };
+        let left = build_keys_table(("a1", "b1", "c1"), 0..10000);
+        let right = build_keys_table(("a2", "b2", "c2"), 5000..15000);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let join = || {
+            HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Full,
+                PartitionMode::Partitioned,
+                false,
+            )
+        };
+        let task_ctx = SessionContext::new().task_ctx();
+        let expected = common::collect(join()?.execute(0, task_ctx)?).await?;
+        let expected = pretty_format_batches(&expected)?.to_string();
+        let mut expected = expected.lines().collect::<Vec<_>>();
+        expected.sort();
+
+        let spill_dir = tempfile::tempdir()?;
+        for compression in ["none", "lz4", "zstd", "snappy"] {
+            let runtime_config = RuntimeConfig::new()
+                .with_memory_limit(20 * 1024, 1.0)
+                .with_disk_manager(DiskManagerConfig::NewSpecified(vec![spill_dir
+                    .path()
+                    .to_path_buf()]));
+            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+            let mut session_config = SessionConfig::default();
+            session_config
+                .options_mut()
+                .execution
+                .hash_join_spill_compression = compression.to_string();
+            let session_ctx = SessionContext::with_config_rt(session_config, runtime);
+            let join = join()?;
+            // an unsupported codec is reported before the join spills
+            let stream = join.execute(0, session_ctx.task_ctx());
+            if compression == "snappy" {
+                match stream {
+                    Err(e) => assert_contains!(
+                        e.to_string(),
+                        "Unsupported hash join spill compression: snappy"
+                    ),
+                    Ok(_) => panic!("unsupported spill compression accepted"),
+                }
+                continue;
+            }
+            let result = common::collect(stream?).await;
+            let batches = pretty_format_batches(&result?)?.to_string();
+            let mut batches = batches.lines().collect::<Vec<_>>();
+            batches.sort();
+            assert_eq!(batches, expected, "{compression}");
+
+            let metrics = join.metrics().unwrap();
+            assert!(metrics.spilled_bytes().unwrap() > 0, "{compression}");
+            let spill_file_bytes = metrics
+                .sum_by_name("spill_file_bytes")
+                .map(|value| value.as_usize());
+            assert!(spill_file_bytes.unwrap() > 0, "{compression}");
+        }
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_anti() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
+    async fn partitioned_join_skewed_partition_spill() -> Result<()> {
+        // the first of the 4 partitions has 100 times as many rows as the
+        // others, the probe side has every other key of the build side
+        let build_partitions = |names: (&str, &str, &str), step: usize| {
+            let partitions = (0..4)
+                .map(|partition| {
+                    let keys = if partition == 0 {
+                        0..100_000
+                    } else {
+                        let start = 100_000 + (partition - 1) * 1000;
+                        start..start + 1000
+                    };
+                    let keys = keys.step_by(step).collect::<Vec<_>>();
+                    keys.chunks(10_000)
+                        .map(|keys| {
+                            let keys = keys.to_vec();
+                            build_table_i32(
+                                (names.0, &keys),
+                                (names.1, &keys),
+                                (names.2, &keys),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let schema = partitions[0][0].schema();
+            Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap())
+                as Arc<dyn ExecutionPlan>
+        };
+        let left = build_partitions(("a1", "b1", "c1"), 1);
+        let right = build_partitions(("a2", "b2", "c2"), 2);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::RightAnti, false)?;
+        let mut session_config = SessionConfig::default();
+        session_config
+            .options_mut()
+            .execution
+            .hash_join_partition_memory_budget = 1024 * 1024;
+        let task_ctx = SessionContext::with_config(session_config).task_ctx();
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+        for (join_type, expected_rows) in
+            [(JoinType::Inner, 51_500), (JoinType::Left, 103_000)]
+        {
+            let join = Arc::new(HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &join_type,
+                PartitionMode::Partitioned,
+                false,
+            )?);
+            let partitions = collect_partitioned(join.clone(), task_ctx.clone()).await?;
+            let num_rows = partitions
+                .iter()
+                .flatten()
+                .map(|batch| batch.num_rows())
+                .sum::<usize>();
+            assert_eq!(num_rows, expected_rows, "{join_type}");
+
+            // only the skewed partition exceeded its budget and spilled
+            let metrics = join.metrics().unwrap();
+            let spill_count = |partition| {
+                metrics
+                    .sum(|metric| {
+                        matches!(metric.value(), MetricValue::SpillCount(_))
+                            && metric.partition() == Some(partition)
+                    })
+                    .map(|value| value.as_usize())
+                    .unwrap_or(0)
+            };
+            assert!(spill_count(0) > 0, "{join_type}");
+            for partition in 1..4 {
+                assert_eq!(spill_count(partition), 0, "{join_type}");
+            }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+            // the build side memory of the skewed partition peaked above its
+            // budget before it spilled
+            let build_mem_used = metric_by_partition(&metrics, "build_mem_used");
+            assert!(build_mem_used[0].1 > 1024 * 1024, "{join_type}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_build_side_memory_released() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+        );
+        let right = build_table(
+            ("a2", &vec![1, 2]),
+            ("b2", &vec![1, 2]),
+            ("c2", &vec![14, 15]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
+
+        for join_type in [JoinType::Inner, JoinType::Full, JoinType::LeftAnti] {
+            let runtime_config = RuntimeConfig::new().with_memory_limit(1 << 20, 1.0);
+            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+            let session_ctx =
+                SessionContext::with_config_rt(SessionConfig::default(), runtime);
+            let task_ctx = session_ctx.task_ctx();
+
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let mut stream = join.execute(0, task_ctx.clone())?;
+            stream.next().await.transpose()?;
+            drop(stream);
+            // the build side is shared by the executions of the join, and not
+            // released by a probe stream which is not done
+            assert!(task_ctx.memory_pool().reserved() > 0);
+
+            drop(join);
+            assert_eq!(task_ctx.memory_pool().reserved(), 0);
+        }
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 2  | 2  | 80  |",
-            "| 4  | 4  | 120 |",
-            "| 6  | 6  | 60  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_anti_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table right anti join right_table on left_table.b1 = right_table.b2 and left_table.a1!=13
+    async fn join_full_mostly_unmatched_bounded_batches() -> Result<()> {
+        // 10 of the 100000 build side rows have a match
+        let keys = (0..100_000).collect::<Vec<_>>();
+        let left = build_table(("a1", &keys), ("b1", &keys), ("c1", &keys));
+        let right = build_table(
+            ("a2", &(0..10).collect()),
+            ("b2", &(0..10).collect()),
+            ("c2", &(0..10).collect()),
+        );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Left,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(13)))),
-        )) as Arc<dyn PhysicalExpr>;
+        let runtime_config = RuntimeConfig::new().with_memory_limit(16 << 20, 1.0);
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+        let session_config = SessionConfig::default().with_batch_size(1000);
+        let session_ctx = SessionContext::with_config_rt(session_config, runtime);
+        let task_ctx = session_ctx.task_ctx();
 
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices,
-            intermediate_schema.clone(),
-        );
+        let join = join(left, right, on, &JoinType::Full, false)?;
+        let mut stream = join.execute(0, task_ctx.clone())?;
+        let mut num_rows = 0;
+        while let Some(batch) = stream.next().await.transpose()? {
+            // the unmatched build side rows are produced in batches of at most
+            // `batch_size` rows, with the build side still reserved
+            assert!(batch.num_rows() <= 1000);
+            assert!(task_ctx.memory_pool().reserved() > 0);
+            num_rows += batch.num_rows();
+        }
+        assert_eq!(num_rows, 100_000);
+
+        // the bitmap of the visited build side rows is part of the build side
+        // memory
+        let build_mem_used = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("build_mem_used")
+            .map(|value| value.as_usize())
+            .unwrap();
+        assert!(build_mem_used >= 100_000 * 12 + 100_000 / 8);
 
-        let join = join_with_filter(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            filter,
-            &JoinType::RightAnti,
-            false,
-        )?;
+        Ok(())
+    }
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+    #[tokio::test]
+    async fn join_build_cancelled_when_stream_dropped() -> Result<()> {
+        // the build side never ends, and is collected by 2 tasks
+        let batch = build_table_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let inputs = [
+            Arc::new(InfiniteExec::new(batch.clone())),
+            Arc::new(InfiniteExec::new(batch)),
+        ];
+        let left = Arc::new(UnionExec::new(
+            inputs
+                .iter()
+                .map(|input| input.clone() as Arc<dyn ExecutionPlan>)
+                .collect(),
+        ));
+        let right = build_table(
+            ("a2", &vec![1, 2]),
+            ("b2", &vec![4, 5]),
+            ("c2", &vec![7, 8]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let batches_produced = || {
+            inputs
+                .iter()
+                .map(|input| input.batches_produced())
+                .sum::<usize>()
+        };
 
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
+        let session_config = SessionConfig::new().with_target_partitions(2);
+        let task_ctx = SessionContext::with_config(session_config).task_ctx();
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        let mut stream = join.execute(0, task_ctx.clone())?;
+        let next =
+            tokio::time::timeout(std::time::Duration::from_millis(100), stream.next())
+                .await;
+        assert!(next.is_err(), "the build side never ends");
+        assert!(batches_produced() > 0);
+
+        // the build tasks stop reading the build side once the stream is
+        // dropped, although the join is not, and release their memory
+        drop(stream);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let produced = batches_produced();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(batches_produced(), produced);
+        assert_eq!(task_ctx.memory_pool().reserved(), 0);
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 10 | 10 | 100 |",
-            "| 12 | 10 | 40  |",
-            "| 2  | 2  | 80  |",
-            "| 4  | 4  | 120 |",
-            "| 6  | 6  | 60  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
 
-        // left_table right anti join right_table on left_table.b1 = right_table.b2 and right_table.b2!=8
-        let column_indices = vec![ColumnIndex {
-            index: 1,
-            side: JoinSide::Right,
-        }];
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
+    #[tokio::test]
+    async fn join_build_side_released_by_last_probe_stream() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+        );
+        let batch1 = build_table_i32(
+            ("a2", &vec![1, 2]),
+            ("b2", &vec![1, 2]),
+            ("c2", &vec![14, 15]),
+        );
+        let batch2 =
+            build_table_i32(("a2", &vec![3]), ("b2", &vec![3]), ("c2", &vec![16]));
+        let schema = batch1.schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![batch1], vec![batch2]],
+            schema,
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
 
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        for join_type in [JoinType::Inner, JoinType::Left, JoinType::LeftAnti] {
+            let session_ctx = SessionContext::new();
+            let task_ctx = session_ctx.task_ctx();
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let stream1 = join.execute(0, task_ctx.clone())?;
+            let stream2 = join.execute(1, task_ctx.clone())?;
+
+            // the other probe stream still uses the build side
+            let batches1 = common::collect(stream1).await?;
+            assert!(task_ctx.memory_pool().reserved() > 0, "{join_type}");
+
+            // the last probe stream releases the build side once it is done,
+            // although the join is not dropped
+            let batches2 = common::collect(stream2).await?;
+            assert_eq!(task_ctx.memory_pool().reserved(), 0, "{join_type}");
+
+            let num_rows = batches1
+                .iter()
+                .chain(&batches2)
+                .map(|batch| batch.num_rows())
+                .sum::<usize>();
+            let expected_rows = match join_type {
+                JoinType::Inner => 3,
+                JoinType::Left => 10,
+                _ => 7,
+            };
+            assert_eq!(num_rows, expected_rows, "{join_type}");
+        }
 
-        let join =
-            join_with_filter(left, right, on, filter, &JoinType::RightAnti, false)?;
+        Ok(())
+    }
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+    /// Builds a build side table with `width` nullable Int32 columns `l0..`,
+    /// where `l0` is the join key and every third value of the others is null
+    fn build_wide_table(width: usize) -> Arc<dyn ExecutionPlan> {
+        let keys = vec![1, 2, 2, 3, 4, 5, 5, 7];
+        let fields = (0..width)
+            .map(|i| Field::new(format!("l{i}"), DataType::Int32, true))
+            .collect::<Vec<_>>();
+        let columns = (0..width)
+            .map(|i| {
+                let values = keys.iter().enumerate().map(|(row, key)| {
+                    if i == 0 {
+                        Some(*key)
+                    } else {
+                        (row % 3 != 0).then_some((row * width + i) as i32)
+                    }
+                });
+                Arc::new(Int32Array::from_iter(values)) as ArrayRef
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new(fields));
+        let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+    #[tokio::test]
+    async fn join_build_row_format() -> Result<()> {
+        let left = build_wide_table(20);
+        assert!(BuildSideRows::is_beneficial(&left.schema()));
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40, 50]),
+            ("b2", &vec![2, 3, 5, 6, 2]),
+            ("c2", &vec![70, 80, 90, 100, 110]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("l0", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
 
-        let expected = vec![
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 2  | 2  | 80  |",
-            "| 4  | 4  | 120 |",
-            "| 6  | 6  | 60  |",
-            "| 8  | 8  | 20  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ] {
+            let mut results = vec![];
+            for row_format in [true, false] {
+                let config = SessionConfig::new().set_bool(
+                    "datafusion.execution.hash_join_build_row_format",
+                    row_format,
+                );
+                let session_ctx = SessionContext::with_config(config);
+                let (_, batches) = join_collect(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    &join_type,
+                    false,
+                    session_ctx.task_ctx(),
+                )
+                .await?;
+                let formatted = pretty_format_batches(&batches)?.to_string();
+                let mut lines = formatted
+                    .trim()
+                    .lines()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                lines.sort_unstable();
+                results.push(lines);
+            }
+            assert_eq!(results[0], results[1], "{join_type}");
+        }
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
-        );
+    async fn join_build_side_of_several_batches() -> Result<()> {
+        let left = build_wide_table(20);
+        let batch = common::collect(left.execute(0, SessionContext::new().task_ctx())?)
+            .await?
+            .remove(0);
+        // the same rows split into batches of different sizes, one of them empty
+        let split = [(0, 2), (2, 0), (2, 3), (5, 3)]
+            .into_iter()
+            .map(|(offset, length)| batch.slice(offset, length))
+            .collect::<Vec<_>>();
+        let split_left: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[split], batch.schema(), None)?);
         let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
-            ("c2", &vec![70, 80, 90]),
+            ("a2", &vec![10, 20, 30, 40, 50]),
+            ("b2", &vec![2, 3, 5, 6, 2]),
+            ("c2", &vec![70, 80, 90, 100, 110]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("l0", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
+        // l1 < c2
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("l1", 0)),
+                Operator::Lt,
+                Arc::new(Column::new("c2", 1)),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 1,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("l1", DataType::Int32, true),
+                Field::new("c2", DataType::Int32, true),
+            ]),
+        );
 
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Right, false, task_ctx).await?;
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+        ] {
+            for filter in [None, Some(filter.clone())] {
+                for row_format in [true, false] {
+                    let config = SessionConfig::new().set_bool(
+                        "datafusion.execution.hash_join_build_row_format",
+                        row_format,
+                    );
+                    let session_ctx = SessionContext::with_config(config);
+                    let mut results = vec![];
+                    for left in [left.clone(), split_left.clone()] {
+                        let join = HashJoinExec::try_new(
+                            left,
+                            right.clone(),
+                            on.clone(),
+                            filter.clone(),
+                            &join_type,
+                            PartitionMode::CollectLeft,
+                            false,
+                        )?;
+                        let stream = join.execute(0, session_ctx.task_ctx())?;
+                        let batches = common::collect(stream).await?;
+                        let formatted = pretty_format_batches(&batches)?.to_string();
+                        let mut lines = formatted
+                            .trim()
+                            .lines()
+                            .map(String::from)
+                            .collect::<Vec<_>>();
+                        lines.sort_unstable();
+                        results.push(lines);
+                    }
+                    assert_eq!(
+                        results[0], results[1],
+                        "{join_type} with filter {filter:?} and row format {row_format}"
+                    );
+                }
+            }
+        }
 
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+        Ok(())
+    }
 
-        let expected = vec![
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
-        ];
+    #[tokio::test]
+    async fn join_empty_build_side() -> Result<()> {
+        let left = build_table(("a1", &vec![]), ("b1", &vec![]), ("c1", &vec![]));
+        let probe_rows = 10_000;
+        let keys = (0..probe_rows).collect::<Vec<_>>();
+        let right_batches = keys
+            .chunks(1000)
+            .map(|keys| {
+                build_table_i32(
+                    ("a2", &keys.to_vec()),
+                    ("b2", &keys.to_vec()),
+                    ("c2", &keys.to_vec()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let right_schema = right_batches[0].schema();
+        let right: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &[right_batches],
+            right_schema.clone(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("a2", &right_schema)?) as _,
+        )];
 
-        assert_batches_sorted_eq!(expected, &batches);
+        for (join_type, expected_rows) in [
+            (JoinType::Inner, 0),
+            (JoinType::Left, 0),
+            (JoinType::LeftSemi, 0),
+            (JoinType::LeftAnti, 0),
+            (JoinType::RightSemi, 0),
+            (JoinType::Right, probe_rows as usize),
+            (JoinType::Full, probe_rows as usize),
+            (JoinType::RightAnti, probe_rows as usize),
+        ] {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let session_ctx = SessionContext::new();
+            let stream = join.execute(0, session_ctx.task_ctx())?;
+            let batches = common::collect(stream).await?;
+            let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+            assert_eq!(num_rows, expected_rows, "{join_type}");
+
+            if expected_rows > 0 {
+                // the probe side rows are passed through, null-padded on the
+                // build side for outer joins
+                let batch = &batches[0];
+                assert_eq!(batch.schema(), join.schema());
+                let probe_column = batch.column(batch.num_columns() - 3);
+                assert_eq!(probe_column.len(), 1000);
+                assert_eq!(probe_column.null_count(), 0);
+                if join_type != JoinType::RightAnti {
+                    assert_eq!(batch.column(0).null_count(), 1000);
+                }
+            }
 
+            // the probe side is not joined with the build side
+            let metrics = join.metrics().unwrap();
+            let join_time = metrics.sum_by_name("join_time").map(|m| m.as_usize());
+            assert_eq!(join_time, Some(0), "{join_type}");
+            let output_rows = metrics.output_rows();
+            assert_eq!(output_rows, Some(expected_rows), "{join_type}");
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn partitioned_join_right_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_output_split_into_batch_size() -> Result<()> {
+        // every probe row with key 1 matches 10000 build rows
+        let build_keys = std::iter::repeat(1)
+            .take(10_000)
+            .chain(2..7)
+            .collect::<Vec<_>>();
         let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
+            ("a1", &build_keys),
+            ("b1", &build_keys),
+            ("c1", &build_keys),
         );
         let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
-            ("c2", &vec![70, 80, 90]),
+            ("a2", &vec![1, 1, 7]),
+            ("b2", &vec![1, 1, 7]),
+            ("c2", &vec![10, 20, 30]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let (columns, batches) =
-            partitioned_join_collect(left, right, on, &JoinType::Right, false, task_ctx)
-                .await?;
-
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
-
-        let expected = vec![
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
-        ];
-
-        assert_batches_sorted_eq!(expected, &batches);
+        let batch_size = 999;
+        for (join_type, expected_rows) in [
+            (JoinType::Inner, 20_000),
+            (JoinType::Left, 20_005),
+            (JoinType::Right, 20_001),
+            (JoinType::Full, 20_006),
+            (JoinType::LeftSemi, 10_000),
+            (JoinType::LeftAnti, 5),
+            (JoinType::RightSemi, 2),
+            (JoinType::RightAnti, 1),
+        ] {
+            let session_ctx = SessionContext::with_config(
+                SessionConfig::new().with_batch_size(batch_size),
+            );
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let stream = join.execute(0, session_ctx.task_ctx())?;
+            let batches = common::collect(stream).await?;
 
+            for batch in &batches {
+                assert!(batch.num_rows() <= batch_size, "{join_type}");
+            }
+            let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+            assert_eq!(num_rows, expected_rows, "{join_type}");
+
+            let metrics = join.metrics().unwrap();
+            let output_batches =
+                metrics.sum_by_name("output_batches").map(|m| m.as_usize());
+            assert_eq!(output_batches, Some(batches.len()), "{join_type}");
+            assert_eq!(metrics.output_rows(), Some(expected_rows), "{join_type}");
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_full_one() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_probe_read_ahead() -> Result<()> {
+        let build_keys = (0..100).map(|i| i * 3).collect::<Vec<_>>();
         let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b2", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
+            ("a1", &build_keys),
+            ("b1", &build_keys),
+            ("c1", &build_keys),
         );
+        let right_batches = (0..10)
+            .map(|batch| {
+                let keys = (batch * 50..(batch + 1) * 50).collect::<Vec<_>>();
+                build_table_i32(("a2", &keys), ("b2", &keys), ("c2", &keys))
+            })
+            .collect::<Vec<_>>();
+        let right_schema = right_batches[0].schema();
+        let right: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &[right_batches],
+            right_schema.clone(),
+            None,
+        )?);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b2", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right_schema)?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Full, false)?;
+        for join_type in [JoinType::Inner, JoinType::Right, JoinType::Full] {
+            let mut results = vec![];
+            for read_ahead in [0, 1, 4] {
+                let config = SessionConfig::new().set_usize(
+                    "datafusion.execution.hash_join_probe_read_ahead",
+                    read_ahead,
+                );
+                let session_ctx = SessionContext::with_config(config);
+                let join =
+                    join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+                let stream = join.execute(0, session_ctx.task_ctx())?;
+                let batches = common::collect(stream).await?;
+                results.push(pretty_format_batches(&batches)?.to_string());
+            }
+            // the probe batches read ahead are joined in order
+            assert_eq!(results[0], results[1], "{join_type}");
+            assert_eq!(results[0], results[2], "{join_type}");
+        }
+        Ok(())
+    }
+
+    /// Wraps `input`, counting the batches polled from it
+    #[derive(Debug)]
+    struct PollCountingExec {
+        input: Arc<dyn ExecutionPlan>,
+        polled: Arc<AtomicUsize>,
+    }
+
+    impl ExecutionPlan for PollCountingExec {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.input.schema()
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            self.input.output_partitioning()
+        }
+
+        fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+            None
+        }
+
+        fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+            vec![self.input.clone()]
+        }
+
+        fn with_new_children(
+            self: Arc<Self>,
+            children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(PollCountingExec {
+                input: children[0].clone(),
+                polled: self.polled.clone(),
+            }))
+        }
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        fn execute(
+            &self,
+            partition: usize,
+            context: Arc<TaskContext>,
+        ) -> Result<SendableRecordBatchStream> {
+            let stream = self.input.execute(partition, context)?;
+            let polled = self.polled.clone();
+            Ok(Box::pin(RecordBatchStreamAdapter::new(
+                stream.schema(),
+                stream.inspect(move |_| {
+                    polled.fetch_add(1, Ordering::SeqCst);
+                }),
+            )))
+        }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "PollCountingExec")
+        }
 
-        let expected = vec![
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        fn statistics(&self) -> Statistics {
+            self.input.statistics()
+        }
+    }
 
-        Ok(())
+    /// A probe side which panics if executed
+    #[derive(Debug)]
+    struct UnexecutableExec {
+        schema: SchemaRef,
     }
 
-    #[test]
-    fn join_with_hash_collision() -> Result<()> {
-        let mut hashmap_left = RawTable::with_capacity(2);
-        let left = build_table_i32(
-            ("a", &vec![10, 20]),
-            ("x", &vec![100, 200]),
-            ("y", &vec![200, 300]),
-        );
+    impl ExecutionPlan for UnexecutableExec {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
 
-        let random_state = RandomState::with_seeds(0, 0, 0, 0);
-        let hashes_buff = &mut vec![0; left.num_rows()];
-        let hashes =
-            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
 
-        // Create hash collisions (same hashes)
-        hashmap_left.insert(hashes[0], (hashes[0], smallvec![0, 1]), |(h, _)| *h);
-        hashmap_left.insert(hashes[1], (hashes[1], smallvec![0, 1]), |(h, _)| *h);
+        fn output_partitioning(&self) -> Partitioning {
+            Partitioning::UnknownPartitioning(1)
+        }
 
-        let right = build_table_i32(
-            ("a", &vec![10, 20]),
-            ("b", &vec![0, 0]),
-            ("c", &vec![30, 40]),
-        );
+        fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+            None
+        }
 
-        let left_data = (JoinHashMap(hashmap_left), left);
-        let (l, r) = build_equal_condition_join_indices(
-            &left_data.0,
-            &left_data.1,
-            &right,
-            &[Column::new("a", 0)],
-            &[Column::new("a", 0)],
-            &random_state,
-            false,
-            &mut vec![0; right.num_rows()],
-            None,
-        )?;
+        fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+            vec![]
+        }
 
-        let mut left_ids = UInt64Builder::with_capacity(0);
-        left_ids.append_value(0);
-        left_ids.append_value(1);
+        fn with_new_children(
+            self: Arc<Self>,
+            _children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(self)
+        }
 
-        let mut right_ids = UInt32Builder::with_capacity(0);
-        right_ids.append_value(0);
-        right_ids.append_value(1);
+        fn execute(
+            &self,
+            _partition: usize,
+            _context: Arc<TaskContext>,
+        ) -> Result<SendableRecordBatchStream> {
+            panic!("the probe side should not be executed")
+        }
 
-        assert_eq!(left_ids.finish(), l);
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "UnexecutableExec")
+        }
 
-        assert_eq!(right_ids.finish(), r);
+        fn statistics(&self) -> Statistics {
+            Statistics::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn join_empty_build_side_skips_probe_side() -> Result<()> {
+        let left = build_table(("a1", &vec![]), ("b1", &vec![]), ("c1", &vec![]));
+        let right: Arc<dyn ExecutionPlan> = Arc::new(UnexecutableExec {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a2", DataType::Int32, false),
+                Field::new("b1", DataType::Int32, false),
+            ])),
+        });
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
 
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+        ] {
+            let session_ctx = SessionContext::new();
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            let stream = join.execute(0, session_ctx.task_ctx())?;
+            let batches = common::collect(stream).await?;
+            assert!(batches.is_empty(), "{join_type}");
+            assert!(join.build_side_info().unwrap().is_empty(), "{join_type}");
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_with_duplicated_column_names() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_probe_read_ahead_bounded() -> Result<()> {
+        let build_keys = (0..100).collect::<Vec<_>>();
         let left = build_table(
-            ("a", &vec![1, 2, 3]),
-            ("b", &vec![4, 5, 7]),
-            ("c", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a", &vec![10, 20, 30]),
-            ("b", &vec![1, 2, 7]),
-            ("c", &vec![70, 80, 90]),
+            ("a1", &build_keys),
+            ("b1", &build_keys),
+            ("c1", &build_keys),
         );
+        let right_batches = (0..20)
+            .map(|batch| {
+                let keys = (batch * 50..(batch + 1) * 50).collect::<Vec<_>>();
+                build_table_i32(("a2", &keys), ("b2", &keys), ("c2", &keys))
+            })
+            .collect::<Vec<_>>();
+        let right_schema = right_batches[0].schema();
+        let polled = Arc::new(AtomicUsize::new(0));
+        let right: Arc<dyn ExecutionPlan> = Arc::new(PollCountingExec {
+            input: Arc::new(MemoryExec::try_new(
+                &[right_batches],
+                right_schema.clone(),
+                None,
+            )?),
+            polled: polled.clone(),
+        });
         let on = vec![(
-            // join on a=b so there are duplicate column names on unjoined columns
-            Column::new_with_schema("a", &left.schema()).unwrap(),
-            Column::new_with_schema("b", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right_schema)?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Inner, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+---+---+---+----+---+----+",
-            "| a | b | c | a  | b | c  |",
-            "+---+---+---+----+---+----+",
-            "| 1 | 4 | 7 | 10 | 1 | 70 |",
-            "| 2 | 5 | 8 | 20 | 2 | 80 |",
-            "+---+---+---+----+---+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
-
+        let read_ahead = 2;
+        let config = SessionConfig::new().set_usize(
+            "datafusion.execution.hash_join_probe_read_ahead",
+            read_ahead,
+        );
+        let session_ctx = SessionContext::with_config(config);
+        // every probe batch produces an output batch
+        let join = join(left, right, on, &JoinType::Right, false)?;
+        let mut stream = join.execute(0, session_ctx.task_ctx())?;
+        let mut num_rows = stream.next().await.unwrap()?.num_rows();
+
+        // while the output is not consumed, the probe side is read ahead of the
+        // joined batch by exactly `read_ahead` batches
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(polled.load(Ordering::SeqCst), 1 + read_ahead);
+
+        while let Some(batch) = stream.next().await.transpose()? {
+            num_rows += batch.num_rows();
+        }
+        assert_eq!(polled.load(Ordering::SeqCst), 20);
+        assert_eq!(num_rows, 1000);
+        // the batches read ahead are released once joined
+        drop(stream);
+        drop(join);
+        assert_eq!(session_ctx.task_ctx().memory_pool().reserved(), 0);
         Ok(())
     }
 
-    fn prepare_join_filter() -> JoinFilter {
-        let column_indices = vec![
-            ColumnIndex {
-                index: 2,
-                side: JoinSide::Left,
-            },
-            ColumnIndex {
-                index: 2,
-                side: JoinSide::Right,
-            },
-        ];
-        let intermediate_schema = Schema::new(vec![
-            Field::new("c", DataType::Int32, true),
-            Field::new("c", DataType::Int32, true),
-        ]);
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("c", 0)),
-            Operator::Gt,
-            Arc::new(Column::new("c", 1)),
-        )) as Arc<dyn PhysicalExpr>;
+    #[tokio::test]
+    async fn join_probe_read_ahead_memory_pressure() -> Result<()> {
+        let build_keys = (0..100).collect::<Vec<_>>();
+        let left = build_table(
+            ("a1", &build_keys),
+            ("b1", &build_keys),
+            ("c1", &build_keys),
+        );
+        let right_batches = (0..20)
+            .map(|batch| {
+                let keys = (batch * 50..(batch + 1) * 50).collect::<Vec<_>>();
+                build_table_i32(("a2", &keys), ("b2", &keys), ("c2", &keys))
+            })
+            .collect::<Vec<_>>();
+        let right_schema = right_batches[0].schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[right_batches],
+            right_schema.clone(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right_schema)?) as _,
+        )];
 
-        JoinFilter::new(filter_expression, column_indices, intermediate_schema)
+        let pool_size = 16 << 20;
+        let runtime_config = RuntimeConfig::new().with_memory_limit(pool_size, 1.0);
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+        let session_config = SessionConfig::new()
+            .set_usize("datafusion.execution.hash_join_probe_read_ahead", 4);
+        let session_ctx = SessionContext::with_config_rt(session_config, runtime);
+        let task_ctx = session_ctx.task_ctx();
+        let join = join(left, right, on, &JoinType::Right, false)?;
+        let mut stream = join.execute(0, task_ctx.clone())?;
+        let mut num_rows = stream.next().await.unwrap()?.num_rows();
+
+        // the probe batches read from now on do not fit in the memory pool, and
+        // are read as the join consumes them instead of failing the join
+        let pool = task_ctx.memory_pool();
+        let mut reservation = MemoryConsumer::new("PressureTest").register(pool);
+        reservation.grow(pool_size.saturating_sub(pool.reserved()));
+        while let Some(batch) = stream.next().await.transpose()? {
+            num_rows += batch.num_rows();
+        }
+        assert_eq!(num_rows, 1000);
+        drop(stream);
+        drop(join);
+        reservation.free();
+        assert_eq!(pool.reserved(), 0);
+        Ok(())
     }
 
     #[tokio::test]
-    async fn join_inner_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+    async fn join_unique_build_keys() -> Result<()> {
+        // the primary keys of the build side are the groups of an aggregation
+        let table = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6]),
+            ("b1", &vec![4, 5, 5, 7, 8, 4]),
+            ("c1", &vec![7, 8, 9, 1, 2, 3]),
         );
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            Arc::new(Column::new("b1", 1)) as _,
+            "b1".to_string(),
+        )]);
+        let aggregate: Arc<dyn ExecutionPlan> = Arc::new(AggregateExec::try_new(
+            AggregateMode::Single,
+            group_by,
+            vec![],
+            vec![],
+            vec![],
+            table.clone(),
+            table.schema(),
+        )?);
+        // the foreign keys of the probe side
         let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+            ("a2", &vec![10, 20, 30, 40, 50, 60]),
+            ("b2", &vec![4, 8, 4, 9, 5, 8]),
+            ("c2", &vec![70, 80, 90, 10, 20, 30]),
         );
+
+        let session_ctx = SessionContext::new();
+        let groups =
+            common::collect(aggregate.execute(0, session_ctx.task_ctx())?).await?;
+        let left: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[groups], aggregate.schema(), None)?);
         let on = vec![(
-            Column::new_with_schema("a", &left.schema()).unwrap(),
-            Column::new_with_schema("b", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &aggregate.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "+---+---+---+----+---+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
 
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ] {
+            let mut results = vec![];
+            for (left, unique_build_keys) in [(&aggregate, true), (&left, false)] {
+                let join =
+                    join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+                let plan = displayable(&join).indent().to_string();
+                assert_eq!(
+                    plan.contains("unique_build_keys=true"),
+                    unique_build_keys,
+                    "{plan}"
+                );
+                let stream = join.execute(0, session_ctx.task_ctx())?;
+                let batches = common::collect(stream).await?;
+                results.push(pretty_format_batches(&batches)?.to_string());
+            }
+            // stopping at the first match of every probe row matches the same rows
+            assert_eq!(results[0], results[1], "{join_type}");
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_left_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+    async fn join_unique_build_keys_below_other_operators() -> Result<()> {
+        let table = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6]),
+            ("b1", &vec![4, 5, 5, 7, 8, 4]),
+            ("c1", &vec![7, 8, 9, 1, 2, 3]),
         );
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            Arc::new(Column::new("b1", 1)) as _,
+            "b1".to_string(),
+        )]);
+        let aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Single,
+            group_by,
+            vec![],
+            vec![],
+            vec![],
+            table.clone(),
+            table.schema(),
+        )?);
+        // the unique key of the aggregation is kept by the operators above it
+        let left: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (
+                    Arc::new(Literal::new(ScalarValue::Int32(Some(1)))) as _,
+                    "one".to_string(),
+                ),
+                (Arc::new(Column::new("b1", 0)) as _, "b1".to_string()),
+            ],
+            Arc::new(CoalesceBatchesExec::new(aggregate, 4096)),
+        )?);
+        assert_eq!(left.unique_keys(), vec![vec![1]]);
         let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+            ("a2", &vec![10, 20, 30, 40, 50, 60]),
+            ("b2", &vec![4, 8, 4, 9, 5, 8]),
+            ("c2", &vec![70, 80, 90, 10, 20, 30]),
         );
         let on = vec![(
-            Column::new_with_schema("a", &left.schema()).unwrap(),
-            Column::new_with_schema("b", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Left, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        let plan = displayable(&join).indent().to_string();
+        assert!(plan.contains("unique_build_keys=true"), "{plan}");
+        // every probe row matches at most one row of the build side
+        let statistics = join.statistics();
+        assert_eq!(statistics.num_rows, Some(6));
+        assert!(!statistics.is_exact);
 
+        let session_ctx = SessionContext::new();
+        let batches = common::collect(join.execute(0, session_ctx.task_ctx())?).await?;
         let expected = vec![
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "| 0 | 4 | 7 |    |   |   |",
-            "| 1 | 5 | 8 |    |   |   |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "| 2 | 8 | 1 |    |   |   |",
-            "+---+---+---+----+---+---+",
+            "+-----+----+----+----+----+",
+            "| one | b1 | a2 | b2 | c2 |",
+            "+-----+----+----+----+----+",
+            "| 1   | 4  | 10 | 4  | 70 |",
+            "| 1   | 4  | 30 | 4  | 90 |",
+            "| 1   | 5  | 50 | 5  | 20 |",
+            "| 1   | 8  | 20 | 8  | 80 |",
+            "| 1   | 8  | 60 | 8  | 30 |",
+            "+-----+----+----+----+----+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
-
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_right_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+    async fn partitioned_join_partition_count_mismatch() -> Result<()> {
+        let batch = build_table_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
         );
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone()], vec![batch.clone()]],
+            batch.schema(),
+            None,
+        )?);
         let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+            ("a2", &vec![1, 2, 3]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![7, 8, 9]),
         );
         let on = vec![(
-            Column::new_with_schema("a", &left.schema()).unwrap(),
-            Column::new_with_schema("b", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Right, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "|   |   |   | 30 | 3 | 6 |",
-            "|   |   |   | 40 | 4 | 4 |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "+---+---+---+----+---+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+            false,
+        )?;
 
+        let session_ctx = SessionContext::new();
+        let err = match join.execute(0, session_ctx.task_ctx()) {
+            Ok(_) => panic!("expected the partition count mismatch to fail"),
+            Err(err) => err,
+        };
+        assert!(matches!(err, DataFusionError::Plan(_)), "{err}");
+        assert_contains!(
+            err.to_string(),
+            "the left input has 2 partitions and the right input has 1"
+        );
+        assert_contains!(err.to_string(), "RepartitionExec");
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_full_with_filter() -> Result<()> {
-        let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
+    async fn join_filter_side_predicates() -> Result<()> {
         let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![1, 1, 2, 2]),
+            ("c1", &vec![10, 20, 30, 40]),
         );
         let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+            ("a2", &vec![1, 2, 3]),
+            ("b2", &vec![1, 2, 3]),
+            ("c2", &vec![5, 15, 25]),
         );
         let on = vec![(
-            Column::new_with_schema("a", &left.schema()).unwrap(),
-            Column::new_with_schema("b", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
 
-        let join = join_with_filter(left, right, on, filter, &JoinType::Full, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "|   |   |   | 30 | 3 | 6 |",
-            "|   |   |   | 40 | 4 | 4 |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "| 0 | 4 | 7 |    |   |   |",
-            "| 1 | 5 | 8 |    |   |   |",
-            "| 2 | 8 | 1 |    |   |   |",
-            "+---+---+---+----+---+---+",
+        // c1 > c2 AND c2 > 5 AND a1 < 4
+        let column_indices = vec![
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
         ];
-        assert_batches_sorted_eq!(expected, &batches);
+        let intermediate_schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, true),
+            Field::new("c2", DataType::Int32, true),
+            Field::new("a1", DataType::Int32, true),
+        ]);
+        let literal = |value| Arc::new(Literal::new(ScalarValue::Int32(Some(value))));
+        let mixed = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("c1", 0)),
+            Operator::Gt,
+            Arc::new(Column::new("c2", 1)),
+        ));
+        let right_only = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("c2", 1)),
+            Operator::Gt,
+            literal(5),
+        ));
+        let left_only = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("a1", 2)),
+            Operator::Lt,
+            literal(4),
+        ));
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(mixed, Operator::And, right_only)),
+            Operator::And,
+            left_only,
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        for (join_type, pushed, expected) in [
+            (
+                JoinType::Inner,
+                (Some("a1@0 < 4"), Some("c2@2 > 5")),
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b2 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "| 3  | 2  | 30 | 2  | 2  | 15 |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+            (
+                // the left side rows failing the filter are still produced
+                JoinType::Left,
+                (None, Some("c2@2 > 5")),
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b2 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "| 1  | 1  | 10 |    |    |    |",
+                    "| 2  | 1  | 20 |    |    |    |",
+                    "| 3  | 2  | 30 | 2  | 2  | 15 |",
+                    "| 4  | 2  | 40 |    |    |    |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+            (
+                // the right side rows failing the filter are still produced
+                JoinType::Right,
+                (Some("a1@0 < 4"), None),
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b2 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "|    |    |    | 1  | 1  | 5  |",
+                    "|    |    |    | 3  | 3  | 25 |",
+                    "| 3  | 2  | 30 | 2  | 2  | 15 |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+        ] {
+            let split_filter = filter.split_side_predicates(&join_type)?;
+            let display = |predicate: Option<Arc<dyn PhysicalExpr>>| {
+                predicate.map(|predicate| predicate.to_string())
+            };
+            assert_eq!(
+                (display(split_filter.left), display(split_filter.right)),
+                (pushed.0.map(str::to_string), pushed.1.map(str::to_string)),
+                "{join_type}"
+            );
+            // the mixed conjunct stays in the filter
+            assert!(split_filter.filter.is_some(), "{join_type}");
 
+            let join = join_with_filter(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                filter.clone(),
+                &join_type,
+                false,
+            )?;
+            let session_ctx = SessionContext::new();
+            let stream = join.execute(0, session_ctx.task_ctx())?;
+            let batches = common::collect(stream).await?;
+            assert_batches_sorted_eq!(expected, &batches);
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_date32() -> Result<()> {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("date", DataType::Date32, false),
-            Field::new("n", DataType::Int32, false),
-        ]));
-
-        let dates: ArrayRef = Arc::new(Date32Array::from(vec![19107, 19108, 19109]));
-        let n: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
-        let batch = RecordBatch::try_new(schema.clone(), vec![dates, n])?;
-        let left =
-            Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None).unwrap());
-
-        let dates: ArrayRef = Arc::new(Date32Array::from(vec![19108, 19108, 19109]));
-        let n: ArrayRef = Arc::new(Int32Array::from(vec![4, 5, 6]));
-        let batch = RecordBatch::try_new(schema.clone(), vec![dates, n])?;
-        let right = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap());
-
+    async fn join_build_side_presized_from_statistics() -> Result<()> {
+        let partitions = vec![(0..4)
+            .map(|batch| {
+                let keys = (batch * 1000..(batch + 1) * 1000).collect::<Vec<_>>();
+                build_table_i32(("a1", &keys), ("b1", &keys), ("c1", &keys))
+            })
+            .collect::<Vec<_>>()];
+        let schema = partitions[0][0].schema();
+        let left: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+        assert_eq!(left.statistics().num_rows, Some(4000));
+        let right = build_table(
+            ("a2", &vec![0, 1500, 3999]),
+            ("b2", &vec![1, 2, 3]),
+            ("c2", &vec![4, 5, 6]),
+        );
         let on = vec![(
-            Column::new_with_schema("date", &left.schema()).unwrap(),
-            Column::new_with_schema("date", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("a1", &schema)?) as _,
+            Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
         )];
 
         let join = join(left, right, on, &JoinType::Inner, false)?;
-
         let session_ctx = SessionContext::new();
-        let task_ctx = session_ctx.task_ctx();
-        let stream = join.execute(0, task_ctx)?;
+        let stream = join.execute(0, session_ctx.task_ctx())?;
         let batches = common::collect(stream).await?;
-
-        let expected = vec![
-            "+------------+---+------------+---+",
-            "| date       | n | date       | n |",
-            "+------------+---+------------+---+",
-            "| 2022-04-26 | 2 | 2022-04-26 | 4 |",
-            "| 2022-04-26 | 2 | 2022-04-26 | 5 |",
-            "| 2022-04-27 | 3 | 2022-04-27 | 6 |",
-            "+------------+---+------------+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
-
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+        // the hash table was allocated for all the rows up front
+        let metrics = join.metrics().unwrap();
+        let rehashes = metrics
+            .sum_by_name("build_hash_table_rehashes")
+            .map(|m| m.as_usize());
+        assert_eq!(rehashes, Some(0));
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_with_error_right() {
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
+    async fn join_parallel_build() -> Result<()> {
+        // the build side has 5 partitions of 2 batches each, with some keys
+        // in several partitions
+        let build_batch = |partition: i32, batch: i32| {
+            let keys = (0..4)
+                .map(|i| (partition * 3 + batch * 2 + i) % 12)
+                .collect::<Vec<_>>();
+            let values = (0..4)
+                .map(|i| partition * 100 + batch * 10 + i)
+                .collect::<Vec<_>>();
+            build_table_i32(("a1", &keys), ("b1", &values), ("c1", &vec![0; 4]))
+        };
+        let partitions = (0..5)
+            .map(|partition| (0..2).map(|batch| build_batch(partition, batch)).collect())
+            .collect::<Vec<Vec<_>>>();
+        let schema = partitions[0][0].schema();
+        let left: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+        let right = build_table(
+            ("a2", &vec![0, 2, 3, 5, 7, 11, 13]),
+            ("b2", &vec![1, 2, 3, 4, 5, 6, 7]),
+            ("c2", &vec![0; 7]),
         );
-
-        // right input stream returns one good batch and then one error.
-        // The error should be returned.
-        let err = Err(DataFusionError::Execution("bad data error".to_string()));
-        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
-
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b1", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("a1", &schema)?) as _,
+            Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
         )];
-        let schema = right.schema();
-        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
-        let right_input = Arc::new(MockExec::new(vec![Ok(right), err], schema));
 
-        let join_types = vec![
+        for join_type in [
             JoinType::Inner,
             JoinType::Left,
             JoinType::Right,
@@ -2966,52 +11496,93 @@ mod tests {
             JoinType::LeftAnti,
             JoinType::RightSemi,
             JoinType::RightAnti,
-        ];
-
-        for join_type in join_types {
-            let join = join(
-                left.clone(),
-                right_input.clone(),
-                on.clone(),
-                &join_type,
-                false,
-            )
-            .unwrap();
-            let session_ctx = SessionContext::new();
-            let task_ctx = session_ctx.task_ctx();
+        ] {
+            let mut results = vec![];
+            // the build side is hashed by 1, 2 and 5 tasks
+            for target_partitions in [1, 2, 8] {
+                let config =
+                    SessionConfig::new().with_target_partitions(target_partitions);
+                let session_ctx = SessionContext::with_config(config);
+                let (_, batches) = join_collect(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    &join_type,
+                    false,
+                    session_ctx.task_ctx(),
+                )
+                .await?;
+                let formatted = pretty_format_batches(&batches)?.to_string();
+                let mut lines = formatted
+                    .trim()
+                    .lines()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                lines.sort_unstable();
+                results.push(lines);
+            }
+            assert_eq!(results[0], results[1], "{join_type}");
+            assert_eq!(results[0], results[2], "{join_type}");
+        }
 
-            let stream = join.execute(0, task_ctx).unwrap();
+        Ok(())
+    }
 
-            // Expect that an error is returned
-            let result_string = crate::physical_plan::common::collect(stream)
-                .await
-                .unwrap_err()
-                .to_string();
-            assert!(
-                result_string.contains("bad data error"),
-                "actual: {result_string}"
-            );
+    #[test]
+    fn test_radix_probe_order() {
+        let buckets = 1 << 12;
+        let hash_values = (0..10_000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect::<Vec<_>>();
+        let order = radix_probe_order(&hash_values, buckets);
+
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..hash_values.len()).collect::<Vec<_>>());
+
+        // rows are ordered by the high bits of their bucket, then by row
+        let partition = |row: usize| (hash_values[row] & (buckets as u64 - 1)) >> 4;
+        for pair in order.windows(2) {
+            let (a, b) = (partition(pair[0]), partition(pair[1]));
+            assert!(a < b || (a == b && pair[0] < pair[1]));
         }
+
+        // a table with less buckets than partitions
+        assert_eq!(radix_probe_order(&[3, 0, 1, 2], 2), vec![1, 3, 0, 2]);
+        assert_eq!(radix_probe_order(&[3, 0, 1, 2], 1), vec![0, 1, 2, 3]);
     }
 
     #[tokio::test]
-    async fn single_partition_join_overallocation() -> Result<()> {
+    async fn join_radix_probe() -> Result<()> {
         let left = build_table(
-            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
-            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
-            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
+            ("a1", &(0..1000).collect()),
+            ("b1", &(0..1000).map(|i| i % 300).collect()),
+            ("c1", &(0..1000).map(|i| i * 7 % 13).collect()),
         );
         let right = build_table(
-            ("a2", &vec![10, 11]),
-            ("b2", &vec![12, 13]),
-            ("c2", &vec![14, 15]),
+            ("a2", &(0..2000).collect()),
+            ("b2", &(0..2000).map(|i| (2000 - i) % 500).collect()),
+            ("c2", &(0..2000).map(|i| i % 11).collect()),
         );
         let on = vec![(
-            Column::new_with_schema("a1", &left.schema()).unwrap(),
-            Column::new_with_schema("b2", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
+        // c1 < c2
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("c1", 0)),
+                Operator::Lt,
+                Arc::new(Column::new("c2", 1)),
+            )),
+            JoinFilter::build_column_indices(vec![2], vec![2]),
+            Schema::new(vec![
+                Field::new("c1", DataType::Int32, true),
+                Field::new("c2", DataType::Int32, true),
+            ]),
+        );
 
-        let join_types = vec![
+        for join_type in [
             JoinType::Inner,
             JoinType::Left,
             JoinType::Right,
@@ -3020,68 +11591,57 @@ mod tests {
             JoinType::LeftAnti,
             JoinType::RightSemi,
             JoinType::RightAnti,
-        ];
-
-        for join_type in join_types {
-            let runtime_config = RuntimeConfig::new().with_memory_limit(100, 1.0);
-            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
-            let session_ctx =
-                SessionContext::with_config_rt(SessionConfig::default(), runtime);
-            let task_ctx = session_ctx.task_ctx();
-
-            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
-
-            let stream = join.execute(0, task_ctx)?;
-            let err = common::collect(stream).await.unwrap_err();
-
-            assert_contains!(
-                err.to_string(),
-                "External error: Resources exhausted: Failed to allocate additional"
-            );
-
-            // Asserting that operator-level reservation attempting to overallocate
-            assert_contains!(err.to_string(), "HashJoinInput");
+        ] {
+            for filter in [None, Some(filter.clone())] {
+                let mut results = vec![];
+                for threshold in [0, usize::MAX] {
+                    let config = SessionConfig::new().set_usize(
+                        "datafusion.execution.hash_join_radix_probe_threshold",
+                        threshold,
+                    );
+                    let session_ctx = SessionContext::with_config(config);
+                    let join = HashJoinExec::try_new(
+                        left.clone(),
+                        right.clone(),
+                        on.clone(),
+                        filter.clone(),
+                        &join_type,
+                        PartitionMode::CollectLeft,
+                        false,
+                    )?;
+                    let stream = join.execute(0, session_ctx.task_ctx())?;
+                    let batches = common::collect(stream).await?;
+                    results.push(pretty_format_batches(&batches)?.to_string());
+                }
+                // the radix probe keeps the output in probe row order
+                assert_eq!(results[0], results[1], "{join_type} {filter:?}");
+            }
         }
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn partitioned_join_overallocation() -> Result<()> {
-        // Prepare partitioned inputs for HashJoinExec
-        // No need to adjust partitioning, as execution should fail with `Resources exhausted` error
-        let left_batch = build_table_i32(
-            ("a1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
-            ("b1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
-            ("c1", &vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]),
-        );
-        let left = Arc::new(
-            MemoryExec::try_new(
-                &[vec![left_batch.clone()], vec![left_batch.clone()]],
-                left_batch.schema(),
-                None,
-            )
-            .unwrap(),
-        );
-        let right_batch = build_table_i32(
-            ("a2", &vec![10, 11]),
-            ("b2", &vec![12, 13]),
-            ("c2", &vec![14, 15]),
+    async fn join_bloom_filter_skips_probe_rows() -> Result<()> {
+        let build_keys = (0..1000).map(|i| i * 100).collect::<Vec<_>>();
+        let left = build_table(
+            ("a1", &build_keys),
+            ("b1", &build_keys),
+            ("c1", &build_keys),
         );
-        let right = Arc::new(
-            MemoryExec::try_new(
-                &[vec![right_batch.clone()], vec![right_batch.clone()]],
-                right_batch.schema(),
-                None,
-            )
-            .unwrap(),
+        // only every 100th probe row has a match
+        let probe_keys = (0..10_000).collect::<Vec<_>>();
+        let right = build_table(
+            ("a2", &probe_keys),
+            ("b2", &probe_keys),
+            ("c2", &probe_keys),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left_batch.schema())?,
-            Column::new_with_schema("b2", &right_batch.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let join_types = vec![
+        for join_type in [
             JoinType::Inner,
             JoinType::Left,
             JoinType::Right,
@@ -3090,35 +11650,35 @@ mod tests {
             JoinType::LeftAnti,
             JoinType::RightSemi,
             JoinType::RightAnti,
-        ];
-
-        for join_type in join_types {
-            let runtime_config = RuntimeConfig::new().with_memory_limit(100, 1.0);
-            let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
-            let session_config = SessionConfig::default().with_batch_size(50);
-            let session_ctx = SessionContext::with_config_rt(session_config, runtime);
-            let task_ctx = session_ctx.task_ctx();
-
-            let join = HashJoinExec::try_new(
-                left.clone(),
-                right.clone(),
-                on.clone(),
-                None,
-                &join_type,
-                PartitionMode::Partitioned,
-                false,
-            )?;
-
-            let stream = join.execute(1, task_ctx)?;
-            let err = common::collect(stream).await.unwrap_err();
-
-            assert_contains!(
-                err.to_string(),
-                "External error: Resources exhausted: Failed to allocate additional"
-            );
-
-            // Asserting that stream-level reservation attempting to overallocate
-            assert_contains!(err.to_string(), "HashJoinInput[1]");
+        ] {
+            let mut results = vec![];
+            for max_size in [0, 1 << 20] {
+                let config = SessionConfig::new().set_usize(
+                    "datafusion.execution.hash_join_bloom_filter_max_size",
+                    max_size,
+                );
+                let session_ctx = SessionContext::with_config(config);
+                let join =
+                    join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+                let stream = join.execute(0, session_ctx.task_ctx())?;
+                let batches = common::collect(stream).await?;
+                results.push(pretty_format_batches(&batches)?.to_string());
+
+                let skipped = join
+                    .metrics()
+                    .unwrap()
+                    .sum_by_name("probe_rows_skipped")
+                    .map(|m| m.as_usize())
+                    .unwrap();
+                if max_size == 0 {
+                    assert_eq!(skipped, 0, "{join_type}");
+                } else {
+                    // most of the 9900 probe rows without a match are skipped
+                    assert!(skipped > 9000, "{join_type} skipped {skipped} rows");
+                }
+            }
+            // skipped probe rows are still output as unmatched
+            assert_eq!(results[0], results[1], "{join_type}");
         }
 
         Ok(())
@@ -19,37 +19,142 @@
 //! related functionality, used both in join calculations and optimization rules.
 
 use std::collections::HashMap;
+use std::ops::ControlFlow;
 use std::sync::Arc;
 use std::{fmt, usize};
 
-use arrow::datatypes::SchemaRef;
+use arrow::array::{new_null_array, Array, ArrayRef, UInt32Array, UInt64Array};
+use arrow::compute;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
 
 use datafusion_common::tree_node::{Transformed, TreeNode};
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::intervals::Interval;
 use datafusion_physical_expr::utils::collect_columns;
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion_row::layout::{row_supported, RowLayout};
+use datafusion_row::reader::read_as_batch;
+use datafusion_row::writer::write_batch_unchecked;
 use hashbrown::raw::RawTable;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 
 use crate::physical_plan::joins::utils::{JoinFilter, JoinSide};
 use datafusion_common::Result;
 
-// Maps a `u64` hash value based on the build side ["on" values] to a list of indices with this key's value.
+// Maps a `u64` hash value based on the build side ["on" values] to the indices of the rows
+// with this key's value.
+//
+// The indices are stored as linked lists: `map` maps the hash value to the latest row with
+// it, and `next` maps every row to the previous row with the same hash value. Both store
+// the row index plus one, so that 0 marks the end of a list.
+// E.g. 1 -> [3, 6, 8] indicates that the column values map to rows 3, 6 and 8 for hash
+// value 1, which is stored as `map: {1 -> 9}`, `next[8] = 7`, `next[6] = 4` and `next[3] = 0`.
+//
+// Storing the lists in a single vector rather than a vector per hash value avoids an
+// allocation per duplicated key when building, and pointer chasing when probing.
 //
-// Note that the `u64` keys are not stored in the hashmap (hence the `()` as key), but are only used
-// to put the indices in a certain bucket.
-// By allocating a `HashMap` with capacity for *at least* the number of rows for entries at the build side,
-// we make sure that we don't have to re-hash the hashmap, which needs access to the key (the hash in this case) value.
-// E.g. 1 -> [3, 6, 8] indicates that the column values map to rows 3, 6 and 8 for hash value 1
 // As the key is a hash value, we need to check possible hash collisions in the probe stage
 // During this stage it might be the case that a row is contained the same hashmap value,
 // but the values don't match. Those are checked in the [equal_rows] macro
 // TODO: speed up collision check and move away from using a hashbrown HashMap
 // https://github.com/apache/arrow-datafusion/issues/50
-pub struct JoinHashMap(pub RawTable<(u64, SmallVec<[u64; 1]>)>);
+pub struct JoinHashMap {
+    /// Maps a hash value to the latest row with it, plus one
+    pub map: RawTable<(u64, u64)>,
+    /// Maps a row to the previous row with the same hash value, plus one, or
+    /// to 0 for the first row with it
+    pub next: Vec<u64>,
+}
 
 impl JoinHashMap {
+    /// Creates an empty hash map with room for `capacity` hash values
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: RawTable::with_capacity(capacity),
+            next: vec![],
+        }
+    }
+
+    /// Estimated memory used by a hash map created with [`Self::with_capacity`],
+    /// without allocating it
+    pub(crate) fn estimated_size(capacity: usize) -> usize {
+        if capacity == 0 {
+            return 0;
+        }
+        // the hash table keeps at least one in eight buckets empty, and has a
+        // power of two of buckets, each with a control byte
+        let buckets = (capacity.saturating_mul(8) / 7)
+            .checked_next_power_of_two()
+            .unwrap_or(usize::MAX)
+            .max(4);
+        buckets.saturating_mul(std::mem::size_of::<(u64, u64)>() + 1)
+    }
+
+    /// Memory used by the hash map: the buckets of the hash table, plus the
+    /// list of the previous rows with the same hash value
+    pub(crate) fn size(&self) -> usize {
+        self.map.allocation_info().1.size()
+            + self.next.capacity() * std::mem::size_of::<u64>()
+    }
+
+    /// Makes room for `additional` more rows. Returns `true` if the hash values
+    /// already in the map had to be rehashed into a larger table.
+    pub(crate) fn reserve(&mut self, additional: usize) -> bool {
+        let buckets = self.map.buckets();
+        self.map.reserve(additional, |(hash, _)| *hash);
+        self.next.reserve(additional);
+        !self.map.is_empty() && self.map.buckets() != buckets
+    }
+
+    /// Largest number of rows with the same hash value, found by following the
+    /// previous rows from the latest row of every hash value
+    pub(crate) fn longest_chain(&self) -> usize {
+        if self.map.is_empty() {
+            return 0;
+        }
+        // the latest row with a hash value is not the previous row of any row
+        let mut is_previous = vec![false; self.next.len()];
+        for next in &self.next {
+            if *next != 0 {
+                is_previous[*next as usize - 1] = true;
+            }
+        }
+        is_previous
+            .iter()
+            .enumerate()
+            .filter(|(_, is_previous)| !**is_previous)
+            .map(|(row, _)| {
+                let mut length = 1;
+                let mut next = self.next[row];
+                while next != 0 {
+                    length += 1;
+                    next = self.next[next as usize - 1];
+                }
+                length
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl fmt::Debug for JoinHashMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JoinHashMap")
+            .field("entries", &self.map.len())
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+// Maps a `u64` hash value based on the build side ["on" values] to a list of indices with
+// this key's value, like [`JoinHashMap`].
+//
+// Unlike [`JoinHashMap`], rows can be removed from it, which the symmetric hash join
+// does when pruning its build side.
+pub struct PruningJoinHashMap(pub RawTable<(u64, SmallVec<[u64; 1]>)>);
+
+impl PruningJoinHashMap {
     /// In this implementation, the scale_factor variable determines how conservative the shrinking strategy is.
     /// The value of scale_factor is set to 4, which means the capacity will be reduced by 25%
     /// when necessary. You can adjust the scale_factor value to achieve the desired
@@ -74,12 +179,276 @@ impl JoinHashMap {
     }
 }
 
-impl fmt::Debug for JoinHashMap {
+impl fmt::Debug for PruningJoinHashMap {
     fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
         Ok(())
     }
 }
 
+/// Hash map from the hash values of the join keys of the build side rows to
+/// the rows, which the build side rows are inserted into and the probe side
+/// rows are looked up in
+pub trait JoinHashMapType {
+    /// Inserts the build side row `row` whose join keys hash to `hash_value`
+    fn insert(&mut self, hash_value: u64, row: u64);
+
+    /// Calls `f` with every build side row inserted with `hash_value`, latest
+    /// first, i.e. in descending order of rows, until `f` breaks
+    fn for_each_row(
+        &self,
+        hash_value: u64,
+        f: impl FnMut(u64) -> Result<ControlFlow<()>>,
+    ) -> Result<()>;
+
+    /// Number of buckets of the underlying hash table
+    fn buckets(&self) -> usize;
+}
+
+impl JoinHashMapType for JoinHashMap {
+    fn insert(&mut self, hash_value: u64, row: u64) {
+        let index = row as usize;
+        if self.next.len() <= index {
+            self.next.resize(index + 1, 0);
+        }
+        match self
+            .map
+            .get_mut(hash_value, |(hash, _)| hash_value == *hash)
+        {
+            Some((_, head)) => {
+                self.next[index] = *head;
+                *head = row + 1;
+            }
+            None => {
+                self.map
+                    .insert(hash_value, (hash_value, row + 1), |(hash, _)| *hash);
+            }
+        }
+    }
+
+    fn for_each_row(
+        &self,
+        hash_value: u64,
+        mut f: impl FnMut(u64) -> Result<ControlFlow<()>>,
+    ) -> Result<()> {
+        if let Some((_, head)) = self.map.get(hash_value, |(hash, _)| hash_value == *hash)
+        {
+            let mut next = *head;
+            while next != 0 {
+                if f(next - 1)?.is_break() {
+                    break;
+                }
+                next = self.next[next as usize - 1];
+            }
+        }
+        Ok(())
+    }
+
+    fn buckets(&self) -> usize {
+        self.map.buckets()
+    }
+}
+
+impl JoinHashMapType for PruningJoinHashMap {
+    fn insert(&mut self, hash_value: u64, row: u64) {
+        match self.0.get_mut(hash_value, |(hash, _)| hash_value == *hash) {
+            Some((_, indices)) => indices.push(row),
+            None => {
+                self.0
+                    .insert(hash_value, (hash_value, smallvec![row]), |(hash, _)| *hash);
+            }
+        }
+    }
+
+    fn for_each_row(
+        &self,
+        hash_value: u64,
+        mut f: impl FnMut(u64) -> Result<ControlFlow<()>>,
+    ) -> Result<()> {
+        if let Some((_, indices)) =
+            self.0.get(hash_value, |(hash, _)| hash_value == *hash)
+        {
+            for &row in indices.iter().rev() {
+                if f(row)?.is_break() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn buckets(&self) -> usize {
+        self.0.buckets()
+    }
+}
+
+/// Number of bits set in a [`BloomFilter`] for every hash value
+const BLOOM_FILTER_HASH_BITS: u32 = 4;
+
+/// A blocked Bloom filter over the hash values of the join keys of the build
+/// side, with which the probe rows whose hash value is not in the hash table are
+/// mostly skipped without looking them up.
+///
+/// Every hash value sets `BLOOM_FILTER_HASH_BITS` bits of a single 64-bit word,
+/// so that checking a hash value reads a single word.
+#[derive(Debug)]
+pub struct BloomFilter {
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter for `num_hashes` hash values, of about a byte per
+    /// hash value and at most `max_size` bytes. Returns `None` if `max_size` is
+    /// less than a bit per hash value, with which hardly any row is skipped.
+    pub(crate) fn try_new(num_hashes: usize, max_size: usize) -> Option<Self> {
+        let max_words = max_size / 8;
+        if max_words == 0 {
+            return None;
+        }
+        // the number of words is a power of two, for `word` to mask the hash
+        let max_words = 1 << (usize::BITS - 1 - max_words.leading_zeros());
+        let num_words = ((num_hashes + 7) / 8).next_power_of_two().min(max_words);
+        if num_words * 64 < num_hashes {
+            return None;
+        }
+        Some(Self {
+            words: vec![0; num_words],
+        })
+    }
+
+    /// Returns the index of the word of `hash_value` and the bits it sets
+    fn word_and_mask(&self, hash_value: u64) -> (usize, u64) {
+        let word = (hash_value >> 32) as usize & (self.words.len() - 1);
+        let mask = (0..BLOOM_FILTER_HASH_BITS)
+            .map(|i| 1u64 << ((hash_value >> (6 * i)) & 63))
+            .fold(0, |mask, bit| mask | bit);
+        (word, mask)
+    }
+
+    /// Adds `hash_value` to the filter
+    pub(crate) fn insert(&mut self, hash_value: u64) {
+        let (word, mask) = self.word_and_mask(hash_value);
+        self.words[word] |= mask;
+    }
+
+    /// Returns false if `hash_value` was not added to the filter, and true if it
+    /// may have been
+    pub(crate) fn may_contain(&self, hash_value: u64) -> bool {
+        let (word, mask) = self.word_and_mask(hash_value);
+        self.words[word] & mask == mask
+    }
+
+    /// Memory used by the filter
+    pub(crate) fn size(&self) -> usize {
+        self.words.len() * std::mem::size_of::<u64>()
+    }
+}
+
+/// Minimum number of columns of the build side for which the row-format payload
+/// is built, below that gathering the columns individually is cheaper.
+const ROW_PAYLOAD_MIN_COLUMNS: usize = 16;
+
+/// Row-format copy of the build side of a hash join.
+///
+/// All build side rows are stored back to back in a single buffer using the
+/// word aligned layout of [`datafusion_row`], so materializing the matched build
+/// rows of an output batch reads one contiguous row per match instead of
+/// gathering every column with scattered indices.
+///
+/// The word aligned layout has no variable-length part, so build sides with
+/// variable-length columns (e.g. strings) are not encoded and keep using the
+/// columnar path only; supporting them needs an offsets section per row.
+pub(crate) struct BuildSideRows {
+    /// Schema of the build side
+    schema: SchemaRef,
+    /// Encoded rows, row `i` starts at `i * row_width`
+    data: Vec<u8>,
+    /// Width of a single encoded row
+    row_width: usize,
+}
+
+impl BuildSideRows {
+    /// Returns true if the build side `schema` is wide enough for the row format
+    /// to pay off and all its fields are fixed-width types supported by it
+    pub(crate) fn is_beneficial(schema: &Schema) -> bool {
+        schema.fields().len() >= ROW_PAYLOAD_MIN_COLUMNS && row_supported(schema)
+    }
+
+    /// Encodes all `num_rows` rows of `batches` one after the other, `schema`
+    /// being their schema, which must satisfy [`Self::is_beneficial`]
+    pub(crate) fn new(
+        schema: SchemaRef,
+        batches: &[RecordBatch],
+        num_rows: usize,
+    ) -> Self {
+        let row_width = RowLayout::new(&schema).fixed_part_width();
+        let mut data = vec![0; row_width * num_rows];
+        let mut offset = 0;
+        for batch in batches {
+            write_batch_unchecked(&mut data, offset, batch, 0, schema.clone());
+            offset += row_width * batch.num_rows();
+        }
+        Self {
+            schema,
+            data,
+            row_width,
+        }
+    }
+
+    /// Memory used by the encoded rows
+    pub(crate) fn size(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Returns the columns of the build side rows at `indices`, a null index
+    /// producing a row of nulls (as for unmatched rows of outer joins)
+    pub(crate) fn take(&self, indices: &UInt64Array) -> Result<Vec<ArrayRef>> {
+        if indices.null_count() == indices.len() {
+            return Ok(self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| new_null_array(field.data_type(), indices.len()))
+                .collect());
+        }
+
+        let offsets = indices
+            .iter()
+            .flatten()
+            .map(|index| index as usize * self.row_width)
+            .collect::<Vec<_>>();
+        let batch = read_as_batch(&self.data, self.schema.clone(), &offsets)?;
+        if indices.null_count() == 0 {
+            return Ok(batch.columns().to_vec());
+        }
+
+        // Spread the densely read rows back to the positions of the valid indices
+        let mut next = 0;
+        let positions = indices
+            .iter()
+            .map(|index| {
+                index.map(|_| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect::<UInt32Array>();
+        batch
+            .columns()
+            .iter()
+            .map(|column| Ok(compute::take(column.as_ref(), &positions, None)?))
+            .collect()
+    }
+}
+
+impl fmt::Debug for BuildSideRows {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BuildSideRows")
+            .field("num_rows", &(self.data.len() / self.row_width.max(1)))
+            .field("row_width", &self.row_width)
+            .finish()
+    }
+}
+
 fn check_filter_expr_contains_sort_information(
     expr: &Arc<dyn PhysicalExpr>,
     reference: &Arc<dyn PhysicalExpr>,
@@ -625,10 +994,108 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_join_hash_map_lists() -> Result<()> {
+        let mut join_hash_map = JoinHashMap::with_capacity(2);
+        for (row, hash_value) in [1, 2, 1, 1, 3, 2].into_iter().enumerate() {
+            join_hash_map.insert(hash_value, row as u64);
+        }
+        assert_eq!(join_hash_map.map.len(), 3);
+        assert_eq!(join_hash_map.next, vec![0, 0, 1, 3, 0, 2]);
+
+        let rows = |hash_value| -> Result<Vec<u64>> {
+            let mut rows = vec![];
+            join_hash_map.for_each_row(hash_value, |row| {
+                rows.push(row);
+                Ok(ControlFlow::Continue(()))
+            })?;
+            Ok(rows)
+        };
+        // the latest row of a hash value comes first
+        assert_eq!(rows(1)?, vec![3, 2, 0]);
+        assert_eq!(rows(2)?, vec![5, 1]);
+        assert_eq!(rows(3)?, vec![4]);
+        assert_eq!(rows(4)?, Vec::<u64>::new());
+
+        // the rows after the one `f` breaks at are not visited
+        let mut rows = vec![];
+        join_hash_map.for_each_row(1, |row| {
+            rows.push(row);
+            Ok(if row == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            })
+        })?;
+        assert_eq!(rows, vec![3, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_hash_map_size() {
+        let num_rows = 10_000;
+        let mut join_hash_map = JoinHashMap::with_capacity(0);
+        join_hash_map.reserve(num_rows);
+        for row in 0..num_rows as u64 {
+            join_hash_map.insert(row, row);
+        }
+        assert_eq!(join_hash_map.map.len(), num_rows);
+
+        // at least an entry per hash value and a list element per row, at most
+        // twice that with the free buckets of the table
+        let size = join_hash_map.size();
+        let min_size = num_rows * std::mem::size_of::<(u64, u64)>()
+            + num_rows * std::mem::size_of::<u64>();
+        assert!(size >= min_size, "{size} < {min_size}");
+        assert!(size <= 2 * min_size, "{size} > 2 * {min_size}");
+
+        assert_eq!(
+            format!("{join_hash_map:?}"),
+            format!("JoinHashMap {{ entries: 10000, size: {size} }}")
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let random_state = ahash::RandomState::with_seeds(0, 0, 0, 0);
+        let mut bloom_filter = BloomFilter::try_new(1000, 1 << 20).unwrap();
+        // about a byte per hash value
+        assert_eq!(bloom_filter.size(), 1024);
+        for i in 0..1000 {
+            bloom_filter.insert(random_state.hash_one(i));
+        }
+        assert!((0..1000).all(|i| bloom_filter.may_contain(random_state.hash_one(i))));
+        let false_positives = (1000..11000)
+            .filter(|i| bloom_filter.may_contain(random_state.hash_one(i)))
+            .count();
+        assert!(false_positives < 1000, "{false_positives} false positives");
+
+        // the size is limited by the maximum size
+        let bloom_filter = BloomFilter::try_new(1000, 200).unwrap();
+        assert_eq!(bloom_filter.size(), 128);
+        // but a filter of less than a bit per hash value is not worth it
+        assert!(BloomFilter::try_new(1000, 100).is_none());
+        assert!(BloomFilter::try_new(1000, 0).is_none());
+    }
+
+    #[test]
+    fn test_join_hash_map_reserve() {
+        let mut join_hash_map = JoinHashMap::with_capacity(0);
+        // allocating the empty map is not a rehash
+        assert!(!join_hash_map.reserve(4));
+        for row in 0..4 {
+            join_hash_map.insert(row, row);
+        }
+        // there is room left for the rows already reserved
+        assert!(!join_hash_map.reserve(0));
+        assert!(join_hash_map.reserve(1024));
+        assert!(join_hash_map.next.capacity() >= 1028);
+    }
+
     #[test]
     fn test_shrink_if_necessary() {
         let scale_factor = 4;
-        let mut join_hash_map = JoinHashMap(RawTable::with_capacity(100));
+        let mut join_hash_map = PruningJoinHashMap(RawTable::with_capacity(100));
         let data_size = 2000;
         let deleted_part = 3 * data_size / 4;
         // Add elements to the JoinHashMap
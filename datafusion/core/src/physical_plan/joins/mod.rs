@@ -18,7 +18,7 @@
 //! DataFusion Join implementations
 
 pub use cross_join::CrossJoinExec;
-pub use hash_join::HashJoinExec;
+pub use hash_join::{BuildSideInfo, HashJoinExec};
 pub use nested_loop_join::NestedLoopJoinExec;
 // Note: SortMergeJoin is not used in plans yet
 pub use sort_merge_join::SortMergeJoinExec;
@@ -36,9 +36,12 @@ pub mod utils;
 pub enum PartitionMode {
     /// Left/right children are partitioned using the left and right keys
     Partitioned,
-    /// Left side will collected into one partition
+    /// Left side will collected into one partition. The right side is collected
+    /// by swapping the inputs of the join, e.g. a `Right` join into a `Left` join
     CollectLeft,
     /// When set to Auto, DataFusion optimizer will decide which PartitionMode mode(Partitioned/CollectLeft) is optimal based on statistics.
-    /// It will also consider swapping the left and right inputs for the Join
+    /// It will also consider swapping the left and right inputs for the Join.
+    /// A join which is not decided by the optimizer runs in the mode it resolves
+    /// to, see `HashJoinExec::with_single_partition_threshold`
     Auto,
 }
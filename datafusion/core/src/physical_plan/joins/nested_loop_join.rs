@@ -25,7 +25,7 @@ use crate::physical_plan::joins::utils::{
     combine_join_equivalence_properties, estimate_join_statistics, get_anti_indices,
     get_anti_u64_indices, get_final_indices_from_bit_map, get_semi_indices,
     get_semi_u64_indices, BuildProbeJoinMetrics, ColumnIndex, JoinFilter, JoinSide,
-    OnceAsync, OnceFut,
+    OnceAsync, OnceFut, DEFAULT_JOIN_FILTER_SELECTIVITY,
 };
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
@@ -251,7 +251,7 @@ impl ExecutionPlan for NestedLoopJoinExec {
 
     fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 let display_filter = self.filter.as_ref().map_or_else(
                     || "".to_string(),
                     |f| format!(", filter={:?}", f.expression()),
@@ -275,6 +275,8 @@ impl ExecutionPlan for NestedLoopJoinExec {
             self.right.clone(),
             vec![],
             &self.join_type,
+            self.filter.as_ref(),
+            DEFAULT_JOIN_FILTER_SELECTIVITY,
         )
     }
 }
@@ -389,7 +391,7 @@ fn build_join_indices(
     // in the nested loop join, the filter can contain non-equal and equal condition.
     if let Some(filter) = filter {
         apply_join_filter_to_indices(
-            left_batch,
+            std::slice::from_ref(left_batch),
             right_batch,
             left_indices,
             right_indices,
@@ -480,7 +482,7 @@ impl NestedLoopJoinStream {
                         // use the left and right indices to produce the batch result
                         let result = build_batch_from_indices(
                             &self.schema,
-                            left_data,
+                            std::slice::from_ref(left_data),
                             &empty_right_batch,
                             left_side,
                             right_side,
@@ -609,7 +611,7 @@ fn join_left_and_right_batch(
 
             build_batch_from_indices(
                 schema,
-                left_batch,
+                std::slice::from_ref(left_batch),
                 right_batch,
                 left_side,
                 right_side,
@@ -42,7 +42,8 @@ use crate::physical_plan::expressions::Column;
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::joins::utils::{
     build_join_schema, check_join_is_valid, combine_join_equivalence_properties,
-    estimate_join_statistics, partitioned_join_output_partitioning, JoinOn,
+    display_join_on, estimate_join_statistics, partitioned_join_output_partitioning,
+    JoinOn, JoinOnRef, DEFAULT_JOIN_FILTER_SELECTIVITY,
 };
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use crate::physical_plan::{
@@ -65,7 +66,7 @@ pub struct SortMergeJoinExec {
     pub(crate) left: Arc<dyn ExecutionPlan>,
     /// Right sorting joining execution plan
     pub(crate) right: Arc<dyn ExecutionPlan>,
-    /// Set of common columns used to join on
+    /// Pairs of left and right join key expressions used to join on
     pub(crate) on: JoinOn,
     /// How the join is performed
     pub(crate) join_type: JoinType,
@@ -121,11 +122,11 @@ impl SortMergeJoinExec {
             .zip(sort_options.iter())
             .map(|((l, r), sort_op)| {
                 let left = PhysicalSortExpr {
-                    expr: Arc::new(l.clone()) as Arc<dyn PhysicalExpr>,
+                    expr: l.clone(),
                     options: *sort_op,
                 };
                 let right = PhysicalSortExpr {
-                    expr: Arc::new(r.clone()) as Arc<dyn PhysicalExpr>,
+                    expr: r.clone(),
                     options: *sort_op,
                 };
                 (left, right)
@@ -194,8 +195,8 @@ impl SortMergeJoinExec {
         })
     }
 
-    /// Set of common columns used to join on
-    pub fn on(&self) -> &[(Column, Column)] {
+    /// Pairs of left and right join key expressions used to join on
+    pub fn on(&self) -> JoinOnRef {
         &self.on
     }
 }
@@ -210,16 +211,8 @@ impl ExecutionPlan for SortMergeJoinExec {
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
-        let (left_expr, right_expr) = self
-            .on
-            .iter()
-            .map(|(l, r)| {
-                (
-                    Arc::new(l.clone()) as Arc<dyn PhysicalExpr>,
-                    Arc::new(r.clone()) as Arc<dyn PhysicalExpr>,
-                )
-            })
-            .unzip();
+        let (left_expr, right_expr) =
+            self.on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
         vec![
             Distribution::HashPartitioned(left_expr),
             Distribution::HashPartitioned(right_expr),
@@ -363,11 +356,12 @@ impl ExecutionPlan for SortMergeJoinExec {
 
     fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
-                    "SortMergeJoin: join_type={:?}, on={:?}",
-                    self.join_type, self.on
+                    "SortMergeJoin: join_type={:?}, on={}",
+                    self.join_type,
+                    display_join_on(&self.on)
                 )
             }
         }
@@ -382,6 +376,8 @@ impl ExecutionPlan for SortMergeJoinExec {
             self.right.clone(),
             self.on.clone(),
             &self.join_type,
+            None,
+            DEFAULT_JOIN_FILTER_SELECTIVITY,
         )
     }
 }
@@ -489,15 +485,15 @@ struct StreamedBatch {
 }
 
 impl StreamedBatch {
-    fn new(batch: RecordBatch, on_column: &[Column]) -> Self {
-        let join_arrays = join_arrays(&batch, on_column);
-        StreamedBatch {
+    fn try_new(batch: RecordBatch, on_column: &[Arc<dyn PhysicalExpr>]) -> Result<Self> {
+        let join_arrays = join_arrays(&batch, on_column)?;
+        Ok(StreamedBatch {
             batch,
             idx: 0,
             join_arrays,
             output_indices: vec![],
             buffered_batch_idx: None,
-        }
+        })
     }
 
     fn new_empty(schema: SchemaRef) -> Self {
@@ -553,8 +549,12 @@ struct BufferedBatch {
 }
 
 impl BufferedBatch {
-    fn new(batch: RecordBatch, range: Range<usize>, on_column: &[Column]) -> Self {
-        let join_arrays = join_arrays(&batch, on_column);
+    fn try_new(
+        batch: RecordBatch,
+        range: Range<usize>,
+        on_column: &[Arc<dyn PhysicalExpr>],
+    ) -> Result<Self> {
+        let join_arrays = join_arrays(&batch, on_column)?;
 
         // Estimation is calculated as
         //   inner batch size
@@ -571,13 +571,13 @@ impl BufferedBatch {
             + mem::size_of::<Range<usize>>()
             + mem::size_of::<usize>();
 
-        BufferedBatch {
+        Ok(BufferedBatch {
             batch,
             range,
             join_arrays,
             null_joined: vec![],
             size_estimation,
-        }
+        })
     }
 }
 
@@ -615,9 +615,9 @@ struct SMJStream {
     /// The comparison result of current streamed row and buffered batches
     pub current_ordering: Ordering,
     /// Join key columns of streamed
-    pub on_streamed: Vec<Column>,
+    pub on_streamed: Vec<Arc<dyn PhysicalExpr>>,
     /// Join key columns of buffered
-    pub on_buffered: Vec<Column>,
+    pub on_buffered: Vec<Arc<dyn PhysicalExpr>>,
     /// Staging output array builders
     pub output_record_batches: Vec<RecordBatch>,
     /// Staging output size, including output batches and staging joined results
@@ -742,8 +742,8 @@ impl SMJStream {
         null_equals_null: bool,
         streamed: SendableRecordBatchStream,
         buffered: SendableRecordBatchStream,
-        on_streamed: Vec<Column>,
-        on_buffered: Vec<Column>,
+        on_streamed: Vec<Arc<dyn PhysicalExpr>>,
+        on_buffered: Vec<Arc<dyn PhysicalExpr>>,
         join_type: JoinType,
         batch_size: usize,
         join_metrics: SortMergeJoinMetrics,
@@ -805,7 +805,7 @@ impl SMJStream {
                             self.join_metrics.input_batches.add(1);
                             self.join_metrics.input_rows.add(batch.num_rows());
                             self.streamed_batch =
-                                StreamedBatch::new(batch, &self.on_streamed);
+                                StreamedBatch::try_new(batch, &self.on_streamed)?;
                             self.streamed_state = StreamedState::Ready;
                         }
                     }
@@ -861,7 +861,7 @@ impl SMJStream {
                         self.join_metrics.input_rows.add(batch.num_rows());
                         if batch.num_rows() > 0 {
                             let buffered_batch =
-                                BufferedBatch::new(batch, 0..1, &self.on_buffered);
+                                BufferedBatch::try_new(batch, 0..1, &self.on_buffered)?;
                             self.reservation.try_grow(buffered_batch.size_estimation)?;
                             self.join_metrics
                                 .peak_mem_used
@@ -903,11 +903,11 @@ impl SMJStream {
                                 self.join_metrics.input_batches.add(1);
                                 self.join_metrics.input_rows.add(batch.num_rows());
                                 if batch.num_rows() > 0 {
-                                    let buffered_batch = BufferedBatch::new(
+                                    let buffered_batch = BufferedBatch::try_new(
                                         batch,
                                         0..0,
                                         &self.on_buffered,
-                                    );
+                                    )?;
                                     self.reservation
                                         .try_grow(buffered_batch.size_estimation)?;
                                     self.join_metrics
@@ -1223,11 +1223,14 @@ impl BufferedData {
     }
 }
 
-/// Get join array refs of given batch and join columns
-fn join_arrays(batch: &RecordBatch, on_column: &[Column]) -> Vec<ArrayRef> {
+/// Evaluate the join keys of given batch
+fn join_arrays(
+    batch: &RecordBatch,
+    on_column: &[Arc<dyn PhysicalExpr>],
+) -> Result<Vec<ArrayRef>> {
     on_column
         .iter()
-        .map(|c| batch.column(c.index()).clone())
+        .map(|c| Ok(c.evaluate(batch)?.into_array(batch.num_rows())))
         .collect()
 }
 
@@ -1589,8 +1592,8 @@ mod tests {
         );
 
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Inner).await?;
@@ -1623,12 +1626,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1661,12 +1664,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1700,12 +1703,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1738,12 +1741,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
         let (_, batches) = join_collect_with_options(
@@ -1790,12 +1793,12 @@ mod tests {
         );
         let on = vec![
             (
-                Column::new_with_schema("a1", &left.schema())?,
-                Column::new_with_schema("a1", &right.schema())?,
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
             ),
             (
-                Column::new_with_schema("b2", &left.schema())?,
-                Column::new_with_schema("b2", &right.schema())?,
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
             ),
         ];
 
@@ -1831,8 +1834,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Left).await?;
@@ -1863,8 +1866,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Right).await?;
@@ -1895,8 +1898,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema()).unwrap(),
-            Column::new_with_schema("b2", &right.schema()).unwrap(),
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Full).await?;
@@ -1927,8 +1930,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::LeftAnti).await?;
@@ -1958,8 +1961,8 @@ mod tests {
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::LeftSemi).await?;
@@ -1991,8 +1994,8 @@ mod tests {
         );
         let on = vec![(
             // join on a=b so there are duplicate column names on unjoined columns
-            Column::new_with_schema("a", &left.schema())?,
-            Column::new_with_schema("b", &right.schema())?,
+            Arc::new(Column::new_with_schema("a", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Inner).await?;
@@ -2023,8 +2026,8 @@ mod tests {
         );
 
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Inner).await?;
@@ -2057,8 +2060,8 @@ mod tests {
         );
 
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b1", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Inner).await?;
@@ -2090,8 +2093,8 @@ mod tests {
             ("c2", &vec![50, 60, 70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Left).await?;
@@ -2126,8 +2129,8 @@ mod tests {
             ("c2", &vec![60, 70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Right).await?;
@@ -2170,8 +2173,8 @@ mod tests {
         let left = build_table_from_batches(vec![left_batch_1, left_batch_2]);
         let right = build_table_from_batches(vec![right_batch_1, right_batch_2]);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Left).await?;
@@ -2219,8 +2222,8 @@ mod tests {
         let left = build_table_from_batches(vec![left_batch_1, left_batch_2]);
         let right = build_table_from_batches(vec![right_batch_1, right_batch_2]);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Right).await?;
@@ -2268,8 +2271,8 @@ mod tests {
         let left = build_table_from_batches(vec![left_batch_1, left_batch_2]);
         let right = build_table_from_batches(vec![right_batch_1, right_batch_2]);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
         let (_, batches) = join_collect(left, right, on, JoinType::Full).await?;
@@ -2307,8 +2310,8 @@ mod tests {
             ("c2", &vec![50, 60, 70, 80, 90]),
         );
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
         let sort_options = vec![SortOptions::default(); on.len()];
 
@@ -2383,8 +2386,8 @@ mod tests {
         let right =
             build_table_from_batches(vec![right_batch_1, right_batch_2, right_batch_3]);
         let on = vec![(
-            Column::new_with_schema("b1", &left.schema())?,
-            Column::new_with_schema("b2", &right.schema())?,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
         let sort_options = vec![SortOptions::default(); on.len()];
 
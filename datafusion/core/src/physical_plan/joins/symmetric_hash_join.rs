@@ -39,7 +39,7 @@ use arrow::array::{
     PrimitiveBuilder,
 };
 use arrow::compute::concat_batches;
-use arrow::datatypes::{ArrowNativeType, Schema, SchemaRef};
+use arrow::datatypes::{ArrowNativeType, Schema, SchemaRef, UInt32Type};
 use arrow::record_batch::RecordBatch;
 use futures::stream::{select, BoxStream};
 use futures::{Stream, StreamExt};
@@ -52,22 +52,22 @@ use datafusion_physical_expr::intervals::{ExprIntervalGraph, Interval, IntervalB
 
 use crate::physical_plan::common::SharedMemoryReservation;
 use crate::physical_plan::joins::hash_join_utils::convert_sort_expr_with_filter_schema;
-use crate::physical_plan::joins::hash_join_utils::JoinHashMap;
+use crate::physical_plan::joins::hash_join_utils::PruningJoinHashMap;
 use crate::physical_plan::{
-    expressions::Column,
     expressions::PhysicalSortExpr,
     joins::{
-        hash_join::{build_join_indices, update_hash},
+        hash_join::{build_join_indices, evaluate_join_keys, update_hash},
         hash_join_utils::{build_filter_input_order, SortedFilterExpr},
         utils::{
             build_batch_from_indices, build_join_schema, check_join_is_valid,
-            combine_join_equivalence_properties, partitioned_join_output_partitioning,
-            ColumnIndex, JoinFilter, JoinOn, JoinSide,
+            combine_join_equivalence_properties, display_join_on,
+            partitioned_join_output_partitioning, ColumnIndex, JoinFilter, JoinOn,
+            JoinOnRef, JoinSide,
         },
     },
     metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
     DisplayFormatType, Distribution, EquivalenceProperties, ExecutionPlan, Partitioning,
-    RecordBatchStream, SendableRecordBatchStream, Statistics,
+    PhysicalExpr, RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
 use datafusion_common::JoinType;
 use datafusion_common::{DataFusionError, Result};
@@ -166,8 +166,8 @@ pub struct SymmetricHashJoinExec {
     pub(crate) left: Arc<dyn ExecutionPlan>,
     /// Right side stream
     pub(crate) right: Arc<dyn ExecutionPlan>,
-    /// Set of common columns used to join on
-    pub(crate) on: Vec<(Column, Column)>,
+    /// Pairs of left and right join key expressions used to join on
+    pub(crate) on: JoinOn,
     /// Filters applied when finding matching rows
     pub(crate) filter: Option<JoinFilter>,
     /// How the join is performed
@@ -329,8 +329,8 @@ impl SymmetricHashJoinExec {
         &self.right
     }
 
-    /// Set of common columns used to join on
-    pub fn on(&self) -> &[(Column, Column)] {
+    /// Pairs of left and right join key expressions used to join on
+    pub fn on(&self) -> JoinOnRef {
         &self.on
     }
 
@@ -396,11 +396,8 @@ impl ExecutionPlan for SymmetricHashJoinExec {
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
-        let (left_expr, right_expr) = self
-            .on
-            .iter()
-            .map(|(l, r)| (Arc::new(l.clone()) as _, Arc::new(r.clone()) as _))
-            .unzip();
+        let (left_expr, right_expr) =
+            self.on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
         // TODO:  This will change when we extend collected executions.
         vec![
             Distribution::HashPartitioned(left_expr),
@@ -455,15 +452,17 @@ impl ExecutionPlan for SymmetricHashJoinExec {
 
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 let display_filter = self.filter.as_ref().map_or_else(
                     || "".to_string(),
                     |f| format!(", filter={}", f.expression()),
                 );
                 write!(
                     f,
-                    "SymmetricHashJoinExec: join_type={:?}, on={:?}{}",
-                    self.join_type, self.on, display_filter
+                    "SymmetricHashJoinExec: join_type={:?}, on={}{}",
+                    self.join_type,
+                    display_join_on(&self.on),
+                    display_filter
                 )
             }
         }
@@ -681,7 +680,7 @@ impl Stream for SymmetricHashJoinStream {
 
 fn prune_hash_values(
     prune_length: usize,
-    hashmap: &mut JoinHashMap,
+    hashmap: &mut PruningJoinHashMap,
     row_hash_values: &mut VecDeque<u64>,
     offset: u64,
 ) -> Result<()> {
@@ -1041,9 +1040,9 @@ struct OneSideHashJoiner {
     /// Input record batch buffer
     input_buffer: RecordBatch,
     /// Columns from the side
-    on: Vec<Column>,
+    on: Vec<Arc<dyn PhysicalExpr>>,
     /// Hashmap
-    hashmap: JoinHashMap,
+    hashmap: PruningJoinHashMap,
     /// To optimize hash deleting in case of pruning, we hold them in memory
     row_hash_values: VecDeque<u64>,
     /// Reuse the hashes buffer
@@ -1071,12 +1070,16 @@ impl OneSideHashJoiner {
         size += std::mem::size_of_val(&self.deleted_offset);
         size
     }
-    pub fn new(build_side: JoinSide, on: Vec<Column>, schema: SchemaRef) -> Self {
+    pub fn new(
+        build_side: JoinSide,
+        on: Vec<Arc<dyn PhysicalExpr>>,
+        schema: SchemaRef,
+    ) -> Self {
         Self {
             build_side,
             input_buffer: RecordBatch::new_empty(schema),
             on,
-            hashmap: JoinHashMap(RawTable::with_capacity(0)),
+            hashmap: PruningJoinHashMap(RawTable::with_capacity(0)),
             row_hash_values: VecDeque::new(),
             hashes_buffer: vec![],
             visited_rows: HashSet::new(),
@@ -1108,6 +1111,8 @@ impl OneSideHashJoiner {
         // Update the hashmap with the join key values and hashes of the incoming batch:
         update_hash(
             &self.on,
+            &[],
+            &[],
             batch,
             &mut self.hashmap,
             self.offset,
@@ -1143,7 +1148,7 @@ impl OneSideHashJoiner {
         &mut self,
         schema: &SchemaRef,
         join_type: JoinType,
-        on_probe: &[Column],
+        on_probe: &[Arc<dyn PhysicalExpr>],
         filter: Option<&JoinFilter>,
         probe_batch: &RecordBatch,
         probe_visited: &mut HashSet<usize>,
@@ -1155,18 +1160,28 @@ impl OneSideHashJoiner {
         if self.input_buffer.num_rows() == 0 || probe_batch.num_rows() == 0 {
             return Ok(None);
         }
-        let (build_indices, probe_indices) = build_join_indices(
+        let build_join_values = evaluate_join_keys(&self.on, &[], &self.input_buffer)?;
+        let (build_indices, probe_indices) = build_join_indices::<UInt32Type, _>(
             probe_batch,
             &self.hashmap,
-            &self.input_buffer,
-            &self.on,
+            std::slice::from_ref(&self.input_buffer),
+            &[build_join_values],
+            None,
+            None,
+            None,
+            None,
             on_probe,
+            &[],
             filter,
+            None,
+            None,
             random_state,
-            null_equals_null,
+            &vec![null_equals_null; on_probe.len()],
             &mut self.hashes_buffer,
             Some(self.deleted_offset),
             self.build_side,
+            false,
+            false,
         )?;
         if need_to_produce_result_in_final(self.build_side, join_type) {
             record_visited_indices(
@@ -1189,7 +1204,7 @@ impl OneSideHashJoiner {
         } else {
             build_batch_from_indices(
                 schema,
-                &self.input_buffer,
+                std::slice::from_ref(&self.input_buffer),
                 probe_batch,
                 build_indices,
                 probe_indices,
@@ -1241,7 +1256,7 @@ impl OneSideHashJoiner {
             // Build the final result from the indices of build and probe sides:
             build_batch_from_indices(
                 output_schema.as_ref(),
-                &self.input_buffer,
+                std::slice::from_ref(&self.input_buffer),
                 &empty_probe_batch,
                 build_indices,
                 probe_indices,
@@ -1608,15 +1623,9 @@ mod tests {
     ) -> Result<Vec<RecordBatch>> {
         let partition_count = 4;
 
-        let left_expr = on
-            .iter()
-            .map(|(l, _)| Arc::new(l.clone()) as _)
-            .collect::<Vec<_>>();
+        let left_expr = on.iter().map(|(l, _)| l.clone()).collect::<Vec<_>>();
 
-        let right_expr = on
-            .iter()
-            .map(|(_, r)| Arc::new(r.clone()) as _)
-            .collect::<Vec<_>>();
+        let right_expr = on.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
 
         let join = SymmetricHashJoinExec::try_new(
             Arc::new(RepartitionExec::try_new(
@@ -1659,10 +1668,8 @@ mod tests {
     ) -> Result<Vec<RecordBatch>> {
         let partition_count = 4;
 
-        let (left_expr, right_expr) = on
-            .iter()
-            .map(|(l, r)| (Arc::new(l.clone()) as _, Arc::new(r.clone()) as _))
-            .unzip();
+        let (left_expr, right_expr) =
+            on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
 
         let join = HashJoinExec::try_new(
             Arc::new(RepartitionExec::try_new(
@@ -2125,8 +2132,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2201,8 +2208,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2262,8 +2269,8 @@ mod tests {
         let (left, right) = create_memory_table(left_batch, right_batch, None, None, 13)?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2314,8 +2321,8 @@ mod tests {
         let (left, right) = create_memory_table(left_batch, right_batch, None, None, 13)?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
         experiment(left, right, None, join_type, on, task_ctx).await?;
         Ok(())
@@ -2373,8 +2380,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2449,7 +2456,7 @@ mod tests {
         let formatted = displayable(physical_plan.as_ref()).indent().to_string();
         let expected = {
             [
-                "SymmetricHashJoinExec: join_type=Full, on=[(Column { name: \"a2\", index: 1 }, Column { name: \"a2\", index: 1 })], filter=CAST(a1@0 AS Int64) > CAST(a1@1 AS Int64) + 3 AND CAST(a1@0 AS Int64) < CAST(a1@1 AS Int64) + 10",
+                "SymmetricHashJoinExec: join_type=Full, on=[(a2@1, a2@1)], filter=CAST(a1@0 AS Int64) > CAST(a1@1 AS Int64) + 3 AND CAST(a1@0 AS Int64) < CAST(a1@1 AS Int64) + 10",
                 "  CoalesceBatchesExec: target_batch_size=8192",
                 "    RepartitionExec: partitioning=Hash([Column { name: \"a2\", index: 1 }], 8), input_partitions=1",
                 // "   CsvExec: file_groups={1 group: [[tempdir/left.csv]]}, projection=[a1, a2], has_header=false",
@@ -2502,7 +2509,7 @@ mod tests {
         let formatted = displayable(physical_plan.as_ref()).indent().to_string();
         let expected = {
             [
-                "SymmetricHashJoinExec: join_type=Full, on=[(Column { name: \"a2\", index: 1 }, Column { name: \"a2\", index: 1 })], filter=CAST(a1@0 AS Int64) > CAST(a1@1 AS Int64) + 3 AND CAST(a1@0 AS Int64) < CAST(a1@1 AS Int64) + 10",
+                "SymmetricHashJoinExec: join_type=Full, on=[(a2@1, a2@1)], filter=CAST(a1@0 AS Int64) > CAST(a1@1 AS Int64) + 3 AND CAST(a1@0 AS Int64) < CAST(a1@1 AS Int64) + 10",
                 "  CoalesceBatchesExec: target_batch_size=8192",
                 "    RepartitionExec: partitioning=Hash([Column { name: \"a2\", index: 1 }], 8), input_partitions=1",
                 // "   CsvExec: file_groups={1 group: [[tempdir/left.csv]]}, projection=[a1, a2], has_header=false",
@@ -2596,8 +2603,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2659,8 +2666,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2723,8 +2730,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2783,8 +2790,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
@@ -2898,13 +2905,13 @@ mod tests {
 
         let mut left_side_joiner = OneSideHashJoiner::new(
             JoinSide::Left,
-            vec![Column::new_with_schema("lc1", &left_schema)?],
+            vec![Arc::new(Column::new_with_schema("lc1", &left_schema)?)],
             left_schema,
         );
 
         let mut right_side_joiner = OneSideHashJoiner::new(
             JoinSide::Right,
-            vec![Column::new_with_schema("rc1", &right_schema)?],
+            vec![Arc::new(Column::new_with_schema("rc1", &right_schema)?)],
             right_schema,
         );
 
@@ -2969,8 +2976,8 @@ mod tests {
         let left_schema = &left_batch.schema();
         let right_schema = &right_batch.schema();
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
         let left_sorted = vec![PhysicalSortExpr {
             expr: col("lt1", left_schema)?,
@@ -3053,8 +3060,8 @@ mod tests {
         let left_schema = &left_batch.schema();
         let right_schema = &right_batch.schema();
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
         let left_sorted = vec![PhysicalSortExpr {
             expr: col("li1", left_schema)?,
@@ -3148,8 +3155,8 @@ mod tests {
         )?;
 
         let on = vec![(
-            Column::new_with_schema("lc1", left_schema)?,
-            Column::new_with_schema("rc1", right_schema)?,
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
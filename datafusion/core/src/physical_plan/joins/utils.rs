@@ -18,17 +18,21 @@
 //! Join related functionality used both on logical and physical plans
 
 use arrow::array::{
-    downcast_array, new_null_array, Array, BooleanBufferBuilder, UInt32Array,
-    UInt32Builder, UInt64Array,
+    downcast_array, new_null_array, Array, ArrayRef, BooleanBufferBuilder,
+    PrimitiveArray, PrimitiveBuilder, UInt32Array, UInt32Builder, UInt64Array,
+    UInt64Builder,
 };
 use arrow::compute;
-use arrow::datatypes::{Field, Schema, SchemaBuilder};
+use arrow::datatypes::{
+    ArrowNativeType, ArrowPrimitiveType, DataType, Field, Fields, Schema, SchemaBuilder,
+};
 use arrow::record_batch::{RecordBatch, RecordBatchOptions};
-use datafusion_physical_expr::expressions::Column;
-use futures::future::{BoxFuture, Shared};
+use datafusion_physical_expr::expressions::{BinaryExpr, Column, Literal};
+use datafusion_physical_expr::utils::{collect_columns, split_conjunction};
+use futures::future::{BoxFuture, Shared, WeakShared};
 use futures::{ready, FutureExt};
 use parking_lot::Mutex;
-use std::cmp::max;
+use std::cmp::{max, Ordering};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::future::Future;
@@ -40,52 +44,110 @@ use datafusion_common::cast::as_boolean_array;
 use datafusion_common::{ScalarValue, SharedResult};
 
 use datafusion_common::tree_node::{Transformed, TreeNode};
-use datafusion_physical_expr::{EquivalentClass, PhysicalExpr};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::{AnalysisContext, EquivalentClass, PhysicalExpr};
 
 use datafusion_common::JoinType;
 use datafusion_common::{DataFusionError, Result};
 
-use crate::physical_plan::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder};
+use crate::physical_plan::metrics::{
+    self, BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder,
+};
 use crate::physical_plan::SchemaRef;
 use crate::physical_plan::{
     ColumnStatistics, EquivalenceProperties, ExecutionPlan, Partitioning, Statistics,
 };
 
-/// The on clause of the join, as vector of (left, right) columns.
-pub type JoinOn = Vec<(Column, Column)>;
+/// The on clause of the join, as vector of (left, right) join key expressions,
+/// evaluated against the left and right input respectively.
+pub type JoinOn = Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>;
 /// Reference for JoinOn.
-pub type JoinOnRef<'a> = &'a [(Column, Column)];
+pub type JoinOnRef<'a> = &'a [(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)];
+
+/// Formats the join keys `on` for the display of a join plan, like
+/// `[(a@0, b@1), (CAST(c@2 AS Int64), d@0)]`
+pub(crate) fn display_join_on(on: JoinOnRef) -> String {
+    let keys = on
+        .iter()
+        .map(|(left, right)| format!("({left}, {right})"))
+        .collect::<Vec<_>>();
+    format!("[{}]", keys.join(", "))
+}
 
-/// Checks whether the schemas "left" and "right" and columns "on" represent a valid join.
-/// They are valid whenever their columns' intersection equals the set `on`
+/// Checks whether the schemas "left" and "right" and join keys "on" represent a valid join.
+/// They are valid whenever the columns referenced by the keys of each side are part of
+/// that side's schema, and the join keys of both sides are comparable
 pub fn check_join_is_valid(left: &Schema, right: &Schema, on: JoinOnRef) -> Result<()> {
-    let left: HashSet<Column> = left
+    let left_columns: HashSet<Column> = left
         .fields()
         .iter()
         .enumerate()
         .map(|(idx, f)| Column::new(f.name(), idx))
         .collect();
-    let right: HashSet<Column> = right
+    let right_columns: HashSet<Column> = right
         .fields()
         .iter()
         .enumerate()
         .map(|(idx, f)| Column::new(f.name(), idx))
         .collect();
 
-    check_join_set_is_valid(&left, &right, on)
+    check_join_set_is_valid(&left_columns, &right_columns, on)?;
+    check_join_key_types(left, right, on)
+}
+
+/// Checks that timestamp join keys have the same time zone on both sides, and
+/// struct join keys the same fields in the same order, as their values are
+/// only comparable then
+fn check_join_key_types(left: &Schema, right: &Schema, on: JoinOnRef) -> Result<()> {
+    for (l, r) in on {
+        let left_type = &l.data_type(left)?;
+        let right_type = &r.data_type(right)?;
+        match (left_type, right_type) {
+            (DataType::Timestamp(_, left_tz), DataType::Timestamp(_, right_tz))
+                if left_tz != right_tz =>
+            {
+                return Err(DataFusionError::Plan(format!(
+                    "Join key columns {l} and {r} have timestamps with different time zones: {left_type:?} and {right_type:?}"
+                )));
+            }
+            (DataType::Struct(left_fields), DataType::Struct(right_fields))
+                if !left_fields
+                    .iter()
+                    .map(|f| f.name())
+                    .eq(right_fields.iter().map(|f| f.name())) =>
+            {
+                let names = |fields: &Fields| {
+                    fields.iter().map(|f| f.name().as_str()).collect::<Vec<_>>()
+                };
+                return Err(DataFusionError::Plan(format!(
+                    "Join key columns {l} and {r} are structs with different fields: {:?} and {:?}",
+                    names(left_fields),
+                    names(right_fields)
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
 /// Checks whether the sets left, right and on compose a valid join.
-/// They are valid whenever their intersection equals the set `on`
+/// They are valid whenever the columns referenced by `on` are contained in them
 fn check_join_set_is_valid(
     left: &HashSet<Column>,
     right: &HashSet<Column>,
-    on: &[(Column, Column)],
+    on: JoinOnRef,
 ) -> Result<()> {
-    let on_left = &on.iter().map(|on| on.0.clone()).collect::<HashSet<_>>();
+    let on_left = &on
+        .iter()
+        .flat_map(|on| collect_columns(&on.0))
+        .collect::<HashSet<_>>();
     let left_missing = on_left.difference(left).collect::<HashSet<_>>();
 
-    let on_right = &on.iter().map(|on| on.1.clone()).collect::<HashSet<_>>();
+    let on_right = &on
+        .iter()
+        .flat_map(|on| collect_columns(&on.1))
+        .collect::<HashSet<_>>();
     let right_missing = on_right.difference(right).collect::<HashSet<_>>();
 
     if !left_missing.is_empty() | !right_missing.is_empty() {
@@ -153,7 +215,7 @@ pub fn combine_join_equivalence_properties(
     left_properties: EquivalenceProperties,
     right_properties: EquivalenceProperties,
     left_columns_len: usize,
-    on: &[(Column, Column)],
+    on: JoinOnRef,
     schema: SchemaRef,
 ) -> EquivalenceProperties {
     let mut new_properties = EquivalenceProperties::new(schema);
@@ -190,15 +252,63 @@ pub fn combine_join_equivalence_properties(
     }
 
     if join_type == JoinType::Inner {
-        on.iter().for_each(|(column1, column2)| {
-            let new_column2 =
-                Column::new(column2.name(), left_columns_len + column2.index());
-            new_properties.add_equal_conditions((column1, &new_column2))
-        })
+        // Only join keys that are plain columns on both sides make the output
+        // columns equivalent
+        on.iter()
+            .filter_map(|(left, right)| {
+                Some((
+                    left.as_any().downcast_ref::<Column>()?,
+                    right.as_any().downcast_ref::<Column>()?,
+                ))
+            })
+            .for_each(|(column1, column2)| {
+                let new_column2 =
+                    Column::new(column2.name(), left_columns_len + column2.index());
+                new_properties.add_equal_conditions((column1, &new_column2))
+            })
     }
     new_properties
 }
 
+/// Adds the equalities of two columns among the conjuncts of the `filter` of a
+/// join of `join_type` to the equivalence `properties` of its output. Only the
+/// output rows of an `Inner` join all pass the filter, and the filter columns
+/// of its right side come after the `left_columns_len` left side columns in
+/// its output.
+pub(crate) fn add_join_filter_equivalence_properties(
+    properties: &mut EquivalenceProperties,
+    join_type: JoinType,
+    filter: Option<&JoinFilter>,
+    left_columns_len: usize,
+    schema: &Schema,
+) {
+    let filter = match filter {
+        Some(filter) if join_type == JoinType::Inner => filter,
+        _ => return,
+    };
+    // the column of the output of the join a column of the filter is
+    let output_column = |expr: &Arc<dyn PhysicalExpr>| {
+        let column = expr.as_any().downcast_ref::<Column>()?;
+        let column_index = filter.column_indices().get(column.index())?;
+        let index = match column_index.side {
+            JoinSide::Left => column_index.index,
+            JoinSide::Right => left_columns_len + column_index.index,
+        };
+        Some(Column::new(schema.field(index).name(), index))
+    };
+    for conjunct in split_conjunction(filter.expression()) {
+        let binary = match conjunct.as_any().downcast_ref::<BinaryExpr>() {
+            Some(binary) if *binary.op() == Operator::Eq => binary,
+            _ => continue,
+        };
+        if let (Some(left), Some(right)) =
+            (output_column(binary.left()), output_column(binary.right()))
+        {
+            properties.add_equal_conditions((&left, &right));
+        }
+    }
+}
+
 /// Calculate the Equivalence Properties for CrossJoin Node
 pub fn cross_join_equivalence_properties(
     left_properties: EquivalenceProperties,
@@ -272,6 +382,9 @@ pub struct JoinFilter {
     column_indices: Vec<ColumnIndex>,
     /// Physical schema of intermediate batch
     schema: Schema,
+    /// Fraction of the pairs of rows matching the join keys kept by the filter,
+    /// if known
+    selectivity: Option<f64>,
 }
 
 impl JoinFilter {
@@ -285,7 +398,23 @@ impl JoinFilter {
             expression,
             column_indices,
             schema,
+            selectivity: None,
+        }
+    }
+
+    /// Sets the fraction of the pairs of rows matching the join keys kept by
+    /// the filter, e.g. known from the statistics of previous queries, which
+    /// the statistics of the join use instead of estimating it from the
+    /// statistics of its inputs. Returns an error unless `selectivity` is
+    /// between 0 and 1.
+    pub fn with_selectivity(mut self, selectivity: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&selectivity) {
+            return Err(DataFusionError::Plan(format!(
+                "The selectivity of a join filter must be between 0 and 1, got {selectivity}"
+            )));
         }
+        self.selectivity = Some(selectivity);
+        Ok(self)
     }
 
     /// Helper for building ColumnIndex vector from left and right indices
@@ -320,6 +449,99 @@ impl JoinFilter {
     pub fn schema(&self) -> &Schema {
         &self.schema
     }
+
+    /// Fraction of the pairs of rows matching the join keys kept by the filter,
+    /// if set by [`Self::with_selectivity`]
+    pub fn selectivity(&self) -> Option<f64> {
+        self.selectivity
+    }
+
+    /// Splits off the conjuncts of the filter which only reference the columns
+    /// of one side of a join of type `join_type`, to filter the rows of that
+    /// side before they are joined. This is only possible for a side whose rows
+    /// without a match are not produced by the join, e.g. not for the left side
+    /// of a left join, whose rows failing the filter are still produced.
+    pub fn split_side_predicates(&self, join_type: &JoinType) -> Result<SplitJoinFilter> {
+        let left_pushable = !matches!(
+            join_type,
+            JoinType::Left | JoinType::Full | JoinType::LeftAnti
+        );
+        let right_pushable = !matches!(
+            join_type,
+            JoinType::Right | JoinType::Full | JoinType::RightAnti
+        );
+        let mut left = vec![];
+        let mut right = vec![];
+        let mut remaining = vec![];
+        for conjunct in split_conjunction(&self.expression) {
+            let sides = collect_columns(conjunct)
+                .iter()
+                .map(|column| self.column_indices[column.index()].side)
+                .collect::<Vec<_>>();
+            let only = |side| !sides.is_empty() && sides.iter().all(|s| *s == side);
+            if left_pushable && only(JoinSide::Left) {
+                left.push(self.side_predicate(conjunct)?);
+            } else if right_pushable && only(JoinSide::Right) {
+                right.push(self.side_predicate(conjunct)?);
+            } else {
+                remaining.push(conjunct.clone());
+            }
+        }
+
+        // the selectivity of the filter, if known, is not the one of its
+        // remaining conjuncts
+        let filter = if left.is_empty() && right.is_empty() {
+            Some(self.clone())
+        } else {
+            conjunction(remaining).map(|expression| {
+                JoinFilter::new(
+                    expression,
+                    self.column_indices.clone(),
+                    self.schema.clone(),
+                )
+            })
+        };
+        Ok(SplitJoinFilter {
+            left: conjunction(left),
+            right: conjunction(right),
+            filter,
+        })
+    }
+
+    /// Returns `predicate` on the columns of the intermediate batch in terms of
+    /// the columns of the side they come from
+    fn side_predicate(
+        &self,
+        predicate: &Arc<dyn PhysicalExpr>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        predicate.clone().transform_down(&|expr| {
+            Ok(match expr.as_any().downcast_ref::<Column>() {
+                Some(column) => Transformed::Yes(Arc::new(Column::new(
+                    column.name(),
+                    self.column_indices[column.index()].index,
+                ))),
+                None => Transformed::No(expr),
+            })
+        })
+    }
+}
+
+/// A [`JoinFilter`] split by [`JoinFilter::split_side_predicates`]
+#[derive(Debug, Clone)]
+pub struct SplitJoinFilter {
+    /// Conjuncts on the columns of the left side, in terms of its schema
+    pub left: Option<Arc<dyn PhysicalExpr>>,
+    /// Conjuncts on the columns of the right side, in terms of its schema
+    pub right: Option<Arc<dyn PhysicalExpr>>,
+    /// Remaining conjuncts, applied to the joined rows
+    pub filter: Option<JoinFilter>,
+}
+
+/// Returns the conjunction of `predicates`, or `None` if there are none
+fn conjunction(predicates: Vec<Arc<dyn PhysicalExpr>>) -> Option<Arc<dyn PhysicalExpr>> {
+    predicates.into_iter().reduce(|left, right| {
+        Arc::new(BinaryExpr::new(left, Operator::And, right)) as Arc<dyn PhysicalExpr>
+    })
 }
 
 /// Returns the output field given the input field. Outer joins may
@@ -425,14 +647,30 @@ pub fn build_join_schema(
 ///
 /// This is useful for joins where the results of one child are buffered in memory
 /// and shared across potentially multiple output partitions
+///
+/// The computation only runs while a [`OnceFut`] refers to it. If all of them are
+/// dropped before it completes, e.g. because the output streams of the join were
+/// dropped, the computation is dropped too, which cancels it and releases what it
+/// buffered, and the next call to [`OnceAsync::once`] starts it again.
 pub(crate) struct OnceAsync<T> {
-    fut: Mutex<Option<OnceFut<T>>>,
+    state: Arc<Mutex<OnceAsyncState<T>>>,
+}
+
+/// The computation of a [`OnceAsync`]
+enum OnceAsyncState<T> {
+    /// Not started yet, reset, or cancelled
+    Idle,
+    /// Running, only referred to by the [`OnceFut`]s, so that it is dropped with
+    /// the last one of them
+    Running(WeakShared<BoxFuture<'static, SharedResult<Arc<T>>>>),
+    /// Completed, successfully or not
+    Done(SharedResult<Arc<T>>),
 }
 
 impl<T> Default for OnceAsync<T> {
     fn default() -> Self {
         Self {
-            fut: Mutex::new(None),
+            state: Arc::new(Mutex::new(OnceAsyncState::Idle)),
         }
     }
 }
@@ -448,16 +686,55 @@ impl<T: 'static> OnceAsync<T> {
     /// `f` to obtain a future and return a [`OnceFut`] referring to this
     ///
     /// If this is not the first call, will return a [`OnceFut`] referring
-    /// to the same future as was returned by the first call
+    /// to the same future as was returned by the first call, unless it was
+    /// cancelled
     pub(crate) fn once<F, Fut>(&self, f: F) -> OnceFut<T>
     where
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<T>> + Send + 'static,
+        T: Send + Sync,
     {
-        self.fut
-            .lock()
-            .get_or_insert_with(|| OnceFut::new(f()))
-            .clone()
+        let mut state = self.state.lock();
+        match &*state {
+            OnceAsyncState::Done(result) => {
+                return OnceFut {
+                    state: OnceFutState::Ready(result.clone()),
+                }
+            }
+            OnceAsyncState::Running(fut) => {
+                if let Some(fut) = fut.upgrade() {
+                    return OnceFut {
+                        state: OnceFutState::Pending(fut),
+                    };
+                }
+            }
+            OnceAsyncState::Idle => {}
+        }
+
+        // the output is kept once the computation completes, for the next calls
+        let done = Arc::downgrade(&self.state);
+        let fut = f()
+            .map(move |result| {
+                let result = result.map(Arc::new).map_err(Arc::new);
+                if let Some(state) = done.upgrade() {
+                    *state.lock() = OnceAsyncState::Done(result.clone());
+                }
+                result
+            })
+            .boxed()
+            .shared();
+        *state = fut
+            .downgrade()
+            .map_or(OnceAsyncState::Idle, OnceAsyncState::Running);
+        OnceFut {
+            state: OnceFutState::Pending(fut),
+        }
+    }
+
+    /// Drops the output of the computation, if no [`OnceFut`] refers to it
+    /// anymore. The next call to [`OnceAsync::once`] invokes `f` again
+    pub(crate) fn reset(&self) {
+        *self.state.lock() = OnceAsyncState::Idle;
     }
 }
 
@@ -484,38 +761,269 @@ impl<T> Clone for OnceFut<T> {
 #[derive(Clone, Debug, Default)]
 struct PartialJoinStatistics {
     pub num_rows: usize,
-    pub column_statistics: Vec<ColumnStatistics>,
+    pub total_byte_size: Option<usize>,
+    pub column_statistics: Option<Vec<ColumnStatistics>>,
+}
+
+/// This function returns the new join type we get after swapping the given
+/// join's inputs.
+pub fn swap_join_type(join_type: JoinType) -> JoinType {
+    match join_type {
+        JoinType::Inner => JoinType::Inner,
+        JoinType::Full => JoinType::Full,
+        JoinType::Left => JoinType::Right,
+        JoinType::Right => JoinType::Left,
+        JoinType::LeftSemi => JoinType::RightSemi,
+        JoinType::RightSemi => JoinType::LeftSemi,
+        JoinType::LeftAnti => JoinType::RightAnti,
+        JoinType::RightAnti => JoinType::LeftAnti,
+    }
+}
+
+/// When the order of the join is changed by the optimizer, the columns in
+/// the output should not be impacted. This function creates the expressions
+/// that will allow to swap back the values from the original left as the first
+/// columns and those on the right next.
+pub fn swap_reverting_projection(
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Vec<(Arc<dyn PhysicalExpr>, String)> {
+    let right_cols = right_schema.fields().iter().enumerate().map(|(i, f)| {
+        (
+            Arc::new(Column::new(f.name(), i)) as Arc<dyn PhysicalExpr>,
+            f.name().to_owned(),
+        )
+    });
+    let right_len = right_cols.len();
+    let left_cols = left_schema.fields().iter().enumerate().map(|(i, f)| {
+        (
+            Arc::new(Column::new(f.name(), right_len + i)) as Arc<dyn PhysicalExpr>,
+            f.name().to_owned(),
+        )
+    });
+
+    left_cols.chain(right_cols).collect()
+}
+
+/// Swaps join sides for filter column indices and produces new JoinFilter
+pub(crate) fn swap_join_filter(filter: Option<&JoinFilter>) -> Option<JoinFilter> {
+    filter.map(|filter| {
+        let column_indices = filter
+            .column_indices()
+            .iter()
+            .map(|idx| {
+                let side = if matches!(idx.side, JoinSide::Left) {
+                    JoinSide::Right
+                } else {
+                    JoinSide::Left
+                };
+                ColumnIndex {
+                    index: idx.index,
+                    side,
+                }
+            })
+            .collect();
+
+        JoinFilter {
+            expression: filter.expression().clone(),
+            column_indices,
+            schema: filter.schema().clone(),
+            selectivity: filter.selectivity(),
+        }
+    })
+}
+
+/// Returns true if the statistics of `plan` estimate its output to be smaller
+/// than `threshold_byte_size` bytes, or than `threshold_num_rows` rows if its
+/// size in bytes is unknown, so that it can be collected into a single partition
+pub(crate) fn supports_collect_by_thresholds(
+    plan: &dyn ExecutionPlan,
+    threshold_byte_size: usize,
+    threshold_num_rows: usize,
+) -> bool {
+    // Currently we do not trust the 0 value from stats, due to stats collection might have bug
+    // TODO check the logic in datasource::get_statistics_with_limit()
+    if let Some(size) = plan.statistics().total_byte_size {
+        size != 0 && size < threshold_byte_size
+    } else if let Some(row_count) = plan.statistics().num_rows {
+        row_count != 0 && row_count < threshold_num_rows
+    } else {
+        false
+    }
 }
 
+/// Returns true if the values of the join keys `keys` of `plan` are unique,
+/// which is the case if some of them, which are columns, are a unique key of
+/// `plan`, see [`ExecutionPlan::unique_keys`]
+pub(crate) fn join_keys_unique(
+    plan: &dyn ExecutionPlan,
+    keys: &[Arc<dyn PhysicalExpr>],
+) -> bool {
+    let columns = keys
+        .iter()
+        .filter_map(|key| key.as_any().downcast_ref::<Column>())
+        .map(|column| column.index())
+        .collect::<Vec<_>>();
+    plan.unique_keys()
+        .iter()
+        .any(|unique_key| unique_key.iter().all(|column| columns.contains(column)))
+}
+
+/// The fraction of the rows matching the join keys assumed to be kept by a
+/// conjunct of a join filter the statistics of the inputs tell nothing about,
+/// unless `hash_join_default_filter_selectivity` is set
+pub(crate) const DEFAULT_JOIN_FILTER_SELECTIVITY: f64 = 0.2;
+
 /// Estimate the statistics for the given join's output.
+///
+/// The rows matching the join keys `on` are reduced by the selectivity of the
+/// join `filter`, if any, whose conjuncts the statistics of the inputs tell
+/// nothing about keep `default_filter_selectivity` of the rows, see
+/// [`join_filter_selectivity`].
 pub(crate) fn estimate_join_statistics(
     left: Arc<dyn ExecutionPlan>,
     right: Arc<dyn ExecutionPlan>,
     on: JoinOn,
     join_type: &JoinType,
+    filter: Option<&JoinFilter>,
+    default_filter_selectivity: f64,
 ) -> Statistics {
     let left_stats = left.statistics();
     let right_stats = right.statistics();
+    let (left_rows, right_rows) = (left_stats.num_rows, right_stats.num_rows);
+
+    let filter_selectivity = filter.map_or(1.0, |filter| {
+        join_filter_selectivity(
+            filter,
+            &left_stats,
+            &right_stats,
+            default_filter_selectivity,
+        )
+    });
+    let mut join_stats = estimate_join_cardinality(
+        join_type,
+        left_stats,
+        right_stats,
+        &on,
+        filter_selectivity,
+    );
+
+    // A row of an inner join matches at most one row of a side whose join keys
+    // are unique, so the join produces at most as many rows as the other side,
+    // which bounds the estimate even if the statistics of the keys are unknown
+    if *join_type == JoinType::Inner {
+        let (on_left, on_right): (Vec<_>, Vec<_>) = on.iter().cloned().unzip();
+        let max_rows = [
+            (join_keys_unique(left.as_ref(), &on_left), right_rows),
+            (join_keys_unique(right.as_ref(), &on_right), left_rows),
+        ]
+        .into_iter()
+        .filter_map(|(unique, max_rows)| max_rows.filter(|_| unique))
+        .min();
+        match (join_stats.as_mut(), max_rows) {
+            (Some(stats), Some(max_rows)) if max_rows < stats.num_rows => {
+                stats.total_byte_size = stats.total_byte_size.map(|size| {
+                    (size as f64 * max_rows as f64 / stats.num_rows as f64).ceil()
+                        as usize
+                });
+                stats.num_rows = max_rows;
+            }
+            (None, Some(max_rows)) => {
+                join_stats = Some(PartialJoinStatistics {
+                    num_rows: max_rows,
+                    total_byte_size: None,
+                    column_statistics: None,
+                });
+            }
+            _ => {}
+        }
+    }
 
-    let join_stats = estimate_join_cardinality(join_type, left_stats, right_stats, &on);
-    let (num_rows, column_statistics) = match join_stats {
-        Some(stats) => (Some(stats.num_rows), Some(stats.column_statistics)),
-        None => (None, None),
+    let (num_rows, total_byte_size, column_statistics) = match join_stats {
+        Some(stats) => (
+            Some(stats.num_rows),
+            stats.total_byte_size,
+            stats.column_statistics,
+        ),
+        None => (None, None, None),
     };
     Statistics {
         num_rows,
-        total_byte_size: None,
+        total_byte_size,
         column_statistics,
         is_exact: false,
     }
 }
 
-// Estimate the cardinality for the given join with input statistics.
+/// Estimates the fraction of the pairs of rows matching the join keys that the
+/// join `filter` keeps, from the statistics of the columns of both sides,
+/// unless it is set by [`JoinFilter::with_selectivity`].
+///
+/// A literal filter keeps all or none of them. Every conjunct of the filter
+/// comparing a column with known min/max values to a literal, such as
+/// `col > 10`, keeps the fraction of the range of the column it selects,
+/// assuming a uniform distribution, and any other conjunct keeps
+/// `default_selectivity` of them.
+pub(crate) fn join_filter_selectivity(
+    filter: &JoinFilter,
+    left_stats: &Statistics,
+    right_stats: &Statistics,
+    default_selectivity: f64,
+) -> f64 {
+    if let Some(selectivity) = filter.selectivity() {
+        return selectivity;
+    }
+    if let Some(literal) = filter.expression().as_any().downcast_ref::<Literal>() {
+        return match literal.value() {
+            ScalarValue::Boolean(Some(true)) => 1.0,
+            _ => 0.0,
+        };
+    }
+
+    // The statistics of the columns of the intermediate batch the filter is
+    // evaluated on, taken from the side each of them comes from
+    let column_statistics = filter
+        .column_indices()
+        .iter()
+        .map(|column| {
+            let stats = match column.side {
+                JoinSide::Left => left_stats,
+                JoinSide::Right => right_stats,
+            };
+            stats
+                .column_statistics
+                .as_ref()
+                .and_then(|columns| columns.get(column.index).cloned())
+                .unwrap_or_default()
+        })
+        .collect();
+    let statistics = Statistics {
+        column_statistics: Some(column_statistics),
+        ..Default::default()
+    };
+    let context = AnalysisContext::from_statistics(filter.schema(), &statistics);
+
+    split_conjunction(filter.expression())
+        .into_iter()
+        .map(|conjunct| {
+            conjunct
+                .analyze(context.clone())
+                .boundaries
+                .and_then(|boundaries| boundaries.selectivity)
+                .unwrap_or(default_selectivity)
+        })
+        .product()
+}
+
+/// Estimate the cardinality for the given join with input statistics, where
+/// `filter_selectivity` is the fraction of the rows matching the join keys kept
+/// by the join filter, 1.0 without a filter.
 fn estimate_join_cardinality(
     join_type: &JoinType,
     left_stats: Statistics,
     right_stats: Statistics,
     on: &JoinOn,
+    filter_selectivity: f64,
 ) -> Option<PartialJoinStatistics> {
     match join_type {
         JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
@@ -523,15 +1031,16 @@ fn estimate_join_cardinality(
             let right_num_rows = right_stats.num_rows?;
 
             // Take the left_col_stats and right_col_stats using the index
-            // obtained from index() method of the each element of 'on'.
+            // obtained from index() method of the each column key of 'on'. Keys
+            // that are not plain columns have unknown statistics.
             let all_left_col_stats = left_stats.column_statistics?;
             let all_right_col_stats = right_stats.column_statistics?;
             let (left_col_stats, right_col_stats) = on
                 .iter()
                 .map(|(left, right)| {
                     (
-                        all_left_col_stats[left.index()].clone(),
-                        all_right_col_stats[right.index()].clone(),
+                        join_key_statistics(&all_left_col_stats, left),
+                        join_key_statistics(&all_right_col_stats, right),
                     )
                 })
                 .unzip::<_, _, Vec<_>, Vec<_>>();
@@ -543,6 +1052,8 @@ fn estimate_join_cardinality(
                 right_col_stats,
                 left_stats.is_exact && right_stats.is_exact,
             )?;
+            let ij_cardinality =
+                (ij_cardinality as f64 * filter_selectivity).ceil() as usize;
 
             // The cardinality for inner join can also be used to estimate
             // the cardinality of left/right/full outer joins as long as it
@@ -562,28 +1073,218 @@ fn estimate_join_cardinality(
 
             Some(PartialJoinStatistics {
                 num_rows: cardinality,
-                // We don't do anything specific here, just combine the existing
-                // statistics which might yield subpar results (although it is
-                // true, esp regarding min/max). For a better estimation, we need
-                // filter selectivity analysis first.
-                column_statistics: all_left_col_stats
-                    .into_iter()
-                    .chain(all_right_col_stats.into_iter())
-                    .collect(),
+                total_byte_size: None,
+                column_statistics: Some(join_column_statistics(
+                    join_type,
+                    on,
+                    all_left_col_stats,
+                    all_right_col_stats,
+                )),
             })
         }
 
         JoinType::LeftSemi
         | JoinType::RightSemi
         | JoinType::LeftAnti
-        | JoinType::RightAnti => None,
+        | JoinType::RightAnti => {
+            // Only the rows of the preserved side are produced, each at most
+            // once, so its row count bounds the cardinality of the join
+            let preserve_left =
+                matches!(join_type, JoinType::LeftSemi | JoinType::LeftAnti);
+            let (outer_stats, inner_stats) = if preserve_left {
+                (left_stats, right_stats)
+            } else {
+                (right_stats, left_stats)
+            };
+            let outer_num_rows = outer_stats.num_rows?;
+
+            let key_stats = match (
+                &outer_stats.column_statistics,
+                &inner_stats.column_statistics,
+            ) {
+                (Some(outer_col_stats), Some(inner_col_stats)) => on
+                    .iter()
+                    .map(|(left, right)| {
+                        let (outer, inner) = if preserve_left {
+                            (left, right)
+                        } else {
+                            (right, left)
+                        };
+                        (
+                            join_key_statistics(outer_col_stats, outer),
+                            join_key_statistics(inner_col_stats, inner),
+                        )
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            let match_fraction =
+                semi_join_match_fraction(outer_num_rows, inner_stats.num_rows, key_stats);
+
+            // Without the number of distinct values of the join keys, all the
+            // rows of the preserved side may or may not have a match
+            let semi_cardinality = ((outer_num_rows as f64
+                * match_fraction.unwrap_or(1.0)
+                * filter_selectivity)
+                .ceil() as usize)
+                .min(outer_num_rows);
+            let cardinality = match (join_type, match_fraction) {
+                (JoinType::LeftSemi | JoinType::RightSemi, _) => semi_cardinality,
+                (_, Some(_)) => outer_num_rows - semi_cardinality,
+                (_, None) => outer_num_rows,
+            };
+
+            Some(PartialJoinStatistics {
+                num_rows: cardinality,
+                // The rows of the preserved side are assumed to be of the same
+                // size on average
+                total_byte_size: outer_stats.total_byte_size.map(|size| {
+                    if outer_num_rows == 0 {
+                        0
+                    } else {
+                        (size as f64 * cardinality as f64 / outer_num_rows as f64).ceil()
+                            as usize
+                    }
+                }),
+                column_statistics: outer_stats.column_statistics,
+            })
+        }
+    }
+}
+
+/// The statistics of the columns of the output of a join producing the columns
+/// of both sides, the `left` ones followed by the `right` ones.
+///
+/// The values of the columns of the output are among the ones of the inputs,
+/// so their statistics are the ones of the inputs, but for the following:
+/// * the values of the join key columns of an inner join are within the ranges
+///   of both keys, see [`intersect_join_key_statistics`]
+/// * the null counts of the columns of the sides padded with nulls by outer
+///   joins are unknown
+fn join_column_statistics(
+    join_type: &JoinType,
+    on: &JoinOn,
+    mut left: Vec<ColumnStatistics>,
+    mut right: Vec<ColumnStatistics>,
+) -> Vec<ColumnStatistics> {
+    match join_type {
+        JoinType::Inner => {
+            for (left_key, right_key) in on {
+                let left_key_stats = join_key_statistics(&left, left_key);
+                let right_key_stats = join_key_statistics(&right, right_key);
+                if let Some(column) = left_key.as_any().downcast_ref::<Column>() {
+                    left[column.index()] =
+                        intersect_join_key_statistics(&left_key_stats, &right_key_stats);
+                }
+                if let Some(column) = right_key.as_any().downcast_ref::<Column>() {
+                    right[column.index()] =
+                        intersect_join_key_statistics(&right_key_stats, &left_key_stats);
+                }
+            }
+        }
+        JoinType::Left => right.iter_mut().for_each(|stats| stats.null_count = None),
+        JoinType::Right => left.iter_mut().for_each(|stats| stats.null_count = None),
+        JoinType::Full => left
+            .iter_mut()
+            .chain(right.iter_mut())
+            .for_each(|stats| stats.null_count = None),
+        _ => {}
+    }
+    left.into_iter().chain(right.into_iter()).collect()
+}
+
+/// The statistics of the join key column with statistics `key` of an inner
+/// join on the key with statistics `other`: its values are within the ranges
+/// of both keys, and it has no more distinct values than either of them.
+/// Whether its nulls are matched is not known here, so its null count is
+/// unknown.
+///
+/// The range is empty, and unknown, if the ranges of the keys are disjoint.
+/// The bounds of keys of different types are not comparable, and ignored.
+fn intersect_join_key_statistics(
+    key: &ColumnStatistics,
+    other: &ColumnStatistics,
+) -> ColumnStatistics {
+    // the bound of `other` replaces the one of `key` if it is narrower
+    let narrow = |bound: &Option<ScalarValue>,
+                  other_bound: &Option<ScalarValue>,
+                  narrower: Ordering| match (bound, other_bound) {
+        (Some(bound), Some(other_bound))
+            if other_bound.partial_cmp(bound) == Some(narrower) =>
+        {
+            Some(other_bound.clone())
+        }
+        _ => bound.clone(),
+    };
+    let mut min_value = narrow(&key.min_value, &other.min_value, Ordering::Greater);
+    let mut max_value = narrow(&key.max_value, &other.max_value, Ordering::Less);
+    if let (Some(min), Some(max)) = (&min_value, &max_value) {
+        if min > max {
+            min_value = None;
+            max_value = None;
+        }
+    }
+    let distinct_count = match (key.distinct_count, other.distinct_count) {
+        (Some(distinct), Some(other_distinct)) => Some(distinct.min(other_distinct)),
+        (distinct, None) | (None, distinct) => distinct,
+    };
+    ColumnStatistics {
+        null_count: None,
+        max_value,
+        min_value,
+        distinct_count,
     }
 }
 
+/// The statistics of the join key `key` from the statistics of the columns
+/// of its side, unknown for keys that are not plain columns
+fn join_key_statistics(
+    stats: &[ColumnStatistics],
+    key: &Arc<dyn PhysicalExpr>,
+) -> ColumnStatistics {
+    key.as_any()
+        .downcast_ref::<Column>()
+        .map(|column| stats[column.index()].clone())
+        .unwrap_or_default()
+}
+
+/// Estimates the fraction of the `outer_num_rows` rows of the preserved side of
+/// a semi join which have a match among the `inner_num_rows` rows of the other
+/// side, from the `(outer, inner)` statistics of every pair of join keys.
+///
+/// The join key values of the side with fewer distinct values are assumed to
+/// all appear on the other side (the containment assumption), and every join
+/// key has to match, so the most selective one bounds the fraction. Returns
+/// `None` if the number of distinct values of no join key is known on both
+/// sides.
+fn semi_join_match_fraction(
+    outer_num_rows: usize,
+    inner_num_rows: Option<usize>,
+    key_stats: Vec<(ColumnStatistics, ColumnStatistics)>,
+) -> Option<f64> {
+    let inner_num_rows = inner_num_rows?;
+    key_stats
+        .into_iter()
+        .filter_map(|(outer, inner)| {
+            let outer_distinct = max_distinct_count(outer_num_rows, outer)?;
+            let inner_distinct = max_distinct_count(inner_num_rows, inner)?;
+            (outer_distinct > 0)
+                .then(|| (inner_distinct as f64 / outer_distinct as f64).min(1.0))
+        })
+        .reduce(f64::min)
+}
+
 /// Estimate the inner join cardinality by using the basic building blocks of
 /// column-level statistics and the total row count. This is a very naive and
 /// a very conservative implementation that can quickly give up if there is not
 /// enough input statistics.
+///
+/// If the distinct counts of all the join keys of both sides are known, the
+/// cardinality is `|L| * |R| / max(ndv(L), ndv(R))`, where the number of
+/// distinct values of the keys of a side is the product of the distinct counts
+/// of its keys, assumed to be independent, capped by its row count. The keys
+/// then need no min and max values. Otherwise, the largest number of distinct
+/// values of a single key is used.
 fn estimate_inner_join_cardinality(
     left_num_rows: usize,
     right_num_rows: usize,
@@ -594,15 +1295,53 @@ fn estimate_inner_join_cardinality(
     // The algorithm here is partly based on the non-histogram selectivity estimation
     // from Spark's Catalyst optimizer.
 
+    // The distinct values of the keys of a side are the combinations of the
+    // distinct values of each key, at most one per row
+    let distinct_keys = |num_rows: usize, col_stats: &[ColumnStatistics]| {
+        col_stats
+            .iter()
+            .try_fold(1_usize, |product, stats| {
+                Some(product.saturating_mul(stats.distinct_count?))
+            })
+            .map(|product| product.min(num_rows))
+    };
+    let distinct_keys = match (
+        distinct_keys(left_num_rows, &left_col_stats),
+        distinct_keys(right_num_rows, &right_col_stats),
+    ) {
+        (Some(left_distinct), Some(right_distinct)) => {
+            Some(max(left_distinct, right_distinct))
+        }
+        _ => None,
+    };
+
     let mut join_selectivity = None;
     for (left_stat, right_stat) in left_col_stats.iter().zip(right_col_stats.iter()) {
-        if (left_stat.min_value.clone()? > right_stat.max_value.clone()?)
-            || (left_stat.max_value.clone()? < right_stat.min_value.clone()?)
-        {
-            // If there is no overlap in any of the join columns, that means the join
-            // itself is disjoint and the cardinality is 0. Though we can only assume
-            // this when the statistics are exact (since it is a very strong assumption).
-            return if is_exact { Some(0) } else { None };
+        let bounds = match (
+            &left_stat.min_value,
+            &left_stat.max_value,
+            &right_stat.min_value,
+            &right_stat.max_value,
+        ) {
+            (Some(left_min), Some(left_max), Some(right_min), Some(right_max)) => {
+                Some((left_min, left_max, right_min, right_max))
+            }
+            _ => None,
+        };
+        match bounds {
+            Some((left_min, left_max, right_min, right_max))
+                if left_min > right_max || left_max < right_min =>
+            {
+                // If there is no overlap in any of the join columns, that means the join
+                // itself is disjoint and the cardinality is 0. Though we can only assume
+                // this when the statistics are exact (since it is a very strong assumption).
+                return if is_exact { Some(0) } else { None };
+            }
+            Some(_) => {}
+            // The overlap of keys without bounds is unknown, which only the
+            // distinct counts of all the keys make up for
+            None if distinct_keys.is_some() => continue,
+            None => return None,
         }
 
         let left_max_distinct = max_distinct_count(left_num_rows, left_stat.clone());
@@ -616,6 +1355,8 @@ fn estimate_inner_join_cardinality(
         }
     }
 
+    join_selectivity = distinct_keys.or(join_selectivity);
+
     // With the assumption that the smaller input's domain is generally represented in the bigger
     // input's domain, we can estimate the inner join's cardinality by taking the cartesian product
     // of the two inputs and normalizing it by the selectivity factor.
@@ -721,6 +1462,23 @@ impl<T: 'static> OnceFut<T> {
             ),
         }
     }
+
+    /// Get a shared reference to the result of the computation if it is ready,
+    /// without consuming it or borrowing `self`
+    pub(crate) fn get_shared(&mut self, cx: &mut Context<'_>) -> Poll<Result<Arc<T>>> {
+        if let OnceFutState::Pending(fut) = &mut self.state {
+            let r = ready!(fut.poll_unpin(cx));
+            self.state = OnceFutState::Ready(r);
+        }
+
+        match &self.state {
+            OnceFutState::Pending(_) => unreachable!(),
+            OnceFutState::Ready(r) => Poll::Ready(
+                r.clone()
+                    .map_err(|e| DataFusionError::External(Box::new(e))),
+            ),
+        }
+    }
 }
 
 /// Some type `join_type` of join need to maintain the matched indices bit map for the left side, and
@@ -768,21 +1526,31 @@ pub(crate) fn get_final_indices_from_bit_map(
     (left_indices, right_indices)
 }
 
-pub(crate) fn apply_join_filter_to_indices(
-    build_input_buffer: &RecordBatch,
+/// Filters the `build_indices` and `probe_indices` of the row pairs for which
+/// `filter` is true.
+///
+/// The build side rows are addressed by their position in the concatenation of
+/// `build_input_buffers`, see [`build_batch_from_indices`].
+///
+/// The probe side indices are of type `P`, which is [`UInt32Type`] unless the
+/// probe side has more rows than it can index.
+///
+/// [`UInt32Type`]: arrow::datatypes::UInt32Type
+pub(crate) fn apply_join_filter_to_indices<P: ArrowPrimitiveType>(
+    build_input_buffers: &[RecordBatch],
     probe_batch: &RecordBatch,
     build_indices: UInt64Array,
-    probe_indices: UInt32Array,
+    probe_indices: PrimitiveArray<P>,
     filter: &JoinFilter,
     build_side: JoinSide,
-) -> Result<(UInt64Array, UInt32Array)> {
+) -> Result<(UInt64Array, PrimitiveArray<P>)> {
     if build_indices.is_empty() && probe_indices.is_empty() {
         return Ok((build_indices, probe_indices));
     };
 
     let intermediate_batch = build_batch_from_indices(
         filter.schema(),
-        build_input_buffer,
+        build_input_buffers,
         probe_batch,
         build_indices.clone(),
         probe_indices.clone(),
@@ -805,12 +1573,15 @@ pub(crate) fn apply_join_filter_to_indices(
 
 /// Returns a new [RecordBatch] by combining the `left` and `right` according to `indices`.
 /// The resulting batch has [Schema] `schema`.
-pub(crate) fn build_batch_from_indices(
+/// The build side rows are addressed by their position in the concatenation of
+/// `build_input_buffers`, which are not concatenated to take them.
+/// The probe side indices are of any type `P` wide enough to index `probe_batch`.
+pub(crate) fn build_batch_from_indices<P: ArrowPrimitiveType>(
     schema: &Schema,
-    build_input_buffer: &RecordBatch,
+    build_input_buffers: &[RecordBatch],
     probe_batch: &RecordBatch,
     build_indices: UInt64Array,
-    probe_indices: UInt32Array,
+    probe_indices: PrimitiveArray<P>,
     column_indices: &[ColumnIndex],
     build_side: JoinSide,
 ) -> Result<RecordBatch> {
@@ -831,40 +1602,130 @@ pub(crate) fn build_batch_from_indices(
     // 2. based on the pick, `take` items from the different RecordBatches
     let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
 
-    for column_index in column_indices {
+    for (i, column_index) in column_indices.iter().enumerate() {
         let array = if column_index.side == build_side {
-            let array = build_input_buffer.column(column_index.index);
-            if array.is_empty() || build_indices.null_count() == build_indices.len() {
-                // Outer join would generate a null index when finding no match at our side.
-                // Therefore, it's possible we are empty but need to populate an n-length null array,
-                // where n is the length of the index array.
-                assert_eq!(build_indices.null_count(), build_indices.len());
-                new_null_array(array.data_type(), build_indices.len())
-            } else {
-                compute::take(array.as_ref(), &build_indices, None)?
-            }
+            take_from_batches(
+                build_input_buffers,
+                column_index.index,
+                schema.field(i).data_type(),
+                &build_indices,
+            )?
         } else {
-            let array = probe_batch.column(column_index.index);
-            if array.is_empty() || probe_indices.null_count() == probe_indices.len() {
-                assert_eq!(probe_indices.null_count(), probe_indices.len());
-                new_null_array(array.data_type(), probe_indices.len())
-            } else {
-                compute::take(array.as_ref(), &probe_indices, None)?
-            }
+            take_probe_column(probe_batch.column(column_index.index), &probe_indices)?
         };
         columns.push(array);
     }
     Ok(RecordBatch::try_new(Arc::new(schema.clone()), columns)?)
 }
 
+/// Takes the values at `probe_indices` of the probe side column `array`, a
+/// null index producing a null value (as for unmatched rows of outer joins)
+pub(crate) fn take_probe_column<P: ArrowPrimitiveType>(
+    array: &ArrayRef,
+    probe_indices: &PrimitiveArray<P>,
+) -> Result<ArrayRef> {
+    if probe_indices.null_count() == probe_indices.len() {
+        Ok(new_null_array(array.data_type(), probe_indices.len()))
+    } else if array.is_empty() {
+        Err(DataFusionError::Internal(format!(
+            "Cannot take {} probe side rows from an empty column",
+            probe_indices.len() - probe_indices.null_count()
+        )))
+    } else {
+        Ok(compute::take(array.as_ref(), probe_indices, None)?)
+    }
+}
+
+/// Takes the values at `indices` of the column `column` of `batches`, of type
+/// `data_type`, addressing the rows by their position in the concatenation of
+/// `batches`. Rows of several batches are taken with [`compute::interleave`]
+/// rather than by concatenating the batches first.
+pub(crate) fn take_from_batches(
+    batches: &[RecordBatch],
+    column: usize,
+    data_type: &DataType,
+    indices: &UInt64Array,
+) -> Result<ArrayRef> {
+    let arrays = batches
+        .iter()
+        .map(|batch| batch.column(column).clone())
+        .collect::<Vec<_>>();
+    take_from_arrays(&arrays, data_type, indices)
+}
+
+/// Takes the values at `indices` of `arrays`, of type `data_type`, addressing
+/// the values by their position in the concatenation of `arrays`, like
+/// [`take_from_batches`].
+pub(crate) fn take_from_arrays(
+    arrays: &[ArrayRef],
+    data_type: &DataType,
+    indices: &UInt64Array,
+) -> Result<ArrayRef> {
+    if indices.null_count() == indices.len() {
+        // Outer join would generate a null index when finding no match at our side.
+        // Therefore, it's possible we are empty but need to populate an n-length null array,
+        // where n is the length of the index array.
+        return Ok(new_null_array(data_type, indices.len()));
+    }
+    if let [array] = arrays {
+        return Ok(compute::take(array.as_ref(), indices, None)?);
+    }
+
+    let offsets = offsets(arrays.iter().map(|array| array.len()));
+    let num_rows = offsets[arrays.len()];
+    // null indices take the single row of an extra array of nulls
+    let nulls = new_null_array(data_type, 1);
+    let values = arrays
+        .iter()
+        .map(|array| array.as_ref())
+        .chain(std::iter::once(nulls.as_ref()))
+        .collect::<Vec<_>>();
+    let indices = indices
+        .iter()
+        .map(|index| match index {
+            Some(index) if (index as usize) < num_rows => {
+                Ok(locate_row(&offsets, index as usize))
+            }
+            Some(index) => Err(DataFusionError::Internal(format!(
+                "Row {index} is out of bounds of the {num_rows} rows of the batches"
+            ))),
+            None => Ok((arrays.len(), 0)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(compute::interleave(&values, &indices)?)
+}
+
+/// Returns the position of the first row of every batch of `batches` in their
+/// concatenation, followed by their total number of rows
+pub(crate) fn batch_offsets(batches: &[RecordBatch]) -> Vec<usize> {
+    offsets(batches.iter().map(|batch| batch.num_rows()))
+}
+
+/// Returns the prefix sums of `lengths`, starting with 0
+fn offsets(lengths: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut offsets = vec![0];
+    for length in lengths {
+        offsets.push(offsets[offsets.len() - 1] + length);
+    }
+    offsets
+}
+
+/// Returns the index of the batch and the row in it of the row at `index` in
+/// the concatenation of batches with `offsets` (see [`batch_offsets`]), which
+/// must be less than their total number of rows
+pub(crate) fn locate_row(offsets: &[usize], index: usize) -> (usize, usize) {
+    let batch = offsets.partition_point(|&offset| offset <= index) - 1;
+    (batch, index - offsets[batch])
+}
+
 /// The input is the matched indices for left and right and
 /// adjust the indices according to the join type
-pub(crate) fn adjust_indices_by_join_type(
+pub(crate) fn adjust_indices_by_join_type<P: ArrowPrimitiveType>(
     left_indices: UInt64Array,
-    right_indices: UInt32Array,
+    right_indices: PrimitiveArray<P>,
     count_right_batch: usize,
     join_type: JoinType,
-) -> (UInt64Array, UInt32Array) {
+) -> (UInt64Array, PrimitiveArray<P>) {
     match join_type {
         JoinType::Inner => {
             // matched
@@ -901,7 +1762,7 @@ pub(crate) fn adjust_indices_by_join_type(
             // When visit the right batch, we can output the matched left row and don't need to wait the end of loop
             (
                 UInt64Array::from_iter_values(vec![]),
-                UInt32Array::from_iter_values(vec![]),
+                PrimitiveArray::<P>::from_iter_values(vec![]),
             )
         }
     }
@@ -910,11 +1771,11 @@ pub(crate) fn adjust_indices_by_join_type(
 /// Appends the `right_unmatched_indices` to the `right_indices`,
 /// and fills Null to tail of `left_indices` to
 /// keep the length of `right_indices` and `left_indices` consistent.
-pub(crate) fn append_right_indices(
+pub(crate) fn append_right_indices<P: ArrowPrimitiveType>(
     left_indices: UInt64Array,
-    right_indices: UInt32Array,
-    right_unmatched_indices: UInt32Array,
-) -> (UInt64Array, UInt32Array) {
+    right_indices: PrimitiveArray<P>,
+    right_unmatched_indices: PrimitiveArray<P>,
+) -> (UInt64Array, PrimitiveArray<P>) {
     // left_indices, right_indices and right_unmatched_indices must not contain the null value
     if right_unmatched_indices.is_empty() {
         (left_indices, right_indices)
@@ -929,26 +1790,62 @@ pub(crate) fn append_right_indices(
         let new_right_indices = right_indices
             .iter()
             .chain(right_unmatched_indices.iter())
-            .collect::<UInt32Array>();
+            .collect::<PrimitiveArray<P>>();
         (new_left_indices, new_right_indices)
     }
 }
 
+/// Like [`append_right_indices`], but inserts every one of the
+/// `right_unmatched_indices` before the first of the `right_indices` greater
+/// than it, so that the right indices stay in increasing order if both
+/// `right_indices` and `right_unmatched_indices` are.
+pub(crate) fn merge_right_indices<P: ArrowPrimitiveType>(
+    left_indices: UInt64Array,
+    right_indices: PrimitiveArray<P>,
+    right_unmatched_indices: PrimitiveArray<P>,
+) -> (UInt64Array, PrimitiveArray<P>) {
+    // left_indices, right_indices and right_unmatched_indices must not contain the null value
+    if right_unmatched_indices.is_empty() {
+        return (left_indices, right_indices);
+    }
+    let len = right_indices.len() + right_unmatched_indices.len();
+    let mut new_left_indices = UInt64Builder::with_capacity(len);
+    let mut new_right_indices = PrimitiveBuilder::<P>::with_capacity(len);
+    let mut unmatched = right_unmatched_indices.values().iter().peekable();
+    for (left, right) in left_indices
+        .values()
+        .iter()
+        .zip(right_indices.values().iter())
+    {
+        while let Some(index) = unmatched.next_if(|index| *index < right) {
+            new_left_indices.append_null();
+            new_right_indices.append_value(*index);
+        }
+        new_left_indices.append_value(*left);
+        new_right_indices.append_value(*right);
+    }
+    for index in unmatched {
+        new_left_indices.append_null();
+        new_right_indices.append_value(*index);
+    }
+    (new_left_indices.finish(), new_right_indices.finish())
+}
+
 /// Get unmatched and deduplicated indices
-pub(crate) fn get_anti_indices(
+pub(crate) fn get_anti_indices<T: ArrowPrimitiveType>(
     row_count: usize,
-    input_indices: &UInt32Array,
-) -> UInt32Array {
+    input_indices: &PrimitiveArray<T>,
+) -> PrimitiveArray<T> {
     let mut bitmap = BooleanBufferBuilder::new(row_count);
     bitmap.append_n(row_count, false);
     input_indices.iter().flatten().for_each(|v| {
-        bitmap.set_bit(v as usize, true);
+        bitmap.set_bit(v.as_usize(), true);
     });
 
     // get the anti index
     (0..row_count)
-        .filter_map(|idx| (!bitmap.get_bit(idx)).then_some(idx as u32))
-        .collect::<UInt32Array>()
+        .filter_map(|idx| (!bitmap.get_bit(idx)).then_some(T::Native::usize_as(idx)))
+        .collect::<PrimitiveArray<T>>()
 }
 
 /// Get unmatched and deduplicated indices
@@ -969,20 +1866,20 @@ pub(crate) fn get_anti_u64_indices(
 }
 
 /// Get matched and deduplicated indices
-pub(crate) fn get_semi_indices(
+pub(crate) fn get_semi_indices<T: ArrowPrimitiveType>(
     row_count: usize,
-    input_indices: &UInt32Array,
-) -> UInt32Array {
+    input_indices: &PrimitiveArray<T>,
+) -> PrimitiveArray<T> {
     let mut bitmap = BooleanBufferBuilder::new(row_count);
     bitmap.append_n(row_count, false);
     input_indices.iter().flatten().for_each(|v| {
-        bitmap.set_bit(v as usize, true);
+        bitmap.set_bit(v.as_usize(), true);
     });
 
     // get the semi index
     (0..row_count)
-        .filter_map(|idx| (bitmap.get_bit(idx)).then_some(idx as u32))
-        .collect::<UInt32Array>()
+        .filter_map(|idx| (bitmap.get_bit(idx)).then_some(T::Native::usize_as(idx)))
+        .collect::<PrimitiveArray<T>>()
 }
 
 /// Get matched and deduplicated indices
@@ -1005,30 +1902,104 @@ pub(crate) fn get_semi_u64_indices(
 /// Metrics for build & probe joins
 #[derive(Clone, Debug)]
 pub(crate) struct BuildProbeJoinMetrics {
-    /// Total time for collecting build-side of join
+    /// Total time for building the hash table and the other data structures
+    /// of the build-side of join, excluding the time waiting for its input
     pub(crate) build_time: metrics::Time,
     /// Number of batches consumed by build-side
     pub(crate) build_input_batches: metrics::Count,
     /// Number of rows consumed by build-side
     pub(crate) build_input_rows: metrics::Count,
-    /// Memory used by build-side in bytes
+    /// Peak memory used by build-side in bytes, which is not lowered when
+    /// memory is released, e.g. when a spilling join moves to its next partition
     pub(crate) build_mem_used: metrics::Gauge,
+    /// Number of times the build-side hash table was rehashed into a larger table
+    pub(crate) build_hash_table_rehashes: metrics::Count,
+    /// Number of distinct hash values of the build-side join keys
+    pub(crate) build_hash_buckets: metrics::Count,
+    /// Memory used by the build-side hash table in bytes
+    pub(crate) build_hash_table_size: metrics::Gauge,
+    /// Largest number of build-side rows with the same hash value
+    pub(crate) build_longest_chain: metrics::Gauge,
     /// Total time for joining probe-side batches to the build-side batches
     pub(crate) join_time: metrics::Time,
+    /// Part of `join_time` evaluating and hashing the join keys of the
+    /// probe-side batches
+    pub(crate) probe_hash_time: metrics::Time,
+    /// Part of `join_time` looking up the probe-side rows in the hash table
+    /// and comparing the join keys of the rows with the same hash value
+    pub(crate) match_time: metrics::Time,
+    /// Part of `join_time` evaluating the join filter on the matched rows
+    pub(crate) filter_time: metrics::Time,
+    /// Number of pairs of rows with equal join keys the join filter was
+    /// evaluated on
+    pub(crate) filter_input_rows: metrics::Count,
+    /// Number of pairs of rows for which the join filter is true
+    pub(crate) filter_output_rows: metrics::Count,
+    /// Part of `join_time` building the output batches from the joined rows
+    pub(crate) output_build_time: metrics::Time,
     /// Number of batches consumed by probe-side of this operator
     pub(crate) input_batches: metrics::Count,
     /// Number of rows consumed by probe-side this operator
     pub(crate) input_rows: metrics::Count,
+    /// Number of probe-side rows skipped by the Bloom filter of the build-side
+    pub(crate) probe_rows_skipped: metrics::Count,
+    /// Number of pairs of probe-side and build-side rows with the same hash
+    /// value whose join keys were compared
+    pub(crate) probe_candidates: metrics::Count,
+    /// Number of compared pairs of rows whose join keys are equal, lower than
+    /// `probe_candidates` if hash values collide
+    pub(crate) probe_matches: metrics::Count,
     /// Number of batches produced by this operator
     pub(crate) output_batches: metrics::Count,
     /// Number of rows produced by this operator
     pub(crate) output_rows: metrics::Count,
+    /// Number of rows produced by an outer join from a pair of matching rows
+    pub(crate) matched_output_rows: metrics::Count,
+    /// Number of rows produced by an outer join from a row without a match,
+    /// padded with nulls
+    pub(crate) unmatched_output_rows: metrics::Count,
 }
 
 impl BuildProbeJoinMetrics {
     pub fn new(partition: usize, metrics: &ExecutionPlanMetricsSet) -> Self {
+        let output_rows = MetricBuilder::new(metrics).output_rows(partition);
+        Self::with_output_rows(partition, metrics, output_rows)
+    }
+
+    /// Creates the metrics of a join whose output rows are counted by the
+    /// output rows of `baseline`, rather than by a counter of their own
+    pub fn with_baseline(
+        partition: usize,
+        metrics: &ExecutionPlanMetricsSet,
+        baseline: &BaselineMetrics,
+    ) -> Self {
+        Self::with_output_rows(partition, metrics, baseline.output_rows().clone())
+    }
+
+    fn with_output_rows(
+        partition: usize,
+        metrics: &ExecutionPlanMetricsSet,
+        output_rows: metrics::Count,
+    ) -> Self {
         let join_time = MetricBuilder::new(metrics).subset_time("join_time", partition);
 
+        let probe_hash_time =
+            MetricBuilder::new(metrics).subset_time("probe_hash_time", partition);
+
+        let match_time = MetricBuilder::new(metrics).subset_time("match_time", partition);
+
+        let filter_time =
+            MetricBuilder::new(metrics).subset_time("filter_time", partition);
+
+        let output_build_time =
+            MetricBuilder::new(metrics).subset_time("output_build_time", partition);
+
+        let filter_input_rows =
+            MetricBuilder::new(metrics).counter("filter_input_rows", partition);
+
+        let filter_output_rows =
+            MetricBuilder::new(metrics).counter("filter_output_rows", partition);
+
         let build_time = MetricBuilder::new(metrics).subset_time("build_time", partition);
 
         let build_input_batches =
@@ -1040,26 +2011,66 @@ impl BuildProbeJoinMetrics {
         let build_mem_used =
             MetricBuilder::new(metrics).gauge("build_mem_used", partition);
 
+        let build_hash_table_rehashes =
+            MetricBuilder::new(metrics).counter("build_hash_table_rehashes", partition);
+
+        let build_hash_buckets =
+            MetricBuilder::new(metrics).counter("build_hash_buckets", partition);
+
+        let build_hash_table_size =
+            MetricBuilder::new(metrics).gauge("build_hash_table_size", partition);
+
+        let build_longest_chain =
+            MetricBuilder::new(metrics).gauge("build_longest_chain", partition);
+
         let input_batches =
             MetricBuilder::new(metrics).counter("input_batches", partition);
 
         let input_rows = MetricBuilder::new(metrics).counter("input_rows", partition);
 
+        let probe_rows_skipped =
+            MetricBuilder::new(metrics).counter("probe_rows_skipped", partition);
+
+        let probe_candidates =
+            MetricBuilder::new(metrics).counter("probe_candidates", partition);
+
+        let probe_matches =
+            MetricBuilder::new(metrics).counter("probe_matches", partition);
+
         let output_batches =
             MetricBuilder::new(metrics).counter("output_batches", partition);
 
-        let output_rows = MetricBuilder::new(metrics).output_rows(partition);
+        let matched_output_rows =
+            MetricBuilder::new(metrics).counter("matched_output_rows", partition);
+
+        let unmatched_output_rows =
+            MetricBuilder::new(metrics).counter("unmatched_output_rows", partition);
 
         Self {
             build_time,
             build_input_batches,
             build_input_rows,
             build_mem_used,
+            build_hash_table_rehashes,
+            build_hash_buckets,
+            build_hash_table_size,
+            build_longest_chain,
             join_time,
+            probe_hash_time,
+            match_time,
+            filter_time,
+            filter_input_rows,
+            filter_output_rows,
+            output_build_time,
             input_batches,
             input_rows,
+            probe_rows_skipped,
+            probe_candidates,
+            probe_matches,
             output_batches,
             output_rows,
+            matched_output_rows,
+            unmatched_output_rows,
         }
     }
 }
@@ -1067,10 +2078,14 @@ impl BuildProbeJoinMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arrow::datatypes::Fields;
+    use crate::test::exec::StatisticsExec;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::TimeUnit;
+    use arrow::error::ArrowError;
     use arrow::error::Result as ArrowResult;
-    use arrow::{datatypes::DataType, error::ArrowError};
     use datafusion_common::ScalarValue;
+    use datafusion_physical_expr::expressions::Literal;
+    use std::collections::HashMap;
     use std::pin::Pin;
 
     fn check(left: &[Column], right: &[Column], on: &[(Column, Column)]) -> Result<()> {
@@ -1082,7 +2097,11 @@ mod tests {
             .iter()
             .map(|x| x.to_owned())
             .collect::<HashSet<Column>>();
-        check_join_set_is_valid(&left, &right, on)
+        let on = on
+            .iter()
+            .map(|(l, r)| (Arc::new(l.clone()) as _, Arc::new(r.clone()) as _))
+            .collect::<JoinOn>();
+        check_join_set_is_valid(&left, &right, &on)
     }
 
     #[test]
@@ -1104,6 +2123,60 @@ mod tests {
         assert!(check(&left, &right, on).is_err());
     }
 
+    #[test]
+    fn check_timestamp_time_zones() -> Result<()> {
+        let timestamp = |tz: Option<&str>| {
+            Schema::new(vec![Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Nanosecond, tz.map(Into::into)),
+                true,
+            )])
+        };
+        let on = &vec![(
+            Arc::new(Column::new("ts", 0)) as _,
+            Arc::new(Column::new("ts", 0)) as _,
+        )];
+
+        check_join_is_valid(&timestamp(Some("UTC")), &timestamp(Some("UTC")), on)?;
+        check_join_is_valid(&timestamp(None), &timestamp(None), on)?;
+
+        let err = check_join_is_valid(&timestamp(Some("UTC")), &timestamp(None), on)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Join key columns ts@0 and ts@0 have timestamps \
+            with different time zones: Timestamp(Nanosecond, Some(\"UTC\")) and \
+            Timestamp(Nanosecond, None)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn check_struct_fields() -> Result<()> {
+        let strukt = |names: &[&str]| {
+            let fields = names
+                .iter()
+                .map(|name| Field::new(*name, DataType::Int32, true))
+                .collect::<Fields>();
+            Schema::new(vec![Field::new("s", DataType::Struct(fields), true)])
+        };
+        let on = &vec![(
+            Arc::new(Column::new("s", 0)) as _,
+            Arc::new(Column::new("s", 0)) as _,
+        )];
+
+        check_join_is_valid(&strukt(&["a", "b"]), &strukt(&["a", "b"]), on)?;
+
+        let err = check_join_is_valid(&strukt(&["a", "b"]), &strukt(&["b", "a"]), on)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Join key columns s@0 and s@0 are structs with \
+            different fields: [\"a\", \"b\"] and [\"b\", \"a\"]"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn check_error_nesting() {
         let once_fut = OnceFut::<()>::new(async {
@@ -1338,18 +2411,23 @@ mod tests {
             //
             // No column level stats.
             ((10, None, None, None), (10, None, None, None), None),
-            // No min or max (or both).
-            ((10, None, None, Some(3)), (10, None, None, Some(3)), None),
+            // No min or max (or both), only the distinct counts.
+            (
+                (10, None, None, Some(3)),
+                (10, None, None, Some(3)),
+                Some(33),
+            ),
             (
                 (10, Some(2), None, Some(3)),
                 (10, None, Some(5), Some(3)),
-                None,
+                Some(33),
             ),
             (
                 (10, None, Some(3), Some(3)),
                 (10, Some(1), None, Some(3)),
-                None,
+                Some(33),
             ),
+            // No min or max, and no distinct count.
             ((10, None, Some(3), None), (10, Some(1), None, None), None),
             // Non overlapping min/max (when exact=False).
             (
@@ -1405,21 +2483,36 @@ mod tests {
 
             // We should also be able to use join_cardinality to get the same results
             let join_type = JoinType::Inner;
-            let join_on = vec![(Column::new("a", 0), Column::new("b", 0))];
+            let join_on = vec![(
+                Arc::new(Column::new("a", 0)) as _,
+                Arc::new(Column::new("b", 0)) as _,
+            )];
             let partial_join_stats = estimate_join_cardinality(
                 &join_type,
                 create_stats(Some(left_num_rows), Some(left_col_stats.clone()), false),
                 create_stats(Some(right_num_rows), Some(right_col_stats.clone()), false),
                 &join_on,
+                1.0,
             );
 
             assert_eq!(
                 partial_join_stats.clone().map(|s| s.num_rows),
                 expected_cardinality
             );
+            // the statistics of the join key columns are the intersection of
+            // the statistics of both keys
             assert_eq!(
-                partial_join_stats.map(|s| s.column_statistics),
-                expected_cardinality.map(|_| [left_col_stats, right_col_stats].concat())
+                partial_join_stats.and_then(|s| s.column_statistics),
+                expected_cardinality.map(|_| vec![
+                    intersect_join_key_statistics(
+                        &left_col_stats[0],
+                        &right_col_stats[0]
+                    ),
+                    intersect_join_key_statistics(
+                        &right_col_stats[0],
+                        &left_col_stats[0]
+                    ),
+                ])
             );
         }
         Ok(())
@@ -1437,8 +2530,24 @@ mod tests {
             create_column_stats(Some(100), Some(500), Some(200)),
         ];
 
-        // We have statistics about 4 columns, where the highest distinct
-        // count is 200, so we are going to pick it.
+        // We have the distinct counts of the 4 columns, whose products of
+        // 100 * 150 and 50 * 200 distinct keys are capped by the 400 rows of
+        // each side.
+        assert_eq!(
+            estimate_inner_join_cardinality(
+                400,
+                400,
+                left_col_stats.clone(),
+                right_col_stats.clone(),
+                false
+            ),
+            Some((400 * 400) / 400)
+        );
+
+        // Without the distinct count of a column, the highest distinct count
+        // of a single column, 200, is picked.
+        let mut left_col_stats = left_col_stats;
+        left_col_stats[1].distinct_count = None;
         assert_eq!(
             estimate_inner_join_cardinality(
                 400,
@@ -1452,6 +2561,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_join_cardinality_distinct_counts_only() -> Result<()> {
+        // distinct counts injected without min and max values, 10 * 20 distinct
+        // keys on the left and 10 * 5 on the right
+        let left_col_stats = vec![
+            create_column_stats(None, None, Some(10)),
+            create_column_stats(None, None, Some(20)),
+        ];
+        let right_col_stats = vec![
+            create_column_stats(None, None, Some(10)),
+            create_column_stats(None, None, Some(5)),
+        ];
+        let join_on = vec![
+            (
+                Arc::new(Column::new("a", 0)) as _,
+                Arc::new(Column::new("c", 0)) as _,
+            ),
+            (
+                Arc::new(Column::new("b", 1)) as _,
+                Arc::new(Column::new("d", 1)) as _,
+            ),
+        ];
+
+        let stats = estimate_join_cardinality(
+            &JoinType::Inner,
+            create_stats(Some(1000), Some(left_col_stats), false),
+            create_stats(Some(500), Some(right_col_stats.clone()), false),
+            &join_on,
+            1.0,
+        );
+        assert_eq!(stats.map(|s| s.num_rows), Some((1000 * 500) / 200));
+
+        // without the distinct count of a key, the missing bounds give up
+        let left_col_stats = vec![
+            create_column_stats(None, None, Some(10)),
+            create_column_stats(None, None, None),
+        ];
+        let stats = estimate_join_cardinality(
+            &JoinType::Inner,
+            create_stats(Some(1000), Some(left_col_stats), false),
+            create_stats(Some(500), Some(right_col_stats), false),
+            &join_on,
+            1.0,
+        );
+        assert_eq!(stats.map(|s| s.num_rows), None);
+        Ok(())
+    }
+
     #[test]
     fn test_inner_join_cardinality_decimal_range() -> Result<()> {
         let left_col_stats = vec![ColumnStatistics {
@@ -1481,6 +2638,90 @@ mod tests {
         Ok(())
     }
 
+    /// Statistics of the generated `columns`, with their exact min and max
+    /// values and distinct counts
+    fn generated_column_stats(columns: &[Vec<i64>]) -> Vec<ColumnStatistics> {
+        columns
+            .iter()
+            .map(|column| {
+                create_column_stats(
+                    column.iter().min().copied(),
+                    column.iter().max().copied(),
+                    Some(column.iter().collect::<HashSet<_>>().len()),
+                )
+            })
+            .collect()
+    }
+
+    /// The number of rows of the inner join of the generated `left` and
+    /// `right` columns on all of them
+    fn actual_join_cardinality(left: &[Vec<i64>], right: &[Vec<i64>]) -> usize {
+        let rows = |columns: &[Vec<i64>]| {
+            (0..columns[0].len())
+                .map(|row| columns.iter().map(|column| column[row]).collect::<Vec<_>>())
+                .fold(HashMap::new(), |mut counts, key| {
+                    *counts.entry(key).or_insert(0) += 1;
+                    counts
+                })
+        };
+        let right_rows = rows(right);
+        rows(left)
+            .iter()
+            .map(|(key, count)| count * right_rows.get(key).unwrap_or(&0))
+            .sum()
+    }
+
+    #[test]
+    fn test_inner_join_cardinality_generated() -> Result<()> {
+        let generate = |num_rows: i64, distinct: &[i64]| {
+            distinct
+                .iter()
+                .map(|distinct| (0..num_rows).map(|row| row % distinct).collect())
+                .collect::<Vec<Vec<i64>>>()
+        };
+        let cases = vec![
+            // a single key with 50 and 10 distinct values
+            (generate(1000, &[50]), generate(300, &[10])),
+            // two keys with 4 * 5 and 2 * 5 distinct combinations
+            (generate(1200, &[4, 5]), generate(600, &[2, 5])),
+            // three keys with 2 * 3 * 5 and 2 * 3 * 1 distinct combinations
+            (generate(900, &[2, 3, 5]), generate(300, &[2, 3, 1])),
+        ];
+
+        for (left, right) in cases {
+            let join_on = (0..left.len())
+                .map(|i| {
+                    (
+                        Arc::new(Column::new("l", i)) as _,
+                        Arc::new(Column::new("r", i)) as _,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let partial_join_stats = estimate_join_cardinality(
+                &JoinType::Inner,
+                create_stats(
+                    Some(left[0].len()),
+                    Some(generated_column_stats(&left)),
+                    false,
+                ),
+                create_stats(
+                    Some(right[0].len()),
+                    Some(generated_column_stats(&right)),
+                    false,
+                ),
+                &join_on,
+                1.0,
+            )
+            .unwrap();
+            assert_eq!(
+                partial_join_stats.num_rows,
+                actual_join_cardinality(&left, &right)
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_join_cardinality() -> Result<()> {
         // Left table (rows=1000)
@@ -1493,12 +2734,13 @@ mod tests {
         //   d: min=0, max=2000, distinct=2500 (how? some inexact statistics)
         //   y: min=0, max=100, distinct=None
         //
-        // Join on a=c, b=d (ignore x/y)
+        // Join on a=c, b=d (ignore x/y): the 100 * 500 and 50 * 2500 distinct
+        // keys are capped by the rows of each side
         let cases = vec![
-            (JoinType::Inner, 800),
+            (JoinType::Inner, 1000),
             (JoinType::Left, 1000),
             (JoinType::Right, 2000),
-            (JoinType::Full, 2200),
+            (JoinType::Full, 2000),
         ];
 
         let left_col_stats = vec![
@@ -1515,8 +2757,14 @@ mod tests {
 
         for (join_type, expected_num_rows) in cases {
             let join_on = vec![
-                (Column::new("a", 0), Column::new("c", 0)),
-                (Column::new("b", 1), Column::new("d", 1)),
+                (
+                    Arc::new(Column::new("a", 0)) as _,
+                    Arc::new(Column::new("c", 0)) as _,
+                ),
+                (
+                    Arc::new(Column::new("b", 1)) as _,
+                    Arc::new(Column::new("d", 1)) as _,
+                ),
             ];
 
             let partial_join_stats = estimate_join_cardinality(
@@ -1524,12 +2772,25 @@ mod tests {
                 create_stats(Some(1000), Some(left_col_stats.clone()), false),
                 create_stats(Some(2000), Some(right_col_stats.clone()), false),
                 &join_on,
+                1.0,
             )
             .unwrap();
             assert_eq!(partial_join_stats.num_rows, expected_num_rows);
+            // the ranges of the join keys of an inner join are intersected
+            let expected_col_stats = match join_type {
+                JoinType::Inner => vec![
+                    create_column_stats(Some(0), Some(100), Some(50)),
+                    create_column_stats(Some(0), Some(500), Some(500)),
+                    create_column_stats(Some(1000), Some(10000), None),
+                    create_column_stats(Some(0), Some(100), Some(50)),
+                    create_column_stats(Some(0), Some(500), Some(500)),
+                    create_column_stats(Some(0), Some(100), None),
+                ],
+                _ => [left_col_stats.clone(), right_col_stats.clone()].concat(),
+            };
             assert_eq!(
                 partial_join_stats.column_statistics,
-                [left_col_stats.clone(), right_col_stats.clone()].concat()
+                Some(expected_col_stats)
             );
         }
 
@@ -1563,8 +2824,14 @@ mod tests {
         ];
 
         let join_on = vec![
-            (Column::new("a", 0), Column::new("c", 0)),
-            (Column::new("x", 2), Column::new("y", 2)),
+            (
+                Arc::new(Column::new("a", 0)) as _,
+                Arc::new(Column::new("c", 0)) as _,
+            ),
+            (
+                Arc::new(Column::new("x", 2)) as _,
+                Arc::new(Column::new("y", 2)) as _,
+            ),
         ];
 
         let cases = vec![
@@ -1589,15 +2856,747 @@ mod tests {
                 create_stats(Some(1000), Some(left_col_stats.clone()), true),
                 create_stats(Some(2000), Some(right_col_stats.clone()), true),
                 &join_on,
+                1.0,
             )
             .unwrap();
             assert_eq!(partial_join_stats.num_rows, expected_num_rows);
+            // the ranges of the disjoint join keys of an inner join are unknown
+            let expected_col_stats = match join_type {
+                JoinType::Inner => vec![
+                    create_column_stats(Some(0), Some(100), Some(50)),
+                    create_column_stats(Some(0), Some(500), Some(500)),
+                    create_column_stats(None, None, None),
+                    create_column_stats(Some(0), Some(100), Some(50)),
+                    create_column_stats(Some(0), Some(2000), Some(2500)),
+                    create_column_stats(None, None, None),
+                ],
+                _ => [left_col_stats.clone(), right_col_stats.clone()].concat(),
+            };
             assert_eq!(
                 partial_join_stats.column_statistics,
-                [left_col_stats.clone(), right_col_stats.clone()].concat()
+                Some(expected_col_stats)
             );
         }
 
         Ok(())
     }
+
+    #[test]
+    fn test_join_cardinality_with_filter() -> Result<()> {
+        // Left table (rows=1000)
+        //   a: min=0, max=100, distinct=100
+        //   x: min=1, max=100, distinct=None
+        //
+        // Right table (rows=2000)
+        //   c: min=0, max=100, distinct=50
+        //   y: min=0, max=100, distinct=None
+        //
+        // Join on a=c, 20000 rows without a filter
+        let left_stats = create_stats(
+            Some(1000),
+            Some(vec![
+                create_column_stats(Some(0), Some(100), Some(100)),
+                create_column_stats(Some(1), Some(100), None),
+            ]),
+            false,
+        );
+        let right_stats = create_stats(
+            Some(2000),
+            Some(vec![
+                create_column_stats(Some(0), Some(100), Some(50)),
+                create_column_stats(Some(0), Some(100), None),
+            ]),
+            false,
+        );
+        let join_on = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+        let filter = |expression| {
+            JoinFilter::new(
+                expression,
+                vec![
+                    ColumnIndex {
+                        index: 1,
+                        side: JoinSide::Left,
+                    },
+                    ColumnIndex {
+                        index: 1,
+                        side: JoinSide::Right,
+                    },
+                ],
+                Schema::new(vec![
+                    Field::new("x", DataType::Int64, true),
+                    Field::new("y", DataType::Int64, true),
+                ]),
+            )
+        };
+        let x = Arc::new(Column::new("x", 0)) as Arc<dyn PhysicalExpr>;
+        let y = Arc::new(Column::new("y", 1)) as Arc<dyn PhysicalExpr>;
+        let x_gt_75 = Arc::new(BinaryExpr::new(
+            x.clone(),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int64(Some(75)))),
+        )) as Arc<dyn PhysicalExpr>;
+        let x_gt_y = Arc::new(BinaryExpr::new(x, Operator::Gt, y)) as _;
+
+        let cases = vec![
+            // the filter `x > 75` keeps a quarter of the range of `x`
+            (x_gt_75.clone(), 0.25),
+            // nothing is known about the filter `x > y`
+            (x_gt_y.clone(), 0.2),
+            // the conjuncts are assumed to be independent
+            (
+                Arc::new(BinaryExpr::new(x_gt_75, Operator::And, x_gt_y)) as _,
+                0.05,
+            ),
+            (
+                Arc::new(Literal::new(ScalarValue::Boolean(Some(true)))) as _,
+                1.0,
+            ),
+            (
+                Arc::new(Literal::new(ScalarValue::Boolean(Some(false)))) as _,
+                0.0,
+            ),
+        ];
+        for (expression, expected_selectivity) in cases {
+            let selectivity = join_filter_selectivity(
+                &filter(expression),
+                &left_stats,
+                &right_stats,
+                0.2,
+            );
+            assert!((selectivity - expected_selectivity).abs() < 1e-9);
+        }
+
+        let cardinality = |join_type, filter_selectivity| {
+            estimate_join_cardinality(
+                &join_type,
+                left_stats.clone(),
+                right_stats.clone(),
+                &join_on,
+                filter_selectivity,
+            )
+            .unwrap()
+            .num_rows
+        };
+        let cases = vec![
+            (JoinType::Inner, 20000, 5000),
+            // the filter does not remove the rows of the preserved sides
+            (JoinType::Left, 20000, 5000),
+            (JoinType::Full, 20000, 5000),
+        ];
+        for (join_type, without_filter, with_filter) in cases {
+            assert_eq!(cardinality(join_type, 1.0), without_filter);
+            assert_eq!(cardinality(join_type, 0.25), with_filter);
+        }
+        assert_eq!(cardinality(JoinType::Inner, 0.0), 0);
+        assert_eq!(cardinality(JoinType::Left, 0.0), 1000);
+        assert_eq!(cardinality(JoinType::Full, 0.0), 3000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_statistics_with_filter_selectivity() -> Result<()> {
+        // join on a=c, 20000 rows without a filter, see
+        // test_join_cardinality_with_filter
+        let side = |num_rows, key_distinct, key, column| {
+            Arc::new(StatisticsExec::new(
+                create_stats(
+                    Some(num_rows),
+                    Some(vec![
+                        create_column_stats(Some(0), Some(100), Some(key_distinct)),
+                        create_column_stats(Some(0), Some(100), None),
+                    ]),
+                    false,
+                ),
+                Schema::new(vec![
+                    Field::new(key, DataType::Int64, true),
+                    Field::new(column, DataType::Int64, true),
+                ]),
+            )) as Arc<dyn ExecutionPlan>
+        };
+        let left = side(1000, 100, "a", "x");
+        let right = side(2000, 50, "c", "y");
+        let join_on: JoinOn = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+        // nothing is known about the filter `x > y`
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("x", 0)),
+                Operator::Gt,
+                Arc::new(Column::new("y", 1)),
+            )),
+            JoinFilter::build_column_indices(vec![1], vec![1]),
+            Schema::new(vec![
+                Field::new("x", DataType::Int64, true),
+                Field::new("y", DataType::Int64, true),
+            ]),
+        );
+        let num_rows = |filter: &JoinFilter| {
+            estimate_join_statistics(
+                left.clone(),
+                right.clone(),
+                join_on.clone(),
+                &JoinType::Inner,
+                Some(filter),
+                0.2,
+            )
+            .num_rows
+        };
+
+        assert_eq!(num_rows(&filter), Some(4000));
+        let filter = filter.with_selectivity(0.1)?;
+        assert_eq!(filter.selectivity(), Some(0.1));
+        assert_eq!(num_rows(&filter), Some(2000));
+        // the selectivity is kept when swapping the sides of the filter
+        let swapped = swap_join_filter(Some(&filter)).unwrap();
+        assert_eq!(swapped.selectivity(), Some(0.1));
+        let filter = filter.with_selectivity(0.0)?;
+        assert_eq!(num_rows(&filter), Some(0));
+
+        for selectivity in [-0.1, 1.5, f64::NAN, f64::INFINITY] {
+            let err = filter.clone().with_selectivity(selectivity).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("The selectivity of a join filter must be between 0 and 1"),
+                "{err}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_column_statistics() {
+        let column_stats = |min, max, distinct, null_count| ColumnStatistics {
+            null_count,
+            ..create_column_stats(min, max, distinct)
+        };
+        // left: a (key), b
+        let left = vec![
+            column_stats(Some(0), Some(100), Some(100), Some(5)),
+            column_stats(Some(-10), Some(10), None, Some(3)),
+        ];
+        // right: c (key), d
+        let right = vec![
+            column_stats(Some(50), Some(200), Some(80), Some(2)),
+            column_stats(None, None, None, Some(7)),
+        ];
+        let on = vec![(
+            Arc::new(Column::new("a", 0)) as _,
+            Arc::new(Column::new("c", 0)) as _,
+        )];
+
+        // the join keys are within [50, 100], with at most 80 distinct values,
+        // and the null counts of the other columns are kept
+        assert_eq!(
+            join_column_statistics(&JoinType::Inner, &on, left.clone(), right.clone()),
+            vec![
+                column_stats(Some(50), Some(100), Some(80), None),
+                left[1].clone(),
+                column_stats(Some(50), Some(100), Some(80), None),
+                right[1].clone(),
+            ]
+        );
+
+        // the null counts of the sides padded with nulls are unknown
+        let without_null_count = |stats: &[ColumnStatistics]| {
+            stats
+                .iter()
+                .map(|stats| ColumnStatistics {
+                    null_count: None,
+                    ..stats.clone()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            join_column_statistics(&JoinType::Left, &on, left.clone(), right.clone()),
+            [left.clone(), without_null_count(&right)].concat()
+        );
+        assert_eq!(
+            join_column_statistics(&JoinType::Right, &on, left.clone(), right.clone()),
+            [without_null_count(&left), right.clone()].concat()
+        );
+        assert_eq!(
+            join_column_statistics(&JoinType::Full, &on, left.clone(), right.clone()),
+            [without_null_count(&left), without_null_count(&right)].concat()
+        );
+
+        // the bounds known on one side only narrow that side, and the bounds of
+        // keys of different types are not comparable
+        let key = column_stats(Some(0), None, None, None);
+        let other = ColumnStatistics {
+            min_value: Some(ScalarValue::Int32(Some(10))),
+            max_value: Some(ScalarValue::Int32(Some(20))),
+            ..Default::default()
+        };
+        assert_eq!(intersect_join_key_statistics(&key, &other), key);
+        assert_eq!(
+            intersect_join_key_statistics(&other, &key),
+            ColumnStatistics {
+                null_count: None,
+                ..other
+            }
+        );
+    }
+
+    #[test]
+    fn test_semi_anti_join_cardinality() -> Result<()> {
+        // Left table (rows=1000, bytes=8000)
+        //   a: min=0, max=1000, distinct=100
+        //   b: min=None, max=None, distinct=None
+        //
+        // Right table (rows=2000, bytes=32000)
+        //   c: min=0, max=1000, distinct=50
+        //   d: min=None, max=None, distinct=None
+        let left_stats = Statistics {
+            total_byte_size: Some(8000),
+            ..create_stats(
+                Some(1000),
+                Some(vec![
+                    create_column_stats(Some(0), Some(1000), Some(100)),
+                    create_column_stats(None, None, None),
+                ]),
+                false,
+            )
+        };
+        let right_stats = Statistics {
+            total_byte_size: Some(32000),
+            ..create_stats(
+                Some(2000),
+                Some(vec![
+                    create_column_stats(Some(0), Some(1000), Some(50)),
+                    create_column_stats(None, None, None),
+                ]),
+                false,
+            )
+        };
+        let on = |left, right| {
+            vec![(
+                Arc::new(Column::new("left", left)) as _,
+                Arc::new(Column::new("right", right)) as _,
+            )]
+        };
+
+        let cases = vec![
+            // Join on a=c: the 50 distinct values of `c` are assumed to be
+            // among the 100 of `a`, so half of the left rows have a match
+            (JoinType::LeftSemi, on(0, 0), 1.0, 500, 4000),
+            (JoinType::LeftAnti, on(0, 0), 1.0, 500, 4000),
+            // all the right rows have a match
+            (JoinType::RightSemi, on(0, 0), 1.0, 2000, 32000),
+            (JoinType::RightAnti, on(0, 0), 1.0, 0, 0),
+            // the join filter removes some of the matches
+            (JoinType::LeftSemi, on(0, 0), 0.2, 100, 800),
+            (JoinType::LeftAnti, on(0, 0), 0.2, 900, 7200),
+            // Join on b=d: without distinct counts, every row of the
+            // preserved side may or may not have a match
+            (JoinType::LeftSemi, on(1, 1), 1.0, 1000, 8000),
+            (JoinType::LeftAnti, on(1, 1), 1.0, 1000, 8000),
+            (JoinType::RightSemi, on(1, 1), 1.0, 2000, 32000),
+            (JoinType::RightAnti, on(1, 1), 1.0, 2000, 32000),
+        ];
+        for (join_type, join_on, filter_selectivity, expected_rows, expected_bytes) in
+            cases
+        {
+            let partial_join_stats = estimate_join_cardinality(
+                &join_type,
+                left_stats.clone(),
+                right_stats.clone(),
+                &join_on,
+                filter_selectivity,
+            )
+            .unwrap();
+            assert_eq!(partial_join_stats.num_rows, expected_rows);
+            assert_eq!(partial_join_stats.total_byte_size, Some(expected_bytes));
+
+            // the estimate never exceeds the rows of the preserved side, whose
+            // columns are the only ones produced
+            let preserved_stats = match join_type {
+                JoinType::LeftSemi | JoinType::LeftAnti => &left_stats,
+                _ => &right_stats,
+            };
+            assert!(partial_join_stats.num_rows <= preserved_stats.num_rows.unwrap());
+            assert_eq!(
+                partial_join_stats.column_statistics,
+                preserved_stats.column_statistics
+            );
+        }
+
+        // the cardinality is unknown without the rows of the preserved side
+        let partial_join_stats = estimate_join_cardinality(
+            &JoinType::LeftSemi,
+            create_stats(None, None, false),
+            right_stats,
+            &on(0, 0),
+            1.0,
+        );
+        assert!(partial_join_stats.is_none());
+
+        Ok(())
+    }
+
+    /// Applies the join filter `l > 1` on the build side column `l`, holding the
+    /// values `0..4`, to the crafted indices and adjusts them for `join_type`,
+    /// returning the sorted `(build index, probe index)` pairs
+    fn filter_and_adjust_indices(
+        build_indices: Vec<u64>,
+        probe_indices: Vec<u32>,
+        probe_rows: usize,
+        join_type: JoinType,
+    ) -> Result<Vec<(Option<u64>, Option<u32>)>> {
+        let build_batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("l", DataType::Int32, false)])),
+            vec![Arc::new(Int32Array::from(vec![0, 1, 2, 3]))],
+        )?;
+        let probe_batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("r", DataType::Int32, false)])),
+            vec![Arc::new(Int32Array::from_iter_values(0..probe_rows as i32))],
+        )?;
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("l", 0)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(1)))),
+            )),
+            vec![ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            }],
+            Schema::new(vec![Field::new("l", DataType::Int32, false)]),
+        );
+
+        let (build_indices, probe_indices) = apply_join_filter_to_indices(
+            std::slice::from_ref(&build_batch),
+            &probe_batch,
+            UInt64Array::from(build_indices),
+            UInt32Array::from(probe_indices),
+            &filter,
+            JoinSide::Left,
+        )?;
+        let (build_indices, probe_indices) = adjust_indices_by_join_type(
+            build_indices,
+            probe_indices,
+            probe_rows,
+            join_type,
+        );
+
+        let mut pairs = build_indices
+            .iter()
+            .zip(probe_indices.iter())
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        Ok(pairs)
+    }
+
+    #[test]
+    fn right_and_full_join_filtered_indices() -> Result<()> {
+        for join_type in [JoinType::Right, JoinType::Full] {
+            // single row, passing the filter
+            let pairs = filter_and_adjust_indices(vec![2], vec![0], 1, join_type)?;
+            assert_eq!(pairs, vec![(Some(2), Some(0))]);
+
+            // single row, filtered out
+            let pairs = filter_and_adjust_indices(vec![1], vec![0], 1, join_type)?;
+            assert_eq!(pairs, vec![(None, Some(0))]);
+
+            // all rows filtered out
+            let pairs =
+                filter_and_adjust_indices(vec![0, 1, 0], vec![0, 1, 2], 3, join_type)?;
+            assert_eq!(
+                pairs,
+                vec![(None, Some(0)), (None, Some(1)), (None, Some(2))]
+            );
+
+            // probe row 1 appears in separate runs without any match passing the
+            // filter, and probe row 3 has no candidate matches at all
+            let pairs = filter_and_adjust_indices(
+                vec![0, 2, 1, 3, 0],
+                vec![1, 0, 1, 2, 1],
+                4,
+                join_type,
+            )?;
+            assert_eq!(
+                pairs,
+                vec![
+                    (None, Some(1)),
+                    (None, Some(3)),
+                    (Some(2), Some(0)),
+                    (Some(3), Some(2)),
+                ]
+            );
+
+            // probe row 1 appears in separate runs with several matches passing
+            // the filter
+            let pairs =
+                filter_and_adjust_indices(vec![2, 0, 3], vec![1, 0, 1], 2, join_type)?;
+            assert_eq!(
+                pairs,
+                vec![(None, Some(0)), (Some(2), Some(1)), (Some(3), Some(1))]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_filter_equivalence_properties() {
+        // left (a, c, x) join right (b, d), filter a = b AND c > d
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("c", DataType::Int32, false),
+            Field::new("x", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+            Field::new("d", DataType::Int32, false),
+        ]));
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(BinaryExpr::new(
+                    Arc::new(Column::new("a", 0)),
+                    Operator::Eq,
+                    Arc::new(Column::new("b", 1)),
+                )),
+                Operator::And,
+                Arc::new(BinaryExpr::new(
+                    Arc::new(Column::new("c", 2)),
+                    Operator::Gt,
+                    Arc::new(Column::new("d", 3)),
+                )),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Right,
+                },
+                ColumnIndex {
+                    index: 1,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 1,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("a", DataType::Int32, false),
+                Field::new("b", DataType::Int32, false),
+                Field::new("c", DataType::Int32, false),
+                Field::new("d", DataType::Int32, false),
+            ]),
+        );
+
+        let mut properties = EquivalenceProperties::new(schema.clone());
+        add_join_filter_equivalence_properties(
+            &mut properties,
+            JoinType::Inner,
+            Some(&filter),
+            3,
+            &schema,
+        );
+        let classes = properties.classes();
+        assert_eq!(classes.len(), 1);
+        assert!(classes[0].contains(&Column::new("a", 0)));
+        assert!(classes[0].contains(&Column::new("b", 3)));
+        assert_eq!(classes[0].iter().count(), 2);
+
+        // the rows of outer joins which do not pass the filter are produced too
+        for join_type in [JoinType::Left, JoinType::Right, JoinType::Full] {
+            let mut properties = EquivalenceProperties::new(schema.clone());
+            add_join_filter_equivalence_properties(
+                &mut properties,
+                join_type,
+                Some(&filter),
+                3,
+                &schema,
+            );
+            assert!(properties.classes().is_empty());
+        }
+    }
+
+    #[test]
+    fn merge_unmatched_right_indices() {
+        // probe rows 1 and 3 match build rows, probe rows 0, 2 and 4 do not
+        let (left, right) = merge_right_indices(
+            UInt64Array::from(vec![5, 6, 7]),
+            UInt32Array::from(vec![1, 1, 3]),
+            UInt32Array::from(vec![0, 2, 4]),
+        );
+        assert_eq!(
+            left,
+            UInt64Array::from(vec![None, Some(5), Some(6), None, Some(7), None])
+        );
+        assert_eq!(right, UInt32Array::from(vec![0, 1, 1, 2, 3, 4]));
+
+        // no unmatched probe row
+        let (left, right) = merge_right_indices(
+            UInt64Array::from(vec![5]),
+            UInt32Array::from(vec![0]),
+            UInt32Array::from(Vec::<u32>::new()),
+        );
+        assert_eq!(left, UInt64Array::from(vec![5]));
+        assert_eq!(right, UInt32Array::from(vec![0]));
+    }
+
+    #[test]
+    fn take_from_several_batches() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("l", DataType::Int32, true)]));
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+        };
+        let batches = vec![batch(vec![0, 1])?, batch(vec![])?, batch(vec![2, 3, 4])?];
+
+        let offsets = batch_offsets(&batches);
+        assert_eq!(offsets, vec![0, 2, 2, 5]);
+        assert_eq!(locate_row(&offsets, 0), (0, 0));
+        assert_eq!(locate_row(&offsets, 1), (0, 1));
+        assert_eq!(locate_row(&offsets, 2), (2, 0));
+        assert_eq!(locate_row(&offsets, 4), (2, 2));
+
+        let indices = UInt64Array::from(vec![Some(4), None, Some(0), Some(2), Some(1)]);
+        let taken = take_from_batches(&batches, 0, &DataType::Int32, &indices)?;
+        assert_eq!(
+            taken.as_ref(),
+            &Int32Array::from(vec![Some(4), None, Some(0), Some(2), Some(1)])
+                as &dyn Array
+        );
+
+        // only null indices can be taken from no batches
+        let indices = UInt64Array::from(vec![None, None]);
+        let taken = take_from_batches(&[], 0, &DataType::Int32, &indices)?;
+        assert_eq!(
+            taken.as_ref(),
+            &Int32Array::from(vec![None, None]) as &dyn Array
+        );
+
+        let indices = UInt64Array::from(vec![5]);
+        let err = take_from_batches(&batches, 0, &DataType::Int32, &indices).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Row 5 is out of bounds of the 5 rows of the batches"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn take_from_probe_column() -> Result<()> {
+        let array = Arc::new(Int32Array::from(vec![0, 1, 2])) as ArrayRef;
+        let taken =
+            take_probe_column(&array, &UInt32Array::from(vec![Some(2), None, Some(0)]))?;
+        assert_eq!(
+            taken.as_ref(),
+            &Int32Array::from(vec![Some(2), None, Some(0)]) as &dyn Array
+        );
+
+        // only null indices can be taken from an empty column
+        let empty = Arc::new(Int32Array::from(Vec::<i32>::new())) as ArrayRef;
+        let taken = take_probe_column(&empty, &UInt32Array::from(vec![None, None]))?;
+        assert_eq!(
+            taken.as_ref(),
+            &Int32Array::from(vec![None, None]) as &dyn Array
+        );
+
+        let err = take_probe_column(&empty, &UInt32Array::from(vec![Some(0), None]))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Cannot take 1 probe side rows from an empty column"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn u64_probe_indices() -> Result<()> {
+        let build_batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("l", DataType::Int32, false)])),
+            vec![Arc::new(Int32Array::from(vec![0, 1, 2, 3]))],
+        )?;
+        let probe_batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("r", DataType::Int32, false)])),
+            vec![Arc::new(Int32Array::from(vec![10, 11, 12]))],
+        )?;
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("l", 0)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(1)))),
+            )),
+            vec![ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            }],
+            Schema::new(vec![Field::new("l", DataType::Int32, false)]),
+        );
+        let output_schema = Schema::new(vec![
+            Field::new("l", DataType::Int32, true),
+            Field::new("r", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+
+        // probe rows 0 and 2 match build rows 1 and 3, only the second pair
+        // passes the filter
+        let (build_indices, probe_indices) = apply_join_filter_to_indices(
+            std::slice::from_ref(&build_batch),
+            &probe_batch,
+            UInt64Array::from(vec![1, 3]),
+            UInt64Array::from(vec![0, 2]),
+            &filter,
+            JoinSide::Left,
+        )?;
+        assert_eq!(probe_indices, UInt64Array::from(vec![2]));
+
+        let (build_indices, probe_indices) = adjust_indices_by_join_type(
+            build_indices,
+            probe_indices,
+            probe_batch.num_rows(),
+            JoinType::Full,
+        );
+        assert_eq!(
+            probe_indices,
+            UInt64Array::from(vec![Some(2), Some(0), Some(1)])
+        );
+
+        let batch = build_batch_from_indices(
+            &output_schema,
+            std::slice::from_ref(&build_batch),
+            &probe_batch,
+            build_indices,
+            probe_indices,
+            &column_indices,
+            JoinSide::Left,
+        )?;
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Int32Array::from(vec![Some(3), None, None]) as &dyn Array
+        );
+        assert_eq!(
+            batch.column(1).as_ref(),
+            &Int32Array::from(vec![Some(12), Some(10), Some(11)]) as &dyn Array
+        );
+
+        // the unmatched and matched probe rows are the same with u32 indices
+        let matched = UInt64Array::from(vec![Some(2), None, Some(0), Some(2)]);
+        assert_eq!(get_anti_indices(4, &matched), UInt64Array::from(vec![1, 3]));
+        assert_eq!(get_semi_indices(4, &matched), UInt64Array::from(vec![0, 2]));
+        let matched = UInt32Array::from(vec![Some(2), None, Some(0), Some(2)]);
+        assert_eq!(get_anti_indices(4, &matched), UInt32Array::from(vec![1, 3]));
+        assert_eq!(get_semi_indices(4, &matched), UInt32Array::from(vec![0, 2]));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,234 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Metrics of all the operators of an [`ExecutionPlan`]
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{Metric, MetricValue, MetricsSet};
+use crate::physical_plan::display::operator_name;
+use crate::physical_plan::ExecutionPlan;
+
+/// Handle on an executed (or executing) [`ExecutionPlan`], giving access to
+/// the metrics of all of its operators.
+///
+/// Every call to [`PlanMetrics::operators`] takes a new snapshot of the
+/// metrics, so it can be used to follow the progress of a query while it is
+/// running as well as to report the final metrics once it completed. The
+/// progress between two snapshots is given by [`PlanMetrics::diff`].
+///
+/// The plan executed last through a [`DataFrame`] is also retained by its
+/// session, see [`SessionContext::last_execution_metrics`].
+///
+/// [`DataFrame`]: crate::dataframe::DataFrame
+/// [`SessionContext::last_execution_metrics`]: crate::execution::context::SessionContext::last_execution_metrics
+///
+/// ```
+/// # use datafusion::prelude::*;
+/// # use datafusion::error::Result;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let ctx = SessionContext::new();
+/// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+/// let (batches, metrics) = df.collect_with_metrics().await?;
+/// for operator in metrics.operators() {
+///     println!("{operator}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlanMetrics {
+    plan: Arc<dyn ExecutionPlan>,
+}
+
+impl PlanMetrics {
+    /// Create a new [`PlanMetrics`] reporting the metrics of `plan`
+    pub fn new(plan: Arc<dyn ExecutionPlan>) -> Self {
+        Self { plan }
+    }
+
+    /// The plan whose metrics are reported
+    pub fn plan(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.plan
+    }
+
+    /// Returns a snapshot of the metrics of every operator of the plan, in
+    /// depth first order (parents before their children)
+    pub fn operators(&self) -> Vec<OperatorMetrics> {
+        let mut operators = vec![];
+        collect_operators(&self.plan, "0".to_string(), &mut operators);
+        operators
+    }
+
+    /// Returns a snapshot of the metrics of the operator at `path`, as
+    /// described in [`OperatorMetrics::path`]
+    pub fn operator(&self, path: &str) -> Option<OperatorMetrics> {
+        self.operators()
+            .into_iter()
+            .find(|operator| operator.path == path)
+    }
+
+    /// Returns the metrics of every operator of the plan accumulated since
+    /// `earlier`, a previous snapshot returned by [`PlanMetrics::operators`].
+    ///
+    /// See [`OperatorMetrics::diff`] for how each metric is compared.
+    pub fn diff(&self, earlier: &[OperatorMetrics]) -> Vec<OperatorMetrics> {
+        self.operators()
+            .into_iter()
+            .map(|operator| {
+                match earlier.iter().find(|before| before.path == operator.path) {
+                    Some(before) => operator.diff(before),
+                    None => operator,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for PlanMetrics {
+    /// Format the metrics of every operator, one operator per line
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for operator in self.operators() {
+            writeln!(f, "{operator}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot of the metrics of a single operator of a [`PlanMetrics`]
+#[derive(Debug, Clone)]
+pub struct OperatorMetrics {
+    /// Position of the operator in the plan: the index of each child taken
+    /// from the root, separated by `.`. The root is `0`, its first child
+    /// `0.0`, the second child of this one `0.0.1` and so on.
+    pub path: String,
+    /// Name of the operator, e.g. `HashJoinExec`
+    pub name: String,
+    /// Metrics of the operator, `None` if it does not report any metrics.
+    /// The values are copied when the snapshot is taken, they do not change
+    /// while the operator keeps running.
+    pub metrics: Option<MetricsSet>,
+}
+
+impl OperatorMetrics {
+    /// Returns the metrics of this operator accumulated since `earlier`, a
+    /// previous snapshot of the same operator, aggregated by name.
+    ///
+    /// Counts and times are the difference between both snapshots, while
+    /// gauges and timestamps, which are not cumulative, keep their value
+    /// in `self`.
+    pub fn diff(&self, earlier: &OperatorMetrics) -> OperatorMetrics {
+        let metrics = self.metrics.as_ref().map(|metrics| {
+            let earlier = earlier.metrics.as_ref().map(|m| m.aggregate_by_name());
+            let mut diff = MetricsSet::new();
+            for metric in metrics.aggregate_by_name().iter() {
+                let before = earlier.as_ref().and_then(|earlier| {
+                    earlier
+                        .iter()
+                        .find(|m| m.value().name() == metric.value().name())
+                });
+                let value = value_since(metric.value(), before.map(|m| m.value()));
+                diff.push(Arc::new(Metric::new(value, None)));
+            }
+            diff
+        });
+        OperatorMetrics {
+            path: self.path.clone(),
+            name: self.name.clone(),
+            metrics,
+        }
+    }
+}
+
+impl Display for OperatorMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.path, self.name)?;
+        if let Some(metrics) = &self.metrics {
+            let metrics = metrics
+                .aggregate_by_name()
+                .sorted_for_display()
+                .timestamps_removed();
+            write!(f, ": {metrics}")?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_operators(
+    plan: &Arc<dyn ExecutionPlan>,
+    path: String,
+    operators: &mut Vec<OperatorMetrics>,
+) {
+    let metrics = plan.metrics().map(|metrics| {
+        let mut snapshot = MetricsSet::new();
+        for metric in metrics.iter() {
+            snapshot.push(Arc::new(Metric::new_with_labels(
+                copy_value(metric.value()),
+                metric.partition(),
+                metric.labels().to_vec(),
+            )));
+        }
+        snapshot
+    });
+    operators.push(OperatorMetrics {
+        path: path.clone(),
+        name: operator_name(plan.as_ref()),
+        metrics,
+    });
+    for (idx, child) in plan.children().iter().enumerate() {
+        collect_operators(child, format!("{path}.{idx}"), operators);
+    }
+}
+
+/// Copies the current value of `value`, which shares its counter with the
+/// operator, into a new [`MetricValue`]
+fn copy_value(value: &MetricValue) -> MetricValue {
+    let mut copy = value.new_empty();
+    // aggregating an empty time would record 1ns
+    if value.is_timestamp() || value.as_usize() > 0 {
+        copy.aggregate(value);
+    }
+    copy
+}
+
+/// Returns what was accumulated in `later` since `earlier`
+fn value_since(later: &MetricValue, earlier: Option<&MetricValue>) -> MetricValue {
+    let delta = later
+        .as_usize()
+        .saturating_sub(earlier.map(|v| v.as_usize()).unwrap_or_default());
+    let value = later.new_empty();
+    match &value {
+        MetricValue::OutputRows(count)
+        | MetricValue::SpillCount(count)
+        | MetricValue::SpilledBytes(count)
+        | MetricValue::SpilledRows(count)
+        | MetricValue::Count { count, .. } => count.add(delta),
+        MetricValue::ElapsedCompute(time) | MetricValue::Time { time, .. } => {
+            if delta > 0 {
+                time.add_duration(Duration::from_nanos(delta as u64))
+            }
+        }
+        MetricValue::CurrentMemoryUsage(_)
+        | MetricValue::Gauge { .. }
+        | MetricValue::StartTimestamp(_)
+        | MetricValue::EndTimestamp(_) => return copy_value(later),
+    }
+    value
+}
@@ -194,6 +194,14 @@ pub trait ExecutionPlan: Debug + Send + Sync {
         OrderingEquivalenceProperties::new(self.schema())
     }
 
+    /// Get the sets of output columns, by index, whose values are unique in the
+    /// output of the plan: no two output rows, in the same or different output
+    /// partitions, have the same values in all the columns of a set, counting
+    /// nulls as equal. By default, no columns are known to be unique.
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        vec![]
+    }
+
     /// Get a list of child execution plans that provide the input for this plan. The returned list
     /// will be empty for leaf nodes, will contain a single value for unary nodes, or two
     /// values for binary nodes (such as joins).
@@ -227,6 +235,16 @@ pub trait ExecutionPlan: Debug + Send + Sync {
         None
     }
 
+    /// Return a summary of the execution of this [`ExecutionPlan`],
+    /// shown under it by `EXPLAIN ANALYZE` in addition to its
+    /// [`MetricsSet`], or `None` if it has nothing to summarize.
+    ///
+    /// As for [`Self::metrics`], the summary is complete once all
+    /// available partitions have been executed.
+    fn metrics_summary(&self) -> Option<String> {
+        None
+    }
+
     /// Format this `ExecutionPlan` to `f` in the specified type.
     ///
     /// Should not include a newline
@@ -682,6 +700,8 @@ pub mod empty;
 pub mod explain;
 pub mod filter;
 pub mod insert;
+#[cfg(feature = "tracing")]
+pub mod instrumented;
 pub mod joins;
 pub mod limit;
 pub mod memory;
@@ -39,7 +39,9 @@ use datafusion_common::display::ToStringifiedPlan;
 use crate::logical_expr::{Limit, Values};
 use crate::physical_expr::create_physical_expr;
 use crate::physical_optimizer::optimizer::PhysicalOptimizerRule;
-use crate::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
+use crate::physical_plan::aggregates::{
+    AggregateExec, AggregateMode, PhysicalGroupBy, INTERNAL_GROUPING_ID,
+};
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions::{Column, PhysicalSortExpr};
 use crate::physical_plan::filter::FilterExec;
@@ -54,13 +56,15 @@ use crate::physical_plan::windows::{
     BoundedWindowAggExec, PartitionSearchMode, WindowAggExec,
 };
 use crate::physical_plan::{joins::utils as join_utils, Partitioning};
-use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr, WindowExpr};
+use crate::physical_plan::{
+    AggregateExpr, DisplayFormatType, ExecutionPlan, PhysicalExpr, WindowExpr,
+};
 use crate::{
     error::{DataFusionError, Result},
     physical_plan::displayable,
 };
 use arrow::compute::SortOptions;
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 use async_trait::async_trait;
 use datafusion_common::{DFSchema, ScalarValue};
 use datafusion_expr::expr::{
@@ -68,11 +72,10 @@ use datafusion_expr::expr::{
     GroupingSet, InList, Like, ScalarUDF, TryCast, WindowFunction,
 };
 use datafusion_expr::expr_rewriter::unnormalize_cols;
-use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
-use datafusion_expr::{logical_plan, DmlStatement, StringifiedPlan, WriteOp};
+use datafusion_expr::{DmlStatement, Operator, StringifiedPlan, WriteOp};
 use datafusion_expr::{WindowFrame, WindowFrameBound};
 use datafusion_optimizer::utils::unalias;
-use datafusion_physical_expr::expressions::Literal;
+use datafusion_physical_expr::expressions::{binary, cast, Grouping, Literal};
 use datafusion_sql::utils::window_expr_common_partition_keys;
 use futures::future::BoxFuture;
 use futures::{FutureExt, StreamExt, TryStreamExt};
@@ -717,7 +720,17 @@ impl DefaultPhysicalPlanner {
                         })
                         .collect::<Result<Vec<_>>>()?;
 
-                    let (aggregates, filters, order_bys) : (Vec<_>, Vec<_>, Vec<_>) = multiunzip(agg_filter.into_iter());
+                    // GROUPING() is not evaluated by the aggregation, but computed
+                    // from the grouping id of each group in a projection on top of it
+                    let all_aggregates = agg_filter
+                        .iter()
+                        .map(|(aggregate, _, _)| aggregate.clone())
+                        .collect::<Vec<_>>();
+                    let (aggregates, filters, order_bys) : (Vec<_>, Vec<_>, Vec<_>) = multiunzip(
+                        agg_filter
+                            .into_iter()
+                            .filter(|(aggregate, _, _)| !is_grouping_aggregate(aggregate))
+                    );
 
                     let initial_aggr = Arc::new(AggregateExec::try_new(
                         AggregateMode::Partial,
@@ -748,15 +761,16 @@ impl DefaultPhysicalPlanner {
                         (initial_aggr, AggregateMode::Final)
                     };
 
+                    let initial_schema = initial_aggr.schema();
                     let final_grouping_set = PhysicalGroupBy::new_single(
                         final_group
                             .iter()
                             .enumerate()
-                            .map(|(i, expr)| (expr.clone(), groups.expr()[i].1.clone()))
+                            .map(|(i, expr)| (expr.clone(), initial_schema.field(i).name().clone()))
                             .collect()
                     );
 
-                    Ok(Arc::new(AggregateExec::try_new(
+                    let final_aggr = Arc::new(AggregateExec::try_new(
                         next_partition_mode,
                         final_grouping_set,
                         aggregates,
@@ -764,7 +778,13 @@ impl DefaultPhysicalPlanner {
                         order_bys,
                         initial_aggr,
                         physical_input_schema.clone(),
-                    )?))
+                    )?);
+
+                    if groups.has_grouping_set() || all_aggregates.len() != final_aggr.aggr_expr().len() {
+                        project_grouping_id(final_aggr, &groups, &all_aggregates)
+                    } else {
+                        Ok(final_aggr)
+                    }
                 }
                 LogicalPlan::Projection(Projection { input, expr, .. }) => {
                     let input_exec = self.create_initial_plan(input, session_state).await?;
@@ -901,93 +921,33 @@ impl DefaultPhysicalPlanner {
                     filter,
                     join_type,
                     null_equals_null,
-                    schema: join_schema,
                     ..
                 }) => {
                     let null_equals_null = *null_equals_null;
 
-                    // If join has expression equijoin keys, add physical projecton.
-                    let has_expr_join_key = keys.iter().any(|(l, r)| {
-                        !(matches!(l, Expr::Column(_))
-                            && matches!(r, Expr::Column(_)))
-                    });
-                    if has_expr_join_key {
-                        let left_keys = keys
-                            .iter()
-                            .map(|(l, _r)| l)
-                            .cloned()
-                            .collect::<Vec<_>>();
-                        let right_keys = keys
-                            .iter()
-                            .map(|(_l, r)| r)
-                            .cloned()
-                            .collect::<Vec<_>>();
-                        let (left, right, column_on, added_project) = {
-                            let (left, left_col_keys, left_projected) =
-                                wrap_projection_for_join_if_necessary(
-                                    left_keys.as_slice(),
-                                    left.as_ref().clone(),
-                                )?;
-                            let (right, right_col_keys, right_projected) =
-                                wrap_projection_for_join_if_necessary(
-                                    &right_keys,
-                                    right.as_ref().clone(),
-                                )?;
-                            (
-                                left,
-                                right,
-                                (left_col_keys, right_col_keys),
-                                left_projected || right_projected,
-                            )
-                        };
-
-                        let join_plan =
-                            LogicalPlan::Join(Join::try_new_with_project_input(
-                                logical_plan,
-                                Arc::new(left),
-                                Arc::new(right),
-                                column_on,
-                            )?);
-
-                        // Remove temporary projected columns
-                        let join_plan = if added_project {
-                            let final_join_result = join_schema
-                                .fields()
-                                .iter()
-                                .map(|field| {
-                                    Expr::Column(field.qualified_column())
-                                })
-                                .collect::<Vec<_>>();
-                            let projection =
-                                logical_plan::Projection::try_new_with_schema(
-                                    final_join_result,
-                                    Arc::new(join_plan),
-                                    join_schema.clone(),
-                                )?;
-                            LogicalPlan::Projection(projection)
-                        } else {
-                            join_plan
-                        };
-
-                        return self
-                            .create_initial_plan(&join_plan, session_state)
-                            .await;
-                    }
-
-                    // All equi-join keys are columns now, create physical join plan
+                    // Equi-join keys which are expressions rather than columns are
+                    // evaluated by the join itself
                     let left_right = self.create_initial_plan_multi([left.as_ref(), right.as_ref()], session_state).await?;
                     let [physical_left, physical_right]: [Arc<dyn ExecutionPlan>; 2] = left_right.try_into().map_err(|_| DataFusionError::Internal("`create_initial_plan_multi` is broken".to_string()))?;
                     let left_df_schema = left.schema();
                     let right_df_schema = right.schema();
+                    let execution_props = session_state.execution_props();
                     let join_on = keys
                         .iter()
                         .map(|(l, r)| {
-                            let l = l.try_into_col()?;
-                            let r = r.try_into_col()?;
-                            Ok((
-                                Column::new(&l.name, left_df_schema.index_of_column(&l)?),
-                                Column::new(&r.name, right_df_schema.index_of_column(&r)?),
-                            ))
+                            let l = create_physical_expr(
+                                l,
+                                left_df_schema,
+                                &physical_left.schema(),
+                                execution_props,
+                            )?;
+                            let r = create_physical_expr(
+                                r,
+                                right_df_schema,
+                                &physical_right.schema(),
+                                execution_props,
+                            )?;
+                            Ok((l, r))
                         })
                         .collect::<Result<join_utils::JoinOn>>()?;
 
@@ -1049,6 +1009,7 @@ impl DefaultPhysicalPlanner {
                     };
 
                     let prefer_hash_join = session_state.config_options().optimizer.prefer_hash_join;
+                    let default_filter_selectivity = session_state.config_options().optimizer.hash_join_default_filter_selectivity;
                     if join_on.is_empty() {
                         // there is no equal join condition, use the nested loop join
                         // TODO optimize the plan, and use the config of `target_partitions` and `repartition_joins`
@@ -1088,15 +1049,30 @@ impl DefaultPhysicalPlanner {
                                 PartitionMode::Partitioned
                             }
                          };
-                        Ok(Arc::new(HashJoinExec::try_new(
-                            physical_left,
-                            physical_right,
-                            join_on,
-                            join_filter,
-                            join_type,
-                            partition_mode,
-                            null_equals_null,
-                        )?))
+                        // a join in Auto mode which is not decided by the
+                        // optimizer is decided by the size of its build side
+                        let single_partition_threshold = session_state
+                            .config_options()
+                            .optimizer
+                            .hash_join_single_partition_threshold;
+                        let single_partition_threshold_rows = session_state
+                            .config_options()
+                            .optimizer
+                            .hash_join_single_partition_threshold_rows;
+                        Ok(Arc::new(
+                            HashJoinExec::try_new(
+                                physical_left,
+                                physical_right,
+                                join_on,
+                                join_filter,
+                                join_type,
+                                partition_mode,
+                                null_equals_null,
+                            )?
+                            .with_single_partition_threshold(single_partition_threshold)
+                            .with_single_partition_threshold_rows(single_partition_threshold_rows)
+                            .with_default_filter_selectivity(default_filter_selectivity)?,
+                        ))
                     } else {
                         Ok(Arc::new(HashJoinExec::try_new(
                             physical_left,
@@ -1106,7 +1082,8 @@ impl DefaultPhysicalPlanner {
                             join_type,
                             PartitionMode::CollectLeft,
                             null_equals_null,
-                        )?))
+                        )?
+                        .with_default_filter_selectivity(default_filter_selectivity)?))
                     }
                 }
                 LogicalPlan::CrossJoin(CrossJoin { left, right, .. }) => {
@@ -1300,6 +1277,87 @@ impl DefaultPhysicalPlanner {
     }
 }
 
+/// Returns true if `aggregate` is the `GROUPING` aggregate function
+fn is_grouping_aggregate(aggregate: &Arc<dyn AggregateExpr>) -> bool {
+    aggregate.as_any().downcast_ref::<Grouping>().is_some()
+}
+
+/// Projects the output of the final aggregation `aggr` of `group_by` onto the
+/// schema of the logical aggregate with aggregate expressions `aggregates`:
+/// the [`INTERNAL_GROUPING_ID`] column is removed and each `GROUPING`
+/// aggregate, which `aggr` does not evaluate, is computed from it.
+fn project_grouping_id(
+    aggr: Arc<AggregateExec>,
+    group_by: &PhysicalGroupBy,
+    aggregates: &[Arc<dyn AggregateExpr>],
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = aggr.schema();
+    let mut exprs = group_by
+        .expr()
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, name))| (Arc::new(Column::new(name, idx)) as _, name.clone()))
+        .collect::<Vec<(Arc<dyn PhysicalExpr>, String)>>();
+
+    let mut aggr_idx = group_by.num_output_exprs();
+    for aggregate in aggregates {
+        let expr: Arc<dyn PhysicalExpr> =
+            match aggregate.as_any().downcast_ref::<Grouping>() {
+                Some(grouping) => grouping_expr(grouping, group_by, &schema)?,
+                None => {
+                    aggr_idx += 1;
+                    Arc::new(Column::new(aggregate.name(), aggr_idx - 1))
+                }
+            };
+        exprs.push((expr, aggregate.name().to_string()));
+    }
+
+    Ok(Arc::new(ProjectionExec::try_new(exprs, aggr)?))
+}
+
+/// Creates the expression computing `grouping` on the output of an aggregation
+/// of `group_by` with schema `schema`: the bit of the argument of `GROUPING` in
+/// the [`INTERNAL_GROUPING_ID`] column.
+fn grouping_expr(
+    grouping: &Grouping,
+    group_by: &PhysicalGroupBy,
+    schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let arg = &grouping.expressions()[0];
+    let idx = group_by
+        .expr()
+        .iter()
+        .position(|(expr, _)| expr.eq(arg))
+        .ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Argument of {} must be a GROUP BY expression",
+                grouping.name()
+            ))
+        })?;
+
+    if !group_by.has_grouping_set() {
+        return Ok(Arc::new(Literal::new(ScalarValue::Int32(Some(0)))));
+    }
+
+    let grouping_id = Arc::new(Column::new(
+        INTERNAL_GROUPING_ID,
+        schema.index_of(INTERNAL_GROUPING_ID)?,
+    ));
+    let shift = (group_by.expr().len() - 1 - idx) as u32;
+    let bit = binary(
+        binary(
+            grouping_id,
+            Operator::BitwiseShiftRight,
+            Arc::new(Literal::new(ScalarValue::UInt32(Some(shift)))),
+            schema,
+        )?,
+        Operator::BitwiseAnd,
+        Arc::new(Literal::new(ScalarValue::UInt32(Some(1)))),
+        schema,
+    )?;
+    cast(bit, schema, DataType::Int32)
+}
+
 /// Expand and align a GROUPING SET expression.
 /// (see <https://www.postgresql.org/docs/current/queries-table-expressions.html#QUERIES-GROUPING-SETS>)
 ///
@@ -1809,6 +1867,12 @@ impl DefaultPhysicalPlanner {
             }
 
             if !config.logical_plan_only && e.logical_optimization_succeeded {
+                // nodes add their details to the physical plans of EXPLAIN VERBOSE
+                let format_type = if e.verbose {
+                    DisplayFormatType::Verbose
+                } else {
+                    DisplayFormatType::Default
+                };
                 match self
                     .create_initial_plan(e.plan.as_ref(), session_state)
                     .await
@@ -1816,6 +1880,7 @@ impl DefaultPhysicalPlanner {
                     Ok(input) => {
                         stringified_plans.push(
                             displayable(input.as_ref())
+                                .with_format_type(format_type)
                                 .to_stringified(InitialPhysicalPlan),
                         );
 
@@ -1825,12 +1890,16 @@ impl DefaultPhysicalPlanner {
                             |plan, optimizer| {
                                 let optimizer_name = optimizer.name().to_string();
                                 let plan_type = OptimizedPhysicalPlan { optimizer_name };
-                                stringified_plans
-                                    .push(displayable(plan).to_stringified(plan_type));
+                                stringified_plans.push(
+                                    displayable(plan)
+                                        .with_format_type(format_type)
+                                        .to_stringified(plan_type),
+                                );
                             },
                         ) {
                             Ok(input) => stringified_plans.push(
                                 displayable(input.as_ref())
+                                    .with_format_type(format_type)
                                     .to_stringified(FinalPhysicalPlan),
                             ),
                             Err(DataFusionError::Context(optimizer_name, e)) => {
@@ -2306,12 +2375,22 @@ mod tests {
             .build()?;
 
         let execution_plan = plan(&logical_plan).await?;
-        let final_hash_agg = execution_plan
+        assert_eq!(
+            "SUM(aggregate_test_100.c3)",
+            execution_plan.schema().field(2).name()
+        );
+        // the grouping id column is projected away on top of the aggregation
+        let projection = execution_plan
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("projection");
+        let final_hash_agg = projection
+            .input()
             .as_any()
             .downcast_ref::<AggregateExec>()
             .expect("hash aggregate");
         assert_eq!(
-            "SUM(aggregate_test_100.c3)",
+            INTERNAL_GROUPING_ID,
             final_hash_agg.schema().field(2).name()
         );
         // we need access to the input to the partial aggregate so that other projects can
@@ -2536,7 +2615,7 @@ mod tests {
 
         fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
             match t {
-                DisplayFormatType::Default => {
+                DisplayFormatType::Default | DisplayFormatType::Verbose => {
                     write!(f, "NoOpExecutionPlan")
                 }
             }
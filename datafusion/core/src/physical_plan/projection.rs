@@ -216,6 +216,22 @@ impl ExecutionPlan for ProjectionExec {
         new_properties
     }
 
+    /// The unique keys of the input all of whose columns are projected as is
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        let output_index = |input_index: usize| {
+            self.expr.iter().position(|(expr, _)| {
+                expr.as_any()
+                    .downcast_ref::<Column>()
+                    .map_or(false, |column| column.index() == input_index)
+            })
+        };
+        self.input
+            .unique_keys()
+            .into_iter()
+            .filter_map(|key| key.into_iter().map(output_index).collect())
+            .collect()
+    }
+
     fn ordering_equivalence_properties(&self) -> OrderingEquivalenceProperties {
         let mut new_properties = OrderingEquivalenceProperties::new(self.schema());
         project_ordering_equivalence_properties(
@@ -266,7 +282,7 @@ impl ExecutionPlan for ProjectionExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 let expr: Vec<String> = self
                     .expr
                     .iter()
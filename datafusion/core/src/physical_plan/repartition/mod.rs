@@ -353,6 +353,10 @@ impl ExecutionPlan for RepartitionExec {
         self.input.equivalence_properties()
     }
 
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.input.unique_keys()
+    }
+
     fn execute(
         &self,
         partition: usize,
@@ -450,7 +454,7 @@ impl ExecutionPlan for RepartitionExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
                     "RepartitionExec: partitioning={:?}, input_partitions={}",
@@ -369,7 +369,7 @@ async fn spill_sorted_batches(
     }
 }
 
-fn read_spill_as_stream(
+pub(crate) fn read_spill_as_stream(
     path: NamedTempFile,
     schema: SchemaRef,
 ) -> Result<SendableRecordBatchStream> {
@@ -570,6 +570,10 @@ impl ExecutionPlan for SortExec {
         self.input.equivalence_properties()
     }
 
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.input.unique_keys()
+    }
+
     fn with_new_children(
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -625,7 +629,7 @@ impl ExecutionPlan for SortExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 let expr: Vec<String> = self.expr.iter().map(|e| e.to_string()).collect();
                 match self.fetch {
                     Some(fetch) => {
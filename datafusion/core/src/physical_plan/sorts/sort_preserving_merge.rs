@@ -129,6 +129,10 @@ impl ExecutionPlan for SortPreservingMergeExec {
         self.input.equivalence_properties()
     }
 
+    fn unique_keys(&self) -> Vec<Vec<usize>> {
+        self.input.unique_keys()
+    }
+
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
         vec![self.input.clone()]
     }
@@ -207,7 +211,7 @@ impl ExecutionPlan for SortPreservingMergeExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 let expr: Vec<String> = self.expr.iter().map(|e| e.to_string()).collect();
                 write!(f, "SortPreservingMergeExec: [{}]", expr.join(","))
             }
@@ -245,6 +249,8 @@ mod tests {
     use crate::test::{self, assert_is_pending};
     use crate::{assert_batches_eq, test_util};
     use arrow::array::{Int32Array, StringArray, TimestampNanosecondArray};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     use super::*;
 
@@ -517,6 +523,112 @@ mod tests {
         result.remove(0)
     }
 
+    /// Every combination of `descending` and `nulls_first`
+    fn all_sort_options() -> Vec<SortOptions> {
+        [false, true]
+            .into_iter()
+            .flat_map(|descending| {
+                [false, true]
+                    .into_iter()
+                    .map(move |nulls_first| SortOptions {
+                        descending,
+                        nulls_first,
+                    })
+            })
+            .collect()
+    }
+
+    /// Merges `partitions` after sorting them individually by all their columns with
+    /// `options`, and checks the result against sorting all the rows at once
+    async fn assert_merge_matches_sort(
+        partitions: &[Vec<RecordBatch>],
+        options: &[SortOptions],
+        context: Arc<TaskContext>,
+    ) {
+        let schema = partitions[0][0].schema();
+        let sort: Vec<_> = schema
+            .fields()
+            .iter()
+            .zip(options)
+            .map(|(field, options)| PhysicalSortExpr {
+                expr: col(field.name(), &schema).unwrap(),
+                options: *options,
+            })
+            .collect();
+        let exec: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(partitions, schema, None).unwrap());
+
+        let basic = basic_sort(exec.clone(), sort.clone(), context.clone()).await;
+        let merged = partition_sort(exec, sort, context).await;
+        assert_eq!(basic, merged, "{options:?}");
+    }
+
+    #[tokio::test]
+    async fn test_merge_all_sort_options() {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        // a number of streams that is not a power of two, and many streams
+        for num_partitions in [3, 64] {
+            // batches with duplicates and nulls
+            let partitions: Vec<Vec<_>> = (0..num_partitions)
+                .map(|_| {
+                    (0..2)
+                        .map(|_| {
+                            let a: Int32Array = (0..50)
+                                .map(|_| rng.gen_bool(0.9).then(|| rng.gen_range(0..10)))
+                                .collect();
+                            let b: StringArray = (0..50)
+                                .map(|_| {
+                                    rng.gen_bool(0.9).then(|| {
+                                        ["a", "b", "c", "d"][rng.gen_range(0..4)]
+                                    })
+                                })
+                                .collect();
+                            RecordBatch::try_from_iter(vec![
+                                ("a", Arc::new(a) as ArrayRef),
+                                ("b", Arc::new(b) as ArrayRef),
+                            ])
+                            .unwrap()
+                        })
+                        .collect()
+                })
+                .collect();
+            let project = |indices: &[usize]| -> Vec<Vec<RecordBatch>> {
+                partitions
+                    .iter()
+                    .map(|batches| {
+                        batches
+                            .iter()
+                            .map(|b| b.project(indices).unwrap())
+                            .collect()
+                    })
+                    .collect()
+            };
+
+            // single primitive and string columns use specialized cursors
+            for columns in [project(&[0]), project(&[1])] {
+                for options in all_sort_options() {
+                    assert_merge_matches_sort(&columns, &[options], task_ctx.clone())
+                        .await;
+                }
+            }
+
+            // multiple columns are compared with the row format
+            for a_options in all_sort_options() {
+                for b_options in all_sort_options() {
+                    assert_merge_matches_sort(
+                        &partitions,
+                        &[a_options, b_options],
+                        task_ctx.clone(),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_partition_sort() {
         let session_ctx = SessionContext::new();
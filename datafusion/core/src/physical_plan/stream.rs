@@ -334,13 +334,143 @@ impl futures::Stream for ObservedStream {
     }
 }
 
+/// Stream wrapper that checks the schema of every batch emitted by an
+/// operator against the schema the operator declares, returning an internal
+/// error naming the operator and the mismatch otherwise.
+///
+/// Use [`validate_batch_schemas`] to only apply it when enabled.
+pub struct SchemaValidationStream {
+    operator: &'static str,
+    inner: SendableRecordBatchStream,
+}
+
+impl SchemaValidationStream {
+    /// Creates a new [`SchemaValidationStream`] for the output of `operator`
+    pub fn new(operator: &'static str, inner: SendableRecordBatchStream) -> Self {
+        Self { operator, inner }
+    }
+}
+
+impl RecordBatchStream for SchemaValidationStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for SchemaValidationStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let poll = self.inner.poll_next_unpin(cx);
+        poll.map(|item| match item {
+            Some(Ok(batch)) => Some(
+                check_batch_schema(self.operator, &self.inner.schema(), &batch)
+                    .map(|_| batch),
+            ),
+            other => other,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Wraps the output stream of `operator` in a [`SchemaValidationStream`] when
+/// `datafusion.execution.validate_batch_schemas` is set
+pub fn validate_batch_schemas(
+    operator: &'static str,
+    stream: SendableRecordBatchStream,
+    context: &TaskContext,
+) -> SendableRecordBatchStream {
+    if context
+        .session_config()
+        .options()
+        .execution
+        .validate_batch_schemas
+    {
+        Box::pin(SchemaValidationStream::new(operator, stream))
+    } else {
+        stream
+    }
+}
+
+/// Checks that the schema of `batch` matches `schema`, the schema declared by
+/// `operator`
+fn check_batch_schema(
+    operator: &str,
+    schema: &SchemaRef,
+    batch: &RecordBatch,
+) -> Result<()> {
+    let batch_schema = batch.schema();
+    if Arc::ptr_eq(schema, &batch_schema) {
+        return Ok(());
+    }
+
+    let mismatch = |msg: String| {
+        Err(DataFusionError::Internal(format!(
+            "{operator} emitted a batch that does not match its declared schema: {msg}"
+        )))
+    };
+
+    if schema.fields().len() != batch_schema.fields().len() {
+        return mismatch(format!(
+            "expected {} fields, got {}",
+            schema.fields().len(),
+            batch_schema.fields().len()
+        ));
+    }
+    for (expected, actual) in schema.fields().iter().zip(batch_schema.fields()) {
+        let name = expected.name();
+        if name != actual.name() {
+            return mismatch(format!(
+                "expected field '{name}', got field '{}'",
+                actual.name()
+            ));
+        }
+        if expected.data_type() != actual.data_type() {
+            return mismatch(format!(
+                "field '{name}' expected type {:?}, got {:?}",
+                expected.data_type(),
+                actual.data_type()
+            ));
+        }
+        if expected.is_nullable() != actual.is_nullable() {
+            return mismatch(format!(
+                "field '{name}' expected nullable={}, got nullable={}",
+                expected.is_nullable(),
+                actual.is_nullable()
+            ));
+        }
+        if expected.metadata() != actual.metadata() {
+            return mismatch(format!(
+                "field '{name}' expected metadata {:?}, got {:?}",
+                expected.metadata(),
+                actual.metadata()
+            ));
+        }
+    }
+    if schema.metadata() != batch_schema.metadata() {
+        return mismatch(format!(
+            "expected schema metadata {:?}, got {:?}",
+            schema.metadata(),
+            batch_schema.metadata()
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use arrow::array::{Float32Array, Int32Array, Int64Array};
     use arrow_schema::{DataType, Field, Schema};
 
     use crate::{
-        execution::context::SessionContext,
+        execution::context::{SessionConfig, SessionContext},
         test::exec::{
             assert_strong_count_converges_to_zero, BlockingExec, MockExec, PanicExec,
         },
@@ -435,6 +565,113 @@ mod test {
         assert!(stream.next().await.is_none());
     }
 
+    #[tokio::test]
+    async fn schema_validation_stream_detects_mismatch() {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let declared = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Float32, false),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = |schema: Schema| {
+            RecordBatch::try_new(
+                Arc::new(schema),
+                vec![
+                    Arc::new(Float32Array::from(vec![1.0])),
+                    Arc::new(Int32Array::from(vec![1])),
+                ],
+            )
+            .unwrap()
+        };
+        let b_int64 = Schema::new(vec![
+            Field::new("a", DataType::Float32, false),
+            Field::new("b", DataType::Int64, true),
+        ]);
+        let b_int64_batch = RecordBatch::try_new(
+            Arc::new(b_int64),
+            vec![
+                Arc::new(Float32Array::from(vec![1.0])),
+                Arc::new(Int64Array::from(vec![1])),
+            ],
+        )
+        .unwrap();
+
+        let cases = vec![
+            (batch(declared.as_ref().clone()), None),
+            (
+                batch(Schema::new(vec![
+                    Field::new("a", DataType::Float32, true),
+                    Field::new("b", DataType::Int32, true),
+                ])),
+                Some("field 'a' expected nullable=false, got nullable=true"),
+            ),
+            (
+                b_int64_batch,
+                Some("field 'b' expected type Int32, got Int64"),
+            ),
+            (
+                batch(Schema::new(vec![
+                    Field::new("a", DataType::Float32, false),
+                    Field::new("c", DataType::Int32, true),
+                ])),
+                Some("expected field 'b', got field 'c'"),
+            ),
+            (
+                batch(declared.as_ref().clone().with_metadata(
+                    [("k".to_string(), "v".to_string())].into_iter().collect(),
+                )),
+                Some("expected schema metadata {}, got {\"k\": \"v\"}"),
+            ),
+        ];
+
+        for (batch, expected) in cases {
+            // a miswired operator, emitting batches not matching its schema
+            let input =
+                MockExec::new(vec![Ok(batch)], declared.clone()).with_use_task(false);
+            let stream = input.execute(0, task_ctx.clone()).unwrap();
+            let mut stream = SchemaValidationStream::new("MockExec", stream);
+
+            let result = stream.next().await.unwrap();
+            match expected {
+                None => assert!(result.is_ok()),
+                Some(msg) => assert_eq!(
+                    result.unwrap_err().to_string(),
+                    format!(
+                        "Internal error: MockExec emitted a batch that does not \
+                        match its declared schema: {msg}. This was likely caused \
+                        by a bug in DataFusion's code and we would welcome that \
+                        you file an bug report in our issue tracker"
+                    )
+                ),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn validate_batch_schemas_follows_option() {
+        let declared =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)])),
+            vec![Arc::new(Int32Array::from(vec![1]))],
+        )
+        .unwrap();
+
+        // only checked when enabled, whatever the build profile
+        for enabled in [false, true] {
+            let config = SessionConfig::new()
+                .set_bool("datafusion.execution.validate_batch_schemas", enabled);
+            let task_ctx = SessionContext::with_config(config).task_ctx();
+            let input = MockExec::new(vec![Ok(batch.clone())], declared.clone())
+                .with_use_task(false);
+            let stream = input.execute(0, task_ctx.clone()).unwrap();
+            let mut stream = validate_batch_schemas("MockExec", stream, &task_ctx);
+
+            let result = stream.next().await.unwrap();
+            assert_eq!(result.is_err(), enabled);
+        }
+    }
+
     /// Consumes all the input's partitions into a
     /// RecordBatchReceiverStream and runs it to completion
     ///
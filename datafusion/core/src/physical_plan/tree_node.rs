@@ -19,7 +19,7 @@
 
 use crate::physical_plan::{with_new_children_if_necessary, ExecutionPlan};
 use datafusion_common::tree_node::{DynTreeNode, Transformed};
-use datafusion_common::Result;
+use datafusion_common::{DataFusionError, Result};
 use std::sync::Arc;
 
 impl DynTreeNode for dyn ExecutionPlan {
@@ -35,3 +35,351 @@ impl DynTreeNode for dyn ExecutionPlan {
         with_new_children_if_necessary(arc_self, new_children).map(Transformed::into)
     }
 }
+
+/// Rewrites [`ExecutionPlan`] trees while threading a payload through the traversal.
+///
+/// This complements the [`TreeNode`] methods available on every
+/// `Arc<dyn ExecutionPlan>` for rules that need to pass information between
+/// parents and children, such as properties required by the parent (top-down)
+/// or facts gathered from the inputs (bottom-up).
+///
+/// Every method reports whether the tree was transformed, and a node is only
+/// rebuilt when at least one of its children was replaced, so a rewrite that
+/// changes nothing returns the very same `Arc`s (including their metrics).
+///
+/// [`TreeNode`]: datafusion_common::tree_node::TreeNode
+pub trait TransformWithPayload: Sized {
+    /// Recursively applies `op` to the node and then to all of its children
+    /// (Preorder Traversal).
+    ///
+    /// `op` receives each node with the payload given by its parent and returns
+    /// the (potentially) transformed node, together with one payload for each
+    /// child of the returned node.
+    fn transform_down_with_payload<P, F>(
+        self,
+        payload: P,
+        op: &mut F,
+    ) -> Result<Transformed<Self>>
+    where
+        F: FnMut(Self, P) -> Result<(Transformed<Self>, Vec<P>)>;
+
+    /// Recursively applies `op` to all of the children of the node and then to
+    /// the node itself (Postorder Traversal).
+    ///
+    /// `op` receives each node, after its children were transformed, with the
+    /// payloads returned for these children, and returns the (potentially)
+    /// transformed node together with the payload passed to its parent.
+    fn transform_up_with_payload<P, F>(
+        self,
+        op: &mut F,
+    ) -> Result<(Transformed<Self>, P)>
+    where
+        F: FnMut(Self, Vec<P>) -> Result<(Transformed<Self>, P)>;
+
+    /// Recursively transforms the node and all of its children, with a payload
+    /// flowing from parents to children.
+    ///
+    /// `f_down` is called on each node before its children are visited, with the
+    /// payload given by its parent, and returns one payload for each child.
+    /// `f_up` is called on each node after its children were transformed, with
+    /// the payload given by its parent, and returns the (potentially) transformed
+    /// node.
+    fn transform_with_payload<P, FD, FU>(
+        self,
+        payload: P,
+        f_down: &mut FD,
+        f_up: &mut FU,
+    ) -> Result<Transformed<Self>>
+    where
+        FD: FnMut(&Self, &P) -> Result<Vec<P>>,
+        FU: FnMut(Self, P) -> Result<Transformed<Self>>;
+}
+
+impl TransformWithPayload for Arc<dyn ExecutionPlan> {
+    fn transform_down_with_payload<P, F>(
+        self,
+        payload: P,
+        op: &mut F,
+    ) -> Result<Transformed<Self>>
+    where
+        F: FnMut(Self, P) -> Result<(Transformed<Self>, Vec<P>)>,
+    {
+        let (after_op, payloads) = op(self, payload)?;
+        let (after_op, transformed) = after_op.into_pair();
+        let after_op_children =
+            map_children_with_payload(after_op, payloads, |child, payload| {
+                child.transform_down_with_payload(payload, op)
+            })?;
+        Ok(transformed_if(after_op_children, transformed))
+    }
+
+    fn transform_up_with_payload<P, F>(self, op: &mut F) -> Result<(Transformed<Self>, P)>
+    where
+        F: FnMut(Self, Vec<P>) -> Result<(Transformed<Self>, P)>,
+    {
+        let mut payloads = vec![];
+        let children = self.children();
+        let after_op_children =
+            map_children_with_payload(self, vec![(); children.len()], |child, ()| {
+                let (child, payload) = child.transform_up_with_payload(op)?;
+                payloads.push(payload);
+                Ok(child)
+            })?;
+        let (after_op_children, transformed) = after_op_children.into_pair();
+        let (new_node, payload) = op(after_op_children, payloads)?;
+        Ok((transformed_if(new_node, transformed), payload))
+    }
+
+    fn transform_with_payload<P, FD, FU>(
+        self,
+        payload: P,
+        f_down: &mut FD,
+        f_up: &mut FU,
+    ) -> Result<Transformed<Self>>
+    where
+        FD: FnMut(&Self, &P) -> Result<Vec<P>>,
+        FU: FnMut(Self, P) -> Result<Transformed<Self>>,
+    {
+        let payloads = f_down(&self, &payload)?;
+        let after_op_children =
+            map_children_with_payload(self, payloads, |child, payload| {
+                child.transform_with_payload(payload, f_down, f_up)
+            })?;
+        let (after_op_children, transformed) = after_op_children.into_pair();
+        let new_node = f_up(after_op_children, payload)?;
+        Ok(transformed_if(new_node, transformed))
+    }
+}
+
+/// Applies `transform` to every child of `plan` with the matching payload, and
+/// only rebuilds `plan` if any of its children was replaced
+fn map_children_with_payload<P, F>(
+    plan: Arc<dyn ExecutionPlan>,
+    payloads: Vec<P>,
+    mut transform: F,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>>
+where
+    F: FnMut(Arc<dyn ExecutionPlan>, P) -> Result<Transformed<Arc<dyn ExecutionPlan>>>,
+{
+    let children = plan.children();
+    if children.len() != payloads.len() {
+        return Err(DataFusionError::Internal(format!(
+            "Expected {} payloads for the children of the plan, got {}",
+            children.len(),
+            payloads.len()
+        )));
+    }
+    if children.is_empty() {
+        return Ok(Transformed::No(plan));
+    }
+
+    let mut transformed = false;
+    let new_children = children
+        .into_iter()
+        .zip(payloads)
+        .map(|(child, payload)| {
+            let (child, child_transformed) = transform(child, payload)?.into_pair();
+            transformed |= child_transformed;
+            Ok(child)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if transformed {
+        with_new_children_if_necessary(plan, new_children)
+    } else {
+        Ok(Transformed::No(plan))
+    }
+}
+
+/// Marks `node` as transformed if it or any of its children was transformed
+fn transformed_if<T>(node: Transformed<T>, children_transformed: bool) -> Transformed<T> {
+    match node {
+        Transformed::No(node) if children_transformed => Transformed::Yes(node),
+        node => node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::empty::EmptyExec;
+    use crate::physical_plan::union::UnionExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn empty() -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        Arc::new(EmptyExec::new(false, schema))
+    }
+
+    fn coalesce(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        Arc::new(CoalesceBatchesExec::new(input, 4096))
+    }
+
+    /// UnionExec
+    ///   CoalesceBatchesExec
+    ///     CoalesceBatchesExec
+    ///       EmptyExec
+    ///   EmptyExec
+    fn test_plan() -> Arc<dyn ExecutionPlan> {
+        Arc::new(UnionExec::new(vec![coalesce(coalesce(empty())), empty()]))
+    }
+
+    fn plan_string(plan: &Arc<dyn ExecutionPlan>) -> Vec<String> {
+        displayable(plan.as_ref())
+            .indent()
+            .to_string()
+            .trim()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Replaces `plan` by a new `CoalesceBatchesExec` with the given target batch size
+    /// if it is a `CoalesceBatchesExec`
+    fn with_target_batch_size(
+        plan: Arc<dyn ExecutionPlan>,
+        target_batch_size: usize,
+    ) -> Transformed<Arc<dyn ExecutionPlan>> {
+        match plan.as_any().downcast_ref::<CoalesceBatchesExec>() {
+            Some(coalesce) => Transformed::Yes(Arc::new(CoalesceBatchesExec::new(
+                coalesce.input().clone(),
+                target_batch_size,
+            ))),
+            None => Transformed::No(plan),
+        }
+    }
+
+    #[test]
+    fn no_op_rewrites_preserve_plan() -> Result<()> {
+        let plan = test_plan();
+
+        let result = plan
+            .clone()
+            .transform_down_with_payload(0, &mut |node, _| {
+                let payloads = vec![0; node.children().len()];
+                Ok((Transformed::No(node), payloads))
+            })?;
+        assert!(matches!(&result, Transformed::No(p) if Arc::ptr_eq(p, &plan)));
+
+        let (result, height) = plan.clone().transform_up_with_payload(
+            &mut |node, heights: Vec<usize>| {
+                let height = heights.into_iter().max().map_or(0, |h| h + 1);
+                Ok((Transformed::No(node), height))
+            },
+        )?;
+        assert!(matches!(&result, Transformed::No(p) if Arc::ptr_eq(p, &plan)));
+        assert_eq!(height, 3);
+
+        let result = plan.clone().transform_with_payload(
+            (),
+            &mut |node, _| Ok(vec![(); node.children().len()]),
+            &mut |node, _| Ok(Transformed::No(node)),
+        )?;
+        assert!(matches!(&result, Transformed::No(p) if Arc::ptr_eq(p, &plan)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn transform_down_with_depth() -> Result<()> {
+        let plan = test_plan();
+
+        let result =
+            plan.clone()
+                .transform_down_with_payload(0, &mut |node, depth| {
+                    let payloads = vec![depth + 1; node.children().len()];
+                    Ok((with_target_batch_size(node, depth), payloads))
+                })?;
+        let (result, transformed) = result.into_pair();
+        assert!(transformed);
+
+        let expected = vec![
+            "UnionExec",
+            "  CoalesceBatchesExec: target_batch_size=1",
+            "    CoalesceBatchesExec: target_batch_size=2",
+            "      EmptyExec: produce_one_row=false",
+            "  EmptyExec: produce_one_row=false",
+        ];
+        assert_eq!(plan_string(&result), expected);
+
+        // untouched subtrees are kept as is
+        let (old_children, new_children) = (plan.children(), result.children());
+        assert!(!Arc::ptr_eq(&old_children[0], &new_children[0]));
+        assert!(Arc::ptr_eq(&old_children[1], &new_children[1]));
+        assert!(Arc::ptr_eq(
+            &old_children[0].children()[0].children()[0],
+            &new_children[0].children()[0].children()[0]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn transform_up_with_height() -> Result<()> {
+        let plan = test_plan();
+
+        let (result, height) = plan.clone().transform_up_with_payload(
+            &mut |node, heights: Vec<usize>| {
+                let height = heights.into_iter().max().map_or(0, |h| h + 1);
+                Ok((with_target_batch_size(node, height), height))
+            },
+        )?;
+        assert_eq!(height, 3);
+        let (result, transformed) = result.into_pair();
+        assert!(transformed);
+
+        let expected = vec![
+            "UnionExec",
+            "  CoalesceBatchesExec: target_batch_size=2",
+            "    CoalesceBatchesExec: target_batch_size=1",
+            "      EmptyExec: produce_one_row=false",
+            "  EmptyExec: produce_one_row=false",
+        ];
+        assert_eq!(plan_string(&result), expected);
+        assert!(Arc::ptr_eq(&plan.children()[1], &result.children()[1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn transform_with_depth() -> Result<()> {
+        let plan = test_plan();
+
+        // only rewrite the innermost CoalesceBatchesExec
+        let result = plan.clone().transform_with_payload(
+            0,
+            &mut |node, depth| Ok(vec![depth + 1; node.children().len()]),
+            &mut |node, depth| {
+                Ok(if depth == 2 {
+                    with_target_batch_size(node, 10)
+                } else {
+                    Transformed::No(node)
+                })
+            },
+        )?;
+        let (result, transformed) = result.into_pair();
+        assert!(transformed);
+
+        let expected = vec![
+            "UnionExec",
+            "  CoalesceBatchesExec: target_batch_size=4096",
+            "    CoalesceBatchesExec: target_batch_size=10",
+            "      EmptyExec: produce_one_row=false",
+            "  EmptyExec: produce_one_row=false",
+        ];
+        assert_eq!(plan_string(&result), expected);
+        assert!(Arc::ptr_eq(&plan.children()[1], &result.children()[1]));
+
+        // a wrong number of payloads is an error
+        let err = plan
+            .transform_with_payload((), &mut |_, _| Ok(vec![]), &mut |node, _| {
+                Ok(Transformed::No(node))
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Expected 2 payloads"), "{err}");
+
+        Ok(())
+    }
+}
@@ -132,7 +132,7 @@ impl ExecutionPlan for UnnestExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "UnnestExec")
             }
         }
@@ -24,6 +24,7 @@ use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{
     BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
 };
+use crate::physical_plan::stream::validate_batch_schemas;
 use crate::physical_plan::windows::{
     calc_requirements, get_ordered_partition_by_indices, window_ordering_equivalence,
 };
@@ -62,8 +63,8 @@ use datafusion_common::DataFusionError;
 use datafusion_expr::ColumnarValue;
 use datafusion_physical_expr::hash_utils::create_hashes;
 use datafusion_physical_expr::window::{
-    PartitionBatchState, PartitionBatches, PartitionKey, PartitionWindowAggStates,
-    WindowAggState, WindowState,
+    PartitionBatchState, PartitionBatches, PartitionKey, PartitionPeerGroups,
+    PartitionWindowAggStates, WindowAggState, WindowState,
 };
 use datafusion_physical_expr::{
     EquivalenceProperties, OrderingEquivalenceProperties, PhysicalExpr,
@@ -287,7 +288,7 @@ impl ExecutionPlan for BoundedWindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, context.clone())?;
         let search_mode = self.get_search_algo()?;
         let stream = Box::pin(BoundedWindowAggStream::new(
             self.schema.clone(),
@@ -296,7 +297,11 @@ impl ExecutionPlan for BoundedWindowAggExec {
             BaselineMetrics::new(&self.metrics, partition),
             search_mode,
         )?);
-        Ok(stream)
+        Ok(validate_batch_schemas(
+            "BoundedWindowAggExec",
+            stream,
+            &context,
+        ))
     }
 
     fn fmt_as(
@@ -305,7 +310,7 @@ impl ExecutionPlan for BoundedWindowAggExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "BoundedWindowAggExec: ")?;
                 let g: Vec<String> = self
                     .window_expr
@@ -926,11 +931,17 @@ impl BoundedWindowAggStream {
     }
 
     fn compute_aggregates(&mut self) -> Result<RecordBatch> {
-        // calculate window cols
+        // calculate window cols, sharing the peer groups of every partition
+        // between the window expressions
+        let mut peers = PartitionPeerGroups::new();
         for (cur_window_expr, state) in
             self.window_expr.iter().zip(&mut self.window_agg_states)
         {
-            cur_window_expr.evaluate_stateful(&self.partition_buffers, state)?;
+            cur_window_expr.evaluate_stateful_with_peers(
+                &self.partition_buffers,
+                state,
+                &mut peers,
+            )?;
         }
 
         let schema = self.schema.clone();
@@ -22,6 +22,7 @@ use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{
     BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
 };
+use crate::physical_plan::stream::validate_batch_schemas;
 use crate::physical_plan::windows::{
     calc_requirements, get_ordered_partition_by_indices, window_ordering_equivalence,
 };
@@ -42,6 +43,7 @@ use datafusion_common::utils::{evaluate_partition_ranges, get_at_indices};
 use datafusion_common::DataFusionError;
 use datafusion_common::Result;
 use datafusion_execution::TaskContext;
+use datafusion_physical_expr::window::PeerGroups;
 use datafusion_physical_expr::{OrderingEquivalenceProperties, PhysicalSortRequirement};
 use futures::stream::Stream;
 use futures::{ready, StreamExt};
@@ -213,7 +215,7 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, context.clone())?;
         let stream = Box::pin(WindowAggStream::new(
             self.schema.clone(),
             self.window_expr.clone(),
@@ -222,7 +224,7 @@ impl ExecutionPlan for WindowAggExec {
             self.partition_by_sort_keys()?,
             self.ordered_partition_by_indices.clone(),
         )?);
-        Ok(stream)
+        Ok(validate_batch_schemas("WindowAggExec", stream, &context))
     }
 
     fn fmt_as(
@@ -231,7 +233,7 @@ impl ExecutionPlan for WindowAggExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "WindowAggExec: ")?;
                 let g: Vec<String> = self
                     .window_expr
@@ -290,14 +292,16 @@ fn create_schema(
     Ok(builder.finish())
 }
 
-/// Compute the window aggregate columns
+/// Compute the window aggregate columns of a partition, sharing the peer
+/// groups of the partition between the window expressions
 fn compute_window_aggregates(
     window_expr: &[Arc<dyn WindowExpr>],
     batch: &RecordBatch,
 ) -> Result<Vec<ArrayRef>> {
+    let mut peers = PeerGroups::new();
     window_expr
         .iter()
-        .map(|window_expr| window_expr.evaluate(batch))
+        .map(|window_expr| window_expr.evaluate_with_peers(batch, &mut peers))
         .collect()
 }
 
@@ -20,7 +20,10 @@
 use std::{
     any::Any,
     pin::Pin,
-    sync::{Arc, Weak},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
     task::{Context, Poll},
 };
 use tokio::sync::Barrier;
@@ -231,7 +234,7 @@ impl ExecutionPlan for MockExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "MockExec")
             }
         }
@@ -358,7 +361,7 @@ impl ExecutionPlan for BarrierExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "BarrierExec")
             }
         }
@@ -437,7 +440,7 @@ impl ExecutionPlan for ErrorExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "ErrorExec")
             }
         }
@@ -516,7 +519,7 @@ impl ExecutionPlan for StatisticsExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
                     "StatisticsExec: col_count={}, row_count={:?}",
@@ -611,7 +614,7 @@ impl ExecutionPlan for BlockingExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "BlockingExec",)
             }
         }
@@ -749,7 +752,7 @@ impl ExecutionPlan for PanicExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         match t {
-            DisplayFormatType::Default => {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(f, "PanickingExec",)
             }
         }
@@ -805,3 +808,148 @@ impl RecordBatchStream for PanicStream {
         Arc::clone(&self.schema)
     }
 }
+
+/// Execution plan that emits streams repeating the same batch forever.
+///
+/// This is useful to test that consumers stop polling their input once they
+/// have enough rows, and drop it.
+#[derive(Debug)]
+pub struct InfiniteExec {
+    /// The batch repeated by the streams
+    batch: RecordBatch,
+
+    /// Number of batches produced by all the streams of this plan
+    batches_produced: Arc<AtomicUsize>,
+
+    /// Ref-counting helper to check if the plan and the produced stream are still in memory.
+    refs: Arc<()>,
+}
+
+impl InfiniteExec {
+    /// Create new [`InfiniteExec`] with a single partition repeating `batch`.
+    pub fn new(batch: RecordBatch) -> Self {
+        Self {
+            batch,
+            batches_produced: Default::default(),
+            refs: Default::default(),
+        }
+    }
+
+    /// Number of batches produced so far by the streams of this plan
+    pub fn batches_produced(&self) -> usize {
+        self.batches_produced.load(Ordering::SeqCst)
+    }
+
+    /// Weak pointer that can be used for ref-counting this execution plan and its streams,
+    /// see [`BlockingExec::refs`].
+    pub fn refs(&self) -> Weak<()> {
+        Arc::downgrade(&self.refs)
+    }
+}
+
+impl ExecutionPlan for InfiniteExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.batch.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn unbounded_output(&self, _children: &[bool]) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Err(DataFusionError::Internal(format!(
+            "Children cannot be replaced in {self:?}"
+        )))
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        Ok(Box::pin(InfiniteStream {
+            batch: self.batch.clone(),
+            batches_produced: Arc::clone(&self.batches_produced),
+            ready: false,
+            _refs: Arc::clone(&self.refs),
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "InfiniteExec",)
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// A [`RecordBatchStream`] that repeats the same batch forever, returning
+/// pending before every batch.
+#[derive(Debug)]
+struct InfiniteStream {
+    /// The batch repeated by this stream
+    batch: RecordBatch,
+
+    /// Number of batches produced by the streams of the plan
+    batches_produced: Arc<AtomicUsize>,
+
+    /// Should we return ready ?
+    ready: bool,
+
+    /// Ref-counting helper to check if the stream are still in memory.
+    _refs: Arc<()>,
+}
+
+impl Stream for InfiniteStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if !self.ready {
+            // yield to let other tasks, e.g. cancellation, make progress
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        self.batches_produced.fetch_add(1, Ordering::SeqCst);
+        Poll::Ready(Some(Ok(self.batch.clone())))
+    }
+}
+
+impl RecordBatchStream for InfiniteStream {
+    fn schema(&self) -> SchemaRef {
+        self.batch.schema()
+    }
+}
@@ -162,6 +162,93 @@ async fn explain_analyze_baseline_metrics() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn explain_analyze_hash_join_metrics() {
+    let config = SessionConfig::new()
+        .with_target_partitions(3)
+        .with_batch_size(4096);
+    let ctx = SessionContext::with_config(config);
+    register_aggregate_csv_by_sql(&ctx).await;
+    // the join filter `a.c2 > b.c2` is evaluated on the rows matching `c1`
+    let sql = "EXPLAIN ANALYZE \
+               SELECT a.c1, b.c1 \
+               FROM aggregate_test_100 AS a \
+               JOIN aggregate_test_100 AS b ON a.c1 = b.c1 AND a.c2 > b.c2";
+    let dataframe = ctx.sql(sql).await.unwrap();
+    let physical_plan = dataframe.create_physical_plan().await.unwrap();
+    let task_ctx = ctx.task_ctx();
+    let results = collect(physical_plan, task_ctx).await.unwrap();
+    let formatted = arrow::util::pretty::pretty_format_batches(&results)
+        .unwrap()
+        .to_string();
+    println!("Query Output:\n\n{formatted}");
+
+    // the parts of `join_time` are reported next to it, as well as the output
+    // rows with and without a match
+    for metric in [
+        "elapsed_compute=",
+        "join_time=",
+        "probe_hash_time=",
+        "match_time=",
+        "filter_time=",
+        "filter_input_rows=",
+        "filter_output_rows=",
+        "output_build_time=",
+        "matched_output_rows=",
+        "unmatched_output_rows=",
+    ] {
+        assert_metrics!(&formatted, "HashJoinExec", metric);
+    }
+}
+
+#[tokio::test]
+async fn explain_analyze_hash_join_summary() {
+    let config = SessionConfig::new()
+        .with_target_partitions(3)
+        .with_batch_size(4096);
+    let ctx = SessionContext::with_config(config);
+    register_aggregate_csv_by_sql(&ctx).await;
+    // the 100 rows of the build side have 5 distinct values of `c1`
+    let sql = "EXPLAIN ANALYZE \
+               SELECT a.c1, b.c1 \
+               FROM aggregate_test_100 AS a \
+               JOIN aggregate_test_100 AS b ON a.c1 = b.c1";
+    let dataframe = ctx.sql(sql).await.unwrap();
+    let physical_plan = dataframe.create_physical_plan().await.unwrap();
+    let task_ctx = ctx.task_ctx();
+    let results = collect(physical_plan, task_ctx).await.unwrap();
+    let formatted = arrow::util::pretty::pretty_format_batches(&results)
+        .unwrap()
+        .to_string();
+    println!("Query Output:\n\n{formatted}");
+
+    // the summary of the build side is shown on the line under the join
+    let lines = formatted.lines().collect::<Vec<_>>();
+    let join_line = lines
+        .iter()
+        .position(|line| line.contains("HashJoinExec"))
+        .expect("no HashJoinExec in the plan");
+    let summary = lines[join_line + 1];
+    assert!(summary.contains("summary=[build_rows=100, "), "{summary}");
+    assert!(summary.contains(", avg_chain_length=20.00, "), "{summary}");
+
+    let load_factor = summary
+        .split("load_factor=")
+        .nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|value| value.parse::<f64>().ok())
+        .expect("no load_factor in the summary");
+    assert!(load_factor > 0.0 && load_factor <= 1.0, "{summary}");
+
+    let capacity = summary
+        .split("hash_table_capacity=")
+        .nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|value| value.parse::<usize>().ok())
+        .expect("no hash_table_capacity in the summary");
+    assert!(capacity >= 5, "{summary}");
+}
+
 #[tokio::test]
 async fn csv_explain_plans() {
     // This test verify the look of each plan in its full cycle plan creation
@@ -646,7 +733,7 @@ async fn test_physical_plan_display_indent_multi_children() {
     let expected = vec![
         "ProjectionExec: expr=[c1@0 as c1]",
         "  CoalesceBatchesExec: target_batch_size=4096",
-        "    HashJoinExec: mode=Partitioned, join_type=Inner, on=[(Column { name: \"c1\", index: 0 }, Column { name: \"c2\", index: 0 })]",
+        "    HashJoinExec: mode=Partitioned, join_type=Inner, on=[(c1@0, c2@0)]",
         "      CoalesceBatchesExec: target_batch_size=4096",
         "        RepartitionExec: partitioning=Hash([Column { name: \"c1\", index: 0 }], 9000), input_partitions=9000",
         "          RepartitionExec: partitioning=RoundRobinBatch(9000), input_partitions=1",
@@ -353,7 +353,7 @@ async fn sort_merge_join_on_date32() -> Result<()> {
     let dataframe = ctx.sql(sql).await.expect(&msg);
     let physical_plan = dataframe.create_physical_plan().await?;
     let expected = vec![
-        "SortMergeJoin: join_type=Inner, on=[(Column { name: \"c1\", index: 0 }, Column { name: \"c1\", index: 0 })]",
+        "SortMergeJoin: join_type=Inner, on=[(c1@0, c1@0)]",
         "  SortExec: expr=[c1@0 ASC]",
         "    CoalesceBatchesExec: target_batch_size=4096",
         "      RepartitionExec: partitioning=Hash([Column { name: \"c1\", index: 0 }], 2), input_partitions=2",
@@ -397,19 +397,17 @@ async fn sort_merge_join_on_decimal() -> Result<()> {
     let dataframe = ctx.sql(sql).await.expect(&msg);
     let physical_plan = dataframe.create_physical_plan().await?;
     let expected = vec![
-        "ProjectionExec: expr=[c1@0 as c1, c2@1 as c2, c3@2 as c3, c4@3 as c4, c1@5 as c1, c2@6 as c2, c3@7 as c3, c4@8 as c4]",
-        "  SortMergeJoin: join_type=Right, on=[(Column { name: \"CAST(t1.c3 AS Decimal128(10, 2))\", index: 4 }, Column { name: \"c3\", index: 2 })]",
-        "    SortExec: expr=[CAST(t1.c3 AS Decimal128(10, 2))@4 ASC]",
-        "      CoalesceBatchesExec: target_batch_size=4096",
-        "        RepartitionExec: partitioning=Hash([Column { name: \"CAST(t1.c3 AS Decimal128(10, 2))\", index: 4 }], 2), input_partitions=2",
-        "          ProjectionExec: expr=[c1@0 as c1, c2@1 as c2, c3@2 as c3, c4@3 as c4, CAST(c3@2 AS Decimal128(10, 2)) as CAST(t1.c3 AS Decimal128(10, 2))]",
-        "            RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
-        "              MemoryExec: partitions=1, partition_sizes=[1]",
-        "    SortExec: expr=[c3@2 ASC]",
-        "      CoalesceBatchesExec: target_batch_size=4096",
-        "        RepartitionExec: partitioning=Hash([Column { name: \"c3\", index: 2 }], 2), input_partitions=2",
-        "          RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
-        "            MemoryExec: partitions=1, partition_sizes=[1]",
+        "SortMergeJoin: join_type=Right, on=[(CAST(c3@2 AS Decimal128(10, 2)), c3@2)]",
+        "  SortExec: expr=[CAST(c3@2 AS Decimal128(10, 2)) ASC]",
+        "    CoalesceBatchesExec: target_batch_size=4096",
+        "      RepartitionExec: partitioning=Hash([CastExpr { expr: Column { name: \"c3\", index: 2 }, cast_type: Decimal128(10, 2), cast_options: CastOptions { safe: false, format_options: FormatOptions { safe: true, null: \"\", date_format: None, datetime_format: None, timestamp_format: None, timestamp_tz_format: None, time_format: None } } }], 2), input_partitions=2",
+        "        RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
+        "          MemoryExec: partitions=1, partition_sizes=[1]",
+        "  SortExec: expr=[c3@2 ASC]",
+        "    CoalesceBatchesExec: target_batch_size=4096",
+        "      RepartitionExec: partitioning=Hash([Column { name: \"c3\", index: 2 }], 2), input_partitions=2",
+        "        RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
+        "          MemoryExec: partitions=1, partition_sizes=[1]",
     ];
     let formatted = displayable(physical_plan.as_ref()).indent().to_string();
     let actual: Vec<&str> = formatted.trim().lines().collect();
@@ -455,7 +453,7 @@ async fn left_semi_join() -> Result<()> {
                 "SortPreservingMergeExec: [t1_id@0 ASC NULLS LAST]",
                 "  SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "    CoalesceBatchesExec: target_batch_size=4096",
-                "      HashJoinExec: mode=Partitioned, join_type=LeftSemi, on=[(Column { name: \"t1_id\", index: 0 }, Column { name: \"t2_id\", index: 0 })]",
+                "      HashJoinExec: mode=Partitioned, join_type=LeftSemi, on=[(t1_id@0, t2_id@0)]",
                 "        CoalesceBatchesExec: target_batch_size=4096",
                 "          RepartitionExec: partitioning=Hash([Column { name: \"t1_id\", index: 0 }], 2), input_partitions=2",
                 "            RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
@@ -469,7 +467,7 @@ async fn left_semi_join() -> Result<()> {
             vec![
                 "SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "  CoalesceBatchesExec: target_batch_size=4096",
-                "    HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(Column { name: \"t1_id\", index: 0 }, Column { name: \"t2_id\", index: 0 })]",
+                "    HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(t1_id@0, t2_id@0)]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
             ]
@@ -531,7 +529,7 @@ async fn left_semi_join() -> Result<()> {
                 "SortPreservingMergeExec: [t1_id@0 ASC NULLS LAST]",
                 "  SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "    CoalesceBatchesExec: target_batch_size=4096",
-                "      HashJoinExec: mode=Partitioned, join_type=LeftSemi, on=[(Column { name: \"t1_id\", index: 0 }, Column { name: \"t2_id\", index: 0 })]",
+                "      HashJoinExec: mode=Partitioned, join_type=LeftSemi, on=[(t1_id@0, t2_id@0)]",
                 "        CoalesceBatchesExec: target_batch_size=4096",
                 "          RepartitionExec: partitioning=Hash([Column { name: \"t1_id\", index: 0 }], 2), input_partitions=2",
                 "            RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
@@ -545,7 +543,7 @@ async fn left_semi_join() -> Result<()> {
             vec![
                 "SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "  CoalesceBatchesExec: target_batch_size=4096",
-                "    HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(Column { name: \"t1_id\", index: 0 }, Column { name: \"t2_id\", index: 0 })]",
+                "    HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(t1_id@0, t2_id@0)]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
             ]
@@ -616,7 +614,7 @@ async fn right_semi_join() -> Result<()> {
                 "SortPreservingMergeExec: [t1_id@0 ASC NULLS LAST]",
                  "  SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                  "    CoalesceBatchesExec: target_batch_size=4096",
-                 "      HashJoinExec: mode=Partitioned, join_type=RightSemi, on=[(Column { name: \"t2_id\", index: 0 }, Column { name: \"t1_id\", index: 0 })], filter=t2_name@1 != t1_name@0",
+                 "      HashJoinExec: mode=Partitioned, join_type=RightSemi, on=[(t2_id@0, t1_id@0)], filter=t2_name@1 != t1_name@0",
                  "        CoalesceBatchesExec: target_batch_size=4096",
                  "          RepartitionExec: partitioning=Hash([Column { name: \"t2_id\", index: 0 }], 2), input_partitions=2",
                  "            RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
@@ -630,7 +628,7 @@ async fn right_semi_join() -> Result<()> {
             vec![
                 "SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "  CoalesceBatchesExec: target_batch_size=4096",
-                "    HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(Column { name: \"t2_id\", index: 0 }, Column { name: \"t1_id\", index: 0 })], filter=t2_name@1 != t1_name@0",
+                "    HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(t2_id@0, t1_id@0)], filter=t2_name@1 != t1_name@0",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
             ]
@@ -661,7 +659,7 @@ async fn right_semi_join() -> Result<()> {
                 "SortPreservingMergeExec: [t1_id@0 ASC NULLS LAST]",
                 "  SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "    CoalesceBatchesExec: target_batch_size=4096",
-                "      HashJoinExec: mode=Partitioned, join_type=RightSemi, on=[(Column { name: \"t2_id\", index: 0 }, Column { name: \"t1_id\", index: 0 })], filter=t2_name@0 != t1_name@1",
+                "      HashJoinExec: mode=Partitioned, join_type=RightSemi, on=[(t2_id@0, t1_id@0)], filter=t2_name@0 != t1_name@1",
                 "        CoalesceBatchesExec: target_batch_size=4096",
                 "          RepartitionExec: partitioning=Hash([Column { name: \"t2_id\", index: 0 }], 2), input_partitions=2",
                 "            RepartitionExec: partitioning=RoundRobinBatch(2), input_partitions=1",
@@ -675,7 +673,7 @@ async fn right_semi_join() -> Result<()> {
             vec![
                 "SortExec: expr=[t1_id@0 ASC NULLS LAST]",
                 "  CoalesceBatchesExec: target_batch_size=4096",
-                "    HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(Column { name: \"t2_id\", index: 0 }, Column { name: \"t1_id\", index: 0 })], filter=t2_name@0 != t1_name@1",
+                "    HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(t2_id@0, t1_id@0)], filter=t2_name@0 != t1_name@1",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
                 "      MemoryExec: partitions=1, partition_sizes=[1]",
             ]
@@ -700,3 +698,122 @@ async fn right_semi_join() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn hash_join_single_partition_threshold() -> Result<()> {
+    use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+
+    /// Returns the mode of the first hash join in `plan`, and the estimated
+    /// size in bytes of its build side
+    fn hash_join(plan: &Arc<dyn ExecutionPlan>) -> Option<(PartitionMode, usize)> {
+        match plan.as_any().downcast_ref::<HashJoinExec>() {
+            Some(join) => Some((
+                *join.partition_mode(),
+                join.left().statistics().total_byte_size?,
+            )),
+            None => plan.children().iter().find_map(hash_join),
+        }
+    }
+
+    let table = |num_rows: u32| -> Result<Arc<MemTable>> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("id", DataType::UInt32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from_iter_values(0..num_rows))],
+        )?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    };
+    let plan_join = |threshold: usize| async move {
+        let mut config = SessionConfig::new()
+            .with_target_partitions(4)
+            .with_repartition_joins(true)
+            .with_collect_statistics(true);
+        config
+            .options_mut()
+            .optimizer
+            .hash_join_single_partition_threshold = threshold;
+        let ctx = SessionContext::with_config(config);
+        ctx.register_table("small", table(100)?)?;
+        ctx.register_table("big", table(200)?)?;
+        let plan = ctx
+            .sql("SELECT * FROM small JOIN big ON small.id = big.id")
+            .await?
+            .create_physical_plan()
+            .await?;
+        Ok::<_, DataFusionError>(
+            hash_join(&plan).expect("the plan should have a hash join"),
+        )
+    };
+
+    // collecting a build side of similar size as the probe side is not
+    // cheaper than repartitioning both sides
+    let (mode, build_size) = plan_join(0).await?;
+    assert_eq!(mode, PartitionMode::Partitioned);
+
+    // the build side is collected below the threshold
+    let (mode, _) = plan_join(build_size + 1).await?;
+    assert_eq!(mode, PartitionMode::CollectLeft);
+    let (mode, _) = plan_join(build_size).await?;
+    assert_eq!(mode, PartitionMode::Partitioned);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hash_join_default_filter_selectivity() -> Result<()> {
+    use datafusion::physical_plan::joins::HashJoinExec;
+
+    /// Returns the default filter selectivity of the first hash join in `plan`
+    fn selectivity(plan: &Arc<dyn ExecutionPlan>) -> Option<f64> {
+        match plan.as_any().downcast_ref::<HashJoinExec>() {
+            Some(join) => Some(join.default_filter_selectivity()),
+            None => plan.children().iter().find_map(selectivity),
+        }
+    }
+
+    let table = |num_rows: u32| -> Result<Arc<MemTable>> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("id", DataType::UInt32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from_iter_values(0..num_rows))],
+        )?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    };
+    let plan_join = |repartition_joins: bool, selectivity: f64| async move {
+        let mut config = SessionConfig::new()
+            .with_target_partitions(4)
+            .with_repartition_joins(repartition_joins)
+            .with_collect_statistics(true);
+        config
+            .options_mut()
+            .optimizer
+            .hash_join_default_filter_selectivity = selectivity;
+        let ctx = SessionContext::with_config(config);
+        ctx.register_table("small", table(100)?)?;
+        ctx.register_table("big", table(200)?)?;
+        ctx.sql(
+            "SELECT * FROM small JOIN big \
+             ON small.id = big.id AND small.id + big.id > 10",
+        )
+        .await?
+        .create_physical_plan()
+        .await
+    };
+
+    // the selectivity survives the join being rebuilt by the physical
+    // optimizer, whether it ends up collecting or repartitioning its inputs
+    for repartition_joins in [false, true] {
+        let plan = plan_join(repartition_joins, 0.5).await?;
+        assert_eq!(selectivity(&plan), Some(0.5), "{repartition_joins}");
+    }
+
+    let err = plan_join(false, 1.5).await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "The default selectivity of a join filter must be between 0 and 1, got 1.5"
+    );
+
+    Ok(())
+}
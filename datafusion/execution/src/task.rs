@@ -50,6 +50,8 @@ pub struct TaskContext {
     aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Batch size overriding the one the plan was created with, if any
+    batch_size_override: Option<usize>,
 }
 
 impl TaskContext {
@@ -69,6 +71,7 @@ impl TaskContext {
             scalar_functions,
             aggregate_functions,
             runtime,
+            batch_size_override: None,
         }
     }
 
@@ -127,6 +130,47 @@ impl TaskContext {
     pub fn runtime_env(&self) -> Arc<RuntimeEnv> {
         self.runtime.clone()
     }
+
+    /// Override the `batch_size` of the session for this execution only.
+    ///
+    /// Besides the operators that read the batch size from the [`SessionConfig`]
+    /// when they are executed, this also applies to operators whose batch size
+    /// was captured from the session when the plan was created, such as
+    /// `CoalesceBatchesExec`, see [`Self::batch_size_override`].
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.session_config = self.session_config.with_batch_size(batch_size);
+        self.batch_size_override = Some(batch_size);
+        self
+    }
+
+    /// Return the batch size set with [`Self::with_batch_size`], which takes
+    /// precedence over any batch size captured when the plan was created
+    pub fn batch_size_override(&self) -> Option<usize> {
+        self.batch_size_override
+    }
+
+    /// Override the `target_partitions` of the session for this execution only.
+    ///
+    /// Note the partitioning of an `ExecutionPlan` is decided when it is
+    /// created, so this only affects the operators reading the target partitions
+    /// from the [`SessionConfig`] when they are executed.
+    pub fn with_target_partitions(mut self, target_partitions: usize) -> Self {
+        self.session_config = self
+            .session_config
+            .with_target_partitions(target_partitions);
+        self
+    }
+
+    /// Use `memory_pool` instead of the [`MemoryPool`] of the [`RuntimeEnv`] for
+    /// this execution only, e.g. to limit the memory of a single query.
+    pub fn with_memory_pool(mut self, memory_pool: Arc<dyn MemoryPool>) -> Self {
+        self.runtime = Arc::new(RuntimeEnv {
+            memory_pool,
+            disk_manager: self.runtime.disk_manager.clone(),
+            object_store_registry: self.runtime.object_store_registry.clone(),
+        });
+        self
+    }
 }
 
 impl FunctionRegistry for TaskContext {
@@ -158,6 +202,7 @@ impl FunctionRegistry for TaskContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory_pool::GreedyMemoryPool;
     use datafusion_common::{config::ConfigExtension, extensions_options};
 
     extensions_options! {
@@ -200,4 +245,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn task_context_overrides() {
+        let runtime = Arc::new(RuntimeEnv::default());
+        let session_config = SessionConfig::new()
+            .with_batch_size(8192)
+            .with_target_partitions(16);
+        let task_context = TaskContext::new(
+            None,
+            "session_id".to_string(),
+            session_config,
+            HashMap::default(),
+            HashMap::default(),
+            runtime.clone(),
+        );
+        assert_eq!(task_context.batch_size_override(), None);
+
+        let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1024));
+        let task_context = task_context
+            .with_batch_size(100)
+            .with_target_partitions(1)
+            .with_memory_pool(pool.clone());
+
+        assert_eq!(task_context.batch_size_override(), Some(100));
+        assert_eq!(task_context.session_config().batch_size(), 100);
+        assert_eq!(task_context.session_config().target_partitions(), 1);
+        assert!(Arc::ptr_eq(task_context.memory_pool(), &pool));
+        assert!(Arc::ptr_eq(
+            &task_context.runtime_env().disk_manager,
+            &runtime.disk_manager
+        ));
+        // the runtime the task context was created with is left untouched
+        assert!(!Arc::ptr_eq(&runtime.memory_pool, &pool));
+    }
 }
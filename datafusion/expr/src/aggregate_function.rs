@@ -132,7 +132,9 @@ impl FromStr for AggregateFunction {
             "bit_xor" => AggregateFunction::BitXor,
             "bool_and" => AggregateFunction::BoolAnd,
             "bool_or" => AggregateFunction::BoolOr,
+            "any" => AggregateFunction::BoolOr,
             "count" => AggregateFunction::Count,
+            "every" => AggregateFunction::BoolAnd,
             "max" => AggregateFunction::Max,
             "mean" => AggregateFunction::Avg,
             "median" => AggregateFunction::Median,
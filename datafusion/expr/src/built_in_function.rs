@@ -280,6 +280,34 @@ impl BuiltinScalarFunction {
                 | BuiltinScalarFunction::MakeArray
         )
     }
+
+    /// Returns true if the function is a deterministic, element-wise transform of
+    /// the strings of its first argument. When this argument is dictionary encoded
+    /// and the other arguments are constant, the function can be applied once to
+    /// the dictionary values and its result keeps the dictionary encoding.
+    pub fn preserves_dictionary_encoding(&self) -> bool {
+        matches!(
+            self,
+            BuiltinScalarFunction::Btrim
+                | BuiltinScalarFunction::InitCap
+                | BuiltinScalarFunction::Left
+                | BuiltinScalarFunction::Lower
+                | BuiltinScalarFunction::Lpad
+                | BuiltinScalarFunction::Ltrim
+                | BuiltinScalarFunction::Repeat
+                | BuiltinScalarFunction::Replace
+                | BuiltinScalarFunction::Reverse
+                | BuiltinScalarFunction::Right
+                | BuiltinScalarFunction::Rpad
+                | BuiltinScalarFunction::Rtrim
+                | BuiltinScalarFunction::SplitPart
+                | BuiltinScalarFunction::Substr
+                | BuiltinScalarFunction::Translate
+                | BuiltinScalarFunction::Trim
+                | BuiltinScalarFunction::Upper
+        )
+    }
+
     /// Returns the [Volatility] of the builtin function.
     pub fn volatility(&self) -> Volatility {
         match self {
@@ -432,6 +460,17 @@ impl BuiltinScalarFunction {
             ));
         }
 
+        // the function is applied to the values of a dictionary encoded first
+        // argument and returns a dictionary with the same keys
+        if self.preserves_dictionary_encoding() {
+            if let Some(Dictionary(key_type, value_type)) = input_expr_types.first() {
+                let mut value_types = input_expr_types.to_vec();
+                value_types[0] = value_type.as_ref().clone();
+                let return_type = self.return_type(&value_types)?;
+                return Ok(Dictionary(key_type.clone(), Box::new(return_type)));
+            }
+        }
+
         // verify that this is a valid set of data types for this function
         data_types(input_expr_types, &self.signature()).map_err(|_| {
             DataFusionError::Plan(self.generate_signature_error_msg(input_expr_types))
@@ -39,6 +39,7 @@ pub mod field_util;
 pub mod function;
 mod literal;
 pub mod logical_plan;
+pub mod null_rejection;
 mod nullif;
 mod operator;
 mod signature;
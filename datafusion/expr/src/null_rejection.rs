@@ -0,0 +1,198 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Null rejection of predicates, which tells whether the outer join below a
+//! filter can be simplified. Shared by the logical and physical optimizer rules
+//! simplifying outer joins, for logical and physical expressions alike.
+
+use crate::expr::{BinaryExpr, Cast, TryCast};
+use crate::{Expr, JoinType, Operator};
+
+/// The shape of an expression as far as its result for null inputs is
+/// concerned, see [`is_null_rejecting`]
+pub enum NullRejectionNode<'a, E: ?Sized> {
+    /// A column
+    Column,
+    /// `left op right`
+    Binary(&'a E, Operator, &'a E),
+    /// `arg IS NOT NULL`
+    IsNotNull(&'a E),
+    /// An expression which is null if its argument is null, like `NOT`, a
+    /// negation or a cast
+    NullPropagating(&'a E),
+    /// Any other expression, which may not be null for null inputs
+    Other,
+}
+
+/// An expression whose null rejection can be analyzed by [`is_null_rejecting`]
+pub trait NullRejection {
+    /// Returns the shape of the expression
+    fn null_rejection_node(&self) -> NullRejectionNode<'_, Self>;
+}
+
+impl NullRejection for Expr {
+    fn null_rejection_node(&self) -> NullRejectionNode<'_, Self> {
+        match self {
+            Expr::Column(_) => NullRejectionNode::Column,
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                NullRejectionNode::Binary(left, *op, right)
+            }
+            Expr::IsNotNull(arg) => NullRejectionNode::IsNotNull(arg),
+            Expr::Not(arg) | Expr::Negative(arg) => {
+                NullRejectionNode::NullPropagating(arg)
+            }
+            Expr::Cast(Cast { expr, .. }) | Expr::TryCast(TryCast { expr, .. }) => {
+                NullRejectionNode::NullPropagating(expr)
+            }
+            _ => NullRejectionNode::Other,
+        }
+    }
+}
+
+/// Returns true if `predicate` is never true when the columns for which
+/// `is_null_column` returns true are null, so that it filters out the rows a
+/// join padded with nulls in these columns.
+///
+/// For example, `r.x > 5` and `r.x IS NOT NULL` reject the rows padded with
+/// nulls on the right side of a join, while `r.x IS NULL` and
+/// `coalesce(r.x, 0) > 5` do not.
+pub fn is_null_rejecting<E: NullRejection + ?Sized>(
+    predicate: &E,
+    is_null_column: &dyn Fn(&E) -> bool,
+) -> bool {
+    match predicate.null_rejection_node() {
+        NullRejectionNode::Binary(left, Operator::And, right) => {
+            is_null_rejecting(left, is_null_column)
+                || is_null_rejecting(right, is_null_column)
+        }
+        NullRejectionNode::Binary(left, Operator::Or, right) => {
+            is_null_rejecting(left, is_null_column)
+                && is_null_rejecting(right, is_null_column)
+        }
+        NullRejectionNode::IsNotNull(arg) => is_null_on_null_input(arg, is_null_column),
+        _ => is_null_on_null_input(predicate, is_null_column),
+    }
+}
+
+/// Returns true if `expr` is known to evaluate to null when the columns for
+/// which `is_null_column` returns true are null
+fn is_null_on_null_input<E: NullRejection + ?Sized>(
+    expr: &E,
+    is_null_column: &dyn Fn(&E) -> bool,
+) -> bool {
+    match expr.null_rejection_node() {
+        NullRejectionNode::Column => is_null_column(expr),
+        // `NULL AND false` and `NULL OR true` are not null
+        NullRejectionNode::Binary(left, Operator::And | Operator::Or, right) => {
+            is_null_on_null_input(left, is_null_column)
+                && is_null_on_null_input(right, is_null_column)
+        }
+        // the distinct operators are never null
+        NullRejectionNode::Binary(
+            _,
+            Operator::IsDistinctFrom | Operator::IsNotDistinctFrom,
+            _,
+        ) => false,
+        NullRejectionNode::Binary(left, _, right) => {
+            is_null_on_null_input(left, is_null_column)
+                || is_null_on_null_input(right, is_null_column)
+        }
+        NullRejectionNode::NullPropagating(arg) => {
+            is_null_on_null_input(arg, is_null_column)
+        }
+        NullRejectionNode::IsNotNull(_) | NullRejectionNode::Other => false,
+    }
+}
+
+/// Returns the type of the outer join `join_type` below a filter which rejects
+/// the rows it pads with nulls on the left side if `left_rejected`, and on the
+/// right side if `right_rejected`
+pub fn simplify_outer_join_type(
+    join_type: JoinType,
+    left_rejected: bool,
+    right_rejected: bool,
+) -> JoinType {
+    match join_type {
+        JoinType::Left if right_rejected => JoinType::Inner,
+        JoinType::Right if left_rejected => JoinType::Inner,
+        JoinType::Full => match (left_rejected, right_rejected) {
+            (true, true) => JoinType::Inner,
+            (true, false) => JoinType::Left,
+            (false, true) => JoinType::Right,
+            (false, false) => JoinType::Full,
+        },
+        join_type => join_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{binary_expr, coalesce, col, lit};
+
+    fn rejects_nulls_of_t2(predicate: &Expr) -> bool {
+        is_null_rejecting(predicate, &|expr: &Expr| match expr {
+            Expr::Column(column) => {
+                column.relation.as_ref().map(|relation| relation.table()) == Some("t2")
+            }
+            _ => false,
+        })
+    }
+
+    #[test]
+    fn null_rejecting_predicates() {
+        for (predicate, rejects) in [
+            (col("t2.b").gt(lit(5)), true),
+            (col("t2.b").is_not_null(), true),
+            ((col("t2.b") + col("t1.b")).eq(lit(5)), true),
+            (col("t2.b").gt(lit(5)).and(col("t1.b").gt(lit(5))), true),
+            (col("t2.b").gt(lit(5)).or(col("t2.c").lt(lit(5))), true),
+            (col("t2.b").gt(lit(5)).or(col("t1.b").lt(lit(5))), false),
+            (col("t2.b").is_null(), false),
+            (coalesce(vec![col("t2.b"), lit(0)]).gt(lit(5)), false),
+            (col("t1.b").gt(lit(5)), false),
+            (
+                binary_expr(col("t2.b"), Operator::IsNotDistinctFrom, col("t1.b")),
+                false,
+            ),
+            // `NOT (NULL AND false)` is true
+            (!(col("t2.b").gt(lit(5)).and(col("t1.b").gt(lit(5)))), false),
+            (!(col("t2.b").gt(lit(5)).and(col("t2.c").gt(lit(5)))), true),
+            (!col("t2.b").is_not_null(), false),
+        ] {
+            assert_eq!(rejects_nulls_of_t2(&predicate), rejects, "{predicate}");
+        }
+    }
+
+    #[test]
+    fn simplified_outer_join_types() {
+        for (join_type, left_rejected, right_rejected, simplified) in [
+            (JoinType::Left, true, false, JoinType::Left),
+            (JoinType::Left, false, true, JoinType::Inner),
+            (JoinType::Right, true, false, JoinType::Inner),
+            (JoinType::Full, true, false, JoinType::Left),
+            (JoinType::Full, false, true, JoinType::Right),
+            (JoinType::Full, true, true, JoinType::Inner),
+            (JoinType::LeftSemi, true, true, JoinType::LeftSemi),
+        ] {
+            assert_eq!(
+                simplify_outer_join_type(join_type, left_rejected, right_rejected),
+                simplified
+            );
+        }
+    }
+}
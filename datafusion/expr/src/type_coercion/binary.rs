@@ -25,7 +25,9 @@ use arrow::datatypes::{
 use datafusion_common::DataFusionError;
 use datafusion_common::Result;
 
-use crate::type_coercion::{is_datetime, is_decimal, is_interval, is_numeric};
+use crate::type_coercion::{
+    is_datetime, is_decimal, is_integer, is_interval, is_numeric,
+};
 use crate::Operator;
 
 /// Returns the result type of applying mathematics operations such as
@@ -129,6 +131,13 @@ pub fn get_result_type(
         {
             mathematics_temporal_result_type(lhs_type, rhs_type)
         }
+        // interval * integer and integer * interval keep the unit of the interval
+        Operator::Multiply if is_interval(lhs_type) && is_integer(rhs_type) => {
+            Some(lhs_type.clone())
+        }
+        Operator::Multiply if is_integer(lhs_type) && is_interval(rhs_type) => {
+            Some(rhs_type.clone())
+        }
         // following same with `coerce_types`
         Operator::BitwiseAnd
         | Operator::BitwiseOr
@@ -769,14 +778,9 @@ fn temporal_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataTyp
         }
         (Timestamp(lhs_unit, lhs_tz), Timestamp(rhs_unit, rhs_tz)) => {
             let tz = match (lhs_tz, rhs_tz) {
-                // can't cast across timezones
-                (Some(lhs_tz), Some(rhs_tz)) => {
-                    if lhs_tz != rhs_tz {
-                        return None;
-                    } else {
-                        Some(lhs_tz.clone())
-                    }
-                }
+                // annotated timestamps are UTC instants, so the annotation of
+                // either side can be used to compare them
+                (Some(lhs_tz), Some(_)) => Some(lhs_tz.clone()),
                 (Some(lhs_tz), None) => Some(lhs_tz.clone()),
                 (None, Some(rhs_tz)) => Some(rhs_tz.clone()),
                 (None, None) => None,
@@ -1033,6 +1037,77 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_timestamp_timezone_coercion() -> Result<()> {
+        let utc = DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()));
+        let plus_two = DataType::Timestamp(TimeUnit::Millisecond, Some("+02:00".into()));
+        let naive = DataType::Timestamp(TimeUnit::Nanosecond, None);
+
+        // differing annotations coerce to the annotation of the left side
+        test_coercion_binary_rule!(utc, plus_two, Operator::Eq, utc);
+        test_coercion_binary_rule!(
+            plus_two,
+            utc,
+            Operator::Lt,
+            DataType::Timestamp(TimeUnit::Millisecond, Some("+02:00".into()))
+        );
+        // timestamps without annotation coerce to the annotated side
+        test_coercion_binary_rule!(naive, utc, Operator::GtEq, utc);
+        test_coercion_binary_rule!(utc, naive, Operator::NotEq, utc);
+        test_coercion_binary_rule!(
+            DataType::Utf8,
+            plus_two,
+            Operator::Eq,
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("+02:00".into()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_arithmetic_result_type() -> Result<()> {
+        use arrow::datatypes::IntervalUnit::*;
+
+        let units = [YearMonth, DayTime, MonthDayNano];
+        for lhs in &units {
+            for rhs in &units {
+                let expected = if lhs == rhs {
+                    DataType::Interval(lhs.clone())
+                } else {
+                    DataType::Interval(MonthDayNano)
+                };
+                for op in [Operator::Plus, Operator::Minus] {
+                    let result_type = get_result_type(
+                        &DataType::Interval(lhs.clone()),
+                        &op,
+                        &DataType::Interval(rhs.clone()),
+                    )?;
+                    assert_eq!(result_type, expected);
+                }
+            }
+
+            let interval = DataType::Interval(lhs.clone());
+            for integer in [DataType::Int8, DataType::Int64, DataType::UInt32] {
+                let op = Operator::Multiply;
+                assert_eq!(get_result_type(&interval, &op, &integer)?, interval);
+                assert_eq!(get_result_type(&integer, &op, &interval)?, interval);
+            }
+            let result_type =
+                get_result_type(&interval, &Operator::Lt, &DataType::Interval(DayTime))?;
+            assert_eq!(result_type, DataType::Boolean);
+        }
+
+        let err = get_result_type(
+            &DataType::Interval(DayTime),
+            &Operator::Multiply,
+            &DataType::Float64,
+        )
+        .unwrap_err()
+        .to_string();
+        assert_contains!(&err, "Can not evaluate Interval(DayTime) * Float64");
+
+        Ok(())
+    }
+
     #[test]
     fn test_type_coercion() -> Result<()> {
         // test like coercion rule
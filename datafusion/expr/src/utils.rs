@@ -1205,7 +1205,7 @@ pub fn can_hash(data_type: &DataType) -> bool {
         DataType::UInt64 => true,
         DataType::Float32 => true,
         DataType::Float64 => true,
-        DataType::Timestamp(time_unit, None) => match time_unit {
+        DataType::Timestamp(time_unit, _) => match time_unit {
             TimeUnit::Second => true,
             TimeUnit::Millisecond => true,
             TimeUnit::Microsecond => true,
@@ -1213,10 +1213,23 @@ pub fn can_hash(data_type: &DataType) -> bool {
         },
         DataType::Utf8 => true,
         DataType::LargeUtf8 => true,
+        DataType::Binary => true,
+        DataType::LargeBinary => true,
         DataType::Decimal128(_, _) => true,
+        DataType::Decimal256(_, _) => true,
         DataType::Date32 => true,
         DataType::Date64 => true,
+        DataType::Time32(time_unit) => match time_unit {
+            TimeUnit::Second | TimeUnit::Millisecond => true,
+            TimeUnit::Microsecond | TimeUnit::Nanosecond => false,
+        },
+        DataType::Time64(time_unit) => match time_unit {
+            TimeUnit::Microsecond | TimeUnit::Nanosecond => true,
+            TimeUnit::Second | TimeUnit::Millisecond => false,
+        },
+        DataType::Interval(_) => true,
         DataType::FixedSizeBinary(_) => true,
+        DataType::Struct(fields) => fields.iter().all(|f| can_hash(f.data_type())),
         DataType::Dictionary(key_type, value_type)
             if *value_type.as_ref() == DataType::Utf8 =>
         {
@@ -37,17 +37,19 @@ use datafusion_expr::type_coercion::functions::data_types;
 use datafusion_expr::type_coercion::other::{
     get_coerce_type_for_case_expression, get_coerce_type_for_list,
 };
-use datafusion_expr::type_coercion::{is_datetime, is_numeric, is_utf8_or_large_utf8};
+use datafusion_expr::type_coercion::{
+    is_datetime, is_integer, is_numeric, is_utf8_or_large_utf8,
+};
 use datafusion_expr::utils::from_plan;
 use datafusion_expr::{
     aggregate_function, is_false, is_not_false, is_not_true, is_not_unknown, is_true,
-    is_unknown, type_coercion, AggregateFunction, Expr, LogicalPlan, Operator,
-    Projection, WindowFrame, WindowFrameBound, WindowFrameUnits,
+    is_unknown, type_coercion, AggregateFunction, BuiltinScalarFunction, Expr,
+    LogicalPlan, Operator, Projection, WindowFrame, WindowFrameBound, WindowFrameUnits,
 };
 use datafusion_expr::{ExprSchemable, Signature};
 
 use crate::analyzer::AnalyzerRule;
-use crate::utils::{merge_schema, rewrite_preserving_name};
+use crate::utils::{is_utc_time_zone, merge_schema, rewrite_preserving_name};
 
 #[derive(Default)]
 pub struct TypeCoercion {}
@@ -63,8 +65,12 @@ impl AnalyzerRule for TypeCoercion {
         "type_coercion"
     }
 
-    fn analyze(&self, plan: LogicalPlan, _: &ConfigOptions) -> Result<LogicalPlan> {
-        analyze_internal(&DFSchema::empty(), &plan)
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        analyze_internal(
+            &DFSchema::empty(),
+            &plan,
+            config.execution.time_zone.as_deref(),
+        )
     }
 }
 
@@ -72,12 +78,14 @@ fn analyze_internal(
     // use the external schema to handle the correlated subqueries case
     external_schema: &DFSchema,
     plan: &LogicalPlan,
+    // the session time zone, used to convert timestamps without a timezone
+    time_zone: Option<&str>,
 ) -> Result<LogicalPlan> {
     // optimize child plans first
     let new_inputs = plan
         .inputs()
         .iter()
-        .map(|p| analyze_internal(external_schema, p))
+        .map(|p| analyze_internal(external_schema, p, time_zone))
         .collect::<Result<Vec<_>>>()?;
     // get schema representing all available input fields. This is used for data type
     // resolution only, so order does not matter here
@@ -94,8 +102,14 @@ fn analyze_internal(
     // select t2.c2 from t1 where t1.c1 in (select t2.c1 from t2 where t2.c2=t1.c3)
     schema.merge(external_schema);
 
+    let schema = Arc::new(schema);
     let mut expr_rewrite = TypeCoercionRewriter {
-        schema: Arc::new(schema),
+        schema: schema.clone(),
+        time_zone: time_zone.map(String::from),
+    };
+    let mut time_zone_rewrite = TimeZoneCastRewriter {
+        schema,
+        time_zone: time_zone.unwrap_or(UTC),
     };
 
     let new_expr = plan
@@ -104,7 +118,8 @@ fn analyze_internal(
         .map(|expr| {
             // ensure aggregate names don't change:
             // https://github.com/apache/arrow-datafusion/issues/3555
-            rewrite_preserving_name(expr, &mut expr_rewrite)
+            let expr = rewrite_preserving_name(expr, &mut expr_rewrite)?;
+            rewrite_preserving_name(expr, &mut time_zone_rewrite)
         })
         .collect::<Result<Vec<_>>>()?;
 
@@ -120,6 +135,17 @@ fn analyze_internal(
 
 pub(crate) struct TypeCoercionRewriter {
     pub(crate) schema: DFSchemaRef,
+    /// The session time zone, passed on to subqueries
+    pub(crate) time_zone: Option<String>,
+}
+
+impl TypeCoercionRewriter {
+    pub(crate) fn new(schema: DFSchemaRef) -> Self {
+        Self {
+            schema,
+            time_zone: None,
+        }
+    }
 }
 
 impl TreeNodeRewriter for TypeCoercionRewriter {
@@ -135,14 +161,19 @@ impl TreeNodeRewriter for TypeCoercionRewriter {
                 subquery,
                 outer_ref_columns,
             }) => {
-                let new_plan = analyze_internal(&self.schema, &subquery)?;
+                let new_plan =
+                    analyze_internal(&self.schema, &subquery, self.time_zone.as_deref())?;
                 Ok(Expr::ScalarSubquery(Subquery {
                     subquery: Arc::new(new_plan),
                     outer_ref_columns,
                 }))
             }
             Expr::Exists(Exists { subquery, negated }) => {
-                let new_plan = analyze_internal(&self.schema, &subquery.subquery)?;
+                let new_plan = analyze_internal(
+                    &self.schema,
+                    &subquery.subquery,
+                    self.time_zone.as_deref(),
+                )?;
                 Ok(Expr::Exists(Exists {
                     subquery: Subquery {
                         subquery: Arc::new(new_plan),
@@ -156,7 +187,11 @@ impl TreeNodeRewriter for TypeCoercionRewriter {
                 subquery,
                 negated,
             }) => {
-                let new_plan = analyze_internal(&self.schema, &subquery.subquery)?;
+                let new_plan = analyze_internal(
+                    &self.schema,
+                    &subquery.subquery,
+                    self.time_zone.as_deref(),
+                )?;
                 let expr_type = expr.get_type(&self.schema)?;
                 let subquery_type = new_plan.schema().field(0).data_type();
                 let common_type = comparison_coercion(&expr_type, subquery_type).ok_or(DataFusionError::Plan(
@@ -254,6 +289,50 @@ impl TreeNodeRewriter for TypeCoercionRewriter {
                         &DataType::Interval(_),
                         DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _),
                     ) if matches!(op, Operator::Plus) => Ok(expr),
+                    // Intervals of different units are added, subtracted and compared
+                    // without casting them to a common unit.
+                    (&DataType::Interval(_), &DataType::Interval(_))
+                        if matches!(
+                            op,
+                            Operator::Plus
+                                | Operator::Minus
+                                | Operator::Eq
+                                | Operator::NotEq
+                                | Operator::Lt
+                                | Operator::LtEq
+                                | Operator::Gt
+                                | Operator::GtEq
+                                | Operator::IsDistinctFrom
+                                | Operator::IsNotDistinctFrom
+                        ) =>
+                    {
+                        Ok(expr)
+                    }
+                    // Intervals are multiplied by 64-bit integers
+                    (&DataType::Interval(_), rhs_type)
+                        if op == Operator::Multiply && is_integer(rhs_type) =>
+                    {
+                        let expr = Expr::BinaryExpr(BinaryExpr::new(
+                            left.clone(),
+                            op,
+                            Box::new(
+                                right.clone().cast_to(&DataType::Int64, &self.schema)?,
+                            ),
+                        ));
+                        Ok(expr)
+                    }
+                    (lhs_type, &DataType::Interval(_))
+                        if op == Operator::Multiply && is_integer(lhs_type) =>
+                    {
+                        let expr = Expr::BinaryExpr(BinaryExpr::new(
+                            Box::new(
+                                left.clone().cast_to(&DataType::Int64, &self.schema)?,
+                            ),
+                            op,
+                            right.clone(),
+                        ));
+                        Ok(expr)
+                    }
                     (DataType::Timestamp(_, _), DataType::Timestamp(_, _))
                         if op.is_numerical_operators() =>
                     {
@@ -387,10 +466,10 @@ impl TreeNodeRewriter for TypeCoercionRewriter {
                 Ok(expr)
             }
             Expr::ScalarFunction(ScalarFunction { fun, args }) => {
-                let nex_expr = coerce_arguments_for_signature(
+                let nex_expr = coerce_arguments_for_scalar_function(
+                    &fun,
                     args.as_slice(),
                     &self.schema,
-                    &fun.signature(),
                 )?;
                 let expr = Expr::ScalarFunction(ScalarFunction::new(fun, nex_expr));
                 Ok(expr)
@@ -452,6 +531,84 @@ impl TreeNodeRewriter for TypeCoercionRewriter {
     }
 }
 
+/// Time zone of timestamps without a timezone when the session has none
+const UTC: &str = "+00:00";
+
+/// Rewrites casts between timestamps with and without a timezone annotation,
+/// including the parsing of strings, to convert through the session time zone.
+///
+/// A timestamp without annotation is the wall clock time in the session time
+/// zone, so e.g. `CAST(naive AS Timestamp(Nanosecond, Some("UTC")))` is
+/// rewritten as `CAST(CAST(naive AS Timestamp(Nanosecond, Some("+02:00")))
+/// AS Timestamp(Nanosecond, Some("UTC")))` for the session time zone `+02:00`.
+/// The inner cast converts the wall clock time to an instant and the outer
+/// one only changes the annotation.
+struct TimeZoneCastRewriter<'a> {
+    schema: DFSchemaRef,
+    time_zone: &'a str,
+}
+
+impl TimeZoneCastRewriter<'_> {
+    /// Returns the type to cast to before casting from `from_type` to
+    /// `to_type`, if the cast has to convert through the session time zone
+    fn session_time_zone_type(
+        &self,
+        from_type: &DataType,
+        to_type: &DataType,
+    ) -> Option<DataType> {
+        match (from_type, to_type) {
+            (
+                DataType::Timestamp(_, None) | DataType::Utf8 | DataType::LargeUtf8,
+                DataType::Timestamp(unit, Some(tz)),
+            )
+            | (DataType::Timestamp(_, Some(tz)), DataType::Timestamp(unit, None))
+                if !is_same_time_zone(tz, self.time_zone) =>
+            {
+                Some(DataType::Timestamp(
+                    unit.clone(),
+                    Some(self.time_zone.into()),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TreeNodeRewriter for TimeZoneCastRewriter<'_> {
+    type N = Expr;
+
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::Cast(expr::Cast { expr, data_type }) => {
+                let expr_type = expr.get_type(&self.schema)?;
+                Ok(match self.session_time_zone_type(&expr_type, &data_type) {
+                    Some(session_type) => Expr::Cast(expr::Cast::new(
+                        Box::new(Expr::Cast(expr::Cast::new(expr, session_type))),
+                        data_type,
+                    )),
+                    None => Expr::Cast(expr::Cast::new(expr, data_type)),
+                })
+            }
+            Expr::TryCast(expr::TryCast { expr, data_type }) => {
+                let expr_type = expr.get_type(&self.schema)?;
+                Ok(match self.session_time_zone_type(&expr_type, &data_type) {
+                    Some(session_type) => Expr::TryCast(expr::TryCast::new(
+                        Box::new(Expr::TryCast(expr::TryCast::new(expr, session_type))),
+                        data_type,
+                    )),
+                    None => Expr::TryCast(expr::TryCast::new(expr, data_type)),
+                })
+            }
+            expr => Ok(expr),
+        }
+    }
+}
+
+/// Returns true if the timezones `lhs` and `rhs` are known to be the same
+fn is_same_time_zone(lhs: &str, rhs: &str) -> bool {
+    lhs == rhs || (is_utc_time_zone(lhs) && is_utc_time_zone(rhs))
+}
+
 /// Casts the given `value` to `target_type`. Note that this function
 /// only considers `Null` or `Utf8` values.
 fn coerce_scalar(target_type: &DataType, value: &ScalarValue) -> Result<ScalarValue> {
@@ -602,6 +759,44 @@ fn coerce_arguments_for_signature(
         .collect::<Result<Vec<_>>>()
 }
 
+/// Returns `expressions` coerced to the types required by the signature of `fun`.
+///
+/// A dictionary encoded first argument is kept as is, rather than being cast to its
+/// value type, when `fun` can be applied to the dictionary values (see
+/// [`BuiltinScalarFunction::preserves_dictionary_encoding`]).
+fn coerce_arguments_for_scalar_function(
+    fun: &BuiltinScalarFunction,
+    expressions: &[Expr],
+    schema: &DFSchema,
+) -> Result<Vec<Expr>> {
+    if let Some(first) = expressions.first() {
+        if let DataType::Dictionary(_, value_type) = first.get_type(schema)? {
+            if fun.preserves_dictionary_encoding() {
+                let mut current_types = expressions
+                    .iter()
+                    .map(|e| e.get_type(schema))
+                    .collect::<Result<Vec<_>>>()?;
+                current_types[0] = value_type.as_ref().clone();
+
+                let new_types = data_types(&current_types, &fun.signature())?;
+                if new_types[0] == *value_type {
+                    return expressions
+                        .iter()
+                        .zip(new_types.iter())
+                        .enumerate()
+                        .map(|(i, (expr, data_type))| match i {
+                            0 => Ok(expr.clone()),
+                            _ => cast_expr(expr, data_type, schema),
+                        })
+                        .collect();
+                }
+            }
+        }
+    }
+
+    coerce_arguments_for_signature(expressions, schema, &fun.signature())
+}
+
 /// Cast `expr` to the specified type, if possible
 fn cast_expr(expr: &Expr, to_type: &DataType, schema: &DFSchema) -> Result<Expr> {
     expr.clone().cast_to(to_type, schema)
@@ -751,14 +946,15 @@ mod test {
 
     use arrow::datatypes::{DataType, TimeUnit};
 
+    use datafusion_common::config::ConfigOptions;
     use datafusion_common::tree_node::TreeNode;
     use datafusion_common::{DFField, DFSchema, DFSchemaRef, Result, ScalarValue};
     use datafusion_expr::expr::{self, InSubquery, Like, ScalarFunction};
     use datafusion_expr::{
-        cast, col, concat, concat_ws, create_udaf, is_true,
-        AccumulatorFunctionImplementation, AggregateFunction, AggregateUDF, BinaryExpr,
-        BuiltinScalarFunction, Case, ColumnarValue, ExprSchemable, Filter, Operator,
-        StateTypeFunction, Subquery,
+        cast, character_length, col, concat, concat_ws, create_udaf, is_true, substr,
+        try_cast, upper, AccumulatorFunctionImplementation, AggregateFunction,
+        AggregateUDF, BinaryExpr, BuiltinScalarFunction, Case, ColumnarValue,
+        ExprSchemable, Filter, Operator, StateTypeFunction, Subquery,
     };
     use datafusion_expr::{
         lit,
@@ -771,6 +967,7 @@ mod test {
     use crate::analyzer::type_coercion::{
         coerce_case_expression, TypeCoercion, TypeCoercionRewriter,
     };
+    use crate::analyzer::AnalyzerRule;
     use crate::test::assert_analyzed_plan_eq;
 
     fn empty() -> Arc<LogicalPlan> {
@@ -879,6 +1076,30 @@ mod test {
         assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)
     }
 
+    #[test]
+    fn scalar_function_dictionary() -> Result<()> {
+        let dictionary =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let empty = empty_with_type(dictionary);
+
+        // functions applied to the dictionary values keep the dictionary encoding
+        let plan = LogicalPlan::Projection(Projection::try_new(
+            vec![upper(col("a")), substr(col("a"), lit(2_i32))],
+            empty.clone(),
+        )?);
+        let expected =
+            "Projection: upper(a), substr(a, CAST(Int32(2) AS Int64))\n  EmptyRelation";
+        assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)?;
+
+        // other functions get their value type
+        let plan = LogicalPlan::Projection(Projection::try_new(
+            vec![character_length(col("a"))],
+            empty,
+        )?);
+        let expected = "Projection: character_length(CAST(a AS Utf8))\n  EmptyRelation";
+        assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)
+    }
+
     #[test]
     fn agg_udaf() -> Result<()> {
         let empty = empty();
@@ -1233,7 +1454,7 @@ mod test {
             vec![DFField::new_unqualified("a", DataType::Int64, true)],
             std::collections::HashMap::new(),
         )?);
-        let mut rewriter = TypeCoercionRewriter { schema };
+        let mut rewriter = TypeCoercionRewriter::new(schema);
         let expr = is_true(lit(12i32).gt(lit(13i64)));
         let expected = is_true(cast(lit(12i32), DataType::Int64).gt(lit(13i64)));
         let result = expr.rewrite(&mut rewriter)?;
@@ -1244,7 +1465,7 @@ mod test {
             vec![DFField::new_unqualified("a", DataType::Int64, true)],
             std::collections::HashMap::new(),
         )?);
-        let mut rewriter = TypeCoercionRewriter { schema };
+        let mut rewriter = TypeCoercionRewriter::new(schema);
         let expr = is_true(lit(12i32).eq(lit(13i64)));
         let expected = is_true(cast(lit(12i32), DataType::Int64).eq(lit(13i64)));
         let result = expr.rewrite(&mut rewriter)?;
@@ -1255,7 +1476,7 @@ mod test {
             vec![DFField::new_unqualified("a", DataType::Int64, true)],
             std::collections::HashMap::new(),
         )?);
-        let mut rewriter = TypeCoercionRewriter { schema };
+        let mut rewriter = TypeCoercionRewriter::new(schema);
         let expr = is_true(lit(12i32).lt(lit(13i64)));
         let expected = is_true(cast(lit(12i32), DataType::Int64).lt(lit(13i64)));
         let result = expr.rewrite(&mut rewriter)?;
@@ -1444,6 +1665,58 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn interval_arithmetic() -> Result<()> {
+        // SELECT INTERVAL '1' YEAR * 2, 3 * INTERVAL '1' YEAR, INTERVAL '1' YEAR > INTERVAL '1' DAY;
+        let year = || lit(ScalarValue::IntervalYearMonth(Some(12)));
+        let day = lit(ScalarValue::IntervalDayTime(Some(4294967296)));
+        let exprs = vec![year() * lit(2_i32), lit(3_u8) * year(), year().gt(day)];
+        let empty = empty();
+        let plan = LogicalPlan::Projection(Projection::try_new(exprs, empty)?);
+        let expected = "Projection: IntervalYearMonth(\"12\") * CAST(Int32(2) AS Int64), CAST(UInt8(3) AS Int64) * IntervalYearMonth(\"12\"), IntervalYearMonth(\"12\") > IntervalDayTime(\"4294967296\")\n  EmptyRelation";
+        assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)?;
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_time_zones() -> Result<()> {
+        let naive = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let utc = DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()));
+        let plus_two = DataType::Timestamp(TimeUnit::Nanosecond, Some("+02:00".into()));
+        let exprs = vec![
+            col("a").eq(cast(col("a"), utc.clone())).alias("c1"),
+            cast(col("a"), utc.clone())
+                .eq(cast(col("a"), plus_two.clone()))
+                .alias("c2"),
+            cast(cast(col("a"), utc), naive).alias("c3"),
+            try_cast(lit("2000-01-01T00:00:00"), plus_two).alias("c4"),
+        ];
+        let plan = LogicalPlan::Projection(Projection::try_new(
+            exprs,
+            empty_with_type(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        )?);
+
+        // timestamps without a timezone are converted through the session time zone
+        let mut config = ConfigOptions::default();
+        config.execution.time_zone = Some("+02:00".into());
+        let analyzed = TypeCoercion::new().analyze(plan.clone(), &config)?;
+        let expected = "Projection: CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) = CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) AS c1, \
+            CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) = CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) AS c2, \
+            CAST(CAST(CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, None)) AS c3, \
+            TRY_CAST(Utf8(\"2000-01-01T00:00:00\") AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS c4\
+            \n  EmptyRelation";
+        assert_eq!(format!("{analyzed:?}"), expected);
+
+        // the default UTC time zone keeps casts from and to UTC as they are
+        let expected = "Projection: CAST(a AS Timestamp(Nanosecond, Some(\"UTC\"))) = CAST(a AS Timestamp(Nanosecond, Some(\"UTC\"))) AS c1, \
+            CAST(a AS Timestamp(Nanosecond, Some(\"UTC\"))) = CAST(CAST(CAST(a AS Timestamp(Nanosecond, Some(\"+00:00\"))) AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS Timestamp(Nanosecond, Some(\"UTC\"))) AS c2, \
+            CAST(CAST(a AS Timestamp(Nanosecond, Some(\"UTC\"))) AS Timestamp(Nanosecond, None)) AS c3, \
+            TRY_CAST(TRY_CAST(Utf8(\"2000-01-01T00:00:00\") AS Timestamp(Nanosecond, Some(\"+00:00\"))) AS Timestamp(Nanosecond, Some(\"+02:00\"))) AS c4\
+            \n  EmptyRelation";
+        assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)?;
+        Ok(())
+    }
+
     #[test]
     fn timestamp_subtract_timestamp() -> Result<()> {
         let expr = Expr::BinaryExpr(BinaryExpr::new(
@@ -17,15 +17,15 @@
 
 //! Optimizer rule to eliminate left/right/full join to inner join if possible.
 use crate::{OptimizerConfig, OptimizerRule};
-use datafusion_common::{Column, DFSchema, Result};
+use datafusion_common::{DFSchema, Result};
+use datafusion_expr::null_rejection::{is_null_rejecting, simplify_outer_join_type};
+use datafusion_expr::Expr;
 use datafusion_expr::{
-    logical_plan::{Join, JoinType, LogicalPlan},
+    logical_plan::{Join, LogicalPlan},
     utils::from_plan,
 };
-use datafusion_expr::{Expr, Operator};
 
 use crate::optimizer::ApplyOrder;
-use datafusion_expr::expr::{BinaryExpr, Cast, TryCast};
 use std::sync::Arc;
 
 #[derive(Default)]
@@ -50,7 +50,8 @@ use std::sync::Arc;
 /// Generally, an outer join can be rewritten to inner join if the
 /// filters from the WHERE clause return false while any inputs are
 /// null and columns of those quals are come from nullable side of
-/// outer join.
+/// outer join. This is checked by [`is_null_rejecting`], which the physical
+/// `SimplifyFilteredOuterJoin` rule of `datafusion` shares.
 pub struct EliminateOuterJoin;
 
 impl EliminateOuterJoin {
@@ -70,31 +71,22 @@ impl OptimizerRule for EliminateOuterJoin {
         match plan {
             LogicalPlan::Filter(filter) => match filter.input.as_ref() {
                 LogicalPlan::Join(join) => {
-                    let mut non_nullable_cols: Vec<Column> = vec![];
-
-                    extract_non_nullable_columns(
-                        &filter.predicate,
-                        &mut non_nullable_cols,
-                        join.left.schema(),
-                        join.right.schema(),
-                        true,
-                    )?;
-
                     let new_join_type = if join.join_type.is_outer() {
-                        let mut left_non_nullable = false;
-                        let mut right_non_nullable = false;
-                        for col in non_nullable_cols.iter() {
-                            if join.left.schema().has_column(col) {
-                                left_non_nullable = true;
-                            }
-                            if join.right.schema().has_column(col) {
-                                right_non_nullable = true;
-                            }
-                        }
-                        eliminate_outer(
+                        // the rows padded with nulls on the side of `schema` are
+                        // filtered out by the predicate
+                        let rejects_nulls = |schema: &DFSchema| {
+                            is_null_rejecting(
+                                &filter.predicate,
+                                &|expr: &Expr| match expr {
+                                    Expr::Column(column) => schema.has_column(column),
+                                    _ => false,
+                                },
+                            )
+                        };
+                        simplify_outer_join_type(
                             join.join_type,
-                            left_non_nullable,
-                            right_non_nullable,
+                            rejects_nulls(join.left.schema()),
+                            rejects_nulls(join.right.schema()),
                         )
                     } else {
                         join.join_type
@@ -127,185 +119,16 @@ impl OptimizerRule for EliminateOuterJoin {
     }
 }
 
-pub fn eliminate_outer(
-    join_type: JoinType,
-    left_non_nullable: bool,
-    right_non_nullable: bool,
-) -> JoinType {
-    let mut new_join_type = join_type;
-    match join_type {
-        JoinType::Left => {
-            if right_non_nullable {
-                new_join_type = JoinType::Inner;
-            }
-        }
-        JoinType::Right => {
-            if left_non_nullable {
-                new_join_type = JoinType::Inner;
-            }
-        }
-        JoinType::Full => {
-            if left_non_nullable && right_non_nullable {
-                new_join_type = JoinType::Inner;
-            } else if left_non_nullable {
-                new_join_type = JoinType::Left;
-            } else if right_non_nullable {
-                new_join_type = JoinType::Right;
-            }
-        }
-        _ => {}
-    }
-    new_join_type
-}
-
-/// Recursively traverses expr, if expr returns false when
-/// any inputs are null, treats columns of both sides as non_nullable columns.
-///
-/// For and/or expr, extracts from all sub exprs and merges the columns.
-/// For or expr, if one of sub exprs returns true, discards all columns from or expr.
-/// For IS NOT NULL/NOT expr, always returns false for NULL input.
-///     extracts columns from these exprs.
-/// For all other exprs, fall through
-fn extract_non_nullable_columns(
-    expr: &Expr,
-    non_nullable_cols: &mut Vec<Column>,
-    left_schema: &Arc<DFSchema>,
-    right_schema: &Arc<DFSchema>,
-    top_level: bool,
-) -> Result<()> {
-    match expr {
-        Expr::Column(col) => {
-            non_nullable_cols.push(col.clone());
-            Ok(())
-        }
-        Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
-            // If one of the inputs are null for these operators, the results should be false.
-            Operator::Eq
-            | Operator::NotEq
-            | Operator::Lt
-            | Operator::LtEq
-            | Operator::Gt
-            | Operator::GtEq => {
-                extract_non_nullable_columns(
-                    left,
-                    non_nullable_cols,
-                    left_schema,
-                    right_schema,
-                    false,
-                )?;
-                extract_non_nullable_columns(
-                    right,
-                    non_nullable_cols,
-                    left_schema,
-                    right_schema,
-                    false,
-                )
-            }
-            Operator::And | Operator::Or => {
-                // treat And as Or if does not from top level, such as
-                // not (c1 < 10 and c2 > 100)
-                if top_level && *op == Operator::And {
-                    extract_non_nullable_columns(
-                        left,
-                        non_nullable_cols,
-                        left_schema,
-                        right_schema,
-                        top_level,
-                    )?;
-                    extract_non_nullable_columns(
-                        right,
-                        non_nullable_cols,
-                        left_schema,
-                        right_schema,
-                        top_level,
-                    )?;
-                    return Ok(());
-                }
-
-                let mut left_non_nullable_cols: Vec<Column> = vec![];
-                let mut right_non_nullable_cols: Vec<Column> = vec![];
-
-                extract_non_nullable_columns(
-                    left,
-                    &mut left_non_nullable_cols,
-                    left_schema,
-                    right_schema,
-                    top_level,
-                )?;
-                extract_non_nullable_columns(
-                    right,
-                    &mut right_non_nullable_cols,
-                    left_schema,
-                    right_schema,
-                    top_level,
-                )?;
-
-                // for query: select *** from a left join b where b.c1 ... or b.c2 ...
-                // this can be eliminated to inner join.
-                // for query: select *** from a left join b where a.c1 ... or b.c2 ...
-                // this can not be eliminated.
-                // If columns of relation exist in both sub exprs, any columns of this relation
-                // can be added to non nullable columns.
-                if !left_non_nullable_cols.is_empty()
-                    && !right_non_nullable_cols.is_empty()
-                {
-                    for left_col in &left_non_nullable_cols {
-                        for right_col in &right_non_nullable_cols {
-                            if (left_schema.has_column(left_col)
-                                && left_schema.has_column(right_col))
-                                || (right_schema.has_column(left_col)
-                                    && right_schema.has_column(right_col))
-                            {
-                                non_nullable_cols.push(left_col.clone());
-                                break;
-                            }
-                        }
-                    }
-                }
-                Ok(())
-            }
-            _ => Ok(()),
-        },
-        Expr::Not(arg) => extract_non_nullable_columns(
-            arg,
-            non_nullable_cols,
-            left_schema,
-            right_schema,
-            false,
-        ),
-        Expr::IsNotNull(arg) => {
-            if !top_level {
-                return Ok(());
-            }
-            extract_non_nullable_columns(
-                arg,
-                non_nullable_cols,
-                left_schema,
-                right_schema,
-                false,
-            )
-        }
-        Expr::Cast(Cast { expr, data_type: _ })
-        | Expr::TryCast(TryCast { expr, data_type: _ }) => extract_non_nullable_columns(
-            expr,
-            non_nullable_cols,
-            left_schema,
-            right_schema,
-            false,
-        ),
-        _ => Ok(()),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test::*;
     use arrow::datatypes::DataType;
+    use datafusion_common::Column;
     use datafusion_expr::{
         binary_expr, cast, col, lit,
         logical_plan::builder::LogicalPlanBuilder,
-        try_cast,
+        try_cast, JoinType,
         Operator::{And, Or},
     };
 
@@ -143,7 +143,7 @@ impl<S: SimplifyInfo> ExprSimplifier<S> {
     // it manually.
     // https://github.com/apache/arrow-datafusion/issues/3793
     pub fn coerce(&self, expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
-        let mut expr_rewrite = TypeCoercionRewriter { schema };
+        let mut expr_rewrite = TypeCoercionRewriter::new(schema);
 
         expr.rewrite(&mut expr_rewrite)
     }
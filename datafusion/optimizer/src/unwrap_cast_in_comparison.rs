@@ -19,7 +19,7 @@
 //! of expr can be added if needed.
 //! This rule can reduce adding the `Expr::Cast` the expr instead of adding the `Expr::Cast` to literal expr.
 use crate::optimizer::ApplyOrder;
-use crate::utils::{merge_schema, rewrite_preserving_name};
+use crate::utils::{is_utc_time_zone, merge_schema, rewrite_preserving_name};
 use crate::{OptimizerConfig, OptimizerRule};
 use arrow::datatypes::{
     DataType, TimeUnit, MAX_DECIMAL_FOR_EACH_PRECISION, MIN_DECIMAL_FOR_EACH_PRECISION,
@@ -305,6 +305,18 @@ fn try_cast_literal_to_type(
     if !is_support_data_type(&lit_data_type) || !is_support_data_type(target_type) {
         return Ok(None);
     }
+    if let (DataType::Timestamp(_, lit_tz), DataType::Timestamp(_, target_tz)) =
+        (&lit_data_type, target_type)
+    {
+        // only casts between UTC and timestamps without a timezone keep the
+        // values, other timezones convert the wall clock time
+        match (lit_tz, target_tz) {
+            (Some(tz), None) | (None, Some(tz)) if !is_utc_time_zone(tz) => {
+                return Ok(None)
+            }
+            _ => {}
+        }
+    }
     if lit_value.is_null() {
         // null value can be cast to any type of null value
         return Ok(Some(ScalarValue::try_from(target_type)?));
@@ -732,6 +744,16 @@ mod tests {
         let expected =
             col("ts_nano_none").lt(lit_timestamp_nano_none(1666612093000000000));
         assert_eq!(optimize_test(expr_lt, &schema), expected);
+
+        // casts to other timezones convert the values, so they can't be unwrapped
+        let plus_two = DataType::Timestamp(TimeUnit::Nanosecond, Some("+02:00".into()));
+        let expr_lt = cast(col("ts_nano_none"), plus_two).lt(lit(
+            ScalarValue::TimestampNanosecond(
+                Some(1666612093000000000),
+                Some("+02:00".into()),
+            ),
+        ));
+        assert_eq!(optimize_test(expr_lt.clone(), &schema), expr_lt);
     }
 
     fn optimize_test(expr: Expr, schema: &DFSchemaRef) -> Expr {
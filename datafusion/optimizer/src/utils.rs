@@ -403,6 +403,19 @@ pub(crate) fn replace_qualified_name(
     replace_col(expr, &replace_map)
 }
 
+/// Returns true if the timezone annotation `tz` is UTC
+pub(crate) fn is_utc_time_zone(tz: &str) -> bool {
+    match tz.strip_prefix('+').or_else(|| tz.strip_prefix('-')) {
+        // a zero offset such as `+00:00` or `+0000`
+        Some(offset) => {
+            !offset.is_empty() && offset.chars().all(|c| c == '0' || c == ':')
+        }
+        None => ["UTC", "Z", "Etc/UTC"]
+            .iter()
+            .any(|utc| tz.eq_ignore_ascii_case(utc)),
+    }
+}
+
 /// Log the plan in debug/tracing mode after some part of the optimizer runs
 pub fn log_plan(description: &str, plan: &LogicalPlan) {
     debug!("{description}:\n{}\n", plan.display_indent());
@@ -0,0 +1,97 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::{ArrayRef, DictionaryArray};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+use arrow::record_batch::RecordBatch;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use datafusion_common::ScalarValue;
+use datafusion_expr::BuiltinScalarFunction;
+use datafusion_physical_expr::execution_props::ExecutionProps;
+use datafusion_physical_expr::expressions::{cast, col, lit};
+use datafusion_physical_expr::functions::create_physical_expr;
+use datafusion_physical_expr::PhysicalExpr;
+use rand::distributions::Alphanumeric;
+use rand::prelude::*;
+use std::sync::Arc;
+
+fn do_bench(
+    c: &mut Criterion,
+    name: &str,
+    batch: &RecordBatch,
+    fun: BuiltinScalarFunction,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+) {
+    let schema = batch.schema();
+    let expr =
+        create_physical_expr(&fun, &args, &schema, &ExecutionProps::new()).unwrap();
+    c.bench_function(name, |b| {
+        b.iter(|| black_box(expr.evaluate(black_box(batch)).unwrap()))
+    });
+}
+
+fn random_string(rng: &mut StdRng, len: usize) -> String {
+    let value = rng.sample_iter(&Alphanumeric).take(len).collect();
+    String::from_utf8(value).unwrap()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(120320);
+    let distinct_values: Vec<_> = (0..20).map(|_| random_string(&mut rng, 20)).collect();
+    let values: DictionaryArray<Int32Type> = (0..8192)
+        .map(|_| distinct_values.choose(&mut rng).unwrap().as_str())
+        .collect();
+    let values: ArrayRef = Arc::new(values);
+
+    let schema = Schema::new(vec![Field::new("a", values.data_type().clone(), true)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![values]).unwrap();
+    let schema = batch.schema();
+    let column = col("a", &schema).unwrap();
+    let expanded = cast(column.clone(), &schema, DataType::Utf8).unwrap();
+
+    for (name, fun, args) in [
+        ("upper", BuiltinScalarFunction::Upper, vec![]),
+        (
+            "substr",
+            BuiltinScalarFunction::Substr,
+            vec![lit(ScalarValue::Int64(Some(5)))],
+        ),
+    ] {
+        let mut dictionary_args = vec![column.clone()];
+        dictionary_args.extend(args.iter().cloned());
+        do_bench(
+            c,
+            &format!("{name} dictionary(20 values, 8192 rows)"),
+            &batch,
+            fun,
+            dictionary_args,
+        );
+
+        let mut expanded_args = vec![expanded.clone()];
+        expanded_args.extend(args.iter().cloned());
+        do_bench(
+            c,
+            &format!("{name} expanded dictionary(20 values, 8192 rows)"),
+            &batch,
+            fun,
+            expanded_args,
+        );
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
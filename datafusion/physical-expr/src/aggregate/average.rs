@@ -33,7 +33,7 @@ use crate::{AggregateExpr, PhysicalExpr};
 use arrow::compute;
 use arrow::datatypes::DataType;
 use arrow::{
-    array::{ArrayRef, UInt64Array},
+    array::{ArrayRef, BooleanArray, UInt64Array},
     datatypes::Field,
 };
 use arrow_array::Array;
@@ -41,6 +41,7 @@ use datafusion_common::{downcast_value, ScalarValue};
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::Accumulator;
 use datafusion_row::accessor::RowAccessor;
+use datafusion_row::layout::RowLayout;
 
 /// AVG aggregate expression
 #[derive(Debug, Clone)]
@@ -319,6 +320,26 @@ impl RowAccumulator for AvgRowAccumulator {
         sum::update_avg_to_row(self.state_index(), accessor, value)
     }
 
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        sum::add_rows_to_row(
+            self.state_index() + 1,
+            Some(self.state_index()),
+            &values[0],
+            &self.sum_datatype,
+            filter,
+            groups,
+            states,
+            layout,
+        )
+    }
+
     fn merge_batch(
         &mut self,
         states: &[ArrayRef],
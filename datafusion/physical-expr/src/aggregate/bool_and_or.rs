@@ -199,7 +199,7 @@ impl AggregateExpr for BoolAnd {
     }
 
     fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
-        Ok(Box::new(BoolAndAccumulator::try_new(&self.data_type)?))
+        Ok(Box::new(SlidingBoolAndAccumulator::default()))
     }
 }
 
@@ -257,6 +257,61 @@ impl Accumulator for BoolAndAccumulator {
     }
 }
 
+/// Returns the number of true and false values in `values`, ignoring nulls
+fn true_false_counts(values: &ArrayRef) -> Result<(u64, u64)> {
+    let array = downcast_value!(values, BooleanArray);
+    let true_count = array.true_count();
+    let false_count = array.len() - array.null_count() - true_count;
+    Ok((true_count as u64, false_count as u64))
+}
+
+/// bool_and accumulator supporting retraction, for sliding window frames. It
+/// keeps the number of true and false values in the frame.
+#[derive(Debug, Default)]
+struct SlidingBoolAndAccumulator {
+    true_count: u64,
+    false_count: u64,
+}
+
+impl Accumulator for SlidingBoolAndAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (true_count, false_count) = true_false_counts(&values[0])?;
+        self.true_count += true_count;
+        self.false_count += false_count;
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (true_count, false_count) = true_false_counts(&values[0])?;
+        self.true_count -= true_count;
+        self.false_count -= false_count;
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let value = if self.false_count > 0 {
+            Some(false)
+        } else if self.true_count > 0 {
+            Some(true)
+        } else {
+            None
+        };
+        Ok(ScalarValue::Boolean(value))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+}
+
 #[derive(Debug)]
 struct BoolAndRowAccumulator {
     index: usize,
@@ -393,7 +448,7 @@ impl AggregateExpr for BoolOr {
     }
 
     fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
-        Ok(Box::new(BoolOrAccumulator::try_new(&self.data_type)?))
+        Ok(Box::new(SlidingBoolOrAccumulator::default()))
     }
 }
 
@@ -451,6 +506,53 @@ impl Accumulator for BoolOrAccumulator {
     }
 }
 
+/// bool_or accumulator supporting retraction, for sliding window frames. It
+/// keeps the number of true and false values in the frame.
+#[derive(Debug, Default)]
+struct SlidingBoolOrAccumulator {
+    true_count: u64,
+    false_count: u64,
+}
+
+impl Accumulator for SlidingBoolOrAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (true_count, false_count) = true_false_counts(&values[0])?;
+        self.true_count += true_count;
+        self.false_count += false_count;
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (true_count, false_count) = true_false_counts(&values[0])?;
+        self.true_count -= true_count;
+        self.false_count -= false_count;
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let value = if self.true_count > 0 {
+            Some(true)
+        } else if self.false_count > 0 {
+            Some(false)
+        } else {
+            None
+        };
+        Ok(ScalarValue::Boolean(value))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+}
+
 #[derive(Debug)]
 struct BoolOrRowAccumulator {
     index: usize,
@@ -566,4 +668,44 @@ mod tests {
         let a: ArrayRef = Arc::new(BooleanArray::from(vec![None, None]));
         generic_test_op!(a, DataType::Boolean, BoolOr, ScalarValue::Boolean(None))
     }
+
+    #[test]
+    fn sliding_bool_and_or() -> Result<()> {
+        let expr = col(
+            "a",
+            &Schema::new(vec![Field::new("a", DataType::Boolean, true)]),
+        )?;
+        let mut bool_and = BoolAnd::new(expr.clone(), "bool_and", DataType::Boolean)
+            .create_sliding_accumulator()?;
+        let mut bool_or = BoolOr::new(expr, "bool_or", DataType::Boolean)
+            .create_sliding_accumulator()?;
+
+        let first: ArrayRef = Arc::new(BooleanArray::from(vec![Some(true), None]));
+        let second: ArrayRef = Arc::new(BooleanArray::from(vec![Some(false), None]));
+        let third: ArrayRef = Arc::new(BooleanArray::from(vec![None, Some(true)]));
+        for acc in [&mut bool_and, &mut bool_or] {
+            acc.update_batch(&[first.clone()])?;
+            acc.update_batch(&[second.clone()])?;
+        }
+        assert_eq!(bool_and.evaluate()?, ScalarValue::from(false));
+        assert_eq!(bool_or.evaluate()?, ScalarValue::from(true));
+
+        // frame of `second` only
+        bool_and.retract_batch(&[first.clone()])?;
+        bool_or.retract_batch(&[first])?;
+        assert_eq!(bool_and.evaluate()?, ScalarValue::from(false));
+        assert_eq!(bool_or.evaluate()?, ScalarValue::from(false));
+
+        // all-null frame
+        bool_and.retract_batch(&[second.clone()])?;
+        bool_or.retract_batch(&[second])?;
+        assert_eq!(bool_and.evaluate()?, ScalarValue::Boolean(None));
+        assert_eq!(bool_or.evaluate()?, ScalarValue::Boolean(None));
+
+        bool_and.update_batch(&[third.clone()])?;
+        bool_or.update_batch(&[third])?;
+        assert_eq!(bool_and.evaluate()?, ScalarValue::from(true));
+        assert_eq!(bool_or.evaluate()?, ScalarValue::from(true));
+        Ok(())
+    }
 }
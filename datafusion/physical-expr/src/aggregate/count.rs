@@ -22,10 +22,10 @@ use std::fmt::Debug;
 use std::ops::BitAnd;
 use std::sync::Arc;
 
-use crate::aggregate::row_accumulator::RowAccumulator;
+use crate::aggregate::row_accumulator::{for_each_selected_row, RowAccumulator};
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::{AggregateExpr, PhysicalExpr};
-use arrow::array::{Array, Int64Array};
+use arrow::array::{Array, BooleanArray, Int64Array};
 use arrow::compute;
 use arrow::datatypes::DataType;
 use arrow::{array::ArrayRef, datatypes::Field};
@@ -34,6 +34,7 @@ use datafusion_common::{downcast_value, ScalarValue};
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::Accumulator;
 use datafusion_row::accessor::RowAccessor;
+use datafusion_row::layout::RowLayout;
 
 use crate::expressions::format_state_name;
 
@@ -267,6 +268,21 @@ impl RowAccumulator for CountRowAccumulator {
         Ok(())
     }
 
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        let index = self.state_index;
+        for_each_selected_row(values, filter, groups, states, layout, |accessor, _| {
+            accessor.add_u64(index, 1);
+            Ok(())
+        })
+    }
+
     fn merge_batch(
         &mut self,
         states: &[ArrayRef],
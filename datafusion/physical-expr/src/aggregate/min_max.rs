@@ -40,13 +40,20 @@ use datafusion_common::{downcast_value, DataFusionError, Result};
 use datafusion_expr::Accumulator;
 
 use crate::aggregate::row_accumulator::{
-    is_row_accumulator_support_dtype, RowAccumulator,
+    for_each_selected_row, is_row_accumulator_support_dtype, update_rows_typed,
+    RowAccumulator,
 };
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
 use arrow::array::Array;
 use arrow::array::Decimal128Array;
+use arrow::compute::cast;
+use arrow::datatypes::{
+    Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
 use datafusion_row::accessor::RowAccessor;
+use datafusion_row::layout::RowLayout;
 
 use super::moving_min_max;
 
@@ -590,6 +597,83 @@ macro_rules! min_max_v2 {
     }};
 }
 
+// min/max of the selected rows of a primitive array into the states of their groups.
+macro_rules! typed_min_max_rows {
+    ($INDEX:ident, $VALUES:ident, $FILTER:ident, $GROUPS:ident, $STATES:ident, $LAYOUT:ident, $ARROW_TYPE:ident, $TYPE:ident, $OP:ident) => {{
+        paste::item! {
+            update_rows_typed::<$ARROW_TYPE>(
+                $VALUES,
+                $FILTER,
+                $GROUPS,
+                $STATES,
+                $LAYOUT,
+                |accessor, v| accessor.[<$OP _ $TYPE>]($INDEX, v),
+            )
+        }
+    }};
+}
+
+// Vectorized version of `min_max_v2`: min/max of the selected rows of an array into
+// the states of their groups, as done by the `update_rows` of the row accumulators.
+macro_rules! min_max_rows {
+    ($INDEX:ident, $VALUES:ident, $FILTER:ident, $GROUPS:ident, $STATES:ident, $LAYOUT:ident, $OP:ident) => {{
+        match $VALUES.data_type() {
+            DataType::Boolean => {
+                let array = downcast_value!($VALUES, BooleanArray);
+                for_each_selected_row(
+                    std::slice::from_ref($VALUES),
+                    $FILTER,
+                    $GROUPS,
+                    $STATES,
+                    $LAYOUT,
+                    |accessor, row| {
+                        paste::item! {
+                            accessor.[<$OP _bool>]($INDEX, array.value(row));
+                        }
+                        Ok(())
+                    },
+                )
+            }
+            DataType::Float64 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Float64Type, f64, $OP
+            ),
+            DataType::Float32 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Float32Type, f32, $OP
+            ),
+            DataType::UInt64 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, UInt64Type, u64, $OP
+            ),
+            DataType::UInt32 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, UInt32Type, u32, $OP
+            ),
+            DataType::UInt16 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, UInt16Type, u16, $OP
+            ),
+            DataType::UInt8 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, UInt8Type, u8, $OP
+            ),
+            DataType::Int64 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Int64Type, i64, $OP
+            ),
+            DataType::Int32 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Int32Type, i32, $OP
+            ),
+            DataType::Int16 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Int16Type, i16, $OP
+            ),
+            DataType::Int8 => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Int8Type, i8, $OP
+            ),
+            DataType::Decimal128(_, _) => typed_min_max_rows!(
+                $INDEX, $VALUES, $FILTER, $GROUPS, $STATES, $LAYOUT, Decimal128Type, i128, $OP
+            ),
+            e => Err(DataFusionError::Internal(format!(
+                "MIN/MAX is not expected to receive arrays of incompatible types {e:?}"
+            ))),
+        }
+    }};
+}
+
 /// the minimum of two scalar values
 pub fn min(lhs: &ScalarValue, rhs: &ScalarValue) -> Result<ScalarValue> {
     min_max!(lhs, rhs, min)
@@ -608,6 +692,32 @@ pub fn max_row(index: usize, accessor: &mut RowAccessor, s: &ScalarValue) -> Res
     min_max_v2!(index, accessor, s, max)
 }
 
+/// Updates the min state at `index` of the group of each selected row of `values`,
+/// see [`RowAccumulator::update_rows`]
+fn min_rows(
+    index: usize,
+    values: &ArrayRef,
+    filter: Option<&BooleanArray>,
+    groups: &[usize],
+    states: &mut [u8],
+    layout: &Arc<RowLayout>,
+) -> Result<()> {
+    min_max_rows!(index, values, filter, groups, states, layout, min)
+}
+
+/// Updates the max state at `index` of the group of each selected row of `values`,
+/// see [`RowAccumulator::update_rows`]
+fn max_rows(
+    index: usize,
+    values: &ArrayRef,
+    filter: Option<&BooleanArray>,
+    groups: &[usize],
+    states: &mut [u8],
+    layout: &Arc<RowLayout>,
+) -> Result<()> {
+    min_max_rows!(index, values, filter, groups, states, layout, max)
+}
+
 /// An accumulator to compute the maximum value
 #[derive(Debug)]
 pub struct MaxAccumulator {
@@ -744,6 +854,18 @@ impl RowAccumulator for MaxRowAccumulator {
         max_row(self.index, accessor, value)
     }
 
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        let values = &cast(&values[0], &self.data_type)?;
+        max_rows(self.index, values, filter, groups, states, layout)
+    }
+
     fn merge_batch(
         &mut self,
         states: &[ArrayRef],
@@ -1004,6 +1126,18 @@ impl RowAccumulator for MinRowAccumulator {
         min_row(self.index, accessor, value)
     }
 
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        let values = &cast(&values[0], &self.data_type)?;
+        min_rows(self.index, values, filter, groups, states, layout)
+    }
+
     fn merge_batch(
         &mut self,
         states: &[ArrayRef],
@@ -17,11 +17,15 @@
 
 //! Accumulator over row format
 
-use arrow::array::ArrayRef;
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::datatypes::ArrowPrimitiveType;
 use arrow_schema::DataType;
+use datafusion_common::cast::as_primitive_array;
 use datafusion_common::{Result, ScalarValue};
 use datafusion_row::accessor::RowAccessor;
+use datafusion_row::layout::RowLayout;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 /// Row-based accumulator where the internal aggregate state(s) are stored using row format.
 ///
@@ -65,6 +69,33 @@ pub trait RowAccumulator: Send + Sync + Debug {
         accessor: &mut RowAccessor,
     ) -> Result<()>;
 
+    /// updates the states of several groups at once from a vector of arrays.
+    ///
+    /// The states of all groups are stored back to back in `states`, each one
+    /// `layout.fixed_part_width()` bytes wide, and row `i` of `values` is
+    /// accumulated into the state of group `groups[i]`. Rows that are null in
+    /// any of `values`, or not selected by `filter`, are skipped.
+    ///
+    /// The default implementation updates the states row by row through
+    /// [`Self::update_scalar_values`]. Accumulators with primitive states
+    /// override it with a typed loop that does not create any [`ScalarValue`].
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        for_each_selected_row(values, filter, groups, states, layout, |accessor, row| {
+            let scalar_values = values
+                .iter()
+                .map(|array| ScalarValue::try_from_array(array, row))
+                .collect::<Result<Vec<_>>>()?;
+            self.update_scalar_values(&scalar_values, accessor)
+        })
+    }
+
     /// updates the accumulator's state from a vector of states.
     fn merge_batch(
         &mut self,
@@ -97,3 +128,71 @@ pub fn is_row_accumulator_support_dtype(data_type: &DataType) -> bool {
             | DataType::Decimal128(_, _)
     )
 }
+
+/// Returns true if `row` is selected by `filter` and not null in any of `values`
+#[inline]
+fn is_row_selected(
+    values: &[ArrayRef],
+    filter: Option<&BooleanArray>,
+    row: usize,
+) -> bool {
+    filter.map_or(true, |filter| filter.is_valid(row) && filter.value(row))
+        && values.iter().all(|array| array.is_valid(row))
+}
+
+/// Calls `update` with an accessor pointing to the state of group `groups[row]`,
+/// for every `row` of `values` that is selected by `filter` and not null in any
+/// of `values`. See [`RowAccumulator::update_rows`] for the layout of `states`.
+pub(crate) fn for_each_selected_row(
+    values: &[ArrayRef],
+    filter: Option<&BooleanArray>,
+    groups: &[usize],
+    states: &mut [u8],
+    layout: &Arc<RowLayout>,
+    mut update: impl FnMut(&mut RowAccessor, usize) -> Result<()>,
+) -> Result<()> {
+    let width = layout.fixed_part_width();
+    for (row, group) in groups.iter().enumerate() {
+        if is_row_selected(values, filter, row) {
+            let mut accessor = RowAccessor::new_from_layout(layout.clone());
+            accessor.point_to(0, &mut states[group * width..(group + 1) * width]);
+            update(&mut accessor, row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Same as [`for_each_selected_row`] for a single primitive array, passing
+/// the native value of each selected row to `update`.
+pub(crate) fn update_rows_typed<T: ArrowPrimitiveType>(
+    array: &ArrayRef,
+    filter: Option<&BooleanArray>,
+    groups: &[usize],
+    states: &mut [u8],
+    layout: &Arc<RowLayout>,
+    mut update: impl FnMut(&mut RowAccessor, T::Native),
+) -> Result<()> {
+    let values = as_primitive_array::<T>(array)?;
+    if values.null_count() > 0 || filter.is_some() {
+        return for_each_selected_row(
+            std::slice::from_ref(array),
+            filter,
+            groups,
+            states,
+            layout,
+            |accessor, row| {
+                update(accessor, values.value(row));
+                Ok(())
+            },
+        );
+    }
+
+    // fast path: every row is selected
+    let width = layout.fixed_part_width();
+    for (value, group) in values.values().iter().zip(groups) {
+        let mut accessor = RowAccessor::new_from_layout(layout.clone());
+        accessor.point_to(0, &mut states[group * width..(group + 1) * width]);
+        update(&mut accessor, *value);
+    }
+    Ok(())
+}
@@ -35,14 +35,16 @@ use datafusion_common::{downcast_value, DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
 
 use crate::aggregate::row_accumulator::{
-    is_row_accumulator_support_dtype, RowAccumulator,
+    is_row_accumulator_support_dtype, update_rows_typed, RowAccumulator,
 };
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
 use arrow::array::Array;
-use arrow::array::Decimal128Array;
+use arrow::array::{BooleanArray, Decimal128Array};
 use arrow::compute::cast;
+use arrow::datatypes::{Decimal128Type, Float32Type, Float64Type, Int64Type, UInt64Type};
 use datafusion_row::accessor::RowAccessor;
+use datafusion_row::layout::RowLayout;
 
 /// SUM aggregate expression
 #[derive(Debug, Clone)]
@@ -288,6 +290,106 @@ pub(crate) fn add_to_row(
     Ok(())
 }
 
+// Adds each selected row of `$VALUES` to the state of its group through
+// `update_rows_typed`, also counting it at `$COUNT_INDEX` if given (for avg).
+macro_rules! sum_rows {
+    ($INDEX:ident, $COUNT_INDEX:ident, $VALUES:ident, $FILTER:ident, $GROUPS:ident, $STATES:ident, $LAYOUT:ident, $ARROW_TYPE:ident, $TYPE:ident) => {{
+        paste::item! {
+            update_rows_typed::<$ARROW_TYPE>(
+                &$VALUES,
+                $FILTER,
+                $GROUPS,
+                $STATES,
+                $LAYOUT,
+                |accessor, v| {
+                    if let Some(count_index) = $COUNT_INDEX {
+                        accessor.add_u64(count_index, 1);
+                    }
+                    accessor.[<add_ $TYPE>]($INDEX, v)
+                },
+            )
+        }
+    }};
+}
+
+/// Vectorized version of [`add_to_row`] and [`update_avg_to_row`], used by the
+/// `update_rows` of the sum and avg row accumulators: casts `values` to
+/// `sum_type` and adds every selected row to the field `index` of the state of
+/// its group, also counting it at `count_index` if given.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn add_rows_to_row(
+    index: usize,
+    count_index: Option<usize>,
+    values: &ArrayRef,
+    sum_type: &DataType,
+    filter: Option<&BooleanArray>,
+    groups: &[usize],
+    states: &mut [u8],
+    layout: &Arc<RowLayout>,
+) -> Result<()> {
+    let values = cast(values, sum_type)?;
+    match sum_type {
+        DataType::Float64 => sum_rows!(
+            index,
+            count_index,
+            values,
+            filter,
+            groups,
+            states,
+            layout,
+            Float64Type,
+            f64
+        ),
+        DataType::Float32 => sum_rows!(
+            index,
+            count_index,
+            values,
+            filter,
+            groups,
+            states,
+            layout,
+            Float32Type,
+            f32
+        ),
+        DataType::UInt64 => sum_rows!(
+            index,
+            count_index,
+            values,
+            filter,
+            groups,
+            states,
+            layout,
+            UInt64Type,
+            u64
+        ),
+        DataType::Int64 => sum_rows!(
+            index,
+            count_index,
+            values,
+            filter,
+            groups,
+            states,
+            layout,
+            Int64Type,
+            i64
+        ),
+        DataType::Decimal128(_, _) => sum_rows!(
+            index,
+            count_index,
+            values,
+            filter,
+            groups,
+            states,
+            layout,
+            Decimal128Type,
+            i128
+        ),
+        e => Err(DataFusionError::Internal(format!(
+            "Row sum updater is not expected to receive the type {e:?}"
+        ))),
+    }
+}
+
 pub(crate) fn update_avg_to_row(
     index: usize,
     accessor: &mut RowAccessor,
@@ -406,6 +508,26 @@ impl RowAccumulator for SumRowAccumulator {
         add_to_row(self.index, accessor, value)
     }
 
+    fn update_rows(
+        &mut self,
+        values: &[ArrayRef],
+        filter: Option<&BooleanArray>,
+        groups: &[usize],
+        states: &mut [u8],
+        layout: &Arc<RowLayout>,
+    ) -> Result<()> {
+        add_rows_to_row(
+            self.index,
+            None,
+            &values[0],
+            &self.datatype,
+            filter,
+            groups,
+            states,
+            layout,
+        )
+    }
+
     fn merge_batch(
         &mut self,
         states: &[ArrayRef],
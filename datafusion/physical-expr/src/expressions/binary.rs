@@ -54,7 +54,9 @@ use arrow::datatypes::*;
 use adapter::{eq_dyn, gt_dyn, gt_eq_dyn, lt_dyn, lt_eq_dyn, neq_dyn};
 use arrow::compute::kernels::concat_elements::concat_elements_utf8;
 
-use datafusion_expr::type_coercion::{is_decimal, is_timestamp, is_utf8_or_large_utf8};
+use datafusion_expr::type_coercion::{
+    is_decimal, is_interval, is_timestamp, is_utf8_or_large_utf8,
+};
 use kernels::{
     bitwise_and_dyn, bitwise_and_dyn_scalar, bitwise_or_dyn, bitwise_or_dyn_scalar,
     bitwise_shift_left_dyn, bitwise_shift_left_dyn_scalar, bitwise_shift_right_dyn,
@@ -69,7 +71,8 @@ use kernels_arrow::{
     is_not_distinct_from_decimal, is_not_distinct_from_f32, is_not_distinct_from_f64,
     is_not_distinct_from_null, is_not_distinct_from_utf8, modulus_decimal_dyn_scalar,
     modulus_dyn_decimal, multiply_decimal_dyn_scalar, multiply_dyn_decimal,
-    subtract_decimal_dyn_scalar, subtract_dyn_decimal, subtract_dyn_temporal,
+    multiply_dyn_interval, normalize_intervals, subtract_decimal_dyn_scalar,
+    subtract_dyn_decimal, subtract_dyn_temporal,
 };
 
 use arrow::datatypes::{DataType, Schema, TimeUnit};
@@ -679,6 +682,12 @@ impl PhysicalExpr for BinaryExpr {
         let left_data_type = left_value.data_type();
         let right_data_type = right_value.data_type();
 
+        if let Some(result) =
+            self.evaluate_interval_op(&left_value, &right_value, batch.num_rows())?
+        {
+            return Ok(ColumnarValue::Array(result));
+        }
+
         let schema = batch.schema();
         let input_schema = schema.as_ref();
 
@@ -1072,6 +1081,63 @@ fn to_result_type_array(
 }
 
 impl BinaryExpr {
+    /// Evaluate the operations involving intervals that arrow has no kernels for:
+    /// addition and subtraction of intervals, multiplication of an interval by an
+    /// `Int64` and comparison of intervals, in which case intervals are compared
+    /// on a normalized basis where a month is 30 days, see [`normalize_intervals`].
+    /// The intervals may have different units. Returns `None` for other operations.
+    fn evaluate_interval_op(
+        &self,
+        left: &ColumnarValue,
+        right: &ColumnarValue,
+        num_rows: usize,
+    ) -> Result<Option<ArrayRef>> {
+        use Operator::*;
+        let (left_type, right_type) = (left.data_type(), right.data_type());
+        let supported = match (&left_type, &right_type) {
+            (DataType::Interval(_), DataType::Interval(_)) => matches!(
+                self.op,
+                Plus | Minus
+                    | Eq
+                    | NotEq
+                    | Lt
+                    | LtEq
+                    | Gt
+                    | GtEq
+                    | IsDistinctFrom
+                    | IsNotDistinctFrom
+            ),
+            (DataType::Interval(_), DataType::Int64)
+            | (DataType::Int64, DataType::Interval(_)) => self.op == Multiply,
+            _ => false,
+        };
+        if !supported {
+            return Ok(None);
+        }
+
+        let left = left.clone().into_array(num_rows);
+        let right = right.clone().into_array(num_rows);
+        let result = match self.op {
+            Plus => resolve_temporal_op(&left, 1, &right)?,
+            Minus => resolve_temporal_op(&left, -1, &right)?,
+            Multiply if is_interval(&left_type) => multiply_dyn_interval(&left, &right)?,
+            Multiply => multiply_dyn_interval(&right, &left)?,
+            _ => {
+                let left = normalize_intervals(&left)?;
+                let right = normalize_intervals(&right)?;
+                let normalized_type = left.data_type().clone();
+                self.evaluate_with_resolved_args(
+                    left,
+                    &normalized_type,
+                    right,
+                    &normalized_type,
+                    &DataType::Boolean,
+                )?
+            }
+        };
+        Ok(Some(result))
+    }
+
     /// Evaluate the expression of the left input is an array and
     /// right is literal - use scalar operations
     fn evaluate_array_scalar(
@@ -1276,7 +1342,17 @@ pub fn binary(
             "The type of {lhs_type} {op:?} {rhs_type} of binary physical should be same"
         )));
     }
-    if !lhs_type.eq(rhs_type) && (!is_decimal(lhs_type) && !is_decimal(rhs_type)) {
+    // intervals of different units can be combined, and be multiplied by integers
+    let interval_op = match (lhs_type, rhs_type) {
+        (DataType::Interval(_), DataType::Interval(_)) => true,
+        (DataType::Interval(_), DataType::Int64)
+        | (DataType::Int64, DataType::Interval(_)) => op == Operator::Multiply,
+        _ => false,
+    };
+    if !lhs_type.eq(rhs_type)
+        && (!is_decimal(lhs_type) && !is_decimal(rhs_type))
+        && !interval_op
+    {
         return Err(DataFusionError::Internal(format!(
             "The type of {lhs_type} {op:?} {rhs_type} of binary physical should be same"
         )));
@@ -4804,6 +4880,237 @@ mod tests {
         assert_eq!(expr.to_string(), "(1 OR 2) AND (3 OR 4)");
     }
 
+    fn interval_dt(values: Vec<Option<(i32, i32)>>) -> ArrayRef {
+        Arc::new(
+            values
+                .into_iter()
+                .map(|v| {
+                    v.map(|(days, millis)| IntervalDayTimeType::make_value(days, millis))
+                })
+                .collect::<IntervalDayTimeArray>(),
+        )
+    }
+
+    fn interval_mdn(values: Vec<Option<(i32, i32, i64)>>) -> ArrayRef {
+        Arc::new(
+            values
+                .into_iter()
+                .map(|v| {
+                    v.map(|(months, days, nanos)| {
+                        IntervalMonthDayNanoType::make_value(months, days, nanos)
+                    })
+                })
+                .collect::<IntervalMonthDayNanoArray>(),
+        )
+    }
+
+    /// Evaluates `left op right` on two columns, as planned by [`binary`]
+    fn evaluate_columns(
+        left: ArrayRef,
+        op: Operator,
+        right: ArrayRef,
+    ) -> Result<ArrayRef> {
+        let schema = Schema::new(vec![
+            Field::new("a", left.data_type().clone(), true),
+            Field::new("b", right.data_type().clone(), true),
+        ]);
+        let expr = binary(col("a", &schema)?, op, col("b", &schema)?, &schema)?;
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![left, right])?;
+        Ok(expr.evaluate(&batch)?.into_array(batch.num_rows()))
+    }
+
+    #[test]
+    fn interval_plus_minus_interval() -> Result<()> {
+        let ym: ArrayRef =
+            Arc::new(IntervalYearMonthArray::from(vec![Some(1), None, Some(14)]));
+        let dt = interval_dt(vec![Some((2, 500)), Some((1, 1)), None]);
+        let mdn = interval_mdn(vec![Some((1, 2, 3)), Some((-1, 0, 0)), Some((0, 1, 1))]);
+
+        let result = evaluate_columns(ym.clone(), Operator::Plus, ym.clone())?;
+        let expected: ArrayRef =
+            Arc::new(IntervalYearMonthArray::from(vec![Some(2), None, Some(28)]));
+        assert_eq!(&result, &expected);
+
+        let result = evaluate_columns(dt.clone(), Operator::Minus, dt.clone())?;
+        assert_eq!(
+            &result,
+            &interval_dt(vec![Some((0, 0)), Some((0, 0)), None])
+        );
+
+        let result = evaluate_columns(mdn.clone(), Operator::Plus, mdn.clone())?;
+        let expected =
+            interval_mdn(vec![Some((2, 4, 6)), Some((-2, 0, 0)), Some((0, 2, 2))]);
+        assert_eq!(&result, &expected);
+
+        // intervals of different units result in MonthDayNano intervals
+        let result = evaluate_columns(ym.clone(), Operator::Plus, dt.clone())?;
+        let expected = interval_mdn(vec![Some((1, 2, 500_000_000)), None, None]);
+        assert_eq!(&result, &expected);
+
+        let result = evaluate_columns(dt, Operator::Minus, mdn.clone())?;
+        let expected = interval_mdn(vec![
+            Some((-1, 0, 499_999_997)),
+            Some((1, 1, 1_000_000)),
+            None,
+        ]);
+        assert_eq!(&result, &expected);
+
+        let result = evaluate_columns(mdn, Operator::Minus, ym)?;
+        let expected = interval_mdn(vec![Some((0, 2, 3)), None, Some((-14, 1, 1))]);
+        assert_eq!(&result, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn interval_multiply_integer() -> Result<()> {
+        let factors: ArrayRef =
+            Arc::new(Int64Array::from(vec![Some(3), Some(-2), None, Some(0)]));
+
+        let ym: ArrayRef = Arc::new(IntervalYearMonthArray::from(vec![
+            Some(5),
+            Some(5),
+            Some(5),
+            None,
+        ]));
+        let result = evaluate_columns(ym.clone(), Operator::Multiply, factors.clone())?;
+        let expected: ArrayRef = Arc::new(IntervalYearMonthArray::from(vec![
+            Some(15),
+            Some(-10),
+            None,
+            None,
+        ]));
+        assert_eq!(&result, &expected);
+        assert_eq!(
+            result.data_type(),
+            &DataType::Interval(IntervalUnit::YearMonth)
+        );
+
+        // components are not carried over to larger units
+        let dt = interval_dt(vec![Some((1, 43_200_000)); 4]);
+        let result = evaluate_columns(factors.clone(), Operator::Multiply, dt)?;
+        let expected = interval_dt(vec![
+            Some((3, 129_600_000)),
+            Some((-2, -86_400_000)),
+            None,
+            Some((0, 0)),
+        ]);
+        assert_eq!(&result, &expected);
+
+        let mdn = interval_mdn(vec![
+            Some((1, 20, 7)),
+            None,
+            Some((1, 1, 1)),
+            Some((1, 1, 1)),
+        ]);
+        let result = evaluate_columns(mdn, Operator::Multiply, factors)?;
+        let expected = interval_mdn(vec![Some((3, 60, 21)), None, None, Some((0, 0, 0))]);
+        assert_eq!(&result, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn interval_comparison() -> Result<()> {
+        // 1 month, 30 days, 31 days, 1 month - 1 nanosecond, null
+        let lhs = interval_mdn(vec![
+            Some((1, 0, 0)),
+            Some((1, 0, 0)),
+            Some((1, 0, 0)),
+            Some((1, 0, -1)),
+            None,
+        ]);
+        let rhs = interval_dt(vec![
+            Some((30, 0)),
+            Some((31, 0)),
+            Some((29, 86_400_000)),
+            Some((30, 0)),
+            Some((1, 0)),
+        ]);
+        let cases = [
+            (Operator::Eq, [true, false, true, false]),
+            (Operator::NotEq, [false, true, false, true]),
+            (Operator::Lt, [false, true, false, true]),
+            (Operator::LtEq, [true, true, true, true]),
+            (Operator::Gt, [false, false, false, false]),
+            (Operator::GtEq, [true, false, true, false]),
+        ];
+        for (op, expected) in cases {
+            let result = evaluate_columns(lhs.clone(), op, rhs.clone())?;
+            let mut expected = expected.into_iter().map(Some).collect::<Vec<_>>();
+            expected.push(None);
+            let expected: ArrayRef = Arc::new(BooleanArray::from(expected));
+            assert_eq!(&result, &expected, "{op}");
+        }
+
+        let result = evaluate_columns(lhs.clone(), Operator::IsDistinctFrom, rhs)?;
+        let expected: ArrayRef =
+            Arc::new(BooleanArray::from(vec![false, true, false, true, true]));
+        assert_eq!(&result, &expected);
+
+        // a year is 12 months, and 360 days
+        let year: ArrayRef = Arc::new(IntervalYearMonthArray::from(vec![12, 12]));
+        let days = interval_dt(vec![Some((360, 0)), Some((365, 0))]);
+        let result = evaluate_columns(year, Operator::Lt, days)?;
+        let expected: ArrayRef = Arc::new(BooleanArray::from(vec![false, true]));
+        assert_eq!(&result, &expected);
+
+        // comparison with a scalar
+        let schema = Schema::new(vec![Field::new("a", lhs.data_type().clone(), true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![lhs])?;
+        let expr = binary(
+            col("a", &schema)?,
+            Operator::Lt,
+            lit(ScalarValue::new_interval_mdn(0, 30, 0)),
+            &schema,
+        )?;
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+        let expected: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(false),
+            Some(false),
+            Some(false),
+            Some(true),
+            None,
+        ]));
+        assert_eq!(&result, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn interval_overflow() -> Result<()> {
+        let max_ym: ArrayRef = Arc::new(IntervalYearMonthArray::from(vec![i32::MAX]));
+        let one_ym: ArrayRef = Arc::new(IntervalYearMonthArray::from(vec![1]));
+        let min_dt = interval_dt(vec![Some((i32::MIN, 0))]);
+        let one_dt = interval_dt(vec![Some((1, 0))]);
+        let max_mdn = interval_mdn(vec![Some((0, 0, i64::MAX))]);
+        let one_mdn = interval_mdn(vec![Some((0, 0, 1))]);
+        let two: ArrayRef = Arc::new(Int64Array::from(vec![2]));
+        let huge: ArrayRef = Arc::new(Int64Array::from(vec![i64::MAX]));
+
+        let cases = [
+            (max_ym.clone(), Operator::Plus, one_ym),
+            (
+                max_ym.clone(),
+                Operator::Minus,
+                interval_mdn(vec![Some((-1, 0, 0))]),
+            ),
+            (min_dt.clone(), Operator::Minus, one_dt),
+            (min_dt, Operator::Minus, interval_mdn(vec![Some((0, 1, 0))])),
+            (max_mdn.clone(), Operator::Plus, one_mdn),
+            (max_ym, Operator::Multiply, two.clone()),
+            (two, Operator::Multiply, max_mdn),
+            (interval_dt(vec![Some((0, 1))]), Operator::Multiply, huge),
+        ];
+        for (left, op, right) in cases {
+            let description = format!("{} {op} {}", left.data_type(), right.data_type());
+            let err = evaluate_columns(left, op, right).unwrap_err().to_string();
+            assert!(err.contains("Overflow happened on"), "{description}: {err}");
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_to_result_type_array() {
         let values = Arc::new(Int32Array::from(vec![1, 2, 3, 4]));
@@ -31,9 +31,11 @@ use arrow::{array::*, datatypes::ArrowNumericType, downcast_dictionary_array};
 use arrow_array::types::{ArrowDictionaryKeyType, DecimalType};
 use arrow_array::ArrowNativeTypeOp;
 use arrow_buffer::ArrowNativeType;
-use arrow_schema::{DataType, IntervalUnit};
+use arrow_schema::{ArrowError, DataType, IntervalUnit};
 use chrono::{Days, Duration, Months, NaiveDate, NaiveDateTime};
-use datafusion_common::cast::{as_date32_array, as_date64_array, as_decimal128_array};
+use datafusion_common::cast::{
+    as_date32_array, as_date64_array, as_decimal128_array, as_int64_array,
+};
 use datafusion_common::scalar::{date32_op, date64_op};
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use std::cmp::min;
@@ -953,7 +955,7 @@ macro_rules! op_interval_macro {
     ($array:expr, $as_interval:expr, $interval_type:ty, $fn_op:expr, $scalar:expr, $sign:expr) => {{
         let array = $as_interval(&$array)?;
         let ret: PrimitiveArray<$interval_type> =
-            unary(array, |lhs| $fn_op(lhs, *$scalar, $sign));
+            try_unary(array, |lhs| Ok($fn_op(lhs, *$scalar, $sign)?))?;
         Arc::new(ret) as _
     }};
 }
@@ -962,13 +964,13 @@ macro_rules! op_interval_cross_macro {
     ($array:expr, $as_interval:expr, $commute:expr, $fn_op:expr, $scalar:expr, $sign:expr, $t1:ty, $t2:ty) => {{
         let array = $as_interval(&$array)?;
         let ret: PrimitiveArray<IntervalMonthDayNanoType> = if $commute {
-            unary(array, |lhs| {
-                $fn_op(*$scalar as $t1, lhs as $t2, $sign, $commute)
-            })
+            try_unary(array, |lhs| {
+                Ok($fn_op(*$scalar as $t1, lhs as $t2, $sign, $commute)?)
+            })?
         } else {
-            unary(array, |lhs| {
-                $fn_op(lhs as $t1, *$scalar as $t2, $sign, $commute)
-            })
+            try_unary(array, |lhs| {
+                Ok($fn_op(lhs as $t1, *$scalar as $t2, $sign, $commute)?)
+            })?
         };
         Arc::new(ret) as _
     }};
@@ -1000,11 +1002,12 @@ macro_rules! interval_op {
     ($lhs:ident, $rhs:ident, $caster:expr, $op:expr, $sign:ident, $type_in:ty) => {{
         let prim_array_lhs = $caster(&$lhs)?;
         let prim_array_rhs = $caster(&$rhs)?;
-        Arc::new(arrow::compute::binary::<$type_in, $type_in, _, $type_in>(
+        let ret: PrimitiveArray<$type_in> = arrow::compute::try_binary(
             prim_array_lhs,
             prim_array_rhs,
-            |interval1, interval2| $op(interval1, interval2, $sign),
-        )?) as _
+            |interval1, interval2| Ok($op(interval1, interval2, $sign)?),
+        )?;
+        Arc::new(ret) as _
     }};
 }
 
@@ -1012,16 +1015,12 @@ macro_rules! interval_cross_op {
     ($lhs:ident, $rhs:ident, $caster1:expr, $caster2:expr, $op:expr, $sign:ident, $commute:ident, $type_in1:ty, $type_in2:ty) => {{
         let prim_array_lhs = $caster1(&$lhs)?;
         let prim_array_rhs = $caster2(&$rhs)?;
-        Arc::new(arrow::compute::binary::<
-            $type_in1,
-            $type_in2,
-            _,
-            IntervalMonthDayNanoType,
-        >(
+        let ret: PrimitiveArray<IntervalMonthDayNanoType> = arrow::compute::try_binary(
             prim_array_lhs,
             prim_array_rhs,
-            |interval1, interval2| $op(interval1, interval2, $sign, $commute),
-        )?) as _
+            |interval1, interval2| Ok($op(interval1, interval2, $sign, $commute)?),
+        )?;
+        Arc::new(ret) as _
     }};
 }
 
@@ -1615,63 +1614,63 @@ pub fn scalar_interval_op_interval(
         (IntervalYearMonth(Some(lhs)), Interval(YearMonth)) => {
             let array = as_interval_ym_array(&array)?;
             let ret: PrimitiveArray<IntervalYearMonthType> =
-                unary(array, |rhs| op_ym(*lhs, rhs, sign));
+                try_unary(array, |rhs| Ok(op_ym(*lhs, rhs, sign)?))?;
             Arc::new(ret) as _
         }
         // DayTime op YearMonth
         (IntervalDayTime(Some(lhs)), Interval(YearMonth)) => {
             let array = as_interval_ym_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_ym_dt(rhs, *lhs, sign, true));
+                try_unary(array, |rhs| Ok(op_ym_dt(rhs, *lhs, sign, true)?))?;
             Arc::new(ret) as _
         }
         // MonthDayNano op YearMonth
         (IntervalMonthDayNano(Some(lhs)), Interval(YearMonth)) => {
             let array = as_interval_ym_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_ym_mdn(rhs, *lhs, sign, true));
+                try_unary(array, |rhs| Ok(op_ym_mdn(rhs, *lhs, sign, true)?))?;
             Arc::new(ret) as _
         }
         // YearMonth op DayTime
         (IntervalYearMonth(Some(lhs)), Interval(DayTime)) => {
             let array = as_interval_dt_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_ym_dt(*lhs, rhs, sign, false));
+                try_unary(array, |rhs| Ok(op_ym_dt(*lhs, rhs, sign, false)?))?;
             Arc::new(ret) as _
         }
         // DayTime op DayTime
         (IntervalDayTime(Some(lhs)), Interval(DayTime)) => {
             let array = as_interval_dt_array(&array)?;
             let ret: PrimitiveArray<IntervalDayTimeType> =
-                unary(array, |rhs| op_dt(*lhs, rhs, sign));
+                try_unary(array, |rhs| Ok(op_dt(*lhs, rhs, sign)?))?;
             Arc::new(ret) as _
         }
         // MonthDayNano op DayTime
         (IntervalMonthDayNano(Some(lhs)), Interval(DayTime)) => {
             let array = as_interval_dt_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_dt_mdn(rhs, *lhs, sign, true));
+                try_unary(array, |rhs| Ok(op_dt_mdn(rhs, *lhs, sign, true)?))?;
             Arc::new(ret) as _
         }
         // YearMonth op MonthDayNano
         (IntervalYearMonth(Some(lhs)), Interval(MonthDayNano)) => {
             let array = as_interval_mdn_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_ym_mdn(*lhs, rhs, sign, false));
+                try_unary(array, |rhs| Ok(op_ym_mdn(*lhs, rhs, sign, false)?))?;
             Arc::new(ret) as _
         }
         // DayTime op MonthDayNano
         (IntervalDayTime(Some(lhs)), Interval(MonthDayNano)) => {
             let array = as_interval_mdn_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_dt_mdn(*lhs, rhs, sign, false));
+                try_unary(array, |rhs| Ok(op_dt_mdn(*lhs, rhs, sign, false)?))?;
             Arc::new(ret) as _
         }
         // MonthDayNano op MonthDayNano
         (IntervalMonthDayNano(Some(lhs)), Interval(MonthDayNano)) => {
             let array = as_interval_mdn_array(&array)?;
             let ret: PrimitiveArray<IntervalMonthDayNanoType> =
-                unary(array, |rhs| op_mdn(*lhs, rhs, sign));
+                try_unary(array, |rhs| Ok(op_mdn(*lhs, rhs, sign)?))?;
             Arc::new(ret) as _
         }
         _ => Err(DataFusionError::Internal(format!(
@@ -1859,6 +1858,121 @@ pub fn interval_array_op(
     }
 }
 
+/// Number of nanoseconds in a month when comparing intervals, see [`normalize_intervals`]
+const NANOSECONDS_IN_MONTH: i128 = 30 * NANOSECONDS_IN_DAY as i128;
+
+/// Converts an interval array of any unit to the equivalent numbers of nanoseconds,
+/// so that intervals can be ordered.
+///
+/// Intervals made of months, days and nanoseconds have no natural order, as the
+/// length of a month (and, across daylight saving time changes, of a day) depends
+/// on the timestamp the interval is added to. Like PostgreSQL, intervals are
+/// compared on a normalized basis where a month is 30 days and a day is 24 hours.
+/// For example `INTERVAL '1 month'` is equal to `INTERVAL '30 days'` and less
+/// than `INTERVAL '31 days'`, even though adding them to `2023-01-01` gives
+/// `2023-02-01`, `2023-01-31` and `2023-02-01` respectively.
+///
+/// The nanoseconds are returned as a [`Decimal128Array`] with a scale of 0, which
+/// the comparison kernels of arrow support.
+pub(crate) fn normalize_intervals(array: &ArrayRef) -> Result<ArrayRef> {
+    let ret: PrimitiveArray<Decimal128Type> = match array.data_type() {
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            unary(as_interval_ym_array(array.as_ref())?, |months| {
+                months as i128 * NANOSECONDS_IN_MONTH
+            })
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            unary(as_interval_dt_array(array.as_ref())?, |interval| {
+                let (days, millis) = IntervalDayTimeType::to_parts(interval);
+                days as i128 * NANOSECONDS_IN_DAY as i128 + millis as i128 * 1_000_000
+            })
+        }
+        DataType::Interval(IntervalUnit::MonthDayNano) => {
+            unary(as_interval_mdn_array(array.as_ref())?, |interval| {
+                let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(interval);
+                months as i128 * NANOSECONDS_IN_MONTH
+                    + days as i128 * NANOSECONDS_IN_DAY as i128
+                    + nanos as i128
+            })
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Cannot normalize values of type {other} as intervals"
+            )))
+        }
+    };
+    Ok(Arc::new(
+        ret.with_precision_and_scale(DECIMAL128_MAX_PRECISION, 0)?,
+    ))
+}
+
+/// Multiplies a 32-bit component of an interval by `factor`, returning an error
+/// if the result does not fit in the component.
+fn multiply_interval_component(value: i32, factor: i64) -> Result<i32, ArrowError> {
+    (value as i64)
+        .checked_mul(factor)
+        .and_then(|product| i32::try_from(product).ok())
+        .ok_or_else(|| {
+            ArrowError::ComputeError(format!(
+                "Overflow happened on: {value:?} * {factor:?}"
+            ))
+        })
+}
+
+/// Multiplies every interval of `intervals` by the integer at the same position
+/// of `factors`, resulting in an interval array of the same unit as `intervals`.
+/// Each component of an interval is multiplied separately, without carrying over
+/// to larger units, e.g. `INTERVAL '1 month 20 days' * 2` is
+/// `INTERVAL '2 months 40 days'`. Returns an error if any component overflows.
+pub(crate) fn multiply_dyn_interval(
+    intervals: &ArrayRef,
+    factors: &ArrayRef,
+) -> Result<ArrayRef> {
+    let factors = as_int64_array(factors.as_ref())?;
+    let ret: ArrayRef = match intervals.data_type() {
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            let intervals = as_interval_ym_array(intervals.as_ref())?;
+            let ret: PrimitiveArray<IntervalYearMonthType> =
+                arrow::compute::try_binary(intervals, factors, |months, factor| {
+                    multiply_interval_component(months, factor)
+                })?;
+            Arc::new(ret)
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            let intervals = as_interval_dt_array(intervals.as_ref())?;
+            let ret: PrimitiveArray<IntervalDayTimeType> =
+                arrow::compute::try_binary(intervals, factors, |interval, factor| {
+                    let (days, millis) = IntervalDayTimeType::to_parts(interval);
+                    Ok(IntervalDayTimeType::make_value(
+                        multiply_interval_component(days, factor)?,
+                        multiply_interval_component(millis, factor)?,
+                    ))
+                })?;
+            Arc::new(ret)
+        }
+        DataType::Interval(IntervalUnit::MonthDayNano) => {
+            let intervals = as_interval_mdn_array(intervals.as_ref())?;
+            let ret: PrimitiveArray<IntervalMonthDayNanoType> =
+                arrow::compute::try_binary(intervals, factors, |interval, factor| {
+                    let (months, days, nanos) =
+                        IntervalMonthDayNanoType::to_parts(interval);
+                    Ok(IntervalMonthDayNanoType::make_value(
+                        multiply_interval_component(months, factor)?,
+                        multiply_interval_component(days, factor)?,
+                        nanos.mul_checked(factor)?,
+                    ))
+                })?;
+            Arc::new(ret)
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Cannot multiply values of type {other} as intervals"
+            )))
+        }
+    };
+    Ok(ret)
+}
+
 /// Performs a timestamp/interval operation on two arrays and returns the resulting array.
 /// The operation sign determines whether to perform addition or subtraction.
 /// The data type and unit of the two input arrays must match the supported combinations.
@@ -22,11 +22,19 @@ use std::sync::Arc;
 use crate::intervals::Interval;
 use crate::physical_expr::down_cast_any_ref;
 use crate::PhysicalExpr;
+use arrow::array::{ArrayRef, Int64Array};
 use arrow::compute;
 use arrow::compute::{kernels, CastOptions};
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{
+    DataType, Schema, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType,
+};
 use arrow::record_batch::RecordBatch;
+use arrow_array::temporal_conversions::as_datetime;
+use arrow_array::timezone::Tz;
+use chrono::{NaiveDateTime, TimeZone};
 use compute::can_cast_types;
+use datafusion_common::cast::as_int64_array;
 use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::ColumnarValue;
@@ -40,6 +48,14 @@ fn default_cast_options() -> CastOptions<'static> {
 }
 
 /// CAST expression casts an expression to a specific data type and returns a runtime error on invalid cast
+///
+/// Casts between timestamps follow these rules for timezone annotations:
+/// * between two annotated timestamps only the annotation changes, the values
+///   are the same UTC instants
+/// * from a timestamp without annotation, the values are the wall clock time
+///   in the target timezone
+/// * to a timestamp without annotation, the values become the wall clock time
+///   in the source timezone
 #[derive(Debug)]
 pub struct CastExpr {
     /// The expression to cast
@@ -156,22 +172,84 @@ pub fn cast_column(
 ) -> Result<ColumnarValue> {
     let cast_options = cast_options.cloned().unwrap_or_else(default_cast_options);
     match value {
-        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(
-            kernels::cast::cast_with_options(array, cast_type, &cast_options)?,
-        )),
+        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(cast_array(
+            array,
+            cast_type,
+            &cast_options,
+        )?)),
         ColumnarValue::Scalar(scalar) => {
             let scalar_array = scalar.to_array();
-            let cast_array = kernels::cast::cast_with_options(
-                &scalar_array,
-                cast_type,
-                &cast_options,
-            )?;
+            let cast_array = cast_array(&scalar_array, cast_type, &cast_options)?;
             let cast_scalar = ScalarValue::try_from_array(&cast_array, 0)?;
             Ok(ColumnarValue::Scalar(cast_scalar))
         }
     }
 }
 
+/// Casts `array` to `cast_type`, see [`CastExpr`] for the handling of
+/// timezone annotations
+pub(crate) fn cast_array(
+    array: &ArrayRef,
+    cast_type: &DataType,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef> {
+    match (array.data_type(), cast_type) {
+        (DataType::Timestamp(_, Some(tz_name)), DataType::Timestamp(unit, None)) => {
+            let tz: Tz = tz_name.parse()?;
+            // change the unit first, keeping the annotation
+            let instants = kernels::cast::cast_with_options(
+                array,
+                &DataType::Timestamp(unit.clone(), Some(tz_name.clone())),
+                cast_options,
+            )?;
+            let instants = kernels::cast::cast(&instants, &DataType::Int64)?;
+            let local = as_int64_array(&instants)?
+                .iter()
+                .map(|value| match value.map(|v| utc_to_local(v, unit, &tz)) {
+                    Some(Err(_)) if cast_options.safe => Ok(None),
+                    other => other.transpose(),
+                })
+                .collect::<Result<Int64Array>>()?;
+            Ok(kernels::cast::cast(
+                &(Arc::new(local) as ArrayRef),
+                cast_type,
+            )?)
+        }
+        _ => Ok(kernels::cast::cast_with_options(
+            array,
+            cast_type,
+            cast_options,
+        )?),
+    }
+}
+
+/// Converts the UTC instant `value` in `unit` to the wall clock time in `tz`
+fn utc_to_local(value: i64, unit: &TimeUnit, tz: &Tz) -> Result<i64> {
+    let utc = match unit {
+        TimeUnit::Second => as_datetime::<TimestampSecondType>(value),
+        TimeUnit::Millisecond => as_datetime::<TimestampMillisecondType>(value),
+        TimeUnit::Microsecond => as_datetime::<TimestampMicrosecondType>(value),
+        TimeUnit::Nanosecond => as_datetime::<TimestampNanosecondType>(value),
+    }
+    .ok_or_else(|| {
+        DataFusionError::Execution(format!(
+            "Cannot convert {value} {unit:?} to a timestamp"
+        ))
+    })?;
+    let local: NaiveDateTime = tz.from_utc_datetime(&utc).naive_local();
+    match unit {
+        TimeUnit::Second => Ok(local.timestamp()),
+        TimeUnit::Millisecond => Ok(local.timestamp_millis()),
+        TimeUnit::Microsecond => Ok(local.timestamp_micros()),
+        // the wall clock time may be out of the range of nanosecond timestamps
+        TimeUnit::Nanosecond => local.timestamp_nanos_opt().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "Cannot convert {value} {unit:?} to the local time {local}, which is out of range"
+            ))
+        }),
+    }
+}
+
 /// Return a PhysicalExpression representing `expr` casted to
 /// `cast_type`, if any casting is needed.
 ///
@@ -214,7 +292,7 @@ mod tests {
         array::{
             Array, Decimal128Array, Float32Array, Float64Array, Int16Array, Int32Array,
             Int64Array, Int8Array, StringArray, Time64NanosecondArray,
-            TimestampNanosecondArray, UInt32Array,
+            TimestampNanosecondArray, TimestampSecondArray, UInt32Array,
         },
         datatypes::*,
     };
@@ -683,6 +761,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_cast_timestamp_timezones() -> Result<()> {
+        // 2000-01-01T00:00:00Z
+        let instant = 946_684_800_000_000_000;
+        let hour = 3_600_000_000_000;
+        let cast_timestamp = |tz: &str, cast_type: DataType| -> Result<ArrayRef> {
+            let array = TimestampNanosecondArray::from(vec![Some(instant), None])
+                .with_timezone(tz);
+            let schema =
+                Schema::new(vec![Field::new("a", array.data_type().clone(), true)]);
+            let batch =
+                RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(array)])?;
+            let expression =
+                cast_with_options(col("a", &schema)?, &schema, cast_type, None)?;
+            Ok(expression.evaluate(&batch)?.into_array(batch.num_rows()))
+        };
+
+        // changing the annotation keeps the UTC instant
+        let result = cast_timestamp(
+            "UTC",
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("+02:00".into())),
+        )?;
+        let expected: ArrayRef = Arc::new(
+            TimestampNanosecondArray::from(vec![Some(instant), None])
+                .with_timezone("+02:00"),
+        );
+        assert_eq!(&result, &expected);
+
+        // dropping the annotation gives the wall clock time of the timezone
+        let result =
+            cast_timestamp("+02:00", DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+        let expected: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![
+            Some(instant + 2 * hour),
+            None,
+        ]));
+        assert_eq!(&result, &expected);
+
+        let result =
+            cast_timestamp("-08:00", DataType::Timestamp(TimeUnit::Second, None))?;
+        let expected: ArrayRef = Arc::new(TimestampSecondArray::from(vec![
+            Some((instant - 8 * hour) / 1_000_000_000),
+            None,
+        ]));
+        assert_eq!(&result, &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cast_timestamp_timezone_out_of_range() -> Result<()> {
+        // the latest nanosecond timestamp, whose wall clock time east of UTC
+        // cannot be represented in nanoseconds
+        let array =
+            TimestampNanosecondArray::from(vec![Some(i64::MAX)]).with_timezone("+02:00");
+        let schema = Schema::new(vec![Field::new("a", array.data_type().clone(), true)]);
+        let batch =
+            RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(array)])?;
+
+        let cast_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let expression =
+            cast_with_options(col("a", &schema)?, &schema, cast_type.clone(), None)?;
+        let err = expression.evaluate(&batch).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+
+        // safe casts return null instead
+        let expression = cast_with_options(
+            col("a", &schema)?,
+            &schema,
+            cast_type,
+            Some(CastOptions {
+                safe: true,
+                format_options: Default::default(),
+            }),
+        )?;
+        let result = expression.evaluate(&batch)?.into_array(batch.num_rows());
+        assert!(result.is_null(0));
+        Ok(())
+    }
+
     #[test]
     #[ignore] // TODO: https://github.com/apache/arrow-datafusion/issues/5396
     fn test_cast_decimal() -> Result<()> {
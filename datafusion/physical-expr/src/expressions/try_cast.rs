@@ -19,10 +19,11 @@ use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
 
+use crate::expressions::cast::cast_array;
 use crate::physical_expr::down_cast_any_ref;
 use crate::PhysicalExpr;
 use arrow::compute;
-use arrow::compute::kernels;
+use arrow::compute::kernels::cast::DEFAULT_CAST_OPTIONS;
 use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 use compute::can_cast_types;
@@ -79,13 +80,15 @@ impl PhysicalExpr for TryCastExpr {
     fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
         let value = self.expr.evaluate(batch)?;
         match value {
-            ColumnarValue::Array(array) => Ok(ColumnarValue::Array(kernels::cast::cast(
+            ColumnarValue::Array(array) => Ok(ColumnarValue::Array(cast_array(
                 &array,
                 &self.cast_type,
+                &DEFAULT_CAST_OPTIONS,
             )?)),
             ColumnarValue::Scalar(scalar) => {
                 let scalar_array = scalar.to_array();
-                let cast_array = kernels::cast::cast(&scalar_array, &self.cast_type)?;
+                let cast_array =
+                    cast_array(&scalar_array, &self.cast_type, &DEFAULT_CAST_OPTIONS)?;
                 let cast_scalar = ScalarValue::try_from_array(&cast_array, 0)?;
                 Ok(ColumnarValue::Scalar(cast_scalar))
             }
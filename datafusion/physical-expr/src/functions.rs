@@ -42,6 +42,7 @@ use arrow::{
     compute::kernels::length::{bit_length, length},
     datatypes::TimeUnit,
     datatypes::{DataType, Int32Type, Int64Type, Schema},
+    downcast_dictionary_array,
 };
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::{
@@ -173,6 +174,15 @@ pub fn create_physical_expr(
         _ => create_physical_fun(fun, execution_props)?,
     };
 
+    let fun_expr = match input_expr_types.first() {
+        Some(DataType::Dictionary(_, value_type))
+            if fun.preserves_dictionary_encoding() =>
+        {
+            dictionary_values_fn(fun_expr, value_type.as_ref().clone(), data_type.clone())
+        }
+        _ => fun_expr,
+    };
+
     Ok(Arc::new(ScalarFunctionExpr::new(
         &format!("{fun}"),
         fun_expr,
@@ -181,6 +191,58 @@ pub fn create_physical_expr(
     )))
 }
 
+/// Wraps `fun`, whose first argument is dictionary encoded with values of
+/// `value_type`, so that it is applied once to the dictionary values instead of
+/// once per row, and returns a dictionary of `return_type` with the original keys.
+///
+/// The dictionary is only expanded when some of the other arguments are not
+/// constant, in which case the result is encoded back to `return_type`.
+fn dictionary_values_fn(
+    fun: ScalarFunctionImplementation,
+    value_type: DataType,
+    return_type: DataType,
+) -> ScalarFunctionImplementation {
+    Arc::new(move |args: &[ColumnarValue]| {
+        let constant_args = args[1..]
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+        match &args[0] {
+            ColumnarValue::Array(array) if constant_args => {
+                let array = array.as_ref();
+                let result = downcast_dictionary_array!(
+                    array => {
+                        let mut values_args = args.to_vec();
+                        values_args[0] = ColumnarValue::Array(array.values().clone());
+                        let values = fun(&values_args)?.into_array(array.values().len());
+                        Arc::new(array.with_values(values.as_ref())) as ArrayRef
+                    }
+                    t => return Err(DataFusionError::Internal(format!(
+                        "Unexpected data type {t} for a dictionary encoded argument"
+                    ))),
+                );
+                Ok(ColumnarValue::Array(result))
+            }
+            ColumnarValue::Scalar(ScalarValue::Dictionary(key_type, value))
+                if constant_args =>
+            {
+                let mut values_args = args.to_vec();
+                values_args[0] = ColumnarValue::Scalar(value.as_ref().clone());
+                match fun(&values_args)? {
+                    ColumnarValue::Scalar(result) => Ok(ColumnarValue::Scalar(
+                        ScalarValue::Dictionary(key_type.clone(), Box::new(result)),
+                    )),
+                    result => cast_column(&result, &return_type, None),
+                }
+            }
+            _ => {
+                let mut expanded_args = args.to_vec();
+                expanded_args[0] = cast_column(&args[0], &value_type, None)?;
+                cast_column(&fun(&expanded_args)?, &return_type, None)
+            }
+        }
+    })
+}
+
 #[cfg(feature = "crypto_expressions")]
 macro_rules! invoke_if_crypto_expressions_feature_flag {
     ($FUNC:ident, $NAME:expr) => {{
@@ -813,13 +875,15 @@ mod tests {
     use crate::expressions::{col, lit};
     use arrow::{
         array::{
-            Array, ArrayRef, BinaryArray, BooleanArray, Float32Array, Float64Array,
-            Int32Array, StringArray, UInt64Array,
+            Array, ArrayRef, BinaryArray, BooleanArray, DictionaryArray, Float32Array,
+            Float64Array, Int32Array, Int64Array, StringArray, UInt64Array,
         },
         datatypes::Field,
         record_batch::RecordBatch,
     };
-    use datafusion_common::cast::as_uint64_array;
+    use datafusion_common::cast::{
+        as_dictionary_array, as_string_array, as_uint64_array,
+    };
     use datafusion_common::{Result, ScalarValue};
     use datafusion_expr::type_coercion::functions::data_types;
     use datafusion_expr::Signature;
@@ -3061,4 +3125,116 @@ mod tests {
 
         Ok(())
     }
+
+    /// Evaluates `fun` on a dictionary encoded column `a`, and checks its result
+    /// is a dictionary with the keys of `a` and the logical values `expected`
+    fn test_dictionary_function(
+        fun: BuiltinScalarFunction,
+        args: Vec<Arc<dyn PhysicalExpr>>,
+        batch: &RecordBatch,
+        expected: Vec<Option<&str>>,
+    ) -> Result<()> {
+        let schema = batch.schema();
+        let expr = create_physical_expr(&fun, &args, &schema, &ExecutionProps::new())?;
+        let dictionary =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        assert_eq!(expr.data_type(&schema)?, dictionary);
+
+        let result = expr.evaluate(batch)?.into_array(batch.num_rows());
+        assert_eq!(result.data_type(), &dictionary);
+        let result = as_dictionary_array::<Int32Type>(result.as_ref())?;
+        let input = as_dictionary_array::<Int32Type>(batch.column(0).as_ref())?;
+        assert_eq!(result.keys(), input.keys());
+
+        let result = arrow::compute::cast(result, &DataType::Utf8)?;
+        assert_eq!(
+            as_string_array(result.as_ref())?,
+            &StringArray::from(expected)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dictionary_functions() -> Result<()> {
+        let values: DictionaryArray<Int32Type> =
+            vec![Some(" foo"), Some("Bar "), None, Some(" foo"), Some("Bar ")]
+                .into_iter()
+                .collect();
+        let schema = Schema::new(vec![Field::new("a", values.data_type().clone(), true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)])?;
+        let a = col("a", &batch.schema())?;
+
+        test_dictionary_function(
+            BuiltinScalarFunction::Upper,
+            vec![a.clone()],
+            &batch,
+            vec![Some(" FOO"), Some("BAR "), None, Some(" FOO"), Some("BAR ")],
+        )?;
+        test_dictionary_function(
+            BuiltinScalarFunction::Trim,
+            vec![a.clone()],
+            &batch,
+            vec![Some("foo"), Some("Bar"), None, Some("foo"), Some("Bar")],
+        )?;
+        test_dictionary_function(
+            BuiltinScalarFunction::Substr,
+            vec![a.clone(), lit(ScalarValue::Int64(Some(2)))],
+            &batch,
+            vec![Some("foo"), Some("ar "), None, Some("foo"), Some("ar ")],
+        )?;
+
+        // the dictionary is expanded when the other arguments are not constant
+        let positions = Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef;
+        let schema = Schema::new(vec![
+            Field::new("a", batch.schema().field(0).data_type().clone(), true),
+            Field::new("p", DataType::Int64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![batch.column(0).clone(), positions],
+        )?;
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Substr,
+            &[a, col("p", &batch.schema())?],
+            &batch.schema(),
+            &ExecutionProps::new(),
+        )?;
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+        let result = arrow::compute::cast(&result, &DataType::Utf8)?;
+        let expected =
+            StringArray::from(vec![Some(" foo"), Some("ar "), None, Some("o"), Some("")]);
+        assert_eq!(as_string_array(result.as_ref())?, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dictionary_function_scalar() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(vec![1]))],
+        )?;
+        let value = ScalarValue::Dictionary(
+            Box::new(DataType::Int32),
+            Box::new(ScalarValue::from("foo")),
+        );
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Upper,
+            &[lit(value)],
+            &schema,
+            &ExecutionProps::new(),
+        )?;
+        match expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(result) => assert_eq!(
+                result,
+                ScalarValue::Dictionary(
+                    Box::new(DataType::Int32),
+                    Box::new(ScalarValue::from("FOO"))
+                )
+            ),
+            result => panic!("expected a scalar, got {result:?}"),
+        }
+        Ok(())
+    }
 }
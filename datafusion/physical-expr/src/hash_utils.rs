@@ -38,6 +38,9 @@ fn combine_hashes(l: u64, r: u64) -> u64 {
     hash.wrapping_mul(37).wrapping_add(r)
 }
 
+/// Hash the values of an array of [`DataType::Null`], all of which get the same
+/// hash independently of the current content of `hashes_buffer`, so that
+/// null keys of every batch land in the same bucket
 fn hash_null(random_state: &RandomState, hashes_buffer: &'_ mut [u64], mul_col: bool) {
     if mul_col {
         hashes_buffer.iter_mut().for_each(|hash| {
@@ -76,8 +79,17 @@ hash_value!(bool, str, [u8]);
 macro_rules! hash_float_value {
     ($(($t:ty, $i:ty)),+) => {
         $(impl HashValue for $t {
+            // Hash all NaNs to one value and `-0.0` to the value of `0.0`, so that
+            // keys that compare equal as join or grouping keys land in the same bucket
             fn hash_one(&self, state: &RandomState) -> u64 {
-                state.hash_one(<$i>::from_ne_bytes(self.to_ne_bytes()))
+                let value = if self.is_nan() {
+                    <$t>::NAN
+                } else if *self == <$t>::default() {
+                    <$t>::default()
+                } else {
+                    *self
+                };
+                state.hash_one(<$i>::from_ne_bytes(value.to_ne_bytes()))
             }
         })+
     };
@@ -129,6 +141,25 @@ fn hash_dictionary<K: ArrowDictionaryKeyType>(
     // hash for each key value to avoid a potentially expensive
     // redundant hashing for large dictionary elements (e.g. strings)
     let values = Arc::clone(array.values());
+    if values.len() > array.len() {
+        // The keys refer to a fraction of the values, like in a slice of a large
+        // dictionary array, so only the values of the keys are hashed
+        let keys_values = arrow::compute::take(values.as_ref(), array.keys(), None)?;
+        let mut values_hashes = vec![0; array.len()];
+        create_hashes(&[keys_values], random_state, &mut values_hashes)?;
+        for (i, (hash, values_hash)) in
+            hashes_buffer.iter_mut().zip(values_hashes).enumerate()
+        {
+            if array.keys().is_valid(i) {
+                *hash = if multi_col {
+                    combine_hashes(values_hash, *hash)
+                } else {
+                    values_hash
+                };
+            } // no update for Null, consistent with other hashes
+        }
+        return Ok(());
+    }
     let mut dict_hashes = vec![0; values.len()];
     create_hashes(&[values], random_state, &mut dict_hashes)?;
 
@@ -149,6 +180,32 @@ fn hash_dictionary<K: ArrowDictionaryKeyType>(
     Ok(())
 }
 
+/// Hash the values in a struct array
+fn hash_struct(
+    array: &StructArray,
+    random_state: &RandomState,
+    hashes_buffer: &mut [u64],
+    multi_col: bool,
+) -> Result<()> {
+    // Hash the fields of every struct together, like the columns of a
+    // multi column key, so that equal structs have equal hashes
+    let mut values_hashes = vec![0; array.len()];
+    create_hashes(array.columns(), random_state, &mut values_hashes)?;
+
+    for (i, (hash, values_hash)) in
+        hashes_buffer.iter_mut().zip(values_hashes).enumerate()
+    {
+        if array.is_valid(i) {
+            *hash = if multi_col {
+                combine_hashes(values_hash, *hash)
+            } else {
+                values_hash
+            };
+        } // no update for Null, consistent with other hashes
+    }
+    Ok(())
+}
+
 /// Test version of `create_hashes` that produces the same value for
 /// all hashes (to test collisions)
 ///
@@ -238,6 +295,10 @@ pub fn create_hashes<'a>(
                 array => hash_dictionary(array, random_state, hashes_buffer, multi_col)?,
                 _ => unreachable!()
             }
+            DataType::Struct(_) => {
+                let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+                hash_struct(array, random_state, hashes_buffer, multi_col)?
+            }
             _ => {
                 // This is internal because we should have caught this before.
                 return Err(DataFusionError::Internal(format!(
@@ -321,6 +382,97 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_hashes_for_interval_arrays() -> Result<()> {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(IntervalYearMonthArray::from(vec![Some(1), None, Some(1)])),
+            Arc::new(IntervalDayTimeArray::from(vec![
+                Some(IntervalDayTimeType::make_value(1, 2)),
+                None,
+                Some(IntervalDayTimeType::make_value(1, 2)),
+            ])),
+            Arc::new(IntervalMonthDayNanoArray::from(vec![
+                Some(IntervalMonthDayNanoType::make_value(1, 2, 3)),
+                None,
+                Some(IntervalMonthDayNanoType::make_value(1, 2, 3)),
+            ])),
+        ];
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        for array in arrays {
+            let hashes_buff = &mut vec![0; array.len()];
+            let hashes = create_hashes(&[array], &random_state, hashes_buff)?;
+            assert_eq!(hashes[0], hashes[2]);
+            assert_ne!(hashes[0], hashes[1]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_hashes_for_null_arrays() -> Result<()> {
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+
+        let nulls: ArrayRef = Arc::new(NullArray::new(3));
+        let hashes_buff = &mut vec![1, 2, 3];
+        let hashes = create_hashes(&[nulls], &random_state, hashes_buff)?.clone();
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0], hashes[2]);
+
+        // same hash in another batch
+        let nulls: ArrayRef = Arc::new(NullArray::new(2));
+        let hashes_buff = &mut vec![0; 2];
+        let other_hashes = create_hashes(&[nulls], &random_state, hashes_buff)?;
+        assert_eq!(hashes[0], other_hashes[0]);
+        assert_eq!(hashes[0], other_hashes[1]);
+
+        // combined with the hash of the other columns
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![1, 1, 2]));
+        let nulls: ArrayRef = Arc::new(NullArray::new(3));
+        let hashes_buff = &mut vec![0; 3];
+        let hashes = create_hashes(&[ints, nulls], &random_state, hashes_buff)?;
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+
+        Ok(())
+    }
+
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_hashes_for_float_nan_and_zero() -> Result<()> {
+        let f32_arr: ArrayRef = Arc::new(Float32Array::from(vec![
+            0.0,
+            -0.0,
+            f32::NAN,
+            -f32::NAN,
+            f32::from_bits(0x7fc0_0001),
+        ]));
+        let f64_arr: ArrayRef = Arc::new(Float64Array::from(vec![
+            0.0,
+            -0.0,
+            f64::NAN,
+            -f64::NAN,
+            f64::from_bits(0x7ff8_0000_0000_0001),
+        ]));
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        for array in [f32_arr, f64_arr] {
+            let hashes_buff = &mut vec![0; array.len()];
+            let hashes = create_hashes(&[array], &random_state, hashes_buff)?;
+            assert_eq!(hashes[0], hashes[1]);
+            assert_ne!(hashes[0], hashes[2]);
+            assert_eq!(hashes[2], hashes[3]);
+            assert_eq!(hashes[2], hashes[4]);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn create_hashes_binary() -> Result<()> {
         let byte_array = Arc::new(BinaryArray::from_vec(vec![
@@ -396,6 +548,32 @@ mod tests {
         assert_ne!(dict_hashes[0], dict_hashes[2]);
     }
 
+    #[test]
+    fn create_hashes_for_sliced_dict_arrays() {
+        let strings = (0..100)
+            .map(|i| Some(format!("value {i}")))
+            .chain([None])
+            .collect::<Vec<_>>();
+        let dict_array: ArrayRef = Arc::new(
+            strings
+                .iter()
+                .cloned()
+                .collect::<DictionaryArray<Int32Type>>(),
+        );
+        // the slice refers to 3 of the 100 values of the dictionary
+        let sliced = dict_array.slice(98, 3);
+        let string_array = strings[98..].iter().cloned().collect::<StringArray>();
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+
+        let mut string_hashes = vec![0; 3];
+        create_hashes(&[Arc::new(string_array)], &random_state, &mut string_hashes)
+            .unwrap();
+        let mut dict_hashes = vec![0; 3];
+        create_hashes(&[sliced], &random_state, &mut dict_hashes).unwrap();
+        assert_eq!(string_hashes, dict_hashes);
+    }
+
     #[test]
     // Tests actual values of hashes, which are different if forcing collisions
     #[cfg(not(feature = "force_hash_collisions"))]
@@ -429,4 +607,46 @@ mod tests {
 
         assert_ne!(one_col_hashes, two_col_hashes);
     }
+
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_hashes_for_struct_arrays() {
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![1, 3, 2, 1]));
+        let strings: ArrayRef =
+            Arc::new(StringArray::from(vec!["foo", "x", "bar", "foo"]));
+        let struct_array = StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int32, false)),
+                ints.clone(),
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Utf8, false)),
+                strings.clone(),
+            ),
+        ]);
+        let struct_array = arrow::compute::nullif(
+            &struct_array,
+            &BooleanArray::from(vec![false, true, false, false]),
+        )
+        .unwrap();
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+
+        let mut struct_hashes = vec![0; 4];
+        create_hashes(&[struct_array], &random_state, &mut struct_hashes).unwrap();
+
+        let mut fields_hashes = vec![0; 4];
+        create_hashes(&[ints, strings], &random_state, &mut fields_hashes).unwrap();
+
+        // Valid structs hash like their fields, null structs result in a zero hash
+        assert_eq!(struct_hashes[0], fields_hashes[0]);
+        assert_eq!(struct_hashes[1], 0);
+        assert_eq!(struct_hashes[2], fields_hashes[2]);
+
+        // equal structs should map to the same hash value, different structs
+        // to different hash values
+        assert_eq!(struct_hashes[0], struct_hashes[3]);
+        assert_ne!(struct_hashes[0], struct_hashes[2]);
+    }
 }
@@ -23,6 +23,7 @@ use datafusion_common::utils::DataPtr;
 use datafusion_common::{
     ColumnStatistics, DataFusionError, Result, ScalarValue, Statistics,
 };
+use datafusion_expr::null_rejection::{NullRejection, NullRejectionNode};
 use datafusion_expr::ColumnarValue;
 
 use std::cmp::Ordering;
@@ -31,6 +32,9 @@ use std::fmt::{Debug, Display};
 use arrow::array::{make_array, Array, ArrayRef, BooleanArray, MutableArrayData};
 use arrow::compute::{and_kleene, filter_record_batch, is_not_null, SlicesIterator};
 
+use crate::expressions::{
+    BinaryExpr, CastExpr, Column, IsNotNullExpr, NegativeExpr, NotExpr, TryCastExpr,
+};
 use crate::intervals::Interval;
 use std::any::Any;
 use std::sync::Arc;
@@ -271,6 +275,33 @@ pub fn down_cast_any_ref(any: &dyn Any) -> &dyn Any {
     }
 }
 
+impl NullRejection for dyn PhysicalExpr {
+    fn null_rejection_node(&self) -> NullRejectionNode<'_, Self> {
+        let any = self.as_any();
+        if any.is::<Column>() {
+            NullRejectionNode::Column
+        } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+            NullRejectionNode::Binary(
+                binary.left().as_ref(),
+                *binary.op(),
+                binary.right().as_ref(),
+            )
+        } else if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+            NullRejectionNode::IsNotNull(is_not_null.arg().as_ref())
+        } else if let Some(not) = any.downcast_ref::<NotExpr>() {
+            NullRejectionNode::NullPropagating(not.arg().as_ref())
+        } else if let Some(negative) = any.downcast_ref::<NegativeExpr>() {
+            NullRejectionNode::NullPropagating(negative.arg().as_ref())
+        } else if let Some(cast) = any.downcast_ref::<CastExpr>() {
+            NullRejectionNode::NullPropagating(cast.expr().as_ref())
+        } else if let Some(cast) = any.downcast_ref::<TryCastExpr>() {
+            NullRejectionNode::NullPropagating(cast.expr().as_ref())
+        } else {
+            NullRejectionNode::Other
+        }
+    }
+}
+
 /// Scatter `truthy` array by boolean mask. When the mask evaluates `true`, next values of `truthy`
 /// are taken, when the mask evaluates `false` values null values are filled.
 ///
@@ -28,14 +28,14 @@ use crate::window::window_expr::{
     BuiltinWindowState, NthValueKind, NthValueState, WindowFn,
 };
 use crate::window::{
-    PartitionBatches, PartitionWindowAggStates, WindowAggState, WindowState,
+    PartitionBatches, PartitionPeerGroups, PartitionWindowAggStates, PeerGroups,
+    WindowAggState, WindowState,
 };
 use crate::{expressions::PhysicalSortExpr, reverse_order_bys, PhysicalExpr};
 use arrow::array::{new_empty_array, Array, ArrayRef};
 use arrow::compute::SortOptions;
 use arrow::datatypes::Field;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::utils::evaluate_partition_ranges;
 use datafusion_common::{Result, ScalarValue};
 use datafusion_expr::WindowFrame;
 
@@ -97,6 +97,14 @@ impl WindowExpr for BuiltInWindowExpr {
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        self.evaluate_with_peers(batch, &mut PeerGroups::new())
+    }
+
+    fn evaluate_with_peers(
+        &self,
+        batch: &RecordBatch,
+        peers: &mut PeerGroups,
+    ) -> Result<ArrayRef> {
         let evaluator = self.expr.create_evaluator()?;
         let num_rows = batch.num_rows();
         if self.expr.uses_window_frame() {
@@ -122,9 +130,8 @@ impl WindowExpr for BuiltInWindowExpr {
             }
             ScalarValue::iter_to_array(row_wise_results.into_iter())
         } else if self.expr.include_rank() {
-            let columns = self.sort_columns(batch)?;
-            let sort_partition_points = evaluate_partition_ranges(num_rows, &columns)?;
-            evaluator.evaluate_with_rank(num_rows, &sort_partition_points)
+            let sort_partition_points = peers.get_or_compute(&self.order_by, batch)?;
+            evaluator.evaluate_with_rank(num_rows, sort_partition_points)
         } else {
             let (values, _) = self.get_values_orderbys(batch)?;
             evaluator.evaluate(&values, num_rows)
@@ -137,6 +144,19 @@ impl WindowExpr for BuiltInWindowExpr {
         &self,
         partition_batches: &PartitionBatches,
         window_agg_state: &mut PartitionWindowAggStates,
+    ) -> Result<()> {
+        self.evaluate_stateful_with_peers(
+            partition_batches,
+            window_agg_state,
+            &mut PartitionPeerGroups::new(),
+        )
+    }
+
+    fn evaluate_stateful_with_peers(
+        &self,
+        partition_batches: &PartitionBatches,
+        window_agg_state: &mut PartitionWindowAggStates,
+        peers: &mut PartitionPeerGroups,
     ) -> Result<()> {
         let field = self.expr.field()?;
         let out_type = field.data_type();
@@ -166,11 +186,13 @@ impl WindowExpr for BuiltInWindowExpr {
             // We iterate on each row to perform a running calculation.
             let record_batch = &partition_batch_state.record_batch;
             let num_rows = record_batch.num_rows();
-            let sort_partition_points = if self.expr.include_rank() {
-                let columns = self.sort_columns(record_batch)?;
-                evaluate_partition_ranges(num_rows, &columns)?
+            let sort_partition_points: &[Range<usize>] = if self.expr.include_rank() {
+                peers
+                    .entry(partition_row.clone())
+                    .or_default()
+                    .get_or_compute(&self.order_by, record_batch)?
             } else {
-                vec![]
+                &[]
             };
             let mut row_wise_results: Vec<ScalarValue> = vec![];
             for idx in state.last_calculated_index..num_rows {
@@ -200,7 +222,7 @@ impl WindowExpr for BuiltInWindowExpr {
                 }
                 // Update last range
                 state.window_frame_range = frame_range;
-                evaluator.update_state(state, idx, &order_bys, &sort_partition_points)?;
+                evaluator.update_state(state, idx, &order_bys, sort_partition_points)?;
                 row_wise_results.push(evaluator.evaluate_stateful(&values)?);
             }
             let out_col = if row_wise_results.is_empty() {
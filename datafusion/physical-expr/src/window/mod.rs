@@ -36,7 +36,9 @@ pub use sliding_aggregate::SlidingAggregateWindowExpr;
 pub use window_expr::PartitionBatchState;
 pub use window_expr::PartitionBatches;
 pub use window_expr::PartitionKey;
+pub use window_expr::PartitionPeerGroups;
 pub use window_expr::PartitionWindowAggStates;
+pub use window_expr::PeerGroups;
 pub use window_expr::WindowAggState;
 pub use window_expr::WindowExpr;
 pub use window_expr::WindowState;
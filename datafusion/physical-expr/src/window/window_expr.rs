@@ -24,6 +24,7 @@ use arrow::compute::{concat, SortOptions};
 use arrow::datatypes::Field;
 use arrow::record_batch::RecordBatch;
 use arrow_schema::DataType;
+use datafusion_common::utils::evaluate_partition_ranges;
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::{Accumulator, WindowFrame};
 use indexmap::IndexMap;
@@ -89,6 +90,17 @@ pub trait WindowExpr: Send + Sync + Debug {
     /// Evaluate the window function values against the batch
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
 
+    /// Evaluate the window function values against the batch, reusing the
+    /// boundaries of the peer groups of the batch computed by the other window
+    /// expressions evaluated over the same batch.
+    fn evaluate_with_peers(
+        &self,
+        batch: &RecordBatch,
+        _peers: &mut PeerGroups,
+    ) -> Result<ArrayRef> {
+        self.evaluate(batch)
+    }
+
     /// Evaluate the window function against the batch. This function facilitates
     /// stateful, bounded-memory implementations.
     fn evaluate_stateful(
@@ -102,6 +114,18 @@ pub trait WindowExpr: Send + Sync + Debug {
         )))
     }
 
+    /// Evaluate the window function against the batch like
+    /// [`Self::evaluate_stateful`], reusing the boundaries of the peer groups
+    /// of every partition computed by the other window expressions.
+    fn evaluate_stateful_with_peers(
+        &self,
+        partition_batches: &PartitionBatches,
+        window_agg_state: &mut PartitionWindowAggStates,
+        _peers: &mut PartitionPeerGroups,
+    ) -> Result<()> {
+        self.evaluate_stateful(partition_batches, window_agg_state)
+    }
+
     /// Expressions that's from the window function's partition by clause, empty if absent
     fn partition_by(&self) -> &[Arc<dyn PhysicalExpr>];
 
@@ -146,6 +170,57 @@ pub trait WindowExpr: Send + Sync + Debug {
     fn get_reverse_expr(&self) -> Option<Arc<dyn WindowExpr>>;
 }
 
+/// Boundaries of the peer groups of a batch, i.e. of the ranges of rows with
+/// equal ORDER BY values.
+///
+/// The boundaries are computed once for every ORDER BY clause and shared by
+/// all the window expressions with that clause evaluated over the same batch,
+/// e.g. by `RANK()`, `DENSE_RANK()` and `PERCENT_RANK()` over the same window.
+///
+/// Only the boundaries are shared: the window expressions are still evaluated
+/// one after the other, each iterating over the rows of the batch. Evaluating
+/// all of them in a single pass over the batch is not implemented yet.
+#[derive(Debug, Default)]
+pub struct PeerGroups {
+    ranges: Vec<(Vec<PhysicalSortExpr>, Vec<Range<usize>>)>,
+}
+
+impl PeerGroups {
+    /// Create an empty set of peer group boundaries
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the boundaries of the peer groups of `batch` according to
+    /// `order_by`, computing them on first use
+    pub fn get_or_compute(
+        &mut self,
+        order_by: &[PhysicalSortExpr],
+        batch: &RecordBatch,
+    ) -> Result<&[Range<usize>]> {
+        let index = match self
+            .ranges
+            .iter()
+            .position(|(o, _)| o.as_slice() == order_by)
+        {
+            Some(index) => index,
+            None => {
+                let columns = order_by
+                    .iter()
+                    .map(|e| e.evaluate_to_sort_column(batch))
+                    .collect::<Result<Vec<_>>>()?;
+                let ranges = evaluate_partition_ranges(batch.num_rows(), &columns)?;
+                self.ranges.push((order_by.to_vec(), ranges));
+                self.ranges.len() - 1
+            }
+        };
+        Ok(&self.ranges[index].1)
+    }
+}
+
+/// The [`PeerGroups`] of the record batch of every partition
+pub type PartitionPeerGroups = IndexMap<PartitionKey, PeerGroups>;
+
 /// Extension trait that adds common functionality to [`AggregateWindowExpr`]s
 pub trait AggregateWindowExpr: WindowExpr {
     /// Get the accumulator for the window expression. Note that distinct
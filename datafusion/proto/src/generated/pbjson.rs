@@ -7387,6 +7387,15 @@ impl serde::Serialize for HashJoinExecNode {
         if self.filter.is_some() {
             len += 1;
         }
+        if self.null_aware {
+            len += 1;
+        }
+        if self.single_partition_threshold != 0 {
+            len += 1;
+        }
+        if self.single_partition_threshold_rows != 0 {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.HashJoinExecNode", len)?;
         if let Some(v) = self.left.as_ref() {
             struct_ser.serialize_field("left", v)?;
@@ -7413,6 +7422,15 @@ impl serde::Serialize for HashJoinExecNode {
         if let Some(v) = self.filter.as_ref() {
             struct_ser.serialize_field("filter", v)?;
         }
+        if self.null_aware {
+            struct_ser.serialize_field("nullAware", &self.null_aware)?;
+        }
+        if self.single_partition_threshold != 0 {
+            struct_ser.serialize_field("singlePartitionThreshold", ToString::to_string(&self.single_partition_threshold).as_str())?;
+        }
+        if self.single_partition_threshold_rows != 0 {
+            struct_ser.serialize_field("singlePartitionThresholdRows", ToString::to_string(&self.single_partition_threshold_rows).as_str())?;
+        }
         struct_ser.end()
     }
 }
@@ -7433,6 +7451,12 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
             "null_equals_null",
             "nullEqualsNull",
             "filter",
+            "null_aware",
+            "nullAware",
+            "single_partition_threshold",
+            "singlePartitionThreshold",
+            "single_partition_threshold_rows",
+            "singlePartitionThresholdRows",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -7444,6 +7468,9 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
             PartitionMode,
             NullEqualsNull,
             Filter,
+            NullAware,
+            SinglePartitionThreshold,
+            SinglePartitionThresholdRows,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -7472,6 +7499,9 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
                             "partitionMode" | "partition_mode" => Ok(GeneratedField::PartitionMode),
                             "nullEqualsNull" | "null_equals_null" => Ok(GeneratedField::NullEqualsNull),
                             "filter" => Ok(GeneratedField::Filter),
+                            "nullAware" | "null_aware" => Ok(GeneratedField::NullAware),
+                            "singlePartitionThreshold" | "single_partition_threshold" => Ok(GeneratedField::SinglePartitionThreshold),
+                            "singlePartitionThresholdRows" | "single_partition_threshold_rows" => Ok(GeneratedField::SinglePartitionThresholdRows),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -7498,6 +7528,9 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
                 let mut partition_mode__ = None;
                 let mut null_equals_null__ = None;
                 let mut filter__ = None;
+                let mut null_aware__ = None;
+                let mut single_partition_threshold__ = None;
+                let mut single_partition_threshold_rows__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Left => {
@@ -7542,6 +7575,28 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
                             }
                             filter__ = map.next_value()?;
                         }
+                        GeneratedField::NullAware => {
+                            if null_aware__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("nullAware"));
+                            }
+                            null_aware__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::SinglePartitionThreshold => {
+                            if single_partition_threshold__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("singlePartitionThreshold"));
+                            }
+                            single_partition_threshold__ = 
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::SinglePartitionThresholdRows => {
+                            if single_partition_threshold_rows__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("singlePartitionThresholdRows"));
+                            }
+                            single_partition_threshold_rows__ = 
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
                     }
                 }
                 Ok(HashJoinExecNode {
@@ -7552,6 +7607,9 @@ impl<'de> serde::Deserialize<'de> for HashJoinExecNode {
                     partition_mode: partition_mode__.unwrap_or_default(),
                     null_equals_null: null_equals_null__.unwrap_or_default(),
                     filter: filter__,
+                    null_aware: null_aware__.unwrap_or_default(),
+                    single_partition_threshold: single_partition_threshold__.unwrap_or_default(),
+                    single_partition_threshold_rows: single_partition_threshold_rows__.unwrap_or_default(),
                 })
             }
         }
@@ -8963,6 +9021,9 @@ impl serde::Serialize for JoinFilter {
         if self.schema.is_some() {
             len += 1;
         }
+        if self.optional_selectivity.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.JoinFilter", len)?;
         if let Some(v) = self.expression.as_ref() {
             struct_ser.serialize_field("expression", v)?;
@@ -8973,6 +9034,13 @@ impl serde::Serialize for JoinFilter {
         if let Some(v) = self.schema.as_ref() {
             struct_ser.serialize_field("schema", v)?;
         }
+        if let Some(v) = self.optional_selectivity.as_ref() {
+            match v {
+                join_filter::OptionalSelectivity::Selectivity(v) => {
+                    struct_ser.serialize_field("selectivity", v)?;
+                }
+            }
+        }
         struct_ser.end()
     }
 }
@@ -8987,6 +9055,7 @@ impl<'de> serde::Deserialize<'de> for JoinFilter {
             "column_indices",
             "columnIndices",
             "schema",
+            "selectivity",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -8994,6 +9063,7 @@ impl<'de> serde::Deserialize<'de> for JoinFilter {
             Expression,
             ColumnIndices,
             Schema,
+            Selectivity,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -9018,6 +9088,7 @@ impl<'de> serde::Deserialize<'de> for JoinFilter {
                             "expression" => Ok(GeneratedField::Expression),
                             "columnIndices" | "column_indices" => Ok(GeneratedField::ColumnIndices),
                             "schema" => Ok(GeneratedField::Schema),
+                            "selectivity" => Ok(GeneratedField::Selectivity),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -9040,6 +9111,7 @@ impl<'de> serde::Deserialize<'de> for JoinFilter {
                 let mut expression__ = None;
                 let mut column_indices__ = None;
                 let mut schema__ = None;
+                let mut optional_selectivity__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Expression => {
@@ -9060,12 +9132,19 @@ impl<'de> serde::Deserialize<'de> for JoinFilter {
                             }
                             schema__ = map.next_value()?;
                         }
+                        GeneratedField::Selectivity => {
+                            if optional_selectivity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("selectivity"));
+                            }
+                            optional_selectivity__ = map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| join_filter::OptionalSelectivity::Selectivity(x.0));
+                        }
                     }
                 }
                 Ok(JoinFilter {
                     expression: expression__,
                     column_indices: column_indices__.unwrap_or_default(),
                     schema: schema__,
+                    optional_selectivity: optional_selectivity__,
                 })
             }
         }
@@ -9305,6 +9384,12 @@ impl serde::Serialize for JoinOn {
         if self.right.is_some() {
             len += 1;
         }
+        if self.left_expr.is_some() {
+            len += 1;
+        }
+        if self.right_expr.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.JoinOn", len)?;
         if let Some(v) = self.left.as_ref() {
             struct_ser.serialize_field("left", v)?;
@@ -9312,6 +9397,12 @@ impl serde::Serialize for JoinOn {
         if let Some(v) = self.right.as_ref() {
             struct_ser.serialize_field("right", v)?;
         }
+        if let Some(v) = self.left_expr.as_ref() {
+            struct_ser.serialize_field("leftExpr", v)?;
+        }
+        if let Some(v) = self.right_expr.as_ref() {
+            struct_ser.serialize_field("rightExpr", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -9324,12 +9415,18 @@ impl<'de> serde::Deserialize<'de> for JoinOn {
         const FIELDS: &[&str] = &[
             "left",
             "right",
+            "left_expr",
+            "leftExpr",
+            "right_expr",
+            "rightExpr",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             Left,
             Right,
+            LeftExpr,
+            RightExpr,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -9353,6 +9450,8 @@ impl<'de> serde::Deserialize<'de> for JoinOn {
                         match value {
                             "left" => Ok(GeneratedField::Left),
                             "right" => Ok(GeneratedField::Right),
+                            "leftExpr" | "left_expr" => Ok(GeneratedField::LeftExpr),
+                            "rightExpr" | "right_expr" => Ok(GeneratedField::RightExpr),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -9374,6 +9473,8 @@ impl<'de> serde::Deserialize<'de> for JoinOn {
             {
                 let mut left__ = None;
                 let mut right__ = None;
+                let mut left_expr__ = None;
+                let mut right_expr__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Left => {
@@ -9388,11 +9489,25 @@ impl<'de> serde::Deserialize<'de> for JoinOn {
                             }
                             right__ = map.next_value()?;
                         }
+                        GeneratedField::LeftExpr => {
+                            if left_expr__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("leftExpr"));
+                            }
+                            left_expr__ = map.next_value()?;
+                        }
+                        GeneratedField::RightExpr => {
+                            if right_expr__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("rightExpr"));
+                            }
+                            right_expr__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(JoinOn {
                     left: left__,
                     right: right__,
+                    left_expr: left_expr__,
+                    right_expr: right_expr__,
                 })
             }
         }
@@ -1771,6 +1771,13 @@ pub struct HashJoinExecNode {
     pub null_equals_null: bool,
     #[prost(message, optional, tag = "8")]
     pub filter: ::core::option::Option<JoinFilter>,
+    #[prost(bool, tag = "9")]
+    pub null_aware: bool,
+    /// Thresholds deciding the partitioning mode of a join in Auto mode
+    #[prost(uint64, tag = "10")]
+    pub single_partition_threshold: u64,
+    #[prost(uint64, tag = "11")]
+    pub single_partition_threshold_rows: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1807,10 +1814,16 @@ pub struct PhysicalColumn {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JoinOn {
+    /// Join keys which are columns, also readable by older versions
     #[prost(message, optional, tag = "1")]
     pub left: ::core::option::Option<PhysicalColumn>,
     #[prost(message, optional, tag = "2")]
     pub right: ::core::option::Option<PhysicalColumn>,
+    /// Join keys which are not columns
+    #[prost(message, optional, tag = "3")]
+    pub left_expr: ::core::option::Option<PhysicalExprNode>,
+    #[prost(message, optional, tag = "4")]
+    pub right_expr: ::core::option::Option<PhysicalExprNode>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1974,6 +1987,17 @@ pub struct JoinFilter {
     pub column_indices: ::prost::alloc::vec::Vec<ColumnIndex>,
     #[prost(message, optional, tag = "3")]
     pub schema: ::core::option::Option<Schema>,
+    #[prost(oneof = "join_filter::OptionalSelectivity", tags = "4")]
+    pub optional_selectivity: ::core::option::Option<join_filter::OptionalSelectivity>,
+}
+/// Nested message and enum types in `JoinFilter`.
+pub mod join_filter {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum OptionalSelectivity {
+        #[prost(double, tag = "4")]
+        Selectivity(f64),
+    }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
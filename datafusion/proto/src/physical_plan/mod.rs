@@ -34,7 +34,7 @@ use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::explain::ExplainExec;
 use datafusion::physical_plan::expressions::{Column, PhysicalSortExpr};
 use datafusion::physical_plan::filter::FilterExec;
-use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter};
+use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter, JoinOn};
 use datafusion::physical_plan::joins::CrossJoinExec;
 use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
 use datafusion::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
@@ -53,6 +53,7 @@ use prost::Message;
 
 use crate::common::proto_error;
 use crate::common::{csv_delimiter_to_string, str_to_byte};
+use crate::convert_required;
 use crate::physical_plan::from_proto::{
     parse_physical_expr, parse_physical_sort_expr, parse_protobuf_file_scan_config,
 };
@@ -61,7 +62,6 @@ use crate::protobuf::physical_expr_node::ExprType;
 use crate::protobuf::physical_plan_node::PhysicalPlanType;
 use crate::protobuf::repartition_exec_node::PartitionMethod;
 use crate::protobuf::{self, PhysicalPlanNode};
-use crate::{convert_required, into_required};
 
 pub mod from_proto;
 pub mod to_proto;
@@ -502,13 +502,23 @@ impl AsExecutionPlan for PhysicalPlanNode {
                     runtime,
                     extension_codec,
                 )?;
-                let on: Vec<(Column, Column)> = hashjoin
+                let on: JoinOn = hashjoin
                     .on
                     .iter()
-                    .map(|col| {
-                        let left = into_required!(col.left)?;
-                        let right = into_required!(col.right)?;
-                        Ok((left, right))
+                    .map(|key| {
+                        let left_key = join_key_from_proto(
+                            &key.left,
+                            &key.left_expr,
+                            registry,
+                            &left.schema(),
+                        )?;
+                        let right_key = join_key_from_proto(
+                            &key.right,
+                            &key.right_expr,
+                            registry,
+                            &right.schema(),
+                        )?;
+                        Ok((left_key, right_key))
                     })
                     .collect::<Result<_>>()?;
                 let join_type = protobuf::JoinType::from_i32(hashjoin.join_type)
@@ -550,7 +560,13 @@ impl AsExecutionPlan for PhysicalPlanNode {
                             })
                             .collect::<Result<Vec<_>>>()?;
 
-                        Ok(JoinFilter::new(expression, column_indices, schema))
+                        let filter = JoinFilter::new(expression, column_indices, schema);
+                        match f.optional_selectivity {
+                            Some(protobuf::join_filter::OptionalSelectivity::Selectivity(
+                                selectivity,
+                            )) => filter.with_selectivity(selectivity),
+                            None => Ok(filter),
+                        }
                     })
                     .map_or(Ok(None), |v: Result<JoinFilter>| v.map(Some))?;
 
@@ -567,15 +583,24 @@ impl AsExecutionPlan for PhysicalPlanNode {
                     protobuf::PartitionMode::Partitioned => PartitionMode::Partitioned,
                     protobuf::PartitionMode::Auto => PartitionMode::Auto,
                 };
-                Ok(Arc::new(HashJoinExec::try_new(
-                    left,
-                    right,
-                    on,
-                    filter,
-                    &join_type.into(),
-                    partition_mode,
-                    hashjoin.null_equals_null,
-                )?))
+                Ok(Arc::new(
+                    HashJoinExec::try_new(
+                        left,
+                        right,
+                        on,
+                        filter,
+                        &join_type.into(),
+                        partition_mode,
+                        hashjoin.null_equals_null,
+                    )?
+                    .with_null_aware(hashjoin.null_aware)?
+                    .with_single_partition_threshold(
+                        hashjoin.single_partition_threshold as usize,
+                    )
+                    .with_single_partition_threshold_rows(
+                        hashjoin.single_partition_threshold_rows as usize,
+                    ),
+                ))
             }
             PhysicalPlanType::Union(union) => {
                 let mut inputs: Vec<Arc<dyn ExecutionPlan>> = vec![];
@@ -810,17 +835,17 @@ impl AsExecutionPlan for PhysicalPlanNode {
             let on: Vec<protobuf::JoinOn> = exec
                 .on()
                 .iter()
-                .map(|tuple| protobuf::JoinOn {
-                    left: Some(protobuf::PhysicalColumn {
-                        name: tuple.0.name().to_string(),
-                        index: tuple.0.index() as u32,
-                    }),
-                    right: Some(protobuf::PhysicalColumn {
-                        name: tuple.1.name().to_string(),
-                        index: tuple.1.index() as u32,
-                    }),
+                .map(|(left, right)| {
+                    let (left, left_expr) = join_key_to_proto(left)?;
+                    let (right, right_expr) = join_key_to_proto(right)?;
+                    Ok(protobuf::JoinOn {
+                        left,
+                        right,
+                        left_expr,
+                        right_expr,
+                    })
                 })
-                .collect();
+                .collect::<Result<_>>()?;
             let join_type: protobuf::JoinType = exec.join_type().to_owned().into();
             let filter = exec
                 .filter()
@@ -843,10 +868,22 @@ impl AsExecutionPlan for PhysicalPlanNode {
                         expression: Some(expression),
                         column_indices,
                         schema: Some(schema),
+                        optional_selectivity: f
+                            .selectivity()
+                            .map(protobuf::join_filter::OptionalSelectivity::Selectivity),
                     })
                 })
                 .map_or(Ok(None), |v: Result<protobuf::JoinFilter>| v.map(Some))?;
 
+            let null_equals_null = exec.null_equals_null_per_key();
+            if null_equals_null.contains(&true) && null_equals_null.contains(&false) {
+                return Err(DataFusionError::NotImplemented(
+                    "Serializing a HashJoinExec with null_equals_null differing \
+                     between the join keys"
+                        .to_string(),
+                ));
+            }
+
             let partition_mode = match exec.partition_mode() {
                 PartitionMode::CollectLeft => protobuf::PartitionMode::CollectLeft,
                 PartitionMode::Partitioned => protobuf::PartitionMode::Partitioned,
@@ -863,6 +900,12 @@ impl AsExecutionPlan for PhysicalPlanNode {
                         partition_mode: partition_mode.into(),
                         null_equals_null: exec.null_equals_null(),
                         filter,
+                        null_aware: exec.null_aware(),
+                        single_partition_threshold: exec.single_partition_threshold()
+                            as u64,
+                        single_partition_threshold_rows: exec
+                            .single_partition_threshold_rows()
+                            as u64,
                     },
                 ))),
             })
@@ -1249,6 +1292,46 @@ fn into_physical_plan(
     }
 }
 
+/// Deserializes a join key from the `PhysicalExprNode` field of `JoinOn`, or
+/// from its `PhysicalColumn` field for the keys which are columns, which is
+/// the only one plans serialized by older versions have
+fn join_key_from_proto(
+    column: &Option<protobuf::PhysicalColumn>,
+    expr: &Option<protobuf::PhysicalExprNode>,
+    registry: &dyn FunctionRegistry,
+    schema: &SchemaRef,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    match (column, expr) {
+        (_, Some(expr)) => parse_physical_expr(expr, registry, schema.as_ref()),
+        (Some(column), None) => Ok(Arc::new(Column::from(column))),
+        (None, None) => Err(DataFusionError::Internal(
+            "join key (JoinOn) in HashJoinExecNode is missing.".to_owned(),
+        )),
+    }
+}
+
+/// Serializes a join key into the `PhysicalColumn` field of `JoinOn` older
+/// versions read if it is a column, and into its `PhysicalExprNode` field
+/// otherwise
+#[allow(clippy::type_complexity)]
+fn join_key_to_proto(
+    key: &Arc<dyn PhysicalExpr>,
+) -> Result<(
+    Option<protobuf::PhysicalColumn>,
+    Option<protobuf::PhysicalExprNode>,
+)> {
+    match key.as_any().downcast_ref::<Column>() {
+        Some(column) => Ok((
+            Some(protobuf::PhysicalColumn {
+                name: column.name().to_string(),
+                index: column.index() as u32,
+            }),
+            None,
+        )),
+        None => Ok((None, Some(key.clone().try_into()?))),
+    }
+}
+
 #[cfg(test)]
 mod roundtrip_tests {
     use std::ops::Deref;
@@ -1285,9 +1368,9 @@ mod roundtrip_tests {
             aggregates::{AggregateExec, AggregateMode},
             empty::EmptyExec,
             expressions::{binary, col, lit, NotExpr},
-            expressions::{Avg, Column, DistinctCount, PhysicalSortExpr},
+            expressions::{Avg, CastExpr, Column, DistinctCount, PhysicalSortExpr},
             filter::FilterExec,
-            joins::{HashJoinExec, PartitionMode},
+            joins::{utils::JoinFilter, HashJoinExec, PartitionMode},
             limit::{GlobalLimitExec, LocalLimitExec},
             sorts::sort::SortExec,
             AggregateExpr, ExecutionPlan, PhysicalExpr, Statistics,
@@ -1390,8 +1473,8 @@ mod roundtrip_tests {
         let schema_left = Schema::new(vec![field_a.clone()]);
         let schema_right = Schema::new(vec![field_a]);
         let on = vec![(
-            Column::new("col", schema_left.index_of("col")?),
-            Column::new("col", schema_right.index_of("col")?),
+            Arc::new(Column::new("col", schema_left.index_of("col")?)) as _,
+            Arc::new(Column::new("col", schema_right.index_of("col")?)) as _,
         )];
 
         let schema_left = Arc::new(schema_left);
@@ -1423,6 +1506,156 @@ mod roundtrip_tests {
         Ok(())
     }
 
+    #[test]
+    fn roundtrip_hash_join_filter_selectivity() -> Result<()> {
+        let field_a = Field::new("col", DataType::Int64, false);
+        let schema_left = Arc::new(Schema::new(vec![field_a.clone()]));
+        let schema_right = Arc::new(Schema::new(vec![field_a.clone()]));
+        let on = vec![(
+            Arc::new(Column::new("col", 0)) as _,
+            Arc::new(Column::new("col", 0)) as _,
+        )];
+        let filter_schema =
+            Schema::new(vec![field_a, Field::new("col2", DataType::Int64, false)]);
+        let expression = binary(
+            col("col", &filter_schema)?,
+            Operator::Lt,
+            col("col2", &filter_schema)?,
+            &filter_schema,
+        )?;
+        let column_indices = JoinFilter::build_column_indices(vec![0], vec![0]);
+
+        for selectivity in [None, Some(0.25)] {
+            let mut filter = JoinFilter::new(
+                expression.clone(),
+                column_indices.clone(),
+                filter_schema.clone(),
+            );
+            if let Some(selectivity) = selectivity {
+                filter = filter.with_selectivity(selectivity)?;
+            }
+            roundtrip_test(Arc::new(HashJoinExec::try_new(
+                Arc::new(EmptyExec::new(false, schema_left.clone())),
+                Arc::new(EmptyExec::new(false, schema_right.clone())),
+                on.clone(),
+                Some(filter),
+                &JoinType::Inner,
+                PartitionMode::CollectLeft,
+                false,
+            )?))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_hash_join_expr_keys() -> Result<()> {
+        let schema_left = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let schema_right = Arc::new(Schema::new(vec![
+            Field::new("c", DataType::Int64, false),
+            Field::new("d", DataType::Int32, false),
+        ]));
+        let on = vec![
+            (
+                Arc::new(CastExpr::new(
+                    col("a", &schema_left)?,
+                    DataType::Int64,
+                    None,
+                )) as _,
+                col("c", &schema_right)?,
+            ),
+            (
+                binary(
+                    col("a", &schema_left)?,
+                    Operator::Plus,
+                    col("b", &schema_left)?,
+                    &schema_left,
+                )?,
+                col("d", &schema_right)?,
+            ),
+        ];
+        let join = Arc::new(HashJoinExec::try_new(
+            Arc::new(EmptyExec::new(false, schema_left)),
+            Arc::new(EmptyExec::new(false, schema_right)),
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+            false,
+        )?);
+
+        // the keys which are columns are in the fields older versions read
+        let codec = DefaultPhysicalExtensionCodec {};
+        let proto =
+            protobuf::PhysicalPlanNode::try_from_physical_plan(join.clone(), &codec)?;
+        let node = match &proto.physical_plan_type {
+            Some(protobuf::physical_plan_node::PhysicalPlanType::HashJoin(node)) => node,
+            _ => panic!("expected a HashJoinExecNode"),
+        };
+        for key in &node.on {
+            assert!(key.left.is_none() && key.left_expr.is_some(), "{key:?}");
+            assert!(key.right.is_some() && key.right_expr.is_none(), "{key:?}");
+        }
+
+        roundtrip_test(join)
+    }
+
+    #[test]
+    fn roundtrip_auto_hash_join_thresholds() -> Result<()> {
+        let field_a = Field::new("col", DataType::Int64, false);
+        let schema_left = Arc::new(Schema::new(vec![field_a.clone()]));
+        let schema_right = Arc::new(Schema::new(vec![field_a]));
+        let on = vec![(
+            Arc::new(Column::new("col", 0)) as _,
+            Arc::new(Column::new("col", 0)) as _,
+        )];
+
+        // the empty build side is below the thresholds, so that the join
+        // resolves to CollectLeft mode, which it has to keep once deserialized
+        let join = HashJoinExec::try_new(
+            Arc::new(EmptyExec::new(false, schema_left)),
+            Arc::new(EmptyExec::new(false, schema_right)),
+            on,
+            None,
+            &JoinType::Inner,
+            PartitionMode::Auto,
+            false,
+        )?
+        .with_single_partition_threshold(1024)
+        .with_single_partition_threshold_rows(128);
+        assert_eq!(join.resolved_partition_mode(), PartitionMode::CollectLeft);
+        roundtrip_test(Arc::new(join))
+    }
+
+    #[test]
+    fn roundtrip_null_aware_hash_join() -> Result<()> {
+        let field_a = Field::new("col", DataType::Int64, true);
+        let schema_left = Arc::new(Schema::new(vec![field_a.clone()]));
+        let schema_right = Arc::new(Schema::new(vec![field_a]));
+        let on = vec![(
+            Arc::new(Column::new("col", 0)) as _,
+            Arc::new(Column::new("col", 0)) as _,
+        )];
+
+        for join_type in [JoinType::LeftAnti, JoinType::RightAnti] {
+            roundtrip_test(Arc::new(
+                HashJoinExec::try_new(
+                    Arc::new(EmptyExec::new(false, schema_left.clone())),
+                    Arc::new(EmptyExec::new(false, schema_right.clone())),
+                    on.clone(),
+                    None,
+                    &join_type,
+                    PartitionMode::CollectLeft,
+                    false,
+                )?
+                .with_null_aware(true)?,
+            ))?;
+        }
+        Ok(())
+    }
+
     #[test]
     fn rountrip_aggregate() -> Result<()> {
         let field_a = Field::new("a", DataType::Int64, false);
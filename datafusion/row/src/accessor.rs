@@ -20,7 +20,7 @@
 use crate::layout::RowLayout;
 use crate::validity::NullBitsFormatter;
 use crate::{fn_get_idx, fn_get_idx_opt, fn_set_idx};
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{ArrowNativeTypeOp, DataType, Schema};
 use arrow::util::bit_util::{get_bit_raw, set_bit_raw};
 use datafusion_common::ScalarValue;
 use std::ops::{BitAnd, BitOr, BitXor};
@@ -71,11 +71,12 @@ impl<'a> std::fmt::Debug for RowAccessor<'a> {
 macro_rules! fn_add_idx {
     ($NATIVE: ident) => {
         paste::item! {
-            /// add field at `idx` with `value`
+            /// add field at `idx` with `value`, wrapping around on overflow
             #[inline(always)]
             pub fn [<add_ $NATIVE>](&mut self, idx: usize, value: $NATIVE) {
                 if self.is_valid_at(idx) {
-                    self.[<set_ $NATIVE>](idx, value + self.[<get_ $NATIVE>](idx));
+                    let v = value.add_wrapping(self.[<get_ $NATIVE>](idx));
+                    self.[<set_ $NATIVE>](idx, v);
                 } else {
                     self.set_non_null_at(idx);
                     self.[<set_ $NATIVE>](idx, value);
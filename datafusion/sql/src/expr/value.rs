@@ -220,14 +220,24 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 let df_op = match op {
                     BinaryOperator::Plus => Operator::Plus,
                     BinaryOperator::Minus => Operator::Minus,
+                    BinaryOperator::Multiply => Operator::Multiply,
+                    BinaryOperator::Eq => Operator::Eq,
+                    BinaryOperator::NotEq => Operator::NotEq,
+                    BinaryOperator::Lt => Operator::Lt,
+                    BinaryOperator::LtEq => Operator::LtEq,
+                    BinaryOperator::Gt => Operator::Gt,
+                    BinaryOperator::GtEq => Operator::GtEq,
                     _ => {
                         return Err(DataFusionError::NotImplemented(format!(
                             "Unsupported interval operator: {op:?}"
                         )));
                     }
                 };
+                // Only `+` and `-` can combine string literals into a single
+                // interval, e.g. `interval '1 month' + '1 day'`
+                let combines_literals = matches!(df_op, Operator::Plus | Operator::Minus);
                 match (leading_field, left.as_ref(), right.as_ref()) {
-                    (_, _, SQLExpr::Value(_)) => {
+                    (_, _, SQLExpr::Value(_)) if combines_literals => {
                         let left_expr = self.sql_interval_to_expr(
                             *left,
                             schema,
@@ -18,17 +18,20 @@
 use async_recursion::async_recursion;
 use datafusion::arrow::datatypes::{DataType, Field, TimeUnit};
 use datafusion::common::{DFField, DFSchema, DFSchemaRef};
+use datafusion::logical_expr::utils::find_valid_equijoin_key_pair;
 use datafusion::logical_expr::{
     aggregate_function, window_function::find_df_window_func, BinaryExpr,
     BuiltinScalarFunction, Case, Expr, LogicalPlan, Operator,
 };
-use datafusion::logical_expr::{build_join_schema, Extension, LogicalPlanBuilder};
+use datafusion::logical_expr::{
+    build_join_schema, Extension, Join, JoinConstraint, LogicalPlanBuilder,
+};
 use datafusion::logical_expr::{expr, Cast, WindowFrameBound, WindowFrameUnits};
 use datafusion::prelude::JoinType;
 use datafusion::sql::TableReference;
 use datafusion::{
     error::{DataFusionError, Result},
-    optimizer::utils::split_conjunction,
+    optimizer::utils::{conjunction, split_conjunction},
     prelude::{Column, SessionContext},
     scalar::ScalarValue,
 };
@@ -57,6 +60,7 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use super::WINDOW_FRAME_UNITS_OPTION;
 use crate::variation_const::{
     DATE_32_TYPE_REF, DATE_64_TYPE_REF, DECIMAL_128_TYPE_REF, DECIMAL_256_TYPE_REF,
     DEFAULT_CONTAINER_TYPE_REF, DEFAULT_TYPE_REF, LARGE_CONTAINER_TYPE_REF,
@@ -342,49 +346,32 @@ pub async fn from_substrait_rel(
                 )) => build_join_schema(left.schema(), right.schema(), &join_type),
                 Err(e) => Err(e),
             };
-            let on = from_substrait_rex(
-                join.expression.as_ref().unwrap(),
-                &join_schema?,
-                extensions,
-            )
-            .await?;
-            let predicates = split_conjunction(&on);
-            // TODO: collect only one null_eq_null
-            let join_exprs: Vec<(Column, Column, bool)> = predicates
-                .iter()
-                .map(|p| match p {
-                    Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
-                        match (left.as_ref(), right.as_ref()) {
-                            (Expr::Column(l), Expr::Column(r)) => match op {
-                                Operator::Eq => Ok((l.clone(), r.clone(), false)),
-                                Operator::IsNotDistinctFrom => {
-                                    Ok((l.clone(), r.clone(), true))
-                                }
-                                _ => Err(DataFusionError::Internal(
-                                    "invalid join condition op".to_string(),
-                                )),
-                            },
-                            _ => Err(DataFusionError::Internal(
-                                "invalid join condition expression".to_string(),
-                            )),
-                        }
-                    }
-                    _ => Err(DataFusionError::Internal(
-                        "Non-binary expression is not supported in join condition"
-                            .to_string(),
-                    )),
-                })
-                .collect::<Result<Vec<_>>>()?;
-            let (left_cols, right_cols, null_eq_nulls): (Vec<_>, Vec<_>, Vec<_>) =
-                itertools::multiunzip(join_exprs);
-            left.join_detailed(
-                right.build()?,
-                join_type,
-                (left_cols, right_cols),
-                None,
-                null_eq_nulls[0],
-            )?
-            .build()
+            let join_schema = join_schema?;
+            let on = match join.expression.as_ref() {
+                Some(expr) => from_substrait_rex(expr, &join_schema, extensions)
+                    .await?
+                    .as_ref()
+                    .clone(),
+                None => {
+                    return Err(DataFusionError::Substrait(
+                        "Join relation without a join expression".to_string(),
+                    ))
+                }
+            };
+            let join_plan =
+                from_substrait_join_expr(left.build()?, right.build()?, join_type, &on)?;
+            // the post join filter is applied to the output of the join
+            match join.post_join_filter.as_ref() {
+                Some(filter) => {
+                    let filter =
+                        from_substrait_rex(filter, join_plan.schema(), extensions)
+                            .await?;
+                    LogicalPlanBuilder::from(join_plan)
+                        .filter(filter.as_ref().clone())?
+                        .build()
+                }
+                None => Ok(join_plan),
+            }
         }
         Some(RelType::Read(read)) => match &read.as_ref().read_type {
             Some(ReadType::NamedTable(nt)) => {
@@ -502,6 +489,66 @@ pub async fn from_substrait_rel(
     }
 }
 
+/// Create a join of `left` and `right` from the Substrait join expression `on`.
+///
+/// Conjuncts comparing an expression of the left input with an expression of the
+/// right input using `=` become the equijoin keys, all the other conjuncts make up
+/// the join filter. `IS NOT DISTINCT FROM` conjuncts are used as keys comparing
+/// nulls as equal when there is no `=` conjunct, and are kept in the filter otherwise.
+fn from_substrait_join_expr(
+    left: LogicalPlan,
+    right: LogicalPlan,
+    join_type: JoinType,
+    on: &Expr,
+) -> Result<LogicalPlan> {
+    let mut eq_keys = vec![];
+    let mut null_eq_keys = vec![];
+    let mut null_eq_predicates = vec![];
+    let mut filters = vec![];
+    for predicate in split_conjunction(on) {
+        match predicate {
+            // a join without any condition
+            Expr::Literal(ScalarValue::Boolean(Some(true))) => {}
+            Expr::BinaryExpr(BinaryExpr {
+                left: l,
+                op: op @ (Operator::Eq | Operator::IsNotDistinctFrom),
+                right: r,
+            }) => match find_valid_equijoin_key_pair(
+                l,
+                r,
+                left.schema().clone(),
+                right.schema().clone(),
+            )? {
+                Some(key) if *op == Operator::Eq => eq_keys.push(key),
+                Some(key) => {
+                    null_eq_keys.push(key);
+                    null_eq_predicates.push(predicate.clone());
+                }
+                None => filters.push(predicate.clone()),
+            },
+            _ => filters.push(predicate.clone()),
+        }
+    }
+
+    let (on, null_equals_null) = if eq_keys.is_empty() && !null_eq_keys.is_empty() {
+        (null_eq_keys, true)
+    } else {
+        filters.extend(null_eq_predicates);
+        (eq_keys, false)
+    };
+    let schema = build_join_schema(left.schema(), right.schema(), &join_type)?;
+    Ok(LogicalPlan::Join(Join {
+        left: Arc::new(left),
+        right: Arc::new(right),
+        on,
+        filter: conjunction(filters),
+        join_type,
+        join_constraint: JoinConstraint::On,
+        schema: Arc::new(schema),
+        null_equals_null,
+    }))
+}
+
 fn from_substrait_jointype(join_type: i32) -> Result<JoinType> {
     if let Some(substrait_join_type) = join_rel::JoinType::from_i32(join_type) {
         match substrait_join_type {
@@ -856,7 +903,16 @@ pub async fn from_substrait_rex(
         },
         Some(RexType::WindowFunction(window)) => {
             let fun = match extensions.get(&window.function_reference) {
-                Some(function_name) => Ok(find_df_window_func(function_name)),
+                Some(function_name) => {
+                    // other producers may qualify the name with the function signature,
+                    // e.g. `rank:` or `sum:opt_i64`
+                    let name = function_name.split(':').next().unwrap_or_default();
+                    find_df_window_func(name).ok_or_else(|| {
+                        DataFusionError::NotImplemented(format!(
+                            "Unsupported window function: {function_name}"
+                        ))
+                    })
+                }
                 None => Err(DataFusionError::NotImplemented(format!(
                     "Window function not found: function anchor = {:?}",
                     &window.function_reference
@@ -864,17 +920,21 @@ pub async fn from_substrait_rex(
             };
             let order_by =
                 from_substrait_sorts(&window.sorts, input_schema, extensions).await?;
-            // Substrait does not encode WindowFrameUnits so we're using a simple logic to determine the units
-            // If there is no `ORDER BY`, then by default, the frame counts each row from the lower up to upper boundary
-            // If there is `ORDER BY`, then by default, each frame is a range starting from unbounded preceding to current row
-            // TODO: Consider the cases where window frame is specified in query and is different from default
-            let units = if order_by.is_empty() {
-                WindowFrameUnits::Rows
-            } else {
-                WindowFrameUnits::Range
+            let units = match window
+                .options
+                .iter()
+                .find(|option| option.name == WINDOW_FRAME_UNITS_OPTION)
+            {
+                Some(option) => from_substrait_frame_units(&option.preference)?,
+                // Substrait does not encode WindowFrameUnits, so unless they were written by
+                // DataFusion we're using a simple logic to determine the units
+                // If there is no `ORDER BY`, then by default, the frame counts each row from the lower up to upper boundary
+                // If there is `ORDER BY`, then by default, each frame is a range starting from unbounded preceding to current row
+                None if order_by.is_empty() => WindowFrameUnits::Rows,
+                None => WindowFrameUnits::Range,
             };
             Ok(Arc::new(Expr::WindowFunction(expr::WindowFunction {
-                fun: fun?.unwrap(),
+                fun: fun?,
                 args: from_substriat_func_args(
                     &window.arguments,
                     input_schema,
@@ -1013,6 +1073,17 @@ fn from_substrait_type(dt: &substrait::proto::Type) -> Result<DataType> {
     }
 }
 
+fn from_substrait_frame_units(preference: &[String]) -> Result<WindowFrameUnits> {
+    match preference.first().map(|units| units.as_str()) {
+        Some("ROWS") => Ok(WindowFrameUnits::Rows),
+        Some("RANGE") => Ok(WindowFrameUnits::Range),
+        Some("GROUPS") => Ok(WindowFrameUnits::Groups),
+        _ => Err(DataFusionError::NotImplemented(format!(
+            "Unsupported window frame units: {preference:?}"
+        ))),
+    }
+}
+
 fn from_substrait_bound(
     bound: &Option<Bound>,
     is_lower: bool,
@@ -17,3 +17,7 @@
 
 pub mod consumer;
 pub mod producer;
+
+/// Name of the window function option holding the units (`ROWS`, `RANGE` or `GROUPS`)
+/// of the window frame, which Substrait window functions can not express otherwise
+pub const WINDOW_FRAME_UNITS_OPTION: &str = "window_frame_units";
@@ -16,11 +16,12 @@
 // under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use datafusion::{
     arrow::datatypes::{DataType, TimeUnit},
     error::{DataFusionError, Result},
-    logical_expr::{WindowFrame, WindowFrameBound},
+    logical_expr::{WindowFrame, WindowFrameBound, WindowFrameUnits},
     prelude::{JoinType, SessionContext},
     scalar::ScalarValue,
 };
@@ -31,7 +32,7 @@ use datafusion::logical_expr::aggregate_function;
 use datafusion::logical_expr::expr::{
     BinaryExpr, Case, Cast, ScalarFunction as DFScalarFunction, Sort, WindowFunction,
 };
-use datafusion::logical_expr::{expr, Between, JoinConstraint, LogicalPlan, Operator};
+use datafusion::logical_expr::{expr, Between, LogicalPlan, Operator};
 use datafusion::prelude::Expr;
 use prost_types::Any as ProtoAny;
 use substrait::{
@@ -61,12 +62,13 @@ use substrait::{
         sort_field::{SortDirection, SortKind},
         AggregateFunction, AggregateRel, AggregationPhase, Expression, ExtensionLeafRel,
         ExtensionMultiRel, ExtensionSingleRel, FetchRel, FilterRel, FunctionArgument,
-        JoinRel, NamedStruct, Plan, PlanRel, ProjectRel, ReadRel, Rel, RelRoot,
-        SortField, SortRel,
+        FunctionOption, JoinRel, NamedStruct, Plan, PlanRel, ProjectRel, ReadRel, Rel,
+        RelRoot, SortField, SortRel,
     },
     version,
 };
 
+use super::WINDOW_FRAME_UNITS_OPTION;
 use crate::variation_const::{
     DATE_32_TYPE_REF, DATE_64_TYPE_REF, DECIMAL_128_TYPE_REF, DECIMAL_256_TYPE_REF,
     DEFAULT_CONTAINER_TYPE_REF, DEFAULT_TYPE_REF, LARGE_CONTAINER_TYPE_REF,
@@ -262,21 +264,12 @@ pub fn to_substrait_rel(
         LogicalPlan::Join(join) => {
             let left = to_substrait_rel(join.left.as_ref(), ctx, extension_info)?;
             let right = to_substrait_rel(join.right.as_ref(), ctx, extension_info)?;
-            let join_type = to_substrait_jointype(join.join_type);
-            // we only support basic joins so return an error for anything not yet supported
-            if join.filter.is_some() {
-                return Err(DataFusionError::NotImplemented("join filter".to_string()));
-            }
-            match join.join_constraint {
-                JoinConstraint::On => {}
-                _ => {
-                    return Err(DataFusionError::NotImplemented(
-                        "join constraint".to_string(),
-                    ))
-                }
-            }
+            let join_type = to_substrait_jointype(join.join_type)?;
+            // `USING` joins are represented like `ON` joins on the same keys, as the
+            // constraint only matters to the SQL planner when expanding wildcards
             // map the left and right columns to binary expressions in the form `l = r`
             // build a single expression for the ON condition, such as `l.a = r.a AND l.b = r.b`
+            // null_equals_null is represented by using `IS NOT DISTINCT FROM` instead of `=`
             let eq_op = if join.null_equals_null {
                 Operator::IsNotDistinctFrom
             } else {
@@ -292,6 +285,7 @@ pub fn to_substrait_rel(
                     expression: Some(Box::new(to_substrait_join_expr(
                         &join.on,
                         eq_op,
+                        join.filter.as_ref(),
                         join.left.schema(),
                         join.right.schema(),
                         extension_info,
@@ -384,9 +378,13 @@ pub fn to_substrait_rel(
     }
 }
 
+/// Build the Substrait join expression of a join, made of the conjunction of its
+/// equijoin conditions `l = r` (or `l IS NOT DISTINCT FROM r`) and of its filter.
+/// A join without any condition is represented by the `true` literal.
 fn to_substrait_join_expr(
     join_conditions: &Vec<(Expr, Expr)>,
     eq_op: Operator,
+    join_filter: Option<&Expr>,
     left_schema: &DFSchemaRef,
     right_schema: &DFSchemaRef,
     extension_info: &mut (
@@ -409,24 +407,39 @@ fn to_substrait_join_expr(
         // AND with existing expression
         exprs.push(make_binary_op_scalar_func(&l, &r, eq_op, extension_info));
     }
-    let join_expr: Expression = exprs
-        .into_iter()
-        .reduce(|acc: Expression, e: Expression| {
-            make_binary_op_scalar_func(&acc, &e, Operator::And, extension_info)
-        })
-        .unwrap();
-    Ok(join_expr)
+    if let Some(filter) = join_filter {
+        // The filter references columns of both inputs, which Substrait indexes
+        // as a single relation made of the left fields followed by the right fields
+        let join_schema = Arc::new(left_schema.join(right_schema)?);
+        exprs.push(to_substrait_rex(filter, &join_schema, 0, extension_info)?);
+    }
+    match exprs.into_iter().reduce(|acc: Expression, e: Expression| {
+        make_binary_op_scalar_func(&acc, &e, Operator::And, extension_info)
+    }) {
+        Some(join_expr) => Ok(join_expr),
+        None => to_substrait_rex(
+            &Expr::Literal(ScalarValue::Boolean(Some(true))),
+            left_schema,
+            0,
+            extension_info,
+        ),
+    }
 }
 
-fn to_substrait_jointype(join_type: JoinType) -> join_rel::JoinType {
+fn to_substrait_jointype(join_type: JoinType) -> Result<join_rel::JoinType> {
     match join_type {
-        JoinType::Inner => join_rel::JoinType::Inner,
-        JoinType::Left => join_rel::JoinType::Left,
-        JoinType::Right => join_rel::JoinType::Right,
-        JoinType::Full => join_rel::JoinType::Outer,
-        JoinType::LeftAnti => join_rel::JoinType::Anti,
-        JoinType::LeftSemi => join_rel::JoinType::Semi,
-        JoinType::RightAnti | JoinType::RightSemi => unimplemented!(),
+        JoinType::Inner => Ok(join_rel::JoinType::Inner),
+        JoinType::Left => Ok(join_rel::JoinType::Left),
+        JoinType::Right => Ok(join_rel::JoinType::Right),
+        JoinType::Full => Ok(join_rel::JoinType::Outer),
+        JoinType::LeftAnti => Ok(join_rel::JoinType::Anti),
+        JoinType::LeftSemi => Ok(join_rel::JoinType::Semi),
+        // Substrait semi and anti joins always return the rows of the left input
+        JoinType::RightAnti | JoinType::RightSemi => {
+            Err(DataFusionError::NotImplemented(format!(
+                "Unsupported join type for Substrait: {join_type}"
+            )))
+        }
     }
 }
 
@@ -823,6 +836,7 @@ pub fn to_substrait_rex(
                 arguments,
                 partition_by,
                 order_by,
+                window_frame.units,
                 bounds,
             ))
         }
@@ -1024,15 +1038,21 @@ fn make_substrait_window_function(
     arguments: Vec<FunctionArgument>,
     partitions: Vec<Expression>,
     sorts: Vec<SortField>,
+    units: WindowFrameUnits,
     bounds: (Bound, Bound),
 ) -> Expression {
+    // Substrait window functions have no frame units, they are kept in an option
+    let units = FunctionOption {
+        name: WINDOW_FRAME_UNITS_OPTION.to_string(),
+        preference: vec![units.to_string()],
+    };
     Expression {
         rex_type: Some(RexType::WindowFunction(SubstraitWindowFunction {
             function_reference,
             arguments,
             partitions,
             sorts,
-            options: vec![],
+            options: vec![units],
             output_type: None,
             phase: 0,      // default to AGGREGATION_PHASE_UNSPECIFIED
             invocation: 0, // TODO: fix
@@ -1043,108 +1063,46 @@ fn make_substrait_window_function(
     }
 }
 
-fn to_substrait_bound(bound: &WindowFrameBound) -> Bound {
-    match bound {
-        WindowFrameBound::CurrentRow => Bound {
-            kind: Some(BoundKind::CurrentRow(SubstraitBound::CurrentRow {})),
-        },
-        WindowFrameBound::Preceding(s) => match s {
-            ScalarValue::UInt8(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt16(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt32(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt64(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int8(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int16(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int32(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int64(Some(v)) => Bound {
-                kind: Some(BoundKind::Preceding(SubstraitBound::Preceding {
-                    offset: *v,
-                })),
-            },
-            _ => Bound {
-                kind: Some(BoundKind::Unbounded(SubstraitBound::Unbounded {})),
-            },
+/// Offset of a `PRECEDING` or `FOLLOWING` window frame bound, `None` when it is unbounded.
+/// Substrait bounds only support integer offsets.
+fn to_substrait_bound_offset(value: &ScalarValue) -> Result<Option<i64>> {
+    match value {
+        ScalarValue::UInt8(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::UInt16(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::UInt32(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::UInt64(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::Int8(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::Int16(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::Int32(Some(v)) => Ok(Some(*v as i64)),
+        ScalarValue::Int64(Some(v)) => Ok(Some(*v)),
+        v if v.is_null() => Ok(None),
+        v => Err(DataFusionError::NotImplemented(format!(
+            "Unsupported window frame bound offset: {v:?}"
+        ))),
+    }
+}
+
+fn to_substrait_bound(bound: &WindowFrameBound) -> Result<Bound> {
+    let kind = match bound {
+        WindowFrameBound::CurrentRow => {
+            BoundKind::CurrentRow(SubstraitBound::CurrentRow {})
+        }
+        WindowFrameBound::Preceding(s) => match to_substrait_bound_offset(s)? {
+            Some(offset) => BoundKind::Preceding(SubstraitBound::Preceding { offset }),
+            None => BoundKind::Unbounded(SubstraitBound::Unbounded {}),
         },
-        WindowFrameBound::Following(s) => match s {
-            ScalarValue::UInt8(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt16(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt32(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::UInt64(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int8(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int16(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int32(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v as i64,
-                })),
-            },
-            ScalarValue::Int64(Some(v)) => Bound {
-                kind: Some(BoundKind::Following(SubstraitBound::Following {
-                    offset: *v,
-                })),
-            },
-            _ => Bound {
-                kind: Some(BoundKind::Unbounded(SubstraitBound::Unbounded {})),
-            },
+        WindowFrameBound::Following(s) => match to_substrait_bound_offset(s)? {
+            Some(offset) => BoundKind::Following(SubstraitBound::Following { offset }),
+            None => BoundKind::Unbounded(SubstraitBound::Unbounded {}),
         },
-    }
+    };
+    Ok(Bound { kind: Some(kind) })
 }
 
 fn to_substrait_bounds(window_frame: &WindowFrame) -> Result<(Bound, Bound)> {
     Ok((
-        to_substrait_bound(&window_frame.start_bound),
-        to_substrait_bound(&window_frame.end_bound),
+        to_substrait_bound(&window_frame.start_bound)?,
+        to_substrait_bound(&window_frame.end_bound)?,
     ))
 }
 
@@ -24,15 +24,44 @@ mod tests {
     use std::sync::Arc;
 
     use crate::{consumer::from_substrait_plan, producer::to_substrait_plan};
+    use datafusion::arrow::array::{ArrayRef, Int32Array};
     use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::arrow::util::pretty::pretty_format_batches;
     use datafusion::common::{DFSchema, DFSchemaRef};
-    use datafusion::error::Result;
+    use datafusion::error::{DataFusionError, Result};
     use datafusion::execution::context::SessionState;
     use datafusion::execution::registry::SerializerRegistry;
     use datafusion::execution::runtime_env::RuntimeEnv;
-    use datafusion::logical_expr::{Extension, LogicalPlan, UserDefinedLogicalNode};
+    use datafusion::logical_expr::window_function::find_df_window_func;
+    use datafusion::logical_expr::{expr, WindowFrameBound, WindowFrameUnits};
+    use datafusion::logical_expr::{
+        Extension, LogicalPlan, LogicalPlanBuilder, UserDefinedLogicalNode,
+    };
     use datafusion::prelude::*;
-    use substrait::proto::extensions::simple_extension_declaration::MappingType;
+    use datafusion::scalar::ScalarValue;
+    use datafusion_substrait::logical_plan::WINDOW_FRAME_UNITS_OPTION;
+    use substrait::proto::expression::field_reference::ReferenceType;
+    use substrait::proto::expression::window_function::bound::{
+        self as substrait_bound, Kind as BoundKind,
+    };
+    use substrait::proto::expression::window_function::Bound;
+    use substrait::proto::expression::{
+        reference_segment, FieldReference, ReferenceSegment, RexType,
+        WindowFunction as SubstraitWindowFunction,
+    };
+    use substrait::proto::extensions::simple_extension_declaration::{
+        ExtensionFunction, MappingType,
+    };
+    use substrait::proto::extensions::SimpleExtensionDeclaration;
+    use substrait::proto::function_argument::ArgType;
+    use substrait::proto::read_rel::{NamedTable, ReadType};
+    use substrait::proto::rel::RelType;
+    use substrait::proto::sort_field::{SortDirection, SortKind};
+    use substrait::proto::{
+        plan_rel, Expression, FunctionArgument, FunctionOption, Plan, PlanRel,
+        ProjectRel, ReadRel, Rel, RelRoot, SortField,
+    };
 
     struct MockSerializerRegistry;
 
@@ -402,6 +431,131 @@ mod tests {
         roundtrip("SELECT RANK() OVER (PARTITION BY a ORDER BY b), d, SUM(b) OVER (PARTITION BY a) FROM data;").await
     }
 
+    #[tokio::test]
+    async fn roundtrip_window_functions() -> Result<()> {
+        roundtrip("SELECT a, ROW_NUMBER() OVER (ORDER BY a) FROM data").await?;
+        roundtrip("SELECT a, RANK() OVER (PARTITION BY d ORDER BY a DESC) FROM data")
+            .await?;
+        roundtrip(
+            "SELECT a, SUM(a) OVER (ORDER BY a ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) FROM data",
+        )
+        .await?;
+        roundtrip(
+            "SELECT a, SUM(a) OVER (ORDER BY a ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM data",
+        )
+        .await?;
+        roundtrip(
+            "SELECT a, COUNT(a) OVER (ORDER BY a RANGE BETWEEN 2 PRECEDING AND CURRENT ROW) FROM data",
+        )
+        .await?;
+        roundtrip(
+            "SELECT a, MAX(a) OVER (ORDER BY a GROUPS BETWEEN 1 PRECEDING AND UNBOUNDED FOLLOWING) FROM data",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn unsupported_window_frame_bound() -> Result<()> {
+        let ctx = create_context().await?;
+        let plan = ctx
+            .sql("SELECT b, COUNT(a) OVER (ORDER BY b RANGE BETWEEN 1.5 PRECEDING AND CURRENT ROW) FROM data")
+            .await?
+            .into_optimized_plan()?;
+        match to_substrait_plan(&plan, &ctx) {
+            Err(DataFusionError::NotImplemented(msg)) => {
+                assert!(msg.contains("window frame bound"), "{msg}")
+            }
+            other => panic!("expected a NotImplemented error, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn consume_foreign_window_functions() -> Result<()> {
+        let mut ctx = create_context().await?;
+
+        // RANK() OVER (PARTITION BY d ORDER BY a DESC), with an explicit frame
+        let proto = foreign_window_plan(
+            "rank:",
+            vec![],
+            vec![field_ref(3)],
+            vec![sort_field(0, SortDirection::DescNullsFirst)],
+            BoundKind::Unbounded(substrait_bound::Unbounded {}),
+            BoundKind::CurrentRow(substrait_bound::CurrentRow {}),
+        );
+        let plan = from_substrait_plan(&mut ctx, &proto).await?;
+        let window = single_window_function(&plan);
+        assert_eq!(Some(window.fun.clone()), find_df_window_func("rank"));
+        assert_eq!(window.partition_by.len(), 1);
+        assert_eq!(window.order_by.len(), 1);
+        // without the frame units option, RANGE is assumed when there is an ORDER BY
+        assert_eq!(window.window_frame.units, WindowFrameUnits::Range);
+        assert_eq!(
+            window.window_frame.start_bound,
+            WindowFrameBound::Preceding(ScalarValue::Null)
+        );
+        assert_eq!(window.window_frame.end_bound, WindowFrameBound::CurrentRow);
+        assert_eq!(execute_row_count(&ctx, plan).await?, 2);
+
+        // SUM(a) OVER (ORDER BY a ... BETWEEN 1 PRECEDING AND 1 FOLLOWING)
+        let proto = foreign_window_plan(
+            "sum:opt_i64",
+            vec![field_ref(0)],
+            vec![],
+            vec![sort_field(0, SortDirection::AscNullsLast)],
+            BoundKind::Preceding(substrait_bound::Preceding { offset: 1 }),
+            BoundKind::Following(substrait_bound::Following { offset: 1 }),
+        );
+        let plan = from_substrait_plan(&mut ctx, &proto).await?;
+        let window = single_window_function(&plan);
+        assert_eq!(Some(window.fun.clone()), find_df_window_func("sum"));
+        assert_eq!(window.args.len(), 1);
+        assert_eq!(
+            window.window_frame.start_bound,
+            WindowFrameBound::Preceding(ScalarValue::Int64(Some(1)))
+        );
+        assert_eq!(
+            window.window_frame.end_bound,
+            WindowFrameBound::Following(ScalarValue::Int64(Some(1)))
+        );
+        assert_eq!(execute_row_count(&ctx, plan).await?, 2);
+
+        // invalid frame units option
+        let mut proto = foreign_window_plan(
+            "rank:",
+            vec![],
+            vec![],
+            vec![sort_field(0, SortDirection::AscNullsLast)],
+            BoundKind::Unbounded(substrait_bound::Unbounded {}),
+            BoundKind::CurrentRow(substrait_bound::CurrentRow {}),
+        );
+        set_window_options(
+            &mut proto,
+            vec![FunctionOption {
+                name: WINDOW_FRAME_UNITS_OPTION.to_string(),
+                preference: vec!["PAGES".to_string()],
+            }],
+        );
+        assert!(from_substrait_plan(&mut ctx, &proto).await.is_err());
+
+        // unknown function
+        let proto = foreign_window_plan(
+            "ntile_by_weight:i64",
+            vec![],
+            vec![],
+            vec![],
+            BoundKind::Unbounded(substrait_bound::Unbounded {}),
+            BoundKind::Unbounded(substrait_bound::Unbounded {}),
+        );
+        match from_substrait_plan(&mut ctx, &proto).await {
+            Err(DataFusionError::NotImplemented(msg)) => {
+                assert!(msg.contains("ntile_by_weight"), "{msg}")
+            }
+            other => panic!("expected a NotImplemented error, got {other:?}"),
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn qualified_schema_table_reference() -> Result<()> {
         roundtrip("SELECT * FROM public.data;").await
@@ -436,6 +590,94 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn roundtrip_join_types() -> Result<()> {
+        for join in ["JOIN", "LEFT JOIN", "RIGHT JOIN", "FULL JOIN"] {
+            roundtrip_join(&format!(
+                "SELECT * FROM join_left {join} join_right ON join_left.b1 = join_right.b1"
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn roundtrip_join_with_filter() -> Result<()> {
+        for join in ["JOIN", "LEFT JOIN", "RIGHT JOIN", "FULL JOIN"] {
+            roundtrip_join(&format!(
+                "SELECT * FROM join_left {join} join_right \
+                ON join_left.b1 = join_right.b1 AND join_left.c1 > 7 AND join_right.c2 < 90"
+            ))
+            .await?;
+        }
+        // a join without equijoin keys
+        roundtrip_join(
+            "SELECT * FROM join_left JOIN join_right ON join_left.a1 * 10 < join_right.a2",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn roundtrip_semi_anti_join() -> Result<()> {
+        roundtrip_join("SELECT * FROM semi_left WHERE b1 IN (SELECT b2 FROM semi_right)")
+            .await?;
+        roundtrip_join(
+            "SELECT * FROM semi_left WHERE b1 NOT IN (SELECT b2 FROM semi_right)",
+        )
+        .await?;
+        roundtrip_join(
+            "SELECT * FROM semi_left WHERE EXISTS \
+            (SELECT * FROM semi_right WHERE b1 = b2 AND a1 < a2)",
+        )
+        .await?;
+        roundtrip_join(
+            "SELECT * FROM semi_left WHERE NOT EXISTS \
+            (SELECT * FROM semi_right WHERE b1 = b2 AND a1 < a2)",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn roundtrip_join_null_equals_null() -> Result<()> {
+        let ctx = create_join_context()?;
+        let left = ctx.table("null_left").await?.into_unoptimized_plan();
+        let right = ctx.table("null_right").await?.into_unoptimized_plan();
+        let plan = LogicalPlanBuilder::from(left)
+            .join_detailed(
+                right,
+                JoinType::Inner,
+                (vec!["null_left.a1"], vec!["null_right.a2"]),
+                None,
+                true,
+            )?
+            .build()?;
+        let plan2 = roundtrip_join_plan(&ctx, plan).await?;
+        match plan2 {
+            LogicalPlan::Join(join) => assert!(join.null_equals_null),
+            _ => panic!("expected a join, got {plan2:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsupported_join_type() -> Result<()> {
+        let ctx = create_join_context()?;
+        for join_type in [JoinType::RightSemi, JoinType::RightAnti] {
+            let left = ctx.table("semi_left").await?.into_unoptimized_plan();
+            let right = ctx.table("semi_right").await?.into_unoptimized_plan();
+            let plan = LogicalPlanBuilder::from(left)
+                .join(right, join_type, (vec!["b1"], vec!["b2"]), None)?
+                .build()?;
+            match to_substrait_plan(&plan, &ctx) {
+                Err(DataFusionError::NotImplemented(msg)) => {
+                    assert!(msg.contains(&join_type.to_string()), "{msg}")
+                }
+                other => panic!("expected a NotImplemented error, got {other:?}"),
+            }
+        }
+        Ok(())
+    }
+
     /// Construct a plan that contains several literals of types that are currently supported.
     /// This case ignores:
     /// - Date64, for this literal is not supported
@@ -584,6 +826,175 @@ mod tests {
         Ok(())
     }
 
+    /// Round trip the optimized plan of `sql` through Substrait, then check that the
+    /// plans are the same and that they return the same results
+    async fn roundtrip_join(sql: &str) -> Result<()> {
+        let ctx = create_join_context()?;
+        let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+        let plan2 = roundtrip_join_plan(&ctx, plan.clone()).await?;
+        assert_eq!(format!("{plan:?}"), format!("{plan2:?}"));
+        Ok(())
+    }
+
+    /// Round trip `plan` through Substrait and check that both plans return the
+    /// same results, returning the optimized plan read back from Substrait
+    async fn roundtrip_join_plan(
+        ctx: &SessionContext,
+        plan: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let proto = to_substrait_plan(&plan, ctx)?;
+        let mut ctx2 = ctx.clone();
+        let plan2 = from_substrait_plan(&mut ctx2, &proto).await?;
+        let plan2 = ctx.state().optimize(&plan2)?;
+
+        let expected = execute_sorted(ctx, plan).await?;
+        let actual = execute_sorted(ctx, plan2.clone()).await?;
+        assert_eq!(expected, actual);
+        Ok(plan2)
+    }
+
+    /// Execute `plan` and return its formatted output lines, sorted
+    async fn execute_sorted(
+        ctx: &SessionContext,
+        plan: LogicalPlan,
+    ) -> Result<Vec<String>> {
+        let batches = ctx.execute_logical_plan(plan).await?.collect().await?;
+        let formatted = pretty_format_batches(&batches)?.to_string();
+        let mut lines: Vec<String> = formatted.lines().map(String::from).collect();
+        lines.sort();
+        Ok(lines)
+    }
+
+    fn field_ref(index: i32) -> Expression {
+        Expression {
+            rex_type: Some(RexType::Selection(Box::new(FieldReference {
+                reference_type: Some(ReferenceType::DirectReference(ReferenceSegment {
+                    reference_type: Some(reference_segment::ReferenceType::StructField(
+                        Box::new(reference_segment::StructField {
+                            field: index,
+                            child: None,
+                        }),
+                    )),
+                })),
+                root_type: None,
+            }))),
+        }
+    }
+
+    fn sort_field(index: i32, direction: SortDirection) -> SortField {
+        SortField {
+            expr: Some(field_ref(index)),
+            sort_kind: Some(SortKind::Direction(direction as i32)),
+        }
+    }
+
+    /// A plan selecting `a` and a window function over the `data` table, written like
+    /// other Substrait producers do: the function name includes its signature and the
+    /// window function has no frame units option
+    fn foreign_window_plan(
+        function_name: &str,
+        arguments: Vec<Expression>,
+        partitions: Vec<Expression>,
+        sorts: Vec<SortField>,
+        lower_bound: BoundKind,
+        upper_bound: BoundKind,
+    ) -> Plan {
+        let read = Rel {
+            rel_type: Some(RelType::Read(Box::new(ReadRel {
+                read_type: Some(ReadType::NamedTable(NamedTable {
+                    names: vec!["data".to_string()],
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }))),
+        };
+        let window = Expression {
+            rex_type: Some(RexType::WindowFunction(SubstraitWindowFunction {
+                function_reference: 1,
+                arguments: arguments
+                    .into_iter()
+                    .map(|arg| FunctionArgument {
+                        arg_type: Some(ArgType::Value(arg)),
+                    })
+                    .collect(),
+                partitions,
+                sorts,
+                lower_bound: Some(Bound {
+                    kind: Some(lower_bound),
+                }),
+                upper_bound: Some(Bound {
+                    kind: Some(upper_bound),
+                }),
+                ..Default::default()
+            })),
+        };
+        let project = Rel {
+            rel_type: Some(RelType::Project(Box::new(ProjectRel {
+                input: Some(Box::new(read)),
+                expressions: vec![field_ref(0), window],
+                ..Default::default()
+            }))),
+        };
+        Plan {
+            extensions: vec![SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(ExtensionFunction {
+                    function_anchor: 1,
+                    name: function_name.to_string(),
+                    ..Default::default()
+                })),
+            }],
+            relations: vec![PlanRel {
+                rel_type: Some(plan_rel::RelType::Root(RelRoot {
+                    input: Some(project),
+                    names: vec!["a".to_string(), "w".to_string()],
+                })),
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the options of the window function of a [`foreign_window_plan`]
+    fn set_window_options(plan: &mut Plan, options: Vec<FunctionOption>) {
+        if let Some(plan_rel::RelType::Root(root)) = &mut plan.relations[0].rel_type {
+            if let Some(RelType::Project(project)) =
+                &mut root.input.as_mut().unwrap().rel_type
+            {
+                if let Some(RexType::WindowFunction(window)) =
+                    &mut project.expressions[1].rex_type
+                {
+                    window.options = options;
+                    return;
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    /// The only window function computed by `plan`
+    fn single_window_function(plan: &LogicalPlan) -> expr::WindowFunction {
+        fn collect(plan: &LogicalPlan, found: &mut Vec<expr::WindowFunction>) {
+            if let LogicalPlan::Window(window) = plan {
+                for e in &window.window_expr {
+                    if let Expr::WindowFunction(f) = e {
+                        found.push(f.clone());
+                    }
+                }
+            }
+            plan.inputs()
+                .into_iter()
+                .for_each(|input| collect(input, found));
+        }
+        let mut found = vec![];
+        collect(plan, &mut found);
+        assert_eq!(found.len(), 1, "{plan:?}");
+        found.pop().unwrap()
+    }
+
+    async fn execute_row_count(ctx: &SessionContext, plan: LogicalPlan) -> Result<usize> {
+        let batches = ctx.execute_logical_plan(plan).await?.collect().await?;
+        Ok(batches.iter().map(|b| b.num_rows()).sum())
+    }
+
     async fn roundtrip_all_types(sql: &str) -> Result<()> {
         let mut ctx = create_all_type_context().await?;
         let df = ctx.sql(sql).await?;
@@ -647,6 +1058,67 @@ mod tests {
         Ok(ctx)
     }
 
+    /// Register the inputs of the hash join tests of the core crate as tables
+    fn create_join_context() -> Result<SessionContext> {
+        fn table(columns: Vec<(&str, Vec<Option<i32>>)>) -> Result<RecordBatch> {
+            Ok(RecordBatch::try_from_iter(columns.into_iter().map(
+                |(name, values)| (name, Arc::new(Int32Array::from(values)) as ArrayRef),
+            ))?)
+        }
+        fn values(values: &[i32]) -> Vec<Option<i32>> {
+            values.iter().copied().map(Some).collect()
+        }
+
+        let ctx = SessionContext::new();
+        ctx.register_batch(
+            "join_left",
+            table(vec![
+                ("a1", values(&[1, 2, 3])),
+                ("b1", values(&[4, 5, 5])),
+                ("c1", values(&[7, 8, 9])),
+            ])?,
+        )?;
+        ctx.register_batch(
+            "join_right",
+            table(vec![
+                ("a2", values(&[10, 20, 30])),
+                ("b1", values(&[4, 5, 6])),
+                ("c2", values(&[70, 80, 90])),
+            ])?,
+        )?;
+        ctx.register_batch(
+            "semi_left",
+            table(vec![
+                ("a1", values(&[1, 3, 5, 7, 9, 11, 13])),
+                ("b1", values(&[1, 3, 5, 7, 8, 8, 10])),
+                ("c1", values(&[10, 30, 50, 70, 90, 110, 130])),
+            ])?,
+        )?;
+        ctx.register_batch(
+            "semi_right",
+            table(vec![
+                ("a2", values(&[8, 12, 6, 2, 10, 4])),
+                ("b2", values(&[8, 10, 6, 2, 10, 4])),
+                ("c2", values(&[20, 40, 60, 80, 100, 120])),
+            ])?,
+        )?;
+        ctx.register_batch(
+            "null_left",
+            table(vec![
+                ("a1", vec![Some(1), None, Some(3)]),
+                ("b1", values(&[4, 5, 6])),
+            ])?,
+        )?;
+        ctx.register_batch(
+            "null_right",
+            table(vec![
+                ("a2", vec![Some(1), None, Some(4)]),
+                ("b2", values(&[7, 8, 9])),
+            ])?,
+        )?;
+        Ok(ctx)
+    }
+
     /// Cover all supported types
     async fn create_all_type_context() -> Result<SessionContext> {
         let ctx = SessionContext::new();